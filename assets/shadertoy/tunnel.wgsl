@@ -0,0 +1,16 @@
+// A textured tunnel, steered by dragging the mouse.
+//
+// Loaded by the `wgpu_shadertoy` example, with an image bound to `iChannel0` and the `speed` and
+// `tint` inputs declared via `iParams`.
+
+fn mainImage(fragCoord: vec2<f32>) -> vec4<f32> {
+    var p = (2.0 * fragCoord - iResolution.xy) / iResolution.y;
+    if iMouse.z > 0.0 {
+        p = p - (2.0 * iMouse.xy - iResolution.xy) / iResolution.y * 0.5;
+    }
+    let angle = atan2(p.y, p.x) / 3.14159265;
+    let radius = length(p);
+    let uv = vec2<f32>(0.3 / radius + iTime * iParams.speed, angle + iTime * 0.1);
+    let color = textureSample(iChannel0, iSampler, uv).rgb * iParams.tint.rgb;
+    return vec4<f32>(color * min(radius * 1.5, 1.0), 1.0);
+}
@@ -1,5 +1,24 @@
 use bevy::prelude::*;
 
+pub use loop_mode::{LoopMode, SetLoopMode};
+pub use pixel_buffer::{PixelBuffer, PixelBufferPlugin};
+#[cfg(feature = "screen_capture")]
+pub use screen_capture::{
+    monitor_names, screenshot, CaptureMonitor, ScreenCapture, ScreenCaptureError,
+    ScreenCapturePlugin,
+};
+pub use window_events::{WindowEvents, WindowEventsPlugin};
+pub use window_ext::WindowExt;
+
+pub mod net;
+
+mod loop_mode;
+mod pixel_buffer;
+#[cfg(feature = "screen_capture")]
+mod screen_capture;
+mod window_events;
+mod window_ext;
+
 struct NannouPlugin;
 
 impl Plugin for NannouPlugin {
@@ -7,7 +26,13 @@ impl Plugin for NannouPlugin {
         app.add_plugins((
             bevy_nannou_render::NannouRenderPlugin,
             bevy_nannou_draw::NannouDrawPlugin,
+            loop_mode::LoopModePlugin,
+            PixelBufferPlugin,
         ));
+        #[cfg(feature = "screen_capture")]
+        app.add_plugins(ScreenCapturePlugin);
+        #[cfg(feature = "ws")]
+        app.add_plugins(net::ws::WsPlugin);
     }
 }
 
@@ -21,4 +46,70 @@ mod tests {
         app.add_plugins(super::NannouPlugin);
         app.update();
     }
+
+    #[test]
+    fn loop_ntimes_stops_continuous_updates() {
+        use super::{LoopMode, SetLoopMode};
+        use bevy::winit::{UpdateMode, WinitSettings};
+
+        let mut app = App::new();
+        app.add_plugins(super::NannouPlugin)
+            .set_loop_mode(LoopMode::loop_ntimes(2));
+        app.update();
+        let settings = app.world.resource::<WinitSettings>();
+        assert_eq!(settings.focused_mode, UpdateMode::Continuous);
+        app.update();
+        let settings = app.world.resource::<WinitSettings>();
+        assert_ne!(settings.focused_mode, UpdateMode::Continuous);
+    }
+
+    #[test]
+    fn window_events_dispatch_to_model() {
+        use super::{WindowEvents, WindowEventsPlugin};
+        use bevy::prelude::*;
+        use bevy::window::{WindowPlugin, WindowResized};
+
+        #[derive(Resource, Default)]
+        struct Model {
+            size: Vec2,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            bevy::input::InputPlugin,
+            WindowPlugin {
+                primary_window: None,
+                ..default()
+            },
+            WindowEventsPlugin::<Model>::default(),
+        ))
+        .init_resource::<Model>();
+        let events = WindowEvents::<Model>::new().resized(|model, size| model.size = size);
+        let window = app.world.spawn((Window::default(), events)).id();
+        app.world.send_event(WindowResized {
+            window,
+            width: 320.0,
+            height: 240.0,
+        });
+        app.update();
+        assert_eq!(app.world.resource::<Model>().size, Vec2::new(320.0, 240.0));
+    }
+
+    #[test]
+    fn window_ext_updates_metadata() {
+        use super::WindowExt;
+        use bevy::prelude::*;
+
+        let mut window = Window::default();
+        window.set_title(format!("fps {}", 60));
+        window.set_resizable(false);
+        window.set_min_size(Some(Vec2::new(100.0, 50.0)));
+        window.set_max_size(Some(Vec2::new(800.0, 600.0)));
+        assert_eq!(window.title, "fps 60");
+        assert!(!window.resizable);
+        assert_eq!(window.min_size(), Vec2::new(100.0, 50.0));
+        assert_eq!(window.max_size(), Some(Vec2::new(800.0, 600.0)));
+        window.set_max_size(None);
+        assert_eq!(window.max_size(), None);
+    }
 }
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::winit::{UpdateMode, WinitSettings};
+
+/// The mode in which the app's update loop runs.
+///
+/// This is applied via bevy's `WinitSettings`, and may be changed at any time either via
+/// `SetLoopMode::set_loop_mode` or by modifying the `LoopMode` resource.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub enum LoopMode {
+    /// Update and render continuously, synchronised with the refresh rate of the display.
+    RefreshSync,
+    /// Update at most once per `update_interval`.
+    ///
+    /// Input and window events may cause the app to update sooner.
+    Rate {
+        /// The minimum duration between the beginning of each update.
+        update_interval: Duration,
+    },
+    /// Only update and render in response to input and window events.
+    Wait,
+    /// Update a fixed number of times, after which the app waits for events.
+    NTimes {
+        /// The number of updates that should occur.
+        number_of_updates: usize,
+    },
+}
+
+/// Extends bevy's `App` with a method for specifying the `LoopMode`.
+pub trait SetLoopMode {
+    /// Specify the `LoopMode` in which the app's update loop runs.
+    fn set_loop_mode(&mut self, mode: LoopMode) -> &mut Self;
+}
+
+// The number of updates that have occurred since the loop mode was last changed.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct LoopState {
+    updates: usize,
+}
+
+pub(crate) struct LoopModePlugin;
+
+impl LoopMode {
+    /// The default number of frames per second used by `rate_fps`.
+    pub const DEFAULT_RATE_FPS: f64 = 60.0;
+
+    /// Specify the **RefreshSync** mode.
+    pub fn refresh_sync() -> Self {
+        LoopMode::RefreshSync
+    }
+
+    /// Specify the **Rate** mode with the given frames-per-second.
+    ///
+    /// A rate that is not positive (or is NaN) never updates, so the **Wait** mode is returned
+    /// instead.
+    pub fn rate_fps(fps: f64) -> Self {
+        if fps.is_nan() || fps <= 0.0 {
+            return LoopMode::Wait;
+        }
+        // Rates too low to represent wait indefinitely.
+        let update_interval = Duration::try_from_secs_f64(1.0 / fps).unwrap_or(Duration::MAX);
+        LoopMode::Rate { update_interval }
+    }
+
+    /// Specify the **Wait** mode.
+    pub fn wait() -> Self {
+        LoopMode::Wait
+    }
+
+    /// Specify the **NTimes** mode with the given number of updates.
+    pub fn loop_ntimes(number_of_updates: usize) -> Self {
+        LoopMode::NTimes { number_of_updates }
+    }
+
+    /// Specify the **NTimes** mode with a single update.
+    pub fn loop_once() -> Self {
+        Self::loop_ntimes(1)
+    }
+
+    // The winit update mode for the loop mode, given the number of updates that have occurred.
+    fn update_mode(&self, updates: usize) -> UpdateMode {
+        let wait = UpdateMode::Reactive {
+            wait: Duration::MAX,
+        };
+        match *self {
+            LoopMode::RefreshSync => UpdateMode::Continuous,
+            LoopMode::Rate { update_interval } => UpdateMode::Reactive {
+                wait: update_interval,
+            },
+            LoopMode::Wait => wait,
+            LoopMode::NTimes { number_of_updates } if updates < number_of_updates => {
+                UpdateMode::Continuous
+            }
+            LoopMode::NTimes { .. } => wait,
+        }
+    }
+}
+
+impl Default for LoopMode {
+    fn default() -> Self {
+        LoopMode::refresh_sync()
+    }
+}
+
+impl SetLoopMode for App {
+    fn set_loop_mode(&mut self, mode: LoopMode) -> &mut Self {
+        self.insert_resource(mode)
+    }
+}
+
+impl Plugin for LoopModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoopMode>()
+            .init_resource::<LoopState>()
+            .init_resource::<WinitSettings>()
+            .add_systems(Last, apply_loop_mode);
+    }
+}
+
+// Count updates and keep the winit settings in sync with the current loop mode.
+fn apply_loop_mode(
+    mode: Res<LoopMode>,
+    mut state: ResMut<LoopState>,
+    mut settings: ResMut<WinitSettings>,
+) {
+    if mode.is_changed() {
+        state.updates = 0;
+    }
+    state.updates = state.updates.saturating_add(1);
+    let update_mode = mode.update_mode(state.updates);
+    if settings.focused_mode != update_mode || settings.unfocused_mode != update_mode {
+        settings.focused_mode = update_mode;
+        settings.unfocused_mode = update_mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LoopMode;
+    use std::time::Duration;
+
+    #[test]
+    fn rate_fps() {
+        let update_interval = Duration::from_millis(20);
+        assert_eq!(LoopMode::rate_fps(50.0), LoopMode::Rate { update_interval });
+        assert_eq!(LoopMode::rate_fps(0.0), LoopMode::Wait);
+        assert_eq!(LoopMode::rate_fps(-60.0), LoopMode::Wait);
+        assert_eq!(LoopMode::rate_fps(f64::NAN), LoopMode::Wait);
+        let update_interval = Duration::MAX;
+        assert_eq!(
+            LoopMode::rate_fps(1e-300),
+            LoopMode::Rate { update_interval }
+        );
+    }
+}
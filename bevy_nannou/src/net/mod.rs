@@ -0,0 +1,4 @@
+//! Networking interop for sketches, e.g. browser-based control panels and telemetry.
+
+#[cfg(feature = "ws")]
+pub mod ws;
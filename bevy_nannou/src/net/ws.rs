@@ -0,0 +1,462 @@
+//! An async WebSocket server and client whose messages surface as bevy events.
+//!
+//! Sockets run on a tokio runtime that is shared by all sockets and managed internally, so
+//! sketches may serve browser-based control panels or stream telemetry without wiring up any
+//! async code themselves.
+//!
+//! - Spawn an entity with a `WsServer` or `WsClient` component.
+//! - Read connections, disconnections and incoming messages via `EventReader<WsEvent>`.
+//! - Send messages via `EventWriter<WsSend>`, or via the `send` methods on the components.
+//!
+//! The socket is closed when its component is removed or its entity despawned.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::WebSocketStream;
+
+/// Uniquely identifies a connection to a `WsServer`.
+pub type ConnectionId = u64;
+
+/// The ID of the single connection of a `WsClient`.
+pub const CLIENT_CONNECTION: ConnectionId = 0;
+
+// How long to wait before accepting again after an error, e.g. when out of file descriptors.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// A message sent or received over a WebSocket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WsMessage {
+    /// A UTF-8 text message, e.g. JSON.
+    Text(String),
+    /// A binary message.
+    Binary(Vec<u8>),
+}
+
+/// An event that occurred on the socket of a `WsServer` or `WsClient`.
+#[derive(Event, Clone, Debug)]
+pub struct WsEvent {
+    /// The entity with the `WsServer` or `WsClient` component.
+    pub entity: Entity,
+    /// The connection on which the event occurred.
+    ///
+    /// This is always `CLIENT_CONNECTION` for clients.
+    pub connection: ConnectionId,
+    /// The kind of event that occurred.
+    pub kind: WsEventKind,
+}
+
+/// The kinds of events that may occur on a socket.
+#[derive(Clone, Debug)]
+pub enum WsEventKind {
+    /// The connection was established.
+    Connected,
+    /// A message was received.
+    Message(WsMessage),
+    /// An error occurred on the connection.
+    ///
+    /// The connection is closed following an error.
+    Error(String),
+    /// The connection was closed.
+    Disconnected,
+}
+
+/// Sends a message via the `WsServer` or `WsClient` on the given entity.
+#[derive(Event, Clone, Debug)]
+pub struct WsSend {
+    /// The entity with the `WsServer` or `WsClient` component.
+    pub entity: Entity,
+    /// The connection to send the message to, or `None` to send to all connections.
+    pub connection: Option<ConnectionId>,
+    /// The message to send.
+    pub message: WsMessage,
+}
+
+/// A WebSocket server accepting any number of connections.
+#[derive(Component)]
+pub struct WsServer {
+    addr: SocketAddr,
+    socket: Socket,
+}
+
+/// A WebSocket client connected to a single server.
+#[derive(Component)]
+pub struct WsClient {
+    url: String,
+    socket: Socket,
+}
+
+/// Errors that might occur while binding a `WsServer`.
+#[derive(Debug)]
+pub enum WsError {
+    /// The given address could not be resolved.
+    NoAddress,
+    /// An I/O error occurred while binding the socket.
+    Io(io::Error),
+}
+
+/// Forwards the events of each `WsServer` and `WsClient` and sends the `WsSend` events.
+pub struct WsPlugin;
+
+// Events received from the tokio runtime.
+type Inbound = (ConnectionId, WsEventKind);
+
+// The channels for sending messages to each open connection.
+type Outbound = Arc<Mutex<HashMap<ConnectionId, mpsc::UnboundedSender<WsMessage>>>>;
+
+// State shared by servers and clients.
+struct Socket {
+    task: JoinHandle<()>,
+    inbound: mpsc::UnboundedReceiver<Inbound>,
+    outbound: Outbound,
+}
+
+impl WsMessage {
+    fn from_tungstenite(msg: tungstenite::Message) -> Option<Self> {
+        match msg {
+            tungstenite::Message::Text(text) => Some(WsMessage::Text(text)),
+            tungstenite::Message::Binary(data) => Some(WsMessage::Binary(data)),
+            _ => None,
+        }
+    }
+}
+
+impl WsSend {
+    /// Send the message to all connections of the socket on the given entity.
+    pub fn broadcast(entity: Entity, message: WsMessage) -> Self {
+        WsSend {
+            entity,
+            connection: None,
+            message,
+        }
+    }
+
+    /// Send the message to a single connection of the socket on the given entity.
+    pub fn to(entity: Entity, connection: ConnectionId, message: WsMessage) -> Self {
+        WsSend {
+            entity,
+            connection: Some(connection),
+            message,
+        }
+    }
+}
+
+impl WsServer {
+    /// Bind a server to the given address, e.g. `"0.0.0.0:9002"`.
+    ///
+    /// Connections are accepted in the background from this point on.
+    pub fn bind<A>(addr: A) -> Result<Self, WsError>
+    where
+        A: ToSocketAddrs,
+    {
+        let addr = addr.to_socket_addrs()?.next().ok_or(WsError::NoAddress)?;
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let _guard = runtime().enter();
+        let listener = TcpListener::from_std(listener)?;
+        let socket = Socket::spawn(|inbound, outbound| serve(listener, inbound, outbound));
+        Ok(WsServer { addr, socket })
+    }
+
+    /// The address to which the server is bound.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The IDs of all open connections.
+    pub fn connections(&self) -> Vec<ConnectionId> {
+        let outbound = self
+            .socket
+            .outbound
+            .lock()
+            .expect("failed to lock connections");
+        let mut ids: Vec<_> = outbound.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Send the message to the given connection.
+    ///
+    /// Returns `false` if no connection with the given ID is open.
+    pub fn send(&self, connection: ConnectionId, message: WsMessage) -> bool {
+        self.socket.send(Some(connection), message)
+    }
+
+    /// Send the message to all open connections.
+    pub fn broadcast(&self, message: WsMessage) {
+        self.socket.send(None, message);
+    }
+}
+
+impl WsClient {
+    /// Begin connecting to the server at the given URL, e.g. `"ws://localhost:9002"`.
+    ///
+    /// A `WsEventKind::Connected` event is emitted once the connection is established, or a
+    /// `WsEventKind::Error` event if it fails.
+    pub fn connect(url: impl Into<String>) -> Self {
+        let url = url.into();
+        let task_url = url.clone();
+        let socket = Socket::spawn(|inbound, outbound| connect(task_url, inbound, outbound));
+        WsClient { url, socket }
+    }
+
+    /// The URL of the server.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether or not the connection is currently established.
+    pub fn is_connected(&self) -> bool {
+        !self
+            .socket
+            .outbound
+            .lock()
+            .expect("failed to lock connections")
+            .is_empty()
+    }
+
+    /// Send the message to the server.
+    ///
+    /// Returns `false` if the connection is not established.
+    pub fn send(&self, message: WsMessage) -> bool {
+        self.socket.send(Some(CLIENT_CONNECTION), message)
+    }
+}
+
+impl Socket {
+    // Spawn the task driving the socket onto the runtime.
+    fn spawn<F, Fut>(f: F) -> Self
+    where
+        F: FnOnce(mpsc::UnboundedSender<Inbound>, Outbound) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let (inbound_tx, inbound) = mpsc::unbounded_channel();
+        let outbound = Outbound::default();
+        let task = runtime().spawn(f(inbound_tx, outbound.clone()));
+        Socket {
+            task,
+            inbound,
+            outbound,
+        }
+    }
+
+    // Send the message to the given connection or to all connections if `None`.
+    fn send(&self, connection: Option<ConnectionId>, message: WsMessage) -> bool {
+        let outbound = self.outbound.lock().expect("failed to lock connections");
+        match connection {
+            Some(id) => match outbound.get(&id) {
+                Some(tx) => tx.send(message).is_ok(),
+                None => false,
+            },
+            None => {
+                for tx in outbound.values() {
+                    let _ = tx.send(message.clone());
+                }
+                true
+            }
+        }
+    }
+
+    // Forward all pending events from the runtime.
+    fn forward(&mut self, entity: Entity, events: &mut EventWriter<WsEvent>) {
+        while let Ok((connection, kind)) = self.inbound.try_recv() {
+            events.send(WsEvent {
+                entity,
+                connection,
+                kind,
+            });
+        }
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        // Dropping the senders closes each connection.
+        self.task.abort();
+        self.outbound
+            .lock()
+            .expect("failed to lock connections")
+            .clear();
+    }
+}
+
+impl fmt::Display for WsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WsError::NoAddress => write!(f, "no socket address to bind to"),
+            WsError::Io(ref err) => write!(f, "failed to bind websocket server: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for WsError {}
+
+impl From<io::Error> for WsError {
+    fn from(err: io::Error) -> Self {
+        WsError::Io(err)
+    }
+}
+
+impl From<WsMessage> for tungstenite::Message {
+    fn from(msg: WsMessage) -> Self {
+        match msg {
+            WsMessage::Text(text) => tungstenite::Message::Text(text),
+            WsMessage::Binary(data) => tungstenite::Message::Binary(data),
+        }
+    }
+}
+
+impl Plugin for WsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WsEvent>()
+            .add_event::<WsSend>()
+            .add_systems(PreUpdate, forward_events)
+            .add_systems(PostUpdate, send_messages);
+    }
+}
+
+// The runtime shared by all sockets.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("nannou_ws")
+            .enable_all()
+            .build()
+            .expect("failed to create websocket runtime")
+    })
+}
+
+// Accept connections until the server is dropped.
+async fn serve(listener: TcpListener, inbound: mpsc::UnboundedSender<Inbound>, outbound: Outbound) {
+    let mut next_id = CLIENT_CONNECTION;
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _addr)) => stream,
+            Err(err) => {
+                warn!("failed to accept websocket connection: {}", err);
+                tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        next_id += 1;
+        let id = next_id;
+        let inbound = inbound.clone();
+        let outbound = outbound.clone();
+        tokio::spawn(async move {
+            match tokio_tungstenite::accept_async(stream).await {
+                Ok(ws) => run_connection(id, ws, inbound, outbound).await,
+                Err(err) => {
+                    let _ = inbound.send((id, WsEventKind::Error(err.to_string())));
+                }
+            }
+        });
+    }
+}
+
+// Connect to the server at the given URL and run the connection until either side closes it.
+async fn connect(url: String, inbound: mpsc::UnboundedSender<Inbound>, outbound: Outbound) {
+    match tokio_tungstenite::connect_async(url.as_str()).await {
+        Ok((ws, _response)) => run_connection(CLIENT_CONNECTION, ws, inbound, outbound).await,
+        Err(err) => {
+            let _ = inbound.send((CLIENT_CONNECTION, WsEventKind::Error(err.to_string())));
+            let _ = inbound.send((CLIENT_CONNECTION, WsEventKind::Disconnected));
+        }
+    }
+}
+
+// Forward messages in both directions until either side closes the connection.
+async fn run_connection<S>(
+    id: ConnectionId,
+    ws: WebSocketStream<S>,
+    inbound: mpsc::UnboundedSender<Inbound>,
+    outbound: Outbound,
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    outbound
+        .lock()
+        .expect("failed to lock connections")
+        .insert(id, tx);
+    let _ = inbound.send((id, WsEventKind::Connected));
+
+    let (mut sink, mut stream) = ws.split();
+    let error = loop {
+        tokio::select! {
+            msg = rx.recv() => match msg {
+                Some(msg) => {
+                    if let Err(err) = sink.send(msg.into()).await {
+                        break Some(err);
+                    }
+                }
+                None => {
+                    let _ = sink.close().await;
+                    break None;
+                }
+            },
+            msg = stream.next() => match msg {
+                Some(Ok(msg)) => {
+                    if let Some(msg) = WsMessage::from_tungstenite(msg) {
+                        let _ = inbound.send((id, WsEventKind::Message(msg)));
+                    }
+                }
+                Some(Err(err)) => break Some(err),
+                None => break None,
+            },
+        }
+    };
+
+    outbound
+        .lock()
+        .expect("failed to lock connections")
+        .remove(&id);
+    if let Some(err) = error {
+        let _ = inbound.send((id, WsEventKind::Error(err.to_string())));
+    }
+    let _ = inbound.send((id, WsEventKind::Disconnected));
+}
+
+// Forward the events of all sockets into the `WsEvent` queue.
+fn forward_events(
+    mut servers: Query<(Entity, &mut WsServer)>,
+    mut clients: Query<(Entity, &mut WsClient)>,
+    mut events: EventWriter<WsEvent>,
+) {
+    for (entity, mut server) in &mut servers {
+        server.socket.forward(entity, &mut events);
+    }
+    for (entity, mut client) in &mut clients {
+        client.socket.forward(entity, &mut events);
+    }
+}
+
+// Send the messages of all `WsSend` events via their sockets.
+fn send_messages(
+    mut sends: EventReader<WsSend>,
+    servers: Query<&WsServer>,
+    clients: Query<&WsClient>,
+) {
+    for send in sends.read() {
+        let socket = match (servers.get(send.entity), clients.get(send.entity)) {
+            (Ok(server), _) => &server.socket,
+            (_, Ok(client)) => &client.socket,
+            _ => continue,
+        };
+        socket.send(send.connection, send.message.clone());
+    }
+}
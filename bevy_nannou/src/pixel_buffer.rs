@@ -0,0 +1,348 @@
+use bevy::math::URect;
+use bevy::prelude::*;
+use bevy::render::render_asset::{RenderAssetUsages, RenderAssets};
+use bevy::render::render_resource::{
+    Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, TextureAspect, TextureDimension,
+    TextureFormat,
+};
+use bevy::render::renderer::RenderQueue;
+use bevy::render::{Extract, ExtractSchedule, Render, RenderApp, RenderSet};
+
+/// A CPU-side copy of an `Image` whose pixels may be read and written directly.
+///
+/// Pixels are stored as 8-bit sRGBA. Only the region of the buffer that has changed since the
+/// previous frame is uploaded to the GPU, so sketches that modify a few pixels at a time remain
+/// cheap regardless of the size of the image.
+///
+/// Insert the buffer as a component and use its `handle` wherever the image is to be displayed,
+/// e.g. as the texture of a sprite or material. Changes made to the `Image` asset directly are
+/// overwritten by those made via the buffer.
+#[derive(Component, Clone, Debug)]
+pub struct PixelBuffer {
+    handle: Handle<Image>,
+    size: UVec2,
+    pixels: Vec<[u8; 4]>,
+    dirty: Option<URect>,
+}
+
+// A region of a `PixelBuffer` to be written to its texture.
+#[derive(Clone, Debug)]
+struct PixelUpload {
+    image: AssetId<Image>,
+    origin: UVec2,
+    size: UVec2,
+    data: Vec<u8>,
+}
+
+// The uploads produced by the most recent update.
+#[derive(Resource, Clone, Debug, Default)]
+struct PixelUploads(Vec<PixelUpload>);
+
+// Uploads extracted to the render world that are waiting for their image to be prepared.
+#[derive(Resource, Debug, Default)]
+struct PendingPixelUploads(Vec<PixelUpload>);
+
+/// Uploads the modified regions of each `PixelBuffer` to its texture.
+pub struct PixelBufferPlugin;
+
+impl PixelBuffer {
+    /// The texture format of the underlying image.
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+    /// Create a new buffer of the given size filled with the given color, along with the image
+    /// asset that it writes to.
+    pub fn new(images: &mut Assets<Image>, width: u32, height: u32, color: Color) -> Self {
+        let pixels = vec![color.as_rgba_u8(); width as usize * height as usize];
+        Self::from_pixels(images, UVec2::new(width, height), pixels)
+    }
+
+    /// Create a new buffer from a copy of the given image, along with a new image asset that it
+    /// writes to.
+    ///
+    /// Returns `None` if the image could not be converted to 8-bit sRGBA.
+    pub fn from_image(images: &mut Assets<Image>, image: &Image) -> Option<Self> {
+        let image = image.convert(Self::FORMAT)?;
+        let size = image.size();
+        let pixels = image
+            .data
+            .chunks_exact(4)
+            .map(|p| [p[0], p[1], p[2], p[3]])
+            .collect();
+        Some(Self::from_pixels(images, size, pixels))
+    }
+
+    fn from_pixels(images: &mut Assets<Image>, size: UVec2, pixels: Vec<[u8; 4]>) -> Self {
+        let extent = Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        };
+        let data = pixels.iter().flatten().cloned().collect();
+        let image = Image::new(
+            extent,
+            TextureDimension::D2,
+            data,
+            Self::FORMAT,
+            RenderAssetUsages::default(),
+        );
+        PixelBuffer {
+            handle: images.add(image),
+            size,
+            pixels,
+            dirty: None,
+        }
+    }
+
+    /// The handle to the image asset to which the buffer is uploaded.
+    pub fn handle(&self) -> &Handle<Image> {
+        &self.handle
+    }
+
+    /// The width of the buffer in pixels.
+    pub fn width(&self) -> u32 {
+        self.size.x
+    }
+
+    /// The height of the buffer in pixels.
+    pub fn height(&self) -> u32 {
+        self.size.y
+    }
+
+    /// The size of the buffer in pixels.
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The color of the pixel at the given coordinates, where `(0, 0)` is the top-left corner.
+    ///
+    /// Returns `None` if the coordinates are out of bounds.
+    pub fn get(&self, x: u32, y: u32) -> Option<Color> {
+        self.get_rgba8(x, y)
+            .map(|[r, g, b, a]| Color::rgba_u8(r, g, b, a))
+    }
+
+    /// Set the color of the pixel at the given coordinates, where `(0, 0)` is the top-left
+    /// corner.
+    ///
+    /// Does nothing if the coordinates are out of bounds.
+    pub fn set(&mut self, x: u32, y: u32, color: Color) {
+        self.set_rgba8(x, y, color.as_rgba_u8());
+    }
+
+    /// The 8-bit sRGBA components of the pixel at the given coordinates.
+    pub fn get_rgba8(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    /// Set the 8-bit sRGBA components of the pixel at the given coordinates.
+    pub fn set_rgba8(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = rgba;
+            self.mark_dirty(URect::new(x, y, x + 1, y + 1));
+        }
+    }
+
+    /// Fill the entire buffer with the given color.
+    pub fn fill(&mut self, color: Color) {
+        let rgba = color.as_rgba_u8();
+        self.pixels.iter_mut().for_each(|p| *p = rgba);
+        self.mark_all_dirty();
+    }
+
+    /// All pixels in row-major order, starting from the top-left corner.
+    pub fn pixels(&self) -> &[[u8; 4]] {
+        &self.pixels
+    }
+
+    /// Mutable access to all pixels in row-major order.
+    ///
+    /// The entire buffer is uploaded on the following frame.
+    pub fn pixels_mut(&mut self) -> &mut [[u8; 4]] {
+        self.mark_all_dirty();
+        &mut self.pixels
+    }
+
+    /// An iterator yielding each row of pixels from top to bottom.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[[u8; 4]]> {
+        self.pixels.chunks_exact(self.size.x.max(1) as usize)
+    }
+
+    /// The row of pixels at the given index, or `None` if out of bounds.
+    pub fn row(&self, y: u32) -> Option<&[[u8; 4]]> {
+        let w = self.size.x as usize;
+        let start = y as usize * w;
+        self.pixels.get(start..start + w)
+    }
+
+    /// Mutable access to the row of pixels at the given index, or `None` if out of bounds.
+    ///
+    /// Only the given row is uploaded on the following frame.
+    pub fn row_mut(&mut self, y: u32) -> Option<&mut [[u8; 4]]> {
+        if y >= self.size.y {
+            return None;
+        }
+        self.mark_dirty(URect::new(0, y, self.size.x, y + 1));
+        let w = self.size.x as usize;
+        let start = y as usize * w;
+        Some(&mut self.pixels[start..start + w])
+    }
+
+    /// An iterator yielding mutable access to each row of pixels from top to bottom.
+    ///
+    /// The entire buffer is uploaded on the following frame.
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = &mut [[u8; 4]]> {
+        let w = self.size.x.max(1) as usize;
+        self.pixels_mut().chunks_exact_mut(w)
+    }
+
+    /// The region of the buffer modified since it was last uploaded, if any.
+    pub fn dirty_region(&self) -> Option<URect> {
+        self.dirty
+    }
+
+    /// Mark the given region as modified so that it is uploaded on the following frame.
+    ///
+    /// The region is clamped to the bounds of the buffer.
+    pub fn mark_dirty(&mut self, region: URect) {
+        let region = region.intersect(URect::from_corners(UVec2::ZERO, self.size));
+        if region.is_empty() {
+            return;
+        }
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => dirty.union(region),
+            None => region,
+        });
+    }
+
+    fn mark_all_dirty(&mut self) {
+        self.mark_dirty(URect::from_corners(UVec2::ZERO, self.size));
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.size.x && y < self.size.y {
+            Some(y as usize * self.size.x as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    // Take the dirty region along with a copy of its pixels.
+    fn take_upload(&mut self) -> Option<PixelUpload> {
+        let region = self.dirty.take()?;
+        let w = self.size.x as usize;
+        let data = (region.min.y..region.max.y)
+            .flat_map(|y| {
+                let start = y as usize * w;
+                &self.pixels[start + region.min.x as usize..start + region.max.x as usize]
+            })
+            .flatten()
+            .cloned()
+            .collect();
+        Some(PixelUpload {
+            image: self.handle.id(),
+            origin: region.min,
+            size: region.size(),
+            data,
+        })
+    }
+}
+
+impl Plugin for PixelBufferPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PixelUploads>()
+            .add_systems(PostUpdate, collect_pixel_uploads);
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<PendingPixelUploads>()
+                .add_systems(ExtractSchedule, extract_pixel_uploads)
+                .add_systems(
+                    Render,
+                    write_pixel_uploads.in_set(RenderSet::PrepareResources),
+                );
+        }
+    }
+}
+
+// Collect the modified regions of each buffer.
+fn collect_pixel_uploads(
+    mut buffers: Query<&mut PixelBuffer, Changed<PixelBuffer>>,
+    mut uploads: ResMut<PixelUploads>,
+) {
+    uploads.0.clear();
+    for mut buffer in &mut buffers {
+        if let Some(upload) = buffer.bypass_change_detection().take_upload() {
+            uploads.0.push(upload);
+        }
+    }
+}
+
+// Queue the uploads collected this frame, dropping any pending uploads whose image has since been
+// removed so that they are not retained indefinitely.
+fn extract_pixel_uploads(
+    uploads: Extract<Res<PixelUploads>>,
+    images: Extract<Res<Assets<Image>>>,
+    mut pending: ResMut<PendingPixelUploads>,
+) {
+    pending.0.retain(|upload| images.contains(upload.image));
+    pending.0.extend(uploads.0.iter().cloned());
+}
+
+// Write each pending upload to its texture, retaining those whose image is not yet prepared.
+fn write_pixel_uploads(
+    mut pending: ResMut<PendingPixelUploads>,
+    images: Res<RenderAssets<Image>>,
+    queue: Res<RenderQueue>,
+) {
+    pending.0.retain(|upload| {
+        let Some(gpu_image) = images.get(upload.image) else {
+            return true;
+        };
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &gpu_image.texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: upload.origin.x,
+                    y: upload.origin.y,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            &upload.data,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(upload.size.x * 4),
+                rows_per_image: None,
+            },
+            Extent3d {
+                width: upload.size.x,
+                height: upload.size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PixelBuffer;
+    use bevy::math::URect;
+    use bevy::prelude::*;
+
+    #[test]
+    fn pixel_buffer_tracks_dirty_region() {
+        let mut images = Assets::<Image>::default();
+        let mut buffer = PixelBuffer::new(&mut images, 8, 4, Color::BLACK);
+        assert_eq!(buffer.dirty_region(), None);
+        buffer.set_rgba8(1, 1, [255, 0, 0, 255]);
+        buffer.set_rgba8(5, 2, [0, 255, 0, 255]);
+        buffer.set_rgba8(8, 0, [0, 0, 255, 255]);
+        assert_eq!(buffer.get_rgba8(1, 1), Some([255, 0, 0, 255]));
+        assert_eq!(buffer.get_rgba8(8, 0), None);
+        assert_eq!(buffer.dirty_region(), Some(URect::new(1, 1, 6, 3)));
+        buffer.row_mut(3).unwrap()[0] = [1, 2, 3, 4];
+        assert_eq!(buffer.dirty_region(), Some(URect::new(0, 1, 8, 4)));
+        assert_eq!(buffer.rows().nth(3).unwrap()[0], [1, 2, 3, 4]);
+    }
+}
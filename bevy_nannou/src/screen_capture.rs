@@ -0,0 +1,247 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+/// Continuously captures the contents of a monitor into an `Image` asset.
+///
+/// Capturing happens on a dedicated thread using the platform's screen capture APIs. The most
+/// recent capture is written to the image once per frame, so sketches may process or remix the
+/// desktop in real time by using the `handle` as they would any other texture. Capturing stops
+/// when the component is dropped.
+///
+/// Note that some platforms require the user to grant permission before the screen may be
+/// captured.
+#[derive(Component)]
+pub struct ScreenCapture {
+    handle: Handle<Image>,
+    latest: Arc<Mutex<Option<Capture>>>,
+    running: Arc<AtomicBool>,
+}
+
+/// The monitor to be captured.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CaptureMonitor {
+    /// The primary monitor.
+    #[default]
+    Primary,
+    /// The monitor at the given index within `monitor_names`.
+    Index(usize),
+    /// The monitor with the given name.
+    Name(String),
+}
+
+/// Errors that might occur while capturing the screen.
+#[derive(Debug)]
+pub enum ScreenCaptureError {
+    /// No monitor matched the requested `CaptureMonitor`.
+    NoSuchMonitor(CaptureMonitor),
+    /// The platform capture API returned an error.
+    Capture(xcap::XCapError),
+}
+
+/// Writes the latest capture of each `ScreenCapture` to its image.
+pub struct ScreenCapturePlugin;
+
+// A single captured frame.
+struct Capture {
+    size: UVec2,
+    data: Vec<u8>,
+}
+
+/// The default minimum interval between captures.
+pub const DEFAULT_CAPTURE_INTERVAL: Duration = Duration::from_millis(16);
+
+impl ScreenCapture {
+    /// The texture format of the captured images.
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+
+    /// Begin capturing the given monitor, along with the image asset that captures are written
+    /// to.
+    pub fn new(
+        images: &mut Assets<Image>,
+        monitor: CaptureMonitor,
+    ) -> Result<Self, ScreenCaptureError> {
+        Self::with_interval(images, monitor, DEFAULT_CAPTURE_INTERVAL)
+    }
+
+    /// Begin capturing the given monitor at most once per `interval`.
+    pub fn with_interval(
+        images: &mut Assets<Image>,
+        monitor: CaptureMonitor,
+        interval: Duration,
+    ) -> Result<Self, ScreenCaptureError> {
+        let size = {
+            let m = find_monitor(&monitor)?;
+            UVec2::new(m.width(), m.height())
+        };
+        let image = Image::new_fill(
+            Extent3d {
+                width: size.x.max(1),
+                height: size.y.max(1),
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            Self::FORMAT,
+            RenderAssetUsages::default(),
+        );
+
+        let latest = Arc::new(Mutex::new(None));
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_latest = latest.clone();
+        let thread_running = running.clone();
+        thread::Builder::new()
+            .name("nannou_screen_capture".into())
+            .spawn(move || capture_loop(monitor, interval, thread_latest, thread_running))
+            .expect("failed to spawn screen capture thread");
+
+        Ok(ScreenCapture {
+            handle: images.add(image),
+            latest,
+            running,
+        })
+    }
+
+    /// The handle to the image asset to which captures are written.
+    pub fn handle(&self) -> &Handle<Image> {
+        &self.handle
+    }
+
+    // Take the most recent capture, if there is a new one.
+    fn take(&self) -> Option<Capture> {
+        self.latest
+            .lock()
+            .expect("failed to lock screen capture")
+            .take()
+    }
+}
+
+impl Drop for ScreenCapture {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for ScreenCaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScreenCaptureError::NoSuchMonitor(ref monitor) => {
+                write!(f, "no monitor matching {:?}", monitor)
+            }
+            ScreenCaptureError::Capture(ref err) => write!(f, "failed to capture screen: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScreenCaptureError {}
+
+impl From<xcap::XCapError> for ScreenCaptureError {
+    fn from(err: xcap::XCapError) -> Self {
+        ScreenCaptureError::Capture(err)
+    }
+}
+
+impl Plugin for ScreenCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, write_screen_captures);
+    }
+}
+
+/// The names of all available monitors, in the order used by `CaptureMonitor::Index`.
+pub fn monitor_names() -> Result<Vec<String>, ScreenCaptureError> {
+    let monitors = xcap::Monitor::all()?;
+    Ok(monitors.iter().map(|m| m.name().to_string()).collect())
+}
+
+/// Capture a single screenshot of the given monitor.
+///
+/// This blocks until the capture completes.
+pub fn screenshot(monitor: &CaptureMonitor) -> Result<Image, ScreenCaptureError> {
+    let capture = capture(&find_monitor(monitor)?)?;
+    Ok(Image::new(
+        Extent3d {
+            width: capture.size.x,
+            height: capture.size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        capture.data,
+        ScreenCapture::FORMAT,
+        RenderAssetUsages::default(),
+    ))
+}
+
+fn find_monitor(monitor: &CaptureMonitor) -> Result<xcap::Monitor, ScreenCaptureError> {
+    let monitors = xcap::Monitor::all()?;
+    let found = match *monitor {
+        CaptureMonitor::Primary => monitors.into_iter().find(|m| m.is_primary()),
+        CaptureMonitor::Index(i) => monitors.into_iter().nth(i),
+        CaptureMonitor::Name(ref name) => monitors.into_iter().find(|m| m.name() == name),
+    };
+    found.ok_or_else(|| ScreenCaptureError::NoSuchMonitor(monitor.clone()))
+}
+
+fn capture(monitor: &xcap::Monitor) -> Result<Capture, ScreenCaptureError> {
+    let image = monitor.capture_image()?;
+    let size = UVec2::new(image.width(), image.height());
+    Ok(Capture {
+        size,
+        data: image.into_raw(),
+    })
+}
+
+// Capture the monitor until the owning `ScreenCapture` is dropped.
+//
+// The monitor is looked up on the capture thread as monitor handles are not `Send` on all
+// platforms.
+fn capture_loop(
+    monitor: CaptureMonitor,
+    interval: Duration,
+    latest: Arc<Mutex<Option<Capture>>>,
+    running: Arc<AtomicBool>,
+) {
+    let monitor = match find_monitor(&monitor) {
+        Ok(monitor) => monitor,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
+    while running.load(Ordering::Relaxed) {
+        let start = Instant::now();
+        match capture(&monitor) {
+            Ok(capture) => *latest.lock().expect("failed to lock screen capture") = Some(capture),
+            Err(err) => warn!("{}", err),
+        }
+        if let Some(remaining) = interval.checked_sub(start.elapsed()) {
+            thread::sleep(remaining);
+        }
+    }
+}
+
+// Write the latest capture of each `ScreenCapture` to its image, resizing it if necessary.
+fn write_screen_captures(captures: Query<&ScreenCapture>, mut images: ResMut<Assets<Image>>) {
+    for screen in &captures {
+        let Some(capture) = screen.take() else {
+            continue;
+        };
+        let Some(image) = images.get_mut(&screen.handle) else {
+            continue;
+        };
+        let size = Extent3d {
+            width: capture.size.x,
+            height: capture.size.y,
+            depth_or_array_layers: 1,
+        };
+        if image.texture_descriptor.size != size {
+            image.texture_descriptor.size = size;
+        }
+        image.data = capture.data;
+    }
+}
@@ -0,0 +1,333 @@
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::window::{
+    CursorEntered, CursorLeft, CursorMoved, FileDragAndDrop, WindowFocused, WindowResized,
+};
+
+/// A function for processing key press events.
+pub type KeyPressedFn<Model> = fn(&mut Model, KeyCode);
+
+/// A function for processing key release events.
+pub type KeyReleasedFn<Model> = fn(&mut Model, KeyCode);
+
+/// A function for processing mouse moved events.
+pub type MouseMovedFn<Model> = fn(&mut Model, Vec2);
+
+/// A function for processing mouse pressed events.
+pub type MousePressedFn<Model> = fn(&mut Model, MouseButton);
+
+/// A function for processing mouse released events.
+pub type MouseReleasedFn<Model> = fn(&mut Model, MouseButton);
+
+/// A function for processing mouse entered events.
+pub type MouseEnteredFn<Model> = fn(&mut Model);
+
+/// A function for processing mouse exited events.
+pub type MouseExitedFn<Model> = fn(&mut Model);
+
+/// A function for processing mouse wheel events.
+pub type MouseWheelFn<Model> = fn(&mut Model, MouseScrollUnit, Vec2);
+
+/// A function for processing window resized events.
+pub type ResizedFn<Model> = fn(&mut Model, Vec2);
+
+/// A function for processing hovered file events.
+pub type HoveredFileFn<Model> = fn(&mut Model, PathBuf);
+
+/// A function for processing hovered file cancelled events.
+pub type HoveredFileCancelledFn<Model> = fn(&mut Model);
+
+/// A function for processing dropped file events.
+pub type DroppedFileFn<Model> = fn(&mut Model, PathBuf);
+
+/// A function for processing window focused events.
+pub type FocusedFn<Model> = fn(&mut Model);
+
+/// A function for processing window unfocused events.
+pub type UnfocusedFn<Model> = fn(&mut Model);
+
+/// The event functions associated with a window.
+///
+/// Insert this component on a window entity to have the window's input and window events
+/// dispatched to the given functions along with the `Model` resource. The dispatch system is
+/// added by the `WindowEventsPlugin` for the same `Model` type.
+///
+/// Positions are described in points relative to the centre of the window with the y axis
+/// pointing upwards, matching the coordinates used for drawing.
+#[derive(Component)]
+pub struct WindowEvents<Model> {
+    key_pressed: Option<KeyPressedFn<Model>>,
+    key_released: Option<KeyReleasedFn<Model>>,
+    mouse_moved: Option<MouseMovedFn<Model>>,
+    mouse_pressed: Option<MousePressedFn<Model>>,
+    mouse_released: Option<MouseReleasedFn<Model>>,
+    mouse_entered: Option<MouseEnteredFn<Model>>,
+    mouse_exited: Option<MouseExitedFn<Model>>,
+    mouse_wheel: Option<MouseWheelFn<Model>>,
+    resized: Option<ResizedFn<Model>>,
+    hovered_file: Option<HoveredFileFn<Model>>,
+    hovered_file_cancelled: Option<HoveredFileCancelledFn<Model>>,
+    dropped_file: Option<DroppedFileFn<Model>>,
+    focused: Option<FocusedFn<Model>>,
+    unfocused: Option<UnfocusedFn<Model>>,
+}
+
+/// Dispatches events to the `WindowEvents<Model>` functions of each window.
+pub struct WindowEventsPlugin<Model>(PhantomData<fn(Model)>);
+
+impl<Model> WindowEvents<Model> {
+    /// A set of window events with no functions.
+    pub fn new() -> Self {
+        WindowEvents {
+            key_pressed: None,
+            key_released: None,
+            mouse_moved: None,
+            mouse_pressed: None,
+            mouse_released: None,
+            mouse_entered: None,
+            mouse_exited: None,
+            mouse_wheel: None,
+            resized: None,
+            hovered_file: None,
+            hovered_file_cancelled: None,
+            dropped_file: None,
+            focused: None,
+            unfocused: None,
+        }
+    }
+
+    /// A function for processing key press events associated with this window.
+    pub fn key_pressed(mut self, f: KeyPressedFn<Model>) -> Self {
+        self.key_pressed = Some(f);
+        self
+    }
+
+    /// A function for processing key release events associated with this window.
+    pub fn key_released(mut self, f: KeyReleasedFn<Model>) -> Self {
+        self.key_released = Some(f);
+        self
+    }
+
+    /// A function for processing mouse moved events associated with this window.
+    pub fn mouse_moved(mut self, f: MouseMovedFn<Model>) -> Self {
+        self.mouse_moved = Some(f);
+        self
+    }
+
+    /// A function for processing mouse pressed events associated with this window.
+    pub fn mouse_pressed(mut self, f: MousePressedFn<Model>) -> Self {
+        self.mouse_pressed = Some(f);
+        self
+    }
+
+    /// A function for processing mouse released events associated with this window.
+    pub fn mouse_released(mut self, f: MouseReleasedFn<Model>) -> Self {
+        self.mouse_released = Some(f);
+        self
+    }
+
+    /// A function for processing mouse entered events associated with this window.
+    pub fn mouse_entered(mut self, f: MouseEnteredFn<Model>) -> Self {
+        self.mouse_entered = Some(f);
+        self
+    }
+
+    /// A function for processing mouse exited events associated with this window.
+    pub fn mouse_exited(mut self, f: MouseExitedFn<Model>) -> Self {
+        self.mouse_exited = Some(f);
+        self
+    }
+
+    /// A function for processing mouse wheel events associated with this window.
+    pub fn mouse_wheel(mut self, f: MouseWheelFn<Model>) -> Self {
+        self.mouse_wheel = Some(f);
+        self
+    }
+
+    /// A function for processing resize events associated with this window.
+    pub fn resized(mut self, f: ResizedFn<Model>) -> Self {
+        self.resized = Some(f);
+        self
+    }
+
+    /// A function for processing hovered file events associated with this window.
+    pub fn hovered_file(mut self, f: HoveredFileFn<Model>) -> Self {
+        self.hovered_file = Some(f);
+        self
+    }
+
+    /// A function for processing hovered file cancelled events associated with this window.
+    pub fn hovered_file_cancelled(mut self, f: HoveredFileCancelledFn<Model>) -> Self {
+        self.hovered_file_cancelled = Some(f);
+        self
+    }
+
+    /// A function for processing dropped file events associated with this window.
+    pub fn dropped_file(mut self, f: DroppedFileFn<Model>) -> Self {
+        self.dropped_file = Some(f);
+        self
+    }
+
+    /// A function for processing the focused event associated with this window.
+    pub fn focused(mut self, f: FocusedFn<Model>) -> Self {
+        self.focused = Some(f);
+        self
+    }
+
+    /// A function for processing the unfocused event associated with this window.
+    pub fn unfocused(mut self, f: UnfocusedFn<Model>) -> Self {
+        self.unfocused = Some(f);
+        self
+    }
+}
+
+impl<Model> Default for WindowEvents<Model> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Model> Default for WindowEventsPlugin<Model> {
+    fn default() -> Self {
+        WindowEventsPlugin(PhantomData)
+    }
+}
+
+impl<Model: Resource> Plugin for WindowEventsPlugin<Model> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, dispatch_window_events::<Model>);
+    }
+}
+
+// Call the functions of each window for the events that occurred since the last update.
+#[allow(clippy::too_many_arguments)]
+fn dispatch_window_events<Model: Resource>(
+    model: Option<ResMut<Model>>,
+    windows: Query<(&Window, &WindowEvents<Model>)>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut cursor_entered: EventReader<CursorEntered>,
+    mut cursor_left: EventReader<CursorLeft>,
+    mut resized: EventReader<WindowResized>,
+    mut focused: EventReader<WindowFocused>,
+    mut file_drag_and_drop: EventReader<FileDragAndDrop>,
+) {
+    let Some(mut model) = model else {
+        return;
+    };
+    let model = &mut *model;
+
+    for event in keyboard.read() {
+        let Ok((_, events)) = windows.get(event.window) else {
+            continue;
+        };
+        let f = match event.state {
+            ButtonState::Pressed => events.key_pressed,
+            ButtonState::Released => events.key_released,
+        };
+        if let Some(f) = f {
+            f(model, event.key_code);
+        }
+    }
+
+    for event in mouse_buttons.read() {
+        let Ok((_, events)) = windows.get(event.window) else {
+            continue;
+        };
+        let f = match event.state {
+            ButtonState::Pressed => events.mouse_pressed,
+            ButtonState::Released => events.mouse_released,
+        };
+        if let Some(f) = f {
+            f(model, event.button);
+        }
+    }
+
+    for event in mouse_wheel.read() {
+        if let Ok((_, events)) = windows.get(event.window) {
+            if let Some(f) = events.mouse_wheel {
+                f(model, event.unit, Vec2::new(event.x, event.y));
+            }
+        }
+    }
+
+    for event in cursor_moved.read() {
+        if let Ok((window, events)) = windows.get(event.window) {
+            if let Some(f) = events.mouse_moved {
+                let x = event.position.x - window.width() * 0.5;
+                let y = window.height() * 0.5 - event.position.y;
+                f(model, Vec2::new(x, y));
+            }
+        }
+    }
+
+    for event in cursor_entered.read() {
+        if let Ok((_, events)) = windows.get(event.window) {
+            if let Some(f) = events.mouse_entered {
+                f(model);
+            }
+        }
+    }
+
+    for event in cursor_left.read() {
+        if let Ok((_, events)) = windows.get(event.window) {
+            if let Some(f) = events.mouse_exited {
+                f(model);
+            }
+        }
+    }
+
+    for event in resized.read() {
+        if let Ok((_, events)) = windows.get(event.window) {
+            if let Some(f) = events.resized {
+                f(model, Vec2::new(event.width, event.height));
+            }
+        }
+    }
+
+    for event in focused.read() {
+        let Ok((_, events)) = windows.get(event.window) else {
+            continue;
+        };
+        let f = match event.focused {
+            true => events.focused,
+            false => events.unfocused,
+        };
+        if let Some(f) = f {
+            f(model);
+        }
+    }
+
+    for event in file_drag_and_drop.read() {
+        match event {
+            FileDragAndDrop::HoveredFile { window, path_buf } => {
+                if let Ok((_, events)) = windows.get(*window) {
+                    if let Some(f) = events.hovered_file {
+                        f(model, path_buf.clone());
+                    }
+                }
+            }
+            FileDragAndDrop::HoveredFileCanceled { window } => {
+                if let Ok((_, events)) = windows.get(*window) {
+                    if let Some(f) = events.hovered_file_cancelled {
+                        f(model);
+                    }
+                }
+            }
+            FileDragAndDrop::DroppedFile { window, path_buf } => {
+                if let Ok((_, events)) = windows.get(*window) {
+                    if let Some(f) = events.dropped_file {
+                        f(model, path_buf.clone());
+                    }
+                }
+            }
+        }
+    }
+}
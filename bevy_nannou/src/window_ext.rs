@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use bevy::window::WindowResizeConstraints;
+
+/// Methods for updating the metadata of a bevy `Window` at runtime.
+///
+/// Changes are applied to the native window by bevy at the end of the frame, so these may be
+/// called freely from any system, e.g. to display the frame rate within the title.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::window::PrimaryWindow;
+/// use bevy_nannou::WindowExt;
+///
+/// fn update(time: Res<Time>, mut window: Query<&mut Window, With<PrimaryWindow>>) {
+///     let fps = 1.0 / time.delta_seconds();
+///     window.single_mut().set_title(format!("fps {:.0}", fps));
+/// }
+/// ```
+pub trait WindowExt {
+    /// Set the title of the window.
+    fn set_title(&mut self, title: impl Into<String>);
+
+    /// Set whether or not the window may be resized by the user.
+    fn set_resizable(&mut self, resizable: bool);
+
+    /// Set the logical size of the window.
+    fn set_size(&mut self, size: Vec2);
+
+    /// Set the minimum logical size of the window, or `None` to use bevy's default minimum.
+    fn set_min_size(&mut self, size: Option<Vec2>);
+
+    /// Set the maximum logical size of the window, or `None` for no maximum.
+    fn set_max_size(&mut self, size: Option<Vec2>);
+
+    /// The minimum logical size of the window.
+    fn min_size(&self) -> Vec2;
+
+    /// The maximum logical size of the window, or `None` if there is no maximum.
+    fn max_size(&self) -> Option<Vec2>;
+}
+
+impl WindowExt for Window {
+    fn set_title(&mut self, title: impl Into<String>) {
+        self.title = title.into();
+    }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        self.resizable = resizable;
+    }
+
+    fn set_size(&mut self, size: Vec2) {
+        self.resolution.set(size.x, size.y);
+    }
+
+    fn set_min_size(&mut self, size: Option<Vec2>) {
+        let default = WindowResizeConstraints::default();
+        let size = size.unwrap_or(Vec2::new(default.min_width, default.min_height));
+        self.resize_constraints.min_width = size.x;
+        self.resize_constraints.min_height = size.y;
+    }
+
+    fn set_max_size(&mut self, size: Option<Vec2>) {
+        let size = size.unwrap_or(Vec2::splat(f32::INFINITY));
+        self.resize_constraints.max_width = size.x;
+        self.resize_constraints.max_height = size.y;
+    }
+
+    fn min_size(&self) -> Vec2 {
+        let c = &self.resize_constraints;
+        Vec2::new(c.min_width, c.min_height)
+    }
+
+    fn max_size(&self) -> Option<Vec2> {
+        let c = &self.resize_constraints;
+        let size = Vec2::new(c.max_width, c.max_height);
+        if size.is_finite() {
+            Some(size)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,408 @@
+//! Serial port communication for talking to Arduinos and other microcontrollers.
+//!
+//! Spawn an entity with a `SerialPort` component to open a port. Each port is serviced by its own
+//! thread, so reads never block the app:
+//!
+//! - Incoming lines or bytes surface as `SerialEvent`s, along with connections and disconnections.
+//! - Outgoing data is queued via `SerialSend` events or `SerialPort::send` and written in order.
+//! - If the device is unplugged, the port is reopened as soon as it becomes available again. Data
+//!   queued while disconnected is written once the port reconnects.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_nannou_serial::{
+//!     NannouSerialPlugin, SerialConfig, SerialEvent, SerialEventKind, SerialPort, SerialSend,
+//! };
+//!
+//! fn setup(mut commands: Commands) {
+//!     commands.spawn(SerialPort::open(SerialConfig::new("/dev/ttyACM0").baud_rate(115_200)));
+//! }
+//!
+//! fn update(mut events: EventReader<SerialEvent>, mut send: EventWriter<SerialSend>) {
+//!     for event in events.read() {
+//!         if let SerialEventKind::Line(ref line) = event.kind {
+//!             println!("sensor: {}", line);
+//!             send.send(SerialSend::line(event.entity, "ack"));
+//!         }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins((DefaultPlugins, NannouSerialPlugin))
+//!         .add_systems(Startup, setup)
+//!         .add_systems(Update, update)
+//!         .run();
+//! }
+//! ```
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use lines::LineBuffer;
+
+mod lines;
+
+/// The default baud rate.
+pub const DEFAULT_BAUD_RATE: u32 = 9_600;
+
+/// A serial port available on this machine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortInfo {
+    /// The path or name used to open the port, e.g. `/dev/ttyACM0` or `COM3`.
+    pub name: String,
+    /// The USB vendor ID, if the port is a USB device.
+    pub vid: Option<u16>,
+    /// The USB product ID, if the port is a USB device.
+    pub pid: Option<u16>,
+    /// The product name reported by the device, if any.
+    pub product: Option<String>,
+}
+
+/// How incoming data is surfaced as events.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReadMode {
+    /// Emit a `SerialEventKind::Line` for each newline-terminated line of text.
+    #[default]
+    Lines,
+    /// Emit a `SerialEventKind::Bytes` for each chunk of bytes as it arrives.
+    Bytes,
+}
+
+/// Configuration for a `SerialPort`.
+#[derive(Clone, Debug)]
+pub struct SerialConfig {
+    /// The path or name of the port, e.g. `/dev/ttyACM0` or `COM3`.
+    pub path: String,
+    /// The baud rate at which to communicate.
+    pub baud_rate: u32,
+    /// How incoming data is surfaced as events.
+    pub read_mode: ReadMode,
+    /// The interval at which to attempt to reopen the port while disconnected.
+    pub reconnect_interval: Duration,
+}
+
+/// An open serial port, reconnecting automatically if the device is unplugged.
+///
+/// The port is closed when the component is removed or its entity despawned.
+#[derive(Component)]
+pub struct SerialPort {
+    path: String,
+    connected: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    inbound: Mutex<mpsc::Receiver<SerialEventKind>>,
+    outbound: mpsc::Sender<Vec<u8>>,
+}
+
+/// An event that occurred on a `SerialPort`.
+#[derive(Event, Clone, Debug)]
+pub struct SerialEvent {
+    /// The entity with the `SerialPort` component.
+    pub entity: Entity,
+    /// The kind of event that occurred.
+    pub kind: SerialEventKind,
+}
+
+/// The kinds of events that may occur on a serial port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SerialEventKind {
+    /// The port was opened.
+    Connected,
+    /// A line of text was received, without its line ending.
+    Line(String),
+    /// A chunk of bytes was received.
+    Bytes(Vec<u8>),
+    /// An error occurred while opening, reading or writing the port.
+    Error(String),
+    /// The port was closed, e.g. as the device was unplugged.
+    Disconnected,
+}
+
+/// Queues data to be written to the `SerialPort` on the given entity.
+#[derive(Event, Clone, Debug)]
+pub struct SerialSend {
+    /// The entity with the `SerialPort` component.
+    pub entity: Entity,
+    /// The bytes to write.
+    pub data: Vec<u8>,
+}
+
+/// Errors that might occur while enumerating ports.
+#[derive(Debug)]
+pub struct SerialError(serialport::Error);
+
+/// Forwards the events of each `SerialPort` and queues the `SerialSend` events.
+pub struct NannouSerialPlugin;
+
+/// The serial ports currently available on this machine.
+pub fn available_ports() -> Result<Vec<PortInfo>, SerialError> {
+    let ports = serialport::available_ports()?
+        .into_iter()
+        .map(|port| {
+            let (vid, pid, product) = match port.port_type {
+                serialport::SerialPortType::UsbPort(usb) => {
+                    (Some(usb.vid), Some(usb.pid), usb.product)
+                }
+                _ => (None, None, None),
+            };
+            PortInfo {
+                name: port.port_name,
+                vid,
+                pid,
+                product,
+            }
+        })
+        .collect();
+    Ok(ports)
+}
+
+impl SerialConfig {
+    /// The default interval at which to attempt to reopen a disconnected port.
+    pub const DEFAULT_RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Configuration for the port at the given path with the default settings.
+    pub fn new(path: impl Into<String>) -> Self {
+        SerialConfig {
+            path: path.into(),
+            baud_rate: DEFAULT_BAUD_RATE,
+            read_mode: ReadMode::default(),
+            reconnect_interval: Self::DEFAULT_RECONNECT_INTERVAL,
+        }
+    }
+
+    /// Builder method for specifying the baud rate.
+    pub fn baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate;
+        self
+    }
+
+    /// Builder method for specifying how incoming data is surfaced as events.
+    pub fn read_mode(mut self, read_mode: ReadMode) -> Self {
+        self.read_mode = read_mode;
+        self
+    }
+
+    /// Builder method for specifying the interval at which to attempt to reopen the port.
+    pub fn reconnect_interval(mut self, interval: Duration) -> Self {
+        self.reconnect_interval = interval;
+        self
+    }
+}
+
+impl SerialPort {
+    /// Begin opening the port with the given configuration.
+    ///
+    /// A `SerialEventKind::Connected` event is emitted once the port is open, or a
+    /// `SerialEventKind::Error` event if it cannot be opened. Opening is retried until the
+    /// component is dropped.
+    pub fn open(config: SerialConfig) -> Self {
+        let path = config.path.clone();
+        let connected = Arc::new(AtomicBool::new(false));
+        let running = Arc::new(AtomicBool::new(true));
+        let (inbound_tx, inbound) = mpsc::channel();
+        let (outbound, outbound_rx) = mpsc::channel();
+        let thread_connected = connected.clone();
+        let thread_running = running.clone();
+        thread::Builder::new()
+            .name(format!("nannou_serial {}", path))
+            .spawn(move || {
+                port_loop(
+                    config,
+                    thread_connected,
+                    thread_running,
+                    inbound_tx,
+                    outbound_rx,
+                )
+            })
+            .expect("failed to spawn serial port thread");
+        SerialPort {
+            path,
+            connected,
+            running,
+            inbound: Mutex::new(inbound),
+            outbound,
+        }
+    }
+
+    /// The path or name of the port.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Whether or not the port is currently open.
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Queue the bytes to be written to the port.
+    pub fn send(&self, data: impl Into<Vec<u8>>) {
+        let _ = self.outbound.send(data.into());
+    }
+
+    /// Queue the text to be written to the port followed by a newline.
+    pub fn send_line(&self, line: &str) {
+        self.send(format!("{}\n", line));
+    }
+}
+
+impl SerialSend {
+    /// Queue the text to be written to the port followed by a newline.
+    pub fn line(entity: Entity, line: &str) -> Self {
+        let data = format!("{}\n", line).into_bytes();
+        SerialSend { entity, data }
+    }
+}
+
+impl Drop for SerialPort {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for SerialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to enumerate serial ports: {}", self.0)
+    }
+}
+
+impl std::error::Error for SerialError {}
+
+impl From<serialport::Error> for SerialError {
+    fn from(err: serialport::Error) -> Self {
+        SerialError(err)
+    }
+}
+
+impl Plugin for NannouSerialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SerialEvent>()
+            .add_event::<SerialSend>()
+            .add_systems(PreUpdate, forward_events)
+            .add_systems(PostUpdate, queue_sends);
+    }
+}
+
+// The duration a read may block, bounding how long writes and shutdown may be delayed.
+const READ_TIMEOUT: Duration = Duration::from_millis(10);
+
+// Open the port, service it until it fails and then retry until the component is dropped.
+fn port_loop(
+    config: SerialConfig,
+    connected: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    inbound: mpsc::Sender<SerialEventKind>,
+    outbound: mpsc::Receiver<Vec<u8>>,
+) {
+    // Only report the first of consecutive failures to open the port.
+    let mut reported_open_error = false;
+    let mut lines = LineBuffer::default();
+    while running.load(Ordering::Relaxed) {
+        let port = serialport::new(&config.path, config.baud_rate)
+            .timeout(READ_TIMEOUT)
+            .open();
+        let mut port = match port {
+            Ok(port) => port,
+            Err(err) => {
+                if !reported_open_error {
+                    let _ = inbound.send(SerialEventKind::Error(err.to_string()));
+                    reported_open_error = true;
+                }
+                thread::sleep(config.reconnect_interval);
+                continue;
+            }
+        };
+        reported_open_error = false;
+        connected.store(true, Ordering::Relaxed);
+        let _ = inbound.send(SerialEventKind::Connected);
+
+        let result = service(
+            &mut *port, &config, &running, &inbound, &outbound, &mut lines,
+        );
+        connected.store(false, Ordering::Relaxed);
+        lines.clear();
+        if let Err(err) = result {
+            let _ = inbound.send(SerialEventKind::Error(err.to_string()));
+        }
+        let _ = inbound.send(SerialEventKind::Disconnected);
+        if running.load(Ordering::Relaxed) {
+            thread::sleep(config.reconnect_interval);
+        }
+    }
+}
+
+// Write queued data and read incoming data until the port fails or the component is dropped.
+fn service(
+    port: &mut dyn serialport::SerialPort,
+    config: &SerialConfig,
+    running: &AtomicBool,
+    inbound: &mpsc::Sender<SerialEventKind>,
+    outbound: &mpsc::Receiver<Vec<u8>>,
+    lines: &mut LineBuffer,
+) -> io::Result<()> {
+    let mut buffer = [0u8; 1024];
+    while running.load(Ordering::Relaxed) {
+        while let Ok(data) = outbound.try_recv() {
+            port.write_all(&data)?;
+        }
+        let len = match port.read(&mut buffer) {
+            Ok(len) => len,
+            Err(ref err) if err.kind() == io::ErrorKind::TimedOut => continue,
+            Err(err) => return Err(err),
+        };
+        let bytes = &buffer[..len];
+        match config.read_mode {
+            ReadMode::Lines => {
+                for line in lines.push(bytes) {
+                    let _ = inbound.send(SerialEventKind::Line(line));
+                }
+            }
+            ReadMode::Bytes if !bytes.is_empty() => {
+                let _ = inbound.send(SerialEventKind::Bytes(bytes.to_vec()));
+            }
+            ReadMode::Bytes => (),
+        }
+    }
+    Ok(())
+}
+
+// Forward the events of all ports into the `SerialEvent` queue.
+fn forward_events(ports: Query<(Entity, &SerialPort)>, mut events: EventWriter<SerialEvent>) {
+    for (entity, port) in &ports {
+        let inbound = port.inbound.lock().expect("failed to lock serial port");
+        for kind in inbound.try_iter() {
+            events.send(SerialEvent { entity, kind });
+        }
+    }
+}
+
+// Queue the data of all `SerialSend` events on their ports.
+fn queue_sends(mut sends: EventReader<SerialSend>, ports: Query<&SerialPort>) {
+    for send in sends.read() {
+        if let Ok(port) = ports.get(send.entity) {
+            port.send(send.data.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lines::LineBuffer;
+
+    #[test]
+    fn lines_split_across_reads() {
+        let mut lines = LineBuffer::default();
+        assert!(lines.push(b"tem").is_empty());
+        assert_eq!(lines.push(b"p=21\r\nhum=4"), vec!["temp=21"]);
+        assert_eq!(lines.push(b"0\n\n"), vec!["hum=40", ""]);
+        lines.push(b"partial");
+        lines.clear();
+        assert_eq!(lines.push(b"ok\n"), vec!["ok"]);
+    }
+}
@@ -0,0 +1,32 @@
+// Splits a stream of bytes into newline-terminated lines.
+#[derive(Debug, Default)]
+pub(crate) struct LineBuffer {
+    pending: Vec<u8>,
+}
+
+impl LineBuffer {
+    // Append the bytes, returning all lines completed by them.
+    //
+    // Trailing carriage returns are stripped, as many microcontrollers terminate lines with
+    // `\r\n`. Invalid UTF-8 is replaced rather than discarding the line.
+    pub(crate) fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        let mut lines = vec![];
+        for &byte in bytes {
+            if byte != b'\n' {
+                self.pending.push(byte);
+                continue;
+            }
+            if self.pending.last() == Some(&b'\r') {
+                self.pending.pop();
+            }
+            lines.push(String::from_utf8_lossy(&self.pending).into_owned());
+            self.pending.clear();
+        }
+        lines
+    }
+
+    // Discard any partially received line, e.g. after the port disconnects.
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
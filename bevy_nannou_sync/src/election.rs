@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The rank of a node that may become master, where lower ranks take precedence.
+///
+/// Nodes that may never become master have no rank.
+pub(crate) type Rank = Option<u8>;
+
+// Tracks the nodes that are currently active and elects the master among them.
+#[derive(Debug)]
+pub(crate) struct Election {
+    node: u64,
+    rank: Rank,
+    timeout: Duration,
+    peers: HashMap<u64, Peer>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Peer {
+    rank: Rank,
+    last_seen: Instant,
+}
+
+impl Election {
+    pub(crate) fn new(node: u64, rank: Rank, timeout: Duration) -> Self {
+        Election {
+            node,
+            rank,
+            timeout,
+            peers: HashMap::new(),
+        }
+    }
+
+    // Record that a message was received from the given peer.
+    pub(crate) fn saw(&mut self, node: u64, rank: Rank, now: Instant) {
+        if node != self.node {
+            let last_seen = now;
+            self.peers.insert(node, Peer { rank, last_seen });
+        }
+    }
+
+    // The number of peers that have been seen within the timeout.
+    pub(crate) fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    // Forget peers that timed out and elect the node with the lowest rank, using the node ID to
+    // break ties.
+    pub(crate) fn master(&mut self, now: Instant) -> Option<u64> {
+        let timeout = self.timeout;
+        self.peers
+            .retain(|_, peer| now.saturating_duration_since(peer.last_seen) < timeout);
+        let me = self.rank.map(|rank| (rank, self.node));
+        let peers = self
+            .peers
+            .iter()
+            .filter_map(|(&node, peer)| peer.rank.map(|rank| (rank, node)));
+        me.into_iter().chain(peers).min().map(|(_, node)| node)
+    }
+}
@@ -0,0 +1,449 @@
+//! Keeps the time, frame number and an optional model in phase across several machines.
+//!
+//! Installations such as multi-projector walls are often driven by several computers, each
+//! rendering a part of the same scene. Adding the `NannouSyncPlugin` to each app elects a master
+//! among the machines on the local network. The master broadcasts its `SyncClock` along with the
+//! model resource over UDP each frame, and all other machines apply them as they arrive.
+//!
+//! Sketches should use the `SyncClock` rather than bevy's `Time` for anything that must remain in
+//! phase across machines.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_nannou_sync::{NannouSyncPlugin, SyncClock, SyncConfig};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Resource, Default, Serialize, Deserialize)]
+//! struct Model {
+//!     hue: f32,
+//! }
+//!
+//! fn update(clock: Res<SyncClock>, mut model: ResMut<Model>) {
+//!     if clock.is_master() {
+//!         model.hue = (clock.elapsed_seconds() * 0.1).fract() as f32;
+//!     }
+//! }
+//!
+//! fn main() {
+//!     App::new()
+//!         .add_plugins(DefaultPlugins)
+//!         .add_plugins(NannouSyncPlugin::<Model>::new(SyncConfig::default()))
+//!         .init_resource::<Model>()
+//!         .add_systems(Update, update)
+//!         .run();
+//! }
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io;
+use std::marker::PhantomData;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use election::{Election, Rank};
+
+mod election;
+
+/// The default port used for synchronisation.
+pub const DEFAULT_PORT: u16 = 9011;
+
+/// The largest message that may be sent, limited by the maximum size of a UDP datagram.
+///
+/// The serialized model must fit within a single message along with the clock.
+pub const MAX_MESSAGE_SIZE: usize = 65_507;
+
+/// The role of this machine within the group.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// Take part in the election, becoming master if no other machine takes precedence.
+    #[default]
+    Auto,
+    /// Take precedence over all `Auto` machines in the election.
+    ///
+    /// If several machines prefer to be master, one of them is elected.
+    Master,
+    /// Never become master.
+    Client,
+}
+
+/// Configuration for the `NannouSyncPlugin`.
+#[derive(Clone, Debug)]
+pub struct SyncConfig {
+    /// The role of this machine within the group.
+    pub role: Role,
+    /// The address on which messages are received.
+    pub bind: SocketAddr,
+    /// The address to which messages are sent.
+    ///
+    /// This is the broadcast address of the local network by default. A specific address may be
+    /// used for networks where broadcast is not available.
+    pub broadcast: SocketAddr,
+    /// The interval at which machines that are not master announce themselves.
+    pub heartbeat_interval: Duration,
+    /// The duration after which a silent machine is considered to have left the group.
+    pub timeout: Duration,
+}
+
+/// The clock shared by all machines in the group.
+///
+/// On the master, the clock advances with each update. On all other machines, the clock is set
+/// to that of the master as messages arrive, advancing locally in between.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SyncClock {
+    node: u64,
+    master: Option<u64>,
+    peers: usize,
+    frame: u64,
+    elapsed: Duration,
+}
+
+/// An empty model, for synchronising the `SyncClock` alone.
+#[derive(Resource, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct NoModel;
+
+/// Synchronises the `SyncClock` and the model resource `T` across machines.
+///
+/// The model is optional: if the resource does not exist, only the clock is synchronised.
+pub struct NannouSyncPlugin<T = NoModel> {
+    config: SyncConfig,
+    _model: PhantomData<fn() -> T>,
+}
+
+// The socket along with the state of the election.
+#[derive(Resource)]
+struct SyncNet {
+    socket: UdpSocket,
+    broadcast: SocketAddr,
+    rank: Rank,
+    election: Election,
+    heartbeat_interval: Duration,
+    last_heartbeat: Option<Instant>,
+    // Reused for each message received.
+    buffer: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Message {
+    Heartbeat {
+        node: u64,
+        rank: Rank,
+    },
+    State {
+        node: u64,
+        rank: Rank,
+        frame: u64,
+        elapsed: Duration,
+        model: Option<Vec<u8>>,
+    },
+}
+
+impl SyncConfig {
+    /// The default interval between heartbeats.
+    pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(250);
+    /// The default duration after which a silent machine is considered to have left the group.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Builder method for specifying the role of this machine.
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Builder method for specifying the port used to send and receive messages.
+    pub fn port(mut self, port: u16) -> Self {
+        self.bind.set_port(port);
+        self.broadcast.set_port(port);
+        self
+    }
+
+    /// Builder method for specifying the address to which messages are sent.
+    pub fn broadcast(mut self, addr: SocketAddr) -> Self {
+        self.broadcast = addr;
+        self
+    }
+}
+
+impl SyncClock {
+    /// The unique ID of this machine.
+    pub fn node(&self) -> u64 {
+        self.node
+    }
+
+    /// The ID of the current master, if one has been elected.
+    pub fn master(&self) -> Option<u64> {
+        self.master
+    }
+
+    /// Whether or not this machine is the master.
+    pub fn is_master(&self) -> bool {
+        self.master == Some(self.node)
+    }
+
+    /// The number of other machines in the group.
+    pub fn peers(&self) -> usize {
+        self.peers
+    }
+
+    /// The frame number of the master.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The time elapsed on the master since it started.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// The time elapsed on the master since it started in seconds.
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+
+    // Advance the clock by a single frame of the given duration.
+    fn advance(&mut self, delta: Duration) {
+        self.frame += 1;
+        self.elapsed += delta;
+    }
+}
+
+impl<T> NannouSyncPlugin<T> {
+    /// Synchronise using the given configuration.
+    pub fn new(config: SyncConfig) -> Self {
+        NannouSyncPlugin {
+            config,
+            _model: PhantomData,
+        }
+    }
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig {
+            role: Role::default(),
+            bind: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, DEFAULT_PORT).into(),
+            broadcast: SocketAddrV4::new(Ipv4Addr::BROADCAST, DEFAULT_PORT).into(),
+            heartbeat_interval: SyncConfig::DEFAULT_HEARTBEAT_INTERVAL,
+            timeout: SyncConfig::DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl<T> Default for NannouSyncPlugin<T> {
+    fn default() -> Self {
+        Self::new(SyncConfig::default())
+    }
+}
+
+impl<T> Plugin for NannouSyncPlugin<T>
+where
+    T: Resource + Serialize + DeserializeOwned,
+{
+    fn build(&self, app: &mut App) {
+        let node = new_node_id();
+        app.insert_resource(SyncClock {
+            node,
+            ..Default::default()
+        });
+        let socket = match bind(&self.config) {
+            Ok(socket) => socket,
+            Err(err) => {
+                // Keep the clock running so that the sketch still works on its own.
+                error!(
+                    "failed to bind sync socket to {}, running unsynchronised: {}",
+                    self.config.bind, err
+                );
+                app.add_systems(PreUpdate, advance_lone_master);
+                return;
+            }
+        };
+        let rank = match self.config.role {
+            Role::Master => Some(0),
+            Role::Auto => Some(1),
+            Role::Client => None,
+        };
+        app.insert_resource(SyncNet {
+            socket,
+            broadcast: self.config.broadcast,
+            rank,
+            election: Election::new(node, rank, self.config.timeout),
+            heartbeat_interval: self.config.heartbeat_interval,
+            last_heartbeat: None,
+            buffer: vec![0u8; MAX_MESSAGE_SIZE],
+        })
+        .add_systems(PreUpdate, receive_sync::<T>)
+        .add_systems(PostUpdate, send_sync::<T>);
+    }
+}
+
+fn bind(config: &SyncConfig) -> io::Result<UdpSocket> {
+    let socket = UdpSocket::bind(config.bind)?;
+    socket.set_nonblocking(true)?;
+    socket.set_broadcast(true)?;
+    Ok(socket)
+}
+
+// A random ID for this machine, used to identify it and to break ties in the election.
+fn new_node_id() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    std::process::id().hash(&mut hasher);
+    Instant::now().hash(&mut hasher);
+    hasher.finish()
+}
+
+// Receive all pending messages, elect the master and apply its latest state.
+fn receive_sync<T>(
+    mut net: ResMut<SyncNet>,
+    mut clock: ResMut<SyncClock>,
+    time: Res<Time>,
+    model: Option<ResMut<T>>,
+) where
+    T: Resource + DeserializeOwned,
+{
+    let now = Instant::now();
+    let SyncNet {
+        socket,
+        buffer,
+        election,
+        ..
+    } = &mut *net;
+    let mut latest = None;
+    loop {
+        let len = match socket.recv_from(buffer) {
+            Ok((len, _addr)) => len,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+            Err(err) => {
+                warn!("failed to receive sync message: {}", err);
+                break;
+            }
+        };
+        let msg: Message = match bincode::deserialize(&buffer[..len]) {
+            Ok(msg) => msg,
+            Err(err) => {
+                warn!("failed to deserialize sync message: {}", err);
+                continue;
+            }
+        };
+        match msg {
+            Message::Heartbeat { node, rank } => election.saw(node, rank, now),
+            Message::State {
+                node,
+                rank,
+                frame,
+                elapsed,
+                model,
+            } => {
+                election.saw(node, rank, now);
+                latest = Some((node, frame, elapsed, model));
+            }
+        }
+    }
+    clock.master = election.master(now);
+    clock.peers = election.peer_count();
+
+    // Advance locally, then adopt the master's state if it arrived.
+    clock.advance(time.delta());
+    if clock.is_master() {
+        return;
+    }
+    let Some((node, frame, elapsed, bytes)) = latest else {
+        return;
+    };
+    if clock.master != Some(node) {
+        return;
+    }
+    clock.frame = frame;
+    clock.elapsed = elapsed;
+    if let (Some(bytes), Some(mut model)) = (bytes, model) {
+        match bincode::deserialize(&bytes) {
+            Ok(m) => *model = m,
+            Err(err) => warn!("failed to deserialize synced model: {}", err),
+        }
+    }
+}
+
+// Advance the clock as the master of a group of one, used when the socket could not be bound.
+fn advance_lone_master(mut clock: ResMut<SyncClock>, time: Res<Time>) {
+    clock.master = Some(clock.node);
+    clock.peers = 0;
+    clock.advance(time.delta());
+}
+
+// Broadcast the state if this machine is the master, or a heartbeat otherwise.
+fn send_sync<T>(mut net: ResMut<SyncNet>, clock: Res<SyncClock>, model: Option<Res<T>>)
+where
+    T: Resource + Serialize,
+{
+    let node = clock.node;
+    let rank = net.rank;
+    let msg = if clock.is_master() {
+        let model = model.and_then(|model| match bincode::serialize(&*model) {
+            Ok(bytes) => Some(bytes),
+            Err(err) => {
+                warn!("failed to serialize synced model: {}", err);
+                None
+            }
+        });
+        Message::State {
+            node,
+            rank,
+            frame: clock.frame,
+            elapsed: clock.elapsed,
+            model,
+        }
+    } else {
+        let now = Instant::now();
+        let due = net.last_heartbeat.map_or(true, |last| {
+            now.duration_since(last) >= net.heartbeat_interval
+        });
+        if !due {
+            return;
+        }
+        net.last_heartbeat = Some(now);
+        Message::Heartbeat { node, rank }
+    };
+    let bytes = match bincode::serialize(&msg) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            warn!("failed to serialize sync message: {}", err);
+            return;
+        }
+    };
+    if bytes.len() > MAX_MESSAGE_SIZE {
+        warn!(
+            "sync message of {} bytes exceeds the maximum of {}",
+            bytes.len(),
+            MAX_MESSAGE_SIZE
+        );
+        return;
+    }
+    if let Err(err) = net.socket.send_to(&bytes, net.broadcast) {
+        warn!("failed to send sync message: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::election::Election;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn election_prefers_rank_then_node() {
+        let now = Instant::now();
+        let timeout = Duration::from_secs(1);
+        let mut election = Election::new(5, Some(1), timeout);
+        assert_eq!(election.master(now), Some(5));
+        election.saw(3, Some(1), now);
+        election.saw(1, None, now);
+        assert_eq!(election.master(now), Some(3));
+        election.saw(9, Some(0), now);
+        assert_eq!(election.master(now), Some(9));
+        // Peers that time out are forgotten.
+        assert_eq!(election.master(now + timeout * 2), Some(5));
+        assert_eq!(election.peer_count(), 0);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::render_asset::{prepare_assets, RenderAssetUsages, RenderAssets};
+use bevy::render::render_resource::{
+    Buffer, Extent3d, SamplerDescriptor, Texture, TextureDimension, TextureFormat,
+    TextureFormatPixelInfo, TextureViewDescriptor,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::texture::GpuImage;
+use bevy::render::{Render, RenderApp, RenderSet};
+
+/// Access to the device and queue used for rendering, for use with other wgpu crates.
+///
+/// Resources created via this device may be used directly by the renderer, avoiding a round-trip
+/// through the CPU. The wgpu device and queue are available via `RenderDevice::wgpu_device` and by
+/// dereferencing the `RenderQueue`.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::render::render_resource::{BufferDescriptor, BufferUsages};
+/// use bevy_nannou_wgpu::{Gpu, SharedBufferId, SharedBuffers};
+///
+/// #[derive(Resource)]
+/// struct Particles(SharedBufferId);
+///
+/// fn setup(mut commands: Commands, gpu: Gpu, buffers: Res<SharedBuffers>) {
+///     let buffer = gpu.device().create_buffer(&BufferDescriptor {
+///         label: Some("particles"),
+///         size: 1024,
+///         usage: BufferUsages::STORAGE | BufferUsages::VERTEX,
+///         mapped_at_creation: false,
+///     });
+///     commands.insert_resource(Particles(buffers.insert(buffer)));
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct Gpu<'w> {
+    device: Res<'w, RenderDevice>,
+    queue: Res<'w, RenderQueue>,
+}
+
+/// Buffers created by external wgpu code, shared with the render world.
+///
+/// The same registry is available as a resource in both the main world and the render world, so
+/// render graph nodes and materials may bind buffers written by other crates without copying them.
+#[derive(Resource, Clone, Default)]
+pub struct SharedBuffers {
+    buffers: Arc<Mutex<HashMap<SharedBufferId, Buffer>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+/// Uniquely identifies a buffer within the `SharedBuffers`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SharedBufferId(u64);
+
+/// Textures created by external wgpu code, registered as drawable images.
+///
+/// Each registered texture is assigned an image handle that may be drawn like any other image.
+/// The texture is used by the renderer directly, so its contents never pass through the CPU.
+#[derive(Resource, Clone, Default)]
+pub struct ExternalTextures {
+    textures: Arc<Mutex<HashMap<AssetId<Image>, ExternalTexture>>>,
+}
+
+/// Adds the `SharedBuffers` and `ExternalTextures` resources and the render systems that make
+/// external textures drawable.
+pub struct GpuInteropPlugin;
+
+#[derive(Clone)]
+struct ExternalTexture {
+    texture: Texture,
+    format: TextureFormat,
+    size: UVec2,
+}
+
+impl<'w> Gpu<'w> {
+    /// The device used for rendering.
+    pub fn device(&self) -> &RenderDevice {
+        &self.device
+    }
+
+    /// The queue used for rendering.
+    pub fn queue(&self) -> &RenderQueue {
+        &self.queue
+    }
+}
+
+impl SharedBuffers {
+    /// Share the given buffer, returning the ID by which it may be retrieved.
+    pub fn insert(&self, buffer: impl Into<Buffer>) -> SharedBufferId {
+        let id = SharedBufferId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.buffers
+            .lock()
+            .expect("failed to lock shared buffers")
+            .insert(id, buffer.into());
+        id
+    }
+
+    /// The buffer with the given ID.
+    pub fn get(&self, id: SharedBufferId) -> Option<Buffer> {
+        self.buffers
+            .lock()
+            .expect("failed to lock shared buffers")
+            .get(&id)
+            .cloned()
+    }
+
+    /// Stop sharing the buffer with the given ID.
+    ///
+    /// The buffer is destroyed once all other references to it are dropped.
+    pub fn remove(&self, id: SharedBufferId) -> Option<Buffer> {
+        self.buffers
+            .lock()
+            .expect("failed to lock shared buffers")
+            .remove(&id)
+    }
+}
+
+impl ExternalTextures {
+    /// Register the given texture, returning an image handle by which it may be drawn.
+    ///
+    /// The `format` and `size` must match those with which the texture was created, and the
+    /// texture must have been created with `TextureUsages::TEXTURE_BINDING`. Only the first mip
+    /// level is used.
+    pub fn register(
+        &self,
+        images: &mut Assets<Image>,
+        texture: impl Into<Texture>,
+        format: TextureFormat,
+        size: UVec2,
+    ) -> Handle<Image> {
+        // A placeholder that is replaced by the external texture once prepared.
+        let placeholder = Image::new_fill(
+            Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &vec![0; format.pixel_size()],
+            format,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        let handle = images.add(placeholder);
+        let external = ExternalTexture {
+            texture: texture.into(),
+            format,
+            size,
+        };
+        self.textures
+            .lock()
+            .expect("failed to lock external textures")
+            .insert(handle.id(), external);
+        handle
+    }
+
+    /// Stop drawing the external texture via the given image.
+    ///
+    /// Returns `false` if the image was not registered.
+    pub fn unregister(&self, image: &Handle<Image>) -> bool {
+        self.textures
+            .lock()
+            .expect("failed to lock external textures")
+            .remove(&image.id())
+            .is_some()
+    }
+
+    /// Whether or not the given image refers to an external texture.
+    pub fn contains(&self, image: &Handle<Image>) -> bool {
+        self.textures
+            .lock()
+            .expect("failed to lock external textures")
+            .contains_key(&image.id())
+    }
+}
+
+impl Plugin for GpuInteropPlugin {
+    fn build(&self, app: &mut App) {
+        let buffers = SharedBuffers::default();
+        let textures = ExternalTextures::default();
+        app.insert_resource(buffers.clone())
+            .insert_resource(textures.clone());
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .insert_resource(buffers)
+                .insert_resource(textures)
+                .add_systems(
+                    Render,
+                    prepare_external_textures
+                        .in_set(RenderSet::PrepareAssets)
+                        .after(prepare_assets::<Image>),
+                );
+        }
+    }
+}
+
+// Replace the prepared placeholder of each external texture with the texture itself.
+fn prepare_external_textures(
+    external: Res<ExternalTextures>,
+    mut images: ResMut<RenderAssets<Image>>,
+    device: Res<RenderDevice>,
+) {
+    let textures = external
+        .textures
+        .lock()
+        .expect("failed to lock external textures");
+    for (&id, external) in textures.iter() {
+        // Wait for the placeholder to be prepared, so that removed images are not revived.
+        match images.get(id) {
+            Some(gpu_image) if gpu_image.texture.id() != external.texture.id() => (),
+            _ => continue,
+        }
+        let gpu_image = GpuImage {
+            texture: external.texture.clone(),
+            texture_view: external
+                .texture
+                .create_view(&TextureViewDescriptor::default())
+                .into(),
+            texture_format: external.format,
+            sampler: device.create_sampler(&SamplerDescriptor::default()),
+            size: external.size.as_vec2(),
+            mip_level_count: 1,
+        };
+        images.insert(id, gpu_image);
+    }
+}
@@ -1,8 +1,17 @@
 use bevy::prelude::*;
 
-//TODO: Does this need to be a plugin to inject anything into the ECS? Or just utils?
-struct NannouWgpuPlugin;
+pub use interop::{ExternalTextures, Gpu, GpuInteropPlugin, SharedBufferId, SharedBuffers};
+pub use readback::{ReadbackError, TextureReadback, TextureReadbackFuture, TextureReadbackPlugin};
+pub use timer::{GpuTimer, GpuTimerPlugin, GpuTiming, GpuTimings};
+
+mod interop;
+mod readback;
+mod timer;
+
+pub struct NannouWgpuPlugin;
 
 impl Plugin for NannouWgpuPlugin {
-    fn build(&self, app: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.add_plugins((GpuInteropPlugin, TextureReadbackPlugin, GpuTimerPlugin));
+    }
 }
@@ -0,0 +1,296 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use bevy::math::URect;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_resource::{
+    Buffer, BufferAsyncError, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d,
+    ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, Maintain, MapMode, Origin3d, TextureAspect,
+    TextureFormat,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::{Render, RenderApp, RenderSet};
+
+/// Schedules asynchronous reads of image textures back to the CPU.
+///
+/// Requests are encoded after the frame has been rendered, so the resulting image contains the
+/// contents of the texture at the end of the frame in which it was requested. This allows
+/// sketches to analyze their own rendered output, e.g. for feedback or fitness functions.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::math::URect;
+/// use bevy_nannou_wgpu::{TextureReadback, TextureReadbackFuture};
+///
+/// #[derive(Resource)]
+/// struct Target(Handle<Image>);
+///
+/// fn request(readback: Res<TextureReadback>, target: Res<Target>) {
+///     let future: TextureReadbackFuture =
+///         readback.read_texture_region(&target.0, URect::new(0, 0, 64, 64));
+///     // Await the future, or poll it via `try_take` in a later frame.
+/// }
+/// ```
+#[derive(Resource, Clone, Default)]
+pub struct TextureReadback {
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+/// The result of a `TextureReadback` request.
+///
+/// Resolves once the copy has completed and the buffer has been mapped. Alternatively, call
+/// `try_take` each frame to poll for the result without an executor.
+#[derive(Clone)]
+pub struct TextureReadbackFuture {
+    shared: Arc<Mutex<Shared>>,
+}
+
+/// Errors that might occur while reading a texture back to the CPU.
+#[derive(Debug)]
+pub enum ReadbackError {
+    /// Only 8-bit RGBA and BGRA textures may be read back.
+    UnsupportedFormat(TextureFormat),
+    /// The requested region does not overlap the texture.
+    EmptyRegion,
+    /// Mapping the destination buffer failed.
+    BufferAsync(BufferAsyncError),
+}
+
+/// Adds the `TextureReadback` resource and the render systems that process its requests.
+pub struct TextureReadbackPlugin;
+
+struct Request {
+    image: AssetId<Image>,
+    rect: URect,
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Default)]
+struct Shared {
+    result: Option<Result<image::RgbaImage, ReadbackError>>,
+    waker: Option<Waker>,
+}
+
+// Requests copied to buffers whose mapping has not yet completed.
+#[derive(Resource, Default)]
+struct InFlight(Arc<Mutex<usize>>);
+
+// Rows of a buffer copied from a texture must be padded to this alignment.
+const COPY_BYTES_PER_ROW_ALIGNMENT: u32 = 256;
+
+impl TextureReadback {
+    /// Schedule a read of the given region of the image's texture.
+    ///
+    /// The region is clamped to the bounds of the texture. If the image has not yet been
+    /// prepared on the GPU, the request remains pending until it has.
+    pub fn read_texture_region(&self, image: &Handle<Image>, rect: URect) -> TextureReadbackFuture {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let request = Request {
+            image: image.id(),
+            rect,
+            shared: shared.clone(),
+        };
+        self.requests
+            .lock()
+            .expect("failed to lock readback requests")
+            .push(request);
+        TextureReadbackFuture { shared }
+    }
+
+    /// Schedule a read of the entire texture of the given image.
+    pub fn read_texture(&self, image: &Handle<Image>) -> TextureReadbackFuture {
+        self.read_texture_region(image, URect::new(0, 0, u32::MAX, u32::MAX))
+    }
+}
+
+impl TextureReadbackFuture {
+    /// Take the result if the read has completed.
+    pub fn try_take(&self) -> Option<Result<image::RgbaImage, ReadbackError>> {
+        self.shared
+            .lock()
+            .expect("failed to lock readback result")
+            .result
+            .take()
+    }
+}
+
+impl Future for TextureReadbackFuture {
+    type Output = Result<image::RgbaImage, ReadbackError>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().expect("failed to lock readback result");
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TextureReadbackFuture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TextureReadbackFuture")
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ReadbackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ReadbackError::UnsupportedFormat(format) => {
+                write!(f, "cannot read back a texture with format {:?}", format)
+            }
+            ReadbackError::EmptyRegion => write!(f, "the region does not overlap the texture"),
+            ReadbackError::BufferAsync(ref err) => write!(f, "failed to map buffer: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReadbackError {}
+
+impl Shared {
+    fn complete(&mut self, result: Result<image::RgbaImage, ReadbackError>) {
+        self.result = Some(result);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Plugin for TextureReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        let readback = TextureReadback::default();
+        app.insert_resource(readback.clone());
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .insert_resource(readback)
+                .init_resource::<InFlight>()
+                .add_systems(Render, encode_readbacks.in_set(RenderSet::Cleanup));
+        }
+    }
+}
+
+// Encode a copy for each request whose image has been prepared, then map the buffers.
+fn encode_readbacks(
+    readback: Res<TextureReadback>,
+    in_flight: Res<InFlight>,
+    images: Res<RenderAssets<Image>>,
+    device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+) {
+    let mut requests = readback
+        .requests
+        .lock()
+        .expect("failed to lock readback requests");
+    let mut encoded = vec![];
+    requests.retain(|request| {
+        let Some(gpu_image) = images.get(request.image) else {
+            return true;
+        };
+        let mut shared = request
+            .shared
+            .lock()
+            .expect("failed to lock readback result");
+        let format = gpu_image.texture_format;
+        let swizzle = match format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            _ => {
+                shared.complete(Err(ReadbackError::UnsupportedFormat(format)));
+                return false;
+            }
+        };
+        let size = gpu_image.size.as_uvec2();
+        let rect = request
+            .rect
+            .intersect(URect::from_corners(UVec2::ZERO, size));
+        if rect.is_empty() {
+            shared.complete(Err(ReadbackError::EmptyRegion));
+            return false;
+        }
+
+        let [w, h] = [rect.width(), rect.height()];
+        let unpadded = w * 4;
+        let padded = unpadded.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("nannou texture readback"),
+            size: padded as u64 * h as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("nannou texture readback"),
+        });
+        encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &gpu_image.texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: rect.min.x,
+                    y: rect.min.y,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit([encoder.finish()]);
+        encoded.push((buffer, [w, h], padded, swizzle, request.shared.clone()));
+        false
+    });
+    drop(requests);
+
+    for (buffer, size, padded, swizzle, shared) in encoded {
+        *in_flight.0.lock().expect("failed to lock in flight count") += 1;
+        let in_flight = in_flight.0.clone();
+        let mapped = buffer.clone();
+        buffer.slice(..).map_async(MapMode::Read, move |result| {
+            let result = match result {
+                Err(err) => Err(ReadbackError::BufferAsync(err)),
+                Ok(()) => Ok(read_image(&mapped, size, padded, swizzle)),
+            };
+            mapped.unmap();
+            shared
+                .lock()
+                .expect("failed to lock readback result")
+                .complete(result);
+            *in_flight.lock().expect("failed to lock in flight count") -= 1;
+        });
+    }
+
+    // Drive the mapping callbacks while any reads are outstanding.
+    if *in_flight.0.lock().expect("failed to lock in flight count") > 0 {
+        device.poll(Maintain::Poll);
+    }
+}
+
+// Copy the mapped rows of the buffer into an image, removing the padding.
+fn read_image(buffer: &Buffer, [w, h]: [u32; 2], padded: u32, swizzle: bool) -> image::RgbaImage {
+    let data = buffer.slice(..).get_mapped_range();
+    let mut bytes = Vec::with_capacity(w as usize * h as usize * 4);
+    for row in data.chunks_exact(padded as usize) {
+        bytes.extend_from_slice(&row[..w as usize * 4]);
+    }
+    drop(data);
+    if swizzle {
+        bytes.chunks_exact_mut(4).for_each(|p| p.swap(0, 2));
+    }
+    image::RgbaImage::from_raw(w, h, bytes).expect("buffer size did not match the image")
+}
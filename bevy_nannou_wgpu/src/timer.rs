@@ -0,0 +1,366 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{
+    Buffer, BufferAddress, BufferDescriptor, BufferUsages, CommandEncoder, Maintain, MapMode,
+    QuerySet, QuerySetDescriptor, QueryType, WgpuFeatures,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::RenderApp;
+
+/// Measures the time spent by the GPU executing arbitrary compute or render passes via timestamp
+/// queries.
+///
+/// Each measured span of commands is a **scope** identified by a label. Scopes are written to a
+/// command encoder between passes via `begin` and `end` (or `scope`), after which `resolve`
+/// encodes a copy of the frame's timestamps for readback. Once the GPU has finished, the
+/// measurements are read back asynchronously and become available via `timings` during a later
+/// frame. Report them to the `GpuTimings` resource to make them available to the main world.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy::render::render_resource::CommandEncoderDescriptor;
+/// use bevy::render::renderer::{RenderDevice, RenderQueue};
+/// use bevy_nannou_wgpu::{GpuTimer, GpuTimings};
+///
+/// // A render world system encoding some passes.
+/// fn blur(
+///     mut timer: Local<Option<GpuTimer>>,
+///     timings: Res<GpuTimings>,
+///     device: Res<RenderDevice>,
+///     queue: Res<RenderQueue>,
+/// ) {
+///     if timer.is_none() {
+///         *timer = GpuTimer::new(&device, &queue);
+///     }
+///     let Some(timer) = timer.as_mut() else {
+///         return;
+///     };
+///     let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+///     timer.scope(&mut encoder, "blur", |encoder| {
+///         let _pass = encoder.begin_compute_pass(&Default::default());
+///         // ...
+///     });
+///     timer.resolve(&device, &mut encoder);
+///     queue.submit([encoder.finish()]);
+///     timings.report(timer);
+/// }
+/// ```
+///
+/// Requires a device with `WgpuFeatures::TIMESTAMP_QUERY` enabled.
+pub struct GpuTimer {
+    query_set: QuerySet,
+    resolve_buffer: Buffer,
+    max_scopes: u32,
+    // The labels of the scopes written since the last `resolve`.
+    labels: Vec<String>,
+    // Whether or not the most recent scope is yet to end.
+    open: bool,
+    readbacks: Vec<Readback>,
+    // The number of nanoseconds per timestamp tick.
+    period: f32,
+    timings: Vec<GpuTiming>,
+}
+
+/// The time spent by the GPU executing a single scope of a `GpuTimer`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GpuTiming {
+    /// The label given to the scope.
+    pub label: String,
+    /// The duration between the beginning and end of the scope.
+    pub duration: Duration,
+}
+
+/// The timings most recently reported by the `GpuTimer`s of the render world, e.g. for display
+/// within a stats overlay.
+///
+/// The same timings are shared between the main and render worlds.
+#[derive(Resource, Clone, Default)]
+pub struct GpuTimings {
+    timings: Arc<Mutex<Vec<GpuTiming>>>,
+}
+
+/// Adds the `GpuTimings` resource to both the main and render worlds.
+pub struct GpuTimerPlugin;
+
+// The readback states mirror those of the `nannou` draw renderer's timer. The two can't share an
+// implementation as `nannou` renders via `nannou_wgpu`'s wgpu, whose types are incompatible with
+// those of the wgpu version used by bevy.
+#[derive(Debug)]
+struct Readback {
+    buffer: Buffer,
+    labels: Vec<String>,
+    state: Arc<Mutex<ReadbackState>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ReadbackState {
+    Free,
+    // Encoded into a command buffer that is yet to be mapped.
+    Encoded,
+    Mapping,
+    Mapped,
+}
+
+impl GpuTimer {
+    /// The default maximum number of scopes that may be measured between calls to `resolve`.
+    pub const DEFAULT_MAX_SCOPES: u32 = 16;
+
+    /// The maximum number of frames awaiting readback at once. Frames are skipped while all are
+    /// in flight.
+    pub const MAX_READBACKS: usize = 4;
+
+    /// Create a timer if the given device supports timestamp queries.
+    pub fn new(device: &RenderDevice, queue: &RenderQueue) -> Option<Self> {
+        Self::with_max_scopes(device, queue, Self::DEFAULT_MAX_SCOPES)
+    }
+
+    /// Create a timer that may measure up to `max_scopes` scopes between calls to `resolve`, if
+    /// the given device supports timestamp queries.
+    ///
+    /// Scopes beyond the maximum are not measured.
+    pub fn with_max_scopes(
+        device: &RenderDevice,
+        queue: &RenderQueue,
+        max_scopes: u32,
+    ) -> Option<Self> {
+        if !device.features().contains(WgpuFeatures::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let max_scopes = max_scopes.max(1);
+        let query_set = device.wgpu_device().create_query_set(&QuerySetDescriptor {
+            label: Some("nannou_gpu_timer_query_set"),
+            ty: QueryType::Timestamp,
+            count: max_scopes * 2,
+        });
+        let resolve_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("nannou_gpu_timer_resolve_buffer"),
+            size: timestamps_size(max_scopes),
+            usage: BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        Some(GpuTimer {
+            query_set,
+            resolve_buffer,
+            max_scopes,
+            labels: vec![],
+            open: false,
+            readbacks: vec![],
+            period: queue.get_timestamp_period(),
+            timings: vec![],
+        })
+    }
+
+    /// The maximum number of scopes that may be measured between calls to `resolve`.
+    pub fn max_scopes(&self) -> u32 {
+        self.max_scopes
+    }
+
+    /// The timings of the scopes of the most recently read back frame, in the order in which
+    /// they began.
+    pub fn timings(&self) -> &[GpuTiming] {
+        &self.timings
+    }
+
+    /// The most recently read back duration of the scope with the given label, if any.
+    pub fn timing(&self, label: &str) -> Option<Duration> {
+        self.timings
+            .iter()
+            .find(|timing| timing.label == label)
+            .map(|timing| timing.duration)
+    }
+
+    /// Write the timestamp beginning a scope with the given label.
+    ///
+    /// Scopes may not be nested. Any scope left open is ended first.
+    pub fn begin(&mut self, encoder: &mut CommandEncoder, label: &str) {
+        if self.open {
+            self.end(encoder);
+        }
+        let ix = self.labels.len() as u32;
+        if ix >= self.max_scopes {
+            return;
+        }
+        encoder.write_timestamp(&self.query_set, ix * 2);
+        self.labels.push(label.to_string());
+        self.open = true;
+    }
+
+    /// Write the timestamp ending the current scope.
+    pub fn end(&mut self, encoder: &mut CommandEncoder) {
+        if !self.open {
+            return;
+        }
+        let ix = self.labels.len() as u32 - 1;
+        encoder.write_timestamp(&self.query_set, ix * 2 + 1);
+        self.open = false;
+    }
+
+    /// Measure the commands encoded by the given function within a scope with the given label.
+    pub fn scope<F, T>(&mut self, encoder: &mut CommandEncoder, label: &str, f: F) -> T
+    where
+        F: FnOnce(&mut CommandEncoder) -> T,
+    {
+        self.begin(encoder, label);
+        let t = f(encoder);
+        self.end(encoder);
+        t
+    }
+
+    /// Collect any finished measurements and encode a copy of the timestamps written since the
+    /// last call for readback.
+    ///
+    /// Call this once per frame after all scopes have been written. The encoder must be
+    /// submitted no earlier than those of the scopes.
+    pub fn resolve(&mut self, device: &RenderDevice, encoder: &mut CommandEncoder) {
+        self.end(encoder);
+        self.collect(device);
+        let labels = std::mem::take(&mut self.labels);
+        if labels.is_empty() {
+            return;
+        }
+        let free = self
+            .readbacks
+            .iter()
+            .position(|rb| *rb.state.lock().unwrap() == ReadbackState::Free);
+        let ix = match free {
+            Some(ix) => ix,
+            None if self.readbacks.len() < Self::MAX_READBACKS => {
+                let buffer = device.create_buffer(&BufferDescriptor {
+                    label: Some("nannou_gpu_timer_readback_buffer"),
+                    size: timestamps_size(self.max_scopes),
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                self.readbacks.push(Readback {
+                    buffer,
+                    labels: vec![],
+                    state: Arc::new(Mutex::new(ReadbackState::Free)),
+                });
+                self.readbacks.len() - 1
+            }
+            None => return,
+        };
+        let count = labels.len() as u32 * 2;
+        let readback = &mut self.readbacks[ix];
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &readback.buffer,
+            0,
+            timestamps_size(count / 2),
+        );
+        readback.labels = labels;
+        *readback.state.lock().unwrap() = ReadbackState::Encoded;
+    }
+
+    // Read back mapped measurements and request mapping for those encoded since the last frame.
+    //
+    // Command buffers are submitted between frames, so those encoded during the previous frame
+    // may now be mapped.
+    fn collect(&mut self, device: &RenderDevice) {
+        let mut mapping = false;
+        for readback in &mut self.readbacks {
+            let mut state = readback.state.lock().unwrap();
+            match *state {
+                ReadbackState::Mapped => {
+                    {
+                        let view = readback.buffer.slice(..).get_mapped_range();
+                        let period = self.period as f64;
+                        self.timings = readback
+                            .labels
+                            .drain(..)
+                            .zip(view.chunks_exact(16))
+                            .map(|(label, bytes)| {
+                                let start = u64::from_ne_bytes(bytes[0..8].try_into().unwrap());
+                                let end = u64::from_ne_bytes(bytes[8..16].try_into().unwrap());
+                                let nanos = end.saturating_sub(start) as f64 * period;
+                                let duration = Duration::from_nanos(nanos as u64);
+                                GpuTiming { label, duration }
+                            })
+                            .collect();
+                    }
+                    readback.buffer.unmap();
+                    *state = ReadbackState::Free;
+                }
+                ReadbackState::Encoded => {
+                    // Release the lock first as the callback may be called immediately on error.
+                    *state = ReadbackState::Mapping;
+                    drop(state);
+                    let state = readback.state.clone();
+                    let slice = readback.buffer.slice(..);
+                    slice.map_async(MapMode::Read, move |result| {
+                        *state.lock().unwrap() = match result {
+                            Ok(()) => ReadbackState::Mapped,
+                            Err(_) => ReadbackState::Free,
+                        };
+                    });
+                    mapping = true;
+                }
+                ReadbackState::Mapping => mapping = true,
+                ReadbackState::Free => (),
+            }
+        }
+        if mapping {
+            device.poll(Maintain::Poll);
+        }
+    }
+}
+
+impl GpuTimings {
+    /// Replace the reported timings with the most recent timings of the given timer.
+    ///
+    /// Call this each frame after resolving the timer.
+    pub fn report(&self, timer: &GpuTimer) {
+        let mut timings = self.timings.lock().expect("failed to lock gpu timings");
+        timings.clear();
+        timings.extend_from_slice(timer.timings());
+    }
+
+    /// The most recently reported timings.
+    pub fn get(&self) -> Vec<GpuTiming> {
+        self.timings
+            .lock()
+            .expect("failed to lock gpu timings")
+            .clone()
+    }
+}
+
+impl Plugin for GpuTimerPlugin {
+    fn build(&self, app: &mut App) {
+        let timings = GpuTimings::default();
+        app.insert_resource(timings.clone());
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.insert_resource(timings);
+        }
+    }
+}
+
+impl fmt::Debug for GpuTimer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GpuTimer")
+            .field("max_scopes", &self.max_scopes)
+            .field("labels", &self.labels)
+            .field("readbacks", &self.readbacks)
+            .field("period", &self.period)
+            .field("timings", &self.timings)
+            .finish()
+    }
+}
+
+impl fmt::Debug for GpuTimings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GpuTimings")
+            .field("timings", &self.get())
+            .finish()
+    }
+}
+
+// The size in bytes of the resolved timestamps of the given number of scopes.
+fn timestamps_size(scopes: u32) -> BufferAddress {
+    scopes as BufferAddress * 2 * std::mem::size_of::<u64>() as BufferAddress
+}
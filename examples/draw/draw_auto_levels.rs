@@ -0,0 +1,76 @@
+//! Demonstrates normalizing the range of a frame with `draw.image_fx`.
+//!
+//! The scene is deliberately drawn with low contrast and its overall brightness drifts over time,
+//! similar to a camera feed under changing light. Press space to cycle between no correction,
+//! auto-levels and histogram equalization.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    fxs: Vec<(&'static str, Option<wgpu::ImageFx>)>,
+    selected: usize,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(1024, 640)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    // Smoothing avoids sudden jumps in brightness as the scene changes.
+    let auto = wgpu::Levels::auto().clip(0.01).smoothing(0.9);
+    let equalize = wgpu::Levels::equalize().smoothing(0.9);
+    let fxs = vec![
+        ("none", None),
+        ("auto levels", Some(auto.into())),
+        ("equalize", Some(equalize.into())),
+    ];
+    Model { fxs, selected: 1 }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if key == Key::Space {
+        model.selected = (model.selected + 1) % model.fxs.len();
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    let t = app.time;
+
+    // A dim, drifting range of gray.
+    let base = 0.3 + 0.15 * (t * 0.2).sin();
+    draw.background().color(rgb(base, base, base));
+
+    // Overlapping shapes that differ only slightly in brightness.
+    let win = app.window_rect();
+    for i in 0..40 {
+        let f = i as f32 / 40.0;
+        let x = (t * 0.2 + f * TAU * 2.0).cos() * win.w() * 0.4;
+        let y = (t * 0.3 + f * TAU * 5.0).sin() * win.h() * 0.4;
+        let l = base + 0.1 * (f - 0.5);
+        draw.ellipse()
+            .x_y(x, y)
+            .radius(30.0 + 50.0 * f)
+            .color(hsla(f, 0.3, l, 0.6));
+    }
+
+    let (name, ref fx) = model.fxs[model.selected];
+    if let Some(fx) = fx {
+        draw.image_fx(fx);
+    }
+
+    let text = format!("{} (press space to change)", name);
+    draw.text(&text)
+        .font_size(28)
+        .w(win.w())
+        .x_y(0.0, win.top() - 40.0)
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,89 @@
+//! Demonstrates gradient and image backgrounds.
+//!
+//! The background is drawn beneath all other primitives. A semi-transparent gradient is drawn
+//! over the previous frame, leaving trails behind the moving circle.
+//!
+//! - Press `Space` to cycle between the two color gradient, multi-stop gradient and image
+//!   backgrounds.
+//! - Press `1`, `2` or `3` to switch between the `Stretch`, `Contain` and `Cover` fill modes.
+
+use nannou::color::{Gradient, GradientSpace};
+use nannou::draw::FillMode;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    texture: wgpu::Texture,
+    sunset: Gradient,
+    background: usize,
+    fill_mode: FillMode,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(720, 480)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let assets = app.assets_path().unwrap();
+    let img_path = assets.join("images").join("nature").join("nature_1.jpg");
+    let texture = wgpu::Texture::from_path(app, img_path).unwrap();
+    let sunset = Gradient::new([
+        (0.0, rgb(0.05, 0.05, 0.25)),
+        (0.5, rgb(0.9, 0.3, 0.4)),
+        (0.8, rgb(1.0, 0.7, 0.2)),
+        (1.0, rgb(0.2, 0.1, 0.1)),
+    ])
+    .space(GradientSpace::Oklch);
+    Model {
+        texture,
+        sunset,
+        background: 0,
+        fill_mode: FillMode::Cover,
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::Space => model.background = (model.background + 1) % 3,
+        Key::Key1 => model.fill_mode = FillMode::Stretch,
+        Key::Key2 => model.fill_mode = FillMode::Contain,
+        Key::Key3 => model.fill_mode = FillMode::Cover,
+        _ => (),
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+
+    match model.background {
+        0 => {
+            // Clear the first frame only, so that the translucent gradient leaves trails.
+            if frame.nth() == 0 {
+                draw.background().color(BLACK);
+            }
+            draw.background()
+                .gradient(rgba(0.1, 0.1, 0.3, 0.1), rgba(0.8, 0.3, 0.2, 0.1));
+        }
+        1 => {
+            draw.background().gradient_stops(&model.sunset);
+        }
+        _ => {
+            draw.background()
+                .color(BLACK)
+                .image(&model.texture, model.fill_mode);
+        }
+    }
+
+    let t = app.time;
+    let win = app.window_rect();
+    let x = (t * 1.3).sin() * win.w() * 0.35;
+    let y = (t * 2.1).cos() * win.h() * 0.35;
+    draw.ellipse().x_y(x, y).radius(24.0).color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
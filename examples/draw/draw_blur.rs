@@ -0,0 +1,56 @@
+//! Demonstrates blurring the contents of a frame with `draw.blur`.
+//!
+//! Everything drawn before the call to `draw.blur` is blurred, while the text drawn afterwards
+//! remains sharp. Move the mouse horizontally to change the blur radius. Radii beyond
+//! `wgpu::Blur::MAX_GAUSSIAN_RADIUS` switch to the dual filter.
+
+use nannou::prelude::*;
+
+// The largest radius reachable with the mouse, in pixels.
+const MAX_RADIUS: f32 = 200.0;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    blur: wgpu::Blur,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().size(1024, 640).view(view).build().unwrap();
+    Model {
+        blur: wgpu::Blur::new(0.0),
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    let win = app.window_rect();
+    let radius = map_range(app.mouse.x, win.left(), win.right(), 0.0, MAX_RADIUS);
+    model.blur.set_radius(radius.max(0.0));
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // A field of moving circles to be blurred.
+    let win = app.window_rect();
+    let t = app.time;
+    for i in 0..24 {
+        let f = i as f32 / 24.0;
+        let x = (t * 0.3 + f * TAU).cos() * win.w() * 0.35;
+        let y = (t * 0.5 + f * TAU * 3.0).sin() * win.h() * 0.35;
+        draw.ellipse()
+            .x_y(x, y)
+            .radius(20.0 + 40.0 * f)
+            .color(hsl(f, 0.8, 0.55));
+    }
+
+    draw.blur(&model.blur);
+
+    let text = format!("radius: {:.1}px", model.blur.radius());
+    draw.text(&text).font_size(32).w(win.w()).color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
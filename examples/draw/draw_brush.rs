@@ -0,0 +1,108 @@
+//! Demonstrates the `draw.brush()` primitive along with stroke capture and smoothing via the
+//! `brush` module.
+//!
+//! Draw with the mouse or a pen. Pen pressure affects the size and opacity of each stroke.
+//!
+//! - Press `S` to toggle input smoothing for new strokes.
+//! - Press `J` to toggle jitter.
+//! - Press `Space` to clear the canvas.
+
+use nannou::brush::{OneEuroFilter, Stroke};
+use nannou::curve::Curve;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    strokes: Vec<Stroke>,
+    drawing: bool,
+    smoothing: bool,
+    jitter: bool,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(1024, 768)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        strokes: vec![],
+        drawing: false,
+        smoothing: true,
+        jitter: false,
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::S => model.smoothing = !model.smoothing,
+        Key::J => model.jitter = !model.jitter,
+        Key::Space => model.strokes.clear(),
+        _ => (),
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    // Prefer the pen while it is in contact, falling back to the mouse at full pressure.
+    let pen = app.pen();
+    let input = if pen.is_down {
+        Some((pen.position, pen.pressure))
+    } else if app.mouse.buttons.left().is_down() {
+        Some((app.mouse.position(), 1.0))
+    } else {
+        None
+    };
+
+    match input {
+        Some((position, pressure)) => {
+            if !model.drawing {
+                let stroke = match model.smoothing {
+                    true => Stroke::new().with_smoothing(OneEuroFilter::default()),
+                    false => Stroke::new(),
+                };
+                model.strokes.push(stroke);
+                model.drawing = true;
+            }
+            let time = app.duration.since_start.secs();
+            if let Some(stroke) = model.strokes.last_mut() {
+                stroke.push(position, pressure, time);
+            }
+        }
+        None => model.drawing = false,
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.95, 0.93, 0.88));
+
+    let jitter = if model.jitter { 0.15 } else { 0.0 };
+    for (i, stroke) in model.strokes.iter().enumerate() {
+        draw.brush()
+            .points(stroke)
+            .size(24.0)
+            .spacing(0.08)
+            .hardness(0.3)
+            .jitter(jitter)
+            .seed(i as u64)
+            .size_curve(Curve::linear())
+            .opacity_curve(Curve::linear())
+            .color(rgba(0.1, 0.15, 0.3, 0.5));
+    }
+
+    let text = format!(
+        "smoothing (S): {}\njitter (J): {}",
+        model.smoothing, model.jitter
+    );
+    draw.text(&text)
+        .wh(app.window_rect().pad(20.0).wh())
+        .align_text_top()
+        .left_justify()
+        .color(BLACK);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,110 @@
+// A demonstration of drawing to a logical canvas far larger than the window (and larger than
+// the maximum texture size of many GPUs), e.g. for an LED wall.
+//
+// The canvas is rendered into a grid of offscreen tiles. The window previews the whole canvas.
+//
+// - Drag with the left mouse button to pan.
+// - Scroll to zoom.
+// - Press `F` to fit the whole canvas within the window.
+
+use nannou::draw::canvas::{self, Canvas};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    // The `Draw` instance used to draw to the canvas.
+    draw: nannou::Draw,
+    canvas: Canvas,
+    preview: canvas::Preview,
+    // Used to determine the mouse delta while panning.
+    last_mouse: Point2,
+}
+
+fn model(app: &App) -> Model {
+    let w_id = app
+        .new_window()
+        .size(1200, 300)
+        .title("nannou")
+        .mouse_moved(mouse_moved)
+        .mouse_wheel(mouse_wheel)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let window = app.window(w_id).unwrap();
+    let device = window.device();
+
+    // A 12000x3000 canvas, split into tiles of at most 4096x4096.
+    let canvas = canvas::Builder::new().build(device, [12_000, 3_000]);
+    let preview = canvas::Preview::fit(&canvas, window.rect());
+
+    Model {
+        draw: nannou::Draw::new(),
+        canvas,
+        preview,
+        last_mouse: Point2::ZERO,
+    }
+}
+
+fn mouse_moved(app: &App, model: &mut Model, pos: Point2) {
+    if app.mouse.buttons.left().is_down() {
+        model.preview.pan(pos - model.last_mouse);
+    }
+    model.last_mouse = pos;
+}
+
+fn mouse_wheel(app: &App, model: &mut Model, delta: MouseScrollDelta, _phase: TouchPhase) {
+    let y = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y * 20.0,
+        MouseScrollDelta::PixelDelta(p) => p.y as f32,
+    };
+    let factor = (y * 0.01).exp();
+    let win = app.window_rect();
+    model.preview.zoom_at(win, app.mouse.position(), factor);
+}
+
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    if let Key::F = key {
+        model.preview = canvas::Preview::fit(&model.canvas, app.window_rect());
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    let draw = &model.draw;
+    draw.reset();
+
+    // Draw in canvas coordinates.
+    let r = model.canvas.rect();
+    let t = app.time;
+    draw.background().color(BLACK);
+    for i in 0..120 {
+        let fract = i as f32 / 120.0;
+        let x = map_range(fract, 0.0, 1.0, r.left(), r.right());
+        let y = (t + fract * TAU * 4.0).sin() * r.h() * 0.35;
+        let hue = (fract + t * 0.05) % 1.0;
+        draw.ellipse()
+            .x_y(x, y)
+            .radius(r.h() * 0.08)
+            .color(hsl(hue, 0.8, 0.5));
+    }
+
+    // Render the drawing to each of the canvas tiles.
+    let window = app.main_window();
+    let device = window.device();
+    let ce_desc = wgpu::CommandEncoderDescriptor {
+        label: Some("canvas renderer"),
+    };
+    let mut encoder = device.create_command_encoder(&ce_desc);
+    model.canvas.render(device, &mut encoder, draw);
+    window.queue().submit(Some(encoder.finish()));
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DIMGRAY);
+    model.preview.draw(&model.canvas, &draw, app.window_rect());
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,42 @@
+//! Draw the outlines of moving metaballs by tracing the contours of a scalar field.
+//!
+//! The field is the sum of the influence of each ball. Its contours are drawn at several levels,
+//! producing rings that merge as the balls pass near one another.
+use nannou::geom::ScalarGrid;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).size(720, 720).run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let t = app.time;
+    let win = app.window_rect();
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // Balls drifting along lissajous curves.
+    let balls: Vec<Point2> = (0..6)
+        .map(|i| {
+            let f = i as f32 + 1.0;
+            vec2((t * 0.3 * f).sin(), (t * 0.2 * (7.0 - f) + f).cos()) * win.wh() * 0.35
+        })
+        .collect();
+
+    // Sample the summed influence of the balls across the window.
+    let grid = ScalarGrid::from_fn(win, 96, 96, |p| {
+        balls
+            .iter()
+            .map(|&b| 2000.0 / p.distance_squared(b).max(1.0))
+            .sum()
+    });
+
+    for (i, &level) in [0.25, 0.5, 1.0, 2.0].iter().enumerate() {
+        draw.contours(&grid, [level])
+            .smooth(2)
+            .weight(2.0)
+            .color(hsv(i as f32 * 0.1 + 0.5, 0.6, 1.0));
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,104 @@
+//! Drag the vertices of the polygon or the panel with the mouse.
+//!
+//! Hold `Shift` to snap the direction of the drag to 15 degree steps, or `Ctrl` to snap to the
+//! grid. Both remain within the bounds of the window.
+
+use nannou::drag::{self, Drag, Phase};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    drag: Drag,
+    vertices: Vec<Vec2>,
+    panel: Rect,
+    moves: usize,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().size(720, 720).view(view).build().unwrap();
+    let vertices = (0..5)
+        .map(|i| {
+            let angle = i as f32 / 5.0 * TAU + PI / 2.0;
+            vec2(angle.cos(), angle.sin()) * 200.0
+        })
+        .collect();
+    Model {
+        drag: Drag::default(),
+        vertices,
+        panel: Rect::from_x_y_w_h(0.0, -280.0, 200.0, 80.0),
+        moves: 0,
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    model.drag.bounds = Some(app.window_rect());
+    model.drag.update(app);
+    for vertex in &mut model.vertices {
+        if let Some(Phase::Released) = model.drag.point(vertex) {
+            model.moves += 1;
+        }
+    }
+    if let Some(Phase::Released) = model.drag.rect(&mut model.panel) {
+        model.moves += 1;
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+
+    // Show the grid while snapping to it.
+    let win = app.window_rect();
+    if model.drag.is_grid_snapping() {
+        let grid = model.drag.grid;
+        let color = rgba(1.0, 1.0, 1.0, 0.08);
+        let mut x = drag::snap_to_grid(win.bottom_left(), grid).x;
+        while x <= win.right() {
+            draw.line()
+                .start(pt2(x, win.bottom()))
+                .end(pt2(x, win.top()))
+                .color(color);
+            x += grid;
+        }
+        let mut y = drag::snap_to_grid(win.bottom_left(), grid).y;
+        while y <= win.top() {
+            draw.line()
+                .start(pt2(win.left(), y))
+                .end(pt2(win.right(), y))
+                .color(color);
+            y += grid;
+        }
+    }
+
+    // Show the origin of the drag while snapping its direction.
+    if let Some(origin) = model.drag.origin() {
+        if model.drag.is_angle_snapping() {
+            draw.ellipse()
+                .xy(origin)
+                .radius(4.0)
+                .no_fill()
+                .stroke_weight(1.0)
+                .stroke_color(GRAY);
+        }
+    }
+
+    draw.polygon()
+        .points(model.vertices.iter().cloned())
+        .color(rgba(0.3, 0.5, 1.0, 0.5));
+    for &v in &model.vertices {
+        draw.ellipse().xy(v).radius(6.0).color(WHITE);
+    }
+
+    draw.rect()
+        .xy(model.panel.xy())
+        .wh(model.panel.wh())
+        .color(rgb(0.9, 0.5, 0.2));
+    draw.text(&format!("moves: {}", model.moves))
+        .xy(model.panel.xy())
+        .color(BLACK);
+
+    draw.to_frame(app, &frame).unwrap();
+}
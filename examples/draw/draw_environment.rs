@@ -0,0 +1,101 @@
+//! Light rows of spheres with an environment map, drawn behind them as a skybox.
+//!
+//! The top row is metal while the bottom row is plastic, each growing rougher from left to right.
+//! An HDR image may be loaded instead of the generated sky via `EnvironmentMap::from_path`.
+//! Move the mouse horizontally to turn the environment.
+use nannou::draw::EnvironmentMap;
+use nannou::prelude::*;
+
+struct Model {
+    environment: EnvironmentMap,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    let window_id = app.new_window().size(960, 540).view(view).build().unwrap();
+    let window = app.window(window_id).unwrap();
+
+    // Generate an equirectangular sky with a sun much brighter than the rest of the scene.
+    let (w, h) = (512, 256);
+    let sun = vec3(0.4, 0.5, -0.8).normalize();
+    let pixels: Vec<[f32; 4]> = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let lon = ((x as f32 + 0.5) / w as f32 - 0.5) * TAU;
+            let lat = (0.5 - (y as f32 + 0.5) / h as f32) * PI;
+            let dir = vec3(lon.sin() * lat.cos(), lat.sin(), -lon.cos() * lat.cos());
+            let sky = if dir.y > 0.0 {
+                vec3(0.9, 0.8, 0.7).lerp(vec3(0.2, 0.4, 0.9), dir.y.sqrt())
+            } else {
+                vec3(0.3, 0.25, 0.2) * (1.0 + dir.y)
+            };
+            let glow = dir.dot(sun).max(0.0).powf(256.0) * 50.0;
+            let c = sky + Vec3::splat(glow);
+            [c.x, c.y, c.z, 1.0]
+        })
+        .collect();
+    let device = window.device();
+    let queue = window.queue();
+    let environment = EnvironmentMap::from_equirectangular(device, queue, [w, h], &pixels);
+
+    Model { environment }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let win = app.window_rect();
+    let draw = app.draw();
+
+    // Turn the environment, along with its reflections, with the mouse.
+    let turn = map_range(app.mouse.x, win.left(), win.right(), -PI, PI);
+    draw.y_radians(turn)
+        .environment_map(&model.environment)
+        .skybox();
+
+    // A sphere with vertices along lines of latitude and longitude.
+    let radius = win.w() / 14.0;
+    let (rings, segments) = (24, 48);
+    let points: Vec<_> = (0..=rings)
+        .flat_map(|ring| {
+            let theta = ring as f32 / rings as f32 * PI;
+            (0..=segments).map(move |segment| {
+                let phi = segment as f32 / segments as f32 * TAU;
+                let p = vec3(
+                    theta.sin() * phi.cos(),
+                    theta.cos(),
+                    theta.sin() * phi.sin(),
+                );
+                p * radius
+            })
+        })
+        .collect();
+    let stride = segments + 1;
+    let indices: Vec<usize> = (0..rings)
+        .flat_map(|ring| {
+            (0..segments).flat_map(move |segment| {
+                let a = ring * stride + segment;
+                let b = a + stride;
+                [a, b, a + 1, a + 1, b, b + 1]
+            })
+        })
+        .collect();
+
+    let draw = draw.lit();
+    let n = 5;
+    for i in 0..n {
+        let roughness = i as f32 / (n - 1) as f32;
+        let x = (i as f32 - (n - 1) as f32 / 2.0) * radius * 2.5;
+        for (y, metallic, color) in [(radius * 1.25, 1.0, GOLD), (-radius * 1.25, 0.0, CRIMSON)] {
+            draw.xy(vec2(x, y))
+                .mesh()
+                .indexed(points.iter().cloned(), indices.iter().cloned())
+                .color(color)
+                .metallic(metallic)
+                .roughness(roughness);
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
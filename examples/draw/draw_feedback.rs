@@ -0,0 +1,54 @@
+//! Leave trails behind the mouse by drawing each frame over a copy of the last.
+//!
+//! At the end of each frame, the window's contents are copied into a texture via
+//! `Frame::snapshot_to`. The next frame draws that texture slightly enlarged and rotated before
+//! fading it towards black, causing older drawing to drift outwards and fade away.
+use nannou::prelude::*;
+
+struct Model {
+    history: wgpu::Texture,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    let window_id = app.new_window().size(720, 720).view(view).build().unwrap();
+    let window = app.window(window_id).unwrap();
+    let (w, h) = window.inner_size_pixels();
+    let history = wgpu::TextureBuilder::new()
+        .size([w, h])
+        .format(Frame::TEXTURE_FORMAT)
+        .usage(
+            wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING,
+        )
+        .build(window.device());
+    Model { history }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let win = app.window_rect();
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // The previous frame, drifting outwards and fading away.
+    draw.rotate(0.01)
+        .texture(&model.history)
+        .wh(win.wh() * 1.02);
+    draw.rect().wh(win.wh()).rgba(0.0, 0.0, 0.0, 0.04);
+
+    // A circle following the mouse, cycling through hues.
+    let hue = (app.time * 0.1).fract();
+    draw.ellipse()
+        .xy(app.mouse.position())
+        .radius(24.0)
+        .color(hsv(hue, 0.8, 1.0));
+
+    draw.to_frame(app, &frame).unwrap();
+
+    // Keep the result for the next frame.
+    frame.snapshot_to(&model.history);
+}
@@ -0,0 +1,92 @@
+//! Demonstrates the `vector_field` module by tracing streamlines through a curl noise flow field.
+//!
+//! - Press `F` to toggle drawing the field as arrows.
+//! - Click to re-seed the noise.
+
+use nannou::noise::{NoiseFn, Perlin, Seedable};
+use nannou::prelude::*;
+use nannou::vector_field::VectorField2;
+
+// The number of streamlines traced through the field.
+const LINES: usize = 800;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    field: VectorField2,
+    lines: Vec<Vec<Point2>>,
+    show_field: bool,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(1024, 768)
+        .key_pressed(key_pressed)
+        .mouse_pressed(mouse_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let bounds = app.window_rect();
+    let (field, lines) = flow(bounds, 0);
+    Model {
+        field,
+        lines,
+        show_field: false,
+    }
+}
+
+// Produce a curl noise field over the given bounds along with streamlines traced through it.
+fn flow(bounds: Rect, seed: u32) -> (VectorField2, Vec<Vec<Point2>>) {
+    let noise = Perlin::new().set_seed(seed);
+    let potential = move |p: Point2| {
+        let scale = 0.003;
+        noise.get([p.x as f64 * scale, p.y as f64 * scale]) as f32 * 400.0
+    };
+    // Cache the field in a grid so that tracing many streamlines is cheap.
+    let curl = VectorField2::curl_of(bounds, potential);
+    let field = VectorField2::grid_from_fn(bounds, 64, 48, |p| curl.sample(p));
+    let lines = (0..LINES)
+        .map(|_| {
+            let x = random_range(bounds.left(), bounds.right());
+            let y = random_range(bounds.bottom(), bounds.top());
+            field.streamline(pt2(x, y), 2.0, 150)
+        })
+        .collect();
+    (field, lines)
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if let Key::F = key {
+        model.show_field = !model.show_field;
+    }
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, _button: MouseButton) {
+    let (field, lines) = flow(app.window_rect(), random());
+    model.field = field;
+    model.lines = lines;
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.05, 0.05, 0.08));
+
+    for (i, line) in model.lines.iter().enumerate() {
+        let hue = 0.5 + 0.15 * (i as f32 / LINES as f32);
+        draw.polyline()
+            .weight(1.0)
+            .points(line.iter().cloned())
+            .color(hsla(hue, 0.6, 0.6, 0.5));
+    }
+
+    if model.show_field {
+        draw.vector_field(&model.field)
+            .resolution(32, 24)
+            .weight(1.5)
+            .color(WHITE);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
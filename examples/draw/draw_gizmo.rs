@@ -0,0 +1,69 @@
+//! Drag the handles to move, rotate and scale the shapes.
+//!
+//! Press `T`, `R` or `S` to switch between translate, rotate and scale modes.
+
+use nannou::gizmo::{self, Gizmo};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    gizmo: Gizmo,
+    light_pos: Vec2,
+    panel: gizmo::Transform,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(720, 720)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        gizmo: Gizmo::default(),
+        light_pos: vec2(-150.0, 150.0),
+        panel: gizmo::Transform::from_position(vec2(80.0, -60.0)),
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::T => model.gizmo.mode = gizmo::Mode::Translate,
+        Key::R => model.gizmo.mode = gizmo::Mode::Rotate,
+        Key::S => model.gizmo.mode = gizmo::Mode::Scale,
+        _ => (),
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    model.gizmo.update(app);
+    model.gizmo.transform(&mut model.light_pos);
+    model.gizmo.transform(&mut model.panel);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+
+    // A panel lit by the light.
+    let panel = &model.panel;
+    let dist = model.light_pos.distance(panel.position);
+    let brightness = (1.0 - dist / 600.0).max(0.1);
+    draw.rect()
+        .xy(panel.position)
+        .rotate(panel.rotation)
+        .w_h(200.0 * panel.scale.x, 120.0 * panel.scale.y)
+        .color(rgb(brightness, brightness * 0.8, brightness * 0.6));
+
+    // The light.
+    draw.ellipse()
+        .xy(model.light_pos)
+        .radius(12.0)
+        .color(LIGHTYELLOW);
+
+    model.gizmo.draw(&draw);
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,147 @@
+//! Draw a ribbon whose vertices are written by a compute shader each frame.
+//!
+//! The vertices never leave the GPU. A compute pass writes them to the storage buffer of a
+//! `draw::GpuMesh`, from which `draw.gpu_mesh` pulls them when rendering. Move the mouse
+//! horizontally to change the frequency of the wave.
+use nannou::draw::GpuMesh;
+use nannou::prelude::*;
+use nannou::wgpu::BufferInitDescriptor;
+
+const SEGMENTS: u32 = 1024;
+// Each segment of the ribbon is a quad made of two triangles.
+const VERTEX_COUNT: u32 = SEGMENTS * 6;
+const WORKGROUP_SIZE: u32 = 64;
+
+// Writes the six vertices of each of the `SEGMENTS` quads.
+const RIBBON_WGSL: &str = r#"
+const SEGMENTS: u32 = 1024u;
+
+struct Params {
+    time: f32,
+    freq: f32,
+    width: f32,
+    height: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+@group(0) @binding(1)
+var<storage, read_write> vertices: array<GpuVertex>;
+
+fn point(i: u32) -> vec2<f32> {
+    let t = f32(i) / f32(SEGMENTS);
+    let x = (t - 0.5) * params.width * 0.9;
+    let wave = sin(t * params.freq + params.time * 2.0) * cos(t * 5.0 - params.time);
+    let y = wave * params.height * 0.3;
+    return vec2<f32>(x, y);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= SEGMENTS {
+        return;
+    }
+    let a = point(i);
+    let b = point(i + 1u);
+    let dir = normalize(b - a);
+    let thickness = 4.0 + 24.0 * (0.5 + 0.5 * sin(f32(i) * 0.03 + params.time * 3.0));
+    let n = vec2<f32>(-dir.y, dir.x) * thickness * 0.5;
+    let hue = f32(i) / f32(SEGMENTS) + params.time * 0.1;
+    let rgb = 0.5 + 0.5 * cos(6.28318 * (hue + vec3<f32>(0.0, 0.33, 0.67)));
+    var corners = array<vec2<f32>, 6>(a - n, a + n, b + n, a - n, b + n, b - n);
+    for (var k = 0u; k < 6u; k++) {
+        vertices[i * 6u + k] = GpuVertex(vec4<f32>(corners[k], 0.0, 1.0), vec4<f32>(rgb, 1.0));
+    }
+}
+"#;
+
+struct Model {
+    mesh: GpuMesh,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct Params {
+    time: f32,
+    freq: f32,
+    width: f32,
+    height: f32,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    let window_id = app.new_window().size(1024, 640).view(view).build().unwrap();
+    let window = app.window(window_id).unwrap();
+    let device = window.device();
+
+    // Prefix the compute shader with the layout of the mesh's vertices.
+    let source = format!("{}{}", GpuMesh::VERTEX_WGSL, RIBBON_WGSL);
+    let cs_mod = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("ribbon"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+        .uniform_buffer(wgpu::ShaderStages::COMPUTE, false)
+        .storage_buffer(wgpu::ShaderStages::COMPUTE, false, false)
+        .build(device);
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("ribbon"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("ribbon"),
+        layout: Some(&pipeline_layout),
+        module: &cs_mod,
+        entry_point: "main",
+    });
+
+    let mesh = GpuMesh::new(device, VERTEX_COUNT);
+    Model {
+        mesh,
+        bind_group_layout,
+        pipeline,
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let device = frame.device_queue_pair().device();
+    let win = app.window_rect();
+
+    // Write the vertices of the ribbon for this frame.
+    let params = Params {
+        time: app.time,
+        freq: map_range(app.mouse.x, win.left(), win.right(), 4.0, 40.0),
+        width: win.w(),
+        height: win.h(),
+    };
+    let params_buffer = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("ribbon-params"),
+        contents: unsafe { wgpu::bytes::from(&params) },
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let bind_group = wgpu::BindGroupBuilder::new()
+        .buffer::<Params>(&params_buffer, 0..1)
+        .buffer_bytes(model.mesh.buffer(), 0, None)
+        .build(device, &model.bind_group_layout);
+    {
+        let mut encoder = frame.command_encoder();
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("ribbon"),
+        });
+        pass.set_pipeline(&model.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(SEGMENTS.div_ceil(WORKGROUP_SIZE), 1, 1);
+    }
+
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    draw.gpu_mesh(&model.mesh);
+    draw.to_frame(app, &frame).unwrap();
+}
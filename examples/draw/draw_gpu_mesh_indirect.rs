@@ -0,0 +1,161 @@
+//! Draw particles culled on the GPU, with the number of vertices drawn decided by a compute pass.
+//!
+//! Each frame a compute pass positions the particles and appends the vertices of those outside of
+//! a circle around the mouse to the storage buffer of a `draw::GpuMesh`. The number of vertices
+//! appended is written to the mesh's indirect buffer, so the CPU never learns how many particles
+//! survived.
+use nannou::draw::GpuMesh;
+use nannou::prelude::*;
+use nannou::wgpu::BufferInitDescriptor;
+
+const PARTICLES: u32 = 20_000;
+// Each particle is a quad made of two triangles.
+const VERTEX_COUNT: u32 = PARTICLES * 6;
+const WORKGROUP_SIZE: u32 = 64;
+
+// Writes the six vertices of each particle that is not culled.
+const PARTICLES_WGSL: &str = r#"
+const PARTICLES: u32 = 20000u;
+
+struct Params {
+    time: f32,
+    radius: f32,
+    mouse: vec2<f32>,
+    size: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+@group(0) @binding(1)
+var<storage, read_write> vertices: array<GpuVertex>;
+@group(0) @binding(2)
+var<storage, read_write> indirect: GpuDrawIndirect;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if i >= PARTICLES {
+        return;
+    }
+    let t = f32(i) / f32(PARTICLES);
+    let phase = t * 6.28318 * 7.0 + params.time * (0.1 + t * 0.2);
+    let p = vec2<f32>(cos(phase * 3.0), sin(phase * 2.0)) * params.size * 0.45;
+    if distance(p, params.mouse) < params.radius {
+        return;
+    }
+    let start = atomicAdd(&indirect.vertex_count, 6u);
+    let r = 1.5 + 2.0 * fract(t * 97.0);
+    let color = vec4<f32>(0.4 + 0.6 * t, 0.7, 1.0 - 0.6 * t, 1.0);
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-r, -r),
+        vec2<f32>(r, -r),
+        vec2<f32>(r, r),
+        vec2<f32>(-r, -r),
+        vec2<f32>(r, r),
+        vec2<f32>(-r, r),
+    );
+    for (var k = 0u; k < 6u; k++) {
+        vertices[start + k] = GpuVertex(vec4<f32>(p + corners[k], 0.0, 1.0), color);
+    }
+}
+"#;
+
+struct Model {
+    mesh: GpuMesh,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct Params {
+    time: f32,
+    radius: f32,
+    mouse: [f32; 2],
+    size: [f32; 2],
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    let window_id = app.new_window().size(1024, 640).view(view).build().unwrap();
+    let window = app.window(window_id).unwrap();
+    let device = window.device();
+
+    // Prefix the compute shader with the layouts of the mesh's vertices and indirect arguments.
+    let source = format!(
+        "{}{}{}",
+        GpuMesh::VERTEX_WGSL,
+        GpuMesh::INDIRECT_WGSL,
+        PARTICLES_WGSL
+    );
+    let cs_mod = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("particles"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+        .uniform_buffer(wgpu::ShaderStages::COMPUTE, false)
+        .storage_buffer(wgpu::ShaderStages::COMPUTE, false, false)
+        .storage_buffer(wgpu::ShaderStages::COMPUTE, false, false)
+        .build(device);
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("particles"),
+        bind_group_layouts: &[&bind_group_layout],
+        push_constant_ranges: &[],
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("particles"),
+        layout: Some(&pipeline_layout),
+        module: &cs_mod,
+        entry_point: "main",
+    });
+
+    let mesh = GpuMesh::new(device, VERTEX_COUNT).indirect(device);
+    Model {
+        mesh,
+        bind_group_layout,
+        pipeline,
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let device = frame.device_queue_pair().device();
+    let win = app.window_rect();
+
+    // Particles append their vertices, so begin each frame with none.
+    model.mesh.clear_indirect(frame.device_queue_pair().queue());
+
+    let params = Params {
+        time: app.time,
+        radius: 120.0,
+        mouse: app.mouse.position().into(),
+        size: win.wh().into(),
+    };
+    let params_buffer = device.create_buffer_init(&BufferInitDescriptor {
+        label: Some("particles-params"),
+        contents: unsafe { wgpu::bytes::from(&params) },
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let indirect = model.mesh.indirect_buffer().unwrap();
+    let bind_group = wgpu::BindGroupBuilder::new()
+        .buffer::<Params>(&params_buffer, 0..1)
+        .buffer_bytes(model.mesh.buffer(), 0, None)
+        .buffer_bytes(indirect, 0, None)
+        .build(device, &model.bind_group_layout);
+    {
+        let mut encoder = frame.command_encoder();
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("particles"),
+        });
+        pass.set_pipeline(&model.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(PARTICLES.div_ceil(WORKGROUP_SIZE), 1, 1);
+    }
+
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    draw.gpu_mesh(&model.mesh);
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,82 @@
+//! Demonstrates the `geom::hex` utilities and the `draw.hexagon()` primitive.
+//!
+//! The hexagon beneath the mouse is highlighted along with the rings of hexagons surrounding it.
+//!
+//! - Press `Space` to switch between pointy-topped and flat-topped hexagons.
+
+use nannou::geom::hex::{Hex, Layout, Orientation};
+use nannou::prelude::*;
+
+const GRID_RADIUS: u32 = 8;
+const HEX_RADIUS: f32 = 24.0;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    layout: Layout,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(900, 800)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        layout: Layout::pointy(HEX_RADIUS),
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if let Key::Space = key {
+        model.layout.orientation = match model.layout.orientation {
+            Orientation::Pointy => Orientation::Flat,
+            Orientation::Flat => Orientation::Pointy,
+        };
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.08, 0.08, 0.1));
+
+    let layout = &model.layout;
+    let hovered = layout.to_hex(app.mouse.position());
+
+    for hex in Hex::ORIGIN.range(GRID_RADIUS) {
+        // Color hexagons by their distance from the hovered hexagon.
+        let distance = hex.distance(hovered);
+        let color = if distance == 0 {
+            hsl(0.1, 0.9, 0.6)
+        } else if distance <= 3 {
+            let phase = (app.time * 2.0 - distance as f32).sin() * 0.5 + 0.5;
+            hsl(0.55, 0.6, 0.2 + 0.2 * phase)
+        } else {
+            hsl(0.6, 0.2, 0.15)
+        };
+        draw.hexagon()
+            .hex(layout, hex)
+            .radius(HEX_RADIUS - 2.0)
+            .color(color);
+    }
+
+    // Outline the neighbours of the hovered hexagon and label its axial coordinates.
+    if hovered.length() <= GRID_RADIUS {
+        for hex in hovered.neighbors() {
+            draw.hexagon()
+                .hex(layout, hex)
+                .no_fill()
+                .stroke_weight(2.0)
+                .stroke(WHITE);
+        }
+        draw.text(&format!("{},{}", hovered.q, hovered.r))
+            .xy(layout.to_point(hovered))
+            .font_size(12)
+            .color(BLACK);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
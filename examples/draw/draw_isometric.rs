@@ -0,0 +1,123 @@
+//! Demonstrates building isometric scenes from plain 2D primitives via `draw.isometric()`.
+//!
+//! Columns of cubes are sorted from back to front with `Axonometric::sort_by_depth` so that
+//! nearer cubes overlap those behind them. The tile beneath the mouse is highlighted.
+//!
+//! - Press `Space` to switch between isometric and 2:1 dimetric projections.
+
+use nannou::draw::Axonometric;
+use nannou::prelude::*;
+
+const GRID: i32 = 8;
+const TILE: f32 = 32.0;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    projection: Axonometric,
+    cubes: Vec<Vec3>,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(900, 700)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+
+    // Stack a column of cubes on some of the tiles.
+    let mut cubes = vec![];
+    for x in 0..GRID {
+        for y in 0..GRID {
+            let height = ((x as f32 * 0.7).sin() + (y as f32 * 0.5).cos() + 1.0).max(0.0) as i32;
+            for z in 0..height {
+                cubes.push(vec3(x as f32, y as f32, z as f32));
+            }
+        }
+    }
+
+    Model {
+        projection: Axonometric::isometric(),
+        cubes,
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if let Key::Space = key {
+        model.projection = match model.projection == Axonometric::isometric() {
+            true => Axonometric::dimetric(),
+            false => Axonometric::isometric(),
+        };
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+
+    // Scale world units to tiles and centre the grid within the window.
+    let offset = -(GRID as f32) * 0.5 + 0.5;
+    let iso = draw
+        .axonometric(&model.projection)
+        .scale(TILE)
+        .x_y(offset, offset);
+
+    // Find the tile beneath the mouse.
+    let mouse = model
+        .projection
+        .unproject(app.mouse.position() / TILE, 0.0)
+        .map(|p| (p.truncate() - vec2(offset, offset)).round());
+
+    // Draw the ground tiles.
+    for x in 0..GRID {
+        for y in 0..GRID {
+            let color = match mouse == Some(vec2(x as f32, y as f32)) {
+                true => hsl(0.12, 0.8, 0.6),
+                false => hsl(0.3, 0.3, 0.25 + 0.05 * ((x + y) % 2) as f32),
+            };
+            iso.rect()
+                .x_y(x as f32, y as f32)
+                .w_h(1.0, 1.0)
+                .color(color);
+        }
+    }
+
+    // Draw the cubes from back to front.
+    let mut cubes = model.cubes.clone();
+    model
+        .projection
+        .sort_by_depth(&mut cubes, |&c| c + vec3(0.0, 0.0, 0.5));
+    for cube in cubes {
+        let hue = 0.55 + cube.z * 0.05;
+        let cube_draw = iso.xyz(cube);
+        // The top face.
+        cube_draw
+            .z(1.0)
+            .rect()
+            .x_y(0.0, 0.0)
+            .w_h(1.0, 1.0)
+            .color(hsl(hue, 0.5, 0.6));
+        // The face towards the negative y axis.
+        cube_draw
+            .y(-0.5)
+            .x_radians(PI / 2.0)
+            .rect()
+            .x_y(0.0, 0.5)
+            .w_h(1.0, 1.0)
+            .color(hsl(hue, 0.5, 0.45));
+        // The face towards the negative x axis.
+        cube_draw
+            .x(-0.5)
+            .z_radians(PI / 2.0)
+            .x_radians(PI / 2.0)
+            .rect()
+            .x_y(0.0, 0.5)
+            .w_h(1.0, 1.0)
+            .color(hsl(hue, 0.5, 0.3));
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
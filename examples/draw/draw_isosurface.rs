@@ -0,0 +1,53 @@
+//! Build a mesh from the surface of 3D metaballs via marching cubes.
+//!
+//! The field is the sum of the influence of each ball, sampled throughout a cube each frame. Each
+//! vertex of the resulting surface is colored by its normal.
+use nannou::geom::{marching_cubes, Cuboid, ScalarGrid3};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).size(720, 720).run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let t = app.time;
+    let win = app.window_rect();
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // Balls drifting around the centre of the cube.
+    let size = win.w().min(win.h()) * 0.6;
+    let balls: Vec<Vec3> = (0..5)
+        .map(|i| {
+            let f = i as f32 + 1.0;
+            vec3(
+                (t * 0.4 * f).sin(),
+                (t * 0.3 * (6.0 - f) + f).cos(),
+                (t * 0.5 + f * 2.0).sin(),
+            ) * size
+                * 0.25
+        })
+        .collect();
+
+    // Sample the summed influence of the balls and extract the surface.
+    let bounds = Cuboid::from_x_y_z_w_h_d(0.0, 0.0, 0.0, size, size, size);
+    let radius_sq = (size * 0.12).powi(2);
+    let grid = ScalarGrid3::from_fn(bounds, 40, 40, 40, |p| {
+        balls
+            .iter()
+            .map(|&b| radius_sq / p.distance_squared(b).max(1.0))
+            .sum()
+    });
+    let surface = marching_cubes(&grid, 1.0);
+
+    let points = surface.points.iter().zip(&surface.normals).map(|(&p, &n)| {
+        let c = n * 0.5 + Vec3::splat(0.5);
+        (p, rgb(c.x, c.y, c.z))
+    });
+    draw.x_radians(t * 0.2)
+        .y_radians(t * 0.3)
+        .mesh()
+        .indexed_colored(points, surface.indices.iter().cloned());
+
+    draw.to_frame(app, &frame).unwrap();
+}
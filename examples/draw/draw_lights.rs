@@ -0,0 +1,84 @@
+//! Illuminate a sphere and a ring of cubes built via the draw API.
+//!
+//! The point light follows the mouse, while the directional light casts shadows onto the floor.
+//! The sphere is a polished metal, while every other cube glows faintly.
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let t = app.time;
+    let win = app.window_rect();
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // A warm light following the mouse, a cool light from above and a little ambient light.
+    draw.point_light(pt3(app.mouse.x, app.mouse.y, 300.0))
+        .color(ORANGE)
+        .intensity(1.5)
+        .range(win.w());
+    draw.directional_light(vec3(0.5, -1.0, -0.5))
+        .color(LIGHTSTEELBLUE)
+        .intensity(0.6)
+        .shadows(true);
+    draw.ambient_light().intensity(0.05);
+
+    // All drawing that follows is lit.
+    let draw = draw.lit().x_radians(0.4).y_radians(t * 0.3);
+
+    // A sphere with vertices along lines of latitude and longitude.
+    let radius = win.w().min(win.h()) * 0.2;
+    let (rings, segments) = (24, 48);
+    let points = (0..=rings).flat_map(|ring| {
+        let theta = ring as f32 / rings as f32 * PI;
+        (0..=segments).map(move |segment| {
+            let phi = segment as f32 / segments as f32 * TAU;
+            let p = vec3(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            (p * radius, GOLD)
+        })
+    });
+    let stride = segments + 1;
+    let indices = (0..rings).flat_map(|ring| {
+        (0..segments).flat_map(move |segment| {
+            let a = ring * stride + segment;
+            let b = a + stride;
+            [a, b, a + 1, a + 1, b, b + 1]
+        })
+    });
+    draw.mesh()
+        .indexed_colored(points, indices)
+        .metallic(1.0)
+        .roughness(0.3);
+
+    // The floor onto which shadows are cast.
+    draw.y(-radius * 1.5)
+        .x_radians(PI / 2.0)
+        .rect()
+        .w_h(radius * 6.0, radius * 6.0)
+        .color(GRAY);
+
+    // A ring of cubes orbiting the sphere.
+    let n = 12;
+    for i in 0..n {
+        let angle = i as f32 / n as f32 * TAU;
+        let pos = vec3(angle.cos(), 0.0, angle.sin()) * radius * 2.0;
+        let cube = geom::Cuboid::from_xyz_whd(pos, Vec3::splat(radius * 0.3));
+        let tris = cube
+            .triangles_iter()
+            .flat_map(geom::Tri::vertices)
+            .map(|p| (p, hsv(i as f32 / n as f32, 0.6, 1.0)));
+        let glow = if i % 2 == 0 { 0.3 } else { 0.0 };
+        draw.mesh()
+            .points_colored(tris)
+            .roughness(0.8)
+            .emissive(hsv(i as f32 / n as f32, 0.6, glow));
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
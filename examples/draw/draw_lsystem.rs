@@ -0,0 +1,65 @@
+//! Grow a field of stochastic plants with an L-system.
+//!
+//! Each plant chooses randomly between three branching rules, so no two plants are alike. Click
+//! to grow a new field.
+use nannou::lsystem::{Interpreter, LSystem, Module};
+use nannou::prelude::*;
+
+struct Model {
+    plants: Vec<(Point2, Vec<Module>)>,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(960, 640)
+        .mouse_pressed(mouse_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        plants: grow(app.window_rect()),
+    }
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, _button: MouseButton) {
+    model.plants = grow(app.window_rect());
+}
+
+// Generate a row of plants along the bottom of the window.
+fn grow(win: Rect) -> Vec<(Point2, Vec<Module>)> {
+    let system = LSystem::new("X")
+        .stochastic_rule('X', 1.0, "F[+X][-X]FX")
+        .stochastic_rule('X', 1.0, "F[+X]F[-X]+X")
+        .stochastic_rule('X', 1.0, "F[-X]F[+X]-X")
+        .rule('F', "FF");
+    let n = 5;
+    (0..n)
+        .map(|i| {
+            let x = map_range(i as f32 + 0.5, 0.0, n as f32, win.left(), win.right());
+            (pt2(x, win.bottom()), system.generate(5))
+        })
+        .collect()
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.96, 0.94, 0.9));
+
+    for (position, modules) in &model.plants {
+        let interpreter = Interpreter::new()
+            .step(4.0)
+            .angle_degrees(22.5)
+            .position(*position);
+        draw.path()
+            .stroke()
+            .weight(1.5)
+            .events(interpreter.path(modules).iter())
+            .color(DARKOLIVEGREEN);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,50 @@
+//! Smear fast moving circles into streaks by accumulating frames over time.
+//!
+//! Press space to toggle the accumulation on and off. Press the up and down keys to lengthen and
+//! shorten the trails.
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) {
+    app.new_window()
+        .size(720, 720)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    app.accumulate(0.1);
+}
+
+fn key_pressed(app: &App, _model: &mut (), key: Key) {
+    match (key, app.accumulation()) {
+        (Key::Space, None) => app.accumulate(0.1),
+        (Key::Space, Some(_)) => app.stop_accumulating(),
+        (Key::Up, Some(alpha)) => app.accumulate(alpha * 0.8),
+        (Key::Down, Some(alpha)) => app.accumulate(alpha * 1.25),
+        _ => (),
+    }
+}
+
+fn view(app: &App, _model: &(), frame: Frame) {
+    let t = app.time;
+    let win = app.window_rect();
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // Circles whirling around the centre of the window at different speeds.
+    let n = 8;
+    for i in 0..n {
+        let f = i as f32 / n as f32;
+        let angle = t * (2.0 + f * 6.0) + f * TAU;
+        let radius = win.w().min(win.h()) * (0.15 + f * 0.3);
+        draw.ellipse()
+            .xy(vec2(angle.cos(), angle.sin()) * radius)
+            .radius(12.0)
+            .color(hsv(f, 0.7, 1.0));
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
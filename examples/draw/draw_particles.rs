@@ -0,0 +1,109 @@
+//! Demonstrates the `particles` module.
+//!
+//! Particles stream from the mouse and swirl through curl noise towards an attractor at the centre
+//! of the window.
+//!
+//! - Click to emit a burst of particles.
+//! - Press `B` to switch between the CPU and GPU simulation backends.
+//! - Press `A` to toggle additive blending.
+
+use nannou::particles::{Backend, Emitter, Force, Shape, System};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    system: System,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(1024, 768)
+        .key_pressed(key_pressed)
+        .mouse_pressed(mouse_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        system: system(Backend::Cpu),
+    }
+}
+
+fn system(backend: Backend) -> System {
+    System::new()
+        .backend(backend)
+        .capacity(50_000)
+        .emitter(
+            Emitter::point(pt2(0.0, 0.0))
+                .rate(2_000.0)
+                .speed(20.0..80.0),
+        )
+        .force(Force::CurlNoise {
+            scale: 0.005,
+            strength: 200.0,
+            speed: 0.2,
+        })
+        .force(Force::Attractor {
+            position: pt2(0.0, 0.0),
+            strength: 60.0,
+        })
+        .force(Force::Drag(0.5))
+        .colors(vec![
+            lin_srgba(1.0, 0.9, 0.5, 1.0),
+            lin_srgba(1.0, 0.3, 0.1, 1.0),
+            lin_srgba(0.2, 0.1, 0.6, 1.0),
+        ])
+        .additive(true)
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::B => {
+            let backend = match model.system.simulation_backend() {
+                Backend::Cpu => Backend::Gpu,
+                Backend::Gpu => Backend::Cpu,
+            };
+            let additive = model.system.additive;
+            model.system = system(backend).additive(additive);
+        }
+        Key::A => model.system.additive = !model.system.additive,
+        _ => (),
+    }
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, _button: MouseButton) {
+    let burst = Emitter::ellipse(Rect::from_xy_wh(app.mouse.position(), vec2(20.0, 20.0)))
+        .speed(100.0..300.0)
+        .lifetime(1.0..3.0);
+    model.system.emit(&burst, 2_000);
+}
+
+fn update(app: &App, model: &mut Model, update: Update) {
+    // Move the stream to the mouse.
+    if let Some(emitter) = model.system.emitters.first_mut() {
+        emitter.shape = Shape::Point(app.mouse.position());
+    }
+    model.system.update(update.since_last.secs() as f32);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    model.system.draw(&draw);
+
+    let text = format!(
+        "backend (B): {:?}\nadditive (A): {}\nparticles: {}",
+        model.system.simulation_backend(),
+        model.system.additive,
+        model.system.len(),
+    );
+    draw.text(&text)
+        .wh(app.window_rect().pad(20.0).wh())
+        .align_text_top()
+        .left_justify()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,66 @@
+//! Select shapes by clicking on them.
+//!
+//! Each shape is assigned an ID via `pick_id`, by which the shape beneath the mouse is found via
+//! `app.pick`. Overlapping shapes are picked from the top down.
+use nannou::prelude::*;
+
+struct Model {
+    selected: Option<u64>,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(640, 640)
+        .mouse_pressed(mouse_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model { selected: None }
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, _button: MouseButton) {
+    model.selected = app.pick(app.mouse.position());
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // A grid of overlapping shapes, alternating between squares and circles.
+    let n = 5;
+    let step = app.window_rect().w() / (n + 1) as f32;
+    for i in 0..n * n {
+        let (x, y) = ((i % n) as f32, (i / n) as f32);
+        let xy = (vec2(x, y) - Vec2::splat((n - 1) as f32 / 2.0)) * step;
+        let id = i as u64;
+        let color = if model.selected == Some(id) {
+            ORANGE
+        } else {
+            STEELBLUE
+        };
+        let size = step * 1.2;
+        if i % 2 == 0 {
+            draw.rect()
+                .xy(xy)
+                .w_h(size, size)
+                .color(color)
+                .stroke(BLACK)
+                .stroke_weight(2.0)
+                .pick_id(id);
+        } else {
+            draw.ellipse()
+                .xy(xy)
+                .w_h(size, size)
+                .color(color)
+                .stroke(BLACK)
+                .stroke_weight(2.0)
+                .pick_id(id);
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,49 @@
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let win = app.window_rect();
+    let t = app.time;
+
+    // Zoom in and out to demonstrate that SDF shapes remain crisp at any scale.
+    let zoom = 1.0 + (t * 0.5).sin() * 0.75;
+    let draw = app.draw().scale(zoom);
+
+    draw.background().color(rgb(0.08, 0.08, 0.1));
+
+    // A circle with a soft drop shadow.
+    let radius = win.w().min(win.h()) * 0.12;
+    draw.ellipse()
+        .sdf()
+        .sdf_shadow(vec2(8.0, -8.0), 16.0, rgba(0.0, 0.0, 0.0, 0.6))
+        .x(-win.w() * 0.25)
+        .radius(radius)
+        .color(CORNFLOWERBLUE)
+        .stroke(WHITE)
+        .stroke_weight(4.0);
+
+    // A glowing rounded rectangle.
+    draw.rect()
+        .sdf_corner_radius(radius * 0.25)
+        .sdf_glow(24.0, rgba(1.0, 0.3, 0.6, 0.5))
+        .w_h(radius * 2.0, radius * 1.5)
+        .rotate(t * 0.2)
+        .color(HOTPINK);
+
+    // Capsules via lines with round caps.
+    for i in 0..5 {
+        let y = (i as f32 - 2.0) * radius * 0.4;
+        draw.line()
+            .sdf()
+            .caps_round()
+            .start(pt2(win.w() * 0.15, y))
+            .end(pt2(win.w() * 0.4, y + (t + i as f32).sin() * radius * 0.3))
+            .weight(2.0 + i as f32 * 4.0)
+            .color(LIGHTGOLDENRODYELLOW);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
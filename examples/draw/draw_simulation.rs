@@ -0,0 +1,86 @@
+//! Grow coral-like patterns with a Gray-Scott reaction-diffusion simulation on the GPU.
+//!
+//! The simulation is seeded with a few random blobs and stepped several times within each call
+//! to `view`, after which its output texture is drawn to fill the window. Press the mouse to
+//! reseed the simulation, or a number key to switch between rules.
+use nannou::prelude::*;
+
+const SIZE: [u32; 2] = [256, 256];
+const STEPS_PER_FRAME: u32 = 8;
+
+struct Model {
+    simulation: wgpu::Simulation,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    let window_id = app
+        .new_window()
+        .size(768, 768)
+        .mouse_pressed(mouse_pressed)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let window = app.window(window_id).unwrap();
+    let simulation = wgpu::Simulation::gray_scott(window.device(), SIZE)
+        .colors([0.02, 0.02, 0.1, 1.0], [1.0, 0.6, 0.3, 1.0]);
+    let model = Model { simulation };
+    seed(app, &model);
+    model
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, _button: MouseButton) {
+    seed(app, model);
+}
+
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    let rule: wgpu::SimulationRule = match key {
+        Key::Key1 => wgpu::GrayScott::default().into(),
+        Key::Key2 => wgpu::GameOfLife::default().into(),
+        Key::Key3 => wgpu::SmoothLife::default().into(),
+        _ => return,
+    };
+    model.simulation.set_rule(rule);
+    seed(app, model);
+}
+
+// Replace the state with a handful of randomly placed blobs.
+fn seed(app: &App, model: &Model) {
+    let window = app.main_window();
+    let device = window.device();
+    let blobs: Vec<(f32, f32, f32)> = (0..12)
+        .map(|_| {
+            let x = random_range(0.0, SIZE[0] as f32);
+            let y = random_range(0.0, SIZE[1] as f32);
+            (x, y, random_range(4.0, 16.0))
+        })
+        .collect();
+    let mut encoder = device.create_command_encoder(&Default::default());
+    model.simulation.seed(device, &mut encoder, |col, row| {
+        let p = vec2(col as f32, row as f32);
+        let inside = blobs.iter().any(|&(x, y, r)| p.distance(vec2(x, y)) < r);
+        if inside && random_f32() < 0.7 {
+            1.0
+        } else {
+            0.0
+        }
+    });
+    window.queue().submit(Some(encoder.finish()));
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let device = frame.device_queue_pair().device();
+    model
+        .simulation
+        .step(device, &mut frame.command_encoder(), STEPS_PER_FRAME);
+
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    draw.texture(model.simulation.texture())
+        .wh(app.window_rect().wh());
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,65 @@
+//! Lay text out along a curve and along a wave.
+//!
+//! Move the mouse horizontally to adjust the letter spacing and vertically to adjust the wave's
+//! amplitude. Press the mouse to keep the glyphs upright rather than following the curves.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let t = app.time;
+    let letter_spacing = map_range(app.mouse.x, win.left(), win.right(), -4.0, 16.0);
+    let rotate = !app.mouse.buttons.left().is_down();
+
+    // A circle that the text slowly travels around.
+    let radius = win.w().min(win.h()) * 0.3;
+    let circle = (0..=128).map(|i| {
+        let a = PI - i as f32 / 128.0 * TAU;
+        pt2(a.cos(), a.sin()) * radius
+    });
+    draw.polyline()
+        .weight(1.0)
+        .points(circle.clone())
+        .color(rgba(1.0, 1.0, 1.0, 0.2));
+    draw.text("nannou is a library for creative coding in rust")
+        .along_points(circle)
+        .path_start((t * 40.0) % 200.0)
+        .letter_spacing(letter_spacing)
+        .rotate_glyphs(rotate)
+        .font_size(20)
+        .color(WHITE);
+
+    // A wave across the bottom of the window, built as a lyon path.
+    let amp = map_range(app.mouse.y, win.bottom(), win.top(), 0.0, 80.0);
+    let y = win.bottom() + 100.0;
+    let quarter = win.w() / 4.0;
+    let wave = nannou::geom::path()
+        .begin(pt2(win.left(), y))
+        .cubic_bezier_to(
+            pt2(win.left() + quarter, y + amp * 2.0),
+            pt2(win.left() + quarter, y - amp * 2.0),
+            pt2(win.x(), y),
+        )
+        .cubic_bezier_to(
+            pt2(win.right() - quarter, y + amp * 2.0),
+            pt2(win.right() - quarter, y - amp * 2.0),
+            pt2(win.right(), y),
+        )
+        .build();
+    draw.text("riding the wave")
+        .along_path(&wave)
+        .center_justify()
+        .letter_spacing(letter_spacing)
+        .rotate_glyphs(rotate)
+        .font_size(32)
+        .color(PLUM);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,46 @@
+//! Manipulate letterforms as geometry by extracting the outline of each glyph.
+//!
+//! Move the mouse away from the centre of the window to explode the glyphs apart.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).simple_window(view).run();
+}
+
+struct Model {
+    glyphs: Vec<nannou::geom::Path>,
+    width: f32,
+}
+
+fn model(_app: &App) -> Model {
+    let font = text::font::default_notosans();
+    let glyphs = text::glyph_outlines("nannou", &font, 96);
+    let width = glyphs
+        .iter()
+        .flat_map(|glyph| glyph.iter())
+        .map(|event| event.to().x)
+        .fold(0.0, f32::max);
+    Model { glyphs, width }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let explode = app.mouse.position().length() / 200.0;
+    for (i, glyph) in model.glyphs.iter().enumerate() {
+        let fract = i as f32 / model.glyphs.len() as f32;
+        let angle = fract * TAU + app.time * 0.5;
+        let offset = vec2(angle.cos(), angle.sin()) * explode * 100.0;
+        draw.x_y(-model.width / 2.0 + offset.x, -30.0 + offset.y)
+            .rotate(explode * (fract - 0.5))
+            .path()
+            .stroke()
+            .weight(2.0)
+            .events(glyph.iter())
+            .hsv(fract, 0.6, 1.0);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
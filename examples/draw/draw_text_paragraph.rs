@@ -0,0 +1,66 @@
+//! Lay out a paragraph within a column and measure it before drawing.
+//!
+//! Move the mouse horizontally to adjust the width of the column. Press the left, center, right or
+//! justify keys (`L`, `C`, `R` and `J`) to change the alignment.
+
+use nannou::prelude::*;
+
+const TEXT: &str = "Nannou is a library that aims to make it easy for artists to express \
+themselves with simple, fast, reliable code.\n\nWhen writing a sketch, the text is laid out \
+within a column and measured before it is drawn, so that the backdrop always fits.";
+
+fn main() {
+    nannou::app(model).event(event).simple_window(view).run();
+}
+
+struct Model {
+    justify: text::Justify,
+}
+
+fn model(_app: &App) -> Model {
+    Model {
+        justify: text::Justify::Full,
+    }
+}
+
+fn event(_app: &App, model: &mut Model, event: Event) {
+    if let Event::WindowEvent {
+        simple: Some(KeyPressed(key)),
+        ..
+    } = event
+    {
+        model.justify = match key {
+            Key::L => text::Justify::Left,
+            Key::C => text::Justify::Center,
+            Key::R => text::Justify::Right,
+            Key::J => text::Justify::Full,
+            _ => return,
+        };
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DIMGRAY);
+
+    let win = app.window_rect();
+    let width = map_range(app.mouse.x, win.left(), win.right(), 120.0, win.w() - 40.0);
+
+    // Begin the backdrop first so that it is drawn behind the text, then fit it to the text.
+    let backdrop = draw.rect();
+    let text = draw
+        .text(TEXT)
+        .wrap(width)
+        .h(win.h())
+        .justify(model.justify)
+        .line_spacing(6.0)
+        .font_size(16);
+    let bounds = text.text_bounds();
+    text.color(BLACK);
+    backdrop
+        .xy(bounds.xy())
+        .wh(bounds.wh() + vec2(32.0, 32.0))
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,65 @@
+//! Demonstrates drawing a fractal tree and a Koch snowflake with `draw.turtle()`.
+//!
+//! Move the mouse horizontally to change the angle between branches of the tree.
+
+use nannou::draw::Turtle;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.96, 0.94, 0.9));
+    let win = app.window_rect();
+
+    // A recursive tree rooted at the bottom of the left half of the window.
+    let angle = map_range(app.mouse.x, win.left(), win.right(), 5.0, 60.0);
+    let t = draw.x_y(win.left() * 0.5, win.bottom() + 40.0).turtle();
+    t.set_heading(PI / 2.0);
+    branch(&t, 160.0, angle, 9);
+
+    // A Koch snowflake centred within the right half of the window.
+    let side = win.w().min(win.h() * 2.0) * 0.3;
+    let t = draw
+        .x_y(win.right() * 0.5 - side * 0.5, side * 0.29)
+        .turtle();
+    t.pen_color(STEELBLUE).pen_weight(2.0);
+    for _ in 0..3 {
+        koch(&t, side, 4);
+        t.turn_degrees(-120.0);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
+
+// Draw a branch, then two smaller branches from its tip.
+fn branch(t: &Turtle, length: f32, angle: f32, depth: usize) {
+    let weight = depth as f32 * 1.2;
+    let color = hsl(0.08 + (9 - depth) as f32 * 0.03, 0.5, 0.3);
+    t.pen_weight(weight).pen_color(color).forward(length);
+    if depth > 0 {
+        t.push().turn_degrees(angle);
+        branch(t, length * 0.72, angle, depth - 1);
+        t.pop().push().turn_degrees(-angle);
+        branch(t, length * 0.72, angle, depth - 1);
+        t.pop();
+    }
+}
+
+// Draw one side of a Koch snowflake.
+fn koch(t: &Turtle, length: f32, depth: usize) {
+    if depth == 0 {
+        t.forward(length);
+        return;
+    }
+    let length = length / 3.0;
+    koch(t, length, depth - 1);
+    t.turn_degrees(60.0);
+    koch(t, length, depth - 1);
+    t.turn_degrees(-120.0);
+    koch(t, length, depth - 1);
+    t.turn_degrees(60.0);
+    koch(t, length, depth - 1);
+}
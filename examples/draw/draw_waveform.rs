@@ -0,0 +1,81 @@
+//! Demonstrates loading a sound file and drawing its waveform and spectrogram.
+//!
+//! A playhead sweeps across the sound while the waveform around it is shown zoomed in below.
+//!
+//! - Press `L` to toggle logarithmic frequency spacing in the spectrogram.
+
+use nannou::audio_buffer::AudioBuffer;
+use nannou::prelude::*;
+
+// The span of time shown around the playhead, in seconds.
+const ZOOM_SECS: f32 = 0.05;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    buffer: AudioBuffer,
+    log_frequency: bool,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(1024, 720)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let buffer = app
+        .load_audio("sounds/thumbpiano.wav")
+        .expect("failed to load sound");
+    Model {
+        buffer,
+        log_frequency: true,
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if let Key::L = key {
+        model.log_frequency = !model.log_frequency;
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.08, 0.08, 0.1));
+
+    let [top, middle, bottom] = {
+        let win = app.window_rect().pad(20.0);
+        let h = win.h() / 3.0;
+        let row = |i: f32| {
+            Rect::from_w_h(win.w(), h - 10.0)
+                .mid_top_of(win)
+                .shift_y(-h * i)
+        };
+        [row(0.0), row(1.0), row(2.0)]
+    };
+
+    // The whole sound.
+    draw.waveform(&model.buffer, top).color(STEELBLUE);
+    draw.spectrogram(&model.buffer, middle)
+        .log_frequency(model.log_frequency)
+        .color(ORANGE);
+
+    // The playhead, looping over the duration of the sound.
+    let duration = model.buffer.duration().as_secs_f32();
+    let t = app.time % duration;
+    let x = map_range(t, 0.0, duration, top.left(), top.right());
+    for rect in [top, middle].iter() {
+        draw.line()
+            .points(pt2(x, rect.bottom()), pt2(x, rect.top()))
+            .color(WHITE);
+    }
+
+    // The waveform around the playhead.
+    draw.waveform(&model.buffer, bottom)
+        .seconds(t - ZOOM_SECS * 0.5..t + ZOOM_SECS * 0.5)
+        .color(LIGHTSTEELBLUE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,84 @@
+//! Inspect the geometry of a mesh built via the draw API.
+//!
+//! - `W` toggles the wireframe.
+//! - `V` cycles through the normals, texture coordinates and depth debug views.
+use nannou::draw::DebugView;
+use nannou::prelude::*;
+
+struct Model {
+    wireframe: bool,
+    debug_view: Option<DebugView>,
+}
+
+fn main() {
+    nannou::app(model).run()
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .title("W: wireframe, V: debug view")
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        wireframe: true,
+        debug_view: None,
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::W => model.wireframe = !model.wireframe,
+        Key::V => {
+            model.debug_view = match model.debug_view {
+                None => Some(DebugView::Normals),
+                Some(DebugView::Normals) => Some(DebugView::TexCoords),
+                Some(DebugView::TexCoords) => Some(DebugView::Depth),
+                Some(DebugView::Depth) => None,
+            };
+        }
+        _ => (),
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let t = app.time;
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // Apply the inspection modes to all drawing that follows.
+    let draw = draw
+        .wireframe(model.wireframe)
+        .debug_view(model.debug_view)
+        .x_radians(t * 0.3)
+        .y_radians(t * 0.5);
+
+    // A sphere with vertices along lines of latitude and longitude.
+    let radius = app.window_rect().w().min(app.window_rect().h()) * 0.35;
+    let (rings, segments) = (16, 32);
+    let points = (0..=rings).flat_map(|ring| {
+        let theta = ring as f32 / rings as f32 * PI;
+        (0..=segments).map(move |segment| {
+            let phi = segment as f32 / segments as f32 * TAU;
+            let p = vec3(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            );
+            let color = srgba(p.x * 0.5 + 0.5, p.y * 0.5 + 0.5, p.z * 0.5 + 0.5, 1.0);
+            (p * radius, color)
+        })
+    });
+    let stride = segments + 1;
+    let indices = (0..rings).flat_map(|ring| {
+        (0..segments).flat_map(move |segment| {
+            let a = ring * stride + segment;
+            let b = a + stride;
+            [a, b, a + 1, a + 1, b, b + 1]
+        })
+    });
+    draw.mesh().indexed_colored(points, indices);
+
+    draw.to_frame(app, &frame).unwrap();
+}
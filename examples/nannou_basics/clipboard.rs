@@ -0,0 +1,113 @@
+//! Copy and paste the seed of a generative sketch via the system clipboard.
+//!
+//! - Press `C` to copy the current seed as text.
+//! - Press `V` to paste a seed, e.g. one copied from another run or shared by a friend.
+//! - Press `I` to copy the palette of the current seed as an image.
+//! - Press `Space` to generate a new seed.
+
+use nannou::prelude::*;
+use nannou::rand::rngs::StdRng;
+use nannou::rand::{Rng, SeedableRng};
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    seed: u64,
+    status: String,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(640, 480)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        seed: random(),
+        status: "C: copy seed  V: paste seed  I: copy palette  Space: new seed".into(),
+    }
+}
+
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    let clipboard = app.clipboard();
+    model.status = match key {
+        Key::C => match clipboard.set_text(model.seed.to_string()) {
+            Ok(()) => format!("copied seed {}", model.seed),
+            Err(err) => format!("failed to copy seed: {}", err),
+        },
+        Key::V => match clipboard.get_text() {
+            Ok(text) => match text.trim().parse() {
+                Ok(seed) => {
+                    model.seed = seed;
+                    format!("pasted seed {}", seed)
+                }
+                Err(_) => format!("{:?} is not a valid seed", text),
+            },
+            Err(err) => format!("failed to paste seed: {}", err),
+        },
+        Key::I => match clipboard.set_image(&palette_image(model.seed)) {
+            Ok(()) => "copied palette".into(),
+            Err(err) => format!("failed to copy palette: {}", err),
+        },
+        Key::Space => {
+            model.seed = random();
+            format!("new seed {}", model.seed)
+        }
+        _ => return,
+    };
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let palette = palette(model.seed);
+    let mut rng = StdRng::seed_from_u64(model.seed);
+    let win = app.window_rect();
+    for i in 0..64 {
+        let x = rng.gen_range(win.left()..win.right());
+        let y = rng.gen_range(win.bottom()..win.top());
+        let radius = rng.gen_range(4.0..48.0);
+        draw.ellipse()
+            .x_y(x, y)
+            .radius(radius)
+            .color(palette[i % palette.len()]);
+    }
+
+    draw.text(&model.status)
+        .wh(win.pad(20.0).wh())
+        .align_text_bottom()
+        .left_justify()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
+
+// The colors used by the sketch for the given seed.
+fn palette(seed: u64) -> Vec<Srgb<u8>> {
+    let mut rng = StdRng::seed_from_u64(!seed);
+    let base_hue = rng.gen_range(0.0..360.0);
+    (0..5)
+        .map(|i| {
+            let hue = base_hue + i as f32 * rng.gen_range(20.0..40.0);
+            let lightness = rng.gen_range(0.35..0.75);
+            hsl(hue / 360.0 % 1.0, 0.7, lightness)
+                .to_srgb()
+                .color
+                .into_format()
+        })
+        .collect()
+}
+
+// A strip of swatches, one per color of the palette.
+fn palette_image(seed: u64) -> nannou::image::RgbaImage {
+    let palette = palette(seed);
+    let size = 64;
+    nannou::image::RgbaImage::from_fn(size * palette.len() as u32, size, |x, _| {
+        let c = palette[(x / size) as usize];
+        nannou::image::Rgba([c.red, c.green, c.blue, 255])
+    })
+}
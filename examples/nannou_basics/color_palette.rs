@@ -0,0 +1,130 @@
+//! Demonstrates the color palette utilities.
+//!
+//! - The top row shows a palette extracted from an image, with each swatch sized by its weight.
+//! - The middle rows compare interpolating between two colors in linear sRGB, Oklab and Oklch.
+//! - The bottom row shows color harmonies of a base color whose hue follows the mouse.
+//!
+//! Click to scatter circles colored by random weighted samples of the image palette.
+
+use nannou::color::{harmony, mix_oklab, mix_oklch, Mix, Palette};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    palette: Palette,
+    dots: Vec<(Point2, LinSrgba)>,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(960, 720)
+        .mouse_pressed(mouse_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let palette = app
+        .load_palette("images/nature/nature_1.jpg", 8)
+        .expect("failed to load image");
+    Model {
+        palette,
+        dots: vec![],
+    }
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, _button: MouseButton) {
+    let win = app.window_rect();
+    model.dots = (0..200)
+        .map(|_| {
+            let x = random_range(win.left(), win.right());
+            let y = random_range(win.bottom(), win.top());
+            (pt2(x, y), model.palette.random())
+        })
+        .collect();
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.1));
+
+    for &(p, color) in &model.dots {
+        draw.ellipse().xy(p).radius(12.0).color(color);
+    }
+
+    let win = app.window_rect().pad(40.0);
+    let row_h = win.h() / 5.0;
+    let row = |i: f32| {
+        Rect::from_w_h(win.w(), row_h * 0.8)
+            .mid_top_of(win)
+            .shift_y(-row_h * i)
+    };
+
+    // The extracted palette, with each swatch sized by its weight.
+    let r = row(0.0);
+    let mut x = r.left();
+    for (color, &weight) in model.palette.iter().zip(model.palette.weights()) {
+        let w = r.w() * weight;
+        draw.rect()
+            .x_y(x + w * 0.5, r.y())
+            .w_h(w, r.h())
+            .color(color);
+        x += w;
+    }
+
+    // Interpolation between two colors in different spaces.
+    let (a, b) = (
+        lin_srgba(0.0, 0.05, 1.0, 1.0),
+        lin_srgba(1.0, 0.9, 0.0, 1.0),
+    );
+    let steps = 48;
+    for (i, mix) in [
+        (|a, b, t| a.mix(&b, t)) as fn(LinSrgba, LinSrgba, f32) -> LinSrgba,
+        |a, b, t| mix_oklab(a, b, t),
+        |a, b, t| mix_oklch(a, b, t),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let r = row(1.0 + i as f32);
+        let w = r.w() / steps as f32;
+        for s in 0..steps {
+            let t = s as f32 / (steps - 1) as f32;
+            draw.rect()
+                .x_y(r.left() + w * (s as f32 + 0.5), r.y())
+                .w_h(w + 1.0, r.h())
+                .color(mix(a, b, t));
+        }
+    }
+
+    // Harmonies of a base color whose hue follows the mouse.
+    let hue = map_range(
+        app.mouse.x,
+        app.window_rect().left(),
+        app.window_rect().right(),
+        0.0,
+        1.0,
+    );
+    let base = oklch(0.7, 0.15, hue);
+    let harmonies: [&[LinSrgba]; 4] = [
+        &harmony::complementary(base),
+        &harmony::analogous(base, 30.0),
+        &harmony::triadic(base),
+        &harmony::tetradic(base),
+    ];
+    let r = row(4.0);
+    let group_w = r.w() / harmonies.len() as f32;
+    for (g, colors) in harmonies.iter().enumerate() {
+        let w = (group_w - 20.0) / colors.len() as f32;
+        let left = r.left() + group_w * g as f32;
+        for (i, &color) in colors.iter().enumerate() {
+            draw.rect()
+                .x_y(left + w * (i as f32 + 0.5), r.y())
+                .w_h(w, r.h())
+                .color(color);
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,60 @@
+//! Preview a palette as it might be seen with common color vision deficiencies.
+//!
+//! Press any key to cycle through the simulated `ColorVision` modes. The simulation is only
+//! applied to the window - frames captured via `capture_frame` are unaffected.
+
+use nannou::frame::ColorVision;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model;
+
+fn model(app: &App) -> Model {
+    let _window = app
+        .new_window()
+        .title(title(ColorVision::Normal))
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model
+}
+
+fn key_pressed(app: &App, _model: &mut Model, _key: Key) {
+    let window = app.main_window();
+    let color_vision = window.color_vision().next();
+    window.set_color_vision(color_vision);
+    window.set_title(&title(color_vision));
+}
+
+fn view(app: &App, _model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+
+    // A row of swatches for each lightness, spanning the hue wheel.
+    let win = app.window_rect().pad(40.0);
+    let (cols, rows) = (12, 4);
+    let w = win.w() / cols as f32;
+    let h = win.h() / rows as f32;
+    for row in 0..rows {
+        let lightness = map_range(row, 0, rows - 1, 0.3, 0.7);
+        for col in 0..cols {
+            let hue = col as f32 / cols as f32;
+            let x = win.left() + w * (col as f32 + 0.5);
+            let y = win.top() - h * (row as f32 + 0.5);
+            draw.rect()
+                .x_y(x, y)
+                .w_h(w * 0.9, h * 0.9)
+                .color(hsl(hue, 0.8, lightness));
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
+
+fn title(color_vision: ColorVision) -> String {
+    format!("Color Vision - `{:?}`", color_vision)
+}
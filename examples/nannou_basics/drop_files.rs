@@ -0,0 +1,108 @@
+//! Drag files onto the window to place them where they are dropped.
+//!
+//! Images are loaded as textures and drawn at the drop position. Other files (e.g. SVGs or audio)
+//! are listed by name.
+
+use nannou::prelude::*;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    // The file currently being dragged over the window and its position.
+    hovered: Option<(PathBuf, Point2)>,
+    dropped: Vec<Dropped>,
+}
+
+struct Dropped {
+    path: PathBuf,
+    position: Point2,
+    texture: Option<wgpu::Texture>,
+}
+
+fn model(app: &App) -> Model {
+    let _window = app
+        .new_window()
+        .title("Drop files here")
+        .hovered_file_at(hovered_file_at)
+        .hovered_file_cancelled(hovered_file_cancelled)
+        .dropped_file_at(dropped_file_at)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        hovered: None,
+        dropped: vec![],
+    }
+}
+
+fn hovered_file_at(_app: &App, model: &mut Model, path: PathBuf, position: Point2) {
+    model.hovered = Some((path, position));
+}
+
+fn hovered_file_cancelled(_app: &App, model: &mut Model) {
+    model.hovered = None;
+}
+
+fn dropped_file_at(app: &App, model: &mut Model, path: PathBuf, position: Point2) {
+    model.hovered = None;
+    let texture = wgpu::Texture::from_path(app, &path).ok();
+    model.dropped.push(Dropped {
+        path,
+        position,
+        texture,
+    });
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+
+    // Draw the dropped files where they were dropped.
+    for dropped in &model.dropped {
+        match dropped.texture {
+            Some(ref texture) => {
+                let [w, h] = texture.size();
+                let scale = (200.0 / w.max(h) as f32).min(1.0);
+                draw.texture(texture)
+                    .xy(dropped.position)
+                    .w_h(w as f32 * scale, h as f32 * scale);
+            }
+            None => {
+                draw.text(&file_name(&dropped.path))
+                    .xy(dropped.position)
+                    .color(WHITE);
+            }
+        }
+    }
+
+    // Preview the file being dragged.
+    match model.hovered {
+        Some((ref path, position)) => {
+            draw.ellipse()
+                .xy(position)
+                .radius(40.0)
+                .no_fill()
+                .stroke(CORNFLOWERBLUE)
+                .stroke_weight(2.0);
+            draw.text(&file_name(path))
+                .xy(position - vec2(0.0, 56.0))
+                .color(CORNFLOWERBLUE);
+        }
+        None if model.dropped.is_empty() => {
+            draw.text("Drag and drop images or other files onto the window")
+                .color(WHITE);
+        }
+        None => (),
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,5 @@
+//! Browse and launch all of nannou's examples, or press `T` to test each of them in turn.
+
+fn main() {
+    nannou::examples::run_gallery();
+}
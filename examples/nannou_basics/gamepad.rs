@@ -0,0 +1,132 @@
+//! Demonstrates gamepad input via `App::gamepads`.
+//!
+//! Run with `cargo run --example gamepad --features gamepad`.
+//!
+//! - Move the left stick to move the circle of the corresponding gamepad.
+//! - Hold the right trigger to grow it.
+//! - Press the south button (e.g. `A` on Xbox controllers) to change its color.
+//! - Press `Up` and `Down` on the keyboard to adjust the stick deadzone.
+
+use nannou::gamepad::{self, Button};
+use nannou::prelude::*;
+use std::collections::HashMap;
+
+fn main() {
+    nannou::app(model).event(event).update(update).run();
+}
+
+struct Model {
+    players: HashMap<gamepad::Id, Player>,
+    log: Vec<String>,
+}
+
+struct Player {
+    position: Point2,
+    hue: f32,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(800, 600)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        players: HashMap::new(),
+        log: vec![],
+    }
+}
+
+fn event(app: &App, model: &mut Model, event: Event) {
+    if let Event::Gamepad(id, event) = event {
+        let name = app
+            .gamepads()
+            .get(id)
+            .map(|g| g.name().to_string())
+            .unwrap_or_default();
+        match event {
+            gamepad::Event::Connected => model.log.push(format!("{} connected", name)),
+            gamepad::Event::Disconnected => {
+                model.players.remove(&id);
+                model.log.push(format!("{} disconnected", name));
+            }
+            gamepad::Event::ButtonPressed(Button::South) => {
+                if let Some(player) = model.players.get_mut(&id) {
+                    player.hue = (player.hue + 0.15) % 1.0;
+                }
+            }
+            _ => (),
+        }
+        let len = model.log.len();
+        if len > 5 {
+            model.log.drain(..len - 5);
+        }
+    }
+}
+
+fn key_pressed(app: &App, _model: &mut Model, key: Key) {
+    let deadzone = app.gamepad_deadzone();
+    match key {
+        Key::Up => app.set_gamepad_deadzone(deadzone + 0.05),
+        Key::Down => app.set_gamepad_deadzone(deadzone - 0.05),
+        _ => (),
+    }
+}
+
+fn update(app: &App, model: &mut Model, update: Update) {
+    let dt = update.since_last.secs() as f32;
+    let win = app.window_rect();
+    for gamepad in app.gamepads().iter() {
+        let player = model.players.entry(gamepad.id()).or_insert_with(|| Player {
+            position: pt2(0.0, 0.0),
+            hue: gamepad.id().index() as f32 * 0.25 % 1.0,
+        });
+        player.position += gamepad.left_stick() * 400.0 * dt;
+        player.position = player.position.clamp(win.bottom_left(), win.top_right());
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    let win = app.window_rect();
+
+    for (&id, player) in &model.players {
+        let gamepad = match app.gamepads().get(id) {
+            Some(gamepad) => gamepad,
+            None => continue,
+        };
+        let radius = 20.0 + 40.0 * gamepad.button_value(Button::RightTrigger2);
+        draw.ellipse()
+            .xy(player.position)
+            .radius(radius)
+            .color(hsl(player.hue, 0.7, 0.5));
+        // Show the direction of the right stick.
+        let aim = gamepad.right_stick();
+        if aim != Vec2::ZERO {
+            draw.arrow()
+                .start(player.position)
+                .end(player.position + aim * (radius + 30.0))
+                .weight(3.0)
+                .color(WHITE);
+        }
+    }
+
+    let mut text = format!(
+        "gamepads connected: {}\ndeadzone: {:.2}\n",
+        app.gamepads().len(),
+        app.gamepad_deadzone()
+    );
+    for line in &model.log {
+        text.push_str(line);
+        text.push('\n');
+    }
+    draw.text(&text)
+        .wh(win.pad(20.0).wh())
+        .align_text_top()
+        .left_justify()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,72 @@
+//! Demonstrates letterboxing a window to a fixed aspect ratio.
+//!
+//! Resize the window to see the 16:9 composition fit within it. The mouse position is clamped to
+//! the visible area of the composition.
+//!
+//! - Press `L` to toggle letterboxing.
+
+use nannou::prelude::*;
+
+const ASPECT: f32 = 16.0 / 9.0;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model;
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(800, 800)
+        .letterbox(ASPECT)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model
+}
+
+fn key_pressed(app: &App, _model: &mut Model, key: Key) {
+    if let Key::L = key {
+        let window = app.main_window();
+        match window.letterbox() {
+            Some(_) => window.set_letterbox(None),
+            None => window.set_letterbox(Some(ASPECT)),
+        }
+    }
+}
+
+fn view(app: &App, _model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DARKSLATEGRAY);
+
+    // Lay out the composition relative to the visible area of the window.
+    let rect = app.main_window().fit_rect(ASPECT);
+    let cell = rect.w() / 16.0;
+    for i in 0..=16 {
+        let x = rect.left() + i as f32 * cell;
+        draw.line()
+            .start(pt2(x, rect.bottom()))
+            .end(pt2(x, rect.top()))
+            .color(GRAY);
+    }
+    for i in 0..=9 {
+        let y = rect.bottom() + i as f32 * cell;
+        draw.line()
+            .start(pt2(rect.left(), y))
+            .end(pt2(rect.right(), y))
+            .color(GRAY);
+    }
+
+    draw.ellipse()
+        .xy(app.mouse.position())
+        .radius(cell * 0.5)
+        .color(ORANGE);
+
+    draw.text(&format!("{}x{}", rect.w().round(), rect.h().round()))
+        .xy(rect.xy())
+        .font_size(24)
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
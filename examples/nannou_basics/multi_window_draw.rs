@@ -27,6 +27,8 @@ fn model(app: &App) -> Model {
         .new_window()
         .title("window c")
         .event(event_c)
+        // Each window may have its own view onto the model.
+        .camera(Mat4::from_scale(Vec3::splat(2.0)))
         .build()
         .unwrap();
     Model { a, b, c }
@@ -47,7 +49,8 @@ fn event_c(_app: &App, _model: &mut Model, event: WindowEvent) {
 }
 
 fn view(app: &App, model: &Model, frame: Frame) {
-    let draw = app.draw();
+    // Each window has its own `Draw` with the window's camera applied.
+    let draw = app.draw_for_window(frame.window_id());
     match frame.window_id() {
         id if id == model.a => {
             draw.background().color(INDIANRED);
@@ -0,0 +1,90 @@
+//! A simple brush responding to the pressure and tilt of a pen tablet or stylus.
+//!
+//! Strokes are drawn with a width that follows the pressure of the pen and an opacity that
+//! follows its tilt where supported. Hold the barrel button to erase. Press `Space` to clear.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    strokes: Vec<Stroke>,
+}
+
+struct Stroke {
+    erase: bool,
+    points: Vec<(Point2, f32, f32)>,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .pen(pen)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    Model { strokes: vec![] }
+}
+
+fn pen(_app: &App, model: &mut Model, pen: PenEvent) {
+    if let TouchPhase::Started = pen.phase {
+        model.strokes.push(Stroke {
+            erase: false,
+            points: vec![],
+        });
+    }
+    if let Some(stroke) = model.strokes.last_mut() {
+        stroke.erase |= pen.barrel_button;
+        let width = 1.0 + pen.pressure * 24.0;
+        let alpha = pen.tilt.map(|tilt| tilt / (PI / 2.0)).unwrap_or(1.0);
+        stroke.points.push((pen.position, width, alpha));
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if let Key::Space = key {
+        model.strokes.clear();
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+
+    for stroke in &model.strokes {
+        for pair in stroke.points.windows(2) {
+            let (a, width, alpha) = pair[0];
+            let (b, _, _) = pair[1];
+            let color = match stroke.erase {
+                true => rgba(1.0, 1.0, 1.0, 1.0),
+                false => rgba(0.1, 0.1, 0.15, alpha),
+            };
+            draw.line()
+                .start(a)
+                .end(b)
+                .weight(width)
+                .caps_round()
+                .color(color);
+        }
+    }
+
+    let pen = app.pen();
+    let status = format!(
+        "pressure: {:.2}  tilt: {}  barrel: {}",
+        pen.pressure,
+        pen.tilt
+            .map(|t| format!("{:.2}", t))
+            .unwrap_or_else(|| "n/a".into()),
+        pen.barrel_button,
+    );
+    let win = app.window_rect().pad(20.0);
+    draw.text(&status)
+        .wh(win.wh())
+        .align_text_bottom()
+        .left_justify()
+        .color(BLACK);
+
+    draw.to_frame(app, &frame).unwrap();
+}
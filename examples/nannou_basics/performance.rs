@@ -0,0 +1,125 @@
+//! Demonstrates switching between an editor layout and a clean performance output.
+//!
+//! The sketch opens an output window along with an editor window containing a scope of the
+//! animation parameters. In performance mode the output fills the chosen display and the editor
+//! window is hidden if it shares that display.
+//!
+//! - Press `Tab` to toggle between the editor and performance modes.
+//! - Press `D` to cycle the display used for the performance.
+
+use nannou::performance::{Mode, Role};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model)
+        .performance_key(Key::Tab)
+        .update(update)
+        .run();
+}
+
+struct Model {
+    // Recent values of the animation parameter, displayed by the editor's scope.
+    history: Vec<f32>,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .title("output")
+        .size(640, 360)
+        .performance_role(Role::Output)
+        .key_pressed(key_pressed)
+        .view(view_output)
+        .build()
+        .unwrap();
+    app.new_window()
+        .title("editor")
+        .size(480, 240)
+        .performance_role(Role::Editor)
+        .key_pressed(key_pressed)
+        .view(view_editor)
+        .build()
+        .unwrap();
+    Model { history: vec![] }
+}
+
+fn key_pressed(app: &App, _model: &mut Model, key: Key) {
+    if let Key::D = key {
+        let n_displays = app.displays().len();
+        if n_displays == 0 {
+            return;
+        }
+        let next = match app.performance_display() {
+            Some(id) => (id.index() + 1) % n_displays,
+            None => 0,
+        };
+        app.set_performance_display(Some(nannou::display::Id(next)));
+        // Move the output to the newly chosen display if we are already performing.
+        if app.performance_mode() == Mode::Performance {
+            app.set_performance_mode(Mode::Performance);
+        }
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    model.history.push(param(app.time));
+    let len = model.history.len();
+    if len > 240 {
+        model.history.drain(..len - 240);
+    }
+}
+
+// The animation parameter driving the output.
+fn param(t: f32) -> f32 {
+    (t * 1.7).sin() * 0.5 + (t * 0.6).sin() * 0.5
+}
+
+fn view_output(app: &App, _model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    let win = app.window_rect();
+    let p = param(app.time);
+    for i in 0..12 {
+        let f = i as f32 / 12.0;
+        let radius = win.h().min(win.w()) * 0.45 * (1.0 - f);
+        let hue = (f + p * 0.2 + app.time * 0.05).rem_euclid(1.0);
+        draw.ellipse()
+            .radius(radius * (0.8 + 0.2 * p))
+            .no_fill()
+            .stroke_weight(2.0)
+            .stroke(hsl(hue, 0.7, 0.5));
+    }
+    draw.to_frame(app, &frame).unwrap();
+}
+
+fn view_editor(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+    let win = app.window_rect();
+    let scope = win.pad(20.0);
+
+    // The scope of the animation parameter.
+    let step = scope.w() / 240.0;
+    let points = model.history.iter().enumerate().map(|(i, &v)| {
+        let x = scope.left() + i as f32 * step;
+        let y = v * scope.h() * 0.4;
+        pt2(x, y)
+    });
+    draw.polyline().weight(1.5).points(points).color(LIGHTGREEN);
+
+    let mode = match app.performance_mode() {
+        Mode::Editor => "editor",
+        Mode::Performance => "performance",
+    };
+    let display = match app.performance_display() {
+        Some(id) => format!("display {}", id.index()),
+        None => "current display".to_string(),
+    };
+    let text = format!("mode: {}\noutput on: {}", mode, display);
+    draw.text(&text)
+        .wh(scope.wh())
+        .align_text_top()
+        .left_justify()
+        .color(WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
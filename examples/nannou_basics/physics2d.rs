@@ -0,0 +1,141 @@
+//! Demonstrates 2D rigid body physics via the `physics2d` module.
+//!
+//! Requires the `physics2d` feature: `cargo run --example physics2d --features physics2d`.
+//!
+//! - Click the left mouse button to drop a ball.
+//! - Click the right mouse button to drop a box.
+//! - Press `D` to toggle the debug view of colliders and joints.
+
+use nannou::physics2d::{BodyType, RigidBodyHandle, World};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    world: World,
+    bodies: Vec<Body>,
+    debug: bool,
+}
+
+struct Body {
+    handle: RigidBodyHandle,
+    shape: Shape,
+    color: Hsl,
+}
+
+enum Shape {
+    Ball(f32),
+    Box(Vec2),
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(900, 700)
+        .key_pressed(key_pressed)
+        .mouse_pressed(mouse_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+
+    let mut world = World::new();
+    let win = app.window_rect();
+
+    // The ground and a ramp.
+    world.add_rect(
+        BodyType::Fixed,
+        Rect::from_x_y_w_h(0.0, win.bottom() + 20.0, win.w() - 40.0, 20.0),
+    );
+    world.add_polyline(
+        BodyType::Fixed,
+        vec![pt2(-400.0, 150.0), pt2(-250.0, 80.0), pt2(-100.0, 60.0)],
+    );
+
+    // A pendulum pinned in place.
+    let pivot = world.add_circle(BodyType::Fixed, pt2(200.0, 250.0), 5.0);
+    let bob = world.add_circle(BodyType::Dynamic, pt2(320.0, 250.0), 25.0);
+    world.add_pin_joint(pivot, bob, pt2(200.0, 250.0));
+    let bodies = vec![Body {
+        handle: bob,
+        shape: Shape::Ball(25.0),
+        color: hsl(0.6, 0.6, 0.5),
+    }];
+
+    Model {
+        world,
+        bodies,
+        debug: true,
+    }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    if let Key::D = key {
+        model.debug = !model.debug;
+    }
+}
+
+fn mouse_pressed(app: &App, model: &mut Model, button: MouseButton) {
+    let position = app.mouse.position();
+    let color = hsl(random(), 0.6, 0.6);
+    let (handle, shape) = match button {
+        MouseButton::Left => {
+            let radius = random_range(10.0, 30.0);
+            let handle = model.world.add_circle(BodyType::Dynamic, position, radius);
+            (handle, Shape::Ball(radius))
+        }
+        MouseButton::Right => {
+            let size = vec2(random_range(20.0, 60.0), random_range(20.0, 60.0));
+            let rect = Rect::from_xy_wh(position, size);
+            let handle = model.world.add_rect(BodyType::Dynamic, rect);
+            (handle, Shape::Box(size))
+        }
+        _ => return,
+    };
+    model.bodies.push(Body {
+        handle,
+        shape,
+        color,
+    });
+}
+
+fn update(_app: &App, model: &mut Model, update: Update) {
+    model.world.update(&update);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+
+    for body in &model.bodies {
+        let (position, rotation) = match (
+            model.world.position(body.handle),
+            model.world.rotation(body.handle),
+        ) {
+            (Some(p), Some(r)) => (p, r),
+            _ => continue,
+        };
+        match body.shape {
+            Shape::Ball(radius) => {
+                draw.ellipse()
+                    .xy(position)
+                    .radius(radius)
+                    .rotate(rotation)
+                    .color(body.color);
+            }
+            Shape::Box(size) => {
+                draw.rect()
+                    .xy(position)
+                    .wh(size)
+                    .rotate(rotation)
+                    .color(body.color);
+            }
+        }
+    }
+
+    if model.debug {
+        draw.debug_physics(&model.world);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
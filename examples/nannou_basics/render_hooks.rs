@@ -0,0 +1,106 @@
+//! Demonstrates the `before_render` and `after_render` window hooks.
+//!
+//! Every few frames, the `after_render` hook reads the finished frame back to the CPU and measures
+//! its average brightness, which is then displayed by the `view` function. The `before_render`
+//! hook records when each frame begins so that the time spent encoding the view can be shown.
+
+use nannou::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// Read back one in every `READBACK_INTERVAL` frames.
+const READBACK_INTERVAL: u64 = 10;
+
+fn main() {
+    nannou::app(model).run();
+}
+
+struct Model {
+    capturer: wgpu::TextureCapturer,
+    brightness: Arc<Mutex<f32>>,
+    frame_start: Mutex<Option<Instant>>,
+    view_duration: Mutex<std::time::Duration>,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(720, 480)
+        .before_render(before_render)
+        .after_render(after_render)
+        .view(view)
+        .build()
+        .unwrap();
+    Model {
+        capturer: wgpu::TextureCapturer::default(),
+        brightness: Arc::new(Mutex::new(0.0)),
+        frame_start: Mutex::new(None),
+        view_duration: Default::default(),
+    }
+}
+
+fn before_render(_app: &App, model: &Model, _textures: &FrameTextures) {
+    *model.frame_start.lock().unwrap() = Some(Instant::now());
+}
+
+fn after_render(_app: &App, model: &Model, textures: &FrameTextures) {
+    if let Some(start) = model.frame_start.lock().unwrap().take() {
+        *model.view_duration.lock().unwrap() = start.elapsed();
+    }
+
+    let texture = match textures.texture() {
+        Some(texture) if textures.nth() % READBACK_INTERVAL == 0 => texture,
+        _ => return,
+    };
+
+    // Copy the resolved frame to a buffer and read it back on another thread.
+    let device_queue_pair = textures.device_queue_pair();
+    let device = device_queue_pair.device();
+    let desc = wgpu::CommandEncoderDescriptor {
+        label: Some("render_hooks_readback"),
+    };
+    let mut encoder = device.create_command_encoder(&desc);
+    let snapshot = model.capturer.capture(device, &mut encoder, texture);
+    device_queue_pair.queue().submit(Some(encoder.finish()));
+
+    let brightness = model.brightness.clone();
+    let result = snapshot.read(move |result| {
+        if let Ok(image) = result {
+            let image = image.to_owned();
+            let sum: f32 = image
+                .pixels()
+                .map(|p| (p[0] as f32 + p[1] as f32 + p[2] as f32) / (3.0 * 255.0))
+                .sum();
+            let n = (image.width() * image.height()).max(1) as f32;
+            *brightness.lock().unwrap() = sum / n;
+        }
+    });
+    if result.is_err() {
+        eprintln!("timed out waiting for a readback worker");
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    // A circle that grows and shrinks, changing the brightness of the frame.
+    let win = app.window_rect();
+    let radius = (app.time.sin() * 0.5 + 0.5) * win.h() * 0.6;
+    draw.ellipse().radius(radius).color(WHITE);
+
+    let brightness = *model.brightness.lock().unwrap();
+    let duration = *model.view_duration.lock().unwrap();
+    let text = format!(
+        "average brightness: {:.3}\nprevious view: {:.2}ms",
+        brightness,
+        duration.as_secs_f64() * 1_000.0,
+    );
+    draw.text(&text)
+        .font_size(20)
+        .w(win.w() - 40.0)
+        .left_justify()
+        .x_y(0.0, win.top() - 40.0)
+        .color(rgb(1.0, 0.2, 0.4));
+
+    draw.to_frame(app, &frame).unwrap();
+}
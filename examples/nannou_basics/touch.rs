@@ -0,0 +1,79 @@
+//! Demonstrates touch input and two-finger gestures.
+//!
+//! Each touch in contact with the screen is drawn as a circle sized by its pressure. Use two
+//! fingers to pinch, rotate and pan the square.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).simple_window(view).run();
+}
+
+struct Model {
+    // The transform of the square before the current gesture began.
+    base: Transform,
+    current: Transform,
+}
+
+#[derive(Copy, Clone)]
+struct Transform {
+    position: Point2,
+    rotation: f32,
+    scale: f32,
+}
+
+fn model(_app: &App) -> Model {
+    let transform = Transform {
+        position: pt2(0.0, 0.0),
+        rotation: 0.0,
+        scale: 1.0,
+    };
+    Model {
+        base: transform,
+        current: transform,
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    match app.touches().gesture() {
+        Some(gesture) => {
+            model.current = Transform {
+                position: model.base.position + gesture.translation,
+                rotation: model.base.rotation + gesture.rotation,
+                scale: model.base.scale * gesture.scale,
+            };
+        }
+        None => model.base = model.current,
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let t = model.current;
+    draw.rect()
+        .xy(t.position)
+        .w_h(200.0 * t.scale, 200.0 * t.scale)
+        .rotate(t.rotation)
+        .color(STEELBLUE);
+
+    for touch in app.touches().iter() {
+        let radius = 30.0 + 40.0 * touch.pressure.unwrap_or(0.5);
+        draw.ellipse()
+            .xy(touch.position)
+            .radius(radius)
+            .no_fill()
+            .stroke(WHITE)
+            .stroke_weight(2.0);
+        draw.text(&touch.id.to_string())
+            .xy(touch.position)
+            .color(WHITE);
+    }
+
+    if let Some(gesture) = app.touches().gesture() {
+        draw.ellipse().xy(gesture.centre).radius(6.0).color(ORANGE);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,91 @@
+//! Edit the curve in the lower half of the window to shape the animation of the circle.
+//!
+//! - Drag a point to move it, click an empty space to add a point and right click to remove one.
+//! - Press `1` to `5` to load one of the built-in presets.
+//! - Press `S` to save the curve to `curve.json` and `L` to load it again.
+
+use nannou::curve::{self, Curve};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+struct Model {
+    curve: Curve,
+    editor: curve::Editor,
+    presets: curve::Presets,
+}
+
+fn model(app: &App) -> Model {
+    app.new_window()
+        .size(720, 720)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let rect = Rect::from_w_h(600.0, 240.0).mid_bottom_of(app.window_rect().pad(40.0));
+    Model {
+        curve: Curve::ease_in_out(),
+        editor: curve::Editor::new(rect),
+        presets: curve::Presets::builtin(),
+    }
+}
+
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    let path = app
+        .project_path()
+        .expect("failed to locate project directory")
+        .join("curve.json");
+    let preset = match key {
+        Key::Key1 => "linear",
+        Key::Key2 => "ease_in",
+        Key::Key3 => "ease_out",
+        Key::Key4 => "ease_in_out",
+        Key::Key5 => "envelope",
+        Key::S => {
+            if let Err(err) = model.curve.save(&path) {
+                eprintln!("failed to save curve: {}", err);
+            }
+            return;
+        }
+        Key::L => {
+            match Curve::load(&path) {
+                Ok(curve) => model.curve = curve,
+                Err(err) => eprintln!("failed to load curve: {}", err),
+            }
+            return;
+        }
+        _ => return,
+    };
+    if let Some(curve) = model.presets.get(preset) {
+        model.curve = curve.clone();
+    }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    model.editor.update(app, &mut model.curve);
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(rgb(0.1, 0.1, 0.12));
+
+    // Move a circle back and forth, shaped by the curve.
+    let t = (app.time * 0.5).fract();
+    let win = app.window_rect();
+    let x = map_range(
+        model.curve.sample(t),
+        0.0,
+        1.0,
+        win.left() + 80.0,
+        win.right() - 80.0,
+    );
+    draw.ellipse()
+        .x_y(x, win.h() * 0.2)
+        .radius(30.0)
+        .color(CORNFLOWERBLUE);
+
+    model.editor.draw(&draw, &model.curve);
+    draw.to_frame(app, &frame).unwrap();
+}
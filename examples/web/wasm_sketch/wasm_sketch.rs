@@ -0,0 +1,61 @@
+//! A sketch that runs natively or embedded within a web page via WebAssembly and WebGPU.
+//!
+//! On the web, the model must be initialised asynchronously as the browser's event loop may not
+//! be blocked, so windows are built via `build_async` and assets are fetched via
+//! `App::load_asset_bytes`.
+//!
+//! To run within the browser, build the example for the `wasm32-unknown-unknown` target with the
+//! `wasm-webgpu` feature and generate its bindings with `wasm-bindgen`:
+//!
+//! ```text
+//! cargo build -p examples --release --example wasm_sketch --target wasm32-unknown-unknown \
+//!     --features wasm-webgpu
+//! wasm-bindgen --target web --out-dir examples/web/wasm_sketch/pkg \
+//!     target/wasm32-unknown-unknown/release/examples/wasm_sketch.wasm
+//! ```
+//!
+//! Then serve the `examples/web/wasm_sketch` directory along with a copy of the `assets`
+//! directory, e.g. via `python3 -m http.server`, and open `index.html` in a browser with WebGPU
+//! support.
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app::Builder::new_async(|app| Box::new(model(app))).run();
+}
+
+struct Model {
+    logo: wgpu::Texture,
+}
+
+async fn model(app: &App) -> Model {
+    app.new_window()
+        .size(640, 480)
+        .title("wasm_sketch")
+        .view(view)
+        .build_async()
+        .await
+        .unwrap();
+    let bytes = app
+        .load_asset_bytes("images/nannou.png")
+        .await
+        .expect("failed to load the logo");
+    let image = nannou::image::load_from_memory(&bytes).unwrap();
+    let logo = wgpu::Texture::from_image(app, &image);
+    Model { logo }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+    let t = app.time;
+    for i in 0..12 {
+        let f = i as f32 / 12.0;
+        let p = pt2((t + f * TAU).cos(), (t * 1.5 + f * TAU).sin()) * 160.0;
+        draw.ellipse().xy(p).radius(12.0).hsla(f, 0.8, 0.6, 0.8);
+    }
+    draw.texture(&model.logo)
+        .xy(app.mouse.position())
+        .w_h(128.0, 128.0)
+        .rotate(t * 0.5);
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,68 @@
+//! Run a Shadertoy-style fragment shader over the whole window.
+//!
+//! The shader is loaded from `assets/shadertoy/tunnel.wgsl` with an image bound to `iChannel0`.
+//! Drag the mouse to steer the tunnel and press the up and down keys to change its speed, passed
+//! to the shader via `iParams`. With nannou's `glsl` feature enabled, shaders copied from
+//! Shadertoy may be loaded from `.glsl` files as they are.
+use nannou::prelude::*;
+
+struct Model {
+    shadertoy: wgpu::Shadertoy,
+    speed: f32,
+}
+
+fn main() {
+    nannou::app(model).update(update).run();
+}
+
+fn model(app: &App) -> Model {
+    let window_id = app
+        .new_window()
+        .size(800, 450)
+        .key_pressed(key_pressed)
+        .view(view)
+        .build()
+        .unwrap();
+    let window = app.window(window_id).unwrap();
+    let assets = app.assets_path().unwrap();
+    let image = wgpu::Texture::from_path(app, assets.join("images").join("bee.jpg")).unwrap();
+    let shader_path = assets.join("shadertoy").join("tunnel.wgsl");
+    let speed = 0.5;
+    let params = wgpu::ShaderParams::new()
+        .float("speed", speed)
+        .color("tint", lin_srgba(1.0, 0.9, 0.7, 1.0));
+    let device = window.device();
+    let shadertoy = match wgpu::Shadertoy::from_path_with_params(device, shader_path, params) {
+        Ok(shadertoy) => shadertoy.channel(0, &image),
+        Err(err) => panic!("{}", err),
+    };
+    Model { shadertoy, speed }
+}
+
+fn key_pressed(_app: &App, model: &mut Model, key: Key) {
+    match key {
+        Key::Up => model.speed += 0.1,
+        Key::Down => model.speed -= 0.1,
+        _ => return,
+    }
+    model.shadertoy.set_param("speed", model.speed);
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    // Shadertoy measures the mouse in pixels from the bottom left of the window.
+    let window = app.main_window();
+    let mouse = (app.mouse.position() - app.window_rect().bottom_left()) * window.scale_factor();
+    let pressed = app.mouse.buttons.left().is_down();
+    model.shadertoy.set_time(app.time);
+    model.shadertoy.set_mouse(mouse.to_array(), pressed);
+}
+
+fn view(_app: &App, model: &Model, frame: Frame) {
+    let device = frame.device_queue_pair().device();
+    model.shadertoy.encode_multisampled(
+        device,
+        &mut frame.command_encoder(),
+        frame.texture_view(),
+        frame.texture_msaa_samples(),
+    );
+}
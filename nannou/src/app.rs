@@ -8,20 +8,37 @@
 //!   thread.
 //! - [**LoopMode**](./enum.LoopMode.html) - describes the behaviour of the application event loop.
 
+use crate::audio_buffer::{self, AudioBuffer};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::clipboard;
+use crate::color::Palette;
+use crate::config;
+use crate::contact_sheet;
+use crate::display::{self, Display};
 use crate::draw;
 use crate::event::{self, Event, Key, LoopEvent, Update};
-use crate::frame::{Frame, RawFrame};
+use crate::frame::{Frame, FrameTextures, RawFrame};
+#[cfg(feature = "gamepad")]
+use crate::gamepad;
 use crate::geom;
+use crate::glam::Mat4;
+use crate::image;
+use crate::performance;
+use crate::profile;
+use crate::span;
 use crate::state;
+use crate::stats;
 use crate::time::DurationF64;
+use crate::watch;
 use crate::wgpu;
 use crate::window::{self, Window};
 use find_folder;
 use instant::Instant;
-use std::cell::{RefCell, RefMut};
+use std::any::Any;
+use std::cell::{Cell, RefCell, RefMut};
 use std::collections::HashMap;
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::Arc;
@@ -30,6 +47,8 @@ use std::{self, future};
 use wgpu_upstream::InstanceDescriptor;
 use winit;
 use winit::event_loop::ControlFlow;
+#[cfg(target_os = "android")]
+use winit::platform::android::activity::AndroidApp;
 
 /// The user function type for initialising their model.
 pub type ModelFn<Model> = fn(&App) -> Model;
@@ -49,6 +68,13 @@ pub type SketchViewFn = fn(&App, Frame);
 /// The user function type allowing them to consume the `model` when the application exits.
 pub type ExitFn<Model> = fn(&App, Model);
 
+/// The user function type for reloading a watched data file into the model.
+pub type WatchFn<Model> = fn(&App, &mut Model, &watch::Data);
+
+/// The user function type for selecting the GPU adapter used by windows, given the info of each
+/// available adapter.
+pub type AdapterFn = fn(&wgpu::AdapterInfo) -> bool;
+
 /// The **App**'s view function.
 enum View<Model = ()> {
     /// A view function allows for viewing the user's model.
@@ -65,11 +91,51 @@ pub struct Builder<M = (), E = Event> {
     update: Option<UpdateFn<M>>,
     default_view: Option<View<M>>,
     exit: Option<ExitFn<M>>,
+    watchers: Vec<watch::Watcher<M>>,
+    persist_model: Option<PersistModel<M>>,
     create_default_window: bool,
     default_window_size: Option<DefaultWindowSize>,
     capture_frame_timeout: Option<Option<Duration>>,
     max_capture_frame_jobs: Option<u32>,
     backends: wgpu::Backends,
+    // The config loaded via `with_config`, or the error that occurred while loading it.
+    user_config: Option<Result<Box<dyn Any>, config::Error>>,
+    #[cfg(target_os = "android")]
+    android_app: Option<AndroidApp>,
+}
+
+// The file and functions used to restore the model on start and save it on exit.
+struct PersistModel<M> {
+    path: PathBuf,
+    load: fn(&Path) -> Result<M, crate::io::JsonFileError>,
+    save: fn(&Path, &M) -> Result<(), crate::io::JsonFileError>,
+}
+
+impl<M> PersistModel<M> {
+    // The model saved during a previous run if any, or the given model otherwise.
+    fn restore(&self, model: M) -> M {
+        if !self.path.exists() {
+            return model;
+        }
+        match (self.load)(&self.path) {
+            Ok(restored) => restored,
+            Err(err) => {
+                eprintln!(
+                    "failed to restore model from `{}`: {}",
+                    self.path.display(),
+                    err
+                );
+                model
+            }
+        }
+    }
+
+    // Save the model, reporting any failure.
+    fn save(&self, model: &M) {
+        if let Err(err) = (self.save)(&self.path, model) {
+            eprintln!("failed to save model to `{}`: {}", self.path.display(), err);
+        }
+    }
 }
 
 /// A nannou `Sketch` builder.
@@ -108,6 +174,19 @@ pub struct App {
     default_window_size: Option<DefaultWindowSize>,
     max_capture_frame_jobs: u32,
     capture_frame_timeout: Option<Duration>,
+    /// Time measurements in wall-clock time, unaffected by the time scale or pausing.
+    real_duration: state::Time,
+    /// The CPU time spent within the user's functions, shown by the stats overlay.
+    cpu_timings: Cell<stats::CpuTimings>,
+    /// Whether or not the app is suspended, in which case windows have no surfaces to draw to.
+    suspended: Cell<bool>,
+    /// The code with which the process exits, set once the user requests an exit.
+    exit_code: Cell<Option<i32>>,
+    /// The config loaded via `Builder::with_config`, if any.
+    user_config: Option<Box<dyn Any>>,
+    /// The handle to the Android activity, used to load assets from the APK.
+    #[cfg(target_os = "android")]
+    android_app: Option<AndroidApp>,
     pub(crate) event_loop_window_target: Option<EventLoopWindowTarget>,
     pub(crate) event_loop_proxy: Proxy,
     pub(crate) windows: RefCell<HashMap<window::Id, Window>>,
@@ -118,6 +197,9 @@ pub struct App {
     /// A map of active wgpu physical device adapters.
     adapters: wgpu::AdapterMap,
     draw_state: DrawState,
+    /// The connection to the system clipboard, opened upon first use.
+    #[cfg(not(target_arch = "wasm32"))]
+    clipboard: RefCell<Option<arboard::Clipboard>>,
     /// The window that is currently in focus.
     pub(crate) focused_window: RefCell<Option<window::Id>>,
     /// The current state of the `Mouse`.
@@ -133,11 +215,21 @@ pub struct App {
     /// change this to query the OS somehow, but I don't think `winit` provides a way to do this
     /// yet.
     pub keys: state::Keys,
+    /// The touches currently in contact with a touch screen or tablet.
+    touches: state::Touches,
+    /// The state of the pen, if any.
+    pen: state::Pen,
+    /// The state of all known gamepads.
+    #[cfg(feature = "gamepad")]
+    gamepads: gamepad::Gamepads,
     /// Key time measurements tracked by the App.
     ///
     /// `duration.since_start` specifies the duration since the app started running.
     ///
     /// `duration.since_prev_update` specifies the duration since the previous update event.
+    ///
+    /// Both durations are affected by the app's time scale and do not advance while the app is
+    /// paused. See `App::set_time_scale` and `App::pause`.
     pub duration: state::Time,
     /// The time in seconds since the `App` started running.
     ///
@@ -164,12 +256,30 @@ struct Config {
     loop_mode: LoopMode,
     exit_on_escape: bool,
     fullscreen_on_shortcut: bool,
+    time_scale: f64,
+    paused: bool,
+    pending_steps: u32,
+    performance_mode: performance::Mode,
+    performance_key: Option<Key>,
+    performance_display: Option<display::Id>,
+    accumulation: Option<f32>,
+    show_stats: bool,
+    stats_key: Option<Key>,
+    touch_emulates_mouse: bool,
+    device_desc: wgpu::DeviceDescriptor<'static>,
+    power_preference: wgpu::PowerPreference,
+    adapter: Option<AdapterFn>,
+    exit_after: Option<Duration>,
+    exit_after_frames: Option<u64>,
+    #[cfg(feature = "gamepad")]
+    gamepad_deadzone: f32,
 }
 
 // Draw state managed by the **App**.
 #[derive(Debug)]
 struct DrawState {
     draw: RefCell<draw::Draw>,
+    window_draws: RefCell<HashMap<window::Id, draw::Draw>>,
     renderers: RefCell<HashMap<window::Id, RefCell<draw::Renderer>>>,
 }
 
@@ -266,11 +376,16 @@ where
             update: None,
             default_view: None,
             exit: None,
+            watchers: vec![],
+            persist_model: None,
             create_default_window: false,
             default_window_size: None,
             max_capture_frame_jobs: None,
             capture_frame_timeout: None,
             backends: Self::DEFAULT_BACKENDS,
+            user_config: None,
+            #[cfg(target_os = "android")]
+            android_app: None,
         }
     }
 
@@ -292,11 +407,16 @@ where
             update,
             default_view,
             exit,
+            watchers,
+            persist_model,
             create_default_window,
             default_window_size,
             max_capture_frame_jobs,
             capture_frame_timeout,
             backends,
+            user_config,
+            #[cfg(target_os = "android")]
+            android_app,
             ..
         } = self;
         Builder {
@@ -306,11 +426,16 @@ where
             update,
             default_view,
             exit,
+            watchers,
+            persist_model,
             create_default_window,
             default_window_size,
             max_capture_frame_jobs,
             capture_frame_timeout,
-            backends
+            backends,
+            user_config,
+            #[cfg(target_os = "android")]
+            android_app,
         }
     }
 }
@@ -379,6 +504,51 @@ where
         self
     }
 
+    /// Watch the data file at the given path, calling the given function with its contents once
+    /// the model is created and again whenever the file is modified.
+    ///
+    /// Relative paths are relative to the current working directory. The file is checked for
+    /// modifications at most once per `watch::POLL_INTERVAL` between updates. Note that when using
+    /// `LoopMode::Wait`, modifications are only noticed once the app wakes for some other event.
+    ///
+    /// This may be called multiple times to watch multiple files. See the `watch` module for an
+    /// example.
+    pub fn watch<P>(mut self, path: P, watch: WatchFn<M>) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let watcher = watch::Watcher::new(path.as_ref().to_path_buf(), watch);
+        self.watchers.push(watcher);
+        self
+    }
+
+    /// Persist the model to the JSON file at the given path, restoring it on start and saving it
+    /// on exit.
+    ///
+    /// If the file exists, the model returned by the model function is replaced by the one loaded
+    /// from the file. This allows the state of long-running generative processes and tweaked
+    /// parameters to survive restarts. The model is saved before the exit function is called.
+    ///
+    /// The restored model fully replaces the one returned by the model function, which is
+    /// discarded. Fields skipped via `#[serde(skip)]` are therefore restored with their `Default`
+    /// values rather than those set by the model function, so models holding state that cannot be
+    /// serialized (e.g. a `window::Id` or GPU resources) should instead use `App::save_model` and
+    /// `App::load_model` directly, copying the loaded fields into the model they create.
+    ///
+    /// Relative paths are relative to the current working directory.
+    pub fn persist_model<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+        M: serde::Serialize + for<'de> serde::Deserialize<'de>,
+    {
+        self.persist_model = Some(PersistModel {
+            path: path.as_ref().to_path_buf(),
+            load: |path| crate::io::load_from_json(path),
+            save: |path, model| crate::io::save_to_json(path, model),
+        });
+        self
+    }
+
     /// Specify the default window size in points.
     ///
     /// If a window is created and its size is not specified, this size will be used.
@@ -394,12 +564,99 @@ where
         self
     }
 
+    /// Load a config of type `C` from the command line arguments and config file, making it
+    /// available to the model function and beyond via `App::config`.
+    ///
+    /// Any standard `size` or `fullscreen` settings are applied as though via `Builder::size` and
+    /// `Builder::fullscreen`. See the `config` module for the accepted argument and file formats.
+    ///
+    /// If the config fails to load, the error is printed by `Builder::run` and the process exits
+    /// with code `2` in place of running the app.
+    ///
+    /// ```no_run
+    /// use nannou::prelude::*;
+    /// use serde_derive::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Config {
+    ///     #[serde(flatten)]
+    ///     standard: nannou::config::Standard,
+    ///     #[serde(default)]
+    ///     particles: usize,
+    /// }
+    ///
+    /// struct Model {
+    ///     particles: usize,
+    /// }
+    ///
+    /// fn main() {
+    ///     // e.g. `cargo run -- --size 1920,1080 --particles 1000`
+    ///     nannou::app(model).with_config::<Config>().run();
+    /// }
+    ///
+    /// fn model(app: &App) -> Model {
+    ///     let config = app.config::<Config>().unwrap();
+    ///     let seed = config.standard.seed.unwrap_or(0);
+    ///     println!("seed: {}", seed);
+    ///     Model {
+    ///         particles: config.particles,
+    ///     }
+    /// }
+    /// ```
+    pub fn with_config<C>(mut self) -> Self
+    where
+        C: for<'de> serde::Deserialize<'de> + 'static,
+    {
+        let (config, standard) = match config::load::<C>() {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                self.user_config = Some(Err(err));
+                return self;
+            }
+        };
+        if let Some([width, height]) = standard.size {
+            self = self.size(width, height);
+        }
+        if standard.fullscreen {
+            self = self.fullscreen();
+        }
+        self.user_config = Some(Ok(Box::new(config)));
+        self
+    }
+
     /// Specify the default initial loop mode for this app.
     pub fn loop_mode(mut self, mode: LoopMode) -> Self {
         self.config.loop_mode = mode;
         self
     }
 
+    /// Specify a key that toggles the app between the editor and performance modes.
+    ///
+    /// See `App::set_performance_mode` and the `performance` module for details.
+    pub fn performance_key(mut self, key: Key) -> Self {
+        self.config.performance_key = Some(key);
+        self
+    }
+
+    /// Specify a key that toggles the statistics overlay.
+    ///
+    /// See `App::show_stats` and the `stats` module for details.
+    pub fn stats_key(mut self, key: Key) -> Self {
+        self.config.stats_key = Some(key);
+        self
+    }
+
+    /// Specify whether or not the first touch in contact should drive the state of `App::mouse`
+    /// as though it were the left mouse button.
+    ///
+    /// This allows for sketches written for the mouse to run on tablets and phones.
+    ///
+    /// By default, this is `true` on Android and iOS and `false` elsewhere.
+    pub fn touch_emulates_mouse(mut self, emulate: bool) -> Self {
+        self.config.touch_emulates_mouse = emulate;
+        self
+    }
+
     /// The maximum number of simultaneous capture frame jobs that can be run per window before we
     /// block and wait for the existing jobs to complete.
     ///
@@ -438,43 +695,170 @@ where
 
     /// Specify the set of preferred WGPU backends.
     ///
+    /// A specific backend may be preferred by specifying it alone, e.g. `wgpu::Backends::VULKAN`
+    /// or `wgpu::Backends::METAL`. On GL ES devices such as the Raspberry Pi, nannou falls back to
+    /// reduced limits and texture formats where necessary. See `wgpu::fallback_limits` and
+    /// `Frame::FALLBACK_TEXTURE_FORMAT`.
+    ///
     /// By default, this is `wgpu::Backends::PRIMARY | wgpu::Backends::GL`.
     pub fn backends(mut self, backends: wgpu::Backends) -> Self {
         self.backends = backends;
         self
     }
 
+    /// Provide the `AndroidApp` passed to the `android_main` entry point.
+    ///
+    /// This is required on Android, where it is used to create the event loop and to load assets
+    /// from the APK via `App::load_asset_bytes`.
+    #[cfg(target_os = "android")]
+    pub fn android_app(mut self, android_app: AndroidApp) -> Self {
+        self.android_app = Some(android_app);
+        self
+    }
+
+    /// Specify the power preference used when requesting the adapter for each window.
+    ///
+    /// Individual windows may override this via `window::Builder::power_preference`.
+    ///
+    /// By default, this is `wgpu::PowerPreference::HighPerformance`.
+    pub fn power_preference(mut self, pref: wgpu::PowerPreference) -> Self {
+        self.config.power_preference = pref;
+        self
+    }
+
+    /// Select the adapter used by windows from those available for the app's backends, e.g. to
+    /// choose between GPUs on a machine with more than one.
+    ///
+    /// The given function is called with the info of each adapter in the order in which they are
+    /// enumerated and the first for which it returns `true` is used. Building a window fails with
+    /// `window::BuildError::NoAvailableAdapter` if no adapter is selected.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # fn model(_: &App) {}
+    /// nannou::app(model)
+    ///     .adapter(|info| info.device_type == wgpu::DeviceType::DiscreteGpu)
+    ///     .run();
+    /// ```
+    ///
+    /// By default, the adapter is requested using the window's power preference. Adapter
+    /// selection is not supported on the web.
+    pub fn adapter(mut self, select: AdapterFn) -> Self {
+        self.config.adapter = Some(select);
+        self
+    }
+
+    /// Specify the device descriptor used when requesting the logical device for each window.
+    ///
+    /// Individual windows may override this via `window::Builder::device_descriptor`.
+    ///
+    /// By default, this is `wgpu::default_device_descriptor()`.
+    pub fn device_descriptor(mut self, device_desc: wgpu::DeviceDescriptor<'static>) -> Self {
+        self.config.device_desc = device_desc;
+        self
+    }
+
+    /// Require the given device features, e.g. `wgpu::Features::POLYGON_MODE_LINE` or
+    /// `wgpu::Features::TIMESTAMP_QUERY`, in addition to those already required.
+    ///
+    /// Building a window fails with `window::BuildError::UnsupportedFeatures` if its adapter
+    /// does not support all required features.
+    pub fn device_features(mut self, features: wgpu::Features) -> Self {
+        self.config.device_desc.features |= features;
+        self
+    }
+
+    /// Specify the device limits required by the app.
+    ///
+    /// Building a window fails with `window::BuildError::UnsupportedLimits` if its adapter does
+    /// not support the given limits.
+    ///
+    /// By default, this is `wgpu::Limits::default()`.
+    pub fn device_limits(mut self, limits: wgpu::Limits) -> Self {
+        self.config.device_desc.limits = limits;
+        self
+    }
+
     /// Build and run an `App` with the specified parameters.
     ///
     /// This function will not return until the application has exited.
     ///
+    /// If a config was specified via `with_config` but failed to load, the error is printed and
+    /// the process exits with code `2` without running the app.
+    ///
     /// If you wish to remain cross-platform friendly, we recommend that you call this on the main
     /// thread as some platforms require that their application event loop and windows are
     /// initialised on the main thread.
+    ///
+    /// On the web, where the browser's event loop may not be blocked, the app is instead spawned
+    /// onto the browser's event loop and this function returns immediately. As windows must then
+    /// be built via `window::Builder::build_async`, use `Builder::new_async` to build them
+    /// within an `async` model function.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn run(self) {
         let rt = Self::build_runtime();
         rt.block_on(self.run_async())
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    fn build_runtime() -> tokio::runtime::Runtime {
-        tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .build()
-            .expect("failed to create tokio runtime")
+    /// Spawn the `App` onto the browser's event loop.
+    ///
+    /// See the native version of this method for details.
+    #[cfg(target_arch = "wasm32")]
+    pub fn run(self) {
+        wasm_bindgen_futures::spawn_local(self.run_async())
     }
 
-    #[cfg(target_arch = "wasm32")]
+    /// Run the `App` until the given duration of wall-clock time has elapsed, then exit.
+    ///
+    /// This is useful for timed installations, automated captures and tests. The exit function is
+    /// called as usual and any pending frame captures are completed first.
+    pub fn run_for(mut self, duration: Duration) {
+        self.config.exit_after = Some(duration);
+        self.run()
+    }
+
+    /// Run the `App` until each window has presented a single frame, then exit.
+    ///
+    /// This is useful for rendering a still image, e.g. via `Window::capture_frame`, from a
+    /// script or CI job. The exit function is called as usual and any pending frame captures are
+    /// completed first.
+    pub fn run_once(mut self) {
+        self.config.exit_after_frames = Some(1);
+        self.run()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
     fn build_runtime() -> tokio::runtime::Runtime {
-        tokio::runtime::Builder::new_current_thread()
+        tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
             .expect("failed to create tokio runtime")
     }
 
     pub async fn run_async(self) {
+        // Report a config that failed to load rather than running without it.
+        let user_config = match self.user_config {
+            Some(Err(err)) => {
+                eprintln!("failed to load config: {}", err);
+                // Exit with a non-zero code so that scripts and supervisors notice the failure.
+                #[cfg(not(target_arch = "wasm32"))]
+                std::process::exit(2);
+                #[cfg(target_arch = "wasm32")]
+                return;
+            }
+            Some(Ok(config)) => Some(config),
+            None => None,
+        };
+
         // Start the winit window event loop.
-        let event_loop = winit::event_loop::EventLoop::new();
+        #[allow(unused_mut)]
+        let mut event_loop_builder = winit::event_loop::EventLoopBuilder::new();
+        #[cfg(target_os = "android")]
+        if let Some(ref android_app) = self.android_app {
+            use winit::platform::android::EventLoopBuilderExtAndroid;
+            event_loop_builder.with_android_app(android_app.clone());
+        }
+        let event_loop = event_loop_builder.build();
 
         // Create the proxy used to awaken the event loop.
         let event_loop_proxy = event_loop.create_proxy();
@@ -492,7 +876,7 @@ where
             .capture_frame_timeout
             .unwrap_or(Some(Self::DEFAULT_CAPTURE_FRAME_TIMEOUT));
         let event_loop_window_target = Some(EventLoopWindowTarget::Owned(event_loop));
-        let app = App::new(
+        let mut app = App::new(
             self.config,
             event_loop_proxy,
             event_loop_window_target,
@@ -501,6 +885,11 @@ where
             capture_frame_timeout,
             self.backends,
         );
+        app.user_config = user_config;
+        #[cfg(target_os = "android")]
+        {
+            app.android_app = self.android_app;
+        }
 
         // Create the default window if necessary
         if self.create_default_window {
@@ -513,7 +902,12 @@ where
         }
 
         // Call the user's model function.
-        let model = Pin::from((self.model)(&app)).await;
+        let mut model = Pin::from((self.model)(&app)).await;
+
+        // Restore the model saved during a previous run, if any.
+        if let Some(ref persist) = self.persist_model {
+            model = persist.restore(model);
+        }
 
         // If there is not yet some default window in "focus" check to see if one has been created.
         if app.focused_window.borrow().is_none() {
@@ -529,6 +923,8 @@ where
             self.update,
             self.default_view,
             self.exit,
+            self.watchers,
+            self.persist_model,
         );
     }
 }
@@ -551,12 +947,53 @@ where
         self
     }
 
+    /// Load a config of type `C` from the command line arguments and config file.
+    ///
+    /// This method delegates to `Builder::with_config`.
+    pub fn with_config<C>(mut self) -> Self
+    where
+        C: for<'de> serde::Deserialize<'de> + 'static,
+    {
+        self.builder = self.builder.with_config::<C>();
+        self
+    }
+
+    /// Require the given device features for the sketch window.
+    ///
+    /// This method delegates to `Builder::device_features`.
+    pub fn device_features(mut self, features: wgpu::Features) -> Self {
+        self.builder = self.builder.device_features(features);
+        self
+    }
+
+    /// Specify the device limits required by the sketch window.
+    ///
+    /// This method delegates to `Builder::device_limits`.
+    pub fn device_limits(mut self, limits: wgpu::Limits) -> Self {
+        self.builder = self.builder.device_limits(limits);
+        self
+    }
+
     /// Build and run a `Sketch` with the specified parameters.
     ///
     /// This calls `App::run` internally. See that method for details!
     pub fn run(self) {
         self.builder.run()
     }
+
+    /// Build and run a `Sketch` until the given duration has elapsed, then exit.
+    ///
+    /// This method delegates to `Builder::run_for`.
+    pub fn run_for(self, duration: Duration) {
+        self.builder.run_for(duration)
+    }
+
+    /// Build and run a `Sketch` until a single frame has been presented, then exit.
+    ///
+    /// This method delegates to `Builder::run_once`.
+    pub fn run_once(self) {
+        self.builder.run_once()
+    }
 }
 
 impl Builder<(), Event> {
@@ -636,6 +1073,23 @@ impl Default for Config {
             loop_mode,
             exit_on_escape,
             fullscreen_on_shortcut,
+            time_scale: 1.0,
+            paused: false,
+            pending_steps: 0,
+            performance_mode: performance::Mode::Editor,
+            performance_key: None,
+            performance_display: None,
+            accumulation: None,
+            show_stats: false,
+            stats_key: None,
+            touch_emulates_mouse: App::DEFAULT_TOUCH_EMULATES_MOUSE,
+            device_desc: wgpu::default_device_descriptor(),
+            power_preference: wgpu::DEFAULT_POWER_PREFERENCE,
+            adapter: None,
+            exit_after: None,
+            exit_after_frames: None,
+            #[cfg(feature = "gamepad")]
+            gamepad_deadzone: App::DEFAULT_GAMEPAD_DEADZONE,
         }
     }
 }
@@ -644,6 +1098,10 @@ impl App {
     pub const ASSETS_DIRECTORY_NAME: &'static str = "assets";
     pub const DEFAULT_EXIT_ON_ESCAPE: bool = true;
     pub const DEFAULT_FULLSCREEN_ON_SHORTCUT: bool = true;
+    pub const DEFAULT_TOUCH_EMULATES_MOUSE: bool =
+        cfg!(any(target_os = "android", target_os = "ios"));
+    #[cfg(feature = "gamepad")]
+    pub const DEFAULT_GAMEPAD_DEADZONE: f32 = 0.1;
 
     // Create a new `App`.
     fn new(
@@ -664,11 +1122,23 @@ impl App {
         let draw = RefCell::new(draw::Draw::default());
         let config = RefCell::new(config);
         let renderers = RefCell::new(Default::default());
-        let draw_state = DrawState { draw, renderers };
+        let window_draws = RefCell::new(HashMap::new());
+        let draw_state = DrawState {
+            draw,
+            window_draws,
+            renderers,
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let clipboard = RefCell::new(None);
         let focused_window = RefCell::new(None);
         let mouse = state::Mouse::new();
         let keys = state::Keys::default();
+        let touches = state::Touches::default();
+        let pen = state::Pen::default();
+        #[cfg(feature = "gamepad")]
+        let gamepads = gamepad::Gamepads::new();
         let duration = state::Time::default();
+        let real_duration = state::Time::default();
         let time = duration.since_start.secs() as _;
         let app = App {
             event_loop_proxy,
@@ -676,6 +1146,13 @@ impl App {
             default_window_size,
             max_capture_frame_jobs,
             capture_frame_timeout,
+            real_duration,
+            cpu_timings: Cell::new(stats::CpuTimings::default()),
+            suspended: Cell::new(false),
+            exit_code: Cell::new(None),
+            user_config: None,
+            #[cfg(target_os = "android")]
+            android_app: None,
             focused_window,
             backends,
             instance,
@@ -683,8 +1160,14 @@ impl App {
             windows,
             config,
             draw_state,
+            #[cfg(not(target_arch = "wasm32"))]
+            clipboard,
             mouse,
             keys,
+            touches,
+            pen,
+            #[cfg(feature = "gamepad")]
+            gamepads,
             duration,
             time,
         };
@@ -726,6 +1209,29 @@ impl App {
         }
     }
 
+    /// Returns a snapshot of all displays currently connected to the system.
+    ///
+    /// Each **Display** describes the geometry, scale factor and refresh rate of a monitor. The
+    /// **Id** of each display is its index within the returned list and may be passed to
+    /// `window::Builder::fullscreen_on`.
+    pub fn displays(&self) -> Vec<Display> {
+        let monitors = self.available_monitors();
+        if monitors.is_empty() {
+            return vec![];
+        }
+        let primary = self.primary_monitor();
+        monitors
+            .into_iter()
+            .enumerate()
+            .map(|(ix, monitor)| Display::new(display::Id(ix), monitor, primary.as_ref()))
+            .collect()
+    }
+
+    /// Returns the display with the given **Id**, if it is connected.
+    pub fn display(&self, id: display::Id) -> Option<Display> {
+        self.displays().into_iter().nth(id.index())
+    }
+
     /// Find and return the absolute path to the project's `assets` directory.
     ///
     /// This method looks for the assets directory in the following order:
@@ -748,6 +1254,88 @@ impl App {
         find_project_path()
     }
 
+    /// Load and decode the sound file at the given path for visualisation.
+    ///
+    /// Relative paths that do not exist relative to the current directory are looked up within
+    /// the project's `assets` directory. See the `audio_buffer` module for supported formats.
+    pub fn load_audio<P>(&self, path: P) -> Result<AudioBuffer, audio_buffer::LoadError>
+    where
+        P: AsRef<Path>,
+    {
+        AudioBuffer::load(self.find_asset(path.as_ref()))
+    }
+
+    /// Open the image at the given path and extract a **Palette** of up to `count` dominant
+    /// colors.
+    ///
+    /// Relative paths that do not exist relative to the current directory are looked up within
+    /// the project's `assets` directory. See `Palette::from_image` for details.
+    pub fn load_palette<P>(&self, path: P, count: usize) -> image::ImageResult<Palette>
+    where
+        P: AsRef<Path>,
+    {
+        Palette::open(self.find_asset(path.as_ref()), count)
+    }
+
+    // Relative paths that do not exist are resolved against the `assets` directory if possible.
+    fn find_asset(&self, path: &Path) -> PathBuf {
+        if path.is_relative() && !path.exists() {
+            if let Ok(assets) = self.assets_path() {
+                let asset = assets.join(path);
+                if asset.exists() {
+                    return asset;
+                }
+            }
+        }
+        path.to_path_buf()
+    }
+
+    /// Asynchronously load the bytes of the asset at the given path.
+    ///
+    /// Relative paths that do not exist relative to the current directory are looked up within
+    /// the project's `assets` directory. On the web, the path is fetched relative to the page's
+    /// `assets` directory instead. On Android, relative paths are first looked up within the
+    /// APK's `assets` directory, e.g. as packaged via the `assets` key of `cargo apk`'s
+    /// manifest metadata. This requires `Builder::android_app`.
+    pub async fn load_asset_bytes<P>(&self, path: P) -> std::io::Result<Vec<u8>>
+    where
+        P: AsRef<Path>,
+    {
+        #[cfg(target_os = "android")]
+        if let Some(bytes) = self.load_apk_asset(path.as_ref()) {
+            return bytes;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        let path = self.find_asset(path.as_ref());
+        #[cfg(target_arch = "wasm32")]
+        let path = Path::new(Self::ASSETS_DIRECTORY_NAME).join(path);
+        crate::io::load_bytes(path).await
+    }
+
+    // Read the asset at the given relative path from the APK via the NDK asset manager.
+    //
+    // Returns `None` if the path is absolute or no such asset is packaged.
+    #[cfg(target_os = "android")]
+    fn load_apk_asset(&self, path: &Path) -> Option<std::io::Result<Vec<u8>>> {
+        use std::io::Read;
+        if !path.is_relative() {
+            return None;
+        }
+        let android_app = self.android_app.as_ref()?;
+        let path = std::ffi::CString::new(path.to_str()?).ok()?;
+        let mut asset = android_app.asset_manager().open(&path)?;
+        let mut bytes = vec![];
+        Some(asset.read_to_end(&mut bytes).map(|_| bytes))
+    }
+
+    /// The `AndroidApp` provided via `Builder::android_app`, if any.
+    ///
+    /// This provides access to the Android activity, e.g. its asset manager and configuration.
+    #[cfg(target_os = "android")]
+    pub fn android_app(&self) -> Option<&AndroidApp> {
+        self.android_app.as_ref()
+    }
+
     /// Begin building a new window.
     pub fn new_window(&self) -> window::Builder {
         let builder = window::Builder::new(self);
@@ -761,6 +1349,14 @@ impl App {
             .capture_frame_timeout(self.capture_frame_timeout)
     }
 
+    /// Begin building a set of windows spanning all connected monitors.
+    ///
+    /// By default, a borderless fullscreen window is created on each monitor. Each window acts as
+    /// a viewport into a single logical canvas. See the **span** module for details.
+    pub fn span_all_monitors(&self) -> span::Builder<'_> {
+        span::Builder::new(self)
+    }
+
     /// The number of windows currently in the application.
     pub fn window_count(&self) -> usize {
         self.windows.borrow().len()
@@ -801,6 +1397,15 @@ impl App {
         self.main_window().rect()
     }
 
+    /// Return the **Rect** for the window with the given **Id**.
+    ///
+    /// The **Rect** coords are described in "points" (pixels divided by the hidpi factor).
+    ///
+    /// Returns `None` if there is no open window for the given **Id**.
+    pub fn window_rect_of(&self, id: window::Id) -> Option<geom::Rect<f32>> {
+        self.window(id).map(|window| window.rect())
+    }
+
     /// A reference to the window currently in focus.
     ///
     /// **Panics** if their are no windows open in the **App**.
@@ -819,6 +1424,62 @@ impl App {
         self.backends
     }
 
+    /// The power preference used when requesting the adapter for windows that do not specify
+    /// their own.
+    pub fn power_preference(&self) -> wgpu::PowerPreference {
+        self.config.borrow().power_preference
+    }
+
+    /// Save the given model to a JSON file at the given path.
+    ///
+    /// The file is saved safely via `io::save_to_json`, so that an existing save is not lost if
+    /// writing fails. See `Builder::persist_model` for saving automatically on exit.
+    pub fn save_model<M, P>(&self, model: &M, path: P) -> Result<(), crate::io::JsonFileError>
+    where
+        M: serde::Serialize,
+        P: AsRef<Path>,
+    {
+        crate::io::save_to_json(path, model)
+    }
+
+    /// Load a model previously saved via `App::save_model` from the JSON file at the given path.
+    ///
+    /// Fields skipped via `#[serde(skip)]` are loaded with their `Default` values.
+    pub fn load_model<M, P>(&self, path: P) -> Result<M, crate::io::JsonFileError>
+    where
+        M: for<'de> serde::Deserialize<'de>,
+        P: AsRef<Path>,
+    {
+        crate::io::load_from_json(path)
+    }
+
+    /// The config loaded via `Builder::with_config`.
+    ///
+    /// Returns `None` if no config was loaded or if it is not of type `C`.
+    pub fn config<C: 'static>(&self) -> Option<&C> {
+        self.user_config.as_ref()?.downcast_ref()
+    }
+
+    /// The function used to select the adapter for windows, if any.
+    pub fn adapter_fn(&self) -> Option<AdapterFn> {
+        self.config.borrow().adapter
+    }
+
+    /// Information about the GPU used by the focused window, including its name, backend,
+    /// features and limits.
+    ///
+    /// Returns `None` if there is no window in focus.
+    pub fn gpu_info(&self) -> Option<window::GpuInfo> {
+        let id = (*self.focused_window.borrow())?;
+        self.window(id).map(|window| window.gpu_info())
+    }
+
+    /// The device descriptor used when requesting the logical device for windows that do not
+    /// specify their own.
+    pub fn device_descriptor(&self) -> wgpu::DeviceDescriptor<'static> {
+        self.config.borrow().device_desc.clone()
+    }
+
     /// Return the main wgpu `Instance` in use.
     ///
     /// This must be passed into the various methods on `AdapterMap`.
@@ -891,6 +1552,242 @@ impl App {
         self.config.borrow_mut().loop_mode = mode;
     }
 
+    /// The rate at which app time advances relative to wall-clock time.
+    ///
+    /// By default this is `1.0`.
+    pub fn time_scale(&self) -> f64 {
+        self.config.borrow().time_scale
+    }
+
+    /// Specify the rate at which app time advances relative to wall-clock time.
+    ///
+    /// E.g. `0.5` runs animations at half speed, `2.0` at double speed. This affects
+    /// `app.duration`, `app.time` and the durations delivered via `Update` events. Negative values
+    /// are treated as `0.0`.
+    pub fn set_time_scale(&self, scale: f64) {
+        self.config.borrow_mut().time_scale = scale.max(0.0);
+    }
+
+    /// Whether or not app time is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.config.borrow().paused
+    }
+
+    /// Pause app time.
+    ///
+    /// While paused, the `update` function is not called and `Update` events are not delivered,
+    /// however windows continue to be redrawn and input events continue to be delivered. This is
+    /// useful for inspecting and single-stepping animation logic via `App::step`.
+    pub fn pause(&self) {
+        self.config.borrow_mut().paused = true;
+    }
+
+    /// Resume app time after a call to `App::pause`.
+    pub fn resume(&self) {
+        let mut config = self.config.borrow_mut();
+        config.paused = false;
+        config.pending_steps = 0;
+    }
+
+    /// Pause or resume app time.
+    pub fn set_paused(&self, paused: bool) {
+        if paused {
+            self.pause();
+        } else {
+            self.resume();
+        }
+    }
+
+    /// Advance a paused app by a single update.
+    ///
+    /// The update advances app time by the wall-clock duration of the frame multiplied by the
+    /// time scale. Has no effect if the app is not paused.
+    pub fn step(&self) {
+        let mut config = self.config.borrow_mut();
+        if config.paused {
+            config.pending_steps = config.pending_steps.saturating_add(1);
+        }
+    }
+
+    /// Accumulate the frames of all windows over time, e.g. for long-exposure, motion blur and
+    /// trail effects.
+    ///
+    /// Each frame is blended with the accumulated image of all previous frames on the GPU before
+    /// it is presented, where `alpha` is the weight of the new frame in the range `0.0..=1.0`. The
+    /// lower the `alpha`, the longer previous frames linger. An `alpha` of `1.0` presents each
+    /// frame as is.
+    ///
+    /// The accumulated image is restarted when a window is resized.
+    ///
+    /// **Note:** This has no effect for windows that use a `raw_view` function.
+    pub fn accumulate(&self, alpha: f32) {
+        self.config.borrow_mut().accumulation = Some(alpha.clamp(0.0, 1.0));
+    }
+
+    /// Stop accumulating frames after a call to `App::accumulate`.
+    pub fn stop_accumulating(&self) {
+        self.config.borrow_mut().accumulation = None;
+    }
+
+    /// The weight of each new frame if accumulating frames via `App::accumulate`.
+    pub fn accumulation(&self) -> Option<f32> {
+        self.config.borrow().accumulation
+    }
+
+    /// Whether the app is currently in its editor or performance mode.
+    ///
+    /// By default this is `performance::Mode::Editor`.
+    pub fn performance_mode(&self) -> performance::Mode {
+        self.config.borrow().performance_mode
+    }
+
+    /// Switch the app between its editor and performance modes.
+    ///
+    /// Windows built with a `performance_role` are fullscreened, hidden or restored accordingly.
+    /// See the `performance` module for details.
+    pub fn set_performance_mode(&self, mode: performance::Mode) {
+        self.config.borrow_mut().performance_mode = mode;
+        performance::apply(self, mode);
+    }
+
+    /// Switch to performance mode if in editor mode and vice versa.
+    pub fn toggle_performance_mode(&self) {
+        self.set_performance_mode(self.performance_mode().toggled());
+    }
+
+    /// The key that toggles the app between the editor and performance modes, if any.
+    pub fn performance_key(&self) -> Option<Key> {
+        self.config.borrow().performance_key
+    }
+
+    /// Specify the key that toggles the app between the editor and performance modes.
+    ///
+    /// By default this is `None`.
+    pub fn set_performance_key(&self, key: Option<Key>) {
+        self.config.borrow_mut().performance_key = key;
+    }
+
+    /// Whether or not the statistics overlay is shown.
+    ///
+    /// By default this is `false`.
+    pub fn stats_shown(&self) -> bool {
+        self.config.borrow().show_stats
+    }
+
+    /// Show or hide the statistics overlay, describing the frame rate, the time spent in each
+    /// stage of the frame and the statistics of the draw renderer.
+    ///
+    /// The overlay is drawn over each window by `Draw::to_frame`. See the `stats` module for
+    /// details.
+    pub fn show_stats(&self, show: bool) {
+        self.config.borrow_mut().show_stats = show;
+    }
+
+    /// Show the statistics overlay if hidden and vice versa.
+    pub fn toggle_stats(&self) {
+        self.show_stats(!self.stats_shown());
+    }
+
+    /// The key that toggles the statistics overlay, if any.
+    pub fn stats_key(&self) -> Option<Key> {
+        self.config.borrow().stats_key
+    }
+
+    /// Specify the key that toggles the statistics overlay.
+    ///
+    /// By default this is `None`.
+    pub fn set_stats_key(&self, key: Option<Key>) {
+        self.config.borrow_mut().stats_key = key;
+    }
+
+    /// The CPU time spent within the most recent `update` and `view` functions.
+    pub fn cpu_timings(&self) -> stats::CpuTimings {
+        self.cpu_timings.get()
+    }
+
+    /// The display on which the output window is presented in performance mode, if specified.
+    pub fn performance_display(&self) -> Option<display::Id> {
+        self.config.borrow().performance_display
+    }
+
+    /// Specify the display on which the output window is presented in performance mode.
+    ///
+    /// By default this is `None`, in which case the output window is fullscreened on the display
+    /// that it currently occupies. Takes effect upon the next switch to performance mode.
+    pub fn set_performance_display(&self, display: Option<display::Id>) {
+        self.config.borrow_mut().performance_display = display;
+    }
+
+    /// The **Id** of the window built with the `performance::Role::Output` role, if any.
+    ///
+    /// This is the window to capture from when recording a performance, as its frames contain
+    /// only the clean output of the sketch in both modes.
+    pub fn output_window(&self) -> Option<window::Id> {
+        self.windows
+            .borrow()
+            .iter()
+            .find(|(_, window)| window.performance_role() == Some(performance::Role::Output))
+            .map(|(&id, _)| id)
+    }
+
+    /// The touches currently in contact with a touch screen or tablet, along with the pinch,
+    /// rotate and pan gestures they describe.
+    pub fn touches(&self) -> &state::Touches {
+        &self.touches
+    }
+
+    /// Whether or not the first touch in contact drives the state of `App::mouse`.
+    ///
+    /// See `Builder::touch_emulates_mouse`.
+    pub fn touch_emulates_mouse(&self) -> bool {
+        self.config.borrow().touch_emulates_mouse
+    }
+
+    /// Specify whether or not the first touch in contact drives the state of `App::mouse`.
+    pub fn set_touch_emulates_mouse(&self, emulate: bool) {
+        self.config.borrow_mut().touch_emulates_mouse = emulate;
+    }
+
+    /// Whether or not the app is currently suspended, e.g. while an Android or iOS app is in the
+    /// background.
+    ///
+    /// While suspended, windows are not redrawn. Their surfaces are recreated upon resuming.
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.get()
+    }
+
+    /// The state of the pen, for sketches that respond to pressure-sensitive stylus input.
+    ///
+    /// See `event::PenEvent` for the platforms on which pen input is supported.
+    pub fn pen(&self) -> &state::Pen {
+        &self.pen
+    }
+
+    /// The state of all connected gamepads.
+    ///
+    /// Requires the `gamepad` feature. See the `gamepad` module for details.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepads(&self) -> &gamepad::Gamepads {
+        &self.gamepads
+    }
+
+    /// The deadzone applied to gamepad sticks and axes.
+    ///
+    /// By default this is `0.1`.
+    #[cfg(feature = "gamepad")]
+    pub fn gamepad_deadzone(&self) -> f32 {
+        self.config.borrow().gamepad_deadzone
+    }
+
+    /// Specify the deadzone applied to gamepad sticks and axes, within the range `0.0..1.0`.
+    ///
+    /// Axis values with a magnitude within the deadzone are reported as `0.0`, avoiding drift
+    /// from sticks that do not rest precisely at their centre. Takes effect from the next update.
+    #[cfg(feature = "gamepad")]
+    pub fn set_gamepad_deadzone(&self, deadzone: f32) {
+        self.config.borrow_mut().gamepad_deadzone = deadzone.clamp(0.0, 0.99);
+    }
+
     /// A handle to the **App** that can be shared across threads.
     ///
     /// This can be used to "wake up" the **App**'s inner event loop.
@@ -898,6 +1795,31 @@ impl App {
         self.event_loop_proxy.clone()
     }
 
+    /// Access the system clipboard for copying and pasting text and images.
+    ///
+    /// See the `clipboard` module for details.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn clipboard(&self) -> clipboard::Clipboard<'_> {
+        clipboard::Clipboard::new(&self.clipboard)
+    }
+
+    /// Begin building a contact sheet that renders the sketch once per seed at thumbnail size and
+    /// composes the results into a labelled grid image, saved to the given path.
+    ///
+    /// See the `contact_sheet` module for details.
+    pub fn contact_sheet<'a, P>(
+        &'a self,
+        seeds: &'a [u64],
+        cols: u32,
+        rows: u32,
+        path: P,
+    ) -> contact_sheet::Builder<'a>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        contact_sheet::Builder::new(self, seeds, cols, rows, path)
+    }
+
     /// Produce the **App**'s **Draw** API for drawing geometry and text with colors and textures.
     ///
     /// **Note:** You can also create your own **Draw** instances via `Draw::new()`! This method
@@ -909,15 +1831,69 @@ impl App {
         draw.clone()
     }
 
+    /// Produce the **Draw** instance associated with the window with the given **Id**.
+    ///
+    /// Each window has its own **Draw** instance, allowing for the same model to be described
+    /// differently for each window without the windows sharing any draw state. The window's
+    /// camera is applied to the returned **Draw** (see `Window::set_camera`). Like `app.draw()`,
+    /// the state is reset on each call.
+    ///
+    /// This is typically called within `view` via `app.draw_for_window(frame.window_id())`.
+    pub fn draw_for_window(&self, id: window::Id) -> draw::Draw {
+        let camera = self.window(id).map(|window| window.camera());
+        let mut draws = self.draw_state.window_draws.borrow_mut();
+        let draw = draws.entry(id).or_default();
+        draw.reset();
+        match camera {
+            Some(camera) if camera != Mat4::IDENTITY => draw.transform(camera),
+            _ => draw.clone(),
+        }
+    }
+
+    /// The ID of the top-most primitive at the given point within the focused window, among those
+    /// assigned an ID via `Drawing::pick_id`.
+    ///
+    /// The point is in the coordinate space of the window, e.g. `app.mouse.position()`. The
+    /// primitives of the window's most recently rendered frame are tested, whether drawn via
+    /// `app.draw_for_window` or otherwise via `app.draw`. See `Draw::pick` for details.
+    pub fn pick(&self, point: geom::Point2) -> Option<u64> {
+        let focused = *self.focused_window.borrow();
+        if let Some(id) = focused {
+            if let Some(draw) = self.draw_state.window_draws.borrow().get(&id) {
+                return draw.pick(point);
+            }
+        }
+        self.draw_state.draw.borrow().pick(point)
+    }
+
     /// The number of times the focused window's **view** function has been called since the start
     /// of the program.
     pub fn elapsed_frames(&self) -> u64 {
         self.main_window().frame_count
     }
 
+    /// The app time since the `App` started running.
+    ///
+    /// This is affected by the time scale and does not advance while the app is paused.
+    ///
+    /// Equivalent to `app.duration.since_start`.
+    pub fn duration_since_start(&self) -> Duration {
+        self.duration.since_start
+    }
+
+    /// The app time in seconds that passed during the most recent update.
+    ///
+    /// This is affected by the time scale and is useful for frame-rate independent animation,
+    /// e.g. `position += velocity * app.delta_seconds()`.
+    pub fn delta_seconds(&self) -> f32 {
+        self.duration.since_prev_update.secs() as _
+    }
+
     /// The number of frames that can currently be displayed a second
+    ///
+    /// This is measured in wall-clock time and is unaffected by the time scale or pausing.
     pub fn fps(&self) -> f32 {
-        self.duration.updates_per_second()
+        self.real_duration.updates_per_second()
     }
 
     /// The name of the nannou executable that is currently running.
@@ -934,6 +1910,51 @@ impl App {
     pub fn quit(&self) {
         self.windows.borrow_mut().clear();
     }
+
+    /// Exit the application successfully once the current event has been processed.
+    ///
+    /// Unlike `quit`, windows remain open until the exit function has been called, allowing it to
+    /// access them, and any pending frame captures are completed first.
+    pub fn exit(&self) {
+        self.exit_with_code(0);
+    }
+
+    /// Exit the application with the given process exit code once the current event has been
+    /// processed.
+    ///
+    /// This is useful for signalling success or failure to a script or CI job, e.g. when
+    /// comparing rendered output against a reference. See `exit` for details.
+    pub fn exit_with_code(&self, code: i32) {
+        self.exit_code.set(Some(code));
+    }
+
+    /// The exit code requested via `exit` or `exit_with_code`, if any.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code.get()
+    }
+
+    // Whether an exit was requested or the run mode's limit has been reached.
+    //
+    // `since_start` is the wall-clock time since the loop started, as `real_duration` is only
+    // updated on updates which do not occur while waiting for events.
+    fn should_exit(&self, since_start: Duration) -> bool {
+        if self.exit_code.get().is_some() {
+            return true;
+        }
+        let config = self.config.borrow();
+        if let Some(duration) = config.exit_after {
+            if since_start >= duration {
+                return true;
+            }
+        }
+        if let Some(frames) = config.exit_after_frames {
+            let windows = self.windows.borrow();
+            if !windows.is_empty() && windows.values().all(|w| w.frame_count >= frames) {
+                return true;
+            }
+        }
+        false
+    }
 }
 
 impl Proxy {
@@ -975,7 +1996,9 @@ impl draw::Draw {
                 let frame_dims: [u32; 2] = window.tracked_state.physical_size.into();
                 let scale_factor = window.tracked_state.scale_factor as f32;
                 let msaa_samples = window.msaa_samples();
-                let target_format = crate::frame::Frame::TEXTURE_FORMAT;
+                let target_format = window
+                    .frame_texture_format()
+                    .unwrap_or(crate::frame::Frame::TEXTURE_FORMAT);
                 let renderer = draw::RendererBuilder::new().build(
                     device,
                     frame_dims,
@@ -990,6 +2013,34 @@ impl draw::Draw {
         let scale_factor = window.tracked_state.scale_factor as _;
         let mut renderer = renderer.borrow_mut();
         renderer.render_to_frame(window.device(), self, scale_factor, frame);
+
+        // Cover the areas outside of the letterbox with bars.
+        if window.letterbox().is_some() {
+            let rect = window.rect();
+            let visible = window.letterbox_rect();
+            let bars = draw::Draw::new();
+            let bar_rects = [
+                geom::Rect::from_corners(rect.bottom_left(), visible.top_left()),
+                geom::Rect::from_corners(visible.bottom_right(), rect.top_right()),
+                geom::Rect::from_corners(rect.top_left(), visible.top_right()),
+                geom::Rect::from_corners(visible.bottom_left(), rect.bottom_right()),
+            ];
+            for bar in bar_rects.iter().filter(|r| r.w() > 0.0 && r.h() > 0.0) {
+                bars.rect()
+                    .xy(bar.xy())
+                    .wh(bar.wh())
+                    .color(crate::color::BLACK);
+            }
+            renderer.render_overlay_to_frame(window.device(), &bars, scale_factor, frame);
+        }
+
+        // Draw the statistics overlay above everything else.
+        if app.stats_shown() {
+            let lines = stats::lines(app.fps(), &app.cpu_timings(), renderer.stats());
+            let overlay = draw::Draw::new();
+            stats::draw_overlay(&overlay, window.rect(), &lines);
+            renderer.render_overlay_to_frame(window.device(), &overlay, scale_factor, frame);
+        }
         Ok(())
     }
 }
@@ -1074,6 +2125,7 @@ impl EventLoopWindowTarget {
 //
 // If you would like to contribute but are unsure about any of the following, feel free to open an
 // issue and ask!
+#[allow(clippy::too_many_arguments)]
 fn run_loop<M, E>(
     mut app: App,
     model: M,
@@ -1081,6 +2133,8 @@ fn run_loop<M, E>(
     update_fn: Option<UpdateFn<M>>,
     default_view: Option<View<M>>,
     exit_fn: Option<ExitFn<M>>,
+    mut watchers: Vec<watch::Watcher<M>>,
+    persist_model: Option<PersistModel<M>>,
 ) where
     M: 'static,
     E: LoopEvent,
@@ -1118,9 +2172,13 @@ fn run_loop<M, E>(
         match event {
             // Check to see if we need to emit an update and request a redraw.
             winit::event::Event::MainEventsCleared => {
+                profile::new_frame();
                 if let Some(model) = model.as_mut() {
-                    let loop_mode = app.loop_mode();
+                    #[cfg(feature = "gamepad")]
+                    poll_gamepads(&mut app, model, event_fn, &mut loop_state);
                     let now = Instant::now();
+                    poll_watchers(&app, model, &mut watchers, &mut loop_state, now);
+                    let loop_mode = app.loop_mode();
                     let mut do_update = |loop_state: &mut LoopState| {
                         apply_update(&mut app, model, event_fn, update_fn, loop_state, now);
                     };
@@ -1145,6 +2203,9 @@ fn run_loop<M, E>(
             //
             // TODO: Only request a frame from the user if this redraw was requested following an
             // update. Otherwise, just use the existing intermediary frame.
+            // Windows have no surface to draw to while the app is suspended.
+            winit::event::Event::RedrawRequested(_) if app.is_suspended() => {}
+
             winit::event::Event::RedrawRequested(window_id) => {
                 if let Some(model) = model.as_mut() {
                     // Retrieve the surface frame and the number of this frame.
@@ -1216,6 +2277,7 @@ fn run_loop<M, E>(
                             surface_texture,
                             window.surface_conf.format,
                             window_rect,
+                            sf as f32,
                         );
 
                         // Clear the raw frame immediately once the window is invalidated
@@ -1224,11 +2286,36 @@ fn run_loop<M, E>(
                                 raw_frame.clear(&data.render.texture_view(), window.clear_color);
                             }
                         }
+                        if let Some(data) = frame_data {
+                            data.render.accumulation.set(app.accumulation());
+                        }
+
+                        // The frame's textures, provided to the window's render hooks.
+                        let textures = FrameTextures::new(
+                            window_id,
+                            nth_frame,
+                            window.device_queue_pair(),
+                            surface_texture,
+                            window.surface_conf.format,
+                            [window.surface_conf.width, window.surface_conf.height],
+                            frame_data.as_ref().map(|data| &data.render),
+                        );
+                        let call_hook = |hook: &Option<window::RenderHookFnAny>| {
+                            if let Some(hook) = hook {
+                                let hook = hook.to_fn_ptr::<M>().expect(
+                                    "unexpected model argument given to window render hook",
+                                );
+                                (*hook)(&app, model, &textures);
+                            }
+                        };
+                        call_hook(&window.user_functions.before_render);
 
                         // If the user specified a view function specifically for this window, use it.
                         // Otherwise, use the fallback, default view passed to the app if there was one.
                         let window_view = window.user_functions.view.clone();
 
+                        let view_start = Instant::now();
+                        let view_span = profile::view();
                         match window_view {
                             Some(window::View::Sketch(view)) => {
                                 let data = frame_data.as_ref().expect("missing `frame_data`");
@@ -1267,6 +2354,12 @@ fn run_loop<M, E>(
                                 None => raw_frame.submit(),
                             },
                         }
+                        drop(view_span);
+                        let mut cpu_timings = app.cpu_timings.get();
+                        cpu_timings.view = view_start.elapsed();
+                        app.cpu_timings.set(cpu_timings);
+
+                        call_hook(&window.user_functions.after_render);
 
                         // Queue has been submitted by now, time to present.
                         surface_tex.present();
@@ -1283,6 +2376,7 @@ fn run_loop<M, E>(
                         // Assume invalidated window was cleared above before `view()`
                         window.is_invalidated = false;
                         window.frame_count += 1;
+                        window.throttle_frame_latency();
                     }
                 }
             }
@@ -1308,13 +2402,27 @@ fn run_loop<M, E>(
             | winit::event::Event::Resumed => {
                 loop_state.updates_since_event = 0;
 
-                // `UserEvent` is emitted on `wakeup`.
-                if let winit::event::Event::UserEvent(_) = event {
-                    app.event_loop_proxy.wakeup_queued.store(false, atomic::Ordering::SeqCst);
+                match event {
+                    // `UserEvent` is emitted on `wakeup`.
+                    winit::event::Event::UserEvent(_) => {
+                        app.event_loop_proxy.wakeup_queued.store(false, atomic::Ordering::SeqCst);
+                    }
+                    // Mobile platforms destroy the native window's surface while suspended.
+                    winit::event::Event::Suspended => app.suspended.set(true),
+                    winit::event::Event::Resumed if app.suspended.replace(false) => {
+                        for window in app.windows.borrow_mut().values_mut() {
+                            window.recreate_surface(app.instance());
+                        }
+                    }
+                    _ => (),
                 }
             }
 
-            // Ignore `NewEvents`.
+            // The deadline of `run_for` is checked below along with every other event.
+            winit::event::Event::NewEvents(winit::event::StartCause::ResumeTimeReached {
+                ..
+            }) => {}
+            // Ignore other `NewEvents`.
             winit::event::Event::NewEvents(_)
             // `LoopDestroyed` is handled later in `process_and_emit_winit_event` so ignore it here.
             | winit::event::Event::LoopDestroyed => {}
@@ -1352,12 +2460,17 @@ fn run_loop<M, E>(
         // Process the event with the user's functions and see if we need to exit.
         if let Some(model) = model.as_mut() {
             exit |= process_and_emit_winit_event::<M, E>(&mut app, model, event_fn, &event);
+            exit |= app.should_exit(loop_state.loop_start.elapsed());
         }
 
         // Set the control flow based on the loop mode.
         let loop_mode = app.loop_mode();
         *control_flow = match loop_mode {
             LoopMode::Wait => ControlFlow::Wait,
+            // On the web, redraw requests are paced by `requestAnimationFrame`, each of which
+            // wakes the loop for the next update.
+            #[cfg(target_arch = "wasm32")]
+            LoopMode::RefreshSync => ControlFlow::Wait,
             LoopMode::NTimes { number_of_updates }
                 if loop_state.total_updates >= number_of_updates as u64 =>
             {
@@ -1366,15 +2479,35 @@ fn run_loop<M, E>(
             _ => ControlFlow::Poll,
         };
 
+        // Wake up at the deadline of `run_for` rather than waiting indefinitely for events.
+        if let Some(duration) = app.config.borrow().exit_after {
+            if *control_flow == ControlFlow::Wait {
+                *control_flow = ControlFlow::WaitUntil(loop_state.loop_start + duration);
+            }
+        }
+
         // If we need to exit, call the user's function and update control flow.
         if exit {
+            // Ensure captured frames are written before the process ends.
+            for window in app.windows.borrow().values() {
+                if window.await_capture_frame_jobs().is_err() {
+                    eprintln!("timed out while waiting for capture jobs to complete");
+                }
+            }
+
             if let Some(model) = model.take() {
+                if let Some(ref persist) = persist_model {
+                    persist.save(&model);
+                }
                 if let Some(exit_fn) = exit_fn {
                     exit_fn(&app, model);
                 }
             }
 
-            *control_flow = ControlFlow::Exit;
+            *control_flow = match app.exit_code.get() {
+                Some(code) if code != 0 => ControlFlow::ExitWithCode(code),
+                _ => ControlFlow::Exit,
+            };
             return;
         }
     });
@@ -1388,6 +2521,48 @@ fn run_loop<M, E>(
     }
 }
 
+// Process pending gamepad events, delivering them to the user's event function.
+#[cfg(feature = "gamepad")]
+fn poll_gamepads<M, E>(
+    app: &mut App,
+    model: &mut M,
+    event_fn: Option<EventFn<M, E>>,
+    loop_state: &mut LoopState,
+) where
+    M: 'static,
+    E: LoopEvent,
+{
+    let deadzone = app.gamepad_deadzone();
+    let events = app.gamepads.poll(deadzone);
+    if events.is_empty() {
+        return;
+    }
+    loop_state.updates_since_event = 0;
+    if let Some(event_fn) = event_fn {
+        for (id, event) in events {
+            if let Some(event) = E::from_gamepad_event(id, event) {
+                event_fn(app, model, event);
+            }
+        }
+    }
+}
+
+// Reload any watched files that were modified, treating each reload as an event so that
+// `LoopMode::Wait` updates in response.
+fn poll_watchers<M>(
+    app: &App,
+    model: &mut M,
+    watchers: &mut [watch::Watcher<M>],
+    loop_state: &mut LoopState,
+    now: Instant,
+) {
+    for watcher in watchers {
+        if watcher.poll(app, model, now) {
+            loop_state.updates_since_event = 0;
+        }
+    }
+}
+
 // Apply an update to the model via the user's function and update the app and loop state
 // accordingly.
 fn apply_update<M, E>(
@@ -1401,9 +2576,30 @@ fn apply_update<M, E>(
     M: 'static,
     E: LoopEvent,
 {
-    // Update the app's durations.
-    let since_last = now.duration_since(loop_state.last_update);
-    let since_start = now.duration_since(loop_state.loop_start);
+    // Update the app's wall-clock durations.
+    let real_since_last = now.duration_since(loop_state.last_update);
+    app.real_duration.since_prev_update = real_since_last;
+    app.real_duration.since_start = now.duration_since(loop_state.loop_start);
+    loop_state.last_update = now;
+
+    // Skip the update while paused, unless a step was requested.
+    let time_scale = {
+        let mut config = app.config.borrow_mut();
+        if config.paused {
+            if config.pending_steps == 0 {
+                drop(config);
+                loop_state.updates_since_event += 1;
+                request_redraw(app);
+                return;
+            }
+            config.pending_steps -= 1;
+        }
+        config.time_scale
+    };
+
+    // Update the app's scaled durations.
+    let since_last = real_since_last.mul_f64(time_scale);
+    let since_start = app.duration.since_start + since_last;
     app.duration.since_prev_update = since_last;
     app.duration.since_start = since_start;
     app.time = since_start.secs() as _;
@@ -1411,6 +2607,8 @@ fn apply_update<M, E>(
         since_start,
         since_last,
     };
+    let update_start = Instant::now();
+    let update_span = profile::update();
     // User event function.
     if let Some(event_fn) = event_fn {
         let event = E::from(update.clone());
@@ -1420,16 +2618,54 @@ fn apply_update<M, E>(
     if let Some(update_fn) = update_fn {
         update_fn(app, model, update);
     }
-    loop_state.last_update = now;
+    drop(update_span);
+    let mut cpu_timings = app.cpu_timings.get();
+    cpu_timings.update = update_start.elapsed();
+    app.cpu_timings.set(cpu_timings);
     loop_state.total_updates += 1;
     loop_state.updates_since_event += 1;
-    // Request redraw from windows.
+    request_redraw(app);
+}
+
+// Request redraw from windows.
+fn request_redraw(app: &App) {
     let windows = app.windows.borrow();
     for window in windows.values() {
         window.window.request_redraw();
     }
 }
 
+// Clamp the positions of mouse and touch events to the letterboxed area of the window.
+fn clamp_to_letterbox(
+    app: &App,
+    window_id: window::Id,
+    event: event::WindowEvent,
+) -> event::WindowEvent {
+    let window = match app.window(window_id) {
+        Some(window) if window.letterbox().is_some() => window,
+        _ => return event,
+    };
+    match event {
+        event::WindowEvent::MouseMoved(p) => {
+            event::WindowEvent::MouseMoved(window.clamp_to_letterbox(p))
+        }
+        event::WindowEvent::Touch(mut touch) => {
+            touch.position = window.clamp_to_letterbox(touch.position);
+            event::WindowEvent::Touch(touch)
+        }
+        event => event,
+    }
+}
+
+// The last known position of the mouse within the given window, used as the position of hovered
+// and dropped files. Falls back to the centre of the window if the mouse was last seen elsewhere.
+fn file_drop_position(app: &App, window_id: window::Id) -> geom::Point2 {
+    match app.mouse.window {
+        Some(id) if id == window_id => app.mouse.position(),
+        _ => geom::Point2::ZERO,
+    }
+}
+
 // Whether or not the given event should toggle fullscreen.
 fn should_toggle_fullscreen(
     winit_event: &winit::event::WindowEvent,
@@ -1503,13 +2739,45 @@ where
             }
         }
 
+        // Check for the key that toggles between the editor and performance modes, ignoring
+        // repeats while the key is held.
+        if let Some(key) = app.performance_key() {
+            if let winit::event::WindowEvent::KeyboardInput { input, .. } = *event {
+                if input.state == winit::event::ElementState::Pressed
+                    && input.virtual_keycode == Some(key)
+                    && !app.keys.down.contains(&key)
+                {
+                    app.toggle_performance_mode();
+                }
+            }
+        }
+
+        // Check for the key that toggles the stats overlay, ignoring repeats.
+        if let Some(key) = app.stats_key() {
+            if let winit::event::WindowEvent::KeyboardInput { input, .. } = *event {
+                if input.state == winit::event::ElementState::Pressed
+                    && input.virtual_keycode == Some(key)
+                    && !app.keys.down.contains(&key)
+                {
+                    app.toggle_stats();
+                }
+            }
+        }
+
         // When a window has been closed, this function is called to remove any state associated
         // with that window so that the state doesn't leak.
         //
         // Returns the `Window` that was removed.
         fn remove_related_window_state(app: &App, window_id: &window::Id) -> Option<Window> {
             app.draw_state.renderers.borrow_mut().remove(window_id);
-            app.windows.borrow_mut().remove(window_id)
+            app.draw_state.window_draws.borrow_mut().remove(window_id);
+            let window = app.windows.borrow_mut().remove(window_id);
+            // If the focused window was removed, fall back to one of the remaining windows.
+            let mut focused_window = app.focused_window.borrow_mut();
+            if focused_window.as_ref() == Some(window_id) {
+                *focused_window = app.windows.borrow().keys().next().cloned();
+            }
+            window
         }
 
         if let winit::event::WindowEvent::Destroyed = *event {
@@ -1556,13 +2824,59 @@ where
             match *event {
                 winit::event::WindowEvent::CursorMoved { position, .. } => {
                     let (x, y) = position.to_logical::<f32>(scale_factor).into();
-                    let x = tx(x);
-                    let y = ty(y);
-                    app.mouse.x = x;
-                    app.mouse.y = y;
+                    let mut p = geom::pt2(tx(x), ty(y));
+                    if let Some(win) = app.window(window_id) {
+                        p = win.clamp_to_letterbox(p);
+                    }
+                    app.mouse.x = p.x;
+                    app.mouse.y = p.y;
                     app.mouse.window = Some(window_id);
                 }
 
+                winit::event::WindowEvent::Touch(touch) => {
+                    let (x, y) = touch.location.to_logical::<f32>(scale_factor).into();
+                    let mut position = geom::pt2(tx(x), ty(y));
+                    if let Some(win) = app.window(window_id) {
+                        position = win.clamp_to_letterbox(position);
+                    }
+                    let pressure = touch.force.map(|force| force.normalized() as f32);
+                    if let Some(pressure) = pressure {
+                        let tilt = match touch.force {
+                            Some(winit::event::Force::Calibrated { altitude_angle, .. }) => {
+                                altitude_angle.map(|angle| angle as f32)
+                            }
+                            _ => None,
+                        };
+                        let (id, phase) = (touch.id, touch.phase);
+                        app.pen
+                            .update(window_id, id, phase, position, pressure, tilt);
+                    }
+                    let touch = event::TouchEvent {
+                        id: touch.id,
+                        phase: touch.phase,
+                        position,
+                        pressure,
+                    };
+                    // The primary touch ends with this event, so check for it before updating.
+                    let primary = app.touches.primary().map(|t| t.id);
+                    app.touches.update(window_id, &touch);
+                    let primary = primary.or_else(|| app.touches.primary().map(|t| t.id));
+                    if primary == Some(touch.id) && app.touch_emulates_mouse() {
+                        app.mouse.x = position.x;
+                        app.mouse.y = position.y;
+                        app.mouse.window = Some(window_id);
+                        match touch.phase {
+                            event::TouchPhase::Started => {
+                                app.mouse.buttons.press(event::MouseButton::Left, position);
+                            }
+                            event::TouchPhase::Ended | event::TouchPhase::Cancelled => {
+                                app.mouse.buttons.release(event::MouseButton::Left);
+                            }
+                            event::TouchPhase::Moved => (),
+                        }
+                    }
+                }
+
                 winit::event::WindowEvent::MouseInput { state, button, .. } => {
                     match state {
                         event::ElementState::Pressed => {
@@ -1573,6 +2887,10 @@ where
                             app.mouse.buttons.release(button);
                         }
                     }
+                    // Tablet drivers report barrel button presses as right mouse button presses.
+                    if button == event::MouseButton::Right && app.pen.is_down {
+                        app.pen.barrel_button = state == event::ElementState::Pressed;
+                    }
                     app.mouse.window = Some(window_id);
                 }
 
@@ -1650,6 +2968,7 @@ where
         // functions to be called.
         if let Some(simple) =
             event::WindowEvent::from_winit_window_event(event, win_w, win_h, scale_factor)
+                .map(|simple| clamp_to_letterbox(app, window_id, simple))
         {
             // Nannou window events.
             if let Some(window_event_fn) = {
@@ -1717,15 +3036,30 @@ where
                 }
                 event::WindowEvent::Moved(pos) => call_user_function!(moved, pos),
                 event::WindowEvent::Resized(size) => call_user_function!(resized, size),
-                event::WindowEvent::Touch(touch) => call_user_function!(touch, touch),
+                event::WindowEvent::Touch(touch) => {
+                    call_user_function!(touch, touch);
+                    if touch.pressure.is_some() {
+                        if let Some(pen) = app.pen.event() {
+                            call_user_function!(pen, pen);
+                        }
+                    }
+                }
                 event::WindowEvent::TouchPressure(pressure) => {
                     call_user_function!(touchpad_pressure, pressure)
                 }
-                event::WindowEvent::HoveredFile(path) => call_user_function!(hovered_file, path),
+                event::WindowEvent::HoveredFile(path) => {
+                    let position = file_drop_position(app, window_id);
+                    call_user_function!(hovered_file, path.clone());
+                    call_user_function!(hovered_file_at, path, position)
+                }
                 event::WindowEvent::HoveredFileCancelled => {
                     call_user_function!(hovered_file_cancelled)
                 }
-                event::WindowEvent::DroppedFile(path) => call_user_function!(dropped_file, path),
+                event::WindowEvent::DroppedFile(path) => {
+                    let position = file_drop_position(app, window_id);
+                    call_user_function!(dropped_file, path.clone());
+                    call_user_function!(dropped_file_at, path, position)
+                }
                 event::WindowEvent::Focused => call_user_function!(focused),
                 event::WindowEvent::Unfocused => call_user_function!(unfocused),
                 event::WindowEvent::Closed => call_user_function!(closed),
@@ -1748,3 +3082,55 @@ where
 
     exit
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Builder, PersistModel};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Model {
+        seed: u64,
+        points: Vec<[f32; 2]>,
+        #[serde(skip)]
+        window: Option<u32>,
+    }
+
+    fn persist_model(path: &std::path::Path) -> PersistModel<Model> {
+        let builder = Builder::new(|_| Model::default()).persist_model(path);
+        builder.persist_model.expect("no persist model")
+    }
+
+    #[test]
+    fn persist_model_round_trip() {
+        let dir = std::env::temp_dir().join(format!("nannou_app_tests_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let persist = persist_model(&dir.join("model.json"));
+        let created = || Model {
+            seed: 1,
+            points: vec![],
+            window: Some(1),
+        };
+
+        // Without a save, the model returned by the model function is kept.
+        assert_eq!(persist.restore(created()), created());
+
+        let saved = Model {
+            seed: 42,
+            points: vec![[1.0, 2.0], [3.0, 4.0]],
+            window: Some(7),
+        };
+        persist.save(&saved);
+
+        // The restored model replaces the created one entirely, including skipped fields.
+        let restored = persist.restore(created());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            restored,
+            Model {
+                window: None,
+                ..saved
+            }
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Items related to loading and analysing audio files for visualisation.
+//!
+//! An **AudioBuffer** holds the decoded samples of a sound file. Load one via `app.load_audio`
+//! or `AudioBuffer::load` and display it via `draw.waveform(&buffer, rect)` or
+//! `draw.spectrogram(&buffer, rect)`.
+//!
+//! Currently WAV files containing 8, 16, 24 or 32-bit integer or 32 or 64-bit float samples are
+//! supported. Note that this module is for visualisation rather than playback. See the
+//! `nannou_audio` crate for audio I/O.
+
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{fmt, fs, io};
+
+/// The decoded samples of a sound file, stored per channel.
+///
+/// Cloning an **AudioBuffer** is cheap as the samples are shared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioBuffer {
+    sample_rate: u32,
+    channels: Arc<Vec<Vec<f32>>>,
+}
+
+/// Errors that might occur while loading an **AudioBuffer**.
+#[derive(Debug)]
+pub enum LoadError {
+    /// Failed to read the file.
+    Io(io::Error),
+    /// The file is not a valid WAV file.
+    InvalidWav(&'static str),
+    /// The WAV file uses a sample format that is not supported.
+    UnsupportedFormat {
+        /// The WAV format tag.
+        format: u16,
+        /// The number of bits per sample.
+        bits_per_sample: u16,
+    },
+}
+
+// WAV format tags.
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+impl AudioBuffer {
+    /// Create a buffer from the samples of each channel.
+    ///
+    /// Channels are truncated to the length of the shortest channel.
+    pub fn from_channels(sample_rate: u32, mut channels: Vec<Vec<f32>>) -> Self {
+        let len = channels.iter().map(Vec::len).min().unwrap_or(0);
+        for channel in &mut channels {
+            channel.truncate(len);
+        }
+        AudioBuffer {
+            sample_rate,
+            channels: Arc::new(channels),
+        }
+    }
+
+    /// Create a buffer from interleaved samples with the given number of channels.
+    ///
+    /// Any trailing samples that do not make up a whole frame are ignored.
+    pub fn from_interleaved(sample_rate: u32, channel_count: usize, samples: &[f32]) -> Self {
+        let channel_count = channel_count.max(1);
+        let channels = (0..channel_count)
+            .map(|ch| {
+                samples
+                    .chunks_exact(channel_count)
+                    .map(|frame| frame[ch])
+                    .collect()
+            })
+            .collect();
+        Self::from_channels(sample_rate, channels)
+    }
+
+    /// Load and decode the sound file at the given path.
+    pub fn load<P>(path: P) -> Result<Self, LoadError>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = fs::read(path)?;
+        Self::from_wav_bytes(&bytes)
+    }
+
+    /// Decode a buffer from the bytes of a WAV file.
+    pub fn from_wav_bytes(bytes: &[u8]) -> Result<Self, LoadError> {
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(LoadError::InvalidWav("missing RIFF WAVE header"));
+        }
+
+        // Find the format and data chunks.
+        let mut fmt = None;
+        let mut data = None;
+        let mut rest = &bytes[12..];
+        while rest.len() >= 8 {
+            let id = &rest[0..4];
+            let len = u32_le(&rest[4..8]) as usize;
+            let body = &rest[8..];
+            let chunk = &body[..len.min(body.len())];
+            match id {
+                b"fmt " => fmt = Some(chunk),
+                b"data" => data = Some(chunk),
+                _ => (),
+            }
+            // Chunks are padded to an even number of bytes.
+            let padded = len + len % 2;
+            rest = &body[padded.min(body.len())..];
+        }
+        let fmt = fmt.ok_or(LoadError::InvalidWav("missing fmt chunk"))?;
+        let data = data.ok_or(LoadError::InvalidWav("missing data chunk"))?;
+        if fmt.len() < 16 {
+            return Err(LoadError::InvalidWav("fmt chunk is too short"));
+        }
+
+        let mut format = u16_le(&fmt[0..2]);
+        let channel_count = u16_le(&fmt[2..4]) as usize;
+        let sample_rate = u32_le(&fmt[4..8]);
+        let bits_per_sample = u16_le(&fmt[14..16]);
+        if format == FORMAT_EXTENSIBLE {
+            // The actual format is stored in the first two bytes of the sub-format GUID.
+            if fmt.len() < 26 {
+                return Err(LoadError::InvalidWav("extensible fmt chunk is too short"));
+            }
+            format = u16_le(&fmt[24..26]);
+        }
+        if channel_count == 0 {
+            return Err(LoadError::InvalidWav("no channels"));
+        }
+
+        let unsupported = LoadError::UnsupportedFormat {
+            format,
+            bits_per_sample,
+        };
+        let decode: fn(&[u8]) -> f32 = match (format, bits_per_sample) {
+            (FORMAT_PCM, 8) => |b| (b[0] as f32 - 128.0) / 128.0,
+            (FORMAT_PCM, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32_768.0,
+            (FORMAT_PCM, 24) => {
+                |b| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0
+            }
+            (FORMAT_PCM, 32) => |b| i32_le(b) as f32 / 2_147_483_648.0,
+            (FORMAT_FLOAT, 32) => |b| f32::from_bits(u32_le(b)),
+            (FORMAT_FLOAT, 64) => |b| {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&b[..8]);
+                f64::from_le_bytes(bytes) as f32
+            },
+            _ => return Err(unsupported),
+        };
+        let sample_bytes = bits_per_sample as usize / 8;
+        let samples: Vec<f32> = data.chunks_exact(sample_bytes).map(decode).collect();
+        Ok(Self::from_interleaved(sample_rate, channel_count, &samples))
+    }
+
+    /// The number of frames per second.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of channels.
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// The number of frames, i.e. the number of samples within each channel.
+    pub fn len_frames(&self) -> usize {
+        self.channels.first().map(Vec::len).unwrap_or(0)
+    }
+
+    /// Whether or not the buffer contains no frames.
+    pub fn is_empty(&self) -> bool {
+        self.len_frames() == 0
+    }
+
+    /// The duration of the sound.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.len_frames() as f64 / self.sample_rate.max(1) as f64)
+    }
+
+    /// The samples of the channel at the given index.
+    pub fn channel(&self, index: usize) -> Option<&[f32]> {
+        self.channels.get(index).map(|ch| &ch[..])
+    }
+
+    /// The index of the frame at the given time in seconds, clamped to the length of the buffer.
+    pub fn frame_at(&self, secs: f32) -> usize {
+        let frame = (secs.max(0.0) as f64 * self.sample_rate as f64) as usize;
+        frame.min(self.len_frames())
+    }
+
+    /// The sample of the given frame, either from a single channel or averaged across all
+    /// channels if `channel` is `None`.
+    ///
+    /// Returns `0.0` if the frame or channel is out of range.
+    pub fn sample(&self, channel: Option<usize>, frame: usize) -> f32 {
+        match channel {
+            Some(ch) => self
+                .channels
+                .get(ch)
+                .and_then(|ch| ch.get(frame))
+                .cloned()
+                .unwrap_or(0.0),
+            None => {
+                let sum: f32 = self.channels.iter().filter_map(|ch| ch.get(frame)).sum();
+                sum / self.channel_count().max(1) as f32
+            }
+        }
+    }
+
+    /// Divide the given range of frames into `bins` and find the minimum and maximum sample
+    /// within each.
+    ///
+    /// This is useful for drawing waveforms. See `sample` for the meaning of `channel`.
+    pub fn peaks(
+        &self,
+        channel: Option<usize>,
+        frames: Range<usize>,
+        bins: usize,
+    ) -> Vec<[f32; 2]> {
+        let end = frames.end.min(self.len_frames());
+        let start = frames.start.min(end);
+        let len = end - start;
+        (0..bins)
+            .map(|i| {
+                let a = start + len * i / bins;
+                let b = (start + len * (i + 1) / bins).max(a + 1).min(end);
+                (a..b)
+                    .map(|frame| self.sample(channel, frame))
+                    .fold(None, |acc: Option<[f32; 2]>, s| match acc {
+                        None => Some([s, s]),
+                        Some([min, max]) => Some([min.min(s), max.max(s)]),
+                    })
+                    .unwrap_or([0.0, 0.0])
+            })
+            .collect()
+    }
+
+    /// The magnitude of each frequency within the window of `window_size` frames centred on the
+    /// given frame.
+    ///
+    /// The window size is rounded up to a power of two. The resulting `window_size / 2 + 1`
+    /// magnitudes are evenly spaced from 0Hz up to half the sample rate, and are normalised so
+    /// that a full-scale sine wave has a magnitude of roughly `1.0`. See `sample` for the meaning
+    /// of `channel`.
+    pub fn spectrum(&self, channel: Option<usize>, frame: usize, window_size: usize) -> Vec<f32> {
+        let n = window_size.max(2).next_power_of_two();
+        let start = frame as isize - (n / 2) as isize;
+        let mut re: Vec<f32> = (0..n)
+            .map(|i| {
+                let frame = start + i as isize;
+                if frame < 0 {
+                    return 0.0;
+                }
+                // A Hann window.
+                let w = 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / n as f32).cos();
+                self.sample(channel, frame as usize) * w
+            })
+            .collect();
+        let mut im = vec![0.0; n];
+        fft(&mut re, &mut im);
+        // The Hann window halves the amplitude and the spectrum is mirrored about n / 2.
+        let scale = 4.0 / n as f32;
+        (0..=n / 2)
+            .map(|i| (re[i] * re[i] + im[i] * im[i]).sqrt() * scale)
+            .collect()
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            LoadError::Io(ref e) => Some(e),
+            LoadError::InvalidWav(_) | LoadError::UnsupportedFormat { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref e) => fmt::Display::fmt(e, f),
+            LoadError::InvalidWav(msg) => write!(f, "invalid WAV file: {}", msg),
+            LoadError::UnsupportedFormat {
+                format,
+                bits_per_sample,
+            } => write!(
+                f,
+                "unsupported WAV sample format {} with {} bits per sample",
+                format, bits_per_sample
+            ),
+        }
+    }
+}
+
+fn u16_le(b: &[u8]) -> u16 {
+    u16::from_le_bytes([b[0], b[1]])
+}
+
+fn u32_le(b: &[u8]) -> u32 {
+    u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+fn i32_le(b: &[u8]) -> i32 {
+    i32::from_le_bytes([b[0], b[1], b[2], b[3]])
+}
+
+// An in-place iterative radix-2 FFT. The length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    // Reorder the input by bit-reversed index.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -std::f32::consts::TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
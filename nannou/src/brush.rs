@@ -0,0 +1,195 @@
+//! Items related to capturing strokes for the `draw.brush()` primitive.
+//!
+//! A **Stroke** records the position and pressure of a pointer (e.g. the mouse or a pen) as it
+//! moves, optionally smoothing out jitter with a **OneEuroFilter**. The recorded samples may then
+//! be rendered as a series of stamps via `draw.brush().points(&stroke)`.
+//!
+//! ```no_run
+//! use nannou::brush::{OneEuroFilter, Stroke};
+//! use nannou::prelude::*;
+//!
+//! fn update(app: &App, stroke: &mut Stroke) {
+//!     if app.mouse.buttons.left().is_down() {
+//!         let time = app.duration.since_start.secs();
+//!         stroke.push(app.mouse.position(), 1.0, time);
+//!     }
+//! }
+//!
+//! fn view(app: &App, stroke: &Stroke, frame: Frame) {
+//!     let draw = app.draw();
+//!     draw.background().color(WHITE);
+//!     draw.brush().points(stroke).size(12.0).color(BLACK);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! # fn main() {
+//! #     let _stroke = Stroke::new().with_smoothing(OneEuroFilter::default());
+//! # }
+//! ```
+
+use crate::geom::Point2;
+use std::f64::consts::PI;
+
+/// A single point along a stroke.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sample {
+    /// The position of the pointer.
+    pub position: Point2,
+    /// The pressure of the pointer within the range `0.0..=1.0`.
+    ///
+    /// Input devices without pressure sensitivity should use `1.0`.
+    pub pressure: f32,
+}
+
+/// A sequence of samples describing the path of a pointer, e.g. while the mouse is held down.
+#[derive(Clone, Debug, Default)]
+pub struct Stroke {
+    samples: Vec<Sample>,
+    smoothing: Option<OneEuroFilter>,
+}
+
+/// A "1€ filter" for smoothing noisy input while keeping latency low.
+///
+/// The filter reduces jitter when the pointer moves slowly, and reduces lag when the pointer moves
+/// quickly, by adapting its cutoff frequency to the speed of the pointer. See Casiez et al., "1€
+/// Filter: A Simple Speed-based Low-pass Filter for Noisy Input in Interactive Systems".
+#[derive(Clone, Debug)]
+pub struct OneEuroFilter {
+    /// The minimum cutoff frequency in hertz. Lower values reduce jitter at low speeds.
+    pub min_cutoff: f64,
+    /// The speed coefficient. Higher values reduce lag at high speeds.
+    pub beta: f64,
+    /// The cutoff frequency used to filter the speed of the pointer.
+    pub derivative_cutoff: f64,
+    // The previous filtered position, filtered velocity and time.
+    prev: Option<(Point2, Point2, f64)>,
+}
+
+impl Stroke {
+    /// An empty stroke without smoothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Smooth the positions of samples pushed to the stroke with the given filter.
+    pub fn with_smoothing(mut self, filter: OneEuroFilter) -> Self {
+        self.smoothing = Some(filter);
+        self
+    }
+
+    /// Append a sample to the stroke.
+    ///
+    /// `time` is the time of the sample in seconds, e.g. `app.duration.since_start.secs()`, and
+    /// is used by the smoothing filter (if any).
+    pub fn push(&mut self, position: Point2, pressure: f32, time: f64) {
+        let position = match self.smoothing {
+            Some(ref mut filter) => filter.filter(position, time),
+            None => position,
+        };
+        let pressure = pressure.clamp(0.0, 1.0);
+        self.samples.push(Sample { position, pressure });
+    }
+
+    /// The samples of the stroke in the order in which they were pushed.
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    /// The most recently pushed sample, if any.
+    pub fn last(&self) -> Option<&Sample> {
+        self.samples.last()
+    }
+
+    /// The number of samples in the stroke.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether or not the stroke contains no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Remove all samples and reset the smoothing filter, ready to begin a new stroke.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        if let Some(ref mut filter) = self.smoothing {
+            filter.reset();
+        }
+    }
+}
+
+impl OneEuroFilter {
+    /// A filter with the given minimum cutoff frequency and speed coefficient.
+    pub fn new(min_cutoff: f64, beta: f64) -> Self {
+        OneEuroFilter {
+            min_cutoff,
+            beta,
+            derivative_cutoff: 1.0,
+            prev: None,
+        }
+    }
+
+    /// Filter the given position sampled at the given time in seconds.
+    pub fn filter(&mut self, position: Point2, time: f64) -> Point2 {
+        let (prev_position, prev_velocity, prev_time) = match self.prev {
+            None => {
+                self.prev = Some((position, Point2::ZERO, time));
+                return position;
+            }
+            Some(prev) => prev,
+        };
+        let dt = time - prev_time;
+        if dt <= 0.0 {
+            return prev_position;
+        }
+        let velocity = (position - prev_position) / dt as f32;
+        let a = smoothing_factor(self.derivative_cutoff, dt);
+        let velocity = prev_velocity.lerp(velocity, a);
+        let cutoff = self.min_cutoff + self.beta * velocity.length() as f64;
+        let a = smoothing_factor(cutoff, dt);
+        let position = prev_position.lerp(position, a);
+        self.prev = Some((position, velocity, time));
+        position
+    }
+
+    /// Forget the previous samples, e.g. before beginning a new stroke.
+    pub fn reset(&mut self) {
+        self.prev = None;
+    }
+}
+
+impl Default for OneEuroFilter {
+    /// A filter suited to smoothing pointer positions in points.
+    fn default() -> Self {
+        OneEuroFilter::new(1.0, 0.01)
+    }
+}
+
+impl From<Point2> for Sample {
+    fn from(position: Point2) -> Self {
+        Sample {
+            position,
+            pressure: 1.0,
+        }
+    }
+}
+
+impl From<(Point2, f32)> for Sample {
+    fn from((position, pressure): (Point2, f32)) -> Self {
+        Sample { position, pressure }
+    }
+}
+
+impl<'a> IntoIterator for &'a Stroke {
+    type Item = Sample;
+    type IntoIter = std::iter::Cloned<std::slice::Iter<'a, Sample>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.samples.iter().cloned()
+    }
+}
+
+// The exponential smoothing factor for the given cutoff frequency and time step.
+fn smoothing_factor(cutoff: f64, dt: f64) -> f32 {
+    let tau = 1.0 / (2.0 * PI * cutoff);
+    (1.0 / (1.0 + tau / dt)) as f32
+}
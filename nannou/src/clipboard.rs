@@ -0,0 +1,124 @@
+//! Items related to reading from and writing to the system clipboard.
+//!
+//! Access the clipboard via `App::clipboard`.
+
+use crate::image;
+use std::cell::RefCell;
+use std::error::Error as StdError;
+use std::fmt;
+
+/// A handle to the system clipboard, retrieved via `App::clipboard`.
+///
+/// The connection to the system clipboard is opened upon first use and is kept open for the
+/// lifetime of the **App**. On some platforms (e.g. X11) the contents set by an application are
+/// only available while the application is running.
+#[derive(Clone, Copy)]
+pub struct Clipboard<'a> {
+    inner: &'a RefCell<Option<arboard::Clipboard>>,
+}
+
+/// Errors that might occur while accessing the clipboard.
+#[derive(Debug)]
+pub enum Error {
+    /// The clipboard does not contain content of the requested kind, e.g. requesting text while
+    /// the clipboard contains an image.
+    ContentNotAvailable,
+    /// The system clipboard could not be accessed.
+    Clipboard(arboard::Error),
+}
+
+impl<'a> Clipboard<'a> {
+    pub(crate) fn new(inner: &'a RefCell<Option<arboard::Clipboard>>) -> Self {
+        Clipboard { inner }
+    }
+
+    /// Retrieve the text currently stored on the clipboard.
+    pub fn get_text(&self) -> Result<String, Error> {
+        self.with(|clipboard| clipboard.get_text())
+    }
+
+    /// Replace the contents of the clipboard with the given text.
+    pub fn set_text<S>(&self, text: S) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        let text = text.into();
+        self.with(|clipboard| clipboard.set_text(text))
+    }
+
+    /// Retrieve the image currently stored on the clipboard.
+    pub fn get_image(&self) -> Result<image::RgbaImage, Error> {
+        let data = self.with(|clipboard| clipboard.get_image())?;
+        let (w, h) = (data.width as u32, data.height as u32);
+        let bytes = data.bytes.into_owned();
+        image::RgbaImage::from_raw(w, h, bytes).ok_or(Error::ContentNotAvailable)
+    }
+
+    /// Replace the contents of the clipboard with the given image.
+    ///
+    /// Other image types may first be converted via `DynamicImage::to_rgba8`.
+    pub fn set_image(&self, image: &image::RgbaImage) -> Result<(), Error> {
+        let data = arboard::ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: image.as_raw()[..].into(),
+        };
+        self.with(|clipboard| clipboard.set_image(data))
+    }
+
+    /// Clear the contents of the clipboard.
+    pub fn clear(&self) -> Result<(), Error> {
+        self.with(|clipboard| clipboard.clear())
+    }
+
+    // Access the system clipboard, opening the connection if necessary.
+    fn with<F, T>(&self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+    {
+        let mut inner = self.inner.borrow_mut();
+        let clipboard = match *inner {
+            Some(ref mut clipboard) => clipboard,
+            None => inner.get_or_insert(arboard::Clipboard::new()?),
+        };
+        f(clipboard).map_err(Error::from)
+    }
+}
+
+impl fmt::Debug for Clipboard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Clipboard").finish_non_exhaustive()
+    }
+}
+
+impl From<arboard::Error> for Error {
+    fn from(err: arboard::Error) -> Self {
+        match err {
+            arboard::Error::ContentNotAvailable => Error::ContentNotAvailable,
+            err => Error::Clipboard(err),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            Error::ContentNotAvailable => None,
+            Error::Clipboard(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::ContentNotAvailable => {
+                write!(
+                    f,
+                    "the clipboard does not contain content of the requested kind"
+                )
+            }
+            Error::Clipboard(ref err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! A **Gradient** type for interpolating between any number of colors.
+//!
+//! This replaces the `Gradient` re-exported from the palette crate, which only supports
+//! interpolation within the space of its colors.
+
+use crate::color::{mix_oklab, mix_oklch, ColorExt, IntoLinSrgba, LinSrgba, Mix, Srgba};
+use crate::image;
+
+/// A gradient between any number of colors, each positioned at a stop.
+///
+/// Colors between stops are interpolated within the gradient's **GradientSpace**, Oklab by
+/// default. Stops are typically positioned within `0.0..=1.0`, though any range may be used.
+///
+/// Gradients may be sampled on the CPU via `at`, e.g. to color the vertices of a mesh, or baked
+/// into an image via `to_image` for sampling on the GPU.
+///
+/// ```
+/// use nannou::color::{Gradient, BLUE, RED, WHITE};
+///
+/// let gradient = Gradient::new([(0.0, RED), (0.7, BLUE), (1.0, WHITE)]);
+/// let color = gradient.at(0.35);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f32, LinSrgba)>,
+    space: GradientSpace,
+}
+
+/// The color space in which a **Gradient** interpolates between its stops.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GradientSpace {
+    /// Interpolate the linear sRGB components, matching the interpolation of vertex colors on the
+    /// GPU.
+    LinearSrgb,
+    /// Interpolate the gamma encoded sRGB components, matching most image editors and CSS.
+    Srgb,
+    /// Interpolate within the perceptually uniform Oklab space.
+    #[default]
+    Oklab,
+    /// Interpolate within Oklch along the shortest path between hues, producing more vivid
+    /// intermediate colors.
+    Oklch,
+}
+
+impl Gradient {
+    /// A gradient with the given stops, each a position and a color.
+    ///
+    /// Stops are sorted by their position.
+    pub fn new<I, C>(stops: I) -> Self
+    where
+        I: IntoIterator<Item = (f32, C)>,
+        C: IntoLinSrgba<f32>,
+    {
+        let mut stops: Vec<_> = stops
+            .into_iter()
+            .map(|(t, c)| (t, c.into_lin_srgba()))
+            .collect();
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Gradient {
+            stops,
+            space: GradientSpace::default(),
+        }
+    }
+
+    /// A gradient with the given colors evenly spaced from `0.0` to `1.0`.
+    pub fn even<I>(colors: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoLinSrgba<f32>,
+    {
+        let colors: Vec<_> = colors.into_iter().map(|c| c.into_lin_srgba()).collect();
+        let div = colors.len().saturating_sub(1).max(1) as f32;
+        let stops = colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (i as f32 / div, c));
+        Self::new(stops)
+    }
+
+    /// Specify the color space in which colors are interpolated.
+    pub fn space(mut self, space: GradientSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Add a stop to the gradient.
+    pub fn push<C>(&mut self, position: f32, color: C)
+    where
+        C: IntoLinSrgba<f32>,
+    {
+        let ix = self.stops.partition_point(|&(t, _)| t <= position);
+        self.stops.insert(ix, (position, color.into_lin_srgba()));
+    }
+
+    /// The stops of the gradient, ordered by position.
+    pub fn stops(&self) -> &[(f32, LinSrgba)] {
+        &self.stops
+    }
+
+    /// The color space in which colors are interpolated.
+    pub fn color_space(&self) -> GradientSpace {
+        self.space
+    }
+
+    /// The color of the gradient at position `t`.
+    ///
+    /// Positions before the first stop or after the last are given the color of the nearest stop.
+    /// An empty gradient is transparent black.
+    pub fn at(&self, t: f32) -> LinSrgba {
+        let ix = self.stops.partition_point(|&(s, _)| s <= t);
+        match (ix.checked_sub(1), self.stops.get(ix)) {
+            (None, None) => LinSrgba::new(0.0, 0.0, 0.0, 0.0),
+            (None, Some(&(_, c))) => c,
+            (Some(i), None) => self.stops[i].1,
+            (Some(i), Some(&(end, b))) => {
+                let (start, a) = self.stops[i];
+                let f = (t - start) / (end - start);
+                self.space.mix(a, b, f)
+            }
+        }
+    }
+
+    /// Sample `count` colors evenly spaced between the first and last stops.
+    ///
+    /// Useful for coloring a series of vertices or filling a lookup table.
+    pub fn samples(&self, count: usize) -> Vec<LinSrgba> {
+        let (start, end) = match (self.stops.first(), self.stops.last()) {
+            (Some(&(start, _)), Some(&(end, _))) => (start, end),
+            _ => (0.0, 1.0),
+        };
+        let div = count.saturating_sub(1).max(1) as f32;
+        (0..count)
+            .map(|i| self.at(start + (end - start) * i as f32 / div))
+            .collect()
+    }
+
+    /// Bake the gradient into a `width` x `1` image, spanning the first to the last stop.
+    ///
+    /// The image may be uploaded via `wgpu::Texture::from_image` for sampling within a shader or
+    /// for use with `draw.texture`. The pixels are gamma encoded sRGB.
+    pub fn to_image(&self, width: u32) -> image::RgbaImage {
+        let samples = self.samples(width as usize);
+        image::RgbaImage::from_fn(width, 1, |x, _| {
+            let c = samples[x as usize].to_srgb();
+            let byte = |f: f32| (f.clamp(0.0, 1.0) * 255.0).round() as u8;
+            image::Rgba([byte(c.red), byte(c.green), byte(c.blue), byte(c.alpha)])
+        })
+    }
+}
+
+impl GradientSpace {
+    /// Interpolate between two colors within this space, where `t` of `0.0` is `a` and `1.0` is
+    /// `b`.
+    pub fn mix(self, a: LinSrgba, b: LinSrgba, t: f32) -> LinSrgba {
+        match self {
+            GradientSpace::LinearSrgb => a.mix(&b, t),
+            GradientSpace::Srgb => {
+                let (a, b) = (a.to_srgb(), b.to_srgb());
+                let lerp = |a: f32, b: f32| a + (b - a) * t;
+                let c = Srgba::new(
+                    lerp(a.red, b.red),
+                    lerp(a.green, b.green),
+                    lerp(a.blue, b.blue),
+                    lerp(a.alpha, b.alpha),
+                );
+                c.to_linear()
+            }
+            GradientSpace::Oklab => mix_oklab(a, b, t),
+            GradientSpace::Oklch => mix_oklch(a, b, t),
+        }
+    }
+}
+
+impl<C> std::iter::FromIterator<(f32, C)> for Gradient
+where
+    C: IntoLinSrgba<f32>,
+{
+    fn from_iter<I>(stops: I) -> Self
+    where
+        I: IntoIterator<Item = (f32, C)>,
+    {
+        Self::new(stops)
+    }
+}
@@ -0,0 +1,19 @@
+//! Color items, including everything from rgb, hsb/l/v, lap, alpha, luma and more, provided by the
+//! [palette crate](https://docs.rs/palette).
+//!
+//! Along with the items re-exported from `nannou_core::color`, this module provides the
+//! **Palette** type for working with collections of colors, including those extracted from
+//! images, and the **Gradient** type for interpolating between any number of colors. The palette
+//! crate's own gradient type remains available as **PaletteGradient**.
+//!
+//! See the [**named**](./named/index.html) module for a set of provided color constants.
+
+pub mod gradient;
+mod palette;
+
+pub use self::gradient::{Gradient, GradientSpace};
+pub use self::palette::Palette;
+#[doc(inline)]
+pub use nannou_core::color::Gradient as PaletteGradient;
+#[doc(inline)]
+pub use nannou_core::color::*;
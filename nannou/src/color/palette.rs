@@ -0,0 +1,345 @@
+use crate::color::{mix_oklab, IntoLinSrgba, LinSrgba, Oklab};
+use crate::image;
+use crate::rand::{rngs::SmallRng, Rng, SeedableRng};
+use std::ops;
+use std::path::Path;
+
+/// An ordered collection of colors, each with a weight used for random sampling.
+///
+/// Palettes may be built from any colors, e.g. those produced by the `harmony` module,
+/// interpolated between two colors via `Palette::ramp` or extracted from an image via
+/// `Palette::from_image`.
+///
+/// ```
+/// use nannou::color::{harmony, Palette, STEELBLUE};
+///
+/// let palette = Palette::new(harmony::triadic(STEELBLUE));
+/// assert_eq!(palette.len(), 3);
+/// let color = palette.random();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Palette {
+    colors: Vec<LinSrgba>,
+    weights: Vec<f32>,
+}
+
+impl Palette {
+    /// The maximum number of pixels considered when extracting a palette from an image.
+    pub const MAX_IMAGE_SAMPLES: usize = 16_384;
+
+    /// A palette of the given colors, each with a weight of `1.0`.
+    pub fn new<I>(colors: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoLinSrgba<f32>,
+    {
+        colors.into_iter().collect()
+    }
+
+    /// A palette of the given colors and their weights.
+    pub fn weighted<I, C>(colors: I) -> Self
+    where
+        I: IntoIterator<Item = (C, f32)>,
+        C: IntoLinSrgba<f32>,
+    {
+        let mut palette = Palette::default();
+        for (color, weight) in colors {
+            palette.push(color, weight);
+        }
+        palette
+    }
+
+    /// A palette of `count` colors evenly interpolated from `start` to `end` in the Oklab space.
+    pub fn ramp<A, B>(start: A, end: B, count: usize) -> Self
+    where
+        A: IntoLinSrgba<f32>,
+        B: IntoLinSrgba<f32>,
+    {
+        let (start, end) = (start.into_lin_srgba(), end.into_lin_srgba());
+        let div = count.saturating_sub(1).max(1) as f32;
+        (0..count)
+            .map(|i| mix_oklab(start, end, i as f32 / div))
+            .collect()
+    }
+
+    /// Extract a palette of up to `count` dominant colors from the given image.
+    ///
+    /// Colors are found by clustering the pixels of the image in the Oklab space. Each color is
+    /// weighted by the fraction of pixels it represents and the colors are ordered from most to
+    /// least dominant. Mostly transparent pixels are ignored.
+    ///
+    /// The result is deterministic for a given image and count.
+    pub fn from_image(image: &image::DynamicImage, count: usize) -> Self {
+        let rgba = image.to_rgba8();
+        let n_pixels = rgba.width() as usize * rgba.height() as usize;
+        let step = (n_pixels / Self::MAX_IMAGE_SAMPLES).max(1);
+        let samples: Vec<Oklab> = rgba
+            .pixels()
+            .step_by(step)
+            .filter(|p| p[3] >= 128)
+            .map(|p| {
+                let color = crate::color::srgb8(p[0], p[1], p[2]).into_lin_srgba();
+                Oklab::from(color.color)
+            })
+            .collect();
+
+        let clusters = k_means(&samples, count);
+        let total = samples.len().max(1) as f32;
+        let mut palette = Palette::default();
+        for (centroid, size) in clusters {
+            palette.push(centroid, size as f32 / total);
+        }
+        palette
+    }
+
+    /// Open the image at the given path and extract a palette of up to `count` dominant colors.
+    ///
+    /// See `Palette::from_image` for details.
+    pub fn open<P>(path: P, count: usize) -> image::ImageResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let image = image::open(path)?;
+        Ok(Self::from_image(&image, count))
+    }
+
+    /// Add a color with the given weight to the end of the palette.
+    pub fn push<C>(&mut self, color: C, weight: f32)
+    where
+        C: IntoLinSrgba<f32>,
+    {
+        self.colors.push(color.into_lin_srgba());
+        self.weights.push(weight.max(0.0));
+    }
+
+    /// The number of colors in the palette.
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Whether or not the palette contains any colors.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// The colors of the palette in order.
+    pub fn colors(&self) -> &[LinSrgba] {
+        &self.colors
+    }
+
+    /// The weight of each color of the palette.
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Set the weight of the color at the given index.
+    ///
+    /// Does nothing if the index is out of range.
+    pub fn set_weight(&mut self, index: usize, weight: f32) {
+        if let Some(w) = self.weights.get_mut(index) {
+            *w = weight.max(0.0);
+        }
+    }
+
+    /// Produce an iterator yielding each color of the palette.
+    pub fn iter(&self) -> std::iter::Cloned<std::slice::Iter<'_, LinSrgba>> {
+        self.colors.iter().cloned()
+    }
+
+    /// The color at the given index, or `None` if out of range.
+    pub fn get(&self, index: usize) -> Option<LinSrgba> {
+        self.colors.get(index).cloned()
+    }
+
+    /// The color at the given index, wrapping around to the start of the palette when the index
+    /// exceeds its length.
+    ///
+    /// Useful for assigning colors to an arbitrary number of items.
+    ///
+    /// **Panics** if the palette is empty.
+    pub fn wrapping(&self, index: usize) -> LinSrgba {
+        self.colors[index % self.colors.len()]
+    }
+
+    /// A random color, chosen according to the weight of each color.
+    ///
+    /// If all weights are zero, each color is equally likely.
+    ///
+    /// **Panics** if the palette is empty.
+    pub fn random(&self) -> LinSrgba {
+        self.random_with(&mut crate::rand::thread_rng())
+    }
+
+    /// The same as `random`, but uses the given random number generator, e.g. a seeded generator
+    /// for reproducible results.
+    ///
+    /// **Panics** if the palette is empty.
+    pub fn random_with<R>(&self, rng: &mut R) -> LinSrgba
+    where
+        R: Rng + ?Sized,
+    {
+        assert!(
+            !self.is_empty(),
+            "cannot sample a color from an empty palette"
+        );
+        let total: f32 = self.weights.iter().sum();
+        if total <= 0.0 {
+            return self.colors[rng.gen_range(0..self.colors.len())];
+        }
+        let mut target = rng.gen::<f32>() * total;
+        for (&color, &weight) in self.colors.iter().zip(&self.weights) {
+            if target < weight {
+                return color;
+            }
+            target -= weight;
+        }
+        // Guard against rounding errors by returning the last color with a non-zero weight.
+        let ix = self.weights.iter().rposition(|&w| w > 0.0).unwrap_or(0);
+        self.colors[ix]
+    }
+
+    /// Interpolate across the colors of the palette in the Oklab space, where `t` of `0.0` is the
+    /// first color and `1.0` is the last.
+    ///
+    /// **Panics** if the palette is empty.
+    pub fn lerp(&self, t: f32) -> LinSrgba {
+        assert!(!self.is_empty(), "cannot interpolate an empty palette");
+        let last = self.colors.len() - 1;
+        let pos = t.clamp(0.0, 1.0) * last as f32;
+        let ix = (pos.floor() as usize).min(last);
+        match self.colors.get(ix + 1) {
+            None => self.colors[ix],
+            Some(&next) => mix_oklab(self.colors[ix], next, pos - ix as f32),
+        }
+    }
+
+    /// Sort the colors of the palette from darkest to lightest by their perceived lightness.
+    pub fn sort_by_lightness(&mut self) {
+        let mut entries: Vec<_> = self
+            .colors
+            .iter()
+            .cloned()
+            .zip(self.weights.iter().cloned())
+            .collect();
+        entries.sort_by(|a, b| {
+            let (la, lb) = (Oklab::from(a.0.color).l, Oklab::from(b.0.color).l);
+            la.partial_cmp(&lb).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let (colors, weights) = entries.into_iter().unzip();
+        self.colors = colors;
+        self.weights = weights;
+    }
+}
+
+impl<C> std::iter::FromIterator<C> for Palette
+where
+    C: IntoLinSrgba<f32>,
+{
+    fn from_iter<I>(colors: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+    {
+        let colors: Vec<_> = colors.into_iter().map(|c| c.into_lin_srgba()).collect();
+        let weights = vec![1.0; colors.len()];
+        Palette { colors, weights }
+    }
+}
+
+impl ops::Index<usize> for Palette {
+    type Output = LinSrgba;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.colors[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a Palette {
+    type Item = LinSrgba;
+    type IntoIter = std::iter::Cloned<std::slice::Iter<'a, LinSrgba>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Cluster the given colors into at most `k` groups, returning the centroid and size of each group
+// ordered from largest to smallest.
+fn k_means(samples: &[Oklab], k: usize) -> Vec<(Oklab, usize)> {
+    const ITERATIONS: usize = 24;
+    let k = k.min(samples.len());
+    if k == 0 {
+        return vec![];
+    }
+
+    // Choose initial centroids via k-means++ with a fixed seed for deterministic results.
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut centroids = vec![samples[rng.gen_range(0..samples.len())]];
+    let mut dists: Vec<f32> = samples
+        .iter()
+        .map(|s| s.distance(centroids[0]).powi(2))
+        .collect();
+    while centroids.len() < k {
+        let total: f32 = dists.iter().sum();
+        if total <= 0.0 {
+            break;
+        }
+        let mut target = rng.gen::<f32>() * total;
+        let ix = dists
+            .iter()
+            .position(|&d| {
+                target -= d;
+                target < 0.0
+            })
+            .unwrap_or(samples.len() - 1);
+        let centroid = samples[ix];
+        centroids.push(centroid);
+        for (d, s) in dists.iter_mut().zip(samples) {
+            *d = d.min(s.distance(centroid).powi(2));
+        }
+    }
+
+    // Alternate between assigning samples to their nearest centroid and updating the centroids.
+    let nearest = |centroids: &[Oklab], s: Oklab| {
+        let mut best = (0, f32::MAX);
+        for (i, &c) in centroids.iter().enumerate() {
+            let d = s.distance(c);
+            if d < best.1 {
+                best = (i, d);
+            }
+        }
+        best.0
+    };
+    let mut assignments = vec![usize::MAX; samples.len()];
+    let mut sizes = vec![0; centroids.len()];
+    for _ in 0..ITERATIONS {
+        let mut changed = false;
+        for (a, &s) in assignments.iter_mut().zip(samples) {
+            let ix = nearest(&centroids, s);
+            changed |= *a != ix;
+            *a = ix;
+        }
+        let mut sums = vec![(0.0, 0.0, 0.0); centroids.len()];
+        sizes.iter_mut().for_each(|n| *n = 0);
+        for (&a, s) in assignments.iter().zip(samples) {
+            sums[a].0 += s.l;
+            sums[a].1 += s.a;
+            sums[a].2 += s.b;
+            sizes[a] += 1;
+        }
+        for ((c, &(l, a, b)), &n) in centroids.iter_mut().zip(&sums).zip(&sizes) {
+            if n > 0 {
+                let n = n as f32;
+                *c = Oklab::new(l / n, a / n, b / n);
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut clusters: Vec<_> = centroids
+        .into_iter()
+        .zip(sizes)
+        .filter(|&(_, n)| n > 0)
+        .collect();
+    clusters.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+    clusters
+}
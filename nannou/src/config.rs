@@ -0,0 +1,184 @@
+//! Items related to configuring sketches and installations via command line arguments and a
+//! config file.
+//!
+//! A config is any type implementing `serde::Deserialize`. It is loaded from a TOML or JSON
+//! config file, after which any command line arguments are applied over the top. See
+//! `app::Builder::with_config` for loading a config before the model is created.
+//!
+//! Arguments take the form `--key value` or `--key=value`, where any dashes within the key are
+//! replaced by underscores. Values are parsed as JSON where possible (e.g. `42`, `true` or
+//! `"42"`), otherwise as a list if they contain commas (e.g. `800,600`) or as a string. A key
+//! without a value (e.g. `--fullscreen`) is `true`.
+//!
+//! The config file is given by the `--config` argument. Otherwise, the first of the
+//! `DEFAULT_PATHS` that exists within the current working directory is used, if any. Files with
+//! a `.json` extension are parsed as JSON and all others as TOML.
+
+use serde_derive::Deserialize;
+use serde_json::{Map, Value};
+use std::error::Error as StdError;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// The config file paths checked in order when no `--config` argument is given.
+pub const DEFAULT_PATHS: &[&str] = &["config.toml", "config.json"];
+
+/// Settings common to most sketches and installations.
+///
+/// When loaded via `app::Builder::with_config`, the `size` and `fullscreen` settings are applied
+/// to the app's default window size. Include these within a config via `#[serde(flatten)]` to
+/// access the remaining settings.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Standard {
+    /// The default window size in points, e.g. `--size 800,600`.
+    pub size: Option<[u32; 2]>,
+    /// Whether or not windows are fullscreen by default, e.g. `--fullscreen`.
+    pub fullscreen: bool,
+    /// The seed for any random number generation, e.g. `--seed 42`.
+    pub seed: Option<u64>,
+    /// The path to which any output is written, e.g. `--output frames/`.
+    pub output: Option<PathBuf>,
+}
+
+/// Errors that might occur while loading a config.
+#[derive(Debug)]
+pub enum Error {
+    /// An invalid command line argument.
+    Args(String),
+    /// Failed to read the config file.
+    Io(io::Error),
+    /// Failed to parse a TOML config file.
+    Toml(toml::de::Error),
+    /// Failed to parse a JSON config file or to deserialize the config.
+    Json(serde_json::Error),
+}
+
+/// Load a config from the current process' command line arguments and config file, along with
+/// the standard settings.
+pub fn load<C>() -> Result<(C, Standard), Error>
+where
+    C: for<'de> serde::Deserialize<'de>,
+{
+    from_args(std::env::args().skip(1))
+}
+
+/// Load a config from the given command line arguments (excluding the program name) and config
+/// file, along with the standard settings.
+pub fn from_args<C, I>(args: I) -> Result<(C, Standard), Error>
+where
+    C: for<'de> serde::Deserialize<'de>,
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    let mut args = parse_args(args)?;
+    let path = match args.remove("config") {
+        Some(Value::String(path)) => Some(PathBuf::from(path)),
+        Some(_) => return Err(Error::Args("`--config` expects a path".to_string())),
+        None => DEFAULT_PATHS.iter().map(PathBuf::from).find(|p| p.exists()),
+    };
+    let mut map = match path {
+        Some(path) => read_file(&path)?,
+        None => Map::new(),
+    };
+    map.extend(args);
+    let value = Value::Object(map);
+    let standard = serde::Deserialize::deserialize(&value)?;
+    let config = serde::Deserialize::deserialize(value)?;
+    Ok((config, standard))
+}
+
+// Parse the given arguments into a map from keys to values.
+fn parse_args<I>(args: I) -> Result<Map<String, Value>, Error>
+where
+    I: IntoIterator,
+    I::Item: Into<String>,
+{
+    let mut map = Map::new();
+    let mut args = args.into_iter().map(Into::into).peekable();
+    while let Some(arg) = args.next() {
+        let key = match arg.strip_prefix("--") {
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(Error::Args(format!("unexpected argument `{}`", arg))),
+        };
+        let (key, value) = match key.split_once('=') {
+            Some((key, value)) => (key, parse_value(value)),
+            None => match args.peek() {
+                Some(next) if !next.starts_with("--") => (key, parse_value(&args.next().unwrap())),
+                _ => (key, Value::Bool(true)),
+            },
+        };
+        map.insert(key.replace('-', "_"), value);
+    }
+    Ok(map)
+}
+
+// Parse a single argument value.
+fn parse_value(s: &str) -> Value {
+    match serde_json::from_str(s) {
+        Ok(value) => value,
+        Err(_) if s.contains(',') => {
+            Value::Array(s.split(',').map(|s| parse_value(s.trim())).collect())
+        }
+        Err(_) => Value::String(s.to_string()),
+    }
+}
+
+// Read the TOML or JSON config file at the given path.
+fn read_file(path: &Path) -> Result<Map<String, Value>, Error> {
+    let string = fs::read_to_string(path)?;
+    let value = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&string)?,
+        _ => {
+            let table: toml::Value = toml::from_str(&string)?;
+            serde_json::to_value(table)?
+        }
+    };
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(Error::Args(format!(
+            "config file `{}` must contain a table of settings",
+            path.display()
+        ))),
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Toml(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            Error::Args(_) => None,
+            Error::Io(ref err) => Some(err),
+            Error::Toml(ref err) => Some(err),
+            Error::Json(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Args(ref msg) => write!(f, "{}", msg),
+            Error::Io(ref err) => write!(f, "failed to read config file: {}", err),
+            Error::Toml(ref err) => write!(f, "failed to parse config file: {}", err),
+            Error::Json(ref err) => write!(f, "invalid config: {}", err),
+        }
+    }
+}
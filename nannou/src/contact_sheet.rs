@@ -0,0 +1,299 @@
+//! Render a sketch once per seed and compose the results into a labelled grid image.
+//!
+//! Contact sheets are useful when curating the outputs of a generative sketch, allowing many
+//! variations to be compared at a glance.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::rand::{rngs::StdRng, Rng, SeedableRng};
+//!
+//! fn model(app: &App) {
+//!     app.new_window().build().unwrap();
+//!     let seeds: Vec<u64> = (0..12).collect();
+//!     app.contact_sheet(&seeds, 4, 3, "contact_sheet.png")
+//!         .render(|draw, rect, seed| {
+//!             let mut rng = StdRng::seed_from_u64(seed);
+//!             draw.background().color(BLACK);
+//!             for _ in 0..32 {
+//!                 let x = rng.gen_range(rect.left()..rect.right());
+//!                 let y = rng.gen_range(rect.bottom()..rect.top());
+//!                 draw.ellipse().x_y(x, y).radius(rng.gen_range(4.0..32.0));
+//!             }
+//!         })
+//!         .unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::color::{self, IntoLinSrgba, LinSrgba};
+use crate::draw::{self, Draw};
+use crate::frame::Frame;
+use crate::geom::Rect;
+use crate::wgpu;
+use crate::App;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::{error, fmt};
+
+/// A builder for a contact sheet.
+///
+/// Created via `App::contact_sheet`.
+pub struct Builder<'a> {
+    app: &'a App,
+    seeds: &'a [u64],
+    cols: u32,
+    rows: u32,
+    path: PathBuf,
+    thumbnail_size: Option<[u32; 2]>,
+    sketch_size: Option<[f32; 2]>,
+    padding: u32,
+    labels: bool,
+    background: LinSrgba,
+    label_color: LinSrgba,
+}
+
+/// Errors that might occur while rendering a contact sheet.
+#[derive(Debug)]
+pub enum Error {
+    /// A window is required in order to access the GPU device.
+    NoWindow,
+    /// Failed to map the rendered image into CPU memory.
+    Read(wgpu::BufferAsyncError),
+    /// Timed out while waiting for the rendered image to be read.
+    Timeout,
+    /// Failed to save the image to the given path.
+    Save(crate::image::ImageError),
+}
+
+impl<'a> Builder<'a> {
+    /// The default width of each thumbnail in pixels.
+    pub const DEFAULT_THUMBNAIL_WIDTH: u32 = 256;
+    /// The default padding between thumbnails in pixels.
+    pub const DEFAULT_PADDING: u32 = 8;
+    /// The font size used for the seed labels.
+    pub const LABEL_FONT_SIZE: u32 = 14;
+
+    /// Begin building a contact sheet with a thumbnail for each seed, laid out in a grid with the
+    /// given number of columns and rows, to be saved to the given path.
+    ///
+    /// Seeds beyond the first `cols * rows` are ignored.
+    pub fn new<P>(app: &'a App, seeds: &'a [u64], cols: u32, rows: u32, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Builder {
+            app,
+            seeds,
+            cols: cols.max(1),
+            rows: rows.max(1),
+            path: path.as_ref().to_path_buf(),
+            thumbnail_size: None,
+            sketch_size: None,
+            padding: Self::DEFAULT_PADDING,
+            labels: true,
+            background: color::rgb(0.12, 0.12, 0.12).into_lin_srgba(),
+            label_color: color::rgb(0.8, 0.8, 0.8).into_lin_srgba(),
+        }
+    }
+
+    /// The size of each thumbnail in pixels.
+    ///
+    /// By default, thumbnails are `DEFAULT_THUMBNAIL_WIDTH` pixels wide with the aspect ratio of
+    /// the sketch.
+    pub fn thumbnail_size(mut self, width: u32, height: u32) -> Self {
+        self.thumbnail_size = Some([width.max(1), height.max(1)]);
+        self
+    }
+
+    /// The size of the area described by the sketch in points.
+    ///
+    /// The sketch is scaled uniformly to fit within each thumbnail. By default, this is the size
+    /// of the main window.
+    pub fn sketch_size(mut self, width: f32, height: f32) -> Self {
+        self.sketch_size = Some([width, height]);
+        self
+    }
+
+    /// The padding between thumbnails and around the edge of the sheet in pixels.
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Whether or not to label each thumbnail with its seed. By default, this is `true`.
+    pub fn labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// The color of the sheet behind the thumbnails.
+    pub fn background<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<f32>,
+    {
+        self.background = color.into_lin_srgba();
+        self
+    }
+
+    /// The color of the seed labels.
+    pub fn label_color<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<f32>,
+    {
+        self.label_color = color.into_lin_srgba();
+        self
+    }
+
+    /// Render the sketch once for each seed and save the resulting sheet.
+    ///
+    /// The given function is called with a **Draw** to describe the sketch to, the **Rect**
+    /// describing the sketch bounds (equivalent to `app.window_rect()`) and the seed. This blocks
+    /// until the image has been written.
+    pub fn render<F>(self, mut view: F) -> Result<(), Error>
+    where
+        F: FnMut(&Draw, Rect, u64),
+    {
+        let Builder {
+            app,
+            seeds,
+            cols,
+            rows,
+            path,
+            thumbnail_size,
+            sketch_size,
+            padding,
+            labels,
+            background,
+            label_color,
+        } = self;
+
+        let window_id = (*app.focused_window.borrow()).ok_or(Error::NoWindow)?;
+        let window = app.window(window_id).ok_or(Error::NoWindow)?;
+        let device = window.device();
+
+        // Determine the layout.
+        let [sketch_w, sketch_h] = sketch_size.unwrap_or_else(|| window.rect().wh().into());
+        let sketch = Rect::from_w_h(sketch_w.max(1.0), sketch_h.max(1.0));
+        let [thumb_w, thumb_h] = thumbnail_size.unwrap_or_else(|| {
+            let w = Self::DEFAULT_THUMBNAIL_WIDTH;
+            let h = (w as f32 * sketch.h() / sketch.w()).round() as u32;
+            [w, h.max(1)]
+        });
+        let label_h = if labels { Self::LABEL_FONT_SIZE * 2 } else { 0 };
+        let cell_w = thumb_w + padding;
+        let cell_h = thumb_h + label_h + padding;
+        let sheet_w = cols * cell_w + padding;
+        let sheet_h = rows * cell_h + padding;
+        let sheet_rect = Rect::from_w_h(sheet_w as f32, sheet_h as f32);
+        // The top-left of the thumbnail for the cell at the given index in pixels.
+        let cell_origin = |ix: u32| {
+            let (col, row) = (ix % cols, ix / cols);
+            [padding + col * cell_w, padding + row * cell_h]
+        };
+
+        let format = Frame::TEXTURE_FORMAT;
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC
+            | wgpu::TextureUsages::COPY_DST;
+        let sheet_texture = wgpu::TextureBuilder::new()
+            .size([sheet_w, sheet_h])
+            .format(format)
+            .usage(usage)
+            .build(device);
+        let thumb_texture = wgpu::TextureBuilder::new()
+            .size([thumb_w, thumb_h])
+            .format(format)
+            .usage(usage)
+            .build(device);
+        let mut sheet_renderer =
+            draw::RendererBuilder::new().build(device, [sheet_w, sheet_h], 1.0, 1, format);
+        let mut thumb_renderer =
+            draw::RendererBuilder::new().build(device, [thumb_w, thumb_h], 1.0, 1, format);
+
+        let ce_desc = wgpu::CommandEncoderDescriptor {
+            label: Some("nannou_contact_sheet"),
+        };
+        let mut encoder = device.create_command_encoder(&ce_desc);
+        let n = seeds.len().min((cols * rows) as usize) as u32;
+
+        // Draw the sheet background and labels.
+        let draw = Draw::new();
+        draw.background().color(background);
+        if labels {
+            for (ix, seed) in seeds.iter().take(n as usize).enumerate() {
+                let [x, y] = cell_origin(ix as u32);
+                let label = Rect::from_corners(
+                    [x as f32, (y + thumb_h) as f32].into(),
+                    [(x + thumb_w) as f32, (y + thumb_h + label_h) as f32].into(),
+                );
+                let xy = [sheet_rect.left() + label.x(), sheet_rect.top() - label.y()];
+                draw.text(&format!("seed {}", seed))
+                    .xy(xy.into())
+                    .wh(label.wh())
+                    .font_size(Self::LABEL_FONT_SIZE)
+                    .color(label_color);
+            }
+        }
+        sheet_renderer.render_to_texture(device, &mut encoder, &draw, &sheet_texture);
+
+        // Render each thumbnail and copy it into place.
+        let scale = (thumb_w as f32 / sketch.w()).min(thumb_h as f32 / sketch.h());
+        for (ix, &seed) in seeds.iter().take(n as usize).enumerate() {
+            draw.reset();
+            draw.background().color(background);
+            view(&draw.scale(scale), sketch, seed);
+            thumb_renderer.render_to_texture(device, &mut encoder, &draw, &thumb_texture);
+            let [x, y] = cell_origin(ix as u32);
+            let dst = wgpu::ImageCopyTexture {
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                ..sheet_texture.as_image_copy()
+            };
+            encoder.copy_texture_to_texture(
+                thumb_texture.as_image_copy(),
+                dst,
+                thumb_texture.extent(),
+            );
+        }
+
+        // Read the sheet back to the CPU and save it.
+        let capturer = wgpu::TextureCapturer::default();
+        let snapshot = capturer.capture(device, &mut encoder, &sheet_texture);
+        window.queue().submit(Some(encoder.finish()));
+        let (tx, rx) = mpsc::channel();
+        snapshot
+            .read(move |result| {
+                let _ = tx.send(result.map(|image| image.to_owned()));
+            })
+            .map_err(|_| Error::Timeout)?;
+        capturer
+            .await_active_snapshots(device)
+            .map_err(|_| Error::Timeout)?;
+        let image = rx
+            .recv()
+            .map_err(|_| Error::Timeout)?
+            .map_err(Error::Read)?;
+        image.save(&path).map_err(Error::Save)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Read(ref err) => Some(err),
+            Error::Save(ref err) => Some(err),
+            Error::NoWindow | Error::Timeout => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NoWindow => write!(f, "a window is required to render a contact sheet"),
+            Error::Read(ref err) => write!(f, "failed to read the contact sheet: {}", err),
+            Error::Timeout => write!(f, "timed out while reading the contact sheet"),
+            Error::Save(ref err) => write!(f, "failed to save the contact sheet: {}", err),
+        }
+    }
+}
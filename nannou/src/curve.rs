@@ -0,0 +1,519 @@
+//! Editable curves for tuning easing, envelopes and other values that vary over time.
+//!
+//! A **Curve** maps an input `t` within `0.0..=1.0` to an output value and may be sampled via
+//! **Curve::sample**. The **Editor** allows for editing a curve's control points with the mouse
+//! from within a window, while **Presets** provides a simple way of persisting named curves to
+//! disk.
+//!
+//! ```no_run
+//! use nannou::curve::{self, Curve};
+//! use nannou::prelude::*;
+//!
+//! struct Model {
+//!     intensity: Curve,
+//!     editor: curve::Editor,
+//! }
+//!
+//! fn update(app: &App, model: &mut Model, _update: Update) {
+//!     model.editor.update(app, &mut model.intensity);
+//! }
+//!
+//! fn view(app: &App, model: &Model, frame: Frame) {
+//!     let draw = app.draw();
+//!     let t = (app.time * 0.25).fract();
+//!     let radius = 50.0 + model.intensity.sample(t) * 200.0;
+//!     draw.ellipse().radius(radius);
+//!     model.editor.draw(&draw, &model.intensity);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::color::LinSrgba;
+use crate::draw::Draw;
+use crate::geom::Rect;
+use crate::glam::Vec2;
+use crate::io;
+use crate::App;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A curve described by a set of control points, sorted by their `x` position.
+///
+/// The `x` of each point describes the input within `0.0..=1.0`, while the `y` describes the
+/// output value at that point.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Curve {
+    points: Vec<Vec2>,
+    /// The method used to interpolate between control points.
+    pub interpolation: Interpolation,
+}
+
+/// The methods by which the segments between a curve's control points may be interpolated.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum Interpolation {
+    /// Hold the value of the previous control point.
+    Step,
+    /// Interpolate linearly between control points.
+    Linear,
+    /// A smooth monotonic cubic that never overshoots the neighbouring control points.
+    Smooth,
+}
+
+/// A collection of named curves that may be saved to and loaded from a JSON file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Presets {
+    /// The curves stored by name.
+    pub curves: BTreeMap<String, Curve>,
+}
+
+/// An in-window editor for the control points of a **Curve**.
+///
+/// - Drag a control point to move it.
+/// - Press the left mouse button on an empty space to add a control point.
+/// - Press the right mouse button on a control point to remove it.
+///
+/// The first and last control points may only be moved vertically.
+#[derive(Clone, Debug)]
+pub struct Editor {
+    /// The area of the window occupied by the editor.
+    pub rect: Rect,
+    /// The range of output values displayed by the editor, from the bottom to the top of `rect`.
+    pub range: (f32, f32),
+    /// Whether or not the editor should be rendered and respond to the mouse.
+    pub enabled: bool,
+    /// The radius of the control point handles in points.
+    pub handle_radius: f32,
+    mouse: Vec2,
+    left_down: bool,
+    right_down: bool,
+    hovered: Option<usize>,
+    dragging: Option<usize>,
+}
+
+impl Curve {
+    /// Create a curve from the given control points.
+    ///
+    /// Points are clamped to the `0.0..=1.0` input range and sorted by their `x` position.
+    pub fn new<I>(points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Vec2>,
+    {
+        let mut curve = Curve {
+            points: points.into_iter().map(Into::into).collect(),
+            interpolation: Interpolation::Smooth,
+        };
+        curve.sort();
+        curve
+    }
+
+    /// A curve that always returns the given value.
+    pub fn constant(value: f32) -> Self {
+        Self::new([[0.0, value], [1.0, value]]).with_interpolation(Interpolation::Linear)
+    }
+
+    /// A straight line from `0.0` to `1.0`.
+    pub fn linear() -> Self {
+        Self::new([[0.0, 0.0], [1.0, 1.0]]).with_interpolation(Interpolation::Linear)
+    }
+
+    /// Starts slowly and accelerates towards the end.
+    pub fn ease_in() -> Self {
+        Self::new([[0.0, 0.0], [0.5, 0.15], [1.0, 1.0]])
+    }
+
+    /// Starts quickly and decelerates towards the end.
+    pub fn ease_out() -> Self {
+        Self::new([[0.0, 0.0], [0.5, 0.85], [1.0, 1.0]])
+    }
+
+    /// Starts and ends slowly.
+    pub fn ease_in_out() -> Self {
+        Self::new([[0.0, 0.0], [0.25, 0.1], [0.75, 0.9], [1.0, 1.0]])
+    }
+
+    /// Rises quickly, holds and then falls away, useful as an intensity envelope.
+    pub fn envelope() -> Self {
+        Self::new([[0.0, 0.0], [0.1, 1.0], [0.6, 0.7], [1.0, 0.0]])
+    }
+
+    /// Builder method for specifying the interpolation method.
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// The control points, sorted by their `x` position.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Insert a control point, returning its index.
+    pub fn insert(&mut self, point: Vec2) -> usize {
+        let point = clamp_x(point);
+        let ix = self.points.partition_point(|p| p.x <= point.x);
+        self.points.insert(ix, point);
+        ix
+    }
+
+    /// Remove the control point at the given index.
+    pub fn remove(&mut self, index: usize) -> Vec2 {
+        self.points.remove(index)
+    }
+
+    /// Move the control point at the given index, returning its new index.
+    pub fn set(&mut self, index: usize, point: Vec2) -> usize {
+        self.points.remove(index);
+        self.insert(point)
+    }
+
+    /// Sample the curve at the given input.
+    ///
+    /// `t` is clamped to the `0.0..=1.0` range. A curve with no control points always returns
+    /// `0.0`.
+    pub fn sample(&self, t: f32) -> f32 {
+        let points = &self.points[..];
+        let (first, last) = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return 0.0,
+        };
+        let t = t.clamp(0.0, 1.0);
+        if t <= first.x {
+            return first.y;
+        } else if t >= last.x {
+            return last.y;
+        }
+
+        // Find the segment containing `t`.
+        let i = points.partition_point(|p| p.x <= t).max(1) - 1;
+        let (a, b) = (points[i], points[i + 1]);
+        let w = b.x - a.x;
+        if w <= 0.0 {
+            return b.y;
+        }
+        let s = (t - a.x) / w;
+        match self.interpolation {
+            Interpolation::Step => a.y,
+            Interpolation::Linear => a.y + (b.y - a.y) * s,
+            Interpolation::Smooth => {
+                let (ma, mb) = (self.tangent(i), self.tangent(i + 1));
+                let s2 = s * s;
+                let s3 = s2 * s;
+                let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+                let h10 = s3 - 2.0 * s2 + s;
+                let h01 = -2.0 * s3 + 3.0 * s2;
+                let h11 = s3 - s2;
+                h00 * a.y + h10 * w * ma + h01 * b.y + h11 * w * mb
+            }
+        }
+    }
+
+    /// Load a curve from the JSON file at the given path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, io::JsonFileError> {
+        io::load_from_json(path)
+    }
+
+    /// Save the curve to a JSON file at the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), io::JsonFileError> {
+        io::save_to_json(path, self)
+    }
+
+    // The tangent at the given control point using the Fritsch-Carlson method, ensuring
+    // monotonicity between control points.
+    fn tangent(&self, i: usize) -> f32 {
+        let p = &self.points;
+        let slope = |a: Vec2, b: Vec2| match b.x - a.x {
+            w if w > 0.0 => (b.y - a.y) / w,
+            _ => 0.0,
+        };
+        let prev = if i > 0 {
+            Some(slope(p[i - 1], p[i]))
+        } else {
+            None
+        };
+        let next = p.get(i + 1).map(|&n| slope(p[i], n));
+        match (prev, next) {
+            (Some(d), None) | (None, Some(d)) => d,
+            (Some(d0), Some(d1)) if d0 * d1 > 0.0 => {
+                // Harmonic mean keeps the interpolant within the bounds of its neighbours.
+                2.0 / (1.0 / d0 + 1.0 / d1)
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn sort(&mut self) {
+        for p in self.points.iter_mut() {
+            *p = clamp_x(*p);
+        }
+        self.points
+            .sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(std::cmp::Ordering::Equal));
+    }
+}
+
+impl Presets {
+    /// An empty set of presets.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The built-in presets: `linear`, `ease_in`, `ease_out`, `ease_in_out` and `envelope`.
+    pub fn builtin() -> Self {
+        let mut presets = Self::new();
+        presets.insert("linear", Curve::linear());
+        presets.insert("ease_in", Curve::ease_in());
+        presets.insert("ease_out", Curve::ease_out());
+        presets.insert("ease_in_out", Curve::ease_in_out());
+        presets.insert("envelope", Curve::envelope());
+        presets
+    }
+
+    /// Store a curve under the given name, returning the curve previously stored, if any.
+    pub fn insert<S: Into<String>>(&mut self, name: S, curve: Curve) -> Option<Curve> {
+        self.curves.insert(name.into(), curve)
+    }
+
+    /// The curve stored under the given name.
+    pub fn get(&self, name: &str) -> Option<&Curve> {
+        self.curves.get(name)
+    }
+
+    /// Remove the curve stored under the given name.
+    pub fn remove(&mut self, name: &str) -> Option<Curve> {
+        self.curves.remove(name)
+    }
+
+    /// Load a set of presets from the JSON file at the given path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, io::JsonFileError> {
+        io::load_from_json(path)
+    }
+
+    /// Save the presets to a JSON file at the given path.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), io::JsonFileError> {
+        io::save_to_json(path, self)
+    }
+}
+
+impl Editor {
+    /// The default radius of control point handles.
+    pub const DEFAULT_HANDLE_RADIUS: f32 = 5.0;
+
+    /// Create an editor occupying the given area of the window.
+    pub fn new(rect: Rect) -> Self {
+        Editor {
+            rect,
+            range: (0.0, 1.0),
+            enabled: true,
+            handle_radius: Self::DEFAULT_HANDLE_RADIUS,
+            mouse: Vec2::ZERO,
+            left_down: false,
+            right_down: false,
+            hovered: None,
+            dragging: None,
+        }
+    }
+
+    /// Builder method for specifying the range of output values displayed by the editor.
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = (min, max);
+        self
+    }
+
+    /// Update the editor with the app's mouse state, applying any edits to the given curve.
+    ///
+    /// Returns `true` if the curve was modified.
+    pub fn update(&mut self, app: &App, curve: &mut Curve) -> bool {
+        let mouse = app.mouse.position();
+        let left = app.mouse.buttons.left().is_down();
+        let right = app.mouse.buttons.right().is_down();
+        self.update_mouse(mouse, left, right, curve)
+    }
+
+    /// Update the editor with a custom mouse state, applying any edits to the given curve.
+    ///
+    /// The `mouse` position should be in the same coordinate space as `rect`.
+    pub fn update_mouse(
+        &mut self,
+        mouse: Vec2,
+        left: bool,
+        right: bool,
+        curve: &mut Curve,
+    ) -> bool {
+        let left_pressed = left && !self.left_down;
+        let right_pressed = right && !self.right_down;
+        self.mouse = mouse;
+        self.left_down = left;
+        self.right_down = right;
+        if !self.enabled {
+            self.hovered = None;
+            self.dragging = None;
+            return false;
+        }
+
+        // The curve may have been modified or replaced since the previous update.
+        let len = curve.points.len();
+        self.hovered = self.hovered.filter(|&ix| ix < len);
+        self.dragging = self.dragging.filter(|&ix| ix < len);
+        if len == 0 {
+            return false;
+        }
+
+        let mut changed = false;
+        if !left {
+            self.dragging = None;
+        }
+        let value = self.to_curve(mouse);
+
+        if let Some(ix) = self.dragging {
+            // The first and last points are pinned to the ends of the input range.
+            let last = curve.points.len() - 1;
+            let x = match ix {
+                0 => curve.points[0].x,
+                ix if ix == last => curve.points[last].x,
+                _ => {
+                    let min = curve.points[ix - 1].x;
+                    let max = curve.points[ix + 1].x;
+                    value.x.max(min).min(max)
+                }
+            };
+            let y = value.y.max(self.range.0.min(self.range.1));
+            let y = y.min(self.range.0.max(self.range.1));
+            let point = Vec2::new(x, y);
+            if curve.points[ix] != point {
+                curve.points[ix] = point;
+                changed = true;
+            }
+        }
+
+        self.hovered = self.dragging.or_else(|| self.hit_test(curve));
+        let inside = self.rect.contains(mouse);
+        if left_pressed && inside {
+            match self.hovered {
+                Some(ix) => self.dragging = Some(ix),
+                None => {
+                    let ix = curve.insert(value);
+                    self.dragging = Some(ix);
+                    self.hovered = Some(ix);
+                    changed = true;
+                }
+            }
+        } else if right_pressed && self.dragging.is_none() {
+            if let Some(ix) = self.hovered {
+                if ix != 0 && ix != curve.points.len() - 1 {
+                    curve.remove(ix);
+                    self.hovered = None;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Whether or not a control point is currently being dragged.
+    pub fn is_active(&self) -> bool {
+        self.dragging.is_some()
+    }
+
+    /// Render the editor along with the given curve.
+    pub fn draw(&self, draw: &Draw, curve: &Curve) {
+        if !self.enabled {
+            return;
+        }
+        let r = self.rect;
+        draw.rect()
+            .xy(r.xy())
+            .wh(r.wh())
+            .color(LinSrgba::new(0.0, 0.0, 0.0, 0.6))
+            .stroke_weight(1.0)
+            .stroke_color(LinSrgba::new(1.0, 1.0, 1.0, 0.3));
+
+        // Grid lines at quarters.
+        let grid = LinSrgba::new(1.0, 1.0, 1.0, 0.1);
+        for i in 1..4 {
+            let f = i as f32 / 4.0;
+            let x = r.left() + r.w() * f;
+            let y = r.bottom() + r.h() * f;
+            draw.line()
+                .start(Vec2::new(x, r.bottom()))
+                .end(Vec2::new(x, r.top()))
+                .color(grid);
+            draw.line()
+                .start(Vec2::new(r.left(), y))
+                .end(Vec2::new(r.right(), y))
+                .color(grid);
+        }
+
+        // The curve itself.
+        let resolution = (r.w().max(2.0) / 2.0) as usize;
+        let points = (0..=resolution).map(|i| {
+            let t = i as f32 / resolution as f32;
+            self.to_window(Vec2::new(t, curve.sample(t)))
+        });
+        draw.polyline()
+            .weight(2.0)
+            .points(points)
+            .color(LinSrgba::new(1.0, 0.8, 0.2, 1.0));
+
+        // The control points.
+        for (i, &p) in curve.points().iter().enumerate() {
+            let color = match self.hovered == Some(i) {
+                true => LinSrgba::new(1.0, 1.0, 1.0, 1.0),
+                false => LinSrgba::new(0.6, 0.6, 0.6, 1.0),
+            };
+            draw.ellipse()
+                .xy(self.to_window(p))
+                .radius(self.handle_radius)
+                .color(color);
+        }
+    }
+
+    // Map a point on the curve to the window.
+    fn to_window(&self, p: Vec2) -> Vec2 {
+        let (min, max) = self.range;
+        let y = if max != min {
+            (p.y - min) / (max - min)
+        } else {
+            0.5
+        };
+        let r = self.rect;
+        Vec2::new(r.left() + p.x * r.w(), r.bottom() + y * r.h())
+    }
+
+    // Map a point in the window to the curve.
+    fn to_curve(&self, p: Vec2) -> Vec2 {
+        let (min, max) = self.range;
+        let r = self.rect;
+        let x = if r.w() > 0.0 {
+            (p.x - r.left()) / r.w()
+        } else {
+            0.0
+        };
+        let y = if r.h() > 0.0 {
+            (p.y - r.bottom()) / r.h()
+        } else {
+            0.0
+        };
+        Vec2::new(x.clamp(0.0, 1.0), min + y * (max - min))
+    }
+
+    // The index of the control point under the mouse, if any.
+    fn hit_test(&self, curve: &Curve) -> Option<usize> {
+        let radius = self.handle_radius + 2.0;
+        curve
+            .points()
+            .iter()
+            .position(|&p| self.to_window(p).distance(self.mouse) <= radius)
+    }
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::linear()
+    }
+}
+
+fn clamp_x(p: Vec2) -> Vec2 {
+    Vec2::new(p.x.clamp(0.0, 1.0), p.y)
+}
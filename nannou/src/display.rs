@@ -0,0 +1,113 @@
+//! Items related to querying the displays (monitors) connected to the system.
+//!
+//! See `App::displays` for retrieving the currently connected displays, and the
+//! `window::Builder::fullscreen_on` and `window::Builder::span_all_displays` methods for placing
+//! windows on them.
+
+use crate::geom::Rect;
+use crate::winit::monitor::MonitorHandle;
+
+/// A unique identifier for a display, equal to its index within the list returned by
+/// `App::displays`.
+///
+/// **Note:** The order in which displays are listed is determined by the platform and may change
+/// when displays are connected or disconnected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id(pub usize);
+
+/// A snapshot of the geometry and capabilities of a display at the time it was queried.
+#[derive(Clone, Debug)]
+pub struct Display {
+    id: Id,
+    name: Option<String>,
+    position: [i32; 2],
+    size: [u32; 2],
+    scale_factor: f64,
+    refresh_rate_millihertz: Option<u32>,
+    is_primary: bool,
+    monitor: MonitorHandle,
+}
+
+impl Id {
+    /// The index of the display within the list returned by `App::displays`.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+impl Display {
+    // Snapshot the state of the given monitor.
+    pub(crate) fn new(id: Id, monitor: MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        Display {
+            id,
+            name: monitor.name(),
+            position: [position.x, position.y],
+            size: [size.width, size.height],
+            scale_factor: monitor.scale_factor(),
+            refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+            is_primary: primary == Some(&monitor),
+            monitor,
+        }
+    }
+
+    /// The unique identifier for the display.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// A human-readable name for the display, if one is available.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The position of the top-left corner of the display in pixels relative to the top-left of
+    /// the desktop.
+    pub fn position_pixels(&self) -> [i32; 2] {
+        self.position
+    }
+
+    /// The size of the display in pixels.
+    pub fn size_pixels(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// The size of the display in points (pixels divided by the scale factor).
+    pub fn size_points(&self) -> [f32; 2] {
+        let [w, h] = self.size;
+        let sf = self.scale_factor as f32;
+        [w as f32 / sf, h as f32 / sf]
+    }
+
+    /// The area covered by the display in desktop pixel coordinates.
+    ///
+    /// Note that desktop coordinates are measured from the top-left with y increasing downwards,
+    /// such that `rect.bottom()` is the desktop y of the top edge of the display.
+    pub fn rect_pixels(&self) -> Rect {
+        let [x, y] = self.position;
+        let [w, h] = self.size;
+        let (w, h) = (w as f32, h as f32);
+        Rect::from_x_y_w_h(x as f32 + w * 0.5, y as f32 + h * 0.5, w, h)
+    }
+
+    /// The ratio of pixels to points on this display.
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// The refresh rate of the display in hertz, if known.
+    pub fn refresh_rate_hz(&self) -> Option<f64> {
+        self.refresh_rate_millihertz.map(|mhz| mhz as f64 / 1000.0)
+    }
+
+    /// Whether or not this is the system's primary display, if the platform is able to tell.
+    pub fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+
+    /// The underlying `winit` monitor handle.
+    pub fn monitor(&self) -> &MonitorHandle {
+        &self.monitor
+    }
+}
@@ -0,0 +1,329 @@
+//! Press, drag and release handling for interactive points and rects, with snapping and
+//! constraints.
+//!
+//! A **Drag** is stored within your model and updated once per frame. Points and rects may then be
+//! attached to it via **Drag::point** and **Drag::rect**, each of which returns the **Phase** of
+//! the interaction with that value, if any. This removes the need for bespoke mouse state machines
+//! when building small editors within a sketch.
+//!
+//! While dragging, holding the **angle_modifiers** (`Shift` by default) snaps the direction of the
+//! drag to multiples of **angle_step**, while holding the **grid_modifiers** (`Ctrl` by default)
+//! snaps the position to the **grid**.
+//!
+//! ```no_run
+//! use nannou::drag::{Drag, Phase};
+//! use nannou::prelude::*;
+//!
+//! struct Model {
+//!     drag: Drag,
+//!     points: Vec<Vec2>,
+//! }
+//!
+//! fn update(app: &App, model: &mut Model, _update: Update) {
+//!     model.drag.update(app);
+//!     for point in &mut model.points {
+//!         if let Some(Phase::Released) = model.drag.point(point) {
+//!             println!("moved a point to {:?}", point);
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::event::ModifiersState;
+use crate::geom::Rect;
+use crate::glam::Vec2;
+use crate::App;
+
+/// The default distance in points within which the mouse is considered to be over a point.
+pub const DEFAULT_TOLERANCE: f32 = 8.0;
+
+/// The default grid size in points used when snapping to the grid.
+pub const DEFAULT_GRID: f32 = 20.0;
+
+/// The default angle in radians used when snapping the direction of a drag.
+pub const DEFAULT_ANGLE_STEP: f32 = std::f32::consts::PI / 12.0;
+
+/// Tracks the mouse interaction with all values attached via **Drag::point** and **Drag::rect**.
+#[derive(Clone, Debug)]
+pub struct Drag {
+    /// The size of the grid cells used when snapping to the grid.
+    pub grid: f32,
+    /// The angle in radians used when snapping the direction of the drag.
+    pub angle_step: f32,
+    /// The modifier keys that enable snapping to the grid.
+    pub grid_modifiers: ModifiersState,
+    /// The modifier keys that enable snapping the direction of the drag.
+    pub angle_modifiers: ModifiersState,
+    /// Restrict dragging to a single axis.
+    pub lock: Option<Lock>,
+    /// Keep dragged values within the given bounds.
+    pub bounds: Option<Rect>,
+    /// The distance in points within which the mouse is considered to be over a point.
+    pub tolerance: f32,
+    /// Whether or not values may be dragged.
+    pub enabled: bool,
+    mouse: Vec2,
+    mouse_down: bool,
+    mouse_pressed: bool,
+    mods: ModifiersState,
+    next_id: usize,
+    hovered: bool,
+    active: Option<Active>,
+    released: Option<Active>,
+}
+
+/// The stage of the interaction with a dragged value.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Phase {
+    /// The mouse was pressed over the value this frame.
+    Pressed,
+    /// The value is being dragged.
+    Dragged,
+    /// The mouse was released this frame, ending the drag.
+    Released,
+}
+
+/// Restricts dragging to a single axis.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Lock {
+    /// Only the x position may change.
+    Horizontal,
+    /// Only the y position may change.
+    Vertical,
+}
+
+// The drag that is currently in progress.
+#[derive(Copy, Clone, Debug)]
+struct Active {
+    id: usize,
+    mouse: Vec2,
+    position: Vec2,
+}
+
+impl Drag {
+    /// Create a new **Drag** with the default snapping settings and no constraints.
+    pub fn new() -> Self {
+        Drag {
+            grid: DEFAULT_GRID,
+            angle_step: DEFAULT_ANGLE_STEP,
+            grid_modifiers: ModifiersState::CTRL,
+            angle_modifiers: ModifiersState::SHIFT,
+            lock: None,
+            bounds: None,
+            tolerance: DEFAULT_TOLERANCE,
+            enabled: true,
+            mouse: Vec2::ZERO,
+            mouse_down: false,
+            mouse_pressed: false,
+            mods: ModifiersState::empty(),
+            next_id: 0,
+            hovered: false,
+            active: None,
+            released: None,
+        }
+    }
+
+    /// Builder method for specifying the grid size.
+    pub fn with_grid(mut self, grid: f32) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Builder method for specifying the angle step in radians.
+    pub fn with_angle_step(mut self, radians: f32) -> Self {
+        self.angle_step = radians;
+        self
+    }
+
+    /// Builder method for restricting dragging to a single axis.
+    pub fn with_lock(mut self, lock: Lock) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// Builder method for keeping dragged values within the given bounds.
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Update the mouse and keyboard state and begin a new frame of interaction.
+    ///
+    /// This should be called once per frame, before any calls to `point` or `rect`.
+    pub fn update(&mut self, app: &App) {
+        let mouse = app.mouse.position();
+        let down = app.mouse.buttons.left().is_down();
+        self.update_mouse(mouse, down, app.keys.mods);
+    }
+
+    /// Update the mouse and keyboard state from a custom source and begin a new frame of
+    /// interaction.
+    ///
+    /// This is useful when values are drawn with a transformed `Draw` instance, in which case the
+    /// given `mouse` should be in the same coordinate space as the `Draw`.
+    pub fn update_mouse(&mut self, mouse: Vec2, down: bool, mods: ModifiersState) {
+        self.mouse_pressed = down && !self.mouse_down;
+        self.mouse_down = down;
+        self.mouse = mouse;
+        self.mods = mods;
+        self.released = None;
+        if !down {
+            self.released = self.active.take();
+        }
+        self.next_id = 0;
+        self.hovered = false;
+    }
+
+    /// Attach the given point, applying any drag made with the mouse.
+    ///
+    /// The point may be dragged when the mouse is pressed within `tolerance` of it. Values are
+    /// identified by the order in which they are attached, so the same values should be attached
+    /// in the same order each frame.
+    pub fn point(&mut self, point: &mut Vec2) -> Option<Phase> {
+        let hovered = self.mouse.distance(*point) <= self.tolerance;
+        let (phase, position) = self.interact(*point, hovered)?;
+        *point = match self.bounds {
+            Some(bounds) => clamp_to_rect(position, bounds),
+            None => position,
+        };
+        Some(phase)
+    }
+
+    /// Attach the given rect, applying any drag made with the mouse.
+    ///
+    /// The rect may be dragged when the mouse is pressed within it. Snapping applies to the
+    /// centre of the rect, while the **bounds** keep the entire rect in view where possible.
+    pub fn rect(&mut self, rect: &mut Rect) -> Option<Phase> {
+        let hovered = rect.contains(self.mouse);
+        let (phase, position) = self.interact(rect.xy(), hovered)?;
+        let (mut position, wh) = (position, rect.wh());
+        if let Some(bounds) = self.bounds {
+            let half = ((bounds.wh() - wh) * 0.5).max(Vec2::ZERO);
+            let min = bounds.xy() - half;
+            let max = bounds.xy() + half;
+            position = position.clamp(min, max);
+        }
+        *rect = Rect::from_xy_wh(position, wh);
+        Some(phase)
+    }
+
+    /// Whether or not a value is currently being dragged.
+    ///
+    /// This can be used to avoid handling mouse input elsewhere while a drag is in progress.
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Whether or not the mouse is over any of the values attached since the last `update`.
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// The position of the dragged value when the drag began, if one is in progress.
+    ///
+    /// This is useful for drawing guides while snapping.
+    pub fn origin(&self) -> Option<Vec2> {
+        self.active.map(|active| active.position)
+    }
+
+    /// Whether or not snapping to the grid is enabled by the current modifiers.
+    pub fn is_grid_snapping(&self) -> bool {
+        !self.grid_modifiers.is_empty() && self.mods.contains(self.grid_modifiers)
+    }
+
+    /// Whether or not snapping the direction of the drag is enabled by the current modifiers.
+    pub fn is_angle_snapping(&self) -> bool {
+        !self.angle_modifiers.is_empty() && self.mods.contains(self.angle_modifiers)
+    }
+
+    // Advance the interaction for the next attached value, returning its phase and new position.
+    fn interact(&mut self, position: Vec2, hovered: bool) -> Option<(Phase, Vec2)> {
+        let id = self.next_id;
+        self.next_id += 1;
+        if !self.enabled {
+            return None;
+        }
+        self.hovered |= hovered;
+
+        if self.released.map(|active| active.id) == Some(id) {
+            return Some((Phase::Released, position));
+        }
+
+        let mut phase = Phase::Dragged;
+        if self.mouse_pressed && self.active.is_none() && hovered {
+            self.active = Some(Active {
+                id,
+                mouse: self.mouse,
+                position,
+            });
+            phase = Phase::Pressed;
+        }
+
+        let active = self.active.filter(|active| active.id == id)?;
+        let target = active.position + (self.mouse - active.mouse);
+        Some((phase, self.constrain(active.position, target)))
+    }
+
+    // Apply the axis lock and any enabled snapping to a drag from `origin` to `target`.
+    fn constrain(&self, origin: Vec2, target: Vec2) -> Vec2 {
+        let mut p = match self.lock {
+            Some(Lock::Horizontal) => Vec2::new(target.x, origin.y),
+            Some(Lock::Vertical) => Vec2::new(origin.x, target.y),
+            None if self.is_angle_snapping() => snap_angle(origin, target, self.angle_step),
+            None => target,
+        };
+        if self.is_grid_snapping() {
+            let snapped = snap_to_grid(p, self.grid);
+            p = match self.lock {
+                Some(Lock::Horizontal) => Vec2::new(snapped.x, p.y),
+                Some(Lock::Vertical) => Vec2::new(p.x, snapped.y),
+                None => snapped,
+            };
+        }
+        p
+    }
+}
+
+impl Default for Drag {
+    fn default() -> Self {
+        Drag::new()
+    }
+}
+
+/// Round the given point to the nearest intersection of a grid with the given cell size.
+///
+/// Returns the point unchanged if `grid` is not positive.
+///
+/// ```
+/// use nannou::drag::snap_to_grid;
+/// use nannou::glam::Vec2;
+///
+/// assert_eq!(snap_to_grid(Vec2::new(14.0, -6.0), 10.0), Vec2::new(10.0, -10.0));
+/// ```
+pub fn snap_to_grid(point: Vec2, grid: f32) -> Vec2 {
+    if grid > 0.0 {
+        (point / grid).round() * grid
+    } else {
+        point
+    }
+}
+
+/// Project `point` onto the nearest ray from `origin` whose angle is a multiple of `step`
+/// radians.
+///
+/// Returns the point unchanged if `step` is not positive.
+pub fn snap_angle(origin: Vec2, point: Vec2, step: f32) -> Vec2 {
+    let delta = point - origin;
+    if step <= 0.0 || delta == Vec2::ZERO {
+        return point;
+    }
+    let angle = (delta.y.atan2(delta.x) / step).round() * step;
+    let dir = Vec2::new(angle.cos(), angle.sin());
+    origin + dir * delta.dot(dir)
+}
+
+/// Clamp the given point to lie within the given rect.
+pub fn clamp_to_rect(point: Vec2, rect: Rect) -> Vec2 {
+    point.clamp(rect.bottom_left(), rect.top_right())
+}
@@ -0,0 +1,124 @@
+//! Items related to drawing with an isometric or other axonometric projection.
+//!
+//! An **Axonometric** projection maps 3D "world" coordinates onto the screen so that the x and y
+//! axes describe the ground and the z axis points up the screen. A **Draw** produced via
+//! `draw.isometric()` or `draw.axonometric(..)` applies this projection, so 2D primitives drawn
+//! with it lie on the ground plane.
+//!
+//! - Use `.z(height)` to raise the ground plane, e.g. to draw the top of a cube.
+//! - Use `.x_radians(PI / 2.0)` to draw on the vertical plane facing the negative y axis.
+//! - Use `.z_radians(PI / 2.0).x_radians(PI / 2.0)` to draw on the vertical plane facing the
+//!   negative x axis.
+//!
+//! The projection flattens depth so that overlapping shapes are drawn in the order in which they
+//! are submitted. Use **Axonometric::sort_by_depth** to order tiles or cubes from back to front.
+
+use crate::geom::{Point2, Point3};
+use crate::glam::{Mat4, Vec2, Vec3, Vec4};
+use std::cmp::Ordering;
+
+/// An axonometric projection described by the on-screen direction and length of each world axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Axonometric {
+    /// The on-screen vector for one unit along the world x axis.
+    pub x_axis: Vec2,
+    /// The on-screen vector for one unit along the world y axis.
+    pub y_axis: Vec2,
+    /// The on-screen vector for one unit along the world z (up) axis.
+    pub z_axis: Vec2,
+}
+
+impl Axonometric {
+    /// A projection in which the x and y axes are each inclined from the horizontal by the given
+    /// angle in radians, receding to the upper right and upper left respectively.
+    pub fn new(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Axonometric {
+            x_axis: Vec2::new(cos, sin),
+            y_axis: Vec2::new(-cos, sin),
+            z_axis: Vec2::Y,
+        }
+    }
+
+    /// A true isometric projection, with the ground axes inclined by 30 degrees.
+    pub fn isometric() -> Self {
+        Self::new(30f32.to_radians())
+    }
+
+    /// The 2:1 dimetric projection common in pixel art, with the ground axes rising one pixel for
+    /// every two pixels across.
+    pub fn dimetric() -> Self {
+        Self::new(0.5f32.atan())
+    }
+
+    /// The transform matrix that applies the projection.
+    ///
+    /// The z component of all transformed points is `0.0`.
+    pub fn transform(&self) -> Mat4 {
+        Mat4::from_cols(
+            self.x_axis.extend(0.0).extend(0.0),
+            self.y_axis.extend(0.0).extend(0.0),
+            self.z_axis.extend(0.0).extend(0.0),
+            Vec4::W,
+        )
+    }
+
+    /// Project the given world point onto the screen.
+    pub fn project(&self, point: Point3) -> Point2 {
+        self.x_axis * point.x + self.y_axis * point.y + self.z_axis * point.z
+    }
+
+    /// Find the world point at the given height that projects to the given screen point.
+    ///
+    /// This is useful for finding the tile beneath the mouse. Returns `None` if the ground axes
+    /// are parallel on screen.
+    pub fn unproject(&self, point: Point2, height: f32) -> Option<Point3> {
+        let target = point - self.z_axis * height;
+        let det = self.x_axis.perp_dot(self.y_axis);
+        if det == 0.0 {
+            return None;
+        }
+        let x = target.perp_dot(self.y_axis) / det;
+        let y = self.x_axis.perp_dot(target) / det;
+        Some(Vec3::new(x, y, height))
+    }
+
+    /// The direction in world space pointing towards the viewer.
+    pub fn view_direction(&self) -> Vec3 {
+        let row_x = Vec3::new(self.x_axis.x, self.y_axis.x, self.z_axis.x);
+        let row_y = Vec3::new(self.x_axis.y, self.y_axis.y, self.z_axis.y);
+        let dir = row_x.cross(row_y).normalize_or_zero();
+        // The viewer looks down upon the ground.
+        if dir.z < 0.0 {
+            -dir
+        } else {
+            dir
+        }
+    }
+
+    /// The depth of the given world point, increasing towards the viewer.
+    pub fn depth(&self, point: Point3) -> f32 {
+        self.view_direction().dot(point)
+    }
+
+    /// Sort the given items from back to front using the world position returned by `position`.
+    ///
+    /// Drawing the items in the resulting order ensures nearer items overlap those behind them.
+    /// For unit cubes or tiles on a grid, use the position of their centres.
+    pub fn sort_by_depth<T, F>(&self, items: &mut [T], mut position: F)
+    where
+        F: FnMut(&T) -> Point3,
+    {
+        let dir = self.view_direction();
+        items.sort_by(|a, b| {
+            let (a, b) = (dir.dot(position(a)), dir.dot(position(b)));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        });
+    }
+}
+
+impl Default for Axonometric {
+    fn default() -> Self {
+        Self::isometric()
+    }
+}
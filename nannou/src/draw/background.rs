@@ -1,12 +1,45 @@
-use crate::color::{self, IntoLinSrgba, Srgb, Srgba};
+use crate::color::{self, Gradient, GradientSpace, IntoLinSrgba, Srgb, Srgba};
 use crate::draw::properties::ColorScalar;
-use crate::draw::Draw;
+use crate::draw::{renderer, Draw};
+use crate::geom::{self, Rect};
+use crate::wgpu;
+
+// The number of rows between each stop of a gradient fill interpolated outside of linear sRGB.
+const GRADIENT_DIVISIONS: usize = 16;
+
+// The depth of a skybox as a fraction of the far edge of the depth range, keeping it just within
+// the range.
+const SKYBOX_DEPTH: f32 = 0.999;
 
 /// A type used to update the background colour.
 pub struct Background<'a> {
     draw: &'a Draw,
 }
 
+/// Describes how an image background is scaled to fill the target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FillMode {
+    /// Stretch the image to the bounds of the target, ignoring its aspect ratio.
+    Stretch,
+    /// Scale the image to the largest size that fits within the target, preserving its aspect
+    /// ratio. The remaining area is left uncovered.
+    Contain,
+    /// Scale the image to the smallest size that covers the target, preserving its aspect ratio.
+    /// The parts of the image that extend beyond the target are cropped.
+    Cover,
+}
+
+// A fill drawn beneath all other primitives, spanning the target to which the **Draw** is
+// rendered.
+#[derive(Clone, Debug)]
+pub(crate) enum Fill {
+    Gradient(Gradient),
+    Image {
+        texture_view: wgpu::TextureView,
+        mode: FillMode,
+    },
+}
+
 /// Begin coloring the background.
 pub fn new<'a>(draw: &'a Draw) -> Background<'a> {
     Background { draw }
@@ -22,8 +55,64 @@ impl<'a> Background<'a> {
     where
         C: IntoLinSrgba<ColorScalar>,
     {
+        let color = self.draw.linear_color(color);
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.background_color = Some(color);
+        }
+        self
+    }
+
+    /// Fill the background with a vertical gradient between the given colors.
+    ///
+    /// The gradient spans the target to which the **Draw** is rendered and is drawn beneath all
+    /// other primitives, regardless of the order in which they were submitted. It is drawn over
+    /// the clear `color` if one was specified, or over the previous contents of the target
+    /// otherwise. Semi-transparent colors may be used to fade out previous frames in a "trails"
+    /// style sketch.
+    ///
+    /// Replaces any previously specified gradient or image.
+    pub fn gradient<C0, C1>(self, top: C0, bottom: C1) -> Self
+    where
+        C0: IntoLinSrgba<ColorScalar>,
+        C1: IntoLinSrgba<ColorScalar>,
+    {
+        let top = self.draw.linear_color(top);
+        let bottom = self.draw.linear_color(bottom);
+        let gradient = Gradient::new([(0.0, top), (1.0, bottom)]).space(GradientSpace::LinearSrgb);
         if let Ok(mut state) = self.draw.state.try_borrow_mut() {
-            state.background_color = Some(color.into_lin_srgba());
+            state.background_fill = Some(Fill::Gradient(gradient));
+        }
+        self
+    }
+
+    /// Fill the background with a vertical **Gradient**, from its first stop at the top of the
+    /// target to its last stop at the bottom.
+    ///
+    /// Colors are interpolated within the gradient's color space. Otherwise, this behaves the
+    /// same as `gradient`.
+    pub fn gradient_stops(self, gradient: &Gradient) -> Self {
+        for &(_, color) in gradient.stops() {
+            self.draw.linear_color(color);
+        }
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.background_fill = Some(Fill::Gradient(gradient.clone()));
+        }
+        self
+    }
+
+    /// Fill the background with the given texture, scaled to the target via the given mode.
+    ///
+    /// Like `gradient`, the image is drawn beneath all other primitives and over the clear
+    /// `color` or previous contents of the target.
+    ///
+    /// Replaces any previously specified gradient or image.
+    pub fn image(self, view: &dyn wgpu::ToTextureView, mode: FillMode) -> Self {
+        let texture_view = view.to_texture_view();
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            if let Some(audit) = state.color_audit.as_mut() {
+                audit.texture(texture_view.format());
+            }
+            state.background_fill = Some(Fill::Image { texture_view, mode });
         }
         self
     }
@@ -94,3 +183,105 @@ impl<'a> Background<'a> {
         self.color(color::Hsva::new(hue, s, v, a))
     }
 }
+
+impl FillMode {
+    /// The rectangle in which an image of the given size is drawn when filling the target `rect`,
+    /// along with the area of the image to draw in texture coordinates.
+    pub fn layout(&self, [w, h]: [u32; 2], rect: Rect) -> (Rect, Rect) {
+        let full_area = Rect::from_x_y_w_h(0.5, 0.5, 1.0, 1.0);
+        let scale = geom::vec2(rect.w() / w as f32, rect.h() / h as f32);
+        match *self {
+            FillMode::Stretch => (rect, full_area),
+            FillMode::Contain => {
+                let scale = scale.min_element();
+                let dst = Rect::from_xy_wh(rect.xy(), geom::vec2(w as f32, h as f32) * scale);
+                (dst, full_area)
+            }
+            FillMode::Cover => {
+                let area_wh = scale / scale.max_element();
+                let area = Rect::from_xy_wh(geom::vec2(0.5, 0.5), area_wh);
+                (rect, area)
+            }
+        }
+    }
+}
+
+// Produce a **Draw** describing the background fill of the given **Draw** for a target covering
+// the given rect, if it has one.
+pub(crate) fn fill_draw(draw: &Draw, rect: Rect) -> Option<Draw> {
+    let state = draw.state.borrow();
+    let skybox_fov = state
+        .lighting
+        .environment
+        .as_ref()
+        .and_then(|env| env.skybox);
+    let fill = state.background_fill.clone();
+    drop(state);
+    let fill_draw = Draw::new();
+    // The environment is drawn behind all other primitives, at the far edge of the depth range.
+    if let Some(fov) = skybox_fov {
+        let far = rect.w().max(rect.h()) * SKYBOX_DEPTH;
+        let scale = (fov * 0.5).tan() / (rect.h() * 0.5);
+        let corner = |x: f32, y: f32| (geom::pt3(x, y, -far), geom::pt2(x, y) * scale);
+        let (l, r, b, t) = (rect.left(), rect.right(), rect.bottom(), rect.top());
+        let (bl, br, tr, tl) = (corner(l, b), corner(r, b), corner(r, t), corner(l, t));
+        let points = [bl, br, tr, bl, tr, tl];
+        fill_draw
+            .mesh()
+            .points_with_mode(renderer::VertexMode::Skybox, points);
+        return Some(fill_draw);
+    }
+    let fill = fill?;
+    match fill {
+        Fill::Gradient(gradient) => {
+            let stops = gradient.stops();
+            let (start, end) = match (stops.first(), stops.last()) {
+                (Some(&(start, _)), Some(&(end, _))) => (start, end),
+                _ => return None,
+            };
+            // Vertex colors are interpolated in linear sRGB, so subdivide the space between stops
+            // to approximate other spaces.
+            let divisions = match gradient.color_space() {
+                GradientSpace::LinearSrgb => 1,
+                _ => GRADIENT_DIVISIONS,
+            };
+            let mut rows = vec![start];
+            for pair in stops.windows(2) {
+                let (a, b) = (pair[0].0, pair[1].0);
+                rows.extend((1..=divisions).map(|i| a + (b - a) * i as f32 / divisions as f32));
+            }
+            // A single stop fills the target with its color.
+            if rows.len() == 1 {
+                rows.push(end);
+            }
+            let range = end - start;
+            let points = rows.iter().enumerate().flat_map(|(ix, &t)| {
+                let f = if range > 0.0 {
+                    (t - start) / range
+                } else {
+                    ix as f32
+                };
+                let y = rect.top() - rect.h() * f;
+                let color = gradient.at(t);
+                [
+                    (geom::pt3(rect.left(), y, 0.0), color),
+                    (geom::pt3(rect.right(), y, 0.0), color),
+                ]
+            });
+            let indices = (0..rows.len() - 1).flat_map(|r| {
+                let i = r * 2;
+                [i, i + 1, i + 3, i, i + 3, i + 2]
+            });
+            fill_draw.mesh().indexed_colored(points, indices);
+        }
+        Fill::Image { texture_view, mode } => {
+            let (dst, area) = mode.layout(texture_view.size(), rect);
+            fill_draw
+                .texture(&texture_view)
+                .xy(dst.xy())
+                .wh(dst.wh())
+                .area(area);
+        }
+    }
+    Some(fill_draw)
+}
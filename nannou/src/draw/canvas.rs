@@ -0,0 +1,353 @@
+//! A logical canvas that may be larger than any single window or texture.
+//!
+//! Large installations (e.g. a 12000x3000 LED wall) often require a canvas that exceeds the
+//! maximum texture size supported by the GPU. A **Canvas** splits the canvas into a grid of
+//! offscreen tiles, renders a **Draw** to each of them, and provides methods for previewing the
+//! whole canvas in a window with pan and zoom, or for cropping the region associated with a
+//! particular output.
+
+use crate::draw::{self, Context, Draw, DrawCommand, Renderer, Scissor};
+use crate::geom::{self, Rect};
+use crate::glam::{Mat4, Vec2};
+use crate::wgpu;
+
+/// A builder for a **Canvas**.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    tile_size: Option<u32>,
+    format: wgpu::TextureFormat,
+    renderer: draw::renderer::Builder,
+}
+
+/// A logical canvas rendered into a grid of offscreen tiles.
+///
+/// The canvas is described in points, with the origin at its centre, matching the coordinates of
+/// the **Draw** API. One point maps to one pixel.
+#[derive(Debug)]
+pub struct Canvas {
+    rect: Rect,
+    tile_size: u32,
+    tiles: Vec<Tile>,
+    renderer: Renderer,
+}
+
+/// A single offscreen target, covering a region of the canvas.
+#[derive(Debug)]
+pub struct Tile {
+    rect: Rect,
+    texture: wgpu::Texture,
+}
+
+/// A pan and zoom view onto a **Canvas**, useful for previewing the canvas within a window.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Preview {
+    /// The point on the canvas displayed at the centre of the preview.
+    pub center: Vec2,
+    /// The scale at which the canvas is displayed.
+    pub zoom: f32,
+}
+
+impl Builder {
+    /// The default maximum size of each tile along both axes.
+    ///
+    /// The tile size is limited further by the device's maximum texture dimension.
+    pub const DEFAULT_TILE_SIZE: u32 = 4096;
+
+    /// The default texture format for the tiles.
+    pub const DEFAULT_FORMAT: wgpu::TextureFormat = crate::frame::Frame::TEXTURE_FORMAT;
+
+    /// Begin building a **Canvas**.
+    pub fn new() -> Self {
+        Builder {
+            tile_size: None,
+            format: Self::DEFAULT_FORMAT,
+            renderer: Default::default(),
+        }
+    }
+
+    /// Specify the maximum width and height of each tile in pixels.
+    pub fn tile_size(mut self, size: u32) -> Self {
+        self.tile_size = Some(size);
+        self
+    }
+
+    /// Specify the texture format of the tiles.
+    pub fn format(mut self, format: wgpu::TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Specify the builder used for the renderer that draws to each of the tiles.
+    pub fn renderer(mut self, renderer: draw::renderer::Builder) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Build a canvas of the given size in pixels.
+    pub fn build(self, device: &wgpu::Device, [w, h]: [u32; 2]) -> Canvas {
+        let max = device.limits().max_texture_dimension_2d;
+        let tile_size = self
+            .tile_size
+            .unwrap_or(Self::DEFAULT_TILE_SIZE)
+            .min(max)
+            .max(1);
+        let (w, h) = (w.max(1), h.max(1));
+        let rect = Rect::from_w_h(w as f32, h as f32);
+
+        // All tiles share the same size so that the renderer's depth texture may be reused. Tiles
+        // along the right and bottom edges may extend beyond the canvas.
+        let tile_w = tile_size.min(w);
+        let tile_h = tile_size.min(h);
+        let cols = w.div_ceil(tile_w);
+        let rows = h.div_ceil(tile_h);
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC;
+        let mut tiles = Vec::with_capacity((cols * rows) as usize);
+        for row in 0..rows {
+            for col in 0..cols {
+                let left = rect.left() + (col * tile_w) as f32;
+                let top = rect.top() - (row * tile_h) as f32;
+                let tile_rect = Rect::from_corners(
+                    [left, top - tile_h as f32].into(),
+                    [left + tile_w as f32, top].into(),
+                );
+                let texture = wgpu::TextureBuilder::new()
+                    .size([tile_w, tile_h])
+                    .format(self.format)
+                    .usage(usage)
+                    .build(device);
+                tiles.push(Tile {
+                    rect: tile_rect,
+                    texture,
+                });
+            }
+        }
+
+        let renderer = self
+            .renderer
+            .build(device, [tile_w, tile_h], 1.0, 1, self.format);
+        Canvas {
+            rect,
+            tile_size,
+            tiles,
+            renderer,
+        }
+    }
+}
+
+impl Canvas {
+    /// The bounds of the canvas in points, centred on the origin.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The maximum size of each tile in pixels.
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// The tiles that make up the canvas, ordered row by row from the top-left.
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Render the given **Draw** to each of the canvas tiles.
+    ///
+    /// The **Draw** should be described in canvas coordinates. Like all rendering via the
+    /// **draw::Renderer**, the **Draw** is drained of its commands.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        draw: &Draw,
+    ) {
+        let commands: Vec<DrawCommand> = draw.drain_commands().collect();
+        for tile in &self.tiles {
+            // Shift the canvas so that the centre of the tile lies at the origin.
+            let offset = -tile.rect.xy();
+            let shift = |ctxt: &Context| {
+                let mut ctxt = ctxt.clone();
+                ctxt.transform = Mat4::from_translation(offset.extend(0.0)) * ctxt.transform;
+                if let Scissor::Rect(rect) = ctxt.scissor {
+                    ctxt.scissor = Scissor::Rect(rect.shift(offset));
+                }
+                ctxt
+            };
+            let first = DrawCommand::Context(shift(&Context::default()));
+            let shifted = commands.iter().map(|cmd| match cmd {
+                DrawCommand::Context(ctxt) => DrawCommand::Context(shift(ctxt)),
+                cmd => cmd.clone(),
+            });
+            draw.state.borrow_mut().draw_commands =
+                Some(first).into_iter().chain(shifted).map(Some).collect();
+            self.renderer
+                .render_to_texture(device, encoder, draw, &tile.texture);
+        }
+    }
+
+    /// Produce an iterator yielding each tile that overlaps the given region of the canvas, along
+    /// with the overlapping area in canvas coordinates.
+    pub fn tiles_in(&self, region: Rect) -> impl Iterator<Item = (&Tile, Rect)> {
+        self.tiles
+            .iter()
+            .filter_map(move |tile| tile.rect.overlap(region).map(|overlap| (tile, overlap)))
+    }
+
+    /// Draw the given region of the canvas into the `dst` rectangle of the given **Draw**.
+    ///
+    /// This is useful for displaying the region of the canvas associated with a particular
+    /// output window, or for previewing the canvas.
+    pub fn draw_region(&self, draw: &Draw, region: Rect, dst: Rect) {
+        if region.w() <= 0.0 || region.h() <= 0.0 {
+            return;
+        }
+        let scale = dst.wh() / region.wh();
+        for (tile, overlap) in self.tiles_in(region) {
+            let area = tile.tex_coords(overlap);
+            let xy = dst.xy() + (overlap.xy() - region.xy()) * scale;
+            let wh = overlap.wh() * scale;
+            draw.texture(&tile.texture).xy(xy).wh(wh).area(area);
+        }
+    }
+
+    /// Encode commands that copy the given region of the canvas into the given texture.
+    ///
+    /// The region is rounded to the nearest pixel and clipped to the bounds of the `dst` texture,
+    /// the top-left corner of which is aligned with the top-left corner of the region. The `dst`
+    /// texture must have the same format as the canvas tiles and support `COPY_DST` usage. This is
+    /// useful for cropping the region of the canvas associated with an output that consumes
+    /// textures, e.g. a video sender.
+    pub fn encode_copy_region(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        region: Rect,
+        dst: &wgpu::Texture,
+    ) {
+        let [dst_w, dst_h] = dst.size();
+        let region = Rect::from_corners(
+            [region.left().round(), region.top().round() - dst_h as f32].into(),
+            [region.left().round() + dst_w as f32, region.top().round()].into(),
+        );
+        for (tile, overlap) in self.tiles_in(region) {
+            let width = overlap.w().round() as u32;
+            let height = overlap.h().round() as u32;
+            if width == 0 || height == 0 {
+                continue;
+            }
+            let src_origin = wgpu::Origin3d {
+                x: (overlap.left() - tile.rect.left()).round() as u32,
+                y: (tile.rect.top() - overlap.top()).round() as u32,
+                z: 0,
+            };
+            let dst_origin = wgpu::Origin3d {
+                x: (overlap.left() - region.left()).round() as u32,
+                y: (region.top() - overlap.top()).round() as u32,
+                z: 0,
+            };
+            let src = wgpu::ImageCopyTexture {
+                origin: src_origin,
+                ..tile.texture.as_image_copy()
+            };
+            let dst = wgpu::ImageCopyTexture {
+                origin: dst_origin,
+                ..dst.as_image_copy()
+            };
+            let extent = wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            };
+            encoder.copy_texture_to_texture(src, dst, extent);
+        }
+    }
+}
+
+impl Tile {
+    /// The region of the canvas covered by the tile.
+    ///
+    /// Tiles along the right and bottom edges may extend beyond the bounds of the canvas.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The texture to which the tile is rendered.
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    // The texture coordinates of the given region of the canvas within this tile.
+    fn tex_coords(&self, region: Rect) -> Rect {
+        let r = self.rect;
+        let x = geom::Range::new(
+            (region.left() - r.left()) / r.w(),
+            (region.right() - r.left()) / r.w(),
+        );
+        let y = geom::Range::new(
+            (region.bottom() - r.bottom()) / r.h(),
+            (region.top() - r.bottom()) / r.h(),
+        );
+        Rect { x, y }
+    }
+}
+
+impl Preview {
+    /// The smallest zoom permitted by `zoom_at`.
+    pub const MIN_ZOOM: f32 = 0.001;
+
+    /// A preview scaled so that the whole canvas fits within the given window rect.
+    pub fn fit(canvas: &Canvas, window: Rect) -> Self {
+        let c = canvas.rect();
+        let zoom = (window.w() / c.w()).min(window.h() / c.h());
+        Preview {
+            center: c.xy(),
+            zoom,
+        }
+    }
+
+    /// The region of the canvas visible within the given window rect.
+    pub fn region(&self, window: Rect) -> Rect {
+        let zoom = self.zoom.max(Self::MIN_ZOOM);
+        Rect::from_xy_wh(self.center, window.wh() / zoom)
+    }
+
+    /// Map a point in the window to the canvas.
+    pub fn window_to_canvas(&self, window: Rect, point: Vec2) -> Vec2 {
+        let zoom = self.zoom.max(Self::MIN_ZOOM);
+        self.center + (point - window.xy()) / zoom
+    }
+
+    /// Pan the preview by the given distance in window points, e.g. the mouse drag delta.
+    pub fn pan(&mut self, delta: Vec2) {
+        let zoom = self.zoom.max(Self::MIN_ZOOM);
+        self.center -= delta / zoom;
+    }
+
+    /// Multiply the zoom by `factor`, keeping the canvas point under `point` in place.
+    pub fn zoom_at(&mut self, window: Rect, point: Vec2, factor: f32) {
+        let before = self.window_to_canvas(window, point);
+        self.zoom = (self.zoom * factor).max(Self::MIN_ZOOM);
+        let after = self.window_to_canvas(window, point);
+        self.center += before - after;
+    }
+
+    /// Draw the visible region of the canvas to fill the given window rect.
+    pub fn draw(&self, canvas: &Canvas, draw: &Draw, window: Rect) {
+        canvas.draw_region(draw, self.region(window), window);
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Preview {
+    fn default() -> Self {
+        Preview {
+            center: Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! A debug mode for detecting likely mix-ups between sRGB and linear sRGB colors.
+//!
+//! nannou blends and renders colors in linear sRGB, converting the colors given to the **Draw**
+//! API via `ColorExt::to_linear`. This is correct as long as each color's type matches the space
+//! in which its components were chosen, e.g. a color picked in an image editor should be
+//! described as `Srgb`, not `LinSrgb`. The audit mode cannot know where a value came from, but it
+//! can flag the situations in which such mix-ups are likely.
+//!
+//! Enable the audit via `Draw::color_audit`. Each kind of warning is logged to stderr the first
+//! time it occurs and may be retrieved via `Draw::color_audit_warnings`, e.g. to display them in
+//! an overlay.
+
+use crate::color::{ColorSpace, LinSrgba};
+use crate::wgpu;
+use std::fmt;
+
+/// A likely mismatch between sRGB and linear sRGB detected by the color audit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Warning {
+    /// Colors described in both sRGB and linear sRGB were submitted within the same frame.
+    ///
+    /// This is often intended, but is also the most common symptom of linear values having been
+    /// chosen by eye or copied from an sRGB color picker.
+    MixedColorSpaces,
+    /// An sRGB color was given components greater than `1.0`.
+    ///
+    /// sRGB is not suited to describing HDR values. Describe these in linear sRGB instead.
+    SrgbOutOfRange,
+    /// A texture with an 8-bit linear format was drawn.
+    ///
+    /// Image data is almost always sRGB encoded, in which case the texture should use the sRGB
+    /// variant of its format (e.g. `Rgba8UnormSrgb`) so that it is decoded when sampled.
+    LinearTexture(wgpu::TextureFormat),
+}
+
+// The state of the color audit for a single **Draw**.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ColorAudit {
+    // The color spaces used since the audit was last reset.
+    used_srgb: bool,
+    used_linear: bool,
+    // Every kind of warning that has occurred, in order of first occurrence.
+    warnings: Vec<Warning>,
+}
+
+impl ColorAudit {
+    // Inspect a color described in the given space.
+    pub(crate) fn color(&mut self, space: ColorSpace, color: LinSrgba) {
+        match space {
+            ColorSpace::Srgb => {
+                self.used_srgb = true;
+                if color.red > 1.0 || color.green > 1.0 || color.blue > 1.0 {
+                    self.warn(Warning::SrgbOutOfRange);
+                }
+            }
+            ColorSpace::LinearSrgb => self.used_linear = true,
+            ColorSpace::Other => (),
+        }
+        if self.used_srgb && self.used_linear {
+            self.warn(Warning::MixedColorSpaces);
+        }
+    }
+
+    // Inspect the format of a texture that is being drawn.
+    pub(crate) fn texture(&mut self, format: wgpu::TextureFormat) {
+        match format {
+            wgpu::TextureFormat::R8Unorm
+            | wgpu::TextureFormat::Rg8Unorm
+            | wgpu::TextureFormat::Rgba8Unorm
+            | wgpu::TextureFormat::Bgra8Unorm => self.warn(Warning::LinearTexture(format)),
+            _ => (),
+        }
+    }
+
+    // Begin a new frame, forgetting the spaces used so far but retaining the warnings.
+    pub(crate) fn reset(&mut self) {
+        self.used_srgb = false;
+        self.used_linear = false;
+    }
+
+    pub(crate) fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        if !self.warnings.contains(&warning) {
+            eprintln!("nannou color audit: {}", warning);
+            self.warnings.push(warning);
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Warning::MixedColorSpaces => write!(
+                f,
+                "colors were described in both sRGB and linear sRGB within the same frame - \
+                 ensure linear colors were not chosen in sRGB"
+            ),
+            Warning::SrgbOutOfRange => write!(
+                f,
+                "an sRGB color has components greater than 1.0 - describe HDR colors in linear \
+                 sRGB instead"
+            ),
+            Warning::LinearTexture(format) => write!(
+                f,
+                "a texture with the linear format {:?} was drawn - if it contains image data, \
+                 use the sRGB variant of the format",
+                format
+            ),
+        }
+    }
+}
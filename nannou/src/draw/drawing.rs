@@ -1,12 +1,16 @@
 use crate::color::IntoLinSrgba;
 use crate::draw::mesh::vertex::{Color, TexCoords};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
+use crate::draw::properties::sdf::SdfOptions;
 use crate::draw::properties::{
-    ColorScalar, SetColor, SetDimensions, SetFill, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, SetColor, SetDimensions, SetFill, SetMaterial, SetOrientation, SetPosition,
+    SetSdf, SetStroke,
 };
 use crate::draw::{self, Draw};
 use crate::geom::{Point2, Point3};
 use crate::glam::{Quat, Vec2, Vec3};
+use crate::wgpu;
 use lyon::path::PathEvent;
 use lyon::tessellation::{FillOptions, LineCap, LineJoin, StrokeOptions};
 use std::marker::PhantomData;
@@ -108,6 +112,17 @@ impl<'a, T> Drawing<'a, T> {
         self.finish_inner()
     }
 
+    /// Assign an ID to the primitive by which it may be found via `Draw::pick` or `App::pick`.
+    ///
+    /// Only primitives with an ID are tested when picking, and so only they incur the cost of
+    /// retaining their geometry between frames.
+    pub fn pick_id(self, id: u64) -> Self {
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.pick_ids.insert(self.index, id);
+        }
+        self
+    }
+
     // Map the given function onto the primitive stored within **Draw** at `index`.
     //
     // The functionn is only applied if the node has not yet been **Drawn**.
@@ -160,6 +175,20 @@ impl<'a, T> Drawing<'a, T> {
         }
     }
 
+    // Apply the given function to the primitive stored within **Draw** at `index` along with the
+    // intermediary state, returning the result.
+    //
+    // Returns `None` if the node has already been **Drawn**.
+    pub(crate) fn with_primitive<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&Primitive, &super::IntermediaryState) -> R,
+    {
+        let state = self.draw.state.try_borrow().ok()?;
+        let primitive = state.drawing.get(&self.index)?;
+        let intermediary_state = state.intermediary_state.try_borrow().ok()?;
+        Some(f(primitive, &intermediary_state))
+    }
+
     /// Apply the given function to the type stored within **Draw**.
     ///
     /// The function is only applied if the node has not yet been **Drawn**.
@@ -197,6 +226,19 @@ impl<'a, T> Drawing<'a, T> {
             ty2.into()
         })
     }
+
+    // Inspect the format of a texture submitted for drawing if the color audit is enabled.
+    pub(crate) fn audit_texture(&self, format: crate::wgpu::TextureFormat) {
+        self.draw.audit_texture(format)
+    }
+
+    // Convert the given color to linear sRGBA, inspecting it if the color audit is enabled.
+    pub(crate) fn linear_color<C>(&self, color: C) -> draw::properties::LinSrgba
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.draw.linear_color(color)
+    }
 }
 
 // SetColor implementations.
@@ -215,6 +257,7 @@ where
     where
         C: IntoLinSrgba<ColorScalar>,
     {
+        let color = self.linear_color(color);
         self.map_ty(|ty| SetColor::color(ty, color))
     }
 
@@ -689,6 +732,11 @@ where
         self.map_ty(|ty| ty.stroke_weight(stroke_weight))
     }
 
+    /// Stroke exactly one physical pixel wide, regardless of the scale factor and transform.
+    pub fn stroke_weight_hairline(self) -> Self {
+        self.map_ty(|ty| ty.stroke_weight_hairline())
+    }
+
     /// Describes the limit before miter lines will clip, as described in the SVG spec.
     ///
     /// Must be greater than or equal to `1.0`.
@@ -706,3 +754,98 @@ where
         self.map_ty(|ty| ty.stroke_opts(opts))
     }
 }
+
+// SetSdf methods
+
+impl<'a, T> Drawing<'a, T>
+where
+    T: SetSdf + Into<Primitive>,
+    Primitive: Into<Option<T>>,
+{
+    /// Render the primitive as a signed distance field with analytic anti-aliasing rather than
+    /// as a tessellated mesh.
+    ///
+    /// SDF shapes remain crisp at any zoom level and do not require MSAA.
+    pub fn sdf(self) -> Self {
+        self.map_ty(|ty| ty.sdf())
+    }
+
+    /// Specify the whole set of SDF options, enabling SDF rendering.
+    pub fn sdf_opts(self, opts: SdfOptions) -> Self {
+        self.map_ty(|ty| ty.sdf_opts(opts))
+    }
+
+    /// Round the corners of the SDF shape with the given radius, enabling SDF rendering.
+    ///
+    /// Only applies to box shapes. A radius of half the shortest side produces a capsule.
+    pub fn sdf_corner_radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.sdf_corner_radius(radius))
+    }
+
+    /// Draw a soft glow of the given radius and color around the shape, enabling SDF rendering.
+    pub fn sdf_glow<C>(self, radius: f32, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.linear_color(color);
+        self.map_ty(|ty| ty.sdf_glow(radius, color))
+    }
+
+    /// Draw a soft shadow beneath the shape, enabling SDF rendering.
+    ///
+    /// The `blur` describes the distance over which the edge of the shadow fades out.
+    pub fn sdf_shadow<C>(self, offset: Vec2, blur: f32, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.linear_color(color);
+        self.map_ty(|ty| ty.sdf_shadow(offset, blur, color))
+    }
+}
+
+// SetMaterial methods
+
+impl<'a, T> Drawing<'a, T>
+where
+    T: SetMaterial + Into<Primitive>,
+    Primitive: Into<Option<T>>,
+{
+    /// Specify the whole physically based material of the primitive, implying that it is lit.
+    pub fn material(self, material: Material) -> Self {
+        self.map_ty(|ty| ty.material(material))
+    }
+
+    /// How metallic the surface is, from `0.0` (dielectric, e.g. plastic) to `1.0` (metal).
+    ///
+    /// Implies that the primitive is lit, as though drawn via `Draw::lit`.
+    pub fn metallic(self, metallic: f32) -> Self {
+        self.map_ty(|ty| ty.metallic(metallic))
+    }
+
+    /// How rough the surface is, from `0.0` (a sharp, mirror-like highlight) to `1.0` (a broad,
+    /// dull highlight).
+    ///
+    /// Implies that the primitive is lit, as though drawn via `Draw::lit`.
+    pub fn roughness(self, roughness: f32) -> Self {
+        self.map_ty(|ty| ty.roughness(roughness))
+    }
+
+    /// The color of the light emitted by the surface, regardless of the lights of the **Draw**.
+    ///
+    /// Implies that the primitive is lit, as though drawn via `Draw::lit`.
+    pub fn emissive<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.linear_color(color);
+        self.map_ty(|ty| ty.emissive(color))
+    }
+
+    /// A tangent space normal map perturbing the normals of the surface, sampled via the texture
+    /// coordinates of the primitive.
+    ///
+    /// Implies that the primitive is lit, as though drawn via `Draw::lit`.
+    pub fn normal_map(self, view: &dyn wgpu::ToTextureView) -> Self {
+        self.map_ty(|ty| ty.normal_map(view))
+    }
+}
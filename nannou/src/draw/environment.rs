@@ -0,0 +1,242 @@
+//! Environment maps for lighting primitives with the surrounding scene and drawing skyboxes.
+//!
+//! An **EnvironmentMap** is created from an equirectangular (latitude-longitude) HDR image, e.g.
+//! via `EnvironmentMap::from_path(app, "sky.hdr")`. The image is prefiltered into a pair of
+//! cubemaps on the GPU: one describing the diffuse light arriving at each surface orientation and
+//! one describing the reflections seen by surfaces of increasing roughness.
+//!
+//! See `Draw::environment_map` for applying the map to lit primitives.
+
+use crate::image;
+use crate::wgpu;
+use std::path::Path;
+use wgpu::util::DeviceExt;
+
+/// The width and height of each face of the specular cubemap, in texels.
+pub const SPECULAR_SIZE: u32 = 256;
+
+/// The number of mip levels of the specular cubemap, each describing the reflections of a rougher
+/// surface than the last.
+pub const SPECULAR_MIP_LEVELS: u32 = 6;
+
+/// The width and height of each face of the irradiance cubemap, in texels.
+pub const IRRADIANCE_SIZE: u32 = 32;
+
+/// The number of samples of the source image taken per cubemap texel when prefiltering.
+const SAMPLE_COUNT: u32 = 512;
+
+/// The prefiltered cubemaps describing the light surrounding a scene.
+///
+/// Cloning an **EnvironmentMap** is cheap, as the GPU resources are shared.
+#[derive(Clone, Debug)]
+pub struct EnvironmentMap {
+    _irradiance_texture: wgpu::Texture,
+    _specular_texture: wgpu::Texture,
+    irradiance: wgpu::TextureView,
+    specular: wgpu::TextureView,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Params {
+    mip_count: u32,
+    sample_count: u32,
+}
+
+impl EnvironmentMap {
+    /// The format of the prefiltered cubemaps.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    /// Load an equirectangular HDR image (`.hdr`) from the given path and prefilter it into an
+    /// environment map.
+    ///
+    /// The device and queue `src` can be either the `App`, a `Window`, a `wgpu::DeviceQueuePair`
+    /// or a tuple `(&wgpu::Device, &wgpu::Queue)`. Note that the environment map may only be used
+    /// with the device with which it was created.
+    pub fn from_path<T, P>(src: T, path: P) -> image::ImageResult<Self>
+    where
+        T: wgpu::WithDeviceQueuePair,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        src.with_device_queue_pair(|device, queue| Self::load_from_path(device, queue, path))
+    }
+
+    /// Load an equirectangular HDR image (`.hdr`) from the given path and prefilter it into an
+    /// environment map.
+    pub fn load_from_path<P>(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        path: P,
+    ) -> image::ImageResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let file = std::fs::File::open(path).map_err(image::ImageError::IoError)?;
+        let decoder = image::codecs::hdr::HdrDecoder::new(std::io::BufReader::new(file))?;
+        let meta = decoder.metadata();
+        let pixels: Vec<[f32; 4]> = decoder
+            .read_image_hdr()?
+            .into_iter()
+            .map(|image::Rgb([r, g, b])| [r, g, b, 1.0])
+            .collect();
+        let size = [meta.width, meta.height];
+        Ok(Self::from_equirectangular(device, queue, size, &pixels))
+    }
+
+    /// Prefilter the given equirectangular image into an environment map.
+    ///
+    /// `pixels` are linear RGBA values in rows from the top of the image, where the top of the
+    /// image lies along the positive y axis and its centre along the negative z axis.
+    ///
+    /// **Panics** if the number of pixels does not match the given size.
+    pub fn from_equirectangular(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        [width, height]: [u32; 2],
+        pixels: &[[f32; 4]],
+    ) -> Self {
+        assert_eq!(
+            pixels.len(),
+            (width * height) as usize,
+            "the number of pixels must match the size of the image",
+        );
+
+        // Upload the source image.
+        let source = wgpu::TextureBuilder::new()
+            .size([width, height])
+            .format(wgpu::TextureFormat::Rgba32Float)
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+            .build(device);
+        let layout = wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(width * std::mem::size_of::<[f32; 4]>() as u32),
+            rows_per_image: None,
+        };
+        let data = unsafe { wgpu::bytes::from_slice(pixels) };
+        queue.write_texture(source.as_image_copy(), data, layout, source.extent());
+        let source_view = source.view().build();
+
+        // Prefilter the source image into each face and mip level of the cubemaps.
+        let irradiance = create_cubemap(device, IRRADIANCE_SIZE, 1);
+        let specular = create_cubemap(device, SPECULAR_SIZE, SPECULAR_MIP_LEVELS);
+        let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .uniform_buffer(wgpu::ShaderStages::FRAGMENT, false)
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                wgpu::TextureSampleType::Float { filterable: false },
+            )
+            .build(device);
+        let params = Params {
+            mip_count: SPECULAR_MIP_LEVELS,
+            sample_count: SAMPLE_COUNT,
+        };
+        let params_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou EnvironmentMap params_buffer"),
+            contents: unsafe { wgpu::bytes::from(&params) },
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .buffer::<Params>(&params_buffer, 0..1)
+            .texture_view(&source_view)
+            .build(device, &bind_group_layout);
+        let module =
+            device.create_shader_module(wgpu::include_wgsl!("renderer/shaders/environment.wgsl"));
+        let pipeline = |entry_point| {
+            wgpu::RenderPipelineBuilder::from_layout_descriptor(&[&bind_group_layout][..], &module)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(&module)
+                .fragment_entry_point(entry_point)
+                .color_format(Self::FORMAT)
+                .build(device)
+        };
+        let irradiance_pipeline = pipeline("fs_irradiance");
+        let specular_pipeline = pipeline("fs_specular");
+
+        let desc = wgpu::CommandEncoderDescriptor {
+            label: Some("nannou_environment_map_prefilter"),
+        };
+        let mut encoder = device.create_command_encoder(&desc);
+        let (irradiance_target, specular_target) = (
+            (&irradiance_pipeline, &irradiance),
+            (&specular_pipeline, &specular),
+        );
+        let targets = (0..6).map(|face| (irradiance_target, 0, face)).chain(
+            (0..SPECULAR_MIP_LEVELS)
+                .flat_map(|level| (0..6).map(move |face| (specular_target, level, face))),
+        );
+        for ((pipeline, texture), level, face) in targets {
+            let view = texture
+                .view()
+                .dimension(wgpu::TextureViewDimension::D2)
+                .layer(face)
+                .level(level)
+                .build();
+            let mut render_pass = wgpu::RenderPassBuilder::new()
+                .color_attachment(&view, |color| color)
+                .begin(&mut encoder);
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            // The instance index describes the face and mip level within the shader.
+            let instance = level * 6 + face;
+            render_pass.draw(0..3, instance..instance + 1);
+        }
+        queue.submit(Some(encoder.finish()));
+
+        EnvironmentMap {
+            irradiance: cube_view(&irradiance),
+            specular: cube_view(&specular),
+            _irradiance_texture: irradiance,
+            _specular_texture: specular,
+        }
+    }
+
+    /// A black environment map that contributes no light.
+    pub(crate) fn black(device: &wgpu::Device) -> Self {
+        let texture = create_cubemap(device, 1, 1);
+        EnvironmentMap {
+            irradiance: cube_view(&texture),
+            specular: cube_view(&texture),
+            _irradiance_texture: texture.clone(),
+            _specular_texture: texture,
+        }
+    }
+
+    /// A cube view of the irradiance cubemap.
+    pub fn irradiance_view(&self) -> &wgpu::TextureView {
+        &self.irradiance
+    }
+
+    /// A cube view of the specular cubemap, in which each mip level describes the reflections of a
+    /// rougher surface than the last.
+    pub fn specular_view(&self) -> &wgpu::TextureView {
+        &self.specular
+    }
+}
+
+impl PartialEq for EnvironmentMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.irradiance.id() == other.irradiance.id() && self.specular.id() == other.specular.id()
+    }
+}
+
+fn create_cubemap(device: &wgpu::Device, size: u32, mip_levels: u32) -> wgpu::Texture {
+    wgpu::TextureBuilder::new()
+        .size([size, size])
+        .depth(6)
+        .dimension(wgpu::TextureDimension::D2)
+        .mip_level_count(mip_levels)
+        .format(EnvironmentMap::FORMAT)
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+        .build(device)
+}
+
+fn cube_view(texture: &wgpu::Texture) -> wgpu::TextureView {
+    texture
+        .view()
+        .dimension(wgpu::TextureViewDimension::Cube)
+        .array_layer_count(Some(6))
+        .build()
+}
@@ -0,0 +1,146 @@
+//! Items for producing output from a **Draw** without a window or GPU.
+//!
+//! The **draw::Renderer** requires a `wgpu::Device` in order to render to a texture. Generators
+//! run from the command line, server-side rendering and tests are often better served by the
+//! geometry itself. `render_to_mesh` tessellates the primitives of a **Draw** into a single
+//! **draw::Mesh**, while `render_to_svg` produces an SVG document from the paths described in the
+//! `vector` module.
+//!
+//! Neither function requires an **App**, so a **Draw** may be created via `Draw::new` and used
+//! entirely on the CPU.
+//!
+//! ```
+//! use nannou::draw::{self, Draw};
+//! use nannou::geom::Rect;
+//!
+//! let draw = Draw::new();
+//! draw.ellipse().w_h(100.0, 100.0);
+//! let mesh = draw::render_to_mesh(&draw);
+//! assert!(mesh.triangle_count() > 0);
+//! let svg = draw::render_to_svg(&draw, Rect::from_w_h(200.0, 200.0));
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use crate::color::{ColorExt, LinSrgba};
+use crate::draw::primitive::Primitive;
+use crate::draw::renderer::tessellate::{self, Command};
+use crate::draw::vector::{self, Style};
+use crate::draw::{self, Draw};
+use crate::geom::Rect;
+use lyon::tessellation::{FillRule, LineCap, LineJoin};
+use std::fmt::Write;
+
+/// Tessellate the primitives submitted to the given **Draw** into a single mesh.
+///
+/// Vertices are positioned in the coordinate space of the **Draw** with the transform of each
+/// primitive applied, and colored in linear sRGBA. Primitives are tessellated as though analytic
+/// anti-aliasing were disabled, and shapes that are rendered as signed distance fields on the GPU
+/// are tessellated from their paths, so the mesh covers the same area as the shape on screen.
+///
+/// **Text** and **Texture** primitives depend upon GPU textures and are skipped. Textured paths
+/// and meshes are included, though their texture coordinates refer to textures that are not
+/// part of the mesh.
+///
+/// Unlike rendering via the **draw::Renderer**, the **Draw** is not drained of its commands, so
+/// this may be called before rendering the same **Draw** to a frame.
+pub fn render_to_mesh(draw: &Draw) -> draw::Mesh {
+    tessellate_to_mesh(draw, None)
+}
+
+/// Tessellate the primitives submitted to the given **Draw** into a single mesh, culling them as
+/// though rendering to a frame spanning the given `rect`.
+///
+/// Primitives drawn via `Draw::cull` that lie entirely outside of the `rect` (or their scissor)
+/// are skipped, exactly as they are when rendering via the **draw::Renderer**. See
+/// `render_to_mesh` for details.
+pub fn render_visible_to_mesh(draw: &Draw, rect: Rect) -> draw::Mesh {
+    tessellate_to_mesh(draw, Some(rect))
+}
+
+// Tessellate into a single mesh, culling against the given rect if any.
+fn tessellate_to_mesh(draw: &Draw, cull_rect: Option<Rect>) -> draw::Mesh {
+    let skip = |prim: &Primitive| matches!(prim, Primitive::Text(_) | Primitive::Texture(_));
+    let mut mesh = draw::Mesh::default();
+    let mut sdf_params = vec![];
+    for cmd in tessellate::tessellate_draw(draw, skip, false, None, cull_rect) {
+        if let Command::Tessellated(tessellated) = cmd {
+            tessellated.stitch(&mut mesh, &mut sdf_params);
+        }
+    }
+    mesh
+}
+
+/// Produce an SVG document from the paths of the primitives submitted to the given **Draw**.
+///
+/// The document spans the given `rect` in the coordinate space of the **Draw**, e.g.
+/// `app.window_rect()`. If a background color was specified, the document begins with a rect of
+/// that color. See the `vector` module for the primitives that are included.
+pub fn render_to_svg(draw: &Draw, rect: Rect) -> String {
+    let (w, h) = rect.w_h();
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = w,
+        h = h,
+    );
+    let background = draw.state.borrow().background_color;
+    if let Some(color) = background {
+        let _ = writeln!(
+            svg,
+            r#"  <rect width="{}" height="{}" {}/>"#,
+            w,
+            h,
+            paint("fill", color)
+        );
+    }
+    let offset = rect.top_left();
+    for path in vector::paths(draw) {
+        let d = path.svg_path_data(offset);
+        let style = match path.style {
+            Style::Fill { color, options } => {
+                let rule = match options.fill_rule {
+                    FillRule::EvenOdd => "evenodd",
+                    FillRule::NonZero => "nonzero",
+                };
+                format!(r#"{} fill-rule="{}""#, paint("fill", color), rule)
+            }
+            Style::Stroke { color, options } => {
+                let cap = match options.start_cap {
+                    LineCap::Butt => "butt",
+                    LineCap::Square => "square",
+                    LineCap::Round => "round",
+                };
+                let join = match options.line_join {
+                    LineJoin::Miter | LineJoin::MiterClip => "miter",
+                    LineJoin::Round => "round",
+                    LineJoin::Bevel => "bevel",
+                };
+                format!(
+                    r#"fill="none" {} stroke-width="{}" stroke-linecap="{}" stroke-linejoin="{}""#,
+                    paint("stroke", color),
+                    options.line_width,
+                    cap,
+                    join,
+                )
+            }
+        };
+        let _ = writeln!(svg, r#"  <path d="{}" {}/>"#, d, style);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// An SVG paint attribute along with its opacity.
+fn paint(attr: &str, color: LinSrgba) -> String {
+    let c = color.to_srgb();
+    let byte = |f: f32| (f.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        r##"{attr}="#{:02x}{:02x}{:02x}" {attr}-opacity="{}""##,
+        byte(c.red),
+        byte(c.green),
+        byte(c.blue),
+        c.alpha,
+        attr = attr,
+    )
+}
@@ -0,0 +1,366 @@
+//! Lights that illuminate the primitives drawn via `Draw::lit`.
+//!
+//! Lights are added to the **Draw** in the same manner as the background, via `Draw::point_light`,
+//! `Draw::directional_light` and `Draw::ambient_light`. They illuminate all lit primitives
+//! submitted during the frame, regardless of the order in which they were added.
+//!
+//! Surfaces are shaded flat using the normal of each triangle as it faces the viewer, as the
+//! vertices produced by the draw API do not carry normals. The way in which a surface responds to
+//! light is described by its material, e.g. `Drawing::metallic` and `Drawing::roughness`. See the
+//! `properties::material` module.
+//!
+//! Directional lights may cast shadows onto lit primitives via `DirectionalLight::shadows`. The
+//! shadow map of each light spans the bounds of all lit primitives, so its resolution is best
+//! spent on a scene that is no larger than necessary. See `Draw::shadows` for quality settings.
+//!
+//! The light surrounding the scene may be described by an **EnvironmentMap** via
+//! `Draw::environment_map`, illuminating lit primitives with diffuse light and reflections from
+//! all directions. The environment may also be drawn as a skybox behind all other primitives.
+
+use crate::color::{IntoLinSrgba, LinSrgb};
+use crate::draw::properties::ColorScalar;
+use crate::draw::{Draw, EnvironmentMap};
+use crate::glam::{Quat, Vec3};
+
+/// The maximum number of point and directional lights that may illuminate a frame.
+///
+/// Lights added beyond this limit are ignored.
+pub const MAX_LIGHTS: usize = 8;
+
+/// The intensity of the white ambient light used when none is specified.
+pub const DEFAULT_AMBIENT_INTENSITY: f32 = 0.1;
+
+/// The default width and height of the shadow map of each shadow casting light.
+pub const DEFAULT_SHADOW_MAP_SIZE: u32 = 2048;
+
+/// The default intensity of the light of an environment map.
+pub const DEFAULT_ENVIRONMENT_INTENSITY: f32 = 1.0;
+
+/// The default vertical field of view of a skybox in radians.
+pub const DEFAULT_SKYBOX_FOV: f32 = std::f32::consts::FRAC_PI_3;
+
+/// The default distance by which surfaces are offset towards the light when testing whether they
+/// are in shadow, avoiding "shadow acne" caused by surfaces shadowing themselves.
+pub const DEFAULT_SHADOW_BIAS: f32 = 0.5;
+
+/// The lights illuminating a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lighting {
+    /// The color of the light that reaches all surfaces equally.
+    pub ambient_color: LinSrgb,
+    /// The intensity of the ambient light.
+    pub ambient_intensity: f32,
+    /// The point and directional lights, in the order in which they were added.
+    pub lights: Vec<Light>,
+    /// The width and height of the shadow map of each shadow casting light.
+    pub shadow_map_size: u32,
+    /// Whether or not the edges of shadows are softened by sampling the shadow map multiple
+    /// times.
+    pub soft_shadows: bool,
+    /// The light surrounding the scene, if any.
+    pub environment: Option<Environment>,
+}
+
+/// The light surrounding a scene, described by an **EnvironmentMap**.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Environment {
+    /// The prefiltered cubemaps of the environment.
+    pub map: EnvironmentMap,
+    /// A multiplier applied to the light of the environment.
+    pub intensity: f32,
+    /// The orientation of the environment within the scene.
+    pub rotation: Quat,
+    /// The vertical field of view with which the environment is drawn behind all other
+    /// primitives, or `None` if it is not drawn.
+    pub skybox: Option<f32>,
+}
+
+/// A single point or directional light.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Light {
+    /// The kind of light along with its placement.
+    pub kind: LightKind,
+    /// The color of the light.
+    pub color: LinSrgb,
+    /// A multiplier applied to the color of the light.
+    pub intensity: f32,
+    /// Whether or not the light casts shadows. Only directional lights support shadows.
+    pub shadows: bool,
+    /// The distance by which surfaces are offset towards the light when testing whether they
+    /// are in shadow.
+    pub shadow_bias: f32,
+}
+
+/// The kinds of light that may be added to a **Draw**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LightKind {
+    /// A light emitted in all directions from a single position.
+    Point {
+        position: Vec3,
+        /// The distance at which the light has faded out entirely, or `None` if the light does
+        /// not fade with distance.
+        range: Option<f32>,
+    },
+    /// A light travelling in a single direction, e.g. sunlight.
+    Directional { direction: Vec3 },
+}
+
+/// A type used to configure a point light. See `Draw::point_light`.
+pub struct PointLight<'a> {
+    draw: &'a Draw,
+    index: usize,
+}
+
+/// A type used to configure a directional light. See `Draw::directional_light`.
+pub struct DirectionalLight<'a> {
+    draw: &'a Draw,
+    index: usize,
+}
+
+/// A type used to configure the ambient light. See `Draw::ambient_light`.
+pub struct AmbientLight<'a> {
+    draw: &'a Draw,
+}
+
+/// A type used to configure the light surrounding the scene. See `Draw::environment_map`.
+pub struct ImageBasedLight<'a> {
+    draw: &'a Draw,
+}
+
+/// A type used to configure the quality of shadows. See `Draw::shadows`.
+pub struct Shadows<'a> {
+    draw: &'a Draw,
+}
+
+impl Lighting {
+    /// Add the given light, returning its index.
+    fn push(&mut self, kind: LightKind) -> usize {
+        let light = Light {
+            kind,
+            color: LinSrgb::new(1.0, 1.0, 1.0),
+            intensity: 1.0,
+            shadows: false,
+            shadow_bias: DEFAULT_SHADOW_BIAS,
+        };
+        self.lights.push(light);
+        self.lights.len() - 1
+    }
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        Lighting {
+            ambient_color: LinSrgb::new(1.0, 1.0, 1.0),
+            ambient_intensity: DEFAULT_AMBIENT_INTENSITY,
+            lights: vec![],
+            shadow_map_size: DEFAULT_SHADOW_MAP_SIZE,
+            soft_shadows: true,
+            environment: None,
+        }
+    }
+}
+
+/// Add a point light at the given position to the **Draw**.
+pub fn point<'a>(draw: &'a Draw, position: Vec3) -> PointLight<'a> {
+    let position = draw.context.transform.transform_point3(position);
+    let kind = LightKind::Point {
+        position,
+        range: None,
+    };
+    let index = draw.state.borrow_mut().lighting.push(kind);
+    PointLight { draw, index }
+}
+
+/// Add a directional light travelling in the given direction to the **Draw**.
+pub fn directional<'a>(draw: &'a Draw, direction: Vec3) -> DirectionalLight<'a> {
+    let direction = draw.context.transform.transform_vector3(direction);
+    let kind = LightKind::Directional { direction };
+    let index = draw.state.borrow_mut().lighting.push(kind);
+    DirectionalLight { draw, index }
+}
+
+/// Begin configuring the ambient light of the **Draw**.
+pub fn ambient<'a>(draw: &'a Draw) -> AmbientLight<'a> {
+    AmbientLight { draw }
+}
+
+/// Illuminate the lit primitives of the **Draw** with the given environment map.
+///
+/// The environment is oriented by the rotation of the draw context.
+pub fn environment<'a>(draw: &'a Draw, map: &EnvironmentMap) -> ImageBasedLight<'a> {
+    let (_, rotation, _) = draw.context.transform.to_scale_rotation_translation();
+    let environment = Environment {
+        map: map.clone(),
+        intensity: DEFAULT_ENVIRONMENT_INTENSITY,
+        rotation,
+        skybox: None,
+    };
+    draw.state.borrow_mut().lighting.environment = Some(environment);
+    ImageBasedLight { draw }
+}
+
+/// Begin configuring the quality of the shadows of the **Draw**.
+pub fn shadows<'a>(draw: &'a Draw) -> Shadows<'a> {
+    Shadows { draw }
+}
+
+// Update the light at the given index.
+fn update_light<F>(draw: &Draw, index: usize, update: F)
+where
+    F: FnOnce(&mut Light),
+{
+    if let Ok(mut state) = draw.state.try_borrow_mut() {
+        if let Some(light) = state.lighting.lights.get_mut(index) {
+            update(light);
+        }
+    }
+}
+
+impl<'a> PointLight<'a> {
+    /// The color of the light. White by default.
+    pub fn color<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.draw.linear_color(color).color;
+        update_light(self.draw, self.index, |light| light.color = color);
+        self
+    }
+
+    /// A multiplier applied to the color of the light. `1.0` by default.
+    pub fn intensity(self, intensity: f32) -> Self {
+        update_light(self.draw, self.index, |light| light.intensity = intensity);
+        self
+    }
+
+    /// The distance at which the light has faded out entirely.
+    ///
+    /// By default, point lights do not fade with distance.
+    pub fn range(self, range: f32) -> Self {
+        update_light(self.draw, self.index, |light| {
+            if let LightKind::Point { position, .. } = light.kind {
+                let range = Some(range);
+                light.kind = LightKind::Point { position, range };
+            }
+        });
+        self
+    }
+}
+
+impl<'a> DirectionalLight<'a> {
+    /// The color of the light. White by default.
+    pub fn color<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.draw.linear_color(color).color;
+        update_light(self.draw, self.index, |light| light.color = color);
+        self
+    }
+
+    /// A multiplier applied to the color of the light. `1.0` by default.
+    pub fn intensity(self, intensity: f32) -> Self {
+        update_light(self.draw, self.index, |light| light.intensity = intensity);
+        self
+    }
+
+    /// Whether or not the light casts shadows onto lit primitives. `false` by default.
+    ///
+    /// Each shadow casting light renders all lit primitives to a shadow map before drawing.
+    pub fn shadows(self, shadows: bool) -> Self {
+        update_light(self.draw, self.index, |light| light.shadows = shadows);
+        self
+    }
+
+    /// The distance by which surfaces are offset towards the light when testing whether they
+    /// are in shadow. `DEFAULT_SHADOW_BIAS` by default.
+    ///
+    /// Increase this if lit surfaces are speckled with shadow. Decrease it if shadows appear
+    /// detached from the primitives that cast them.
+    pub fn shadow_bias(self, bias: f32) -> Self {
+        update_light(self.draw, self.index, |light| light.shadow_bias = bias);
+        self
+    }
+}
+
+impl<'a> AmbientLight<'a> {
+    /// The color of the ambient light. White by default.
+    pub fn color<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.draw.linear_color(color).color;
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.lighting.ambient_color = color;
+        }
+        self
+    }
+
+    /// The intensity of the ambient light. `DEFAULT_AMBIENT_INTENSITY` by default.
+    pub fn intensity(self, intensity: f32) -> Self {
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.lighting.ambient_intensity = intensity;
+        }
+        self
+    }
+}
+
+// Update the environment of the draw.
+fn update_environment<F>(draw: &Draw, update: F)
+where
+    F: FnOnce(&mut Environment),
+{
+    if let Ok(mut state) = draw.state.try_borrow_mut() {
+        if let Some(environment) = state.lighting.environment.as_mut() {
+            update(environment);
+        }
+    }
+}
+
+impl<'a> ImageBasedLight<'a> {
+    /// A multiplier applied to the light of the environment. `DEFAULT_ENVIRONMENT_INTENSITY` by
+    /// default.
+    pub fn intensity(self, intensity: f32) -> Self {
+        update_environment(self.draw, |env| env.intensity = intensity);
+        self
+    }
+
+    /// Draw the environment behind all other primitives, as though viewed through a perspective
+    /// camera with a vertical field of view of `DEFAULT_SKYBOX_FOV`.
+    ///
+    /// Replaces any background gradient or image.
+    pub fn skybox(self) -> Self {
+        self.skybox_fov(DEFAULT_SKYBOX_FOV)
+    }
+
+    /// Draw the environment behind all other primitives, as though viewed through a perspective
+    /// camera with the given vertical field of view in radians.
+    ///
+    /// Replaces any background gradient or image.
+    pub fn skybox_fov(self, fov: f32) -> Self {
+        update_environment(self.draw, |env| env.skybox = Some(fov));
+        self
+    }
+}
+
+impl<'a> Shadows<'a> {
+    /// The width and height of the shadow map of each shadow casting light.
+    /// `DEFAULT_SHADOW_MAP_SIZE` by default.
+    ///
+    /// Larger maps produce sharper shadows at the cost of memory and fill rate.
+    pub fn map_size(self, size: u32) -> Self {
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.lighting.shadow_map_size = size.max(1);
+        }
+        self
+    }
+
+    /// Whether or not the edges of shadows are softened by sampling the shadow map multiple
+    /// times. `true` by default.
+    pub fn soft(self, soft: bool) -> Self {
+        if let Ok(mut state) = self.draw.state.try_borrow_mut() {
+            state.lighting.soft_shadows = soft;
+        }
+        self
+    }
+}
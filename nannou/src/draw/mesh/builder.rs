@@ -11,7 +11,7 @@ use crate::glam::Mat4;
 use lyon::tessellation::geometry_builder::{
     self, FillGeometryBuilder, GeometryBuilder, StrokeGeometryBuilder,
 };
-use lyon::tessellation::{FillVertex, GeometryBuilderError, StrokeVertex, VertexId};
+use lyon::tessellation::{FillVertex, GeometryBuilderError, Side, StrokeVertex, VertexId};
 
 pub struct MeshBuilder<'a, A> {
     /// The mesh that is to be extended.
@@ -126,7 +126,7 @@ impl<'a> StrokeGeometryBuilder for MeshBuilder<'a, SingleColor> {
         let p = Point2::new(position.x, position.y).extend(0.0);
         let point = self.transform.transform_point3(p);
         let SingleColor(color) = self.attributes;
-        let tex_coords = draw::mesh::vertex::default_tex_coords();
+        let tex_coords = stroke_edge_tex_coords(&vertex);
         let vertex = draw::mesh::vertex::new(point, color, tex_coords);
         self.mesh.push_vertex(vertex);
 
@@ -174,7 +174,7 @@ impl<'a> StrokeGeometryBuilder for MeshBuilder<'a, ColorPerPoint> {
         let point = self.transform.transform_point3(p);
         let col = vertex.interpolated_attributes();
         let color: draw::mesh::vertex::Color = (col[0], col[1], col[2], col[3]).into();
-        let tex_coords = draw::mesh::vertex::default_tex_coords();
+        let tex_coords = stroke_edge_tex_coords(&vertex);
         let vertex = draw::mesh::vertex::new(point, color, tex_coords);
         self.mesh.push_vertex(vertex);
 
@@ -230,3 +230,21 @@ impl<'a> StrokeGeometryBuilder for MeshBuilder<'a, TexCoordsPerPoint> {
         Ok(id)
     }
 }
+
+// Texture coordinates describing the signed distance of the stroke vertex from the centre of the
+// stroke along with the half width of the stroke.
+//
+// These are ignored in `Color` mode, but allow the `ColorAa` vertex mode to anti-alias the edges
+// of the stroke within the fragment shader.
+fn stroke_edge_tex_coords(vertex: &StrokeVertex) -> draw::mesh::vertex::TexCoords {
+    let normal_len = vertex.normal().length();
+    if normal_len <= 0.0 {
+        return draw::mesh::vertex::default_tex_coords();
+    }
+    let half_width = (vertex.position() - vertex.position_on_path()).length() / normal_len;
+    let distance = match vertex.side() {
+        Side::Left => -half_width,
+        Side::Right => half_width,
+    };
+    [distance, half_width].into()
+}
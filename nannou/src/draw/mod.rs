@@ -2,31 +2,53 @@
 //!
 //! See the [**Draw** type](./struct.Draw.html) for more details.
 
+use crate::audio_buffer::AudioBuffer;
+use crate::color::{ColorExt, IntoLinSrgba};
 use crate::geom::{self, Point2};
 use crate::glam::{vec3, EulerRot, Mat4, Quat, Vec2, Vec3};
 use crate::math::{deg_to_rad, turns_to_rad};
+use crate::vector_field::VectorField2;
 use crate::wgpu;
 use lyon::path::PathEvent;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::mem;
 use std::rc::Rc;
+use std::sync::Arc;
 
-pub use self::background::Background;
+pub use self::axonometric::Axonometric;
+pub use self::background::{Background, FillMode};
 pub use self::drawing::{Drawing, DrawingContext};
+pub use self::environment::EnvironmentMap;
+#[cfg(feature = "headless")]
+pub use self::headless::{render_to_mesh, render_to_svg, render_visible_to_mesh};
 use self::mesh::vertex::{Color, TexCoords};
 pub use self::mesh::Mesh;
 use self::primitive::Primitive;
-pub use self::renderer::{Builder as RendererBuilder, Renderer};
+pub use self::record::{RecordedCommand, RecordedFrame, Recording};
+pub use self::renderer::{Builder as RendererBuilder, GpuMesh, GpuVertex, Renderer};
 pub use self::theme::Theme;
+pub use self::turtle::Turtle;
 
+pub mod axonometric;
 pub mod background;
+pub mod canvas;
+pub mod color_audit;
 mod drawing;
+pub mod environment;
+#[cfg(feature = "headless")]
+pub mod headless;
+pub mod light;
 pub mod mesh;
+mod pick;
 pub mod primitive;
 pub mod properties;
+pub mod record;
 pub mod renderer;
 pub mod theme;
+pub mod turtle;
+pub mod vector;
 
 /// A simple API for drawing 2D and 3D graphics.
 ///
@@ -64,10 +86,44 @@ pub struct Context {
     pub transform: Mat4,
     pub blend: wgpu::BlendState,
     pub scissor: Scissor,
-    // TODO: Consider changing `PolygonMode` (added as of wgpu 0.7) rather than `PrimitiveTopology`
-    // here.
     pub topology: wgpu::PrimitiveTopology,
+    /// The way in which triangles are rasterized. See `Draw::wireframe`.
+    pub polygon_mode: wgpu::PolygonMode,
+    /// Whether or not drawn fragments write their depth to the depth buffer.
+    pub depth_write: bool,
+    /// The comparison against the depth buffer that a fragment must pass in order to be drawn.
+    pub depth_compare: wgpu::CompareFunction,
+    /// The layer in which drawing occurs. See `Draw::z_index`.
+    pub z_index: i32,
     pub sampler: wgpu::SamplerDescriptor<'static>,
+    pub analytic_aa: bool,
+    /// Whether or not drawn primitives are illuminated by the lights of the **Draw**.
+    pub lit: bool,
+    /// Replaces the color of all drawn vertices with a visualisation of their geometry.
+    pub debug_view: Option<DebugView>,
+    /// Whether or not primitives lying entirely outside of the visible area are skipped before
+    /// tessellation. See `Draw::cull`.
+    pub cull: bool,
+    /// The path of the named scope in which drawing occurs, used to attribute the cost of
+    /// rendering when profiling. See `Draw::scope`.
+    pub scope: Option<Arc<str>>,
+}
+
+/// Visualisations of the geometry submitted to a **Draw**, useful for debugging meshes.
+///
+/// See `Draw::debug_view`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DebugView {
+    /// Color each surface by its normal, with the `x`, `y` and `z` axes mapped to red, green and
+    /// blue respectively. Flat shapes facing the viewer appear light blue.
+    ///
+    /// Normals are derived from the rasterized surface, so each triangle is shaded flat.
+    Normals,
+    /// Color each vertex by its texture coordinates, with `u` and `v` mapped to red and green
+    /// respectively.
+    TexCoords,
+    /// Color each fragment by its depth, with nearer surfaces appearing brighter.
+    Depth,
 }
 
 /// Commands generated by drawings.
@@ -80,6 +136,8 @@ pub enum DrawCommand {
     Primitive(Primitive),
     /// A change in the rendering context occurred.
     Context(Context),
+    /// Call a user's callback with the command encoder.
+    Custom(renderer::Custom),
 }
 
 /// The scissor for a **Draw**'s render context.
@@ -106,6 +164,9 @@ pub struct State {
     last_draw_context: Option<Context>,
     /// If `Some`, the **Draw** should first clear the frame's texture with the given color.
     background_color: Option<properties::LinSrgba>,
+    /// If `Some`, the **Draw** should first fill the frame's texture with the given gradient or
+    /// image.
+    background_fill: Option<background::Fill>,
     /// Primitives that are in the process of being drawn.
     ///
     /// Keys are indices into the `draw_commands` Vec.
@@ -118,6 +179,20 @@ pub struct State {
     intermediary_state: RefCell<IntermediaryState>,
     /// The theme containing default values.
     theme: Theme,
+    /// The state of the color audit, if enabled.
+    color_audit: Option<color_audit::ColorAudit>,
+    /// The lights illuminating primitives drawn via `Draw::lit`.
+    lighting: light::Lighting,
+    /// Drawings recorded via `Draw::cached`, retained between frames. Keys are hashes of the
+    /// user's keys.
+    cache: HashMap<u64, Rc<RecordedFrame>>,
+    /// The **Draw** whose drawing accumulates between frames, if `Draw::persist` has been used.
+    persistent: Option<Draw>,
+    /// The IDs assigned to primitives via `Drawing::pick_id`. Keys are indices into the
+    /// `draw_commands` Vec.
+    pick_ids: HashMap<usize, u64>,
+    /// The geometry of the pickable primitives of the most recently rendered frame.
+    picks: pick::Picks,
 }
 
 /// State made accessible via the `DrawingContext`.
@@ -149,10 +224,20 @@ impl State {
     // Resets all state within the `Draw` instance.
     fn reset(&mut self) {
         self.background_color = None;
+        self.background_fill = None;
+        self.lighting = Default::default();
         self.last_draw_context = None;
         self.drawing.clear();
         self.draw_commands.clear();
+        self.pick_ids.clear();
         self.intermediary_state.borrow_mut().reset();
+        if let Some(audit) = self.color_audit.as_mut() {
+            audit.reset();
+        }
+        // The persistent drawing itself is retained by the renderer, not the **Draw**.
+        if let Some(persistent) = self.persistent.as_ref() {
+            persistent.reset();
+        }
     }
 
     // Drain any remaining `drawing`s and insert them as draw commands.
@@ -381,6 +466,20 @@ impl Draw {
         self.z_radians(radians)
     }
 
+    /// Produce a new **Draw** instance that projects its contents with the given axonometric
+    /// projection.
+    ///
+    /// 2D primitives drawn with the new instance lie on the ground plane. See the
+    /// `draw::axonometric` module for details.
+    pub fn axonometric(&self, projection: &Axonometric) -> Self {
+        self.transform(projection.transform())
+    }
+
+    /// Short-hand for `axonometric` with a true isometric projection.
+    pub fn isometric(&self) -> Self {
+        self.axonometric(&Axonometric::isometric())
+    }
+
     /// Produce a new **Draw** instance that will draw with the given alpha blend descriptor.
     pub fn alpha_blend(&self, blend_descriptor: wgpu::BlendComponent) -> Self {
         let mut context = self.context.clone();
@@ -453,6 +552,242 @@ impl Draw {
         self.primitive_topology(wgpu::PrimitiveTopology::TriangleList)
     }
 
+    /// Produce a new **Draw** instance where all triangles are rasterized as the lines along
+    /// their edges when `wireframe` is `true`, or filled when `false`.
+    ///
+    /// Unlike `line_mode`, this reveals the actual triangles produced by tessellation, which is
+    /// useful when building meshes via the draw API.
+    ///
+    /// This uses the `Line` polygon mode of the render pipeline when the device was created with
+    /// `wgpu::Features::POLYGON_MODE_LINE`. Otherwise, the edges of each triangle are submitted as
+    /// a line list instead.
+    pub fn wireframe(&self, wireframe: bool) -> Self {
+        let mut context = self.context.clone();
+        context.polygon_mode = match wireframe {
+            true => wgpu::PolygonMode::Line,
+            false => wgpu::PolygonMode::Fill,
+        };
+        self.context(context)
+    }
+
+    /// Produce a **Draw** instance whose drawing accumulates from frame to frame, rather than
+    /// being cleared along with the rest of the frame.
+    ///
+    /// This allows for Processing-style sketches that "never clear the background" while still
+    /// drawing over a freshly cleared frame each frame, e.g. for a cursor or UI. The drawing is
+    /// rendered into a texture retained by the **draw::Renderer** and drawn behind all other
+    /// drawing, in front of the background. It remains until cleared by specifying a background
+    /// for the persistent **Draw**, e.g. `draw.persist().background().color(BLACK)`, or until the
+    /// size of the target changes.
+    ///
+    /// The returned **Draw** begins with the context of this one, e.g. its transform.
+    ///
+    /// ```
+    /// use nannou::prelude::*;
+    ///
+    /// fn view(app: &App, frame: Frame) {
+    ///     let draw = app.draw();
+    ///     draw.background().color(BLACK);
+    ///     // Paint with the mouse, slowly fading older strokes.
+    ///     let canvas = draw.persist();
+    ///     if app.elapsed_frames() == 0 {
+    ///         canvas.background().color(WHITE);
+    ///     }
+    ///     canvas.rect().wh(app.window_rect().wh()).color(rgba(1.0, 1.0, 1.0, 0.02));
+    ///     if app.mouse.buttons.left().is_down() {
+    ///         canvas.ellipse().xy(app.mouse.position()).radius(10.0).color(BLACK);
+    ///     }
+    ///     // The cursor is redrawn each frame.
+    ///     draw.ellipse().xy(app.mouse.position()).radius(10.0).no_fill().stroke(RED);
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn persist(&self) -> Draw {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let theme = &state.theme;
+        let persistent = state.persistent.get_or_insert_with(|| {
+            let draw = Draw::new();
+            draw.state.borrow_mut().theme = theme.clone();
+            draw
+        });
+        Draw {
+            state: persistent.state.clone(),
+            context: self.context.clone(),
+        }
+    }
+
+    /// Produce a new **Draw** instance whose drawing occurs within the layer of the given index.
+    ///
+    /// Drawing within layers of a greater index is rendered after that of lesser indices,
+    /// regardless of the order in which it was submitted. Drawing within the same layer is
+    /// rendered in the order in which it was submitted. The index is `0` by default.
+    ///
+    /// Note that the depth test still applies, so a layer only appears above primitives that
+    /// share its depth or that lie behind it. See `no_depth_test` for layering at any depth.
+    ///
+    /// ```
+    /// use nannou::prelude::*;
+    ///
+    /// fn view(app: &App, frame: Frame) {
+    ///     let draw = app.draw();
+    ///     let labels = draw.z_index(1);
+    ///     for i in 0..3 {
+    ///         let x = i as f32 * 100.0;
+    ///         // Each label is drawn above all circles, including those submitted after it.
+    ///         labels.x(x).text("label").color(BLACK);
+    ///         draw.x(x).ellipse().w_h(80.0, 80.0).color(WHITE);
+    ///     }
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn z_index(&self, z_index: i32) -> Self {
+        let mut context = self.context.clone();
+        context.z_index = z_index;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance whose drawing neither is tested against nor writes to the
+    /// depth buffer.
+    ///
+    /// Primitives are drawn over everything drawn before them in the order in which they are
+    /// submitted, regardless of their depth. This is useful for overlays and for layering
+    /// translucent primitives, which otherwise hide anything drawn behind them later.
+    pub fn no_depth_test(&self) -> Self {
+        self.depth_compare(wgpu::CompareFunction::Always)
+            .depth_write(false)
+    }
+
+    /// Produce a new **Draw** instance whose drawing writes its depth to the depth buffer when
+    /// `depth_write` is `true`. `true` by default.
+    ///
+    /// Disabling depth writes while drawing translucent primitives allows primitives behind them
+    /// to still be drawn afterwards, while remaining hidden behind opaque primitives.
+    pub fn depth_write(&self, depth_write: bool) -> Self {
+        let mut context = self.context.clone();
+        context.depth_write = depth_write;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance whose drawing is only drawn where the given comparison
+    /// against the depth buffer passes.
+    ///
+    /// `LessEqual` by default, drawing fragments at or in front of those already drawn. Note that
+    /// depth increases away from the viewer.
+    pub fn depth_compare(&self, compare: wgpu::CompareFunction) -> Self {
+        let mut context = self.context.clone();
+        context.depth_compare = compare;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance where the color of all drawing is replaced by the given
+    /// visualisation of its geometry, or drawn as usual if `None`.
+    ///
+    /// As the view is part of the draw context, it may be toggled at runtime, e.g. in response
+    /// to a key press.
+    ///
+    /// ```
+    /// use nannou::draw::DebugView;
+    /// use nannou::prelude::*;
+    ///
+    /// fn view(app: &App, frame: Frame) {
+    ///     let view = match app.keys.down.contains(&Key::N) {
+    ///         true => Some(DebugView::Normals),
+    ///         false => None,
+    ///     };
+    ///     let draw = app.draw().debug_view(view);
+    ///     draw.tri().color(RED);
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn debug_view(&self, view: Option<DebugView>) -> Self {
+        let mut context = self.context.clone();
+        context.debug_view = view;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance where all strokes will be anti-aliased analytically within
+    /// the fragment shader.
+    ///
+    /// Strokes are widened by roughly one pixel during tessellation and their edges are faded out
+    /// based on their distance from the centre of the stroke. This produces smooth lines without
+    /// requiring MSAA, which can be a significant performance saving on integrated GPUs. Consider
+    /// using this alongside a window with `msaa_samples(1)`.
+    ///
+    /// Note that only strokes are affected. The edges of fills remain aliased without MSAA.
+    pub fn analytic_aa(&self) -> Self {
+        let mut context = self.context.clone();
+        context.analytic_aa = true;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance where all drawn primitives are illuminated by the lights
+    /// added via `point_light`, `directional_light` and `ambient_light`.
+    ///
+    /// The color of each primitive is used as its diffuse color. Primitives drawn without this
+    /// are unaffected by lights.
+    pub fn lit(&self) -> Self {
+        let mut context = self.context.clone();
+        context.lit = true;
+        self.context(context)
+    }
+
+    /// Produce a new **Draw** instance where primitives lying entirely outside of the visible area
+    /// are skipped before tessellation.
+    ///
+    /// The visible area is the window (or texture) being drawn to, limited further by the
+    /// **Draw**'s scissor if any. Useful for sketches that draw large scrolling worlds, where most
+    /// of the drawn geometry is typically offscreen.
+    ///
+    /// Primitives are culled by their bounding rect, which is computed for ellipses, rects, lines,
+    /// tris and quads. All other primitives, along with those drawn with `sdf`, are always drawn.
+    pub fn cull(&self) -> Self {
+        let mut context = self.context.clone();
+        context.cull = true;
+        self.context(context)
+    }
+
+    /// Call the given function with a **Draw** instance whose primitives belong to the scope of
+    /// the given name, returning its result.
+    ///
+    /// When the `profile-tracing` or `profile-puffin` feature is enabled, the function call along
+    /// with the tessellation and encoding of the scope's primitives are each measured within a
+    /// span carrying the scope's name, attributing the cost of rendering to parts of the sketch
+    /// within flamegraphs. Nested scopes are named by their path, e.g. `"background/stars"`. See
+    /// the `profile` module for details.
+    ///
+    /// With profiling enabled, the primitives of different scopes are never batched into the same
+    /// draw call. Otherwise, scopes have no effect.
+    ///
+    /// ```
+    /// # use nannou::prelude::*;
+    /// # fn view(app: &App, frame: Frame) {
+    /// let draw = app.draw();
+    /// draw.scope("background", |draw| {
+    ///     draw.background().color(BLACK);
+    ///     draw.scope("stars", |draw| {
+    ///         for i in 0..100 {
+    ///             draw.ellipse().x(i as f32).radius(1.0);
+    ///         }
+    ///     });
+    /// });
+    /// # }
+    /// # fn main() {}
+    /// ```
+    pub fn scope<F, T>(&self, name: &str, f: F) -> T
+    where
+        F: FnOnce(&Self) -> T,
+    {
+        let mut context = self.context.clone();
+        let path: Arc<str> = match context.scope {
+            Some(ref parent) => format!("{}/{}", parent, name).into(),
+            None => name.into(),
+        };
+        let _span = crate::profile::scope(&path);
+        context.scope = Some(path);
+        f(&self.context(context))
+    }
+
     /// Produce a new **Draw** instance where all textures and textured vertices drawn will be
     /// sampled via a sampler of the given descriptor.
     pub fn sampler(&self, desc: wgpu::SamplerDescriptor<'static>) -> Self {
@@ -483,31 +818,198 @@ impl Draw {
         background::new(self)
     }
 
+    /// Add a point light at the given position, illuminating primitives drawn via `lit`.
+    ///
+    /// The position is transformed by the current transform of the **Draw**. See the `light`
+    /// module for details.
+    ///
+    /// ```
+    /// use nannou::prelude::*;
+    ///
+    /// fn view(app: &App, frame: Frame) {
+    ///     let draw = app.draw();
+    ///     draw.background().color(BLACK);
+    ///     draw.point_light(pt3(100.0, 100.0, 200.0))
+    ///         .color(ORANGE)
+    ///         .intensity(2.0);
+    ///     draw.ambient_light().intensity(0.2);
+    ///     draw.lit().ellipse().w_h(200.0, 200.0).color(WHITE);
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn point_light(&self, position: Vec3) -> light::PointLight {
+        light::point(self, position)
+    }
+
+    /// Add a directional light travelling in the given direction, illuminating primitives drawn
+    /// via `lit`.
+    ///
+    /// The direction is transformed by the current transform of the **Draw**. E.g. a direction of
+    /// `vec3(0.0, 0.0, -1.0)` illuminates shapes facing the viewer.
+    pub fn directional_light(&self, direction: Vec3) -> light::DirectionalLight {
+        light::directional(self, direction)
+    }
+
+    /// Configure the light that reaches all lit surfaces equally, regardless of their position or
+    /// orientation.
+    pub fn ambient_light(&self) -> light::AmbientLight {
+        light::ambient(self)
+    }
+
+    /// Configure the quality of the shadows cast by lights via `DirectionalLight::shadows`.
+    ///
+    /// ```
+    /// use nannou::prelude::*;
+    ///
+    /// fn view(app: &App, frame: Frame) {
+    ///     let draw = app.draw();
+    ///     draw.background().color(BLACK);
+    ///     draw.shadows().map_size(4096).soft(true);
+    ///     draw.directional_light(vec3(-1.0, -1.0, -1.0))
+    ///         .shadows(true)
+    ///         .shadow_bias(1.0);
+    ///     let lit = draw.lit();
+    ///     lit.rect().w_h(400.0, 400.0).color(WHITE);
+    ///     lit.z(50.0).ellipse().w_h(100.0, 100.0).color(RED);
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn shadows(&self) -> light::Shadows {
+        light::shadows(self)
+    }
+
+    /// Illuminate lit primitives with the light surrounding the scene, as described by the given
+    /// environment map.
+    ///
+    /// The environment is oriented by the rotation of the draw context. Only one environment map
+    /// may be applied per frame, replacing any previously specified.
+    ///
+    /// ```no_run
+    /// use nannou::draw::EnvironmentMap;
+    /// use nannou::prelude::*;
+    ///
+    /// struct Model {
+    ///     environment: EnvironmentMap,
+    /// }
+    ///
+    /// fn model(app: &App) -> Model {
+    ///     let path = app.assets_path().unwrap().join("sky.hdr");
+    ///     let environment = EnvironmentMap::from_path(app, path).unwrap();
+    ///     Model { environment }
+    /// }
+    ///
+    /// fn view(app: &App, model: &Model, frame: Frame) {
+    ///     let draw = app.draw();
+    ///     draw.environment_map(&model.environment)
+    ///         .intensity(0.8)
+    ///         .skybox();
+    ///     draw.lit()
+    ///         .ellipse()
+    ///         .w_h(200.0, 200.0)
+    ///         .color(WHITE)
+    ///         .metallic(1.0)
+    ///         .roughness(0.2);
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn environment_map(&self, map: &EnvironmentMap) -> light::ImageBasedLight {
+        light::environment(self, map)
+    }
+
+    /// Call the given function with the command encoder at this point in the drawing order.
+    ///
+    /// The render pass encoding all primitives drawn so far is ended before the callback is
+    /// called, and a new render pass is begun for those drawn afterwards. This allows for
+    /// interleaving the commands of other wgpu libraries (e.g. custom text or UI renderers)
+    /// among nannou's primitives. The **ViewResources** provide access to the attachments of the
+    /// target along with the transform of this **Draw** instance.
+    ///
+    /// The callback is called each time the **Draw** is rendered, e.g. once for each tile of a
+    /// **draw::canvas::Canvas**.
+    pub fn custom<F>(&self, callback: F)
+    where
+        F: 'static + FnMut(&mut wgpu::CommandEncoder, &renderer::ViewResources),
+    {
+        let mut state = self.state.borrow_mut();
+        if state.last_draw_context.as_ref() != Some(&self.context) {
+            state
+                .draw_commands
+                .push(Some(DrawCommand::Context(self.context.clone())));
+            state.last_draw_context = Some(self.context.clone());
+        }
+        let custom = renderer::Custom::new(callback);
+        state.draw_commands.push(Some(DrawCommand::Custom(custom)));
+    }
+
+    /// Blur everything drawn to the target so far with the given **wgpu::Blur**.
+    ///
+    /// Primitives drawn afterwards are unaffected, e.g. text may be drawn crisply over a blurred
+    /// background. This is encoded as a `custom` callback, so the radius is in pixels of the
+    /// target rather than points.
+    ///
+    /// Non-multisampled targets must have `TextureUsages::TEXTURE_BINDING` enabled, as is the
+    /// case for window frames.
+    pub fn blur(&self, blur: &wgpu::Blur) {
+        self.image_fx(&blur.clone().into());
+    }
+
+    /// Apply the given **wgpu::ImageFx** pass to everything drawn to the target so far.
+    ///
+    /// E.g. `draw.image_fx(&wgpu::ImageFx::auto_levels())` normalizes the range of everything
+    /// drawn before it. Primitives drawn afterwards are unaffected. See `blur` for details on the
+    /// requirements of the target.
+    ///
+    /// Passes that retain state between frames, such as smoothed levels, should be stored in the
+    /// model and reused rather than recreated each frame.
+    pub fn image_fx(&self, fx: &wgpu::ImageFx) {
+        let fx = fx.clone();
+        self.custom(move |encoder, view| {
+            let output = view.output_attachment;
+            fx.encode_multisampled(view.device, encoder, output, output, view.sample_count);
+            if let Some(resolve_target) = view.resolve_target {
+                wgpu::resolve_texture(output, resolve_target, encoder);
+            }
+        });
+    }
+
+    /// Draw the given **GpuMesh**, pulling its vertices directly from its storage buffer.
+    ///
+    /// This allows for drawing the output of a compute pass, e.g. a GPU simulation, without
+    /// reading it back to the CPU. The vertices are transformed by this **Draw** instance's
+    /// transform and drawn in order, after everything drawn so far. This is encoded as a `custom`
+    /// callback, so the depth attachment is unused.
+    pub fn gpu_mesh(&self, mesh: &GpuMesh) {
+        let mesh = mesh.clone();
+        self.custom(move |encoder, view| mesh.encode(encoder, view));
+    }
+
     /// Add the given type to be drawn.
     pub fn a<T>(&self, primitive: T) -> Drawing<T>
     where
         T: Into<Primitive>,
         Primitive: Into<Option<T>>,
     {
-        let index = {
-            let mut state = self.state.borrow_mut();
-            // If drawing with a different context, insert the necessary command to update it.
-            if state.last_draw_context.as_ref() != Some(&self.context) {
-                state
-                    .draw_commands
-                    .push(Some(DrawCommand::Context(self.context.clone())));
-                state.last_draw_context = Some(self.context.clone());
-            }
-            // The primitive will be inserted in the next element.
-            let index = state.draw_commands.len();
-            let primitive: Primitive = primitive.into();
-            state.draw_commands.push(None);
-            state.drawing.insert(index, primitive);
-            index
-        };
+        let index = self.insert_drawing(primitive.into());
         drawing::new(self, index)
     }
 
+    // Begin drawing the given primitive, returning its index within the draw commands.
+    fn insert_drawing(&self, primitive: Primitive) -> usize {
+        let mut state = self.state.borrow_mut();
+        // If drawing with a different context, insert the necessary command to update it.
+        if state.last_draw_context.as_ref() != Some(&self.context) {
+            state
+                .draw_commands
+                .push(Some(DrawCommand::Context(self.context.clone())));
+            state.last_draw_context = Some(self.context.clone());
+        }
+        // The primitive will be inserted in the next element.
+        let index = state.draw_commands.len();
+        state.draw_commands.push(None);
+        state.drawing.insert(index, primitive);
+        index
+    }
+
     /// Begin drawing a **Path**.
     pub fn path(&self) -> Drawing<primitive::PathInit> {
         self.a(Default::default())
@@ -518,6 +1020,19 @@ impl Draw {
         self.a(Default::default())
     }
 
+    /// Begin drawing the primitive registered with the given name.
+    ///
+    /// See the `primitive::custom` module for registering primitives. Nothing is drawn if no
+    /// primitive with the given name is registered at the time of rendering.
+    pub fn custom_primitive(&self, name: &str) -> Drawing<primitive::CustomPrimitive> {
+        self.a(primitive::CustomPrimitive::new(name))
+    }
+
+    /// Begin drawing a **Hexagon**.
+    pub fn hexagon(&self) -> Drawing<primitive::Hexagon> {
+        self.a(Default::default())
+    }
+
     /// Begin drawing a **Line**.
     pub fn line(&self) -> Drawing<primitive::Line> {
         self.a(Default::default())
@@ -553,6 +1068,107 @@ impl Draw {
         self.a(Default::default())
     }
 
+    /// Draw the geometry described by `draw_fn`, tessellating it only the first time the given
+    /// `key` is encountered.
+    ///
+    /// The **Draw** passed to `draw_fn` begins with a default context and shares the theme of
+    /// this **Draw**. Its primitives are recorded as a **RecordedFrame** that is retained between
+    /// frames and replayed within the context of this **Draw**, allowing static backgrounds and
+    /// complex paths to be positioned and transformed each frame without being re-tessellated.
+    /// Use `invalidate_cached` when the geometry must be described again.
+    ///
+    /// As with recording, primitives keep their vertex modes, signed distance fields, textures
+    /// and materials, and context changes within `draw_fn` are applied over the context of this
+    /// **Draw**. **Text** primitives and backgrounds are not supported.
+    ///
+    /// ```
+    /// use nannou::prelude::*;
+    ///
+    /// fn view(app: &App, frame: Frame) {
+    ///     let draw = app.draw();
+    ///     draw.background().color(BLACK);
+    ///     // The grid of circles is only tessellated on the first frame.
+    ///     draw.rotate(app.time * 0.1).cached("grid", |draw| {
+    ///         for x in -10..=10 {
+    ///             for y in -10..=10 {
+    ///                 let p = pt2(x as f32, y as f32) * 30.0;
+    ///                 draw.ellipse().xy(p).radius(5.0).color(WHITE);
+    ///             }
+    ///         }
+    ///     });
+    ///     draw.to_frame(app, &frame).unwrap();
+    /// }
+    /// ```
+    pub fn cached<K, F>(&self, key: K, draw_fn: F)
+    where
+        K: Hash,
+        F: FnOnce(&Draw),
+    {
+        let id = cache_id(&key);
+        let cached = self.state.borrow().cache.get(&id).cloned();
+        let frame = match cached {
+            Some(frame) => frame,
+            None => {
+                let draw = Draw::new();
+                draw.state.borrow_mut().theme = self.state.borrow().theme.clone();
+                draw_fn(&draw);
+                let mut frame = RecordedFrame::record(&draw);
+                frame.background = None;
+                let frame = Rc::new(frame);
+                self.state.borrow_mut().cache.insert(id, frame.clone());
+                frame
+            }
+        };
+        frame.replay(self);
+    }
+
+    /// Discard the geometry cached under the given `key` via `cached`, if any, so that it is
+    /// described again the next time it is drawn.
+    pub fn invalidate_cached<K>(&self, key: K)
+    where
+        K: Hash,
+    {
+        self.state.borrow_mut().cache.remove(&cache_id(&key));
+    }
+
+    /// Discard all geometry cached via `cached`.
+    pub fn clear_cached(&self) {
+        self.state.borrow_mut().cache.clear();
+    }
+
+    /// Begin drawing a **Brush** stroke.
+    ///
+    /// See the `brush` module for capturing and smoothing strokes from pointer input.
+    pub fn brush(&self) -> Drawing<primitive::Brush> {
+        self.a(Default::default())
+    }
+
+    /// Begin tracing lines with a **Turtle** starting at the origin and heading along the *x* axis.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # fn view(app: &App, frame: Frame) {
+    /// let draw = app.draw();
+    /// let t = draw.turtle();
+    /// for _ in 0..5 {
+    ///     t.forward(100.0).turn_degrees(144.0);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// See the **Turtle** type for details.
+    pub fn turtle(&self) -> Turtle {
+        Turtle::new(self.clone())
+    }
+
+    /// Draw the outlines of all colliders and joints within the given physics world.
+    ///
+    /// See `physics2d::World::debug_draw` for details.
+    #[cfg(feature = "physics2d")]
+    pub fn debug_physics(&self, world: &crate::physics2d::World) {
+        world.debug_draw(self);
+    }
+
     /// Begin drawing a **Polyline**.
     ///
     /// Note that this is simply short-hand for `draw.path().stroke()`
@@ -560,6 +1176,35 @@ impl Draw {
         self.path().stroke()
     }
 
+    /// Begin drawing the **Waveform** of the given audio buffer within the given rect.
+    pub fn waveform(&self, buffer: &AudioBuffer, rect: geom::Rect) -> Drawing<primitive::Waveform> {
+        self.a(primitive::Waveform::new(buffer, rect))
+    }
+
+    /// Begin drawing the **Spectrogram** of the given audio buffer within the given rect.
+    pub fn spectrogram(
+        &self,
+        buffer: &AudioBuffer,
+        rect: geom::Rect,
+    ) -> Drawing<primitive::Spectrogram> {
+        self.a(primitive::Spectrogram::new(buffer, rect))
+    }
+
+    /// Begin drawing the given **VectorField2** as a grid of arrows.
+    pub fn vector_field(&self, field: &VectorField2) -> Drawing<primitive::VectorField> {
+        self.a(primitive::VectorField::new(field))
+    }
+
+    /// Begin drawing the contours of the given **ScalarGrid** at each of the given levels.
+    ///
+    /// See `geom::marching_squares` for details.
+    pub fn contours<I>(&self, grid: &geom::ScalarGrid, levels: I) -> Drawing<primitive::Contours>
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        self.a(primitive::Contours::new(grid, levels))
+    }
+
     /// Begin drawing a **Text**.
     pub fn text(&self, s: &str) -> Drawing<primitive::Text> {
         let text = {
@@ -573,15 +1218,99 @@ impl Draw {
 
     /// Begin drawing a **Texture**.
     pub fn texture(&self, view: &dyn wgpu::ToTextureView) -> Drawing<primitive::Texture> {
+        self.audit_texture(view.to_texture_view().format());
         self.a(primitive::Texture::new(view))
     }
 
+    /// Enable or disable the color audit debug mode.
+    ///
+    /// When enabled, colors and textures submitted to the **Draw** are inspected for likely
+    /// mix-ups between sRGB and linear sRGB. See the `draw::color_audit` module for details.
+    pub fn color_audit(&self, enabled: bool) {
+        let mut state = self.state.borrow_mut();
+        match enabled {
+            true if state.color_audit.is_none() => state.color_audit = Some(Default::default()),
+            true => (),
+            false => state.color_audit = None,
+        }
+    }
+
+    /// The warnings produced by the color audit since it was enabled.
+    pub fn color_audit_warnings(&self) -> Vec<color_audit::Warning> {
+        let state = self.state.borrow();
+        state
+            .color_audit
+            .as_ref()
+            .map(|audit| audit.warnings().to_vec())
+            .unwrap_or_default()
+    }
+
+    // Inspect the format of a texture submitted for drawing if the color audit is enabled.
+    pub(crate) fn audit_texture(&self, format: wgpu::TextureFormat) {
+        if let Some(audit) = self.state.borrow_mut().color_audit.as_mut() {
+            audit.texture(format);
+        }
+    }
+
+    // Convert the given color to linear sRGBA, inspecting it if the color audit is enabled.
+    pub(crate) fn linear_color<C>(&self, color: C) -> properties::LinSrgba
+    where
+        C: IntoLinSrgba<properties::ColorScalar>,
+    {
+        let space = color.color_space();
+        let color = color.to_linear();
+        if let Ok(mut state) = self.state.try_borrow_mut() {
+            if let Some(audit) = state.color_audit.as_mut() {
+                audit.color(space, color);
+            }
+        }
+        color
+    }
+
+    /// The ID of the top-most primitive containing the given point, among those assigned an ID via
+    /// `Drawing::pick_id`.
+    ///
+    /// The point is in the coordinate space of the **Draw**, e.g. that of `app.mouse.position()`.
+    /// Until the **Draw** is rendered, the primitives submitted so far are tested. Afterwards, the
+    /// primitives of the most recently rendered frame are tested until the next frame is rendered,
+    /// allowing for picking within event handlers.
+    ///
+    /// **Text** primitives are not pickable.
+    ///
+    /// ```
+    /// use nannou::draw::Draw;
+    /// use nannou::geom::pt2;
+    ///
+    /// let draw = Draw::new();
+    /// draw.rect().w_h(100.0, 100.0).pick_id(1);
+    /// draw.ellipse().x(25.0).w_h(50.0, 50.0).pick_id(2);
+    /// assert_eq!(draw.pick(pt2(-25.0, 0.0)), Some(1));
+    /// assert_eq!(draw.pick(pt2(25.0, 0.0)), Some(2));
+    /// assert_eq!(draw.pick(pt2(100.0, 0.0)), None);
+    /// ```
+    pub fn pick(&self, point: Point2) -> Option<u64> {
+        self.finish_remaining_drawings();
+        let state = self.state.borrow();
+        if state.draw_commands.is_empty() {
+            state.picks.pick(point)
+        } else {
+            pick::tessellate(&state).pick(point)
+        }
+    }
+
     /// Finish any drawings-in-progress and produce an iterator draining the inner draw commands
     /// and yielding them by value.
     pub fn drain_commands(&self) -> impl Iterator<Item = DrawCommand> {
         self.finish_remaining_drawings();
         let cmds = {
             let mut state = self.state.borrow_mut();
+            // Retain the geometry of pickable primitives for picking until the next frame.
+            state.picks = if state.pick_ids.is_empty() {
+                Default::default()
+            } else {
+                pick::tessellate(&state)
+            };
+            state.pick_ids.clear();
             let empty = Vec::with_capacity(state.draw_commands.len());
             std::mem::replace(&mut state.draw_commands, empty)
         };
@@ -615,10 +1344,13 @@ impl Default for State {
     fn default() -> Self {
         let last_draw_context = None;
         let background_color = Default::default();
+        let background_fill = None;
         let draw_commands = Default::default();
         let drawing = Default::default();
         let intermediary_state = RefCell::new(Default::default());
         let theme = Default::default();
+        let color_audit = None;
+        let lighting = Default::default();
         State {
             last_draw_context,
             draw_commands,
@@ -626,6 +1358,13 @@ impl Default for State {
             intermediary_state,
             theme,
             background_color,
+            background_fill,
+            color_audit,
+            lighting,
+            cache: Default::default(),
+            persistent: None,
+            pick_ids: Default::default(),
+            picks: Default::default(),
         }
     }
 }
@@ -648,7 +1387,46 @@ impl Default for Context {
             },
             scissor: Scissor::Full,
             topology: wgpu::RenderPipelineBuilder::DEFAULT_PRIMITIVE_TOPOLOGY,
+            polygon_mode: wgpu::RenderPipelineBuilder::DEFAULT_POLYGON_MODE,
+            depth_write: wgpu::RenderPipelineBuilder::DEFAULT_DEPTH_WRITE_ENABLED,
+            depth_compare: wgpu::RenderPipelineBuilder::DEFAULT_DEPTH_COMPARE,
+            z_index: 0,
             sampler: wgpu::SamplerBuilder::new().into_descriptor(),
+            analytic_aa: false,
+            lit: false,
+            debug_view: None,
+            cull: false,
+            scope: None,
+        }
+    }
+}
+
+// Stably sort the given commands by the `z_index` of the context in which they were submitted.
+pub(crate) fn sort_by_z_index(cmds: Vec<DrawCommand>) -> Vec<DrawCommand> {
+    // Split the commands into runs, each beginning with a change of context.
+    let mut runs: Vec<(i32, Vec<DrawCommand>)> = vec![(0, vec![])];
+    for cmd in cmds {
+        if let DrawCommand::Context(ref ctxt) = cmd {
+            runs.push((ctxt.z_index, vec![]));
         }
+        runs.last_mut().unwrap().1.push(cmd);
+    }
+    let sorted = runs.windows(2).all(|w| w[0].0 <= w[1].0);
+    if !sorted {
+        // Commands preceding the first change of context use the default context.
+        if !runs[0].1.is_empty() {
+            runs[0]
+                .1
+                .insert(0, DrawCommand::Context(Context::default()));
+        }
+        runs.sort_by_key(|&(z_index, _)| z_index);
     }
+    runs.into_iter().flat_map(|(_, cmds)| cmds).collect()
+}
+
+// The ID under which geometry is cached for the given key.
+fn cache_id<K: Hash>(key: &K) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
 }
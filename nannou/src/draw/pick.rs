@@ -0,0 +1,119 @@
+//! Picking of drawn primitives, e.g. for finding the primitive beneath the mouse.
+//!
+//! Primitives opt in to picking via `Drawing::pick_id`. When the **Draw** is rendered, the
+//! geometry of each pickable primitive is tessellated and retained on the CPU until the next time
+//! the **Draw** is rendered, allowing for `Draw::pick` and `App::pick` to be called from event
+//! handlers between frames.
+//!
+//! Primitives rendered as signed distance fields are tessellated from their paths, so that only
+//! the shape itself is picked rather than the quad in which it is rendered.
+
+use crate::draw::primitive::Primitive;
+use crate::draw::renderer::{GlyphCache, RenderContext, RenderPrimitive};
+use crate::draw::{self, DrawCommand};
+use crate::geom::{Point2, Tri};
+use crate::glam::Vec2;
+use lyon::tessellation::{FillTessellator, StrokeTessellator};
+
+/// The tessellated geometry of the pickable primitives of a **Draw**.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Picks {
+    // Ordered from the bottom-most primitive to the top-most.
+    shapes: Vec<Shape>,
+}
+
+#[derive(Clone, Debug)]
+struct Shape {
+    id: u64,
+    z_index: i32,
+    tris: Vec<Tri<Vec2>>,
+}
+
+impl Picks {
+    /// The ID of the top-most primitive containing the given point, if any.
+    pub fn pick(&self, point: Point2) -> Option<u64> {
+        self.shapes
+            .iter()
+            .rev()
+            .find(|shape| shape.tris.iter().any(|tri| contains(tri, point)))
+            .map(|shape| shape.id)
+    }
+}
+
+/// Tessellate the pickable primitives among the draw commands of the given state.
+///
+/// **Text** primitives depend upon the glyph cache of the renderer and are skipped.
+pub(crate) fn tessellate(state: &draw::State) -> Picks {
+    let intermediary_state = state.intermediary_state.borrow();
+    let mut fill_tessellator = FillTessellator::new();
+    let mut stroke_tessellator = StrokeTessellator::new();
+    let mut glyph_cache = GlyphCache::new([1, 1], 1.0, 1.0);
+    let mut sdf_params_buffer = vec![];
+    let mut curr_ctxt = draw::Context::default();
+    let mut shapes = vec![];
+    for (index, cmd) in state.draw_commands.iter().enumerate() {
+        let prim = match cmd {
+            Some(DrawCommand::Context(ctxt)) => {
+                curr_ctxt = ctxt.clone();
+                continue;
+            }
+            Some(DrawCommand::Primitive(prim)) => prim,
+            _ => continue,
+        };
+        let id = match state.pick_ids.get(&index) {
+            Some(&id) => id,
+            None => continue,
+        };
+        if let Primitive::Text(_) = prim {
+            continue;
+        }
+        let ctxt = RenderContext {
+            intermediary_mesh: &intermediary_state.intermediary_mesh,
+            path_event_buffer: &intermediary_state.path_event_buffer,
+            path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+            path_points_textured_buffer: &intermediary_state.path_points_textured_buffer,
+            text_buffer: &intermediary_state.text_buffer,
+            theme: &state.theme,
+            transform: &curr_ctxt.transform,
+            fill_tessellator: &mut fill_tessellator,
+            stroke_tessellator: &mut stroke_tessellator,
+            sdf_params_buffer: &mut sdf_params_buffer,
+            glyph_cache: &mut glyph_cache,
+            output_attachment_size: Vec2::ONE,
+            output_attachment_scale_factor: 1.0,
+            analytic_aa: false,
+            sdf: false,
+            vector_paths: None,
+        };
+        let mut mesh = draw::Mesh::default();
+        prim.clone().render_primitive(ctxt, &mut mesh);
+        sdf_params_buffer.clear();
+        let points = mesh.points();
+        let tris = mesh
+            .indices()
+            .chunks_exact(3)
+            .map(|ix| Tri([0, 1, 2].map(|i| points[ix[i] as usize].truncate())))
+            .filter(|&Tri([a, b, c])| (b - a).perp_dot(c - a) != 0.0)
+            .collect();
+        shapes.push(Shape {
+            id,
+            z_index: curr_ctxt.z_index,
+            tris,
+        });
+    }
+    // Primitives are drawn in order of their z index, then in the order in which they were
+    // submitted.
+    shapes.sort_by_key(|shape| shape.z_index);
+    Picks { shapes }
+}
+
+// Whether the triangle contains the point. Unlike `Tri::contains`, points along the edges are
+// included so that points along the edges shared between triangles are not missed.
+fn contains(&Tri([a, b, c]): &Tri<Vec2>, p: Point2) -> bool {
+    let d1 = (b - a).perp_dot(p - a);
+    let d2 = (c - b).perp_dot(p - b);
+    let d3 = (a - c).perp_dot(p - c);
+    let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_neg && has_pos)
+}
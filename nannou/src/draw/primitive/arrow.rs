@@ -185,7 +185,8 @@ impl draw::renderer::RenderPrimitive for Arrow {
         }
 
         // Calculate the arrow head points.
-        let line_w_2 = line.path.opts.line_width * 2.0;
+        let line_w = ctxt.stroke_weight(line.path.opts.line_width, ctxt.transform);
+        let line_w_2 = line_w * 2.0;
         let line_w_4 = line_w_2 * 2.0;
         let head_width = head_width.unwrap_or(line_w_2);
         let head_length = head_length.unwrap_or(line_w_4);
@@ -223,6 +224,7 @@ impl draw::renderer::RenderPrimitive for Arrow {
             &draw::theme::Primitive::Arrow,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
             mesh,
         );
 
@@ -236,11 +238,12 @@ impl draw::renderer::RenderPrimitive for Arrow {
                 line_events,
                 line.path.color,
                 transform,
-                path::Options::Stroke(line.path.opts),
+                path::Options::Stroke(ctxt.stroke_options(line.path.opts, &transform)),
                 &ctxt.theme,
                 &draw::theme::Primitive::Arrow,
                 &mut ctxt.fill_tessellator,
                 &mut ctxt.stroke_tessellator,
+                ctxt.vector_paths.as_deref_mut(),
                 mesh,
             );
         }
@@ -0,0 +1,422 @@
+use crate::audio_buffer::AudioBuffer;
+use crate::draw::mesh::vertex;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
+use crate::draw::{self, Drawing};
+use crate::geom;
+use crate::glam::{Mat4, Vec2};
+use std::ops::Range;
+
+/// Properties related to drawing the **Waveform** of an **AudioBuffer**.
+///
+/// The waveform is drawn as a vertical bar spanning the minimum and maximum sample of each column
+/// within the rect, with silence along the rect's horizontal centre.
+#[derive(Clone, Debug)]
+pub struct Waveform {
+    buffer: AudioBuffer,
+    rect: geom::Rect,
+    channel: Option<usize>,
+    seconds: Option<Range<f32>>,
+    resolution: f32,
+    color: Option<LinSrgba>,
+    position: position::Properties,
+    orientation: orientation::Properties,
+}
+
+/// The drawing context for a **Waveform**.
+pub type DrawingWaveform<'a> = Drawing<'a, Waveform>;
+
+/// Properties related to drawing the **Spectrogram** of an **AudioBuffer**.
+///
+/// Time runs from the left of the rect to the right and frequency from the bottom to the top.
+/// The loudness of each frequency is drawn with the opacity of the color.
+#[derive(Clone, Debug)]
+pub struct Spectrogram {
+    buffer: AudioBuffer,
+    rect: geom::Rect,
+    channel: Option<usize>,
+    seconds: Option<Range<f32>>,
+    resolution: f32,
+    window_size: usize,
+    decibels: Range<f32>,
+    log_frequency: bool,
+    color: Option<LinSrgba>,
+    position: position::Properties,
+    orientation: orientation::Properties,
+}
+
+/// The drawing context for a **Spectrogram**.
+pub type DrawingSpectrogram<'a> = Drawing<'a, Spectrogram>;
+
+// The minimum height of a waveform bar so that silence remains visible.
+const MIN_BAR_HEIGHT: f32 = 1.0;
+
+// Waveform-specific methods.
+
+impl Waveform {
+    /// The default width of each column in points.
+    pub const DEFAULT_RESOLUTION: f32 = 1.0;
+
+    /// Draw the waveform of the given buffer within the given rect.
+    pub fn new(buffer: &AudioBuffer, rect: geom::Rect) -> Self {
+        Waveform {
+            buffer: buffer.clone(),
+            rect,
+            channel: None,
+            seconds: None,
+            resolution: Self::DEFAULT_RESOLUTION,
+            color: None,
+            position: Default::default(),
+            orientation: Default::default(),
+        }
+    }
+
+    /// Draw a single channel rather than the average of all channels.
+    pub fn channel(mut self, channel: usize) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Draw only the given span of time in seconds rather than the whole buffer.
+    pub fn seconds(mut self, seconds: Range<f32>) -> Self {
+        self.seconds = Some(seconds);
+        self
+    }
+
+    /// The width of each column in points. By default, this is `DEFAULT_RESOLUTION`.
+    pub fn resolution(mut self, points: f32) -> Self {
+        self.resolution = points;
+        self
+    }
+}
+
+// Spectrogram-specific methods.
+
+impl Spectrogram {
+    /// The default width and height of each cell in points.
+    pub const DEFAULT_RESOLUTION: f32 = 2.0;
+    /// The default number of frames analysed for each column.
+    pub const DEFAULT_WINDOW_SIZE: usize = 1024;
+    /// The default range of loudness in decibels mapped to opacity.
+    pub const DEFAULT_DECIBELS: Range<f32> = -80.0..0.0;
+
+    /// Draw the spectrogram of the given buffer within the given rect.
+    pub fn new(buffer: &AudioBuffer, rect: geom::Rect) -> Self {
+        Spectrogram {
+            buffer: buffer.clone(),
+            rect,
+            channel: None,
+            seconds: None,
+            resolution: Self::DEFAULT_RESOLUTION,
+            window_size: Self::DEFAULT_WINDOW_SIZE,
+            decibels: Self::DEFAULT_DECIBELS,
+            log_frequency: false,
+            color: None,
+            position: Default::default(),
+            orientation: Default::default(),
+        }
+    }
+
+    /// Draw a single channel rather than the average of all channels.
+    pub fn channel(mut self, channel: usize) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Draw only the given span of time in seconds rather than the whole buffer.
+    pub fn seconds(mut self, seconds: Range<f32>) -> Self {
+        self.seconds = Some(seconds);
+        self
+    }
+
+    /// The width and height of each cell in points. By default, this is `DEFAULT_RESOLUTION`.
+    pub fn resolution(mut self, points: f32) -> Self {
+        self.resolution = points;
+        self
+    }
+
+    /// The number of frames analysed for each column, rounded up to a power of two.
+    ///
+    /// Larger windows distinguish between frequencies more finely at the cost of blurring over
+    /// time. By default, this is `DEFAULT_WINDOW_SIZE`.
+    pub fn window_size(mut self, frames: usize) -> Self {
+        self.window_size = frames;
+        self
+    }
+
+    /// The range of loudness in decibels that is mapped from transparent to opaque.
+    pub fn decibels(mut self, decibels: Range<f32>) -> Self {
+        self.decibels = decibels;
+        self
+    }
+
+    /// Whether or not to space frequencies logarithmically, as they are perceived.
+    pub fn log_frequency(mut self, log: bool) -> Self {
+        self.log_frequency = log;
+        self
+    }
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Waveform {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Waveform {
+            buffer,
+            rect,
+            channel,
+            seconds,
+            resolution,
+            color,
+            position,
+            orientation,
+        } = self;
+        let transform = *ctxt.transform * position.transform() * orientation.transform();
+        let color =
+            color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&draw::theme::Primitive::Waveform));
+        let frames = frame_range(&buffer, seconds);
+        let columns = column_count(rect.w(), resolution);
+        let column_w = rect.w() / columns as f32;
+        let half_h = rect.h() * 0.5;
+        for (i, [min, max]) in buffer
+            .peaks(channel, frames, columns)
+            .into_iter()
+            .enumerate()
+        {
+            let x = rect.left() + i as f32 * column_w;
+            let (mut bottom, mut top) = (min * half_h, max * half_h);
+            let pad = (MIN_BAR_HEIGHT - (top - bottom)).max(0.0) * 0.5;
+            bottom = (bottom - pad).max(-half_h) + rect.y();
+            top = (top + pad).min(half_h) + rect.y();
+            let corners = [
+                [x, bottom],
+                [x + column_w, bottom],
+                [x + column_w, top],
+                [x, top],
+            ];
+            push_quad(mesh, &transform, color, &corners);
+        }
+        draw::renderer::PrimitiveRender::default()
+    }
+}
+
+impl draw::renderer::RenderPrimitive for Spectrogram {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Spectrogram {
+            buffer,
+            rect,
+            channel,
+            seconds,
+            resolution,
+            window_size,
+            decibels,
+            log_frequency,
+            color,
+            position,
+            orientation,
+        } = self;
+        let transform = *ctxt.transform * position.transform() * orientation.transform();
+        let color = color.unwrap_or_else(|| {
+            ctxt.theme
+                .fill_lin_srgba(&draw::theme::Primitive::Spectrogram)
+        });
+        let frames = frame_range(&buffer, seconds);
+        let columns = column_count(rect.w(), resolution);
+        let rows = column_count(rect.h(), resolution);
+        let column_w = rect.w() / columns as f32;
+        let row_h = rect.h() / rows as f32;
+        let db_span = (decibels.end - decibels.start).max(f32::EPSILON);
+
+        for c in 0..columns {
+            // Analyse the window centred on the middle of the column.
+            let t = (c as f32 + 0.5) / columns as f32;
+            let frame = frames.start + ((frames.end - frames.start) as f32 * t) as usize;
+            let spectrum = buffer.spectrum(channel, frame, window_size);
+            let bins = spectrum.len() - 1;
+            let x = rect.left() + c as f32 * column_w;
+            for r in 0..rows {
+                // The range of bins covered by the row, taking the loudest within.
+                let bin_at = |row: f32| -> f32 {
+                    let f = row / rows as f32;
+                    match log_frequency {
+                        // Span from the first non-zero bin up to the highest.
+                        true => (bins as f32).powf(f),
+                        false => f * bins as f32,
+                    }
+                };
+                let (lo, hi) = (bin_at(r as f32), bin_at(r as f32 + 1.0));
+                let lo = (lo.floor() as usize).min(bins);
+                let hi = (hi.ceil() as usize).clamp(lo + 1, bins + 1);
+                let magnitude = spectrum[lo..hi].iter().cloned().fold(0.0, f32::max);
+                let db = 20.0 * magnitude.max(1e-10).log10();
+                let intensity = ((db - decibels.start) / db_span).clamp(0.0, 1.0);
+                if intensity <= 0.0 {
+                    continue;
+                }
+                let mut color = color;
+                color.alpha *= intensity;
+                let y = rect.bottom() + r as f32 * row_h;
+                let corners = [
+                    [x, y],
+                    [x + column_w, y],
+                    [x + column_w, y + row_h],
+                    [x, y + row_h],
+                ];
+                push_quad(mesh, &transform, color, &corners);
+            }
+        }
+        draw::renderer::PrimitiveRender::default()
+    }
+}
+
+impl SetOrientation for Waveform {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl SetPosition for Waveform {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl SetColor<ColorScalar> for Waveform {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        &mut self.color
+    }
+}
+
+impl SetOrientation for Spectrogram {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl SetPosition for Spectrogram {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl SetColor<ColorScalar> for Spectrogram {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        &mut self.color
+    }
+}
+
+// Primitive conversions.
+
+impl From<Waveform> for Primitive {
+    fn from(prim: Waveform) -> Self {
+        Primitive::Waveform(prim)
+    }
+}
+
+impl Into<Option<Waveform>> for Primitive {
+    fn into(self) -> Option<Waveform> {
+        match self {
+            Primitive::Waveform(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+impl From<Spectrogram> for Primitive {
+    fn from(prim: Spectrogram) -> Self {
+        Primitive::Spectrogram(prim)
+    }
+}
+
+impl Into<Option<Spectrogram>> for Primitive {
+    fn into(self) -> Option<Spectrogram> {
+        match self {
+            Primitive::Spectrogram(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingWaveform<'a> {
+    /// Draw a single channel rather than the average of all channels.
+    pub fn channel(self, channel: usize) -> Self {
+        self.map_ty(|ty| ty.channel(channel))
+    }
+
+    /// Draw only the given span of time in seconds rather than the whole buffer.
+    pub fn seconds(self, seconds: Range<f32>) -> Self {
+        self.map_ty(|ty| ty.seconds(seconds))
+    }
+
+    /// The width of each column in points.
+    pub fn resolution(self, points: f32) -> Self {
+        self.map_ty(|ty| ty.resolution(points))
+    }
+}
+
+impl<'a> DrawingSpectrogram<'a> {
+    /// Draw a single channel rather than the average of all channels.
+    pub fn channel(self, channel: usize) -> Self {
+        self.map_ty(|ty| ty.channel(channel))
+    }
+
+    /// Draw only the given span of time in seconds rather than the whole buffer.
+    pub fn seconds(self, seconds: Range<f32>) -> Self {
+        self.map_ty(|ty| ty.seconds(seconds))
+    }
+
+    /// The width and height of each cell in points.
+    pub fn resolution(self, points: f32) -> Self {
+        self.map_ty(|ty| ty.resolution(points))
+    }
+
+    /// The number of frames analysed for each column, rounded up to a power of two.
+    pub fn window_size(self, frames: usize) -> Self {
+        self.map_ty(|ty| ty.window_size(frames))
+    }
+
+    /// The range of loudness in decibels that is mapped from transparent to opaque.
+    pub fn decibels(self, decibels: Range<f32>) -> Self {
+        self.map_ty(|ty| ty.decibels(decibels))
+    }
+
+    /// Whether or not to space frequencies logarithmically, as they are perceived.
+    pub fn log_frequency(self, log: bool) -> Self {
+        self.map_ty(|ty| ty.log_frequency(log))
+    }
+}
+
+// The range of frames covered by the given span of seconds, or the whole buffer.
+fn frame_range(buffer: &AudioBuffer, seconds: Option<Range<f32>>) -> Range<usize> {
+    match seconds {
+        Some(secs) => buffer.frame_at(secs.start)..buffer.frame_at(secs.end),
+        None => 0..buffer.len_frames(),
+    }
+}
+
+// The number of columns of the given width that fit within the given length, at least one.
+fn column_count(len: f32, resolution: f32) -> usize {
+    (len.abs() / resolution.max(0.1)).ceil().max(1.0) as usize
+}
+
+fn push_quad(mesh: &mut draw::Mesh, transform: &Mat4, color: LinSrgba, corners: &[[f32; 2]; 4]) {
+    let start = mesh.points().len() as u32;
+    for &[x, y] in corners {
+        let point = transform.transform_point3(Vec2::new(x, y).extend(0.0));
+        mesh.push_vertex(vertex::new(point, color, Vec2::ZERO));
+    }
+    for &i in [0, 1, 2, 0, 2, 3].iter() {
+        mesh.push_index(start + i);
+    }
+}
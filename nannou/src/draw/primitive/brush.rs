@@ -0,0 +1,431 @@
+use crate::brush::Sample;
+use crate::curve::Curve;
+use crate::draw::mesh::vertex;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
+use crate::draw::renderer::sdf::{self, SdfParams};
+use crate::draw::renderer::{PrimitiveRender, VertexMode};
+use crate::draw::{self, Drawing};
+use crate::glam::{Mat4, Vec2};
+use crate::rand::rngs::SmallRng;
+use crate::rand::{Rng, SeedableRng};
+use crate::wgpu;
+
+/// Properties related to drawing a **Brush** stroke.
+///
+/// A brush stroke is rendered by stamping a series of quads (or "dabs") along the path described
+/// by its samples. By default each dab is a soft round shape. A texture may be provided via
+/// `stamp` in order to stamp an image instead, tinted by the brush color.
+#[derive(Clone, Debug)]
+pub struct Brush {
+    samples: Vec<Sample>,
+    stamp: Option<wgpu::TextureView>,
+    size: f32,
+    spacing: f32,
+    jitter: f32,
+    rotation_jitter: f32,
+    follow_direction: bool,
+    hardness: f32,
+    size_curve: Option<Curve>,
+    opacity_curve: Option<Curve>,
+    seed: u64,
+    color: Option<LinSrgba>,
+    position: position::Properties,
+    orientation: orientation::Properties,
+}
+
+/// The drawing context for a brush stroke.
+pub type DrawingBrush<'a> = Drawing<'a, Brush>;
+
+// The minimum distance between dabs, avoiding excessive stamping for tiny sizes or spacings.
+const MIN_STEP: f32 = 0.5;
+
+// Brush-specific methods.
+
+impl Brush {
+    /// The samples along which the stroke is stamped.
+    ///
+    /// Accepts any iterator yielding items that may be converted into a **Sample**, e.g. a
+    /// `&brush::Stroke`, points (with full pressure) or `(point, pressure)` pairs.
+    pub fn points<I>(mut self, points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Sample>,
+    {
+        self.samples = points.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Stamp the given texture rather than a soft round dab.
+    ///
+    /// The texture is multiplied by the brush color, so white stamps take on the brush color.
+    pub fn stamp(mut self, view: &dyn wgpu::ToTextureView) -> Self {
+        self.stamp = Some(view.to_texture_view());
+        self
+    }
+
+    /// The diameter of each dab at full pressure.
+    ///
+    /// By default, this is `16.0`.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// The distance between dabs as a fraction of the size of the dab.
+    ///
+    /// By default, this is `0.1`.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Randomly offset each dab by up to the given fraction of its size.
+    ///
+    /// By default, this is `0.0`.
+    pub fn jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Randomly rotate each dab by up to the given angle in radians in either direction.
+    ///
+    /// By default, this is `0.0`.
+    pub fn rotation_jitter(mut self, radians: f32) -> Self {
+        self.rotation_jitter = radians;
+        self
+    }
+
+    /// Whether or not to rotate each dab to follow the direction of the stroke.
+    ///
+    /// By default, this is `false`.
+    pub fn follow_direction(mut self, follow: bool) -> Self {
+        self.follow_direction = follow;
+        self
+    }
+
+    /// The fraction of the radius of the default round dab that is fully opaque, the remainder
+    /// of which fades out towards the edge.
+    ///
+    /// Has no effect when a `stamp` is specified. By default, this is `0.5`.
+    pub fn hardness(mut self, hardness: f32) -> Self {
+        self.hardness = hardness;
+        self
+    }
+
+    /// Map the pressure of each sample to a multiplier for the size of the dab.
+    ///
+    /// By default, the size is proportional to the pressure.
+    pub fn size_curve(mut self, curve: Curve) -> Self {
+        self.size_curve = Some(curve);
+        self
+    }
+
+    /// Map the pressure of each sample to a multiplier for the opacity of the dab.
+    ///
+    /// By default, the opacity is unaffected by pressure.
+    pub fn opacity_curve(mut self, curve: Curve) -> Self {
+        self.opacity_curve = Some(curve);
+        self
+    }
+
+    /// The seed used to produce jitter.
+    ///
+    /// Jitter is deterministic for a given seed, so a stroke looks the same from frame to frame.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Brush {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> PrimitiveRender {
+        let Brush {
+            samples,
+            stamp,
+            size,
+            spacing,
+            jitter,
+            rotation_jitter,
+            follow_direction,
+            hardness,
+            size_curve,
+            opacity_curve,
+            seed,
+            color,
+            position,
+            orientation,
+        } = self;
+        let transform = *ctxt.transform * position.transform() * orientation.transform();
+        let color =
+            color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&draw::theme::Primitive::Brush));
+        let size_at = |pressure: f32| {
+            let scale = match size_curve {
+                Some(ref curve) => curve.sample(pressure),
+                None => pressure,
+            };
+            size * scale
+        };
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let aa_pad = sdf::aa_padding(transform);
+        let hardness = hardness.clamp(0.01, 1.0);
+        // Ensure the params buffer is aligned with the mesh before pushing our own vertices.
+        ctxt.sdf_params_buffer
+            .resize(mesh.points().len(), Default::default());
+
+        for dab in dabs(&samples, spacing, &size_at) {
+            // Always draw from the rng so that the jitter of each dab is stable.
+            let offset = Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0));
+            let rotation = rng.gen_range(-1.0..=1.0) * rotation_jitter;
+            let diameter = size_at(dab.pressure);
+            let alpha = match opacity_curve {
+                Some(ref curve) => curve.sample(dab.pressure),
+                None => 1.0,
+            };
+            if diameter <= 0.0 || alpha <= 0.0 {
+                continue;
+            }
+            let mut color = color;
+            color.alpha *= alpha;
+            let radius = diameter * 0.5;
+            let centre = dab.position + offset * jitter * diameter;
+            let angle = match follow_direction {
+                true => dab.direction + rotation,
+                false => rotation,
+            };
+            let dab_transform = transform
+                * Mat4::from_translation(centre.extend(0.0))
+                * Mat4::from_rotation_z(angle);
+
+            match stamp {
+                Some(_) => {
+                    let corners = [
+                        ([-radius, -radius], [0.0, 1.0]),
+                        ([radius, -radius], [1.0, 1.0]),
+                        ([radius, radius], [1.0, 0.0]),
+                        ([-radius, radius], [0.0, 0.0]),
+                    ];
+                    push_quad(mesh, &dab_transform, color, corners.iter().cloned());
+                }
+                None => {
+                    let half_size = radius * hardness;
+                    let params = SdfParams {
+                        half_size: [half_size; 2],
+                        corner_radius: 0.0,
+                        stroke_weight: 0.0,
+                        softness: radius - half_size,
+                    };
+                    let r = radius + aa_pad;
+                    let corners = [[-r, -r], [r, -r], [r, r], [-r, r]];
+                    push_quad(mesh, &dab_transform, color, corners.iter().map(|&p| (p, p)));
+                    ctxt.sdf_params_buffer.extend((0..4).map(|_| params));
+                }
+            }
+        }
+
+        match stamp {
+            Some(texture_view) => PrimitiveRender {
+                texture_view: Some(texture_view),
+                vertex_mode: VertexMode::TextureTinted,
+            },
+            None => PrimitiveRender::vertex_mode(VertexMode::SdfEllipse),
+        }
+    }
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Brush {
+            samples: vec![],
+            stamp: None,
+            size: 16.0,
+            spacing: 0.1,
+            jitter: 0.0,
+            rotation_jitter: 0.0,
+            follow_direction: false,
+            hardness: 0.5,
+            size_curve: None,
+            opacity_curve: None,
+            seed: 0,
+            color: None,
+            position: Default::default(),
+            orientation: Default::default(),
+        }
+    }
+}
+
+impl SetOrientation for Brush {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl SetPosition for Brush {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl SetColor<ColorScalar> for Brush {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        &mut self.color
+    }
+}
+
+// Primitive conversion.
+
+impl From<Brush> for Primitive {
+    fn from(prim: Brush) -> Self {
+        Primitive::Brush(prim)
+    }
+}
+
+impl Into<Option<Brush>> for Primitive {
+    fn into(self) -> Option<Brush> {
+        match self {
+            Primitive::Brush(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingBrush<'a> {
+    /// The samples along which the stroke is stamped.
+    pub fn points<I>(self, points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Sample>,
+    {
+        self.map_ty(|ty| ty.points(points))
+    }
+
+    /// Stamp the given texture rather than a soft round dab.
+    pub fn stamp(self, view: &dyn wgpu::ToTextureView) -> Self {
+        self.audit_texture(view.to_texture_view().format());
+        self.map_ty(|ty| ty.stamp(view))
+    }
+
+    /// The diameter of each dab at full pressure.
+    pub fn size(self, size: f32) -> Self {
+        self.map_ty(|ty| ty.size(size))
+    }
+
+    /// The distance between dabs as a fraction of the size of the dab.
+    pub fn spacing(self, spacing: f32) -> Self {
+        self.map_ty(|ty| ty.spacing(spacing))
+    }
+
+    /// Randomly offset each dab by up to the given fraction of its size.
+    pub fn jitter(self, jitter: f32) -> Self {
+        self.map_ty(|ty| ty.jitter(jitter))
+    }
+
+    /// Randomly rotate each dab by up to the given angle in radians in either direction.
+    pub fn rotation_jitter(self, radians: f32) -> Self {
+        self.map_ty(|ty| ty.rotation_jitter(radians))
+    }
+
+    /// Whether or not to rotate each dab to follow the direction of the stroke.
+    pub fn follow_direction(self, follow: bool) -> Self {
+        self.map_ty(|ty| ty.follow_direction(follow))
+    }
+
+    /// The fraction of the radius of the default round dab that is fully opaque.
+    pub fn hardness(self, hardness: f32) -> Self {
+        self.map_ty(|ty| ty.hardness(hardness))
+    }
+
+    /// Map the pressure of each sample to a multiplier for the size of the dab.
+    pub fn size_curve(self, curve: Curve) -> Self {
+        self.map_ty(|ty| ty.size_curve(curve))
+    }
+
+    /// Map the pressure of each sample to a multiplier for the opacity of the dab.
+    pub fn opacity_curve(self, curve: Curve) -> Self {
+        self.map_ty(|ty| ty.opacity_curve(curve))
+    }
+
+    /// The seed used to produce jitter.
+    pub fn seed(self, seed: u64) -> Self {
+        self.map_ty(|ty| ty.seed(seed))
+    }
+}
+
+// A single stamp along a stroke.
+struct Dab {
+    position: Vec2,
+    pressure: f32,
+    // The angle of the stroke at the dab in radians.
+    direction: f32,
+}
+
+// Walk the polyline described by the samples, producing a dab every `spacing * size` points.
+fn dabs(samples: &[Sample], spacing: f32, size_at: &dyn Fn(f32) -> f32) -> Vec<Dab> {
+    let step = |pressure: f32| (spacing * size_at(pressure)).max(MIN_STEP);
+    let mut dabs = vec![];
+    let first = match samples.first() {
+        None => return dabs,
+        Some(first) => first,
+    };
+    let mut direction = match samples.get(1) {
+        Some(next) => angle(next.position - first.position),
+        None => 0.0,
+    };
+    // The distance along the current segment at which the next dab should be stamped.
+    let mut next = 0.0;
+    for pair in samples.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let delta = b.position - a.position;
+        let len = delta.length();
+        if len > 0.0 {
+            direction = angle(delta);
+        }
+        while next <= len {
+            let t = if len > 0.0 { next / len } else { 0.0 };
+            let pressure = a.pressure + (b.pressure - a.pressure) * t;
+            dabs.push(Dab {
+                position: a.position + delta * t,
+                pressure,
+                direction,
+            });
+            next += step(pressure);
+        }
+        next -= len;
+    }
+    if samples.len() == 1 {
+        dabs.push(Dab {
+            position: first.position,
+            pressure: first.pressure,
+            direction,
+        });
+    }
+    dabs
+}
+
+fn angle(v: Vec2) -> f32 {
+    v.y.atan2(v.x)
+}
+
+// Push a quad with the given local corner positions and texture coordinates to the mesh.
+fn push_quad<I>(mesh: &mut draw::Mesh, transform: &Mat4, color: LinSrgba, corners: I)
+where
+    I: IntoIterator<Item = ([f32; 2], [f32; 2])>,
+{
+    let start = mesh.points().len() as u32;
+    for ([x, y], [tx, ty]) in corners {
+        let point = transform.transform_point3(Vec2::new(x, y).extend(0.0));
+        mesh.push_vertex(vertex::new(point, color, Vec2::new(tx, ty)));
+    }
+    for &i in [0, 1, 2, 0, 2, 3].iter() {
+        mesh.push_index(start + i);
+    }
+}
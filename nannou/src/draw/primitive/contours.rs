@@ -0,0 +1,170 @@
+use crate::color::LinSrgba;
+use crate::draw::primitive::path;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, SetColor, SetOrientation, SetPosition};
+use crate::draw::{self, Drawing};
+use crate::geom::{self, ScalarGrid};
+use lyon::tessellation::StrokeOptions;
+
+/// Properties related to drawing the contours of a **ScalarGrid** at a set of levels.
+#[derive(Clone, Debug)]
+pub struct Contours {
+    grid: ScalarGrid,
+    levels: Vec<f32>,
+    smoothing: usize,
+    weight: f32,
+    color: Option<LinSrgba>,
+    position: position::Properties,
+    orientation: orientation::Properties,
+}
+
+/// The drawing context for **Contours**.
+pub type DrawingContours<'a> = Drawing<'a, Contours>;
+
+// Contours specific methods.
+
+impl Contours {
+    /// The default stroke weight of each contour.
+    pub const DEFAULT_WEIGHT: f32 = 1.0;
+
+    /// Draw the contours of the given grid at each of the given levels.
+    pub fn new<I>(grid: &ScalarGrid, levels: I) -> Self
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        Contours {
+            grid: grid.clone(),
+            levels: levels.into_iter().collect(),
+            smoothing: 0,
+            weight: Self::DEFAULT_WEIGHT,
+            color: None,
+            position: Default::default(),
+            orientation: Default::default(),
+        }
+    }
+
+    /// The number of iterations of smoothing applied to each contour.
+    ///
+    /// See `Contour::smooth` for details. By default, contours are not smoothed.
+    pub fn smooth(mut self, iterations: usize) -> Self {
+        self.smoothing = iterations;
+        self
+    }
+
+    /// The stroke weight of each contour.
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Contours {
+    fn render_primitive(
+        self,
+        mut ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Contours {
+            grid,
+            levels,
+            smoothing,
+            weight,
+            color,
+            position,
+            orientation,
+        } = self;
+
+        // Collect the contours at all levels into one path.
+        let mut builder = lyon::path::Path::builder();
+        let point = |p: geom::Point2| lyon::math::point(p.x, p.y);
+        for level in levels {
+            for contour in geom::marching_squares(&grid, level) {
+                let contour = contour.smooth(smoothing);
+                let mut points = contour.points.iter();
+                let first = match points.next() {
+                    Some(&p) => p,
+                    None => continue,
+                };
+                builder.begin(point(first));
+                for &p in points {
+                    builder.line_to(point(p));
+                }
+                builder.end(contour.closed);
+            }
+        }
+
+        let transform = *ctxt.transform * position.transform() * orientation.transform();
+        let color = color.unwrap_or_else(|| {
+            ctxt.theme
+                .stroke_lin_srgba(&draw::theme::Primitive::Contours)
+        });
+        let stroke_opts = StrokeOptions::default().with_line_width(weight);
+        let path = builder.build();
+        path::render_path_events(
+            path.iter(),
+            Some(color),
+            transform,
+            path::Options::Stroke(ctxt.stroke_options(stroke_opts, &transform)),
+            ctxt.theme,
+            &draw::theme::Primitive::Contours,
+            ctxt.fill_tessellator,
+            ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
+            mesh,
+        );
+
+        draw::renderer::PrimitiveRender::default()
+    }
+}
+
+impl SetOrientation for Contours {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl SetPosition for Contours {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl SetColor<ColorScalar> for Contours {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        &mut self.color
+    }
+}
+
+// Primitive conversions.
+
+impl From<Contours> for Primitive {
+    fn from(prim: Contours) -> Self {
+        Primitive::Contours(prim)
+    }
+}
+
+impl Into<Option<Contours>> for Primitive {
+    fn into(self) -> Option<Contours> {
+        match self {
+            Primitive::Contours(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingContours<'a> {
+    /// The number of iterations of smoothing applied to each contour.
+    pub fn smooth(self, iterations: usize) -> Self {
+        self.map_ty(|ty| ty.smooth(iterations))
+    }
+
+    /// The stroke weight of each contour.
+    pub fn weight(self, weight: f32) -> Self {
+        self.map_ty(|ty| ty.weight(weight))
+    }
+}
@@ -0,0 +1,321 @@
+//! Named primitives whose geometry is provided by user-registered functions.
+//!
+//! Libraries, plugins and scripting layers can extend the **Draw** API without forking the crate
+//! by registering a function that produces the outline of a new primitive. The primitive may then
+//! be drawn by name via **Draw::custom_primitive**, supporting the same position, orientation,
+//! dimension, color and stroke methods as the built-in polygonal primitives.
+//!
+//! ```
+//! use nannou::draw::primitive::custom::{self, Params};
+//! use nannou::draw::Draw;
+//! use nannou::lyon::math::point;
+//! use nannou::lyon::path::Path;
+//!
+//! custom::register_with_defaults("starburst", Params::new().with("points", 8.0), |size, params| {
+//!     let points = params.get_or("points", 8.0).max(2.0) as usize;
+//!     let (outer, inner) = (size * 0.5, size * 0.2);
+//!     let mut builder = Path::builder();
+//!     for i in 0..points * 2 {
+//!         let r = if i % 2 == 0 { outer } else { inner };
+//!         let angle = i as f32 / (points * 2) as f32 * std::f32::consts::TAU;
+//!         let p = point(angle.cos() * r.x, angle.sin() * r.y);
+//!         if i == 0 {
+//!             builder.begin(p);
+//!         } else {
+//!             builder.line_to(p);
+//!         }
+//!     }
+//!     builder.end(true);
+//!     builder.build()
+//! });
+//!
+//! let draw = Draw::new();
+//! draw.custom_primitive("starburst").param("points", 12.0).w_h(200.0, 200.0);
+//! assert!(!nannou::draw::vector::paths(&draw).is_empty());
+//! ```
+
+use crate::color::conv::IntoLinSrgba;
+use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{dimension, orientation, position};
+use crate::draw::properties::{
+    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+};
+use crate::draw::{self, Drawing};
+use crate::glam::Vec2;
+use lyon::path::Path;
+use lyon::tessellation::StrokeOptions;
+use std::collections::{BTreeMap, HashMap};
+use std::iter::FromIterator;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// A function producing the outline of a custom primitive.
+///
+/// The function is given the width and height of the primitive along with its parameters and
+/// should produce a path centred on the origin.
+pub type PathFn = dyn Fn(Vec2, &Params) -> Path + Send + Sync;
+
+/// Named numeric parameters passed to the function of a custom primitive.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, f32>,
+}
+
+/// Properties related to drawing a primitive registered via `register`.
+#[derive(Clone, Debug, Default)]
+pub struct CustomPrimitive {
+    name: String,
+    params: Params,
+    dimensions: spatial::dimension::Properties,
+    polygon: PolygonInit,
+}
+
+/// The drawing context for a custom primitive.
+pub type DrawingCustomPrimitive<'a> = Drawing<'a, CustomPrimitive>;
+
+// A registered primitive.
+#[derive(Clone)]
+struct Registered {
+    defaults: Params,
+    path: Arc<PathFn>,
+}
+
+// All registered primitives by name.
+fn registry() -> &'static RwLock<HashMap<String, Registered>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Registered>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Register a primitive with the given name, replacing any previously registered with the same
+/// name.
+pub fn register<F>(name: impl Into<String>, path: F)
+where
+    F: Fn(Vec2, &Params) -> Path + Send + Sync + 'static,
+{
+    register_with_defaults(name, Params::new(), path);
+}
+
+/// Register a primitive with the given name and default parameters.
+///
+/// Parameters specified when drawing the primitive take precedence over the defaults.
+pub fn register_with_defaults<F>(name: impl Into<String>, defaults: Params, path: F)
+where
+    F: Fn(Vec2, &Params) -> Path + Send + Sync + 'static,
+{
+    let registered = Registered {
+        defaults,
+        path: Arc::new(path),
+    };
+    registry()
+        .write()
+        .expect("failed to lock primitive registry")
+        .insert(name.into(), registered);
+}
+
+/// Remove the primitive with the given name, returning whether or not it was registered.
+pub fn unregister(name: &str) -> bool {
+    registry()
+        .write()
+        .expect("failed to lock primitive registry")
+        .remove(name)
+        .is_some()
+}
+
+/// Whether or not a primitive with the given name is registered.
+pub fn is_registered(name: &str) -> bool {
+    registry()
+        .read()
+        .expect("failed to lock primitive registry")
+        .contains_key(name)
+}
+
+/// The names of all registered primitives in alphabetical order.
+pub fn names() -> Vec<String> {
+    let registry = registry()
+        .read()
+        .expect("failed to lock primitive registry");
+    let mut names: Vec<_> = registry.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+impl Params {
+    /// An empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder method for setting the parameter with the given name.
+    pub fn with(mut self, name: impl Into<String>, value: f32) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Set the parameter with the given name.
+    pub fn set(&mut self, name: impl Into<String>, value: f32) {
+        self.values.insert(name.into(), value);
+    }
+
+    /// The value of the parameter with the given name, if it was set.
+    pub fn get(&self, name: &str) -> Option<f32> {
+        self.values.get(name).cloned()
+    }
+
+    /// The value of the parameter with the given name, or `default` if it was not set.
+    pub fn get_or(&self, name: &str, default: f32) -> f32 {
+        self.get(name).unwrap_or(default)
+    }
+
+    /// An iterator yielding all parameters in alphabetical order of their names.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.values.iter().map(|(k, &v)| (&k[..], v))
+    }
+}
+
+impl CustomPrimitive {
+    /// Begin describing the primitive registered with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        CustomPrimitive {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The name of the registered primitive.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Stroke the outline with the given color.
+    pub fn stroke<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.stroke_color(color)
+    }
+
+    /// Set the parameter with the given name, overriding the registered default.
+    pub fn param(mut self, name: impl Into<String>, value: f32) -> Self {
+        self.params.set(name, value);
+        self
+    }
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for CustomPrimitive {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let CustomPrimitive {
+            name,
+            params,
+            dimensions,
+            polygon,
+        } = self;
+
+        // Primitives that are not registered are skipped.
+        let registered = registry()
+            .read()
+            .expect("failed to lock primitive registry")
+            .get(&name)
+            .cloned();
+        if let Some(registered) = registered {
+            let w = dimensions.x.map(f32::abs).unwrap_or(100.0);
+            let h = dimensions.y.map(f32::abs).unwrap_or(100.0);
+            let mut all_params = registered.defaults;
+            all_params.values.extend(params.values);
+            let path = (registered.path)(Vec2::new(w, h), &all_params);
+            polygon::render_events_themed(
+                polygon.opts,
+                || (&path).into_iter(),
+                ctxt,
+                &draw::theme::Primitive::Custom,
+                mesh,
+            );
+        }
+
+        draw::renderer::PrimitiveRender::default()
+    }
+}
+
+impl SetOrientation for CustomPrimitive {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.polygon)
+    }
+}
+
+impl SetPosition for CustomPrimitive {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.polygon)
+    }
+}
+
+impl SetDimensions for CustomPrimitive {
+    fn properties(&mut self) -> &mut dimension::Properties {
+        SetDimensions::properties(&mut self.dimensions)
+    }
+}
+
+impl SetColor<ColorScalar> for CustomPrimitive {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.polygon)
+    }
+}
+
+impl SetStroke for CustomPrimitive {
+    fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
+        SetStroke::stroke_options_mut(&mut self.polygon)
+    }
+}
+
+impl SetPolygon for CustomPrimitive {
+    fn polygon_options_mut(&mut self) -> &mut PolygonOptions {
+        SetPolygon::polygon_options_mut(&mut self.polygon)
+    }
+}
+
+impl<'a> FromIterator<(&'a str, f32)> for Params {
+    fn from_iter<I: IntoIterator<Item = (&'a str, f32)>>(iter: I) -> Self {
+        let values = iter.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        Params { values }
+    }
+}
+
+// Primitive conversion.
+
+impl From<CustomPrimitive> for Primitive {
+    fn from(prim: CustomPrimitive) -> Self {
+        Primitive::CustomPrimitive(prim)
+    }
+}
+
+impl Into<Option<CustomPrimitive>> for Primitive {
+    fn into(self) -> Option<CustomPrimitive> {
+        match self {
+            Primitive::CustomPrimitive(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingCustomPrimitive<'a> {
+    /// Stroke the outline with the given color.
+    pub fn stroke<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.linear_color(color);
+        self.map_ty(|ty| ty.stroke(color))
+    }
+
+    /// Set the parameter with the given name, overriding the registered default.
+    pub fn param(self, name: impl Into<String>, value: f32) -> Self {
+        let name = name.into();
+        self.map_ty(|ty| ty.param(name, value))
+    }
+}
@@ -2,13 +2,16 @@ use crate::color::conv::IntoLinSrgba;
 use crate::draw;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
+use crate::draw::properties::sdf::SdfOptions;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetMaterial, SetOrientation,
+    SetPosition, SetSdf, SetStroke,
 };
 use crate::draw::Drawing;
 use crate::geom;
-use crate::glam::Vec2;
+use crate::glam::{Mat4, Vec2};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing an **Ellipse**.
@@ -17,6 +20,7 @@ pub struct Ellipse {
     dimensions: spatial::dimension::Properties,
     resolution: Option<f32>,
     polygon: PolygonInit,
+    sdf: Option<SdfOptions>,
 }
 
 /// The drawing context for an ellipse.
@@ -51,6 +55,18 @@ impl Ellipse {
 
 // Trait implementations.
 
+impl Ellipse {
+    pub(crate) fn bounds(&self) -> Option<(geom::Rect, Mat4)> {
+        // SDF effects such as glow and shadow may extend beyond the ellipse.
+        if self.sdf.is_some() {
+            return None;
+        }
+        let w = self.dimensions.x.unwrap_or(100.0).abs();
+        let h = self.dimensions.y.unwrap_or(100.0).abs();
+        Some(self.polygon.opts.bounds(geom::Rect::from_w_h(w, h)))
+    }
+}
+
 impl draw::renderer::RenderPrimitive for Ellipse {
     fn render_primitive(
         self,
@@ -61,6 +77,7 @@ impl draw::renderer::RenderPrimitive for Ellipse {
             dimensions,
             polygon,
             resolution,
+            sdf,
         } = self;
 
         // First get the dimensions of the ellipse.
@@ -72,6 +89,17 @@ impl draw::renderer::RenderPrimitive for Ellipse {
 
         let w = maybe_x.map(f32::abs).unwrap_or(100.0);
         let h = maybe_y.map(f32::abs).unwrap_or(100.0);
+        if let Some(sdf) = sdf.filter(|_| ctxt.sdf) {
+            return draw::renderer::sdf::render_polygon(
+                draw::renderer::sdf::Shape::Ellipse,
+                Vec2::new(w, h) * 0.5,
+                polygon.opts,
+                &sdf,
+                ctxt,
+                &draw::theme::Primitive::Ellipse,
+                mesh,
+            );
+        }
         match resolution {
             None => {
                 // Determine the transform to apply to all points.
@@ -142,12 +170,24 @@ impl SetStroke for Ellipse {
     }
 }
 
+impl SetSdf for Ellipse {
+    fn sdf_options_mut(&mut self) -> &mut Option<SdfOptions> {
+        SetSdf::sdf_options_mut(&mut self.sdf)
+    }
+}
+
 impl SetPolygon for Ellipse {
     fn polygon_options_mut(&mut self) -> &mut PolygonOptions {
         SetPolygon::polygon_options_mut(&mut self.polygon)
     }
 }
 
+impl SetMaterial for Ellipse {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.polygon)
+    }
+}
+
 // Primitive conversion.
 
 impl From<Ellipse> for Primitive {
@@ -173,6 +213,7 @@ impl<'a> DrawingEllipse<'a> {
     where
         C: IntoLinSrgba<ColorScalar>,
     {
+        let color = self.linear_color(color);
         self.map_ty(|ty| ty.stroke(color))
     }
 
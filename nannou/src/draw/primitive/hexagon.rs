@@ -0,0 +1,185 @@
+use crate::color::conv::IntoLinSrgba;
+use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{
+    ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition, SetStroke,
+};
+use crate::draw::{self, Drawing};
+use crate::geom::hex::{self, Hex};
+use crate::glam::Vec2;
+use lyon::tessellation::StrokeOptions;
+
+/// Properties related to drawing a **Hexagon**.
+#[derive(Clone, Debug, Default)]
+pub struct Hexagon {
+    size: Option<Vec2>,
+    orientation: hex::Orientation,
+    polygon: PolygonInit,
+}
+
+/// The drawing context for a hexagon.
+pub type DrawingHexagon<'a> = Drawing<'a, Hexagon>;
+
+// Hexagon-specific methods.
+
+impl Hexagon {
+    /// Stroke the outline with the given color.
+    pub fn stroke<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        self.stroke_color(color)
+    }
+
+    /// The distance from the centre of the hexagon to each of its corners.
+    ///
+    /// By default, this is `50.0`.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.size = Some(Vec2::splat(radius));
+        self
+    }
+
+    /// Specify whether the hexagon has a corner or an edge at the top.
+    ///
+    /// By default, hexagons are pointy-topped.
+    pub fn hex_orientation(mut self, orientation: hex::Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Draw the hexagon with a corner at the top.
+    pub fn pointy(self) -> Self {
+        self.hex_orientation(hex::Orientation::Pointy)
+    }
+
+    /// Draw the hexagon with an edge at the top.
+    pub fn flat(self) -> Self {
+        self.hex_orientation(hex::Orientation::Flat)
+    }
+
+    /// Position, size and orient the hexagon to fill the given cell of the grid described by
+    /// `layout`.
+    pub fn hex(mut self, layout: &hex::Layout, hex: Hex) -> Self {
+        self.size = Some(layout.size);
+        self.orientation = layout.orientation;
+        self.xy(layout.to_point(hex))
+    }
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for Hexagon {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Hexagon {
+            size,
+            orientation,
+            polygon,
+        } = self;
+        let layout = hex::Layout {
+            orientation,
+            size: size.unwrap_or(Vec2::splat(50.0)),
+            origin: Vec2::ZERO,
+        };
+        let corners = layout.corners(Hex::ORIGIN);
+        polygon::render_points_themed(
+            polygon.opts,
+            corners.iter().cloned(),
+            ctxt,
+            &draw::theme::Primitive::Hexagon,
+            mesh,
+        );
+
+        draw::renderer::PrimitiveRender::default()
+    }
+}
+
+impl SetOrientation for Hexagon {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.polygon)
+    }
+}
+
+impl SetPosition for Hexagon {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.polygon)
+    }
+}
+
+impl SetColor<ColorScalar> for Hexagon {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.polygon)
+    }
+}
+
+impl SetStroke for Hexagon {
+    fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
+        SetStroke::stroke_options_mut(&mut self.polygon)
+    }
+}
+
+impl SetPolygon for Hexagon {
+    fn polygon_options_mut(&mut self) -> &mut PolygonOptions {
+        SetPolygon::polygon_options_mut(&mut self.polygon)
+    }
+}
+
+// Primitive conversion.
+
+impl From<Hexagon> for Primitive {
+    fn from(prim: Hexagon) -> Self {
+        Primitive::Hexagon(prim)
+    }
+}
+
+impl Into<Option<Hexagon>> for Primitive {
+    fn into(self) -> Option<Hexagon> {
+        match self {
+            Primitive::Hexagon(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingHexagon<'a> {
+    /// Stroke the outline with the given color.
+    pub fn stroke<C>(self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.linear_color(color);
+        self.map_ty(|ty| ty.stroke(color))
+    }
+
+    /// The distance from the centre of the hexagon to each of its corners.
+    pub fn radius(self, radius: f32) -> Self {
+        self.map_ty(|ty| ty.radius(radius))
+    }
+
+    /// Specify whether the hexagon has a corner or an edge at the top.
+    pub fn hex_orientation(self, orientation: hex::Orientation) -> Self {
+        self.map_ty(|ty| ty.hex_orientation(orientation))
+    }
+
+    /// Draw the hexagon with a corner at the top.
+    pub fn pointy(self) -> Self {
+        self.map_ty(|ty| ty.pointy())
+    }
+
+    /// Draw the hexagon with an edge at the top.
+    pub fn flat(self) -> Self {
+        self.map_ty(|ty| ty.flat())
+    }
+
+    /// Position, size and orient the hexagon to fill the given cell of the grid described by
+    /// `layout`.
+    pub fn hex(self, layout: &hex::Layout, hex: Hex) -> Self {
+        self.map_ty(|ty| ty.hex(layout, hex))
+    }
+}
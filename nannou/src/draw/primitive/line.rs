@@ -1,11 +1,15 @@
 use crate::color::LinSrgba;
-use crate::draw::primitive::path;
+use crate::draw::primitive::{path, polygon};
 use crate::draw::primitive::{PathStroke, Primitive};
+use crate::draw::properties::sdf::SdfOptions;
 use crate::draw::properties::spatial::{orientation, position};
-use crate::draw::properties::{ColorScalar, SetColor, SetOrientation, SetPosition, SetStroke};
+use crate::draw::properties::{
+    ColorScalar, SetColor, SetOrientation, SetPosition, SetSdf, SetStroke,
+};
 use crate::draw::{self, Drawing};
-use crate::geom::{pt2, Point2};
-use lyon::tessellation::StrokeOptions;
+use crate::geom::{self, pt2, Point2};
+use crate::glam::{Mat4, Vec2};
+use lyon::tessellation::{LineCap, StrokeOptions};
 
 /// A path containing only two points - a start and end.
 ///
@@ -16,6 +20,7 @@ pub struct Line {
     pub path: PathStroke,
     pub start: Option<Point2>,
     pub end: Option<Point2>,
+    pub sdf: Option<SdfOptions>,
 }
 
 /// The drawing context for a line.
@@ -54,9 +59,19 @@ impl Line {
     where
         F: FnOnce(PathStroke) -> PathStroke,
     {
-        let Line { path, start, end } = self;
+        let Line {
+            path,
+            start,
+            end,
+            sdf,
+        } = self;
         let path = map(path);
-        Line { path, start, end }
+        Line {
+            path,
+            start,
+            end,
+            sdf,
+        }
     }
 }
 
@@ -93,6 +108,12 @@ impl SetStroke for Line {
     }
 }
 
+impl SetSdf for Line {
+    fn sdf_options_mut(&mut self) -> &mut Option<SdfOptions> {
+        SetSdf::sdf_options_mut(&mut self.sdf)
+    }
+}
+
 impl SetOrientation for Line {
     fn properties(&mut self) -> &mut orientation::Properties {
         SetOrientation::properties(&mut self.path)
@@ -126,18 +147,40 @@ impl Into<Option<Line>> for Primitive {
     }
 }
 
+impl Line {
+    pub(crate) fn bounds(&self) -> Option<(geom::Rect, Mat4)> {
+        if self.sdf.is_some() {
+            return None;
+        }
+        let start = self.start.unwrap_or(pt2(0.0, 0.0));
+        let end = self.end.unwrap_or(pt2(0.0, 0.0));
+        let pad = polygon::stroke_pad(&self.path.opts);
+        let rect = geom::Rect::from_corners(start, end).pad(-pad);
+        let transform = self.path.position.transform() * self.path.orientation.transform();
+        Some((rect, transform))
+    }
+}
+
 impl draw::renderer::RenderPrimitive for Line {
     fn render_primitive(
         self,
         mut ctxt: draw::renderer::RenderContext,
         mesh: &mut draw::Mesh,
     ) -> draw::renderer::PrimitiveRender {
-        let Line { path, start, end } = self;
+        let Line {
+            path,
+            start,
+            end,
+            sdf,
+        } = self;
         let start = start.unwrap_or(pt2(0.0, 0.0));
         let end = end.unwrap_or(pt2(0.0, 0.0));
         if start == end {
             return draw::renderer::PrimitiveRender::default();
         }
+        if let Some(sdf) = sdf.filter(|_| ctxt.sdf) {
+            return render_sdf(path, start, end, &sdf, ctxt, mesh);
+        }
         let close = false;
         let points = [start, end];
         let points = points.iter().cloned().map(|p| p.to_array().into());
@@ -152,14 +195,58 @@ impl draw::renderer::RenderPrimitive for Line {
             events,
             path.color,
             transform,
-            path::Options::Stroke(path.opts),
+            path::Options::Stroke(ctxt.stroke_options(path.opts, &transform)),
             &ctxt.theme,
             &draw::theme::Primitive::Line,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
             mesh,
         );
 
         draw::renderer::PrimitiveRender::default()
     }
 }
+
+// Render the line as a box aligned with the segment, or a capsule in the case of round caps.
+fn render_sdf(
+    path: PathStroke,
+    start: Point2,
+    end: Point2,
+    sdf: &SdfOptions,
+    ctxt: draw::renderer::RenderContext,
+    mesh: &mut draw::Mesh,
+) -> draw::renderer::PrimitiveRender {
+    let weight = ctxt.stroke_weight(path.opts.line_width, ctxt.transform);
+    let cap = path.opts.start_cap;
+    let cap_len = match cap {
+        LineCap::Butt => 0.0,
+        LineCap::Square | LineCap::Round => weight * 0.5,
+    };
+    let mut opts = sdf.clone();
+    if let LineCap::Round = cap {
+        opts.corner_radius = weight * 0.5;
+    }
+    let direction = end - start;
+    let half_size = Vec2::new(direction.length() * 0.5 + cap_len, weight * 0.5);
+    let local_transform = path.position.transform()
+        * path.orientation.transform()
+        * Mat4::from_translation(((start + end) * 0.5).extend(0.0))
+        * Mat4::from_rotation_z(direction.y.atan2(direction.x));
+    let transform = *ctxt.transform * local_transform;
+    let color = path
+        .color
+        .unwrap_or_else(|| ctxt.theme.stroke_lin_srgba(&draw::theme::Primitive::Line));
+    let shape = draw::renderer::sdf::Shape::Box;
+    draw::renderer::sdf::render_shape(
+        shape,
+        half_size,
+        transform,
+        Some(color),
+        None,
+        &opts,
+        ctxt.sdf_params_buffer,
+        mesh,
+    );
+    draw::renderer::PrimitiveRender::vertex_mode(shape.vertex_mode())
+}
@@ -1,8 +1,12 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::mesh::vertex::{self, Point, TexCoords, Vertex};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
 use crate::draw::properties::spatial::{orientation, position};
-use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
+use crate::draw::properties::{
+    ColorScalar, LinSrgba, SetColor, SetMaterial, SetOrientation, SetPosition,
+};
+use crate::draw::renderer::SdfParams;
 use crate::draw::{self, Drawing};
 use crate::geom;
 use crate::wgpu;
@@ -22,6 +26,8 @@ pub struct Mesh {
     vertex_mode: draw::renderer::VertexMode,
     fill_color: Option<FillColor>,
     texture_view: Option<wgpu::TextureView>,
+    material: Option<Material>,
+    sdf_params: Vec<SdfParams>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -115,6 +121,28 @@ impl Vertexless {
         mesh
     }
 
+    /// Describe the mesh with a sequence of points with texture coordinates, shaded via the given
+    /// vertex mode without a texture.
+    pub(crate) fn points_with_mode<I, P, T>(
+        self,
+        inner_mesh: &mut draw::Mesh,
+        vertex_mode: draw::renderer::VertexMode,
+        points: I,
+    ) -> Mesh
+    where
+        I: IntoIterator<Item = (P, T)>,
+        P: Into<Point>,
+        T: Into<TexCoords>,
+    {
+        let vertices = points.into_iter().map(|(p, t)| {
+            let point = p.into();
+            let color = vertex::DEFAULT_VERTEX_COLOR;
+            let tex_coords = t.into();
+            ((point, color), tex_coords).into()
+        });
+        self.points_inner(inner_mesh, vertices, vertex_mode, None)
+    }
+
     fn points_inner<I>(
         self,
         inner_mesh: &mut draw::Mesh,
@@ -302,11 +330,37 @@ impl Vertexless {
         let v_start = inner_mesh.points().len();
         let i_start = inner_mesh.indices().len();
         inner_mesh.extend_vertices(vertices);
-        inner_mesh.extend_indices(indices.into_iter().map(|ix| ix as u32));
+        // Indices into the intermediary mesh are offset by the vertices that precede the mesh.
+        inner_mesh.extend_indices(indices.into_iter().map(|ix| (v_start + ix) as u32));
         let v_end = inner_mesh.points().len();
         let i_end = inner_mesh.indices().len();
         Mesh::new(v_start..v_end, i_start..i_end, vertex_mode, texture_view)
     }
+
+    /// Describe the mesh with the given indexed vertices and the state required to render them,
+    /// as produced by tessellating a primitive, e.g. when replaying a **draw::RecordedFrame**.
+    ///
+    /// If not empty, `sdf_params` must contain an element for each vertex.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn tessellated<V, I>(
+        self,
+        inner_mesh: &mut draw::Mesh,
+        vertices: V,
+        indices: I,
+        vertex_mode: draw::renderer::VertexMode,
+        texture_view: Option<wgpu::TextureView>,
+        sdf_params: Vec<SdfParams>,
+        material: Option<Material>,
+    ) -> Mesh
+    where
+        V: IntoIterator<Item = Vertex>,
+        I: IntoIterator<Item = usize>,
+    {
+        let mut mesh = self.indexed_inner(inner_mesh, vertices, indices, vertex_mode, texture_view);
+        mesh.sdf_params = sdf_params;
+        mesh.material = material;
+        mesh
+    }
 }
 
 impl Mesh {
@@ -320,6 +374,8 @@ impl Mesh {
         let orientation = Default::default();
         let position = Default::default();
         let fill_color = None;
+        let material = None;
+        let sdf_params = vec![];
         Mesh {
             orientation,
             position,
@@ -328,11 +384,57 @@ impl Mesh {
             vertex_mode,
             fill_color,
             texture_view,
+            material,
+            sdf_params,
         }
     }
 }
 
 impl<'a> Drawing<'a, Vertexless> {
+    /// Describe the mesh with a sequence of points with texture coordinates, shaded via the given
+    /// vertex mode without a texture.
+    pub(crate) fn points_with_mode<I, P, T>(
+        self,
+        vertex_mode: draw::renderer::VertexMode,
+        points: I,
+    ) -> DrawingMesh<'a>
+    where
+        I: IntoIterator<Item = (P, T)>,
+        P: Into<Point>,
+        T: Into<TexCoords>,
+    {
+        self.map_ty_with_context(|ty, ctxt| ty.points_with_mode(ctxt.mesh, vertex_mode, points))
+    }
+
+    /// Describe the mesh with the given indexed vertices and the state required to render them.
+    /// See `Vertexless::tessellated`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn tessellated<V, I>(
+        self,
+        vertices: V,
+        indices: I,
+        vertex_mode: draw::renderer::VertexMode,
+        texture_view: Option<wgpu::TextureView>,
+        sdf_params: Vec<SdfParams>,
+        material: Option<Material>,
+    ) -> DrawingMesh<'a>
+    where
+        V: IntoIterator<Item = Vertex>,
+        I: IntoIterator<Item = usize>,
+    {
+        self.map_ty_with_context(|ty, ctxt| {
+            ty.tessellated(
+                ctxt.mesh,
+                vertices,
+                indices,
+                vertex_mode,
+                texture_view,
+                sdf_params,
+                material,
+            )
+        })
+    }
+
     /// Describe the mesh with a sequence of points.
     ///
     /// The given iterator may yield any type that can be converted directly into `Point3`s.
@@ -495,6 +597,8 @@ impl draw::renderer::RenderPrimitive for Mesh {
             vertex_mode,
             fill_color,
             texture_view,
+            material: _,
+            sdf_params,
         } = self;
 
         // Determine the transform to apply to vertices.
@@ -502,6 +606,13 @@ impl draw::renderer::RenderPrimitive for Mesh {
         let local_transform = position.transform() * orientation.transform();
         let transform = global_transform * local_transform;
 
+        // Tessellated SDF shapes carry the params of each vertex.
+        if !sdf_params.is_empty() {
+            let n_vs = mesh.points().len();
+            ctxt.sdf_params_buffer.resize(n_vs, SdfParams::default());
+            ctxt.sdf_params_buffer.extend(sdf_params);
+        }
+
         // We need to update the indices to point to where vertices will be in the new mesh.
         let old_mesh_vertex_start = vertex_range.start as u32;
         let new_mesh_vertex_start = mesh.raw_vertex_count() as u32;
@@ -585,6 +696,12 @@ impl SetColor<ColorScalar> for Mesh {
     }
 }
 
+impl SetMaterial for Mesh {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.material)
+    }
+}
+
 impl From<Vertexless> for Primitive {
     fn from(prim: Vertexless) -> Self {
         Primitive::MeshVertexless(prim)
@@ -1,5 +1,10 @@
 pub mod arrow;
+pub mod audio;
+pub mod brush;
+pub mod contours;
+pub mod custom;
 pub mod ellipse;
+pub mod hexagon;
 pub mod line;
 pub mod mesh;
 pub mod path;
@@ -9,9 +14,21 @@ pub mod rect;
 pub mod text;
 pub mod texture;
 pub mod tri;
+pub mod turtle;
+pub mod vector_field;
+
+use crate::draw::properties::material::Material;
+use crate::draw::properties::SetMaterial;
+use crate::geom;
+use crate::glam::Mat4;
 
 pub use self::arrow::Arrow;
+pub use self::audio::{Spectrogram, Waveform};
+pub use self::brush::Brush;
+pub use self::contours::Contours;
+pub use self::custom::CustomPrimitive;
 pub use self::ellipse::Ellipse;
+pub use self::hexagon::Hexagon;
 pub use self::line::Line;
 pub use self::mesh::Mesh;
 pub use self::path::{Path, PathFill, PathInit, PathStroke};
@@ -21,6 +38,8 @@ pub use self::rect::Rect;
 pub use self::text::Text;
 pub use self::texture::Texture;
 pub use self::tri::Tri;
+pub use self::turtle::TurtlePath;
+pub use self::vector_field::VectorField;
 
 /// A wrapper around all primitive sets of properties so that they may be stored within the
 /// **Draw**'s `drawing` field while they are being drawn.
@@ -30,7 +49,11 @@ pub use self::tri::Tri;
 #[derive(Clone, Debug)]
 pub enum Primitive {
     Arrow(Arrow),
+    Brush(Brush),
+    Contours(Contours),
+    CustomPrimitive(CustomPrimitive),
     Ellipse(Ellipse),
+    Hexagon(Hexagon),
     Line(Line),
     MeshVertexless(mesh::Vertexless),
     Mesh(Mesh),
@@ -42,7 +65,43 @@ pub enum Primitive {
     Polygon(Polygon),
     Quad(Quad),
     Rect(Rect),
+    Spectrogram(Spectrogram),
     Text(Text),
     Texture(Texture),
     Tri(Tri),
+    TurtlePath(TurtlePath),
+    VectorField(VectorField),
+    Waveform(Waveform),
+}
+
+impl Primitive {
+    /// Take the material of the primitive, if it supports one and a material was specified.
+    pub(crate) fn take_material(&mut self) -> Option<Material> {
+        let material = match self {
+            Primitive::Ellipse(prim) => prim.material_mut(),
+            Primitive::Mesh(prim) => prim.material_mut(),
+            Primitive::Polygon(prim) => prim.material_mut(),
+            Primitive::Quad(prim) => prim.material_mut(),
+            Primitive::Rect(prim) => prim.material_mut(),
+            Primitive::Tri(prim) => prim.material_mut(),
+            _ => return None,
+        };
+        material.take()
+    }
+
+    /// The bounding rect of the primitive within its local space, along with the transform from
+    /// its local space to that of the **Draw**.
+    ///
+    /// Returns `None` for primitives whose bounds are unknown ahead of tessellation, in which case
+    /// they are never culled. See `Draw::cull`.
+    pub(crate) fn bounds(&self) -> Option<(geom::Rect, Mat4)> {
+        match self {
+            Primitive::Ellipse(prim) => prim.bounds(),
+            Primitive::Line(prim) => prim.bounds(),
+            Primitive::Quad(prim) => prim.bounds(),
+            Primitive::Rect(prim) => prim.bounds(),
+            Primitive::Tri(prim) => prim.bounds(),
+            _ => None,
+        }
+    }
 }
@@ -360,10 +360,21 @@ pub(crate) fn render_path_events<I>(
     theme_prim: &draw::theme::Primitive,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<draw::vector::VectorPath>>,
     mesh: &mut draw::Mesh,
 ) where
     I: IntoIterator<Item = lyon::path::PathEvent>,
 {
+    if let Some(vector_paths) = vector_paths {
+        let color = color.unwrap_or_else(|| match options {
+            Options::Fill(_) => theme.fill_lin_srgba(theme_prim),
+            Options::Stroke(_) => theme.stroke_lin_srgba(theme_prim),
+        });
+        let path = draw::vector::VectorPath::from_events(events, transform, options, color);
+        vector_paths.push(path);
+        return;
+    }
+
     let res = match options {
         Options::Fill(options) => {
             let color = color.unwrap_or_else(|| theme.fill_lin_srgba(theme_prim));
@@ -388,15 +399,28 @@ pub(crate) fn render_path_points_colored<I>(
     options: Options,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<draw::vector::VectorPath>>,
     mesh: &mut draw::Mesh,
 ) where
     I: IntoIterator<Item = (Point2, Color)>,
 {
+    let mut points_colored = points_colored.into_iter().peekable();
+    let first_color = match points_colored.peek() {
+        None => return,
+        Some(&(_, color)) => color,
+    };
     let path = match points_colored_to_lyon_path(points_colored, close) {
         None => return,
         Some(p) => p,
     };
 
+    // Vector paths are described with a single color, so use that of the first point.
+    if let Some(vector_paths) = vector_paths {
+        let path = draw::vector::VectorPath::from_events(&path, transform, options, first_color);
+        vector_paths.push(path);
+        return;
+    }
+
     // Extend the mesh with the built path.
     let mut mesh_builder = draw::mesh::MeshBuilder::color_per_point(mesh, transform);
     let res = match options {
@@ -427,10 +451,16 @@ pub(crate) fn render_path_points_textured<I>(
     options: Options,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<draw::vector::VectorPath>>,
     mesh: &mut draw::Mesh,
 ) where
     I: IntoIterator<Item = (Point2, TexCoords)>,
 {
+    // Textured paths cannot be described by a vector path.
+    if vector_paths.is_some() {
+        return;
+    }
+
     let path = match points_textured_to_lyon_path(points_textured, close) {
         None => return,
         Some(p) => p,
@@ -469,6 +499,7 @@ pub(crate) fn render_path_source(
     theme_prim: &draw::theme::Primitive,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
     stroke_tessellator: &mut lyon::tessellation::StrokeTessellator,
+    vector_paths: Option<&mut Vec<draw::vector::VectorPath>>,
     mesh: &mut draw::Mesh,
 ) {
     match path_src {
@@ -481,6 +512,7 @@ pub(crate) fn render_path_source(
             theme_prim,
             fill_tessellator,
             stroke_tessellator,
+            vector_paths,
             mesh,
         ),
         PathEventSourceIter::ColoredPoints { points, close } => render_path_points_colored(
@@ -490,6 +522,7 @@ pub(crate) fn render_path_source(
             options,
             fill_tessellator,
             stroke_tessellator,
+            vector_paths,
             mesh,
         ),
         PathEventSourceIter::TexturedPoints { points, close } => render_path_points_textured(
@@ -499,6 +532,7 @@ pub(crate) fn render_path_source(
             options,
             fill_tessellator,
             stroke_tessellator,
+            vector_paths,
             mesh,
         ),
     }
@@ -524,8 +558,13 @@ impl draw::renderer::RenderPrimitive for Path {
         let global_transform = *ctxt.transform;
         let local_transform = position.transform() * orientation.transform();
         let transform = global_transform * local_transform;
+        let options = match options {
+            Options::Stroke(opts) => Options::Stroke(ctxt.stroke_options(opts, &transform)),
+            options => options,
+        };
 
         // A function for rendering the path.
+        let vector_paths = ctxt.vector_paths.take();
         let render =
             |src: PathEventSourceIter,
              theme: &draw::Theme,
@@ -540,6 +579,7 @@ impl draw::renderer::RenderPrimitive for Path {
                     &draw::theme::Primitive::Path,
                     fill_tessellator,
                     stroke_tessellator,
+                    vector_paths,
                     mesh,
                 )
             };
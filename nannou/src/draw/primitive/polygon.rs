@@ -3,12 +3,14 @@ use crate::draw::drawing::DrawingContext;
 use crate::draw::mesh::vertex::TexCoords;
 use crate::draw::primitive::path::{self, PathEventSource};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
 use crate::draw::properties::spatial::{orientation, position};
 use crate::draw::properties::{
-    ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, LinSrgba, SetColor, SetMaterial, SetOrientation, SetPosition, SetStroke,
 };
 use crate::draw::{self, Drawing};
-use crate::geom::Point2;
+use crate::geom::{self, Point2};
+use crate::glam::Mat4;
 use crate::wgpu;
 use lyon::path::PathEvent;
 use lyon::tessellation::StrokeOptions;
@@ -58,6 +60,22 @@ pub struct PolygonOptions {
     pub stroke_color: Option<LinSrgba>,
     pub color: Option<LinSrgba>,
     pub stroke: Option<StrokeOptions>,
+    pub material: Option<Material>,
+}
+
+impl PolygonOptions {
+    /// Pad the given local bounds to fit the stroke and produce the local transform.
+    pub(crate) fn bounds(&self, rect: geom::Rect) -> (geom::Rect, Mat4) {
+        let pad = self.stroke.as_ref().map(stroke_pad).unwrap_or(0.0);
+        let transform = self.position.transform() * self.orientation.transform();
+        (rect.pad(-pad), transform)
+    }
+}
+
+/// The distance a stroke with the given options may extend beyond the path it follows, allowing
+/// for miter joins.
+pub(crate) fn stroke_pad(opts: &StrokeOptions) -> f32 {
+    opts.line_width * 0.5 * opts.miter_limit.max(1.0)
 }
 
 /// A polygon with vertices already submitted.
@@ -191,6 +209,7 @@ pub fn render_events_themed<F, I>(
         stroke_color,
         color,
         stroke,
+        material: _,
     } = opts;
 
     // Determine the transform to apply to all points.
@@ -199,6 +218,7 @@ pub fn render_events_themed<F, I>(
     let transform = global_transform * local_transform;
 
     // A function for rendering the path.
+    let mut vector_paths = ctxt.vector_paths.take();
     let mut render =
         |opts: path::Options,
          color: Option<LinSrgba>,
@@ -214,6 +234,7 @@ pub fn render_events_themed<F, I>(
                 theme_primitive,
                 fill_tessellator,
                 stroke_tessellator,
+                vector_paths.as_deref_mut(),
                 mesh,
             )
         };
@@ -232,7 +253,7 @@ pub fn render_events_themed<F, I>(
 
     // Do the stroke tessellation on top.
     if let Some(stroke_opts) = stroke {
-        let opts = path::Options::Stroke(stroke_opts);
+        let opts = path::Options::Stroke(ctxt.stroke_options(stroke_opts, &transform));
         let color = stroke_color;
         render(
             opts,
@@ -279,25 +300,28 @@ impl Polygon {
                     stroke_color,
                     color,
                     stroke,
+                    material: _,
                 },
             texture_view,
         } = self;
+
+        // Determine the transform to apply to all points.
+        let global_transform = *ctxt.transform;
+        let local_transform = position.transform() * orientation.transform();
+        let transform = global_transform * local_transform;
+
+        let stroke = stroke.map(|opts| ctxt.stroke_options(opts, &transform));
         let draw::renderer::RenderContext {
             fill_tessellator,
             stroke_tessellator,
             path_event_buffer,
             path_points_colored_buffer,
             path_points_textured_buffer,
-            transform,
             theme,
+            mut vector_paths,
             ..
         } = ctxt;
 
-        // Determine the transform to apply to all points.
-        let global_transform = *transform;
-        let local_transform = position.transform() * orientation.transform();
-        let transform = global_transform * local_transform;
-
         // A function for rendering the path.
         let mut render =
             |src: path::PathEventSourceIter,
@@ -315,6 +339,7 @@ impl Polygon {
                     theme_primitive,
                     fill_tessellator,
                     stroke_tessellator,
+                    vector_paths.as_deref_mut(),
                     mesh,
                 )
             };
@@ -462,6 +487,7 @@ where
     where
         C: IntoLinSrgba<ColorScalar>,
     {
+        let color = self.linear_color(color);
         self.map_ty(|ty| ty.stroke_color(color))
     }
 
@@ -477,6 +503,7 @@ impl<'a> DrawingPolygonInit<'a> {
     where
         C: IntoLinSrgba<ColorScalar>,
     {
+        let color = self.linear_color(color);
         self.map_ty(|ty| ty.stroke(color))
     }
 
@@ -528,6 +555,12 @@ impl SetPolygon for PolygonOptions {
     }
 }
 
+impl SetMaterial for PolygonOptions {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.material)
+    }
+}
+
 impl SetOrientation for PolygonInit {
     fn properties(&mut self) -> &mut orientation::Properties {
         SetOrientation::properties(&mut self.opts.orientation)
@@ -558,6 +591,12 @@ impl SetStroke for PolygonInit {
     }
 }
 
+impl SetMaterial for PolygonInit {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.opts)
+    }
+}
+
 impl SetOrientation for Polygon {
     fn properties(&mut self) -> &mut orientation::Properties {
         SetOrientation::properties(&mut self.opts.orientation)
@@ -576,6 +615,12 @@ impl SetColor<ColorScalar> for Polygon {
     }
 }
 
+impl SetMaterial for Polygon {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.opts)
+    }
+}
+
 impl From<PolygonInit> for Primitive {
     fn from(prim: PolygonInit) -> Self {
         Primitive::PolygonInit(prim)
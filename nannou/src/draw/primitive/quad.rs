@@ -1,13 +1,15 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetMaterial, SetOrientation,
+    SetPosition, SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom::{self, pt2, Point2};
-use crate::glam::vec2;
+use crate::glam::{vec2, Mat4};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing a **Quad**.
@@ -47,6 +49,20 @@ impl Quad {
 }
 
 // Trait implementations.
+impl Quad {
+    pub(crate) fn bounds(&self) -> Option<(geom::Rect, Mat4)> {
+        // Scale the bounds about the centroid as the points are scaled when rendering.
+        let rect = self.quad.bounding_rect();
+        let centroid = self.quad.centroid();
+        let x_scale = self.dimensions.x.map(|x| x / rect.w()).unwrap_or(1.0);
+        let y_scale = self.dimensions.y.map(|y| y / rect.h()).unwrap_or(1.0);
+        let scale = vec2(x_scale, y_scale);
+        let a = centroid + (rect.bottom_left() - centroid) * scale;
+        let b = centroid + (rect.top_right() - centroid) * scale;
+        Some(self.polygon.opts.bounds(geom::Rect::from_corners(a, b)))
+    }
+}
+
 impl draw::renderer::RenderPrimitive for Quad {
     fn render_primitive(
         self,
@@ -153,6 +169,12 @@ impl SetPolygon for Quad {
     }
 }
 
+impl SetMaterial for Quad {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.polygon)
+    }
+}
+
 // Primitive conversions.
 
 impl From<Quad> for Primitive {
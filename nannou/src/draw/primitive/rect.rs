@@ -1,13 +1,17 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
+use crate::draw::properties::sdf::SdfOptions;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetMaterial, SetOrientation, SetPosition,
+    SetSdf, SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom;
-use crate::glam::Vec2;
+use crate::glam::{Mat4, Vec2};
+use lyon::path::builder::PathBuilder;
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing a **Rect**.
@@ -15,6 +19,7 @@ use lyon::tessellation::StrokeOptions;
 pub struct Rect {
     dimensions: dimension::Properties,
     polygon: PolygonInit,
+    sdf: Option<SdfOptions>,
 }
 
 /// The drawing context for a Rect.
@@ -38,10 +43,23 @@ impl<'a> DrawingRect<'a> {
     where
         C: IntoLinSrgba<ColorScalar>,
     {
+        let color = self.linear_color(color);
         self.map_ty(|ty| ty.stroke(color))
     }
 }
 
+impl Rect {
+    pub(crate) fn bounds(&self) -> Option<(geom::Rect, Mat4)> {
+        // SDF effects such as glow and shadow may extend beyond the rect.
+        if self.sdf.is_some() {
+            return None;
+        }
+        let w = self.dimensions.x.unwrap_or(100.0).abs();
+        let h = self.dimensions.y.unwrap_or(100.0).abs();
+        Some(self.polygon.opts.bounds(geom::Rect::from_w_h(w, h)))
+    }
+}
+
 impl draw::renderer::RenderPrimitive for Rect {
     fn render_primitive(
         self,
@@ -51,6 +69,7 @@ impl draw::renderer::RenderPrimitive for Rect {
         let Rect {
             polygon,
             dimensions,
+            sdf,
         } = self;
 
         // If dimensions were specified, scale the points to those dimensions.
@@ -61,6 +80,36 @@ impl draw::renderer::RenderPrimitive for Rect {
         );
         let w = maybe_x.unwrap_or(100.0);
         let h = maybe_y.unwrap_or(100.0);
+        if let Some(sdf) = sdf {
+            if ctxt.sdf {
+                return draw::renderer::sdf::render_polygon(
+                    draw::renderer::sdf::Shape::Box,
+                    Vec2::new(w, h) * 0.5,
+                    polygon.opts,
+                    &sdf,
+                    ctxt,
+                    &draw::theme::Primitive::Rect,
+                    mesh,
+                );
+            }
+            // Fall back to the path of the rounded rect, matching the shape of the SDF.
+            let radius = sdf.corner_radius.max(0.0).min(w.abs().min(h.abs()) * 0.5);
+            if radius > 0.0 {
+                let rect = lyon::math::rect(-w * 0.5, -h * 0.5, w, h);
+                let radii = lyon::path::builder::BorderRadii::new(radius);
+                let mut builder = lyon::path::Path::builder();
+                builder.add_rounded_rectangle(&rect, &radii, lyon::path::Winding::Positive);
+                let path = builder.build();
+                polygon::render_events_themed(
+                    polygon.opts,
+                    || (&path).into_iter(),
+                    ctxt,
+                    &draw::theme::Primitive::Rect,
+                    mesh,
+                );
+                return draw::renderer::PrimitiveRender::default();
+            }
+        }
         let rect = geom::Rect::from_wh([w, h].into());
         let points = rect.corners().vertices().map(Vec2::from);
         polygon::render_points_themed(
@@ -86,9 +135,11 @@ impl Default for Rect {
     fn default() -> Self {
         let dimensions = <_>::default();
         let polygon = <_>::default();
+        let sdf = None;
         Rect {
             dimensions,
             polygon,
+            sdf,
         }
     }
 }
@@ -123,12 +174,24 @@ impl SetStroke for Rect {
     }
 }
 
+impl SetSdf for Rect {
+    fn sdf_options_mut(&mut self) -> &mut Option<SdfOptions> {
+        SetSdf::sdf_options_mut(&mut self.sdf)
+    }
+}
+
 impl SetPolygon for Rect {
     fn polygon_options_mut(&mut self) -> &mut PolygonOptions {
         SetPolygon::polygon_options_mut(&mut self.polygon)
     }
 }
 
+impl SetMaterial for Rect {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.polygon)
+    }
+}
+
 // Primitive conversions.
 
 impl From<Rect> for Primitive {
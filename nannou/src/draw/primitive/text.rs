@@ -8,6 +8,11 @@ use crate::draw::properties::{
 use crate::draw::{self, theme, Drawing};
 use crate::geom::{self, Point2};
 use crate::text::{self, Align, Font, FontSize, Justify, Layout, Scalar, Wrap};
+use lyon::path::iterator::PathIterator;
+use lyon::path::PathEvent;
+
+/// The tolerance used when flattening the curves of a path along which text is laid out.
+pub const DEFAULT_PATH_TOLERANCE: f32 = 0.1;
 
 /// Properties related to drawing the **Text** primitive.
 #[derive(Clone, Debug)]
@@ -16,6 +21,20 @@ pub struct Text {
     style: Style,
     // The byte range into the `Draw` context's text buffer.
     text: std::ops::Range<usize>,
+    // The flattened path along which glyphs are laid out, if any.
+    path: Option<Vec<Point2>>,
+    path_style: PathStyle,
+}
+
+/// Properties related to laying out **Text** along a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PathStyle {
+    /// The distance along the path at which the text begins, before justification.
+    pub start: Scalar,
+    /// Additional space inserted between each glyph.
+    pub letter_spacing: Scalar,
+    /// Whether or not each glyph is rotated to follow the tangent of the path.
+    pub rotate_glyphs: bool,
 }
 
 /// Styling properties for the **Text** primitive.
@@ -38,10 +57,14 @@ impl Text {
         let text = start..end;
         let spatial = Default::default();
         let style = Default::default();
+        let path = None;
+        let path_style = Default::default();
         Text {
             spatial,
             style,
             text,
+            path,
+            path_style,
         }
     }
 
@@ -112,6 +135,19 @@ impl Text {
         self.map_layout(|l| l.right_justify())
     }
 
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range.
+    pub fn full_justify(self) -> Self {
+        self.map_layout(|l| l.full_justify())
+    }
+
+    /// Wrap the text by word at the given width.
+    ///
+    /// Shorthand for setting the width of the bounding **Rect** along with `wrap_by_word`.
+    pub fn wrap(mut self, width: Scalar) -> Self {
+        self.spatial.dimensions.x = Some(width);
+        self.wrap_by_word()
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(self, spacing: Scalar) -> Self {
         self.map_layout(|l| l.line_spacing(spacing))
@@ -156,6 +192,80 @@ impl Text {
         self.style.glyph_colors = colors;
         self
     }
+
+    /// Lay out the glyphs in a single line along the given path.
+    ///
+    /// Curves are flattened using the `DEFAULT_PATH_TOLERANCE`. Subsequent sub-paths continue on
+    /// from the end of the previous sub-path.
+    pub fn along_path<I>(self, events: I) -> Self
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        let mut points = vec![];
+        for event in events.into_iter().flattened(DEFAULT_PATH_TOLERANCE) {
+            match event {
+                PathEvent::Begin { at } => points.push(Point2::new(at.x, at.y)),
+                PathEvent::Line { to, .. } => points.push(Point2::new(to.x, to.y)),
+                PathEvent::End {
+                    first, close: true, ..
+                } => points.push(Point2::new(first.x, first.y)),
+                _ => (),
+            }
+        }
+        self.along_points(points)
+    }
+
+    /// Lay out the glyphs in a single line along the polyline described by the given points.
+    pub fn along_points<I>(mut self, points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Point2>,
+    {
+        self.path = Some(points.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// The distance along the path at which the text begins.
+    ///
+    /// When the text is center or right justified, it is aligned within the remainder of the path.
+    pub fn path_start(mut self, start: Scalar) -> Self {
+        self.path_style.start = start;
+        self
+    }
+
+    /// Additional space inserted between each glyph laid out along a path.
+    ///
+    /// Negative values draw the glyphs closer together.
+    pub fn letter_spacing(mut self, spacing: Scalar) -> Self {
+        self.path_style.letter_spacing = spacing;
+        self
+    }
+
+    /// Whether or not glyphs laid out along a path are rotated to follow its tangent.
+    ///
+    /// When `false`, each glyph remains upright. The default is `true`.
+    pub fn rotate_glyphs(mut self, rotate: bool) -> Self {
+        self.path_style.rotate_glyphs = rotate;
+        self
+    }
+
+    /// Specify the entire styling for laying out the **Text** along a path.
+    pub fn with_path_style(mut self, style: PathStyle) -> Self {
+        self.path_style = style;
+        self
+    }
+
+    /// The bounding rectangle reached by the text once laid out, positioned relative to the
+    /// origin of the **Draw**.
+    ///
+    /// The orientation of the text and any path it is laid out along are not accounted for.
+    pub fn bounds(&self, text_buffer: &str) -> geom::Rect {
+        let text_str = &text_buffer[self.text.clone()];
+        let layout = self.style.layout.clone().build();
+        let text = build_text(text_str, &layout, &self.spatial.dimensions);
+        let position = self.spatial.position.point;
+        text.bounding_rect().shift([position.x, position.y].into())
+    }
 }
 
 impl<'a> DrawingText<'a> {
@@ -209,6 +319,43 @@ impl<'a> DrawingText<'a> {
         self.map_ty(|ty| ty.right_justify())
     }
 
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range.
+    pub fn full_justify(self) -> Self {
+        self.map_ty(|ty| ty.full_justify())
+    }
+
+    /// Align the text to the left of its bounding **Rect**'s *x* axis range.
+    pub fn align_text_left(self) -> Self {
+        self.left_justify()
+    }
+
+    /// Align the text to the middle of its bounding **Rect**'s *x* axis range.
+    ///
+    /// This is the default behaviour.
+    pub fn align_text_center(self) -> Self {
+        self.center_justify()
+    }
+
+    /// Align the text to the right of its bounding **Rect**'s *x* axis range.
+    pub fn align_text_right(self) -> Self {
+        self.right_justify()
+    }
+
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range.
+    ///
+    /// Extra space is inserted between words, while the last line of each paragraph is aligned to
+    /// the left.
+    pub fn align_text_justify(self) -> Self {
+        self.full_justify()
+    }
+
+    /// Wrap the text by word at the given width.
+    ///
+    /// Shorthand for setting the width of the text's bounding **Rect** along with `wrap_by_word`.
+    pub fn wrap(self, width: text::Scalar) -> Self {
+        self.map_ty(|ty| ty.wrap(width))
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(self, spacing: text::Scalar) -> Self {
         self.map_ty(|ty| ty.line_spacing(spacing))
@@ -257,6 +404,100 @@ impl<'a> DrawingText<'a> {
 
         self.map_ty(|ty| ty.glyph_colors(glyph_colors))
     }
+
+    /// Lay out the glyphs in a single line along the given path.
+    ///
+    /// The path is described in the same local coordinates as the text's position, so the text
+    /// may be positioned and rotated along with the path.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # fn view(app: &App, frame: Frame) {
+    /// let draw = app.draw();
+    /// let path = nannou::geom::path()
+    ///     .begin(pt2(-200.0, 0.0))
+    ///     .quadratic_bezier_to(pt2(0.0, 200.0), pt2(200.0, 0.0))
+    ///     .build();
+    /// draw.text("hello along a curve")
+    ///     .along_path(&path)
+    ///     .center_justify()
+    ///     .letter_spacing(2.0)
+    ///     .font_size(24);
+    /// # }
+    /// ```
+    pub fn along_path<I>(self, events: I) -> Self
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        self.map_ty(|ty| ty.along_path(events))
+    }
+
+    /// Lay out the glyphs in a single line along the polyline described by the given points.
+    pub fn along_points<I>(self, points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Point2>,
+    {
+        self.map_ty(|ty| ty.along_points(points))
+    }
+
+    /// The distance along the path at which the text begins.
+    pub fn path_start(self, start: Scalar) -> Self {
+        self.map_ty(|ty| ty.path_start(start))
+    }
+
+    /// Additional space inserted between each glyph laid out along a path.
+    pub fn letter_spacing(self, spacing: Scalar) -> Self {
+        self.map_ty(|ty| ty.letter_spacing(spacing))
+    }
+
+    /// Whether or not glyphs laid out along a path are rotated to follow its tangent.
+    pub fn rotate_glyphs(self, rotate: bool) -> Self {
+        self.map_ty(|ty| ty.rotate_glyphs(rotate))
+    }
+
+    /// Specify the entire styling for laying out the **Text** along a path.
+    pub fn with_path_style(self, style: PathStyle) -> Self {
+        self.map_ty(|ty| ty.with_path_style(style))
+    }
+
+    /// The bounding rectangle reached by the text once laid out, positioned relative to the
+    /// origin of the **Draw**.
+    ///
+    /// This allows for measuring a paragraph before it is drawn, e.g. to fit a backdrop to it. The
+    /// orientation of the text and any path it is laid out along are not accounted for.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # fn view(app: &App, frame: Frame) {
+    /// let draw = app.draw();
+    /// let text = draw
+    ///     .text("Some paragraph that is long enough to wrap onto multiple lines.")
+    ///     .wrap(240.0)
+    ///     .align_text_justify()
+    ///     .line_spacing(4.0)
+    ///     .color(BLACK);
+    /// let bounds = text.text_bounds();
+    /// draw.rect().xy(bounds.xy()).wh(bounds.wh() + vec2(20.0, 20.0)).color(WHITE);
+    /// # }
+    /// ```
+    pub fn text_bounds(&self) -> geom::Rect {
+        self.with_primitive(|primitive, state| match primitive {
+            Primitive::Text(text) => text.bounds(&state.text_buffer),
+            _ => geom::Rect::from_w_h(0.0, 0.0),
+        })
+        .unwrap_or_else(|| geom::Rect::from_w_h(0.0, 0.0))
+    }
+}
+
+impl Default for PathStyle {
+    fn default() -> Self {
+        PathStyle {
+            start: 0.0,
+            letter_spacing: 0.0,
+            rotate_glyphs: true,
+        }
+    }
 }
 
 impl draw::renderer::RenderPrimitive for Text {
@@ -269,6 +510,8 @@ impl draw::renderer::RenderPrimitive for Text {
             spatial,
             style,
             text,
+            path,
+            path_style,
         } = self;
         let Style {
             color,
@@ -276,33 +519,59 @@ impl draw::renderer::RenderPrimitive for Text {
             layout,
         } = style;
         let layout = layout.build();
-        let (maybe_x, maybe_y, maybe_z) = (
-            spatial.dimensions.x,
-            spatial.dimensions.y,
-            spatial.dimensions.z,
-        );
-        assert!(
-            maybe_z.is_none(),
-            "z dimension support for text is unimplemented"
-        );
-        let w = maybe_x.unwrap_or(200.0);
-        let h = maybe_y.unwrap_or(200.0);
-        let rect: geom::Rect = geom::Rect::from_wh([w, h].into());
         let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
+        let scale_factor = ctxt.output_attachment_scale_factor;
 
         let text_str = &ctxt.text_buffer[text.clone()];
-        let text = text::text(text_str).layout(&layout).build(rect);
-
-        // Queue the glyphs to be cached
-        let font_id = text::font::id(text.font());
-        let positioned_glyphs: Vec<_> = text
-            .rt_glyphs(
-                ctxt.output_attachment_size,
-                ctxt.output_attachment_scale_factor,
+        let text = build_text(text_str, &layout, &spatial.dimensions);
+
+        // Missing glyphs are resolved through the chain of fallback fonts.
+        let fallbacks = text::font::fallbacks_for(text.font(), text_str);
+
+        // If a path was given, lay the glyphs out along it rather than within the rect.
+        let path_glyphs = path.map(|points| {
+            layout_along_path(
+                text_str,
+                text.font(),
+                &fallbacks,
+                &layout,
+                &points,
+                &path_style,
+                scale_factor,
             )
+        });
+
+        // Each font in the fallback chain alongside its cache ID.
+        let fonts: Vec<_> = std::iter::once(text.font())
+            .chain(fallbacks.iter())
             .collect();
-        for glyph in positioned_glyphs.iter() {
-            ctxt.glyph_cache.queue_glyph(font_id.index(), glyph.clone());
+        let font_ids: Vec<_> = fonts
+            .iter()
+            .map(|font| text::font::id(font).index())
+            .collect();
+
+        // Queue the glyphs to be cached
+        let positioned_glyphs: Vec<_> = match path_glyphs {
+            Some(ref glyphs) => glyphs
+                .iter()
+                .map(|g| (g.font_ix, g.glyph.clone()))
+                .collect(),
+            None => text
+                .rt_glyphs_with_fonts(ctxt.output_attachment_size, scale_factor)
+                .filter(|&(font_ix, _)| font_ix < font_ids.len())
+                .collect(),
+        };
+        // Color glyphs are cached within the color glyph atlas instead.
+        for &(font_ix, ref glyph) in positioned_glyphs.iter() {
+            if ctxt
+                .glyph_cache
+                .color_glyphs
+                .is_color(fonts[font_ix], glyph.id())
+            {
+                continue;
+            }
+            ctxt.glyph_cache
+                .queue_glyph(font_ids[font_ix], glyph.clone());
         }
 
         // Cache the enqueued glyphs within the pixel buffer.
@@ -345,8 +614,34 @@ impl draw::renderer::RenderPrimitive for Text {
         let local_transform = spatial.position.transform() * spatial.orientation.transform();
         let transform = global_transform * local_transform;
 
+        // Extend the mesh with a rotated rect for each glyph along the path.
+        if let Some(path_glyphs) = path_glyphs {
+            for g in &path_glyphs {
+                let g_color = glyph_colors.get(g.char_index).unwrap_or(&color);
+                let rects = glyph_rects(
+                    ctxt.glyph_cache,
+                    fonts[g.font_ix],
+                    font_ids[g.font_ix],
+                    &g.glyph,
+                );
+                if let Some((uv_rect, screen_rect)) = rects {
+                    // The glyph's rect relative to the centre of its baseline.
+                    let l = screen_rect.min.x as f32 / scale_factor - g.half_advance;
+                    let r = screen_rect.max.x as f32 / scale_factor - g.half_advance;
+                    let t = -screen_rect.min.y as f32 / scale_factor;
+                    let b = -screen_rect.max.y as f32 / scale_factor;
+                    let (sin, cos) = g.angle.sin_cos();
+                    let place = |x: f32, y: f32| {
+                        g.position + Point2::new(x * cos - y * sin, x * sin + y * cos)
+                    };
+                    let corners = [place(l, t), place(l, b), place(r, b), place(r, t)];
+                    push_glyph_quad(mesh, &transform, corners, uv_rect, g_color);
+                }
+            }
+            return draw::renderer::PrimitiveRender::text();
+        }
+
         // A function for converting RustType rects to nannou rects.
-        let scale_factor = ctxt.output_attachment_scale_factor;
         let (out_w, out_h) = ctxt.output_attachment_size.into();
         let [half_out_w, half_out_h] = [out_w as f32 / 2.0, out_h as f32 / 2.0];
         let to_nannou_rect = |screen_rect: text::rt::Rect<i32>| {
@@ -369,46 +664,17 @@ impl draw::renderer::RenderPrimitive for Text {
             .chain(std::iter::repeat(&color));
 
         // Extend the mesh with a rect for each displayed glyph.
-        for (g, g_color) in positioned_glyphs.iter().zip(glyph_colors_iter) {
-            if let Ok(Some((uv_rect, screen_rect))) = ctxt.glyph_cache.rect_for(font_id.index(), &g)
-            {
+        for (&(font_ix, ref g), g_color) in positioned_glyphs.iter().zip(glyph_colors_iter) {
+            let rects = glyph_rects(ctxt.glyph_cache, fonts[font_ix], font_ids[font_ix], g);
+            if let Some((uv_rect, screen_rect)) = rects {
                 let rect = to_nannou_rect(screen_rect);
-
-                // Create a mesh-compatible vertex from the position and tex_coords.
-                let v = |p: Point2, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
-                    let p = transform.transform_point3([p.x, p.y, 0.0].into());
-                    let point = draw::mesh::vertex::Point::from(p);
-                    draw::mesh::vertex::new(point, g_color.to_owned(), tex_coords.into())
-                };
-
-                // The sides of the UV rect.
-                let uv_l = uv_rect.min.x;
-                let uv_t = uv_rect.min.y;
-                let uv_r = uv_rect.max.x;
-                let uv_b = uv_rect.max.y;
-
-                // Insert the vertices.
-                let bottom_left = v(rect.bottom_left(), [uv_l, uv_b]);
-                let bottom_right = v(rect.bottom_right(), [uv_r, uv_b]);
-                let top_left = v(rect.top_left(), [uv_l, uv_t]);
-                let top_right = v(rect.top_right(), [uv_r, uv_t]);
-                let start_ix = mesh.points().len() as u32;
-                mesh.push_vertex(top_left);
-                mesh.push_vertex(bottom_left);
-                mesh.push_vertex(bottom_right);
-                mesh.push_vertex(top_right);
-
-                // Now the indices.
-                let tl_ix = start_ix;
-                let bl_ix = start_ix + 1;
-                let br_ix = start_ix + 2;
-                let tr_ix = start_ix + 3;
-                mesh.push_index(tl_ix);
-                mesh.push_index(bl_ix);
-                mesh.push_index(br_ix);
-                mesh.push_index(tl_ix);
-                mesh.push_index(br_ix);
-                mesh.push_index(tr_ix);
+                let corners = [
+                    rect.top_left(),
+                    rect.bottom_left(),
+                    rect.bottom_right(),
+                    rect.top_right(),
+                ];
+                push_glyph_quad(mesh, &transform, corners, uv_rect, g_color);
             }
         }
 
@@ -416,6 +682,217 @@ impl draw::renderer::RenderPrimitive for Text {
     }
 }
 
+// Lay out the given text within a rect of the given dimensions.
+fn build_text<'a>(
+    text_str: &'a str,
+    layout: &Layout,
+    dimensions: &dimension::Properties,
+) -> text::Text<'a> {
+    assert!(
+        dimensions.z.is_none(),
+        "z dimension support for text is unimplemented"
+    );
+    let w = dimensions.x.unwrap_or(200.0);
+    let h = dimensions.y.unwrap_or(200.0);
+    let rect: geom::Rect = geom::Rect::from_wh([w, h].into());
+    text::text(text_str).layout(layout).build(rect)
+}
+
+// A glyph laid out along a path.
+struct PathGlyph {
+    // The glyph positioned at the pixel origin, ready for caching.
+    glyph: text::PositionedGlyph,
+    // The index of the font providing the glyph within the fallback chain.
+    font_ix: usize,
+    // The index of the glyph's character within the text, used to look up its color.
+    char_index: usize,
+    // The point on the path at the centre of the glyph's baseline.
+    position: Point2,
+    // The rotation of the glyph in radians.
+    angle: f32,
+    // Half of the glyph's advance width.
+    half_advance: f32,
+}
+
+// Lay out the given text in a single line along the polyline described by `points`.
+//
+// Glyphs are advanced along the arc length of the path. Each glyph is centred on the point at the
+// middle of its advance and rotated to match the chord spanning its advance, so that glyphs
+// follow curves smoothly. Glyphs that would fall beyond either end of the path are skipped.
+fn layout_along_path(
+    text: &str,
+    font: &Font,
+    fallbacks: &[Font],
+    layout: &Layout,
+    points: &[Point2],
+    style: &PathStyle,
+    scale_factor: f32,
+) -> Vec<PathGlyph> {
+    // The arc length of the path at each point.
+    let mut lengths = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    for (i, &p) in points.iter().enumerate() {
+        if i > 0 {
+            total += p.distance(points[i - 1]);
+        }
+        lengths.push(total);
+    }
+
+    // Lay out the glyphs along a straight baseline in pixel space.
+    let scale = text::f32_pt_to_scale(layout.font_size as f32 * scale_factor);
+    let spacing = style.letter_spacing * scale_factor;
+    let mut glyphs = vec![];
+    let mut x = 0.0;
+    let mut last = None;
+    let mut spaces = 0;
+    for (char_index, ch) in text.chars().enumerate() {
+        if ch.is_control() {
+            continue;
+        }
+        let (font_ix, font) = text::font::select(font, fallbacks, ch);
+        let glyph = font.glyph(ch).scaled(scale);
+        if let Some((last_ix, last_id)) = last {
+            if last_ix == font_ix {
+                x += font.pair_kerning(scale, last_id, glyph.id());
+            }
+            x += spacing;
+        }
+        last = Some((font_ix, glyph.id()));
+        let advance = glyph.h_metrics().advance_width;
+        let glyph = glyph.positioned(text::rt::point(0.0, 0.0)).standalone();
+        glyphs.push((char_index, font_ix, x, spaces, advance, glyph));
+        x += advance;
+        if ch.is_whitespace() {
+            spaces += 1;
+        }
+    }
+    let width = x / scale_factor;
+
+    // Justify the text within the remainder of the path.
+    let remaining = total - style.start;
+    let start = style.start
+        + match layout.justify {
+            Justify::Left | Justify::Full => 0.0,
+            Justify::Center => (remaining - width) / 2.0,
+            Justify::Right => remaining - width,
+        };
+    let word_spacing = match layout.justify {
+        Justify::Full if spaces > 0 => ((remaining - width) / spaces as f32).max(0.0),
+        _ => 0.0,
+    };
+
+    glyphs
+        .into_iter()
+        .filter_map(|(char_index, font_ix, x, spaces, advance, glyph)| {
+            let half_advance = advance / scale_factor / 2.0;
+            let x = x / scale_factor + spaces as f32 * word_spacing;
+            let distance = start + x + half_advance;
+            let position = point_at_distance(points, &lengths, distance)?;
+            let angle = if style.rotate_glyphs {
+                let a = (distance - half_advance).max(0.0);
+                let b = (distance + half_advance).min(total);
+                let a = point_at_distance(points, &lengths, a).unwrap_or(position);
+                let b = point_at_distance(points, &lengths, b).unwrap_or(position);
+                let chord = b - a;
+                chord.y.atan2(chord.x)
+            } else {
+                0.0
+            };
+            Some(PathGlyph {
+                glyph,
+                font_ix,
+                char_index,
+                position,
+                angle,
+                half_advance,
+            })
+        })
+        .collect()
+}
+
+// The point at the given arc length along the polyline, or `None` if beyond either end.
+fn point_at_distance(points: &[Point2], lengths: &[f32], distance: f32) -> Option<Point2> {
+    let total = *lengths.last()?;
+    if distance < 0.0 || distance > total {
+        return None;
+    }
+    let ix = lengths.partition_point(|&l| l < distance);
+    if ix == 0 {
+        return Some(points[0]);
+    }
+    let (start, end) = (lengths[ix - 1], lengths[ix]);
+    let t = if end > start {
+        (distance - start) / (end - start)
+    } else {
+        0.0
+    };
+    Some(points[ix - 1].lerp(points[ix], t))
+}
+
+// Push a textured quad for a single glyph.
+//
+// The corners are expected in the order top-left, bottom-left, bottom-right, top-right.
+// The texture coordinates and pixel rect of the given glyph within either the glyph cache or,
+// for color glyphs, the color glyph atlas.
+//
+// The texture coordinates of color glyphs are mirrored into the negative range, i.e. `x` becomes
+// `-1.0 - x`, as expected by the fragment shader's text mode.
+fn glyph_rects(
+    glyph_cache: &mut draw::renderer::GlyphCache,
+    font: &Font,
+    font_id: usize,
+    glyph: &text::PositionedGlyph,
+) -> Option<(text::rt::Rect<f32>, text::rt::Rect<i32>)> {
+    if glyph_cache.color_glyphs.is_color(font, glyph.id()) {
+        let (mut uv_rect, screen_rect) = glyph_cache.color_glyphs.rect_for(font, glyph)?;
+        uv_rect.min.x = -1.0 - uv_rect.min.x;
+        uv_rect.max.x = -1.0 - uv_rect.max.x;
+        return Some((uv_rect, screen_rect));
+    }
+    glyph_cache.rect_for(font_id, glyph).ok().flatten()
+}
+
+fn push_glyph_quad(
+    mesh: &mut draw::Mesh,
+    transform: &crate::glam::Mat4,
+    corners: [Point2; 4],
+    uv_rect: text::rt::Rect<f32>,
+    color: &LinSrgba,
+) {
+    // Create a mesh-compatible vertex from the position and tex_coords.
+    let v = |p: Point2, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
+        let p = transform.transform_point3([p.x, p.y, 0.0].into());
+        let point = draw::mesh::vertex::Point::from(p);
+        draw::mesh::vertex::new(point, color.to_owned(), tex_coords.into())
+    };
+
+    // The sides of the UV rect.
+    let uv_l = uv_rect.min.x;
+    let uv_t = uv_rect.min.y;
+    let uv_r = uv_rect.max.x;
+    let uv_b = uv_rect.max.y;
+
+    // Insert the vertices.
+    let [tl, bl, br, tr] = corners;
+    let start_ix = mesh.points().len() as u32;
+    mesh.push_vertex(v(tl, [uv_l, uv_t]));
+    mesh.push_vertex(v(bl, [uv_l, uv_b]));
+    mesh.push_vertex(v(br, [uv_r, uv_b]));
+    mesh.push_vertex(v(tr, [uv_r, uv_t]));
+
+    // Now the indices.
+    let tl_ix = start_ix;
+    let bl_ix = start_ix + 1;
+    let br_ix = start_ix + 2;
+    let tr_ix = start_ix + 3;
+    mesh.push_index(tl_ix);
+    mesh.push_index(bl_ix);
+    mesh.push_index(br_ix);
+    mesh.push_index(tl_ix);
+    mesh.push_index(br_ix);
+    mesh.push_index(tr_ix);
+}
+
 impl SetOrientation for Text {
     fn properties(&mut self) -> &mut orientation::Properties {
         SetOrientation::properties(&mut self.spatial)
@@ -121,6 +121,7 @@ impl draw::renderer::RenderPrimitive for Texture {
             path::Options::Fill(Default::default()),
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
             mesh,
         );
 
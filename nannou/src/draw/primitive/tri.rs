@@ -1,13 +1,15 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetMaterial, SetOrientation, SetPosition,
+    SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom::{self, pt2, Point2};
-use crate::glam::vec2;
+use crate::glam::{vec2, Mat4};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing a **Tri**.
@@ -67,6 +69,20 @@ impl<'a> DrawingTri<'a> {
 
 // Trait implementations.
 
+impl Tri {
+    pub(crate) fn bounds(&self) -> Option<(geom::Rect, Mat4)> {
+        // Scale the bounds about the centroid as the points are scaled when rendering.
+        let rect = self.tri.bounding_rect();
+        let centroid = self.tri.centroid();
+        let x_scale = self.dimensions.x.map(|x| x / rect.w()).unwrap_or(1.0);
+        let y_scale = self.dimensions.y.map(|y| y / rect.h()).unwrap_or(1.0);
+        let scale = vec2(x_scale, y_scale);
+        let a = centroid + (rect.bottom_left() - centroid) * scale;
+        let b = centroid + (rect.top_right() - centroid) * scale;
+        Some(self.polygon.opts.bounds(geom::Rect::from_corners(a, b)))
+    }
+}
+
 impl draw::renderer::RenderPrimitive for Tri {
     fn render_primitive(
         self,
@@ -166,6 +182,12 @@ impl SetPolygon for Tri {
     }
 }
 
+impl SetMaterial for Tri {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        SetMaterial::material_mut(&mut self.polygon)
+    }
+}
+
 // Primitive conversions.
 
 impl From<Tri> for Primitive {
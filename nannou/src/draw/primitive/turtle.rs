@@ -0,0 +1,104 @@
+use crate::color::LinSrgba;
+use crate::draw;
+use crate::draw::primitive::path;
+use crate::draw::primitive::{PathStroke, Primitive};
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, SetColor, SetOrientation, SetPosition, SetStroke};
+use crate::geom::Point2;
+use lyon::tessellation::StrokeOptions;
+
+/// A polyline traced by a **Turtle** whose points may be extended while it is being drawn.
+///
+/// See `Draw::turtle` for details.
+#[derive(Clone, Debug, Default)]
+pub struct TurtlePath {
+    pub path: PathStroke,
+    pub points: Vec<Point2>,
+}
+
+impl TurtlePath {
+    /// A path beginning with the given points, stroked with the given color and weight.
+    pub fn new(points: Vec<Point2>, color: Option<LinSrgba>, weight: f32) -> Self {
+        let mut path = PathStroke::default()
+            .stroke_weight(weight)
+            .caps_round()
+            .join_round();
+        path.color = color;
+        TurtlePath { path, points }
+    }
+}
+
+impl SetStroke for TurtlePath {
+    fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
+        SetStroke::stroke_options_mut(&mut self.path)
+    }
+}
+
+impl SetOrientation for TurtlePath {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.path)
+    }
+}
+
+impl SetPosition for TurtlePath {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.path)
+    }
+}
+
+impl SetColor<ColorScalar> for TurtlePath {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.path)
+    }
+}
+
+impl From<TurtlePath> for Primitive {
+    fn from(prim: TurtlePath) -> Self {
+        Primitive::TurtlePath(prim)
+    }
+}
+
+impl Into<Option<TurtlePath>> for Primitive {
+    fn into(self) -> Option<TurtlePath> {
+        match self {
+            Primitive::TurtlePath(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+impl draw::renderer::RenderPrimitive for TurtlePath {
+    fn render_primitive(
+        self,
+        mut ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let TurtlePath { path, points } = self;
+        if points.len() < 2 {
+            return draw::renderer::PrimitiveRender::default();
+        }
+        let close = false;
+        let points = points.iter().map(|p| p.to_array().into());
+        let events = lyon::path::iterator::FromPolyline::new(close, points);
+
+        // Determine the transform to apply to all points.
+        let global_transform = *ctxt.transform;
+        let local_transform = path.position.transform() * path.orientation.transform();
+        let transform = global_transform * local_transform;
+
+        path::render_path_events(
+            events,
+            path.color,
+            transform,
+            path::Options::Stroke(ctxt.stroke_options(path.opts, &transform)),
+            ctxt.theme,
+            &draw::theme::Primitive::Path,
+            ctxt.fill_tessellator,
+            ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
+            mesh,
+        );
+
+        draw::renderer::PrimitiveRender::default()
+    }
+}
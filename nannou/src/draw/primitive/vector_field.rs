@@ -0,0 +1,260 @@
+use crate::color::LinSrgba;
+use crate::draw::primitive::path;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, SetColor, SetOrientation, SetPosition};
+use crate::draw::{self, Drawing};
+use crate::geom::Point2;
+use crate::glam::Vec2;
+use crate::vector_field::VectorField2;
+use lyon::tessellation::StrokeOptions;
+
+/// Properties related to drawing a **VectorField2** as a grid of arrows.
+#[derive(Clone, Debug)]
+pub struct VectorField {
+    field: VectorField2,
+    resolution: Option<[usize; 2]>,
+    scale: Option<f32>,
+    normalize: bool,
+    weight: f32,
+    color: Option<LinSrgba>,
+    position: position::Properties,
+    orientation: orientation::Properties,
+}
+
+/// The drawing context for a **VectorField**.
+pub type DrawingVectorField<'a> = Drawing<'a, VectorField>;
+
+// Vector field specific methods.
+
+impl VectorField {
+    /// The number of columns and rows of arrows drawn for fields described by a function.
+    pub const DEFAULT_RESOLUTION: [usize; 2] = [24, 24];
+    /// The length of the longest arrow as a fraction of the distance between arrows.
+    pub const DEFAULT_FILL: f32 = 0.9;
+    /// The default stroke weight of each arrow.
+    pub const DEFAULT_WEIGHT: f32 = 1.0;
+
+    /// Draw the given field.
+    pub fn new(field: &VectorField2) -> Self {
+        VectorField {
+            field: field.clone(),
+            resolution: None,
+            scale: None,
+            normalize: false,
+            weight: Self::DEFAULT_WEIGHT,
+            color: None,
+            position: Default::default(),
+            orientation: Default::default(),
+        }
+    }
+
+    /// The number of columns and rows of arrows.
+    ///
+    /// By default, grids draw an arrow at the centre of each cell and function fields use
+    /// `DEFAULT_RESOLUTION`.
+    pub fn resolution(mut self, cols: usize, rows: usize) -> Self {
+        self.resolution = Some([cols.max(1), rows.max(1)]);
+        self
+    }
+
+    /// The length of an arrow in points per unit of magnitude.
+    ///
+    /// By default, arrows are scaled so that the longest fills `DEFAULT_FILL` of the distance
+    /// between arrows.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    /// Draw all arrows with the same length, showing only the direction of the field.
+    pub fn normalize(mut self) -> Self {
+        self.normalize = true;
+        self
+    }
+
+    /// The stroke weight of each arrow.
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+// Trait implementations.
+
+impl draw::renderer::RenderPrimitive for VectorField {
+    fn render_primitive(
+        self,
+        mut ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let VectorField {
+            field,
+            resolution,
+            scale,
+            normalize,
+            weight,
+            color,
+            position,
+            orientation,
+        } = self;
+        let [cols, rows] = resolution
+            .or_else(|| field.resolution())
+            .unwrap_or(Self::DEFAULT_RESOLUTION);
+        let bounds = field.bounds();
+        let spacing = (bounds.w() / cols as f32).min(bounds.h() / rows as f32);
+
+        // Sample the field at the centre of each cell.
+        let mut arrows: Vec<(Point2, Vec2)> = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                let p = bounds.bottom_left()
+                    + Vec2::new(
+                        (col as f32 + 0.5) * bounds.w() / cols as f32,
+                        (row as f32 + 0.5) * bounds.h() / rows as f32,
+                    );
+                arrows.push((p, field.sample(p)));
+            }
+        }
+
+        // Determine the length of each arrow.
+        let max_len = spacing * Self::DEFAULT_FILL;
+        let scale = match (normalize, scale) {
+            (true, _) => None,
+            (false, Some(scale)) => Some(scale),
+            (false, None) => {
+                let longest = arrows.iter().fold(0.0f32, |l, &(_, v)| l.max(v.length()));
+                Some(if longest > 0.0 {
+                    max_len / longest
+                } else {
+                    0.0
+                })
+            }
+        };
+
+        // Collect the shafts and heads of all arrows into one path each.
+        let mut shafts = lyon::path::Path::builder();
+        let mut heads = lyon::path::Path::builder();
+        let head_length = weight * 4.0;
+        let head_width = weight * 2.0;
+        let point = |p: Point2| lyon::math::point(p.x, p.y);
+        for (p, v) in arrows {
+            let len = match scale {
+                Some(scale) => v.length() * scale,
+                None => max_len,
+            };
+            let dir = v.normalize_or_zero();
+            if len <= 0.0 || dir == Vec2::ZERO {
+                continue;
+            }
+            // Centre the arrow on its sample point.
+            let start = p - dir * len * 0.5;
+            let end = p + dir * len * 0.5;
+            let head_len = head_length.min(len);
+            let head_start = end - dir * head_len;
+            let side = dir.perp() * head_width;
+            heads.begin(point(end));
+            heads.line_to(point(head_start + side));
+            heads.line_to(point(head_start - side));
+            heads.end(true);
+            if len > head_len {
+                shafts.begin(point(start));
+                shafts.line_to(point(head_start));
+                shafts.end(false);
+            }
+        }
+
+        let transform = *ctxt.transform * position.transform() * orientation.transform();
+        let color = color.unwrap_or_else(|| {
+            ctxt.theme
+                .stroke_lin_srgba(&draw::theme::Primitive::VectorField)
+        });
+        let stroke_opts = StrokeOptions::default().with_line_width(weight);
+        let (heads, shafts) = (heads.build(), shafts.build());
+        path::render_path_events(
+            heads.iter(),
+            Some(color),
+            transform,
+            path::Options::Fill(Default::default()),
+            ctxt.theme,
+            &draw::theme::Primitive::VectorField,
+            ctxt.fill_tessellator,
+            ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
+            mesh,
+        );
+        path::render_path_events(
+            shafts.iter(),
+            Some(color),
+            transform,
+            path::Options::Stroke(ctxt.stroke_options(stroke_opts, &transform)),
+            ctxt.theme,
+            &draw::theme::Primitive::VectorField,
+            ctxt.fill_tessellator,
+            ctxt.stroke_tessellator,
+            ctxt.vector_paths.as_deref_mut(),
+            mesh,
+        );
+
+        draw::renderer::PrimitiveRender::default()
+    }
+}
+
+impl SetOrientation for VectorField {
+    fn properties(&mut self) -> &mut orientation::Properties {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl SetPosition for VectorField {
+    fn properties(&mut self) -> &mut position::Properties {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl SetColor<ColorScalar> for VectorField {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        &mut self.color
+    }
+}
+
+// Primitive conversions.
+
+impl From<VectorField> for Primitive {
+    fn from(prim: VectorField) -> Self {
+        Primitive::VectorField(prim)
+    }
+}
+
+impl Into<Option<VectorField>> for Primitive {
+    fn into(self) -> Option<VectorField> {
+        match self {
+            Primitive::VectorField(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
+
+// Drawing methods.
+
+impl<'a> DrawingVectorField<'a> {
+    /// The number of columns and rows of arrows.
+    pub fn resolution(self, cols: usize, rows: usize) -> Self {
+        self.map_ty(|ty| ty.resolution(cols, rows))
+    }
+
+    /// The length of an arrow in points per unit of magnitude.
+    pub fn scale(self, scale: f32) -> Self {
+        self.map_ty(|ty| ty.scale(scale))
+    }
+
+    /// Draw all arrows with the same length, showing only the direction of the field.
+    pub fn normalize(self) -> Self {
+        self.map_ty(|ty| ty.normalize())
+    }
+
+    /// The stroke weight of each arrow.
+    pub fn weight(self, weight: f32) -> Self {
+        self.map_ty(|ty| ty.weight(weight))
+    }
+}
@@ -0,0 +1,109 @@
+//! Properties describing how the surface of a primitive responds to light.
+
+use crate::color::conv::IntoLinSrgba;
+use crate::color::LinSrgb;
+use crate::draw::properties::ColorScalar;
+use crate::wgpu;
+
+/// The metalness of surfaces for which none is specified.
+pub const DEFAULT_METALLIC: f32 = 0.0;
+
+/// The roughness of surfaces for which none is specified.
+pub const DEFAULT_ROUGHNESS: f32 = 0.5;
+
+/// The physically based material of a lit primitive.
+///
+/// Surfaces are shaded with a metallic-roughness model. The base color of the surface is the
+/// color of the primitive, e.g. its fill color or texture.
+#[derive(Clone, Debug)]
+pub struct Material {
+    /// How metallic the surface is, from `0.0` (dielectric, e.g. plastic) to `1.0` (metal).
+    pub metallic: f32,
+    /// How rough the surface is, from `0.0` (a sharp, mirror-like highlight) to `1.0` (a broad,
+    /// dull highlight).
+    pub roughness: f32,
+    /// The color of the light emitted by the surface, regardless of the lights of the **Draw**.
+    pub emissive: LinSrgb,
+    /// A tangent space normal map perturbing the flat normal of each triangle.
+    ///
+    /// The map is sampled via the texture coordinates of the primitive, so it is only effective
+    /// for primitives with texture coordinates, e.g. textured meshes and polygons.
+    pub normal_map: Option<wgpu::TextureView>,
+}
+
+/// Primitives whose surface may be described by a **Material**.
+///
+/// This trait allows the `Drawing` context to automatically provide an implementation of the
+/// following builder methods for all primitives that support materials.
+///
+/// Specifying any part of a material implies that the primitive is lit, as though drawn via
+/// `Draw::lit`.
+pub trait SetMaterial: Sized {
+    /// Provide a mutable reference to the material field.
+    fn material_mut(&mut self) -> &mut Option<Material>;
+
+    /// Specify the whole material of the primitive.
+    fn material(mut self, material: Material) -> Self {
+        *self.material_mut() = Some(material);
+        self
+    }
+
+    /// How metallic the surface is, from `0.0` (dielectric) to `1.0` (metal).
+    /// `DEFAULT_METALLIC` by default.
+    fn metallic(mut self, metallic: f32) -> Self {
+        self.material_mut()
+            .get_or_insert_with(Default::default)
+            .metallic = metallic.clamp(0.0, 1.0);
+        self
+    }
+
+    /// How rough the surface is, from `0.0` (mirror-like) to `1.0` (dull).
+    /// `DEFAULT_ROUGHNESS` by default.
+    fn roughness(mut self, roughness: f32) -> Self {
+        self.material_mut()
+            .get_or_insert_with(Default::default)
+            .roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// The color of the light emitted by the surface. Black by default.
+    fn emissive<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.material_mut()
+            .get_or_insert_with(Default::default)
+            .emissive = color.color * color.alpha;
+        self
+    }
+
+    /// A tangent space normal map perturbing the normals of the surface.
+    ///
+    /// The red, green and blue channels describe the tangent, bitangent and normal components
+    /// respectively, with green pointing towards the top of the image. The texture should use a
+    /// linear (non-sRGB) format.
+    fn normal_map(mut self, view: &dyn wgpu::ToTextureView) -> Self {
+        self.material_mut()
+            .get_or_insert_with(Default::default)
+            .normal_map = Some(view.to_texture_view());
+        self
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            metallic: DEFAULT_METALLIC,
+            roughness: DEFAULT_ROUGHNESS,
+            emissive: LinSrgb::new(0.0, 0.0, 0.0),
+            normal_map: None,
+        }
+    }
+}
+
+impl SetMaterial for Option<Material> {
+    fn material_mut(&mut self) -> &mut Option<Material> {
+        self
+    }
+}
@@ -8,11 +8,15 @@
 
 pub mod color;
 pub mod fill;
+pub mod material;
+pub mod sdf;
 pub mod spatial;
 pub mod stroke;
 
 pub use self::color::SetColor;
 pub use self::fill::SetFill;
+pub use self::material::SetMaterial;
+pub use self::sdf::SetSdf;
 pub use self::spatial::dimension::SetDimensions;
 pub use self::spatial::orientation::SetOrientation;
 pub use self::spatial::position::SetPosition;
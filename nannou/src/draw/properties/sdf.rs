@@ -0,0 +1,112 @@
+//! Properties related to rendering primitives as signed distance fields.
+
+use crate::color::conv::IntoLinSrgba;
+use crate::draw::properties::{ColorScalar, LinSrgba};
+use crate::glam::Vec2;
+
+/// Options for rendering a primitive as a screen-space signed distance field rather than as a
+/// tessellated mesh.
+///
+/// SDF shapes are drawn as a single quad and their edges are anti-aliased analytically within the
+/// fragment shader, so they remain crisp at any zoom level without requiring MSAA.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SdfOptions {
+    /// The radius used to round the corners of box shapes.
+    ///
+    /// A radius of half the shortest side produces a capsule. Ignored by ellipses.
+    pub corner_radius: f32,
+    /// A soft halo drawn around the shape.
+    pub glow: Option<Glow>,
+    /// A soft shadow drawn beneath the shape.
+    pub shadow: Option<Shadow>,
+}
+
+/// A soft halo drawn around an SDF shape.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glow {
+    /// The distance over which the glow fades out from the edge of the shape.
+    pub radius: f32,
+    /// The color of the glow at the edge of the shape.
+    pub color: LinSrgba,
+}
+
+/// A soft shadow drawn beneath an SDF shape.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shadow {
+    /// The offset of the shadow from the shape.
+    pub offset: Vec2,
+    /// The distance over which the edge of the shadow fades out.
+    pub blur: f32,
+    /// The color of the shadow.
+    pub color: LinSrgba,
+}
+
+/// Nodes that may be rendered as signed distance fields.
+///
+/// This trait allows the `Drawing` context to automatically provide an implementation of the
+/// following builder methods for all primitives that support SDF rendering.
+pub trait SetSdf: Sized {
+    /// Provide a mutable reference to the SDF options field.
+    fn sdf_options_mut(&mut self) -> &mut Option<SdfOptions>;
+
+    /// Render the primitive as a signed distance field with analytic anti-aliasing rather than
+    /// as a tessellated mesh.
+    fn sdf(mut self) -> Self {
+        self.sdf_options_mut().get_or_insert_with(Default::default);
+        self
+    }
+
+    /// Specify the whole set of SDF options, enabling SDF rendering.
+    fn sdf_opts(mut self, opts: SdfOptions) -> Self {
+        *self.sdf_options_mut() = Some(opts);
+        self
+    }
+
+    /// Round the corners of the SDF shape with the given radius, enabling SDF rendering.
+    ///
+    /// Only applies to box shapes.
+    fn sdf_corner_radius(mut self, radius: f32) -> Self {
+        self.sdf_options_mut()
+            .get_or_insert_with(Default::default)
+            .corner_radius = radius;
+        self
+    }
+
+    /// Draw a soft glow of the given radius and color around the shape, enabling SDF rendering.
+    fn sdf_glow<C>(mut self, radius: f32, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        let glow = Glow { radius, color };
+        self.sdf_options_mut()
+            .get_or_insert_with(Default::default)
+            .glow = Some(glow);
+        self
+    }
+
+    /// Draw a soft shadow beneath the shape, enabling SDF rendering.
+    ///
+    /// The `blur` describes the distance over which the edge of the shadow fades out.
+    fn sdf_shadow<C>(mut self, offset: Vec2, blur: f32, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        let shadow = Shadow {
+            offset,
+            blur,
+            color,
+        };
+        self.sdf_options_mut()
+            .get_or_insert_with(Default::default)
+            .shadow = Some(shadow);
+        self
+    }
+}
+
+impl SetSdf for Option<SdfOptions> {
+    fn sdf_options_mut(&mut self) -> &mut Option<SdfOptions> {
+        self
+    }
+}
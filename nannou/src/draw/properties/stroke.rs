@@ -1,5 +1,11 @@
 use lyon::tessellation::{LineCap, LineJoin, StrokeOptions};
 
+/// The stroke weight that renders as a hairline, exactly one physical pixel wide regardless of
+/// the scale factor of the output and the transform of the **Draw**.
+///
+/// All other stroke weights are specified in points and so are scaled by both.
+pub const HAIRLINE: f32 = 0.0;
+
 /// Nodes that support stroke tessellation.
 ///
 /// This trait allows the `Drawing` context to automatically provide an implementation of the
@@ -136,6 +142,13 @@ pub trait SetStroke: Sized {
         self
     }
 
+    /// Stroke exactly one physical pixel wide, regardless of the scale factor and transform.
+    ///
+    /// Useful for fine linework that should remain crisp on high DPI displays and when zooming.
+    fn stroke_weight_hairline(self) -> Self {
+        self.stroke_weight(HAIRLINE)
+    }
+
     /// Describes the limit before miter lines will clip, as described in the SVG spec.
     ///
     /// Must be greater than or equal to `1.0`.
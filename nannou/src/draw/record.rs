@@ -0,0 +1,588 @@
+//! Items for recording the commands submitted to a **Draw** and replaying them later.
+//!
+//! A **RecordedFrame** captures the stream of context changes and primitives submitted to a
+//! **Draw** during a single frame. Primitives are recorded as the tessellated meshes that they
+//! produce along with the vertex mode, signed distance field parameters, texture and material
+//! required to render them as they were drawn. A recording does not depend on the types used to
+//! draw them and may be serialized, saved to disk, streamed over the network and replayed within
+//! another sketch or process.
+//!
+//! As replaying a recording reproduces the exact same geometry, recordings are also useful for
+//! deterministic playback and for comparing the output of the draw API against a known-good
+//! "golden" frame.
+//!
+//! ```
+//! use nannou::draw::{Draw, RecordedFrame};
+//!
+//! let draw = Draw::new();
+//! draw.ellipse().w_h(100.0, 100.0);
+//! draw.scale(2.0).rect().w_h(10.0, 10.0);
+//! let frame = RecordedFrame::record(&draw);
+//!
+//! // Serialize the frame and replay it into another **Draw**.
+//! let json = serde_json::to_string(&frame).unwrap();
+//! let frame: RecordedFrame = serde_json::from_str(&json).unwrap();
+//! let replay = Draw::new();
+//! frame.replay(&replay);
+//! assert_eq!(RecordedFrame::record(&replay), frame);
+//! ```
+//!
+//! **Text** primitives depend upon the renderer's glyph cache and are not recorded. Textures,
+//! including normal maps, are retained by a recording but are not serialized, so a deserialized
+//! textured mesh samples the renderer's default texture unless a texture is assigned to its
+//! `texture` field.
+
+use crate::color::{LinSrgb, LinSrgba};
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
+use crate::draw::renderer::tessellate::{self, Command};
+use crate::draw::renderer::{SdfParams, VertexMode};
+use crate::draw::{self, Draw, DrawCommand, Scissor};
+use crate::geom::{self, Vec2, Vec3};
+use crate::wgpu;
+use serde_derive::{Deserialize, Serialize};
+
+/// A serializable recording of the commands submitted to a **Draw** over any number of frames.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    /// The recorded frames in the order in which they were recorded.
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// The commands submitted to a **Draw** during a single frame.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// The background color, if one was specified.
+    pub background: Option<LinSrgba>,
+    /// The recorded commands in the order in which they were submitted.
+    pub commands: Vec<RecordedCommand>,
+}
+
+/// A single recorded command.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum RecordedCommand {
+    /// A change in the rendering context.
+    ///
+    /// Transforms are applied to the recorded meshes and so are not included. See
+    /// `draw::Context` for the meaning of each field.
+    Context {
+        #[serde(with = "BlendStateDef")]
+        blend: wgpu::BlendState,
+        scissor: RecordedScissor,
+        #[serde(with = "PrimitiveTopologyDef")]
+        topology: wgpu::PrimitiveTopology,
+        #[serde(with = "PolygonModeDef")]
+        polygon_mode: wgpu::PolygonMode,
+        depth_write: bool,
+        #[serde(with = "CompareFunctionDef")]
+        depth_compare: wgpu::CompareFunction,
+        z_index: i32,
+        #[serde(with = "SamplerDescriptorDef")]
+        sampler: wgpu::SamplerDescriptor<'static>,
+        analytic_aa: bool,
+        lit: bool,
+    },
+    /// Consecutive tessellated primitives that share the same vertex mode and texture.
+    Mesh {
+        points: Vec<Vec3>,
+        colors: Vec<LinSrgba>,
+        tex_coords: Vec<Vec2>,
+        indices: Vec<u32>,
+        /// How the vertices are shaded.
+        vertex_mode: VertexMode,
+        /// The parameters of each vertex belonging to a signed distance field, or empty if the
+        /// mesh contains no signed distance fields.
+        sdf_params: Vec<SdfParams>,
+        /// The texture sampled by the mesh, if any. Not serialized.
+        #[serde(skip)]
+        texture: RecordedTexture,
+        /// The material with which the mesh is shaded, if any.
+        material: Option<RecordedMaterial>,
+    },
+}
+
+/// A serializable form of the **draw::Scissor**.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedScissor {
+    /// The extent of the scissor matches the bounds of the target texture.
+    Full,
+    /// Crop the view to the rect with the given position and size.
+    Rect { x: f32, y: f32, w: f32, h: f32 },
+    /// Nothing is drawn.
+    NoOverlap,
+}
+
+/// A texture retained by a recording.
+///
+/// Textures are compared by the ID of their view and are not serialized.
+#[derive(Clone, Debug, Default)]
+pub struct RecordedTexture(pub Option<wgpu::TextureView>);
+
+/// A serializable form of the **Material** of a recorded mesh.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMaterial {
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: LinSrgb,
+    /// The normal map of the material, if any. Not serialized.
+    #[serde(skip)]
+    pub normal_map: RecordedTexture,
+}
+
+// Mirrors of the wgpu types within a recorded context, as wgpu only implements serde traits when
+// tracing is enabled.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendState")]
+struct BlendStateDef {
+    #[serde(with = "BlendComponentDef")]
+    color: wgpu::BlendComponent,
+    #[serde(with = "BlendComponentDef")]
+    alpha: wgpu::BlendComponent,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendComponent")]
+struct BlendComponentDef {
+    #[serde(with = "BlendFactorDef")]
+    src_factor: wgpu::BlendFactor,
+    #[serde(with = "BlendFactorDef")]
+    dst_factor: wgpu::BlendFactor,
+    #[serde(with = "BlendOperationDef")]
+    operation: wgpu::BlendOperation,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendFactor")]
+enum BlendFactorDef {
+    Zero,
+    One,
+    Src,
+    OneMinusSrc,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    Dst,
+    OneMinusDst,
+    DstAlpha,
+    OneMinusDstAlpha,
+    SrcAlphaSaturated,
+    Constant,
+    OneMinusConstant,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendOperation")]
+enum BlendOperationDef {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::PrimitiveTopology")]
+enum PrimitiveTopologyDef {
+    PointList,
+    LineList,
+    LineStrip,
+    TriangleList,
+    TriangleStrip,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::PolygonMode")]
+enum PolygonModeDef {
+    Fill,
+    Line,
+    Point,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::CompareFunction")]
+enum CompareFunctionDef {
+    Never,
+    Less,
+    Equal,
+    LessEqual,
+    Greater,
+    NotEqual,
+    GreaterEqual,
+    Always,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::SamplerDescriptor<'static>")]
+struct SamplerDescriptorDef {
+    #[serde(skip)]
+    label: Option<&'static str>,
+    #[serde(with = "AddressModeDef")]
+    address_mode_u: wgpu::AddressMode,
+    #[serde(with = "AddressModeDef")]
+    address_mode_v: wgpu::AddressMode,
+    #[serde(with = "AddressModeDef")]
+    address_mode_w: wgpu::AddressMode,
+    #[serde(with = "FilterModeDef")]
+    mag_filter: wgpu::FilterMode,
+    #[serde(with = "FilterModeDef")]
+    min_filter: wgpu::FilterMode,
+    #[serde(with = "FilterModeDef")]
+    mipmap_filter: wgpu::FilterMode,
+    lod_min_clamp: f32,
+    lod_max_clamp: f32,
+    #[serde(with = "option_compare_function")]
+    compare: Option<wgpu::CompareFunction>,
+    anisotropy_clamp: u16,
+    #[serde(with = "option_sampler_border_color")]
+    border_color: Option<wgpu::SamplerBorderColor>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::AddressMode")]
+enum AddressModeDef {
+    ClampToEdge,
+    Repeat,
+    MirrorRepeat,
+    ClampToBorder,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::FilterMode")]
+enum FilterModeDef {
+    Nearest,
+    Linear,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::SamplerBorderColor")]
+enum SamplerBorderColorDef {
+    TransparentBlack,
+    OpaqueBlack,
+    OpaqueWhite,
+    Zero,
+}
+
+// Optional fields of mirrored types are (de)serialized via a newtype.
+mod option_compare_function {
+    use super::CompareFunctionDef;
+    use crate::wgpu;
+    use serde::{Deserializer, Serializer};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "CompareFunctionDef")] wgpu::CompareFunction);
+
+    pub fn serialize<S>(value: &Option<wgpu::CompareFunction>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serde::Serialize::serialize(&value.map(Wrapper), s)
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<wgpu::CompareFunction>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Option<Wrapper> as serde::Deserialize>::deserialize(d).map(|opt| opt.map(|Wrapper(f)| f))
+    }
+}
+
+mod option_sampler_border_color {
+    use super::SamplerBorderColorDef;
+    use crate::wgpu;
+    use serde::{Deserializer, Serializer};
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper(#[serde(with = "SamplerBorderColorDef")] wgpu::SamplerBorderColor);
+
+    pub fn serialize<S>(value: &Option<wgpu::SamplerBorderColor>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serde::Serialize::serialize(&value.map(Wrapper), s)
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Option<wgpu::SamplerBorderColor>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <Option<Wrapper> as serde::Deserialize>::deserialize(d).map(|opt| opt.map(|Wrapper(c)| c))
+    }
+}
+
+// The tessellated primitives pending a new mesh command.
+struct PendingMesh {
+    mesh: draw::Mesh,
+    sdf_params: Vec<SdfParams>,
+    vertex_mode: VertexMode,
+    texture: RecordedTexture,
+    material: Option<Material>,
+}
+
+impl Recording {
+    /// Create a new, empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the commands submitted to the given **Draw** as a new frame.
+    ///
+    /// The **Draw** is not drained of its commands, so this may be called before rendering the
+    /// same **Draw** to a frame.
+    pub fn record_frame(&mut self, draw: &Draw) {
+        self.frames.push(RecordedFrame::record(draw));
+    }
+
+    /// Replay the frame at the given index into the given **Draw**.
+    ///
+    /// Returns `false` if there is no frame at the given index.
+    pub fn replay_frame(&self, index: usize, draw: &Draw) -> bool {
+        match self.frames.get(index) {
+            Some(frame) => {
+                frame.replay(draw);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether or not any frames have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl RecordedFrame {
+    /// Record the commands submitted to the given **Draw**.
+    pub fn record(draw: &Draw) -> Self {
+        let skip = |prim: &Primitive| matches!(prim, Primitive::Text(_));
+        let mut commands = vec![];
+        let mut last_context = None;
+        let mut pending: Option<PendingMesh> = None;
+        for cmd in tessellate::tessellate_draw(draw, skip, true, None, None) {
+            match cmd {
+                Command::Draw(DrawCommand::Context(ctxt)) => {
+                    // Transforms are applied to the mesh, so only record changes to the rest of
+                    // the context.
+                    let command = RecordedCommand::from(&ctxt);
+                    if last_context.as_ref() == Some(&command) {
+                        continue;
+                    }
+                    push_mesh(&mut commands, pending.take());
+                    if let Some(RecordedCommand::Context { .. }) = commands.last() {
+                        commands.pop();
+                    }
+                    commands.push(command.clone());
+                    last_context = Some(command);
+                }
+                Command::Draw(_) => (),
+                Command::Tessellated(tessellated) => {
+                    // Primitives with a material are recorded individually.
+                    let vertex_mode = tessellated.render.vertex_mode;
+                    let texture = RecordedTexture(tessellated.render.texture_view.clone());
+                    let continues = pending.as_ref().is_some_and(|p| {
+                        p.material.is_none()
+                            && tessellated.material.is_none()
+                            && p.vertex_mode == vertex_mode
+                            && p.texture == texture
+                    });
+                    if !continues {
+                        push_mesh(&mut commands, pending.take());
+                    }
+                    let p = pending.get_or_insert_with(|| PendingMesh {
+                        mesh: draw::Mesh::default(),
+                        sdf_params: vec![],
+                        vertex_mode,
+                        texture,
+                        material: None,
+                    });
+                    let (material, _render) = tessellated.stitch(&mut p.mesh, &mut p.sdf_params);
+                    p.material = material;
+                }
+            }
+        }
+        push_mesh(&mut commands, pending);
+        let background = draw.state.borrow().background_color;
+        RecordedFrame {
+            background,
+            commands,
+        }
+    }
+
+    /// Submit the recorded commands to the given **Draw**.
+    ///
+    /// The meshes are drawn relative to the current transform of the **Draw**. Recorded layers
+    /// are offset by the `z_index` of the **Draw** and recorded meshes are lit if either the
+    /// recording or the **Draw** is lit.
+    pub fn replay(&self, draw: &Draw) {
+        if let Some(color) = self.background {
+            draw.background().color(color);
+        }
+        let mut ctxt_draw = draw.clone();
+        for command in &self.commands {
+            match *command {
+                RecordedCommand::Context {
+                    blend,
+                    scissor,
+                    topology,
+                    polygon_mode,
+                    depth_write,
+                    depth_compare,
+                    z_index,
+                    ref sampler,
+                    analytic_aa,
+                    lit,
+                } => {
+                    let mut context = draw.context.clone();
+                    context.blend = blend;
+                    context.scissor = scissor.into();
+                    context.topology = topology;
+                    context.polygon_mode = polygon_mode;
+                    context.depth_write = depth_write;
+                    context.depth_compare = depth_compare;
+                    context.z_index = draw.context.z_index + z_index;
+                    context.sampler = sampler.clone();
+                    context.analytic_aa = analytic_aa;
+                    context.lit |= lit;
+                    ctxt_draw = draw.context(context);
+                }
+                RecordedCommand::Mesh {
+                    ref points,
+                    ref colors,
+                    ref tex_coords,
+                    ref indices,
+                    vertex_mode,
+                    ref sdf_params,
+                    ref texture,
+                    ref material,
+                } => {
+                    let vertices = points
+                        .iter()
+                        .zip(colors)
+                        .zip(tex_coords)
+                        .map(|((&p, &c), &t)| ((p, c), t).into());
+                    let indices = indices.iter().map(|&i| i as usize);
+                    ctxt_draw.mesh().tessellated(
+                        vertices,
+                        indices,
+                        vertex_mode,
+                        texture.0.clone(),
+                        sdf_params.clone(),
+                        material.clone().map(Material::from),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a draw::Context> for RecordedCommand {
+    fn from(ctxt: &'a draw::Context) -> Self {
+        RecordedCommand::Context {
+            blend: ctxt.blend,
+            scissor: ctxt.scissor.into(),
+            topology: ctxt.topology,
+            polygon_mode: ctxt.polygon_mode,
+            depth_write: ctxt.depth_write,
+            depth_compare: ctxt.depth_compare,
+            z_index: ctxt.z_index,
+            // Labels are not serialized.
+            sampler: wgpu::SamplerDescriptor {
+                label: None,
+                ..ctxt.sampler.clone()
+            },
+            analytic_aa: ctxt.analytic_aa,
+            lit: ctxt.lit,
+        }
+    }
+}
+
+impl PartialEq for RecordedTexture {
+    fn eq(&self, other: &Self) -> bool {
+        let id = |t: &Self| t.0.as_ref().map(|view| view.id());
+        id(self) == id(other)
+    }
+}
+
+impl From<Material> for RecordedMaterial {
+    fn from(material: Material) -> Self {
+        RecordedMaterial {
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emissive: material.emissive,
+            normal_map: RecordedTexture(material.normal_map),
+        }
+    }
+}
+
+impl From<RecordedMaterial> for Material {
+    fn from(material: RecordedMaterial) -> Self {
+        Material {
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emissive: material.emissive,
+            normal_map: material.normal_map.0,
+        }
+    }
+}
+
+impl From<Scissor> for RecordedScissor {
+    fn from(scissor: Scissor) -> Self {
+        match scissor {
+            Scissor::Full => RecordedScissor::Full,
+            Scissor::Rect(rect) => {
+                let (x, y, w, h) = rect.x_y_w_h();
+                RecordedScissor::Rect { x, y, w, h }
+            }
+            Scissor::NoOverlap => RecordedScissor::NoOverlap,
+        }
+    }
+}
+
+impl From<RecordedScissor> for Scissor {
+    fn from(scissor: RecordedScissor) -> Self {
+        match scissor {
+            RecordedScissor::Full => Scissor::Full,
+            RecordedScissor::Rect { x, y, w, h } => {
+                Scissor::Rect(geom::Rect::from_x_y_w_h(x, y, w, h))
+            }
+            RecordedScissor::NoOverlap => Scissor::NoOverlap,
+        }
+    }
+}
+
+// Take the given pending mesh as a new command, if it contains any triangles.
+fn push_mesh(commands: &mut Vec<RecordedCommand>, pending: Option<PendingMesh>) {
+    let PendingMesh {
+        mesh,
+        mut sdf_params,
+        vertex_mode,
+        texture,
+        material,
+    } = match pending {
+        Some(pending) => pending,
+        None => return,
+    };
+    if mesh.indices().is_empty() {
+        return;
+    }
+    if sdf_params.iter().all(|p| *p == SdfParams::default()) {
+        sdf_params.clear();
+    }
+    commands.push(RecordedCommand::Mesh {
+        points: mesh.points().to_vec(),
+        colors: mesh.colors().to_vec(),
+        tex_coords: mesh.tex_coords().to_vec(),
+        indices: mesh.indices().to_vec(),
+        vertex_mode,
+        sdf_params,
+        texture,
+        material: material.map(RecordedMaterial::from),
+    });
+}
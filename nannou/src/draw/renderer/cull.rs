@@ -0,0 +1,85 @@
+//! Culling of primitives lying outside of the visible area ahead of tessellation.
+//!
+//! See `Draw::cull` for details.
+
+use crate::draw::primitive::Primitive;
+use crate::draw::{self, DrawCommand};
+use crate::geom::{self, Point2};
+use crate::glam::{Mat4, Vec4};
+
+/// The distance in points by which the visible area is extended before testing primitives.
+///
+/// Allows for the hairline strokes and anti-aliased fringes that may extend slightly beyond the
+/// bounds of a primitive.
+pub const MARGIN: f32 = 2.0;
+
+/// Remove the primitives that lie entirely outside of the visible area from the given commands,
+/// for those submitted within a context where culling is enabled.
+///
+/// The given `full_rect` describes the area of the output attachment in points.
+pub(crate) fn cull(cmds: Vec<DrawCommand>, full_rect: geom::Rect) -> Vec<DrawCommand> {
+    let any_cull = cmds
+        .iter()
+        .any(|cmd| matches!(cmd, DrawCommand::Context(ctxt) if ctxt.cull));
+    if !any_cull {
+        return cmds;
+    }
+
+    // The transform and visible area of the current context, if culling is enabled.
+    let mut culling: Option<(Mat4, Option<geom::Rect>)> = None;
+    cmds.into_iter()
+        .filter(|cmd| match cmd {
+            DrawCommand::Context(ctxt) => {
+                culling = if ctxt.cull {
+                    Some((ctxt.transform, visible_rect(ctxt, full_rect)))
+                } else {
+                    None
+                };
+                true
+            }
+            DrawCommand::Primitive(prim) => match culling {
+                Some((ref transform, visible)) => is_visible(prim, transform, visible),
+                None => true,
+            },
+            DrawCommand::Custom(_) => true,
+        })
+        .collect()
+}
+
+// The area within which drawing is visible for the given context, or `None` if nothing is.
+fn visible_rect(ctxt: &draw::Context, full_rect: geom::Rect) -> Option<geom::Rect> {
+    let rect = match ctxt.scissor {
+        draw::Scissor::Full => full_rect,
+        draw::Scissor::Rect(rect) => full_rect.overlap(rect)?,
+        draw::Scissor::NoOverlap => return None,
+    };
+    Some(rect.pad(-MARGIN))
+}
+
+// Whether or not any part of the given primitive may lie within the visible area.
+fn is_visible(prim: &Primitive, transform: &Mat4, visible: Option<geom::Rect>) -> bool {
+    let visible = match visible {
+        Some(rect) => rect,
+        None => return false,
+    };
+    let (rect, local_transform) = match prim.bounds() {
+        Some(bounds) => bounds,
+        None => return true,
+    };
+    let transform = *transform * local_transform;
+    let mut corners = [Point2::ZERO; 4];
+    for (corner, [x, y]) in corners.iter_mut().zip(rect.corners_iter()) {
+        let p = transform * Vec4::new(x, y, 0.0, 1.0);
+        // Leave primitives reaching behind a perspective transform to the rasterizer.
+        if p.w <= 0.0 {
+            return true;
+        }
+        *corner = p.truncate().truncate() / p.w;
+    }
+    let bounds = corners[1..]
+        .iter()
+        .fold(geom::Rect::from_corners(corners[0], corners[0]), |r, &p| {
+            r.stretch_to(p)
+        });
+    bounds.overlap(visible).is_some()
+}
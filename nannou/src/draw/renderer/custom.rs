@@ -0,0 +1,72 @@
+//! Items related to encoding user callbacks among the primitives of a **Draw**.
+//!
+//! See `Draw::custom` for details.
+
+use crate::glam::Mat4;
+use crate::wgpu;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A user callback submitted via `Draw::custom`.
+///
+/// The callback is called with the command encoder in between the render passes encoding the
+/// primitives drawn before and after it.
+#[derive(Clone)]
+pub struct Custom {
+    callback: Rc<RefCell<Callback>>,
+}
+
+type Callback = dyn FnMut(&mut wgpu::CommandEncoder, &ViewResources);
+
+/// The resources associated with the target to which a **Draw** is being rendered.
+///
+/// Provided to `Draw::custom` callbacks so that they may encode their own commands targeting the
+/// same attachments as the **draw::Renderer**.
+#[derive(Debug)]
+pub struct ViewResources<'a> {
+    /// The device with which the renderer was created.
+    pub device: &'a wgpu::Device,
+    /// The texture view to which the **Draw** is being rendered.
+    ///
+    /// This is multisampled if `sample_count` is greater than `1`.
+    pub output_attachment: &'a wgpu::TextureView,
+    /// The texture to which the `output_attachment` is resolved, if any.
+    pub resolve_target: Option<&'a wgpu::TextureView>,
+    /// The depth attachment used by the renderer.
+    pub depth_attachment: &'a wgpu::TextureView,
+    /// The size of the output attachment in pixels.
+    pub output_attachment_size: [u32; 2],
+    /// The texture format of the output attachment.
+    pub output_attachment_format: wgpu::TextureFormat,
+    /// The depth format of the depth attachment.
+    pub depth_format: wgpu::TextureFormat,
+    /// The sample count of the output and depth attachments.
+    pub sample_count: u32,
+    /// The ratio of pixels to points for the output attachment.
+    pub scale_factor: f32,
+    /// The transform of the **Draw** instance that submitted the callback.
+    pub transform: Mat4,
+}
+
+impl Custom {
+    /// Wrap the given function as a custom callback.
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: 'static + FnMut(&mut wgpu::CommandEncoder, &ViewResources),
+    {
+        let callback = Rc::new(RefCell::new(callback));
+        Custom { callback }
+    }
+
+    /// Call the callback.
+    pub fn call(&self, encoder: &mut wgpu::CommandEncoder, view: &ViewResources) {
+        (*self.callback.borrow_mut())(encoder, view)
+    }
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Custom").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,295 @@
+//! Items related to rendering meshes whose vertices live within a GPU storage buffer.
+//!
+//! See `Draw::gpu_mesh` for details.
+
+use crate::draw::renderer::{self, ViewResources};
+use crate::glam::Mat4;
+use crate::wgpu::{self, util::DeviceExt};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// A vertex as laid out within the storage buffer of a **GpuMesh**.
+///
+/// This matches the `GpuVertex` struct declared by `GpuMesh::VERTEX_WGSL`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct GpuVertex {
+    /// The position of the vertex. The `w` component is ignored.
+    pub position: [f32; 4],
+    /// The linear color of the vertex.
+    pub color: [f32; 4],
+}
+
+/// A mesh whose vertices are stored within a GPU storage buffer, e.g. written by a compute pass.
+///
+/// Rather than being tessellated on the CPU, each vertex is pulled from the buffer by the vertex
+/// shader, allowing GPU simulations to be drawn without reading their state back to the CPU.
+/// Vertices are drawn in order according to the mesh's topology, a triangle list by default.
+///
+/// The number of vertices drawn may also be decided on the GPU by enabling indirect drawing via
+/// `GpuMesh::indirect`, e.g. so that a compute pass culling particles can append only the
+/// vertices of those that remain.
+///
+/// Clones of a **GpuMesh** share the same buffers. Note that vertex pulling requires a device
+/// supporting storage buffers within vertex shaders, which excludes WebGL and GL ES 3.0. On such
+/// devices the mesh is not drawn. See `GpuMesh::is_supported`.
+#[derive(Clone, Debug)]
+pub struct GpuMesh {
+    buffer: Arc<wgpu::Buffer>,
+    indirect_buffer: Option<Arc<wgpu::Buffer>>,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    vertex_count: u32,
+    topology: wgpu::PrimitiveTopology,
+    resources: Option<Resources>,
+}
+
+#[derive(Debug)]
+struct Resources {
+    shader: wgpu::ShaderModule,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    topology: wgpu::PrimitiveTopology,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Uniforms {
+    transform: Mat4,
+}
+
+impl GpuMesh {
+    /// A WGSL declaration of the `GpuVertex` struct, for use within shaders writing to the
+    /// buffer.
+    pub const VERTEX_WGSL: &'static str =
+        "struct GpuVertex {\n    position: vec4<f32>,\n    color: vec4<f32>,\n};\n";
+
+    /// A WGSL declaration of the `GpuDrawIndirect` struct, for use within shaders writing to the
+    /// indirect buffer.
+    ///
+    /// The vertex count is atomic so that invocations may append vertices via `atomicAdd`.
+    pub const INDIRECT_WGSL: &'static str = "struct GpuDrawIndirect {
+    vertex_count: atomic<u32>,
+    instance_count: u32,
+    first_vertex: u32,
+    first_instance: u32,
+};
+";
+
+    /// Create a mesh with a zeroed buffer large enough for the given number of vertices.
+    pub fn new(device: &wgpu::Device, vertex_count: u32) -> Self {
+        let size = vertex_count.max(1) as usize * mem::size_of::<GpuVertex>();
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("nannou gpu_mesh vertex_buffer"),
+            size: size as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self::from_buffer(buffer, vertex_count)
+    }
+
+    /// Create a mesh with a buffer initialised with the given vertices.
+    pub fn from_vertices(device: &wgpu::Device, vertices: &[GpuVertex]) -> Self {
+        if vertices.is_empty() {
+            return Self::new(device, 0);
+        }
+        let buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou gpu_mesh vertex_buffer"),
+            contents: unsafe { wgpu::bytes::from_slice(vertices) },
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+        Self::from_buffer(buffer, vertices.len() as u32)
+    }
+
+    /// Create a mesh from the given buffer of **GpuVertex**s.
+    ///
+    /// The buffer must have `BufferUsages::STORAGE` enabled and contain at least `vertex_count`
+    /// vertices.
+    pub fn from_buffer(buffer: wgpu::Buffer, vertex_count: u32) -> Self {
+        let state = State {
+            vertex_count,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            resources: None,
+        };
+        GpuMesh {
+            buffer: Arc::new(buffer),
+            indirect_buffer: None,
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Specify the topology with which the vertices are drawn.
+    pub fn topology(self, topology: wgpu::PrimitiveTopology) -> Self {
+        let mut state = self.state.lock().expect("failed to lock gpu_mesh state");
+        state.topology = topology;
+        drop(state);
+        self
+    }
+
+    /// Draw the mesh with the number of vertices read from an indirect buffer on the GPU.
+    ///
+    /// The indirect buffer is initialised with the current vertex count and may be written by a
+    /// compute pass via the `GpuDrawIndirect` layout of `GpuMesh::INDIRECT_WGSL`. Once enabled,
+    /// `vertex_count` is ignored when drawing. Requires a device supporting indirect execution.
+    pub fn indirect(mut self, device: &wgpu::Device) -> Self {
+        let args = wgpu::util::DrawIndirect {
+            vertex_count: self.vertex_count(),
+            instance_count: 1,
+            base_vertex: 0,
+            base_instance: 0,
+        };
+        let buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou gpu_mesh indirect_buffer"),
+            contents: args.as_bytes(),
+            usage: wgpu::BufferUsages::INDIRECT
+                | wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST,
+        });
+        self.indirect_buffer = Some(Arc::new(buffer));
+        self
+    }
+
+    /// Whether or not the given device supports the storage buffers required to draw a
+    /// **GpuMesh**.
+    pub fn is_supported(device: &wgpu::Device) -> bool {
+        device.limits().max_storage_buffers_per_shader_stage > 0
+    }
+
+    /// The buffer of **GpuVertex**s, e.g. for binding to a compute pass.
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// The indirect buffer from which the vertex count is read, if enabled via `indirect`.
+    pub fn indirect_buffer(&self) -> Option<&wgpu::Buffer> {
+        self.indirect_buffer.as_deref()
+    }
+
+    /// Reset the vertex count within the indirect buffer to zero.
+    ///
+    /// Useful before a compute pass that appends vertices each frame. Does nothing if indirect
+    /// drawing is not enabled.
+    pub fn clear_indirect(&self, queue: &wgpu::Queue) {
+        if let Some(buffer) = self.indirect_buffer() {
+            let vertex_count = 0u32;
+            queue.write_buffer(buffer, 0, &vertex_count.to_ne_bytes());
+        }
+    }
+
+    /// The number of vertices drawn.
+    ///
+    /// When drawing indirectly, this is the number of vertices the mesh was created with rather
+    /// than the number last written by the GPU.
+    pub fn vertex_count(&self) -> u32 {
+        let state = self.state.lock().expect("failed to lock gpu_mesh state");
+        state.vertex_count
+    }
+
+    /// Set the number of vertices drawn, e.g. as the number of live vertices changes.
+    ///
+    /// This must not exceed the number of vertices within the buffer.
+    pub fn set_vertex_count(&self, vertex_count: u32) {
+        let mut state = self.state.lock().expect("failed to lock gpu_mesh state");
+        state.vertex_count = vertex_count;
+    }
+
+    /// Encode a render pass drawing the mesh to the given view.
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder, view: &ViewResources) {
+        let mut state = self.state.lock().expect("failed to lock gpu_mesh state");
+        let State {
+            vertex_count,
+            topology,
+            ref mut resources,
+        } = *state;
+        if vertex_count == 0 && self.indirect_buffer.is_none() {
+            return;
+        }
+        let device = view.device;
+        if !Self::is_supported(device) {
+            return;
+        }
+        let resources = resources.get_or_insert_with(|| Resources::new(device));
+
+        let projection = renderer::projection(view.output_attachment_size, view.scale_factor);
+        let uniforms = Uniforms {
+            transform: projection * view.transform,
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou gpu_mesh uniform_buffer"),
+            contents: unsafe { wgpu::bytes::from(&uniforms) },
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .buffer::<Uniforms>(&uniform_buffer, 0..1)
+            .buffer_bytes(&self.buffer, 0, None)
+            .build(device, &resources.bind_group_layout);
+
+        let key = PipelineKey {
+            format: view.output_attachment_format,
+            sample_count: view.sample_count,
+            topology,
+        };
+        let Resources {
+            ref shader,
+            ref pipeline_layout,
+            ref mut pipelines,
+            ..
+        } = *resources;
+        let pipeline = pipelines.entry(key).or_insert_with(|| {
+            wgpu::RenderPipelineBuilder::from_layout(pipeline_layout, shader)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(shader)
+                .fragment_entry_point("fs_main")
+                .color_format(key.format)
+                .primitive_topology(key.topology)
+                .sample_count(key.sample_count)
+                .build(device)
+        });
+
+        let mut pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(view.output_attachment, |color| {
+                color
+                    .resolve_target(view.resolve_target)
+                    .load_op(wgpu::LoadOp::Load)
+            })
+            .begin(encoder);
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        match self.indirect_buffer {
+            Some(ref indirect) => pass.draw_indirect(indirect, 0),
+            None => pass.draw(0..vertex_count, 0..1),
+        }
+    }
+}
+
+impl Resources {
+    fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/gpu_mesh.wgsl"));
+        let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .uniform_buffer(wgpu::ShaderStages::VERTEX, false)
+            .storage_buffer(wgpu::ShaderStages::VERTEX, false, true)
+            .build(device);
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("nannou gpu_mesh"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        Resources {
+            shader,
+            bind_group_layout,
+            pipeline_layout,
+            pipelines: HashMap::new(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Measuring the time spent by the GPU executing the render passes of a **Renderer**.
+
+use crate::wgpu;
+use std::convert::TryInto;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// The maximum number of measurements awaiting readback at once. Measurements are skipped while
+/// all are in flight.
+const MAX_READBACKS: usize = 4;
+
+// The size of the two resolved timestamps in bytes.
+const TIMESTAMPS_SIZE: wgpu::BufferAddress = 2 * std::mem::size_of::<u64>() as wgpu::BufferAddress;
+
+/// Measures the duration between two timestamps written to a command encoder, reading the result
+/// back asynchronously once the GPU has finished.
+///
+/// Requires a device with `Features::TIMESTAMP_QUERY` enabled.
+pub(crate) struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readbacks: Vec<Readback>,
+    // The number of nanoseconds per timestamp tick.
+    period: f32,
+    latest: Option<Duration>,
+}
+
+// The readback states are mirrored by `bevy_nannou_wgpu`'s `GpuTimer`, which can't share this
+// implementation as bevy uses a different version of wgpu.
+#[derive(Debug)]
+struct Readback {
+    buffer: wgpu::Buffer,
+    state: Arc<Mutex<ReadbackState>>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ReadbackState {
+    Free,
+    // Encoded into a command buffer that is yet to be mapped.
+    Encoded,
+    Mapping,
+    Mapped,
+}
+
+impl GpuTimer {
+    /// Create a timer if the given device supports timestamp queries.
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("nannou Renderer timestamp_query_set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("nannou Renderer timestamp_resolve_buffer"),
+            size: TIMESTAMPS_SIZE,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        Some(GpuTimer {
+            query_set,
+            resolve_buffer,
+            readbacks: vec![],
+            period: queue.get_timestamp_period(),
+            latest: None,
+        })
+    }
+
+    /// The most recently read back measurement.
+    pub fn latest(&self) -> Option<Duration> {
+        self.latest
+    }
+
+    /// Collect any finished measurements and write the starting timestamp.
+    pub fn begin(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
+        self.collect(device);
+        encoder.write_timestamp(&self.query_set, 0);
+    }
+
+    /// Write the ending timestamp and encode a copy of both for readback.
+    pub fn end(&mut self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder) {
+        encoder.write_timestamp(&self.query_set, 1);
+        let free = self
+            .readbacks
+            .iter()
+            .position(|rb| *rb.state.lock().unwrap() == ReadbackState::Free);
+        let ix = match free {
+            Some(ix) => ix,
+            None if self.readbacks.len() < MAX_READBACKS => {
+                let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("nannou Renderer timestamp_readback_buffer"),
+                    size: TIMESTAMPS_SIZE,
+                    usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                self.readbacks.push(Readback {
+                    buffer,
+                    state: Arc::new(Mutex::new(ReadbackState::Free)),
+                });
+                self.readbacks.len() - 1
+            }
+            None => return,
+        };
+        let readback = &self.readbacks[ix];
+        encoder.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(
+            &self.resolve_buffer,
+            0,
+            &readback.buffer,
+            0,
+            TIMESTAMPS_SIZE,
+        );
+        *readback.state.lock().unwrap() = ReadbackState::Encoded;
+    }
+
+    // Read back mapped measurements and request mapping for those encoded since the last frame.
+    //
+    // Command buffers are submitted between frames, so those encoded during the previous frame
+    // may now be mapped.
+    fn collect(&mut self, device: &wgpu::Device) {
+        let mut mapping = false;
+        for readback in &self.readbacks {
+            let mut state = readback.state.lock().unwrap();
+            match *state {
+                ReadbackState::Mapped => {
+                    {
+                        let view = readback.buffer.slice(..).get_mapped_range();
+                        let start = u64::from_ne_bytes(view[0..8].try_into().unwrap());
+                        let end = u64::from_ne_bytes(view[8..16].try_into().unwrap());
+                        let nanos = end.saturating_sub(start) as f64 * self.period as f64;
+                        self.latest = Some(Duration::from_nanos(nanos as u64));
+                    }
+                    readback.buffer.unmap();
+                    *state = ReadbackState::Free;
+                }
+                ReadbackState::Encoded => {
+                    // Release the lock first as the callback may be called immediately on error.
+                    *state = ReadbackState::Mapping;
+                    drop(state);
+                    let state = readback.state.clone();
+                    let slice = readback.buffer.slice(..);
+                    slice.map_async(wgpu::MapMode::Read, move |result| {
+                        *state.lock().unwrap() = match result {
+                            Ok(()) => ReadbackState::Mapped,
+                            Err(_) => ReadbackState::Free,
+                        };
+                    });
+                    mapping = true;
+                }
+                ReadbackState::Mapping => mapping = true,
+                ReadbackState::Free => (),
+            }
+        }
+        if mapping {
+            device.poll(wgpu::Maintain::Poll);
+        }
+    }
+}
+
+impl fmt::Debug for GpuTimer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GpuTimer")
+            .field("readbacks", &self.readbacks)
+            .field("period", &self.period)
+            .field("latest", &self.latest)
+            .finish()
+    }
+}
@@ -0,0 +1,380 @@
+//! Items related to illuminating primitives with the lights of a **Draw**.
+
+use crate::draw::environment::SPECULAR_MIP_LEVELS;
+use crate::draw::light::{LightKind, Lighting, MAX_LIGHTS};
+use crate::draw::properties::material::Material;
+use crate::glam::{Mat4, Vec3};
+use crate::wgpu;
+use std::collections::HashMap;
+
+/// Per-vertex parameters describing how a surface responds to light.
+///
+/// These are uploaded as an additional vertex buffer alongside the **draw::Mesh** channels.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MaterialParams {
+    /// `1` if the vertex is illuminated by the lights of the **Draw**, `0` otherwise.
+    pub lit: u32,
+    /// `1` if the normals of the surface are perturbed by a normal map, `0` otherwise.
+    pub normal_map: u32,
+    /// How metallic the surface is.
+    pub metallic: f32,
+    /// How rough the surface is.
+    pub roughness: f32,
+    /// The color of the light emitted by the surface.
+    pub emissive: [f32; 3],
+}
+
+/// The lights of a **Draw** laid out for the uniform buffer shared by the shaders.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct LightUniforms {
+    /// The ambient color premultiplied by its intensity.
+    ambient: [f32; 4],
+    count: u32,
+    _pad: [u32; 3],
+    lights: [LightUniform; MAX_LIGHTS],
+    /// Rotates directions from world space into the space of the environment map.
+    environment_rotation: [[f32; 4]; 4],
+    /// The intensity of the environment map (`0.0` if there is none), the mip level of the
+    /// roughest reflections and the tangent of half the vertical field of view of the skybox.
+    environment: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct LightUniform {
+    /// The position of a point light with `w` of `1.0`, or the direction of a directional light
+    /// with `w` of `0.0`.
+    position: [f32; 4],
+    /// The color premultiplied by the intensity, along with the range of point lights in `w`
+    /// (`0.0` if the light does not fade).
+    color: [f32; 4],
+    /// The shadow map layer (`-1.0` if the light casts no shadows), the depth bias and the size
+    /// of a shadow map texel if shadows are soft (`0.0` otherwise).
+    shadow: [f32; 4],
+    /// Transforms from world space to the clip space of the light's shadow map.
+    view_proj: [[f32; 4]; 4],
+}
+
+/// The shadow maps of the lights of a **Draw**, along with the pipeline used to render them.
+#[derive(Debug)]
+pub(crate) struct ShadowMaps {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    layer_views: Vec<wgpu::TextureView>,
+    sampler: wgpu::Sampler,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+/// The bind groups for the normal maps of the materials within a frame.
+#[derive(Debug)]
+pub(crate) struct NormalMaps {
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    // Bound for primitives without a normal map, which is never sampled.
+    default_bind_group: wgpu::BindGroup,
+    bind_groups: HashMap<wgpu::TextureViewId, wgpu::BindGroup>,
+}
+
+impl MaterialParams {
+    /// The vertex attributes describing the layout of **MaterialParams** within the vertex buffer.
+    pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 4] =
+        wgpu::vertex_attr_array![6 => Uint32, 7 => Uint32, 8 => Float32x2, 9 => Float32x3];
+
+    /// The parameters for the given draw context and the material of the primitive, if any.
+    ///
+    /// Primitives with a material are always lit.
+    pub fn new(ctxt: &crate::draw::Context, material: Option<&Material>) -> Self {
+        let lit = ctxt.lit || material.is_some();
+        let default_material = Material::default();
+        let material = material.unwrap_or(&default_material);
+        let emissive = material.emissive;
+        MaterialParams {
+            lit: lit as u32,
+            normal_map: material.normal_map.is_some() as u32,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emissive: [emissive.red, emissive.green, emissive.blue],
+        }
+    }
+}
+
+impl LightUniforms {
+    /// Lay out the given lighting.
+    ///
+    /// `bounds` are the minimum and maximum corners of the lit primitives, spanned by the shadow
+    /// map of each shadow casting light. No shadows are cast if `None`.
+    pub fn new(lighting: &Lighting, bounds: Option<(Vec3, Vec3)>) -> Self {
+        let ambient = lighting.ambient_color * lighting.ambient_intensity;
+        let texel = match lighting.soft_shadows {
+            true => 1.0 / lighting.shadow_map_size as f32,
+            false => 0.0,
+        };
+        let mut layers = 0;
+        let mut lights = [LightUniform::default(); MAX_LIGHTS];
+        for (uniform, light) in lights.iter_mut().zip(&lighting.lights) {
+            let (position, range) = match light.kind {
+                LightKind::Point { position, range } => {
+                    (position.extend(1.0), range.unwrap_or(0.0))
+                }
+                LightKind::Directional { direction } => (direction.extend(0.0), 0.0),
+            };
+            let color = light.color * light.intensity;
+            uniform.position = position.into();
+            uniform.color = [color.red, color.green, color.blue, range];
+            if let (true, LightKind::Directional { direction }, Some(bounds)) =
+                (light.shadows, light.kind, bounds)
+            {
+                let (view_proj, depth) = directional_view_proj(direction, bounds);
+                uniform.shadow = [layers as f32, light.shadow_bias / depth, texel, 0.0];
+                uniform.view_proj = view_proj.to_cols_array_2d();
+                layers += 1;
+            }
+        }
+        let (environment_rotation, environment) = match lighting.environment {
+            None => (Mat4::IDENTITY, [0.0; 4]),
+            Some(ref env) => {
+                let rotation = Mat4::from_quat(env.rotation.inverse());
+                let max_lod = (SPECULAR_MIP_LEVELS - 1) as f32;
+                let tan_half_fov = (env.skybox.unwrap_or(0.0) * 0.5).tan();
+                (rotation, [env.intensity, max_lod, tan_half_fov, 0.0])
+            }
+        };
+        LightUniforms {
+            ambient: [ambient.red, ambient.green, ambient.blue, 1.0],
+            count: lighting.lights.len().min(MAX_LIGHTS) as u32,
+            _pad: [0; 3],
+            lights,
+            environment_rotation: environment_rotation.to_cols_array_2d(),
+            environment,
+        }
+    }
+
+    /// The index of each shadow casting light along with the layer of its shadow map.
+    pub fn shadow_casters(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.lights[..self.count as usize]
+            .iter()
+            .enumerate()
+            .filter(|(_, light)| light.shadow[0] >= 0.0)
+            .map(|(i, light)| (i as u32, light.shadow[0] as u32))
+    }
+}
+
+impl ShadowMaps {
+    /// The format of the shadow map texture.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+    /// Create a single, minimal shadow map for rendering the lights within the given buffer.
+    pub fn new(device: &wgpu::Device, light_buffer: &wgpu::Buffer) -> Self {
+        let (texture, view, layer_views) = create_shadow_texture(device, 1, 1);
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("nannou Renderer shadow_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+        let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .uniform_buffer(wgpu::ShaderStages::VERTEX, false)
+            .build(device);
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .buffer::<LightUniforms>(light_buffer, 0..1)
+            .build(device, &bind_group_layout);
+        let vs_desc = wgpu::include_wgsl!("shaders/shadow.wgsl");
+        let vs_mod = device.create_shader_module(vs_desc);
+        let pipeline =
+            wgpu::RenderPipelineBuilder::from_layout_descriptor(&[&bind_group_layout][..], &vs_mod)
+                .add_vertex_buffer::<crate::draw::mesh::vertex::Point>(
+                    &wgpu::vertex_attr_array![0 => Float32x3],
+                )
+                .depth_format(Self::FORMAT)
+                .depth_bias_slope_scale(2.0)
+                .build(device);
+        ShadowMaps {
+            texture,
+            view,
+            layer_views,
+            sampler,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    /// A view of all layers of the shadow map texture.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// The comparison sampler used to sample the shadow maps.
+    pub fn sampler(&self) -> &wgpu::Sampler {
+        &self.sampler
+    }
+
+    /// Ensure there is a shadow map of the given size for each of the given number of layers.
+    ///
+    /// Returns `true` if the shadow map texture was recreated.
+    pub fn resize(&mut self, device: &wgpu::Device, size: u32, layers: u32) -> bool {
+        let layers = layers.max(1);
+        if self.texture.size() == [size, size] && self.layer_views.len() as u32 == layers {
+            return false;
+        }
+        let (texture, view, layer_views) = create_shadow_texture(device, size, layers);
+        self.texture = texture;
+        self.view = view;
+        self.layer_views = layer_views;
+        true
+    }
+
+    /// Encode a render pass for each shadow casting light, rendering the triangles described by
+    /// the given indices into the light's shadow map.
+    pub fn encode(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        lights: &LightUniforms,
+        points: &wgpu::Buffer,
+        indices: &wgpu::Buffer,
+        index_count: u32,
+    ) {
+        for (light, layer) in lights.shadow_casters() {
+            let view = &self.layer_views[layer as usize];
+            let mut render_pass = wgpu::RenderPassBuilder::new()
+                .depth_stencil_attachment(view, |depth| depth)
+                .begin(encoder);
+            render_pass.set_pipeline(&self.pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, points.slice(..));
+            render_pass.set_index_buffer(indices.slice(..), super::Renderer::INDEX_FORMAT);
+            // The instance index selects the light within the vertex shader.
+            render_pass.draw_indexed(0..index_count, 0, light..light + 1);
+        }
+    }
+}
+
+impl NormalMaps {
+    /// Create the bind group layout along with the bind group used for primitives without a
+    /// normal map.
+    pub fn new(device: &wgpu::Device, default_view: &wgpu::TextureView) -> Self {
+        let layout = wgpu::BindGroupLayoutBuilder::new()
+            .sampler(wgpu::ShaderStages::FRAGMENT, true)
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                wgpu::TextureSampleType::Float { filterable: true },
+            )
+            .build(device);
+        let sampler = wgpu::SamplerBuilder::new()
+            .address_mode(wgpu::AddressMode::Repeat)
+            .build(device);
+        let default_bind_group =
+            create_normal_map_bind_group(device, &layout, &sampler, default_view);
+        NormalMaps {
+            layout,
+            sampler,
+            default_bind_group,
+            bind_groups: Default::default(),
+        }
+    }
+
+    /// The layout of the normal map bind groups.
+    pub fn layout(&self) -> &wgpu::BindGroupLayout {
+        &self.layout
+    }
+
+    /// Ensure there is a bind group for each of the given normal maps, but no more.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        views: &HashMap<wgpu::TextureViewId, wgpu::TextureView>,
+    ) {
+        self.bind_groups.retain(|id, _| views.contains_key(id));
+        for (id, view) in views {
+            if !self.bind_groups.contains_key(id) {
+                let bind_group =
+                    create_normal_map_bind_group(device, &self.layout, &self.sampler, view);
+                self.bind_groups.insert(*id, bind_group);
+            }
+        }
+    }
+
+    /// The bind group for the normal map with the given ID, or the default bind group if `None`.
+    pub fn bind_group(&self, id: Option<wgpu::TextureViewId>) -> &wgpu::BindGroup {
+        id.and_then(|id| self.bind_groups.get(&id))
+            .unwrap_or(&self.default_bind_group)
+    }
+}
+
+impl Default for LightUniforms {
+    fn default() -> Self {
+        LightUniforms::new(&Lighting::default(), None)
+    }
+}
+
+impl Default for LightUniform {
+    fn default() -> Self {
+        LightUniform {
+            position: [0.0; 4],
+            color: [0.0; 4],
+            shadow: [-1.0, 0.0, 0.0, 0.0],
+            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+        }
+    }
+}
+
+// The view projection of a directional light's shadow map spanning the given bounds, along with
+// the depth of the projection.
+fn directional_view_proj(direction: Vec3, (min, max): (Vec3, Vec3)) -> (Mat4, f32) {
+    let dir = direction.normalize_or_zero();
+    let dir = if dir == Vec3::ZERO { -Vec3::Z } else { dir };
+    let center = (min + max) * 0.5;
+    let radius = ((max - min).length() * 0.5).max(1.0);
+    let eye = center - dir * radius;
+    let up = if dir.y.abs() > 0.99 { Vec3::Z } else { Vec3::Y };
+    let view = Mat4::look_at_rh(eye, center, up);
+    let depth = radius * 2.0;
+    let proj = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, depth);
+    (proj * view, depth)
+}
+
+fn create_shadow_texture(
+    device: &wgpu::Device,
+    size: u32,
+    layers: u32,
+) -> (wgpu::Texture, wgpu::TextureView, Vec<wgpu::TextureView>) {
+    let texture = wgpu::TextureBuilder::new()
+        .size([size, size])
+        .depth(layers)
+        .dimension(wgpu::TextureDimension::D2)
+        .format(ShadowMaps::FORMAT)
+        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+        .build(device);
+    let view = texture
+        .view()
+        .dimension(wgpu::TextureViewDimension::D2Array)
+        .array_layer_count(None)
+        .build();
+    let layer_views = (0..layers)
+        .map(|layer| {
+            texture
+                .view()
+                .dimension(wgpu::TextureViewDimension::D2)
+                .layer(layer)
+                .build()
+        })
+        .collect();
+    (texture, view, layer_views)
+}
+
+fn create_normal_map_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    sampler: &wgpu::Sampler,
+    view: &wgpu::TextureView,
+) -> wgpu::BindGroup {
+    wgpu::BindGroupBuilder::new()
+        .sampler(sampler)
+        .texture_view(view)
+        .build(device, layout)
+}
@@ -1,19 +1,42 @@
 use crate::draw;
 use crate::draw::mesh::vertex::{Color, TexCoords};
+use crate::draw::properties::stroke::HAIRLINE;
 use crate::frame::Frame;
 use crate::geom::{self, Point2, Rect};
 use crate::glam::{Mat4, Vec2, Vec3};
 use crate::math::map_range;
+use crate::profile::{self, DrawStage};
 use crate::text;
 use crate::wgpu;
+use instant::Instant;
 use lyon::path::PathEvent;
-use lyon::tessellation::{FillTessellator, StrokeTessellator};
+use lyon::tessellation::{FillTessellator, StrokeOptions, StrokeTessellator};
+use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::time::Duration;
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
+pub use self::custom::{Custom, ViewResources};
+pub use self::gpu_mesh::{GpuMesh, GpuVertex};
+use self::gpu_timer::GpuTimer;
+pub use self::lighting::MaterialParams;
+use self::lighting::{LightUniforms, NormalMaps, ShadowMaps};
+pub use self::sdf::SdfParams;
+use self::tessellate::Command;
+
+pub mod cull;
+pub mod custom;
+pub mod gpu_mesh;
+mod gpu_timer;
+pub mod lighting;
+pub mod sdf;
+pub mod tessellate;
+
 /// Draw API primitives that may be rendered via the **Renderer** type.
 pub trait RenderPrimitive {
     /// Render self into the given mesh.
@@ -44,8 +67,16 @@ pub struct RenderContext<'a> {
     pub glyph_cache: &'a mut GlyphCache,
     pub fill_tessellator: &'a mut FillTessellator,
     pub stroke_tessellator: &'a mut StrokeTessellator,
+    pub sdf_params_buffer: &'a mut Vec<SdfParams>,
     pub output_attachment_size: Vec2, // logical coords
     pub output_attachment_scale_factor: f32,
+    pub analytic_aa: bool,
+    /// Whether or not primitives may be rendered as signed distance fields. When `false`, they
+    /// are tessellated from their paths instead, e.g. for CPU output and picking.
+    pub sdf: bool,
+    /// When `Some`, path-based primitives push their source paths here rather than tessellating
+    /// them into the mesh. See the `draw::vector` module.
+    pub vector_paths: Option<&'a mut Vec<draw::vector::VectorPath>>,
 }
 
 pub struct GlyphCache {
@@ -55,10 +86,12 @@ pub struct GlyphCache {
     pub pixel_buffer: Vec<u8>,
     /// Will be set to `true` after the cache has been updated if the texture requires re-uploading.
     pub requires_upload: bool,
+    /// The atlas of color glyphs, e.g. emoji, which are not supported by the rusttype cache.
+    pub color_glyphs: text::color_glyph::Atlas,
 }
 
 /// A top-level indicator of whether or not
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[repr(u32)]
 pub enum VertexMode {
     /// Use the color values and ignore the texture coordinates.
@@ -68,7 +101,35 @@ pub enum VertexMode {
     /// A special mode used by the text primitive.
     ///
     /// Uses the color values, but multiplies the alpha by the glyph cache texture's red value.
+    /// Texture coordinates mirrored into the negative range refer to the color glyph atlas, in
+    /// which case the atlas color is used and only the alpha of the color values is applied.
     Text = 2,
+    /// Renders an ellipse as a signed distance field with analytic anti-aliasing.
+    ///
+    /// Texture coordinates are interpreted as the local position within the shape.
+    SdfEllipse = 3,
+    /// Renders a box with optionally rounded corners as a signed distance field with analytic
+    /// anti-aliasing.
+    ///
+    /// Texture coordinates are interpreted as the local position within the shape.
+    SdfBox = 4,
+    /// Uses the color values, anti-aliasing the edges of strokes analytically.
+    ///
+    /// Texture coordinates are interpreted as the signed distance from the centre of the stroke
+    /// and the half width of the stroke respectively. Vertices with a half width of `0.0` (e.g.
+    /// fills) are drawn as though in `Color` mode.
+    ColorAa = 5,
+    /// Multiplies the texture color by the color values.
+    TextureTinted = 6,
+    /// Colors the surface by its normal. See `draw::DebugView::Normals`.
+    DebugNormals = 7,
+    /// Colors the vertices by their texture coordinates. See `draw::DebugView::TexCoords`.
+    DebugTexCoords = 8,
+    /// Colors the surface by its depth. See `draw::DebugView::Depth`.
+    DebugDepth = 9,
+    /// Samples the environment map in the direction described by the texture coordinates. See
+    /// `draw::light::ImageBasedLight::skybox`.
+    Skybox = 10,
 }
 
 /// A helper type aimed at simplifying the rendering of nannou primitives via wgpu.
@@ -77,9 +138,11 @@ pub struct Renderer {
     glyph_cache: GlyphCache,
     vs_mod: wgpu::ShaderModule,
     fs_mod: wgpu::ShaderModule,
-    // One pipeline per unique Pipeline ID (combination of blend, topology and component type).
+    // One pipeline per unique Pipeline ID (combination of blend, topology, polygon mode and
+    // component type).
     pipelines: HashMap<PipelineId, wgpu::RenderPipeline>,
     glyph_cache_texture: wgpu::Texture,
+    color_glyphs_texture: wgpu::Texture,
     depth_texture: wgpu::Texture,
     depth_texture_view: wgpu::TextureView,
     _default_texture: wgpu::Texture,
@@ -97,7 +160,29 @@ pub struct Renderer {
     render_commands: Vec<RenderCommand>,
     mesh: draw::Mesh,
     vertex_mode_buffer: Vec<VertexMode>,
+    sdf_params_buffer: Vec<SdfParams>,
+    material_params_buffer: Vec<MaterialParams>,
     uniform_buffer: wgpu::Buffer,
+    light_uniforms: LightUniforms,
+    light_buffer: wgpu::Buffer,
+    shadow_maps: ShadowMaps,
+    shadow_map_size: u32,
+    // The indices of the lit triangles that cast shadows.
+    shadow_indices: Vec<u32>,
+    // The minimum and maximum corners of the lit triangles.
+    shadow_bounds: Option<(Vec3, Vec3)>,
+    normal_maps: NormalMaps,
+    // The environment map bound within the uniform bind group.
+    environment_map: draw::EnvironmentMap,
+    // Bound in place of an environment map when the **Draw** has none.
+    black_environment_map: draw::EnvironmentMap,
+    // The drawing accumulated via `Draw::persist`, if any.
+    persistent: Option<Persistent>,
+    environment_sampler: wgpu::Sampler,
+    // Statistics describing the most recent render.
+    stats: Stats,
+    // Measures the duration of the render passes on the GPU, if supported by the device.
+    gpu_timer: Option<GpuTimer>,
 }
 
 /// A type aimed at simplifying construction of a `draw::Renderer`.
@@ -116,6 +201,8 @@ enum RenderCommand {
     SetPipeline(PipelineId),
     /// Change bind group for a new image.
     SetBindGroup(BindGroupId),
+    /// Change bind group for a new normal map, or the default if `None`.
+    SetNormalMap(Option<wgpu::TextureViewId>),
     /// Set the rectangular scissor.
     SetScissor(Scissor),
     /// Draw the given vertex range.
@@ -123,6 +210,41 @@ enum RenderCommand {
         start_vertex: i32,
         index_range: std::ops::Range<u32>,
     },
+    /// End the current render pass, call the user's callback and begin a new render pass.
+    Custom { custom: Custom, transform: Mat4 },
+    /// Enter the profiling span of the named **Draw** scope, if any. Only submitted when
+    /// profiling is enabled.
+    Scope(Option<Arc<str>>),
+}
+
+/// Statistics describing the most recent render of a **Renderer**, e.g. for profiling heavy
+/// sketches.
+///
+/// See `Renderer::stats` and `App::show_stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// The CPU time spent tessellating primitives and generating render commands.
+    pub tessellation: Duration,
+    /// The CPU time spent uploading vertex data and encoding the render passes.
+    pub encoding: Duration,
+    /// The time spent by the GPU executing the render passes.
+    ///
+    /// This is only measured when rendering to a **Frame** with a device that has
+    /// `Features::TIMESTAMP_QUERY` enabled. Measurements are read back asynchronously, so this
+    /// typically describes a frame rendered a few frames earlier.
+    pub gpu: Option<Duration>,
+    /// The number of vertices submitted.
+    pub vertices: usize,
+    /// The number of indices submitted.
+    pub indices: usize,
+    /// The number of indexed draw calls encoded.
+    pub draw_calls: usize,
+    /// The number of times the render pipeline was switched.
+    pub pipeline_switches: usize,
+    /// The number of times a texture bind group was bound.
+    pub texture_binds: usize,
+    /// The number of custom callbacks called, e.g. via `Draw::custom`.
+    pub custom_callbacks: usize,
 }
 
 /// The position and dimensions of the scissor.
@@ -134,6 +256,17 @@ pub struct Scissor {
     height: u32,
 }
 
+/// The drawing accumulated between frames via `Draw::persist`.
+#[derive(Debug)]
+struct Persistent {
+    // Renders the persistent **Draw** into the texture.
+    renderer: Box<Renderer>,
+    // The multisampled texture into which drawing accumulates, if the renderer is multisampled.
+    msaa_texture: Option<(wgpu::Texture, wgpu::TextureView)>,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
 #[derive(Debug)]
 pub struct DrawError;
 
@@ -162,6 +295,9 @@ struct PipelineId {
     color_id: ColorId,
     alpha_id: AlphaId,
     topology: wgpu::PrimitiveTopology,
+    polygon_mode: wgpu::PolygonMode,
+    depth_write: bool,
+    depth_compare: wgpu::CompareFunction,
     texture_sample_type: wgpu::TextureSampleType,
 }
 
@@ -175,27 +311,81 @@ impl RenderPrimitive for draw::Primitive {
     fn render_primitive(self, ctxt: RenderContext, mesh: &mut draw::Mesh) -> PrimitiveRender {
         match self {
             draw::Primitive::Arrow(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Brush(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::CustomPrimitive(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Mesh(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Path(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Polygon(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Tri(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Ellipse(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Hexagon(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Quad(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Rect(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Line(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Text(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Texture(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::TurtlePath(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Waveform(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Spectrogram(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::VectorField(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::Contours(prim) => prim.render_primitive(ctxt, mesh),
             _ => PrimitiveRender::default(),
         }
     }
 }
 
+impl<'a> RenderContext<'a> {
+    /// Prepare the given stroke options for tessellation with the given transform.
+    ///
+    /// When analytic anti-aliasing is enabled, the stroke is widened by roughly one physical pixel
+    /// to make room for the anti-aliased fringe that is faded out within the fragment shader.
+    ///
+    /// A **HAIRLINE** weight is resolved to exactly one physical pixel via `stroke_weight`.
+    pub fn stroke_options(&self, mut opts: StrokeOptions, transform: &Mat4) -> StrokeOptions {
+        opts.line_width = self.stroke_weight(opts.line_width, transform);
+        if self.analytic_aa {
+            let px_scale = self.physical_pixel_scale(transform);
+            if px_scale > 0.0 {
+                opts.line_width += 1.0 / px_scale;
+            }
+        }
+        opts
+    }
+
+    /// Resolve the given stroke weight for the given transform.
+    ///
+    /// Weights are specified in points and so are scaled by the scale factor of the output
+    /// attachment along with the transform. The **HAIRLINE** weight is the exception, resolving to
+    /// the weight that produces exactly one physical pixel regardless of scale factor and zoom.
+    pub fn stroke_weight(&self, weight: f32, transform: &Mat4) -> f32 {
+        if weight != HAIRLINE {
+            return weight;
+        }
+        let px_scale = self.physical_pixel_scale(transform);
+        if px_scale > 0.0 {
+            1.0 / px_scale
+        } else {
+            weight
+        }
+    }
+
+    // The number of physical pixels per unit along the least-scaled axis of the transform.
+    fn physical_pixel_scale(&self, transform: &Mat4) -> f32 {
+        let scale = transform
+            .transform_vector3(Vec3::X)
+            .length()
+            .min(transform.transform_vector3(Vec3::Y).length());
+        scale * self.output_attachment_scale_factor
+    }
+}
+
 impl fmt::Debug for GlyphCache {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("GlyphCache")
             .field("cache", &self.cache.dimensions())
             .field("pixel_buffer", &self.pixel_buffer.len())
             .field("requires_upload", &self.requires_upload)
+            .field("color_glyphs", &self.color_glyphs.dimensions())
             .finish()
     }
 }
@@ -225,6 +415,17 @@ impl PrimitiveRender {
     }
 }
 
+impl VertexMode {
+    /// The vertex mode used to visualise the given debug view.
+    pub fn debug(view: draw::DebugView) -> Self {
+        match view {
+            draw::DebugView::Normals => VertexMode::DebugNormals,
+            draw::DebugView::TexCoords => VertexMode::DebugTexCoords,
+            draw::DebugView::Depth => VertexMode::DebugDepth,
+        }
+    }
+}
+
 impl Builder {
     /// The default depth format
     pub const DEFAULT_DEPTH_FORMAT: wgpu::TextureFormat = Renderer::DEFAULT_DEPTH_FORMAT;
@@ -324,7 +525,7 @@ impl Builder {
 }
 
 impl GlyphCache {
-    fn new(size: [u32; 2], scale_tolerance: f32, position_tolerance: f32) -> Self {
+    pub(crate) fn new(size: [u32; 2], scale_tolerance: f32, position_tolerance: f32) -> Self {
         let [w, h] = size;
         let cache = text::GlyphCache::builder()
             .dimensions(w, h)
@@ -334,10 +535,12 @@ impl GlyphCache {
             .into();
         let pixel_buffer = vec![0u8; w as usize * h as usize];
         let requires_upload = false;
+        let color_glyphs = text::color_glyph::Atlas::new(size);
         GlyphCache {
             cache,
             pixel_buffer,
             requires_upload,
+            color_glyphs,
         }
     }
 }
@@ -353,6 +556,9 @@ impl Renderer {
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 = 0.1;
     /// The texture format of the inner glyph cache.
     pub const GLYPH_CACHE_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+    /// The texture format of the color glyph atlas.
+    pub const COLOR_GLYPHS_TEXTURE_FORMAT: wgpu::TextureFormat =
+        wgpu::TextureFormat::Rgba8UnormSrgb;
     /// The index format used to index into vertices.
     pub const INDEX_FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
 
@@ -401,6 +607,13 @@ impl Renderer {
             .build(device);
         let glyph_cache_texture_view =
             glyph_cache_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let color_glyphs_texture = wgpu::TextureBuilder::new()
+            .size(glyph_cache_size)
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+            .format(Self::COLOR_GLYPHS_TEXTURE_FORMAT)
+            .build(device);
+        let color_glyphs_texture_view =
+            color_glyphs_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         // Create the depth texture.
         let depth_texture =
@@ -424,10 +637,35 @@ impl Renderer {
             usage,
         });
 
+        // The lights are uploaded on each draw.
+        let light_uniforms = LightUniforms::default();
+        let light_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou Renderer light_buffer"),
+            contents: light_uniforms_as_bytes(&light_uniforms),
+            usage,
+        });
+
+        // The shadow maps are resized as necessary on each draw.
+        let shadow_maps = ShadowMaps::new(device, &light_buffer);
+
+        // The environment map is replaced as necessary on each draw.
+        let black_environment_map = draw::EnvironmentMap::black(device);
+        let environment_map = black_environment_map.clone();
+        let environment_sampler = wgpu::SamplerBuilder::new()
+            .mipmap_filter(wgpu::FilterMode::Linear)
+            .build(device);
+
         // Bind group for uniforms.
         let uniform_bind_group_layout = create_uniform_bind_group_layout(device);
-        let uniform_bind_group =
-            create_uniform_bind_group(device, &uniform_bind_group_layout, &uniform_buffer);
+        let uniform_bind_group = create_uniform_bind_group(
+            device,
+            &uniform_bind_group_layout,
+            &uniform_buffer,
+            &light_buffer,
+            &shadow_maps,
+            &environment_map,
+            &environment_sampler,
+        );
 
         // Bind group for text.
         let text_bind_group_layout = create_text_bind_group_layout(device, text_sampler_filtering);
@@ -436,6 +674,7 @@ impl Renderer {
             &text_bind_group_layout,
             &text_sampler,
             &glyph_cache_texture_view,
+            &color_glyphs_texture_view,
         );
 
         // Initialise the sampler set with the default sampler.
@@ -443,6 +682,9 @@ impl Renderer {
         let sampler_id = sampler_descriptor_hash(&sampler_desc);
         let texture_sampler = device.create_sampler(&sampler_desc);
 
+        // Bind group per normal map.
+        let normal_maps = NormalMaps::new(device, &default_texture_view);
+
         // Bind group per user-uploaded texture.
         let texture_bind_group_layouts = Default::default();
         let texture_bind_groups = Default::default();
@@ -454,12 +696,15 @@ impl Renderer {
         let render_commands = vec![];
         let mesh = Default::default();
         let vertex_mode_buffer = vec![];
+        let sdf_params_buffer = vec![];
+        let material_params_buffer = vec![];
 
         Self {
             vs_mod,
             fs_mod,
             glyph_cache,
             glyph_cache_texture,
+            color_glyphs_texture,
             depth_texture,
             depth_texture_view,
             _default_texture: default_texture,
@@ -478,7 +723,22 @@ impl Renderer {
             render_commands,
             mesh,
             vertex_mode_buffer,
+            sdf_params_buffer,
+            material_params_buffer,
             uniform_buffer,
+            light_uniforms,
+            light_buffer,
+            shadow_maps,
+            shadow_map_size: draw::light::DEFAULT_SHADOW_MAP_SIZE,
+            shadow_indices: vec![],
+            shadow_bounds: None,
+            normal_maps,
+            environment_map,
+            black_environment_map,
+            environment_sampler,
+            persistent: None,
+            stats: Stats::default(),
+            gpu_timer: None,
         }
     }
 
@@ -487,6 +747,10 @@ impl Renderer {
         self.render_commands.clear();
         self.mesh.clear();
         self.vertex_mode_buffer.clear();
+        self.sdf_params_buffer.clear();
+        self.material_params_buffer.clear();
+        self.shadow_indices.clear();
+        self.shadow_bounds = None;
     }
 
     /// Generate a list of `RenderCommand`s from the given **Draw** instance and prepare any
@@ -541,159 +805,311 @@ impl Renderer {
         let mut fill_tessellator = FillTessellator::new();
         let mut stroke_tessellator = StrokeTessellator::new();
 
-        // Keep track of context changes.
-        let mut curr_ctxt = draw::Context::default();
         let mut new_pipeline_ids = HashMap::new();
         let mut curr_start_index = 0;
         let mut new_tex_views = HashMap::new();
         let mut new_tex_sampler_combos = HashMap::new();
+        let mut new_samplers = HashMap::new();
+        let mut new_normal_maps = HashMap::new();
         // Track whether new commands are required.
         let mut curr_pipeline_id = None;
         let mut curr_scissor = None;
         let mut curr_tex_sampler_id = None;
-
-        // Collect all draw commands to avoid borrow errors.
-        let draw_cmds: Vec<_> = draw.drain_commands().collect();
-        let draw_state = draw.state.borrow_mut();
-        let intermediary_state = draw_state.intermediary_state.borrow();
-        for cmd in draw_cmds {
-            match cmd {
-                draw::DrawCommand::Context(ctxt) => curr_ctxt = ctxt,
-                draw::DrawCommand::Primitive(prim) => {
-                    // Track the prev index and vertex counts.
-                    let prev_index_count = self.mesh.indices().len() as u32;
-                    let prev_vert_count = self.mesh.vertex_count();
-
-                    // Info required during rendering.
-                    let ctxt = RenderContext {
-                        intermediary_mesh: &intermediary_state.intermediary_mesh,
-                        path_event_buffer: &intermediary_state.path_event_buffer,
-                        path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
-                        path_points_textured_buffer: &intermediary_state
-                            .path_points_textured_buffer,
-                        text_buffer: &intermediary_state.text_buffer,
-                        theme: &draw_state.theme,
-                        transform: &curr_ctxt.transform,
-                        fill_tessellator: &mut fill_tessellator,
-                        stroke_tessellator: &mut stroke_tessellator,
-                        glyph_cache: &mut self.glyph_cache,
-                        output_attachment_size: Vec2::new(px_to_pt(w_px), px_to_pt(h_px)),
-                        output_attachment_scale_factor: scale_factor,
-                    };
-
-                    // Render the primitive.
-                    let render = prim.render_primitive(ctxt, &mut self.mesh);
-
-                    // If the mesh indices are unchanged, there's nothing to be drawn.
-                    if prev_index_count == self.mesh.indices().len() as u32 {
-                        assert_eq!(
-                            prev_vert_count,
-                            self.mesh.vertex_count(),
-                            "vertices were submitted during `render` without submitting indices",
-                        );
+        let mut curr_normal_map_id = None;
+
+        // The background fill is drawn as a pre-pass beneath all other primitives, followed by the
+        // drawing accumulated via `Draw::persist`.
+        let background = draw::background::fill_draw(draw, full_rect);
+        let persistent = self.persistent.as_ref().map(|persistent| {
+            let layer = draw::Draw::new();
+            layer
+                .no_depth_test()
+                .color_blend(wgpu::blend::PREMULTIPLIED)
+                .texture(&persistent.view)
+                .wh(full_rect.wh());
+            layer
+        });
+        for draw in background.iter().chain(&persistent).chain(Some(draw)) {
+            // Keep track of context changes, along with the hashes of its blend and sampler.
+            let mut curr_ctxt = draw::Context::default();
+            let mut curr_ctxt_ids = context_ids(&curr_ctxt);
+            // The span of the current named scope, if any.
+            let mut scope_span = None;
+
+            // Collect all draw commands to avoid borrow errors.
+            let draw_cmds = draw::sort_by_z_index(draw.drain_commands().collect());
+            let draw_cmds = cull::cull(draw_cmds, full_rect);
+            let draw_state = draw.state.borrow_mut();
+            let intermediary_state = draw_state.intermediary_state.borrow();
+
+            // Tessellate independent primitives ahead of time where possible.
+            let shared = tessellate::Shared {
+                intermediary_state: &intermediary_state,
+                theme: &draw_state.theme,
+                output_attachment_size: Vec2::new(px_to_pt(w_px), px_to_pt(h_px)),
+                output_attachment_scale_factor: scale_factor,
+            };
+            let cmds = tessellate::tessellate(draw_cmds, &shared);
+
+            for cmd in cmds {
+                // Track the prev index and vertex counts.
+                let prev_index_count = self.mesh.indices().len() as u32;
+                let prev_vert_count = self.mesh.vertex_count();
+
+                let (material, render) = match cmd {
+                    Command::Draw(draw::DrawCommand::Context(ctxt)) => {
+                        // Draw the primitives of the previous scope separately when profiling.
+                        if profile::ENABLED && ctxt.scope != curr_ctxt.scope {
+                            // End the previous span before entering the next.
+                            scope_span.take();
+                            push_draw_cmd(
+                                &mut curr_start_index,
+                                self.mesh.indices().len() as u32,
+                                &mut self.render_commands,
+                            );
+                            let cmd = RenderCommand::Scope(ctxt.scope.clone());
+                            self.render_commands.push(cmd);
+                            scope_span = ctxt
+                                .scope
+                                .as_deref()
+                                .map(|name| profile::draw_scope(DrawStage::Tessellate, name));
+                        }
+                        curr_ctxt_ids = context_ids(&ctxt);
+                        curr_ctxt = ctxt;
                         continue;
                     }
-
-                    // Retrieve the current texture view and texture view ID. These are necessary
-                    // for producing the current pipeline and bind group IDs. Also ensure we have
-                    // an entry for them in our map.
-                    let tex_view = match render.texture_view {
-                        Some(tex_view) => tex_view,
-                        None => self.default_texture_view.clone(),
-                    };
-                    let tex_view_id = tex_view.id();
-                    let texture_sample_type = tex_view.sample_type();
-                    new_tex_views.insert(tex_view_id, tex_view);
-
-                    // Determine the new current bind group layout ID, pipeline ID, bind group ID
-                    // and scissor required for drawing this primitive.
-                    let new_pipeline_id = {
-                        let color_id = blend_component_hash(&curr_ctxt.blend.color);
-                        let alpha_id = blend_component_hash(&curr_ctxt.blend.alpha);
-                        let topology = curr_ctxt.topology;
-                        PipelineId {
-                            color_id,
-                            alpha_id,
-                            topology,
-                            texture_sample_type,
-                        }
-                    };
-                    let new_bind_group_id = {
-                        let sampler_id = sampler_descriptor_hash(&curr_ctxt.sampler);
-                        (sampler_id, tex_view_id)
-                    };
-                    let new_scissor = curr_ctxt.scissor;
-
-                    // Determine which have changed and in turn which require submitting new
-                    // commands.
-                    let pipeline_changed = Some(new_pipeline_id) != curr_pipeline_id;
-                    let bind_group_changed = Some(new_bind_group_id) != curr_tex_sampler_id;
-                    let scissor_changed = Some(new_scissor) != curr_scissor;
-
-                    // If we require submitting a scissor, pipeline or bind group command, first
-                    // draw whatever pending vertices we have collected so far. If there have been
-                    // no graphics yet, this will do nothing.
-                    if scissor_changed || pipeline_changed || bind_group_changed {
+                    Command::Draw(draw::DrawCommand::Custom(custom)) => {
+                        // Draw everything submitted so far before the callback.
                         push_draw_cmd(
                             &mut curr_start_index,
-                            prev_index_count,
+                            self.mesh.indices().len() as u32,
                             &mut self.render_commands,
                         );
+                        let transform = curr_ctxt.transform;
+                        let cmd = RenderCommand::Custom { custom, transform };
+                        self.render_commands.push(cmd);
+                        // The following render pass must begin by setting the state again.
+                        curr_pipeline_id = None;
+                        curr_scissor = None;
+                        curr_tex_sampler_id = None;
+                        curr_normal_map_id = None;
+                        continue;
                     }
+                    Command::Draw(draw::DrawCommand::Primitive(mut prim)) => {
+                        // Info required during rendering.
+                        let ctxt = RenderContext {
+                            intermediary_mesh: &intermediary_state.intermediary_mesh,
+                            path_event_buffer: &intermediary_state.path_event_buffer,
+                            path_points_colored_buffer: &intermediary_state
+                                .path_points_colored_buffer,
+                            path_points_textured_buffer: &intermediary_state
+                                .path_points_textured_buffer,
+                            text_buffer: &intermediary_state.text_buffer,
+                            theme: &draw_state.theme,
+                            transform: &curr_ctxt.transform,
+                            fill_tessellator: &mut fill_tessellator,
+                            stroke_tessellator: &mut stroke_tessellator,
+                            sdf_params_buffer: &mut self.sdf_params_buffer,
+                            glyph_cache: &mut self.glyph_cache,
+                            output_attachment_size: Vec2::new(px_to_pt(w_px), px_to_pt(h_px)),
+                            output_attachment_scale_factor: scale_factor,
+                            analytic_aa: curr_ctxt.analytic_aa,
+                            sdf: true,
+                            vector_paths: None,
+                        };
 
-                    // If necessary, push a new pipeline command.
-                    if pipeline_changed {
-                        curr_pipeline_id = Some(new_pipeline_id);
-                        let color_blend = curr_ctxt.blend.color.clone();
-                        let alpha_blend = curr_ctxt.blend.alpha.clone();
-                        let sampler_filtering = wgpu::sampler_filtering(&curr_ctxt.sampler);
-                        new_pipeline_ids.insert(
-                            new_pipeline_id,
-                            (color_blend, alpha_blend, sampler_filtering),
-                        );
-                        let cmd = RenderCommand::SetPipeline(new_pipeline_id);
-                        self.render_commands.push(cmd);
+                        // Render the primitive.
+                        let material = prim.take_material();
+                        let render = prim.render_primitive(ctxt, &mut self.mesh);
+                        (material, render)
+                    }
+                    Command::Tessellated(tessellated) => {
+                        tessellated.stitch(&mut self.mesh, &mut self.sdf_params_buffer)
                     }
+                };
 
-                    // If necessary, push a new bind group command.
-                    if bind_group_changed {
-                        curr_tex_sampler_id = Some(new_bind_group_id);
-                        new_tex_sampler_combos.insert(new_bind_group_id, new_pipeline_id);
-                        let cmd = RenderCommand::SetBindGroup(new_bind_group_id);
-                        self.render_commands.push(cmd);
+                // If the mesh indices are unchanged, there's nothing to be drawn.
+                if prev_index_count == self.mesh.indices().len() as u32 {
+                    assert_eq!(
+                        prev_vert_count,
+                        self.mesh.vertex_count(),
+                        "vertices were submitted during `render` without submitting indices",
+                    );
+                    continue;
+                }
+
+                // Retrieve the current texture view and texture view ID. These are necessary
+                // for producing the current pipeline and bind group IDs. Also ensure we have
+                // an entry for them in our map.
+                let tex_view = match render.texture_view {
+                    Some(tex_view) => tex_view,
+                    None => self.default_texture_view.clone(),
+                };
+                let tex_view_id = tex_view.id();
+                let texture_sample_type = tex_view.sample_type();
+                new_tex_views.insert(tex_view_id, tex_view);
+
+                // Determine the new current bind group layout ID, pipeline ID, bind group ID
+                // and scissor required for drawing this primitive.
+                let new_pipeline_id = {
+                    let (color_id, alpha_id, _) = curr_ctxt_ids;
+                    let topology = curr_ctxt.topology;
+                    // The polygon mode only affects the rasterization of triangles.
+                    let polygon_mode = match topology {
+                        wgpu::PrimitiveTopology::TriangleList
+                        | wgpu::PrimitiveTopology::TriangleStrip => curr_ctxt.polygon_mode,
+                        _ => wgpu::PolygonMode::Fill,
+                    };
+                    PipelineId {
+                        color_id,
+                        alpha_id,
+                        topology,
+                        polygon_mode,
+                        depth_write: curr_ctxt.depth_write,
+                        depth_compare: curr_ctxt.depth_compare,
+                        texture_sample_type,
                     }
+                };
+                let new_bind_group_id = {
+                    let (_, _, sampler_id) = curr_ctxt_ids;
+                    (sampler_id, tex_view_id)
+                };
+                let new_scissor = curr_ctxt.scissor;
+                let new_normal_map_id = material
+                    .as_ref()
+                    .and_then(|material| material.normal_map.as_ref())
+                    .map(|view| {
+                        let id = view.id();
+                        new_normal_maps.insert(id, view.clone());
+                        id
+                    });
+
+                // Determine which have changed and in turn which require submitting new
+                // commands.
+                let pipeline_changed = Some(new_pipeline_id) != curr_pipeline_id;
+                let bind_group_changed = Some(new_bind_group_id) != curr_tex_sampler_id;
+                let scissor_changed = Some(new_scissor) != curr_scissor;
+                let normal_map_changed = Some(new_normal_map_id) != curr_normal_map_id;
+
+                // If we require submitting a scissor, pipeline or bind group command, first
+                // draw whatever pending vertices we have collected so far. If there have been
+                // no graphics yet, this will do nothing.
+                if scissor_changed || pipeline_changed || bind_group_changed || normal_map_changed {
+                    push_draw_cmd(
+                        &mut curr_start_index,
+                        prev_index_count,
+                        &mut self.render_commands,
+                    );
+                }
 
-                    // If necessary, push a new scissor command.
-                    if scissor_changed {
-                        curr_scissor = Some(new_scissor);
-                        let rect = match curr_ctxt.scissor {
-                            draw::Scissor::Full => full_rect,
-                            draw::Scissor::Rect(rect) => full_rect
-                                .overlap(rect)
-                                .unwrap_or(geom::Rect::from_w_h(0.0, 0.0)),
-                            draw::Scissor::NoOverlap => geom::Rect::from_w_h(0.0, 0.0),
-                        };
-                        let [left, bottom] = window_to_scissor(rect.bottom_left().into());
-                        let (width, height) = rect.w_h();
-                        let (width, height) = (pt_to_px(width), pt_to_px(height));
-                        let scissor = Scissor {
-                            left,
-                            bottom,
-                            width,
-                            height,
+                // If necessary, push a new pipeline command.
+                if pipeline_changed {
+                    curr_pipeline_id = Some(new_pipeline_id);
+                    let color_blend = curr_ctxt.blend.color.clone();
+                    let alpha_blend = curr_ctxt.blend.alpha.clone();
+                    let sampler_filtering = wgpu::sampler_filtering(&curr_ctxt.sampler);
+                    new_pipeline_ids.insert(
+                        new_pipeline_id,
+                        (color_blend, alpha_blend, sampler_filtering),
+                    );
+                    let cmd = RenderCommand::SetPipeline(new_pipeline_id);
+                    self.render_commands.push(cmd);
+                }
+
+                // If necessary, push a new bind group command.
+                if bind_group_changed {
+                    curr_tex_sampler_id = Some(new_bind_group_id);
+                    new_tex_sampler_combos.insert(new_bind_group_id, new_pipeline_id);
+                    new_samplers
+                        .entry(new_bind_group_id.0)
+                        .or_insert_with(|| curr_ctxt.sampler.clone());
+                    let cmd = RenderCommand::SetBindGroup(new_bind_group_id);
+                    self.render_commands.push(cmd);
+                }
+
+                // If necessary, push a new normal map command.
+                if normal_map_changed {
+                    curr_normal_map_id = Some(new_normal_map_id);
+                    let cmd = RenderCommand::SetNormalMap(new_normal_map_id);
+                    self.render_commands.push(cmd);
+                }
+
+                // If necessary, push a new scissor command.
+                if scissor_changed {
+                    curr_scissor = Some(new_scissor);
+                    let rect = match curr_ctxt.scissor {
+                        draw::Scissor::Full => full_rect,
+                        draw::Scissor::Rect(rect) => full_rect
+                            .overlap(rect)
+                            .unwrap_or(geom::Rect::from_w_h(0.0, 0.0)),
+                        draw::Scissor::NoOverlap => geom::Rect::from_w_h(0.0, 0.0),
+                    };
+                    let [left, bottom] = window_to_scissor(rect.bottom_left().into());
+                    let (width, height) = rect.w_h();
+                    let (width, height) = (pt_to_px(width), pt_to_px(height));
+                    let scissor = Scissor {
+                        left,
+                        bottom,
+                        width,
+                        height,
+                    };
+                    let cmd = RenderCommand::SetScissor(scissor);
+                    self.render_commands.push(cmd);
+                }
+
+                // Extend the vertex mode channel.
+                let mode = match (curr_ctxt.debug_view, render.vertex_mode) {
+                    (Some(view), _) => VertexMode::debug(view),
+                    (None, VertexMode::Color) if curr_ctxt.analytic_aa => VertexMode::ColorAa,
+                    (None, mode) => mode,
+                };
+                let new_vs = self.mesh.points().len() - self.vertex_mode_buffer.len();
+                self.vertex_mode_buffer.extend((0..new_vs).map(|_| mode));
+
+                // Pad the SDF params channel for primitives that did not provide any.
+                let n_vs = self.mesh.points().len();
+                self.sdf_params_buffer.resize(n_vs, SdfParams::default());
+
+                // Extend the material channel.
+                let material = MaterialParams::new(&curr_ctxt, material.as_ref());
+                self.material_params_buffer.resize(n_vs, material);
+
+                // Lit triangles cast shadows.
+                if material.lit != 0 && curr_ctxt.topology == wgpu::PrimitiveTopology::TriangleList
+                {
+                    let indices = &self.mesh.indices()[prev_index_count as usize..];
+                    self.shadow_indices.extend_from_slice(indices);
+                    for &p in &self.mesh.points()[prev_vert_count..] {
+                        self.shadow_bounds = match self.shadow_bounds {
+                            None => Some((p, p)),
+                            Some((min, max)) => Some((min.min(p), max.max(p))),
                         };
-                        let cmd = RenderCommand::SetScissor(scissor);
-                        self.render_commands.push(cmd);
                     }
-
-                    // Extend the vertex mode channel.
-                    let mode = render.vertex_mode;
-                    let new_vs = self.mesh.points().len() - self.vertex_mode_buffer.len();
-                    self.vertex_mode_buffer.extend((0..new_vs).map(|_| mode));
                 }
             }
         }
 
+        // The lights of the draw illuminate all lit primitives.
+        let lighting = &draw.state.borrow().lighting;
+        self.light_uniforms = LightUniforms::new(lighting, self.shadow_bounds);
+        self.shadow_map_size = lighting.shadow_map_size;
+        let environment_map = match lighting.environment {
+            Some(ref env) => &env.map,
+            None => &self.black_environment_map,
+        };
+        if *environment_map != self.environment_map {
+            self.environment_map = environment_map.clone();
+            self.uniform_bind_group = create_uniform_bind_group(
+                device,
+                &self.uniform_bind_group_layout,
+                &self.uniform_buffer,
+                &self.light_buffer,
+                &self.shadow_maps,
+                &self.environment_map,
+                &self.environment_sampler,
+            );
+        }
+
         // Insert the final draw command if there is still some drawing to be done.
         push_draw_cmd(
             &mut curr_start_index,
@@ -701,6 +1117,18 @@ impl Renderer {
             &mut self.render_commands,
         );
 
+        // Merge draws that share the same state and drop any redundant changes of state.
+        self.render_commands = batch_render_commands(mem::take(&mut self.render_commands));
+
+        // Emulate non-fill polygon modes if the device does not support them.
+        let polygon_mode_features = device.features();
+        if new_pipeline_ids
+            .keys()
+            .any(|id| !polygon_mode_supported(id.polygon_mode, polygon_mode_features))
+        {
+            self.emulate_polygon_modes(polygon_mode_features);
+        }
+
         // Clear out unnecessary pipelines.
         self.pipelines
             .retain(|id, _| new_pipeline_ids.contains_key(id));
@@ -723,6 +1151,7 @@ impl Renderer {
                 &self.uniform_bind_group_layout,
                 &self.text_bind_group_layout,
                 &bind_group_layout,
+                self.normal_maps.layout(),
                 &self.vs_mod,
                 &self.fs_mod,
                 self.output_color_format,
@@ -731,6 +1160,9 @@ impl Renderer {
                 color_blend,
                 alpha_blend,
                 new_id.topology,
+                new_id.polygon_mode,
+                new_id.depth_write,
+                new_id.depth_compare,
             );
             self.pipelines.insert(new_id, new_pipeline);
         }
@@ -750,7 +1182,7 @@ impl Renderer {
             let sampler = self
                 .texture_samplers
                 .entry(new_sampler_id)
-                .or_insert_with(|| device.create_sampler(&curr_ctxt.sampler));
+                .or_insert_with(|| device.create_sampler(&new_samplers[&new_sampler_id]));
             // Retrieve the texture view.
             let texture_view = &new_tex_views[&new_tex_view_id];
             // Retrieve the associated bind group layout.
@@ -761,6 +1193,43 @@ impl Renderer {
                 create_texture_bind_group(device, bind_group_layout, sampler, texture_view);
             self.texture_bind_groups.insert(new_id, bind_group);
         }
+
+        // Ensure we have a bind group for each of the normal maps, but no more.
+        self.normal_maps.update(device, &new_normal_maps);
+    }
+
+    // Rewrite the indices drawn via pipelines with polygon modes unsupported by the given device
+    // features so that they may be drawn as line or point lists instead.
+    fn emulate_polygon_modes(&mut self, features: wgpu::Features) {
+        let src = self.mesh.indices().to_vec();
+        let mut indices = Vec::with_capacity(src.len());
+        let mut emulated = None;
+        for cmd in &mut self.render_commands {
+            match cmd {
+                RenderCommand::SetPipeline(id) => {
+                    emulated = Some(id.polygon_mode)
+                        .filter(|&mode| !polygon_mode_supported(mode, features));
+                }
+                RenderCommand::DrawIndexed { index_range, .. } => {
+                    let tris = &src[index_range.start as usize..index_range.end as usize];
+                    let start = indices.len() as u32;
+                    match emulated {
+                        // Each triangle is replaced by the lines along its three edges.
+                        Some(wgpu::PolygonMode::Line) => {
+                            for tri in tris.chunks_exact(3) {
+                                let (a, b, c) = (tri[0], tri[1], tri[2]);
+                                indices.extend_from_slice(&[a, b, b, c, c, a]);
+                            }
+                        }
+                        _ => indices.extend_from_slice(tris),
+                    }
+                    *index_range = start..indices.len() as u32;
+                }
+                _ => (),
+            }
+        }
+        self.mesh.clear_indices();
+        self.mesh.extend_indices(indices);
     }
 
     /// Encode a render pass with the given **Draw**ing to the given `output_attachment`.
@@ -780,23 +1249,104 @@ impl Renderer {
         output_attachment: &wgpu::TextureView,
         resolve_target: Option<&wgpu::TextureView>,
     ) {
+        let start = Instant::now();
+        let persistent = draw.state.borrow().persistent.clone();
+        if let Some(persistent) = persistent {
+            self.render_persistent(
+                device,
+                encoder,
+                &persistent,
+                scale_factor,
+                output_attachment_size,
+            );
+        }
+
         self.clear();
         self.fill(device, draw, scale_factor, output_attachment_size);
+        let filled = Instant::now();
+        let gpu = self.stats.gpu;
+        self.stats = self.render_command_stats();
+        self.stats.tessellation = filled - start;
+        self.stats.gpu = gpu;
 
+        self.encode_render_commands(
+            device,
+            encoder,
+            draw,
+            scale_factor,
+            output_attachment_size,
+            output_attachment,
+            resolve_target,
+        );
+        self.stats.encoding = filled.elapsed();
+    }
+
+    /// Statistics describing the most recent render, e.g. for profiling heavy sketches.
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    // Count the commands and vertex data produced by the most recent `fill`.
+    fn render_command_stats(&self) -> Stats {
+        let mut stats = Stats {
+            vertices: self.mesh.vertex_count(),
+            indices: self.mesh.indices().len(),
+            ..Stats::default()
+        };
+        for cmd in &self.render_commands {
+            match cmd {
+                RenderCommand::SetPipeline(_) => stats.pipeline_switches += 1,
+                RenderCommand::SetBindGroup(_) => stats.texture_binds += 1,
+                RenderCommand::DrawIndexed { .. } => stats.draw_calls += 1,
+                RenderCommand::Custom { .. } => stats.custom_callbacks += 1,
+                RenderCommand::SetNormalMap(_)
+                | RenderCommand::SetScissor(_)
+                | RenderCommand::Scope(_) => (),
+            }
+        }
+        stats
+    }
+
+    // Encode the render commands produced by the most recent `fill`.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_render_commands(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        draw: &draw::Draw,
+        scale_factor: f32,
+        output_attachment_size: [u32; 2],
+        output_attachment: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+    ) {
         let Renderer {
             ref pipelines,
-            ref glyph_cache,
+            ref mut glyph_cache,
             ref glyph_cache_texture,
+            ref color_glyphs_texture,
             ref mut depth_texture,
             ref mut depth_texture_view,
-            ref uniform_bind_group,
+            ref mut uniform_bind_group,
+            ref uniform_bind_group_layout,
             ref text_bind_group,
             ref texture_bind_groups,
+            ref normal_maps,
             ref mesh,
             ref vertex_mode_buffer,
+            ref sdf_params_buffer,
+            ref material_params_buffer,
             ref mut render_commands,
             ref uniform_buffer,
+            ref light_uniforms,
+            ref light_buffer,
+            ref mut shadow_maps,
+            ref environment_map,
+            ref environment_sampler,
+            ref shadow_indices,
+            shadow_map_size,
             scale_factor: ref mut old_scale_factor,
+            output_color_format,
+            sample_count,
             ..
         } = *self;
 
@@ -804,6 +1354,11 @@ impl Renderer {
         if glyph_cache.requires_upload {
             glyph_cache_texture.upload_data(device, encoder, &glyph_cache.pixel_buffer);
         }
+        if glyph_cache.color_glyphs.requires_upload {
+            let pixels = glyph_cache.color_glyphs.pixels();
+            color_glyphs_texture.upload_data(device, encoder, pixels);
+            glyph_cache.color_glyphs.requires_upload = false;
+        }
 
         // Resize the depth texture if the output attachment size has changed.
         let depth_size = depth_texture.size();
@@ -827,17 +1382,44 @@ impl Renderer {
             }
         };
 
-        // Create render pass builder.
-        let render_pass_builder = wgpu::RenderPassBuilder::new()
-            .color_attachment(output_attachment, |color| {
-                color.resolve_target(resolve_target).load_op(load_op)
-            })
-            .depth_stencil_attachment(&*depth_texture_view, |depth| depth);
+        // Create render pass builder. Render passes following a custom callback load the results
+        // of the previous passes.
+        let depth_texture_view = &*depth_texture_view;
+        let render_pass_builder = |load_op, depth_load_op| {
+            wgpu::RenderPassBuilder::new()
+                .color_attachment(output_attachment, |color| {
+                    color.resolve_target(resolve_target).load_op(load_op)
+                })
+                .depth_stencil_attachment(depth_texture_view, |depth| {
+                    depth.depth_load_op(depth_load_op)
+                })
+        };
+        let depth_load_op = wgpu::RenderPassBuilder::DEFAULT_DEPTH_LOAD_OP;
+
+        // Resources provided to custom callbacks.
+        let mut view_resources = ViewResources {
+            device,
+            output_attachment,
+            resolve_target,
+            depth_attachment: depth_texture_view,
+            output_attachment_size,
+            output_attachment_format: output_color_format,
+            depth_format: depth_texture.format(),
+            sample_count,
+            scale_factor,
+            transform: Mat4::IDENTITY,
+        };
 
         // Guard for empty mesh.
         if mesh.points().is_empty() {
             // Encode the render pass. Only clears the frame.
-            render_pass_builder.begin(encoder);
+            render_pass_builder(load_op, depth_load_op).begin(encoder);
+            for cmd in render_commands.drain(..) {
+                if let RenderCommand::Custom { custom, transform } = cmd {
+                    view_resources.transform = transform;
+                    custom.call(encoder, &view_resources);
+                }
+            }
             return;
         }
 
@@ -847,6 +1429,8 @@ impl Renderer {
         let colors_bytes = colors_as_bytes(mesh.colors());
         let tex_coords_bytes = tex_coords_as_bytes(mesh.tex_coords());
         let modes_bytes = vertex_modes_as_bytes(vertex_mode_buffer);
+        let sdf_params_bytes = sdf_params_as_bytes(sdf_params_buffer);
+        let material_params_bytes = material_params_as_bytes(material_params_buffer);
         let indices_bytes = indices_as_bytes(mesh.indices());
         let point_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("nannou Renderer point_buffer"),
@@ -868,6 +1452,16 @@ impl Renderer {
             contents: modes_bytes,
             usage: vertex_usage,
         });
+        let sdf_params_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer sdf_params_buffer"),
+            contents: sdf_params_bytes,
+            usage: vertex_usage,
+        });
+        let material_params_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer material_params_buffer"),
+            contents: material_params_bytes,
+            usage: vertex_usage,
+        });
         let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("nannou Renderer index_buffer"),
             contents: indices_bytes,
@@ -891,55 +1485,228 @@ impl Renderer {
             encoder.copy_buffer_to_buffer(&new_uniform_buffer, 0, uniform_buffer, 0, uniforms_size);
         }
 
-        // Encode the render pass.
-        let mut render_pass = render_pass_builder.begin(encoder);
+        // Upload the lights.
+        let lights_size = std::mem::size_of::<LightUniforms>() as wgpu::BufferAddress;
+        let new_light_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("nannou Renderer light_buffer"),
+            contents: light_uniforms_as_bytes(light_uniforms),
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        encoder.copy_buffer_to_buffer(&new_light_buffer, 0, light_buffer, 0, lights_size);
+
+        // Render the lit triangles into the shadow map of each shadow casting light.
+        let shadow_layers = light_uniforms.shadow_casters().count() as u32;
+        if shadow_layers > 0 {
+            if shadow_maps.resize(device, shadow_map_size, shadow_layers) {
+                *uniform_bind_group = create_uniform_bind_group(
+                    device,
+                    uniform_bind_group_layout,
+                    uniform_buffer,
+                    light_buffer,
+                    shadow_maps,
+                    environment_map,
+                    environment_sampler,
+                );
+            }
+            let shadow_index_buffer = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("nannou Renderer shadow_index_buffer"),
+                contents: indices_as_bytes(shadow_indices),
+                usage: wgpu::BufferUsages::INDEX,
+            });
+            let index_count = shadow_indices.len() as u32;
+            shadow_maps.encode(
+                encoder,
+                light_uniforms,
+                &point_buffer,
+                &shadow_index_buffer,
+                index_count,
+            );
+        }
+        let uniform_bind_group = &*uniform_bind_group;
+
+        // Encode the render passes, ending each pass at a custom callback.
+        let mut render_commands = render_commands.drain(..);
+        // The span of the current named scope, if any.
+        let mut scope_span = None;
+        let (mut load_op, mut depth_load_op) = (load_op, depth_load_op);
+        loop {
+            let mut custom = None;
+            {
+                let mut render_pass = render_pass_builder(load_op, depth_load_op).begin(encoder);
+
+                // Set the buffers.
+                render_pass.set_index_buffer(index_buffer.slice(..), Self::INDEX_FORMAT);
+                render_pass.set_vertex_buffer(0, point_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, color_buffer.slice(..));
+                render_pass.set_vertex_buffer(2, tex_coords_buffer.slice(..));
+                render_pass.set_vertex_buffer(3, mode_buffer.slice(..));
+                render_pass.set_vertex_buffer(4, sdf_params_buffer.slice(..));
+                render_pass.set_vertex_buffer(5, material_params_buffer.slice(..));
+
+                // Set the uniform and text bind groups here.
+                render_pass.set_bind_group(0, uniform_bind_group, &[]);
+                render_pass.set_bind_group(1, text_bind_group, &[]);
+                render_pass.set_bind_group(3, normal_maps.bind_group(None), &[]);
+
+                // Follow the render commands.
+                for cmd in render_commands.by_ref() {
+                    match cmd {
+                        RenderCommand::SetPipeline(id) => {
+                            let pipeline = &pipelines[&id];
+                            render_pass.set_pipeline(pipeline);
+                        }
 
-        // Set the buffers.
-        render_pass.set_index_buffer(index_buffer.slice(..), Self::INDEX_FORMAT);
-        render_pass.set_vertex_buffer(0, point_buffer.slice(..));
-        render_pass.set_vertex_buffer(1, color_buffer.slice(..));
-        render_pass.set_vertex_buffer(2, tex_coords_buffer.slice(..));
-        render_pass.set_vertex_buffer(3, mode_buffer.slice(..));
+                        RenderCommand::SetBindGroup(tex_view_id) => {
+                            let bind_group = &texture_bind_groups[&tex_view_id];
+                            render_pass.set_bind_group(2, bind_group, &[]);
+                        }
 
-        // Set the uniform and text bind groups here.
-        render_pass.set_bind_group(0, uniform_bind_group, &[]);
-        render_pass.set_bind_group(1, text_bind_group, &[]);
+                        RenderCommand::SetNormalMap(id) => {
+                            let bind_group = normal_maps.bind_group(id);
+                            render_pass.set_bind_group(3, bind_group, &[]);
+                        }
 
-        // Follow the render commands.
-        for cmd in render_commands.drain(..) {
-            match cmd {
-                RenderCommand::SetPipeline(id) => {
-                    let pipeline = &pipelines[&id];
-                    render_pass.set_pipeline(pipeline);
-                }
+                        RenderCommand::SetScissor(Scissor {
+                            left,
+                            bottom,
+                            width,
+                            height,
+                        }) => {
+                            render_pass.set_scissor_rect(left, bottom, width, height);
+                        }
 
-                RenderCommand::SetBindGroup(tex_view_id) => {
-                    let bind_group = &texture_bind_groups[&tex_view_id];
-                    render_pass.set_bind_group(2, bind_group, &[]);
-                }
+                        RenderCommand::DrawIndexed {
+                            start_vertex,
+                            index_range,
+                        } => {
+                            let instance_range = 0..1u32;
+                            render_pass.draw_indexed(index_range, start_vertex, instance_range);
+                        }
 
-                RenderCommand::SetScissor(Scissor {
-                    left,
-                    bottom,
-                    width,
-                    height,
-                }) => {
-                    render_pass.set_scissor_rect(left, bottom, width, height);
-                }
+                        RenderCommand::Custom {
+                            custom: c,
+                            transform,
+                        } => {
+                            custom = Some((c, transform));
+                            break;
+                        }
 
-                RenderCommand::DrawIndexed {
-                    start_vertex,
-                    index_range,
-                } => {
-                    let instance_range = 0..1u32;
-                    render_pass.draw_indexed(index_range, start_vertex, instance_range);
+                        RenderCommand::Scope(name) => {
+                            // End the previous span before entering the next.
+                            scope_span.take();
+                            scope_span = name
+                                .as_deref()
+                                .map(|name| profile::draw_scope(DrawStage::Encode, name));
+                        }
+                    }
                 }
             }
+
+            // Call the custom callback, if any, before continuing with a new render pass.
+            let (custom, transform) = match custom {
+                None => break,
+                Some(custom) => custom,
+            };
+            view_resources.transform = transform;
+            custom.call(encoder, &view_resources);
+            load_op = wgpu::LoadOp::Load;
+            depth_load_op = wgpu::LoadOp::Load;
         }
     }
 
+    // Accumulate the drawing of the given persistent **Draw** within the persistent texture,
+    // (re)creating the texture if necessary.
+    fn render_persistent(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        draw: &draw::Draw,
+        scale_factor: f32,
+        size: [u32; 2],
+    ) {
+        let (output_color_format, sample_count) = (self.output_color_format, self.sample_count);
+        let resized = self
+            .persistent
+            .as_ref()
+            .map(|persistent| persistent.texture.size() != size);
+        if resized != Some(false) {
+            // The contents of a new texture are undefined, so clear them first.
+            let mut state = draw.state.borrow_mut();
+            let transparent = draw::properties::LinSrgba::new(0.0, 0.0, 0.0, 0.0);
+            state.background_color.get_or_insert(transparent);
+            drop(state);
+            let builder = || {
+                wgpu::TextureBuilder::new()
+                    .size(size)
+                    .format(output_color_format)
+                    .usage(
+                        wgpu::TextureUsages::RENDER_ATTACHMENT
+                            | wgpu::TextureUsages::TEXTURE_BINDING,
+                    )
+            };
+            let texture = builder().build(device);
+            let msaa_texture = match sample_count {
+                1 => None,
+                n => {
+                    let msaa_texture = builder().sample_count(n).build(device);
+                    let msaa_view = msaa_texture.view().build();
+                    Some((msaa_texture, msaa_view))
+                }
+            };
+            let view = texture.view().build();
+            let renderer = match self.persistent.take() {
+                Some(persistent) => persistent.renderer,
+                None => Box::new(Builder::new().build(
+                    device,
+                    size,
+                    scale_factor,
+                    sample_count,
+                    output_color_format,
+                )),
+            };
+            self.persistent = Some(Persistent {
+                renderer,
+                msaa_texture,
+                texture,
+                view,
+            });
+        }
+
+        let persistent = self.persistent.as_mut().expect("no persistent texture");
+        let (attachment, resolve_target) = match persistent.msaa_texture {
+            Some((_, ref msaa_view)) => (msaa_view, Some(&persistent.view)),
+            None => (&persistent.view, None),
+        };
+        persistent.renderer.encode_render_pass(
+            device,
+            encoder,
+            draw,
+            scale_factor,
+            size,
+            attachment,
+            resolve_target,
+        );
+    }
+
+    /// The ratio of pixels to points used for the most recent render, or the `scale_factor` with
+    /// which the renderer was built if it has not yet been used.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Specify the ratio of pixels to points used by `render_to_texture`.
+    ///
+    /// Drawings are described in points, so a scale factor of `2.0` renders a drawing that fills
+    /// a `512x512` pixel texture within the bounds of a `256x256` point rect, as on a HiDPI
+    /// display.
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        self.scale_factor = scale_factor;
+    }
+
     /// Encode the necessary commands to render the contents of the given **Draw**ing to the given
     /// **Texture**.
+    ///
+    /// The texture is rendered using the renderer's current `scale_factor`.
     pub fn render_to_texture(
         &mut self,
         device: &wgpu::Device,
@@ -949,8 +1716,7 @@ impl Renderer {
     ) {
         let size = texture.size();
         let view = texture.view().build();
-        // TODO: Should we expose this for rendering to textures?
-        let scale_factor = 1.0;
+        let scale_factor = self.scale_factor;
         let resolve_target = None;
         self.encode_render_pass(
             device,
@@ -975,7 +1741,14 @@ impl Renderer {
         let size = frame.texture().size();
         let attachment = frame.texture_view();
         let resolve_target = None;
+        if self.gpu_timer.is_none() {
+            let queue = frame.device_queue_pair().queue();
+            self.gpu_timer = GpuTimer::new(device, queue);
+        }
         let mut command_encoder = frame.command_encoder();
+        if let Some(timer) = self.gpu_timer.as_mut() {
+            timer.begin(device, &mut command_encoder);
+        }
         self.encode_render_pass(
             device,
             &mut *command_encoder,
@@ -985,6 +1758,34 @@ impl Renderer {
             attachment,
             resolve_target,
         );
+        if let Some(timer) = self.gpu_timer.as_mut() {
+            timer.end(device, &mut command_encoder);
+            self.stats.gpu = timer.latest();
+        }
+    }
+
+    // Render the given **Draw**ing over the frame without affecting the renderer's `stats`, e.g.
+    // for letterbox bars and the stats overlay.
+    pub(crate) fn render_overlay_to_frame(
+        &mut self,
+        device: &wgpu::Device,
+        draw: &draw::Draw,
+        scale_factor: f32,
+        frame: &Frame,
+    ) {
+        let stats = self.stats.clone();
+        let size = frame.texture().size();
+        let mut command_encoder = frame.command_encoder();
+        self.encode_render_pass(
+            device,
+            &mut command_encoder,
+            draw,
+            scale_factor,
+            size,
+            frame.texture_view(),
+            None,
+        );
+        self.stats = stats;
     }
 }
 
@@ -1021,7 +1822,14 @@ fn create_depth_texture(
         .build(device)
 }
 
-fn create_uniforms([img_w, img_h]: [u32; 2], scale_factor: f32) -> Uniforms {
+fn create_uniforms(size: [u32; 2], scale_factor: f32) -> Uniforms {
+    let proj = projection(size, scale_factor);
+    Uniforms { proj }
+}
+
+/// The projection from the logical coordinate space of an output attachment with the given size
+/// in pixels to clip space, as used by the **Renderer**.
+pub(crate) fn projection([img_w, img_h]: [u32; 2], scale_factor: f32) -> Mat4 {
     let right = img_w as f32 * 0.5 / scale_factor;
     let left = -right;
     let top = img_h as f32 * 0.5 / scale_factor;
@@ -1034,14 +1842,33 @@ fn create_uniforms([img_w, img_h]: [u32; 2], scale_factor: f32) -> Uniforms {
     // TODO: Can possibly solve this more easily by using `Mat4::orthographic_rh` above instead.
     let trans = Mat4::from_translation(Vec3::Z);
     let scale = Mat4::from_scale([1.0, 1.0, 0.5].into());
-    let proj = scale * trans * proj;
-    let proj = proj.into();
-    Uniforms { proj }
+    scale * trans * proj
 }
 
 fn create_uniform_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     wgpu::BindGroupLayoutBuilder::new()
         .uniform_buffer(wgpu::ShaderStages::VERTEX, false)
+        .uniform_buffer(wgpu::ShaderStages::FRAGMENT, false)
+        .texture(
+            wgpu::ShaderStages::FRAGMENT,
+            false,
+            wgpu::TextureViewDimension::D2Array,
+            wgpu::TextureSampleType::Depth,
+        )
+        .comparison_sampler(wgpu::ShaderStages::FRAGMENT)
+        .texture(
+            wgpu::ShaderStages::FRAGMENT,
+            false,
+            wgpu::TextureViewDimension::Cube,
+            wgpu::TextureSampleType::Float { filterable: true },
+        )
+        .texture(
+            wgpu::ShaderStages::FRAGMENT,
+            false,
+            wgpu::TextureViewDimension::Cube,
+            wgpu::TextureSampleType::Float { filterable: true },
+        )
+        .sampler(wgpu::ShaderStages::FRAGMENT, true)
         .build(device)
 }
 
@@ -1056,6 +1883,14 @@ fn create_text_bind_group_layout(device: &wgpu::Device, filtering: bool) -> wgpu
                 .sample_type(None)
                 .expect("Expected format to have sample type"),
         )
+        .texture(
+            wgpu::ShaderStages::FRAGMENT,
+            false,
+            wgpu::TextureViewDimension::D2,
+            Renderer::COLOR_GLYPHS_TEXTURE_FORMAT
+                .sample_type(None)
+                .expect("Expected format to have sample type"),
+        )
         .build(device)
 }
 
@@ -1079,9 +1914,19 @@ fn create_uniform_bind_group(
     device: &wgpu::Device,
     layout: &wgpu::BindGroupLayout,
     uniform_buffer: &wgpu::Buffer,
+    light_buffer: &wgpu::Buffer,
+    shadow_maps: &ShadowMaps,
+    environment_map: &draw::EnvironmentMap,
+    environment_sampler: &wgpu::Sampler,
 ) -> wgpu::BindGroup {
     wgpu::BindGroupBuilder::new()
         .buffer::<Uniforms>(uniform_buffer, 0..1)
+        .buffer::<LightUniforms>(light_buffer, 0..1)
+        .texture_view(shadow_maps.view())
+        .sampler(shadow_maps.sampler())
+        .texture_view(environment_map.irradiance_view())
+        .texture_view(environment_map.specular_view())
+        .sampler(environment_sampler)
         .build(device, layout)
 }
 
@@ -1090,10 +1935,12 @@ fn create_text_bind_group(
     layout: &wgpu::BindGroupLayout,
     sampler: &wgpu::Sampler,
     glyph_cache_texture_view: &wgpu::TextureViewHandle,
+    color_glyphs_texture_view: &wgpu::TextureViewHandle,
 ) -> wgpu::BindGroup {
     wgpu::BindGroupBuilder::new()
         .sampler(sampler)
         .texture_view(glyph_cache_texture_view)
+        .texture_view(color_glyphs_texture_view)
         .build(device, layout)
 }
 
@@ -1109,11 +1956,13 @@ fn create_texture_bind_group(
         .build(device, layout)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_render_pipeline(
     device: &wgpu::Device,
     uniform_layout: &wgpu::BindGroupLayout,
     text_layout: &wgpu::BindGroupLayout,
     texture_layout: &wgpu::BindGroupLayout,
+    normal_map_layout: &wgpu::BindGroupLayout,
     vs_mod: &wgpu::ShaderModule,
     fs_mod: &wgpu::ShaderModule,
     dst_format: wgpu::TextureFormat,
@@ -1122,8 +1971,22 @@ fn create_render_pipeline(
     color_blend: wgpu::BlendComponent,
     alpha_blend: wgpu::BlendComponent,
     topology: wgpu::PrimitiveTopology,
+    polygon_mode: wgpu::PolygonMode,
+    depth_write: bool,
+    depth_compare: wgpu::CompareFunction,
 ) -> wgpu::RenderPipeline {
-    let bind_group_layouts = &[uniform_layout, text_layout, texture_layout];
+    // Polygon modes unsupported by the device are emulated via `Renderer::emulate_polygon_modes`.
+    let (topology, polygon_mode) = match polygon_mode {
+        mode if polygon_mode_supported(mode, device.features()) => (topology, mode),
+        wgpu::PolygonMode::Point => (wgpu::PrimitiveTopology::PointList, wgpu::PolygonMode::Fill),
+        _ => (wgpu::PrimitiveTopology::LineList, wgpu::PolygonMode::Fill),
+    };
+    let bind_group_layouts = &[
+        uniform_layout,
+        text_layout,
+        texture_layout,
+        normal_map_layout,
+    ];
     wgpu::RenderPipelineBuilder::from_layout_descriptor(&bind_group_layouts[..], vs_mod)
         .fragment_shader(fs_mod)
         .color_format(dst_format)
@@ -1133,14 +1996,131 @@ fn create_render_pipeline(
             &wgpu::vertex_attr_array![2 => Float32x2],
         )
         .add_vertex_buffer::<VertexMode>(&wgpu::vertex_attr_array![3 => Uint32])
+        .add_vertex_buffer::<SdfParams>(&SdfParams::VERTEX_ATTRIBUTES)
+        .add_vertex_buffer::<MaterialParams>(&MaterialParams::VERTEX_ATTRIBUTES)
         .depth_format(depth_format)
+        .depth_write_enabled(depth_write)
+        .depth_compare(depth_compare)
         .sample_count(sample_count)
         .color_blend(color_blend)
         .alpha_blend(alpha_blend)
         .primitive_topology(topology)
+        .polygon_mode(polygon_mode)
         .build(device)
 }
 
+// Whether or not the device supports rendering with the given polygon mode.
+fn polygon_mode_supported(mode: wgpu::PolygonMode, features: wgpu::Features) -> bool {
+    match mode {
+        wgpu::PolygonMode::Fill => true,
+        wgpu::PolygonMode::Line => features.contains(wgpu::Features::POLYGON_MODE_LINE),
+        wgpu::PolygonMode::Point => features.contains(wgpu::Features::POLYGON_MODE_POINT),
+    }
+}
+
+// The hashes of the blend components and sampler of the given context.
+fn context_ids(ctxt: &draw::Context) -> (ColorId, AlphaId, SamplerId) {
+    let color_id = blend_component_hash(&ctxt.blend.color);
+    let alpha_id = blend_component_hash(&ctxt.blend.alpha);
+    let sampler_id = sampler_descriptor_hash(&ctxt.sampler);
+    (color_id, alpha_id, sampler_id)
+}
+
+// Merge consecutive draws of contiguous index ranges that share the same state and remove state
+// changes that either match the current state or are replaced before the next draw.
+fn batch_render_commands(commands: Vec<RenderCommand>) -> Vec<RenderCommand> {
+    // The state set within a render pass. The default normal map is set as each pass begins.
+    #[derive(Default)]
+    struct State {
+        pipeline: Option<PipelineId>,
+        bind_group: Option<BindGroupId>,
+        normal_map: Option<Option<wgpu::TextureViewId>>,
+        scissor: Option<Scissor>,
+    }
+    let pass_state = || State {
+        normal_map: Some(None),
+        ..Default::default()
+    };
+
+    let mut batched = Vec::with_capacity(commands.len());
+    let mut bound = pass_state();
+    let mut pending = State::default();
+    for cmd in commands {
+        match cmd {
+            RenderCommand::SetPipeline(id) => pending.pipeline = Some(id),
+            RenderCommand::SetBindGroup(id) => pending.bind_group = Some(id),
+            RenderCommand::SetNormalMap(id) => pending.normal_map = Some(id),
+            RenderCommand::SetScissor(scissor) => pending.scissor = Some(scissor),
+            RenderCommand::DrawIndexed {
+                start_vertex,
+                index_range,
+            } => {
+                // Submit only the pending state that differs from that already bound.
+                let len = batched.len();
+                if let Some(id) = pending
+                    .pipeline
+                    .take()
+                    .filter(|&id| bound.pipeline != Some(id))
+                {
+                    bound.pipeline = Some(id);
+                    batched.push(RenderCommand::SetPipeline(id));
+                }
+                if let Some(id) = pending
+                    .bind_group
+                    .take()
+                    .filter(|&id| bound.bind_group != Some(id))
+                {
+                    bound.bind_group = Some(id);
+                    batched.push(RenderCommand::SetBindGroup(id));
+                }
+                if let Some(id) = pending
+                    .normal_map
+                    .take()
+                    .filter(|&id| bound.normal_map != Some(id))
+                {
+                    bound.normal_map = Some(id);
+                    batched.push(RenderCommand::SetNormalMap(id));
+                }
+                if let Some(scissor) = pending
+                    .scissor
+                    .take()
+                    .filter(|&scissor| bound.scissor != Some(scissor))
+                {
+                    bound.scissor = Some(scissor);
+                    batched.push(RenderCommand::SetScissor(scissor));
+                }
+
+                // Extend the previous draw if the state is unchanged.
+                if batched.len() == len {
+                    if let Some(RenderCommand::DrawIndexed {
+                        start_vertex: prev_start_vertex,
+                        index_range: prev_range,
+                    }) = batched.last_mut()
+                    {
+                        if *prev_start_vertex == start_vertex && prev_range.end == index_range.start
+                        {
+                            prev_range.end = index_range.end;
+                            continue;
+                        }
+                    }
+                }
+                batched.push(RenderCommand::DrawIndexed {
+                    start_vertex,
+                    index_range,
+                });
+            }
+            // Each custom command begins a new render pass with its own state.
+            cmd @ RenderCommand::Custom { .. } => {
+                batched.push(cmd);
+                bound = pass_state();
+            }
+            // Scopes also prevent the merging of draws on either side.
+            cmd @ RenderCommand::Scope(_) => batched.push(cmd),
+        }
+    }
+    batched
+}
+
 fn sampler_descriptor_hash(desc: &wgpu::SamplerDescriptor) -> SamplerId {
     let mut s = std::collections::hash_map::DefaultHasher::new();
     desc.address_mode_u.hash(&mut s);
@@ -1187,6 +2167,161 @@ fn vertex_modes_as_bytes(data: &[VertexMode]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
+fn sdf_params_as_bytes(data: &[SdfParams]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
+fn material_params_as_bytes(data: &[MaterialParams]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
+
+fn light_uniforms_as_bytes(uniforms: &LightUniforms) -> &[u8] {
+    unsafe { wgpu::bytes::from(uniforms) }
+}
+
 fn indices_as_bytes(data: &[u32]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{batch_render_commands, custom::Custom, PipelineId, RenderCommand, Scissor};
+    use crate::glam::Mat4;
+    use crate::wgpu;
+
+    fn pipeline(color_id: u64) -> PipelineId {
+        PipelineId {
+            color_id,
+            alpha_id: 0,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            depth_write: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            texture_sample_type: wgpu::TextureSampleType::Float { filterable: true },
+        }
+    }
+
+    fn draw(start_vertex: i32, index_range: std::ops::Range<u32>) -> RenderCommand {
+        RenderCommand::DrawIndexed {
+            start_vertex,
+            index_range,
+        }
+    }
+
+    // A short description of each command, as `RenderCommand` is not `PartialEq`.
+    fn describe(commands: &[RenderCommand]) -> Vec<String> {
+        commands
+            .iter()
+            .map(|cmd| match cmd {
+                RenderCommand::SetPipeline(id) => format!("pipeline {}", id.color_id),
+                RenderCommand::SetBindGroup(_) => "bind_group".to_string(),
+                RenderCommand::SetNormalMap(_) => "normal_map".to_string(),
+                RenderCommand::SetScissor(s) => format!("scissor {}", s.width),
+                RenderCommand::DrawIndexed {
+                    start_vertex,
+                    index_range,
+                } => format!("draw {} {:?}", start_vertex, index_range),
+                RenderCommand::Custom { .. } => "custom".to_string(),
+                RenderCommand::Scope(_) => "scope".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merges_contiguous_draws() {
+        let commands = vec![
+            RenderCommand::SetPipeline(pipeline(1)),
+            draw(0, 0..3),
+            draw(0, 3..6),
+            draw(0, 9..12),
+            draw(4, 12..15),
+        ];
+        assert_eq!(
+            describe(&batch_render_commands(commands)),
+            ["pipeline 1", "draw 0 0..6", "draw 0 9..12", "draw 4 12..15"],
+        );
+    }
+
+    #[test]
+    fn drops_redundant_state() {
+        let scissor = Scissor {
+            left: 0,
+            bottom: 0,
+            width: 10,
+            height: 10,
+        };
+        let commands = vec![
+            RenderCommand::SetPipeline(pipeline(1)),
+            draw(0, 0..3),
+            // The default normal map is already bound as the pass begins.
+            RenderCommand::SetPipeline(pipeline(1)),
+            RenderCommand::SetNormalMap(None),
+            RenderCommand::SetScissor(scissor),
+            draw(0, 3..6),
+            RenderCommand::SetScissor(scissor),
+            draw(0, 6..9),
+            // Only the last of several pending changes is submitted.
+            RenderCommand::SetPipeline(pipeline(2)),
+            RenderCommand::SetPipeline(pipeline(3)),
+            draw(0, 9..12),
+        ];
+        assert_eq!(
+            describe(&batch_render_commands(commands)),
+            [
+                "pipeline 1",
+                "draw 0 0..3",
+                "scissor 10",
+                "draw 0 3..9",
+                "pipeline 3",
+                "draw 0 9..12",
+            ],
+        );
+    }
+
+    #[test]
+    fn resets_state_after_custom() {
+        let commands = vec![
+            RenderCommand::SetPipeline(pipeline(1)),
+            draw(0, 0..3),
+            RenderCommand::Custom {
+                custom: Custom::new(|_, _| {}),
+                transform: Mat4::IDENTITY,
+            },
+            RenderCommand::SetPipeline(pipeline(1)),
+            draw(0, 3..6),
+        ];
+        assert_eq!(
+            describe(&batch_render_commands(commands)),
+            [
+                "pipeline 1",
+                "draw 0 0..3",
+                "custom",
+                "pipeline 1",
+                "draw 0 3..6",
+            ],
+        );
+    }
+
+    #[test]
+    fn does_not_merge_across_scopes() {
+        let commands = vec![
+            RenderCommand::SetPipeline(pipeline(1)),
+            draw(0, 0..3),
+            RenderCommand::Scope(Some("scope".into())),
+            draw(0, 3..6),
+            RenderCommand::Scope(None),
+            draw(0, 6..9),
+        ];
+        assert_eq!(
+            describe(&batch_render_commands(commands)),
+            [
+                "pipeline 1",
+                "draw 0 0..3",
+                "scope",
+                "draw 0 3..6",
+                "scope",
+                "draw 0 6..9",
+            ],
+        );
+    }
+}
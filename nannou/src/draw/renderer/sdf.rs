@@ -0,0 +1,182 @@
+//! Items related to rendering primitives as screen-space signed distance fields.
+
+use crate::color::LinSrgba;
+use crate::draw;
+use crate::draw::mesh::vertex;
+use crate::draw::primitive::polygon::PolygonOptions;
+use crate::draw::properties::sdf::SdfOptions;
+use crate::draw::renderer::VertexMode;
+use crate::glam::{Mat4, Vec2, Vec3};
+use crate::wgpu;
+use serde_derive::{Deserialize, Serialize};
+
+/// Per-vertex parameters describing the shape that an SDF quad should render.
+///
+/// These are uploaded as an additional vertex buffer alongside the **draw::Mesh** channels.
+/// Vertices that do not belong to an SDF shape use the default value, which is ignored by the
+/// fragment shader.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SdfParams {
+    /// Half the width and height of the shape in local coordinates.
+    pub half_size: [f32; 2],
+    /// The radius used to round the corners of box shapes.
+    pub corner_radius: f32,
+    /// If greater than zero, only an outline of the given weight is rendered.
+    pub stroke_weight: f32,
+    /// The distance over which the edge of the shape fades out.
+    pub softness: f32,
+}
+
+impl SdfParams {
+    /// The vertex attributes describing the layout of **SdfParams** within the vertex buffer.
+    pub const VERTEX_ATTRIBUTES: [wgpu::VertexAttribute; 2] =
+        wgpu::vertex_attr_array![4 => Float32x4, 5 => Float32];
+}
+
+/// The shapes that may be described by a signed distance field.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// An ellipse inscribed within the shape's bounds.
+    Ellipse,
+    /// A box with optionally rounded corners.
+    Box,
+}
+
+impl Shape {
+    /// The vertex mode used to render the shape within the fragment shader.
+    pub fn vertex_mode(&self) -> VertexMode {
+        match *self {
+            Shape::Ellipse => VertexMode::SdfEllipse,
+            Shape::Box => VertexMode::SdfBox,
+        }
+    }
+}
+
+/// Render the given shape as a set of SDF quads.
+///
+/// The shadow and glow (if any) are rendered first, followed by the fill and finally the stroke.
+///
+/// `half_size` is the half of the width and height of the shape in the local coordinate space
+/// described by `transform`. The given `sdf_params` buffer is extended with one element per vertex
+/// pushed to the `mesh`.
+pub fn render_shape(
+    shape: Shape,
+    half_size: Vec2,
+    transform: Mat4,
+    fill_color: Option<LinSrgba>,
+    stroke: Option<(f32, LinSrgba)>,
+    opts: &SdfOptions,
+    sdf_params: &mut Vec<SdfParams>,
+    mesh: &mut draw::Mesh,
+) {
+    let half_size = half_size.abs();
+    if half_size.x <= 0.0 || half_size.y <= 0.0 {
+        return;
+    }
+
+    // Pad each quad so that the anti-aliased edge is never clipped by the quad's bounds.
+    let aa_pad = aa_padding(transform);
+    let corner_radius = match shape {
+        Shape::Ellipse => 0.0,
+        Shape::Box => opts.corner_radius.max(0.0).min(half_size.min_element()),
+    };
+    // Ensure the params buffer is aligned with the mesh before pushing our own vertices.
+    sdf_params.resize(mesh.points().len(), Default::default());
+    let mut quad = |offset: Vec2, stroke_weight: f32, softness: f32, color: LinSrgba| {
+        let params = SdfParams {
+            half_size: half_size.to_array(),
+            corner_radius,
+            stroke_weight,
+            softness,
+        };
+        let pad = stroke_weight * 0.5 + softness + aa_pad;
+        let [hw, hh] = (half_size + Vec2::splat(pad)).to_array();
+        let corners = [[-hw, -hh], [hw, -hh], [hw, hh], [-hw, hh]];
+        let start = mesh.points().len() as u32;
+        for &[x, y] in corners.iter() {
+            let local = Vec2::new(x, y);
+            let point = transform.transform_point3((local + offset).extend(0.0));
+            mesh.push_vertex(vertex::new(point, color, local));
+            sdf_params.push(params);
+        }
+        for &i in [0, 1, 2, 0, 2, 3].iter() {
+            mesh.push_index(start + i);
+        }
+    };
+
+    if let Some(ref shadow) = opts.shadow {
+        quad(shadow.offset, 0.0, shadow.blur.max(0.0), shadow.color);
+    }
+    if let Some(ref glow) = opts.glow {
+        quad(Vec2::ZERO, 0.0, glow.radius.max(0.0), glow.color);
+    }
+    if let Some(color) = fill_color {
+        quad(Vec2::ZERO, 0.0, 0.0, color);
+    }
+    if let Some((weight, color)) = stroke {
+        if weight > 0.0 {
+            quad(Vec2::ZERO, weight, 0.0, color);
+        }
+    }
+}
+
+/// The padding required around an SDF quad so that its anti-aliased edge is not clipped.
+///
+/// The scale of the given transform is accounted for so that the padding is roughly one pixel.
+pub fn aa_padding(transform: Mat4) -> f32 {
+    let scale = transform
+        .transform_vector3(Vec3::X)
+        .length()
+        .min(transform.transform_vector3(Vec3::Y).length());
+    if scale > 0.0 {
+        2.0 / scale
+    } else {
+        0.0
+    }
+}
+
+/// Render a polygon-like primitive (e.g. an ellipse or rect) as an SDF shape.
+///
+/// Colors are resolved from the given polygon options, falling back to the theme's defaults for
+/// the given primitive.
+pub(crate) fn render_polygon(
+    shape: Shape,
+    half_size: Vec2,
+    polygon: PolygonOptions,
+    opts: &SdfOptions,
+    ctxt: draw::renderer::RenderContext,
+    theme_primitive: &draw::theme::Primitive,
+    mesh: &mut draw::Mesh,
+) -> draw::renderer::PrimitiveRender {
+    let PolygonOptions {
+        position,
+        orientation,
+        no_fill,
+        stroke_color,
+        color,
+        stroke,
+        material: _,
+    } = polygon;
+    let transform = *ctxt.transform * position.transform() * orientation.transform();
+    let theme = ctxt.theme;
+    let fill_color = match no_fill {
+        true => None,
+        false => Some(color.unwrap_or_else(|| theme.fill_lin_srgba(theme_primitive))),
+    };
+    let stroke = stroke.map(|stroke| {
+        let color = stroke_color.unwrap_or_else(|| theme.stroke_lin_srgba(theme_primitive));
+        (ctxt.stroke_weight(stroke.line_width, &transform), color)
+    });
+    render_shape(
+        shape,
+        half_size,
+        transform,
+        fill_color,
+        stroke,
+        opts,
+        ctxt.sdf_params_buffer,
+        mesh,
+    );
+    draw::renderer::PrimitiveRender::vertex_mode(shape.vertex_mode())
+}
@@ -0,0 +1,132 @@
+// Prefilters an equirectangular HDR image into the cubemaps of an environment map.
+
+struct Params {
+    // The number of mip levels of the specular cubemap.
+    mip_count: u32,
+    // The number of samples taken per texel.
+    sample_count: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) pos: vec4<f32>,
+    // The position on the cube face, from -1.0 to 1.0 with y pointing down.
+    @location(0) uv: vec2<f32>,
+    // The cube face, along with the mip level in multiples of six.
+    @location(1) @interpolate(flat) layer: u32,
+};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+@group(0) @binding(1)
+var source: texture_2d<f32>;
+
+const PI: f32 = 3.14159265359;
+
+// A triangle covering the target, rendered once per cube face.
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex: u32,
+    @builtin(instance_index) instance: u32,
+) -> VertexOutput {
+    let uv: vec2<f32> = vec2<f32>(f32((vertex << u32(1)) & u32(2)), f32(vertex & u32(2)));
+    let ndc: vec2<f32> = uv * 2.0 - vec2<f32>(1.0);
+    return VertexOutput(vec4<f32>(ndc, 0.0, 1.0), vec2<f32>(ndc.x, -ndc.y), instance);
+}
+
+// The direction through the given position on the given cube face.
+fn face_direction(face: u32, uv: vec2<f32>) -> vec3<f32> {
+    var dir: vec3<f32>;
+    switch (face) {
+        case 0u: { dir = vec3<f32>(1.0, -uv.y, -uv.x); }
+        case 1u: { dir = vec3<f32>(-1.0, -uv.y, uv.x); }
+        case 2u: { dir = vec3<f32>(uv.x, 1.0, uv.y); }
+        case 3u: { dir = vec3<f32>(uv.x, -1.0, -uv.y); }
+        case 4u: { dir = vec3<f32>(uv.x, -uv.y, 1.0); }
+        default: { dir = vec3<f32>(-uv.x, -uv.y, -1.0); }
+    }
+    return normalize(dir);
+}
+
+// Bilinearly sample the source image in the given direction. The centre of the image lies along
+// the negative z axis.
+fn sample_source(dir: vec3<f32>) -> vec3<f32> {
+    let size: vec2<i32> = vec2<i32>(textureDimensions(source));
+    let u: f32 = 0.5 + atan2(dir.x, -dir.z) / (2.0 * PI);
+    let v: f32 = 0.5 - asin(clamp(dir.y, -1.0, 1.0)) / PI;
+    let texel: vec2<f32> = vec2<f32>(u, v) * vec2<f32>(size) - vec2<f32>(0.5);
+    let base: vec2<f32> = floor(texel);
+    let t: vec2<f32> = texel - base;
+    let x0: i32 = (i32(base.x) % size.x + size.x) % size.x;
+    let x1: i32 = (x0 + 1) % size.x;
+    let y0: i32 = clamp(i32(base.y), 0, size.y - 1);
+    let y1: i32 = clamp(i32(base.y) + 1, 0, size.y - 1);
+    let a: vec3<f32> = textureLoad(source, vec2<i32>(x0, y0), 0).xyz;
+    let b: vec3<f32> = textureLoad(source, vec2<i32>(x1, y0), 0).xyz;
+    let c: vec3<f32> = textureLoad(source, vec2<i32>(x0, y1), 0).xyz;
+    let d: vec3<f32> = textureLoad(source, vec2<i32>(x1, y1), 0).xyz;
+    return mix(mix(a, b, t.x), mix(c, d, t.x), t.y);
+}
+
+// The tangent and bitangent of a frame around the given normal.
+fn tangent_frame(n: vec3<f32>) -> mat3x3<f32> {
+    var up: vec3<f32> = vec3<f32>(0.0, 0.0, 1.0);
+    if (abs(n.z) > 0.999) {
+        up = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    let tx: vec3<f32> = normalize(cross(up, n));
+    let ty: vec3<f32> = cross(n, tx);
+    return mat3x3<f32>(tx, ty, n);
+}
+
+// The `i`th of `n` points of the Hammersley sequence.
+fn hammersley(i: u32, n: u32) -> vec2<f32> {
+    return vec2<f32>(f32(i) / f32(n), f32(reverseBits(i)) * 2.3283064365386963e-10);
+}
+
+// The cosine weighted irradiance arriving from the hemisphere around each direction.
+@fragment
+fn fs_irradiance(in: VertexOutput) -> @location(0) vec4<f32> {
+    let n: vec3<f32> = face_direction(in.layer % u32(6), in.uv);
+    let frame: mat3x3<f32> = tangent_frame(n);
+    var sum: vec3<f32> = vec3<f32>(0.0);
+    for (var i: u32 = u32(0); i < params.sample_count; i = i + u32(1)) {
+        // Cosine weighted samples over the hemisphere.
+        let xi: vec2<f32> = hammersley(i, params.sample_count);
+        let phi: f32 = 2.0 * PI * xi.x;
+        let cos_theta: f32 = sqrt(1.0 - xi.y);
+        let sin_theta: f32 = sqrt(xi.y);
+        let l: vec3<f32> = frame * vec3<f32>(sin_theta * cos(phi), sin_theta * sin(phi), cos_theta);
+        sum = sum + sample_source(l);
+    }
+    return vec4<f32>(sum / f32(params.sample_count), 1.0);
+}
+
+// The radiance reflected towards each direction by surfaces of the roughness of the mip level.
+@fragment
+fn fs_specular(in: VertexOutput) -> @location(0) vec4<f32> {
+    let n: vec3<f32> = face_direction(in.layer % u32(6), in.uv);
+    let mip: u32 = in.layer / u32(6);
+    let roughness: f32 = f32(mip) / f32(max(params.mip_count - u32(1), u32(1)));
+    if (mip == u32(0)) {
+        return vec4<f32>(sample_source(n), 1.0);
+    }
+    let a: f32 = roughness * roughness;
+    let frame: mat3x3<f32> = tangent_frame(n);
+    var sum: vec3<f32> = vec3<f32>(0.0);
+    var weight: f32 = 0.0;
+    for (var i: u32 = u32(0); i < params.sample_count; i = i + u32(1)) {
+        // Importance sample the GGX distribution, assuming the view and normal directions match.
+        let xi: vec2<f32> = hammersley(i, params.sample_count);
+        let phi: f32 = 2.0 * PI * xi.x;
+        let cos_theta: f32 = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+        let sin_theta: f32 = sqrt(1.0 - cos_theta * cos_theta);
+        let h: vec3<f32> = frame * vec3<f32>(sin_theta * cos(phi), sin_theta * sin(phi), cos_theta);
+        let l: vec3<f32> = 2.0 * dot(n, h) * h - n;
+        let n_dot_l: f32 = dot(n, l);
+        if (n_dot_l > 0.0) {
+            sum = sum + sample_source(l) * n_dot_l;
+            weight = weight + n_dot_l;
+        }
+    }
+    return vec4<f32>(sum / max(weight, 0.00001), 1.0);
+}
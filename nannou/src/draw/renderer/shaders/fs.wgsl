@@ -2,24 +2,265 @@ struct FragmentOutput {
     @location(0) color: vec4<f32>,
 };
 
+struct Light {
+    // The position of a point light with w of 1.0, or the direction of a directional light.
+    position: vec4<f32>,
+    // The color premultiplied by the intensity, along with the range of point lights in w.
+    color: vec4<f32>,
+    // The shadow map layer (negative if none), depth bias and texel size for soft shadows.
+    shadow: vec4<f32>,
+    // Transforms from world space to the clip space of the light's shadow map.
+    view_proj: mat4x4<f32>,
+};
+
+struct Lights {
+    ambient: vec4<f32>,
+    count: u32,
+    lights: array<Light, 8>,
+    // Rotates directions from world space into the space of the environment map.
+    env_rotation: mat4x4<f32>,
+    // The intensity of the environment map (0.0 if none), the mip level of the roughest
+    // reflections and the tangent of half the vertical field of view of the skybox.
+    env: vec4<f32>,
+};
+
+@group(0) @binding(1)
+var<uniform> lights: Lights;
+@group(0) @binding(2)
+var shadow_maps: texture_depth_2d_array;
+@group(0) @binding(3)
+var shadow_sampler: sampler_comparison;
+@group(0) @binding(4)
+var env_irradiance: texture_cube<f32>;
+@group(0) @binding(5)
+var env_specular: texture_cube<f32>;
+@group(0) @binding(6)
+var env_sampler: sampler;
+
+// The fraction of the given light that reaches the given position.
+fn shadow(l: Light, position: vec3<f32>) -> f32 {
+    if (l.shadow.x < 0.0) {
+        return 1.0;
+    }
+    let p: vec4<f32> = l.view_proj * vec4<f32>(position, 1.0);
+    let ndc: vec3<f32> = p.xyz / p.w;
+    let uv: vec2<f32> = ndc.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    if (any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0) {
+        return 1.0;
+    }
+    let layer: i32 = i32(l.shadow.x);
+    let depth: f32 = ndc.z - l.shadow.y;
+    let texel: f32 = l.shadow.z;
+    if (texel <= 0.0) {
+        return textureSampleCompareLevel(shadow_maps, shadow_sampler, uv, layer, depth);
+    }
+    // Soften the edges by averaging the surrounding texels.
+    var lit: f32 = 0.0;
+    for (var x: i32 = -1; x <= 1; x = x + 1) {
+        for (var y: i32 = -1; y <= 1; y = y + 1) {
+            let offset: vec2<f32> = vec2<f32>(f32(x), f32(y)) * texel;
+            lit = lit + textureSampleCompareLevel(shadow_maps, shadow_sampler, uv + offset, layer, depth);
+        }
+    }
+    return lit / 9.0;
+}
+
 @group(1) @binding(0)
 var text_sampler: sampler;
 @group(1) @binding(1)
 var text: texture_2d<f32>;
+@group(1) @binding(2)
+var color_glyphs: texture_2d<f32>;
 @group(2) @binding(0)
 var tex_sampler: sampler;
 @group(2) @binding(1)
 var tex: texture_2d<f32>;
 
+@group(3) @binding(0)
+var normal_map_sampler: sampler;
+@group(3) @binding(1)
+var normal_map: texture_2d<f32>;
+
+const PI: f32 = 3.14159265359;
+
+// The GGX distribution of microfacet normals.
+fn distribution_ggx(n_dot_h: f32, a: f32) -> f32 {
+    let a2: f32 = a * a;
+    let d: f32 = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+// The height-correlated Smith visibility term, including the BRDF denominator.
+fn visibility_smith_ggx(n_dot_v: f32, n_dot_l: f32, a: f32) -> f32 {
+    let a2: f32 = a * a;
+    let ggx_v: f32 = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - a2) + a2);
+    let ggx_l: f32 = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - a2) + a2);
+    return 0.5 / max(ggx_v + ggx_l, 0.00001);
+}
+
+// The Schlick approximation of the Fresnel reflectance.
+fn fresnel_schlick(f0: vec3<f32>, v_dot_h: f32) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+// Perturb the given normal by the given tangent space normal map sample, deriving the tangent
+// frame from the screen space derivatives of the position and texture coordinates.
+fn perturb_normal(
+    normal: vec3<f32>,
+    dp1: vec3<f32>,
+    dp2: vec3<f32>,
+    duv1: vec2<f32>,
+    duv2: vec2<f32>,
+    sample: vec3<f32>,
+) -> vec3<f32> {
+    let dp2_perp: vec3<f32> = cross(dp2, normal);
+    let dp1_perp: vec3<f32> = cross(normal, dp1);
+    let t: vec3<f32> = dp2_perp * duv1.x + dp1_perp * duv2.x;
+    let b: vec3<f32> = dp2_perp * duv1.y + dp1_perp * duv2.y;
+    let len2: f32 = max(dot(t, t), dot(b, b));
+    if (len2 <= 0.0) {
+        return normal;
+    }
+    // Texture coordinates increase downwards, whereas green points up the image.
+    let s: f32 = inverseSqrt(len2);
+    let n: vec3<f32> = (t * sample.x - b * sample.y) * s + normal * sample.z;
+    return n / max(length(n), 0.00001);
+}
+
+// An analytic approximation of the scale and bias applied to F0 by the split-sum approximation
+// of the specular reflection of the environment.
+fn env_brdf_approx(f0: vec3<f32>, roughness: f32, n_dot_v: f32) -> vec3<f32> {
+    let c0: vec4<f32> = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1: vec4<f32> = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r: vec4<f32> = roughness * c0 + c1;
+    let a004: f32 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let ab: vec2<f32> = vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+    return f0 * ab.x + vec3<f32>(ab.y);
+}
+
+// The given world space direction within the space of the environment map.
+fn env_direction(dir: vec3<f32>) -> vec3<f32> {
+    return (lights.env_rotation * vec4<f32>(dir, 0.0)).xyz;
+}
+
+// Illuminate the given base color at the given position by the lights using a metallic-roughness
+// model, before adding the emitted light.
+fn shade(
+    color: vec4<f32>,
+    position: vec3<f32>,
+    normal: vec3<f32>,
+    metallic_roughness: vec2<f32>,
+    emissive: vec3<f32>,
+) -> vec4<f32> {
+    let metallic: f32 = metallic_roughness.x;
+    let roughness: f32 = clamp(metallic_roughness.y, 0.045, 1.0);
+    let a: f32 = roughness * roughness;
+    let f0: vec3<f32> = mix(vec3<f32>(0.04), color.xyz, metallic);
+    let diffuse_color: vec3<f32> = color.xyz * (1.0 - metallic);
+    // The projection is orthographic, so the viewer looks down the z axis.
+    let v: vec3<f32> = vec3<f32>(0.0, 0.0, 1.0);
+    let n_dot_v: f32 = max(dot(normal, v), 0.0001);
+    var out: vec3<f32> = lights.ambient.xyz * (diffuse_color + f0);
+    // The diffuse light and reflections of the environment.
+    if (lights.env.x > 0.0) {
+        let r: vec3<f32> = reflect(-v, normal);
+        let lod: f32 = roughness * lights.env.y;
+        let irradiance: vec3<f32> =
+            textureSampleLevel(env_irradiance, env_sampler, env_direction(normal), 0.0).xyz;
+        let reflection: vec3<f32> =
+            textureSampleLevel(env_specular, env_sampler, env_direction(r), lod).xyz;
+        let specular: vec3<f32> = reflection * env_brdf_approx(f0, roughness, n_dot_v);
+        out = out + (diffuse_color * irradiance + specular) * lights.env.x;
+    }
+    for (var i: u32 = u32(0); i < lights.count; i = i + u32(1)) {
+        let l: Light = lights.lights[i];
+        var dir: vec3<f32>;
+        var attenuation: f32 = 1.0;
+        if (l.position.w > 0.5) {
+            let to_light: vec3<f32> = l.position.xyz - position;
+            let dist: f32 = length(to_light);
+            dir = to_light / max(dist, 0.00001);
+            if (l.color.w > 0.0) {
+                let r: f32 = clamp(1.0 - pow(dist / l.color.w, 2.0), 0.0, 1.0);
+                attenuation = r * r;
+            }
+        } else {
+            dir = -normalize(l.position.xyz);
+        }
+        let n_dot_l: f32 = max(dot(normal, dir), 0.0);
+        if (n_dot_l > 0.0) {
+            let h: vec3<f32> = normalize(dir + v);
+            let n_dot_h: f32 = max(dot(normal, h), 0.0);
+            let v_dot_h: f32 = max(dot(v, h), 0.0);
+            let f: vec3<f32> = fresnel_schlick(f0, v_dot_h);
+            let d: f32 = distribution_ggx(n_dot_h, a);
+            let vis: f32 = visibility_smith_ggx(n_dot_v, n_dot_l, a);
+            // Scaled by PI so that a white light of intensity 1.0 fully lights a white surface.
+            let specular: vec3<f32> = f * (d * vis * PI);
+            let diffuse: vec3<f32> = diffuse_color * (vec3<f32>(1.0) - f);
+            let radiance: vec3<f32> = l.color.xyz * (n_dot_l * attenuation * shadow(l, position));
+            out = out + (diffuse + specular) * radiance;
+        }
+    }
+    return vec4<f32>(out + emissive, color.w);
+}
+
+// Approximate signed distance to an ellipse with the given radii.
+fn sdf_ellipse(p: vec2<f32>, radii: vec2<f32>) -> f32 {
+    let k0: f32 = length(p / radii);
+    let k1: f32 = length(p / (radii * radii));
+    if (k1 == 0.0) {
+        return -min(radii.x, radii.y);
+    }
+    return k0 * (k0 - 1.0) / k1;
+}
+
+// Signed distance to a box with the given half size and corner radius.
+fn sdf_box(p: vec2<f32>, half_size: vec2<f32>, radius: f32) -> f32 {
+    let q: vec2<f32> = abs(p) - half_size + vec2<f32>(radius);
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
 @fragment
 fn main(
     @location(0) color: vec4<f32>,
     @location(1) tex_coords: vec2<f32>,
     @location(2) mode: u32,
+    @location(3) sdf_shape: vec4<f32>,
+    @location(4) sdf_softness: f32,
+    @location(5) position: vec3<f32>,
+    @location(6) lit: u32,
+    @location(7) normal_mapped: u32,
+    @location(8) metallic_roughness: vec2<f32>,
+    @location(9) emissive: vec3<f32>,
+    @builtin(position) frag_pos: vec4<f32>,
 ) -> FragmentOutput {
     let tex_color: vec4<f32> = textureSample(tex, tex_sampler, tex_coords);
     let text_color: vec4<f32> = textureSample(text, text_sampler, tex_coords);
     let text_alpha: f32 = text_color.x;
+    // The texture coordinates of color glyphs are mirrored into the negative range, i.e. `x`
+    // becomes `-1.0 - x`, to distinguish them from glyphs within the glyph cache.
+    let color_glyph_coords: vec2<f32> = vec2<f32>(-1.0 - tex_coords.x, tex_coords.y);
+    let color_glyph: vec4<f32> = textureSample(color_glyphs, text_sampler, color_glyph_coords);
+    // The size of a pixel in the local space of SDF shapes. Derivatives must be computed within
+    // uniform control flow, so we do so here regardless of the mode.
+    let sdf_px: f32 = max(length(fwidth(tex_coords)) * 0.7071, 0.00001);
+    let stroke_px: f32 = max(fwidth(tex_coords.x), 0.00001);
+    // The flat normal of the surface for lighting and the normals debug view. The y axis of the framebuffer
+    // points down, so the operands are swapped to produce a right-handed normal.
+    let dp1: vec3<f32> = dpdx(position);
+    let dp2: vec3<f32> = -dpdy(position);
+    let face_normal: vec3<f32> = cross(dpdy(position), dpdx(position));
+    var normal: vec3<f32> = face_normal / max(length(face_normal), 0.00001);
+    // Sample the normal map regardless of whether or not there is one to stay within uniform
+    // control flow.
+    let duv1: vec2<f32> = dpdx(tex_coords);
+    let duv2: vec2<f32> = -dpdy(tex_coords);
+    let normal_sample: vec3<f32> = textureSample(normal_map, normal_map_sampler, tex_coords).xyz;
+    if (normal_mapped != u32(0)) {
+        let sample: vec3<f32> = normal_sample * 2.0 - vec3<f32>(1.0);
+        normal = perturb_normal(normal, dp1, dp2, duv1, duv2, sample);
+    }
     var out_color: vec4<f32>;
     if (mode == u32(0)) {
         out_color = color;
@@ -28,11 +269,76 @@ fn main(
             out_color = tex_color;
         } else {
             if (mode == u32(2)) {
-                out_color = vec4<f32>(color.xyz, color.w * text_alpha);
+                if (tex_coords.x < 0.0) {
+                    out_color = vec4<f32>(color_glyph.xyz, color.w * color_glyph.w);
+                } else {
+                    out_color = vec4<f32>(color.xyz, color.w * text_alpha);
+                }
             } else {
-                out_color = vec4<f32>(1.0, 0.0, 0.0, 1.0);
+                if (mode == u32(3) || mode == u32(4)) {
+                    var d: f32;
+                    if (mode == u32(3)) {
+                        d = sdf_ellipse(tex_coords, sdf_shape.xy);
+                    } else {
+                        d = sdf_box(tex_coords, sdf_shape.xy, sdf_shape.z);
+                    }
+                    // Outlines are described by the distance to the edge.
+                    if (sdf_shape.w > 0.0) {
+                        d = abs(d) - sdf_shape.w * 0.5;
+                    }
+                    let edge: f32 = sdf_px * 0.5;
+                    let coverage: f32 = 1.0 - smoothstep(-edge, sdf_softness + edge, d);
+                    out_color = vec4<f32>(color.xyz, color.w * coverage);
+                } else {
+                    if (mode == u32(5)) {
+                        // Fade out the outermost pixel of the stroke, which was widened during
+                        // tessellation to make room for it.
+                        var coverage: f32 = 1.0;
+                        if (tex_coords.y > 0.0) {
+                            let d: f32 = tex_coords.y - abs(tex_coords.x);
+                            coverage = clamp(d / stroke_px, 0.0, 1.0);
+                        }
+                        out_color = vec4<f32>(color.xyz, color.w * coverage);
+                    } else {
+                        if (mode == u32(6)) {
+                            out_color = tex_color * color;
+                        } else {
+                            if (mode == u32(7)) {
+                                out_color = vec4<f32>(normal * 0.5 + vec3<f32>(0.5), 1.0);
+                            } else {
+                                if (mode == u32(8)) {
+                                    out_color = vec4<f32>(tex_coords, 0.0, 1.0);
+                                } else {
+                                    if (mode == u32(9)) {
+                                        out_color = vec4<f32>(vec3<f32>(1.0 - frag_pos.z), 1.0);
+                                    } else {
+                                        if (mode == u32(10)) {
+                                            // The texture coordinates are scaled such that the
+                                            // top of the target lies at a y of 1.0.
+                                            let tan_half_fov: f32 = lights.env.z;
+                                            let xy: vec2<f32> = tex_coords * tan_half_fov;
+                                            let dir: vec3<f32> = normalize(vec3<f32>(xy, -1.0));
+                                            let env: vec3<f32> = textureSampleLevel(
+                                                env_specular,
+                                                env_sampler,
+                                                env_direction(dir),
+                                                0.0,
+                                            ).xyz;
+                                            out_color = vec4<f32>(env * lights.env.x, 1.0);
+                                        } else {
+                                            out_color = vec4<f32>(1.0, 0.0, 0.0, 1.0);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
             }
         }
     }
+    if (lit != u32(0)) {
+        out_color = shade(out_color, position, normal, metallic_roughness, emissive);
+    }
     return FragmentOutput(out_color);
 }
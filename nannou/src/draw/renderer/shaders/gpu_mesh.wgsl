@@ -0,0 +1,34 @@
+// Renders a mesh by pulling each vertex from a storage buffer.
+
+struct GpuVertex {
+    position: vec4<f32>,
+    color: vec4<f32>,
+};
+
+struct Uniforms {
+    transform: mat4x4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+@group(0) @binding(1)
+var<storage, read> vertices: array<GpuVertex>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let v = vertices[vertex_index];
+    var out: VertexOutput;
+    out.position = uniforms.transform * vec4<f32>(v.position.xyz, 1.0);
+    out.color = v.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,24 @@
+struct Light {
+    position: vec4<f32>,
+    color: vec4<f32>,
+    shadow: vec4<f32>,
+    view_proj: mat4x4<f32>,
+};
+
+struct Lights {
+    ambient: vec4<f32>,
+    count: u32,
+    lights: array<Light, 8>,
+};
+
+@group(0) @binding(0)
+var<uniform> lights: Lights;
+
+// Renders lit primitives into the shadow map of the light selected by the instance index.
+@vertex
+fn main(
+    @location(0) position: vec3<f32>,
+    @builtin(instance_index) light: u32,
+) -> @builtin(position) vec4<f32> {
+    return lights.lights[light].view_proj * vec4<f32>(position, 1.0);
+}
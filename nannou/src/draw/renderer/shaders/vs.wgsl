@@ -6,6 +6,13 @@ struct VertexOutput {
     @location(0) color: vec4<f32>,
     @location(1) tex_coords: vec2<f32>,
     @location(2) mode: u32,
+    @location(3) sdf_shape: vec4<f32>,
+    @location(4) sdf_softness: f32,
+    @location(5) position: vec3<f32>,
+    @location(6) lit: u32,
+    @location(7) normal_map: u32,
+    @location(8) metallic_roughness: vec2<f32>,
+    @location(9) emissive: vec3<f32>,
     @builtin(position) pos: vec4<f32>,
 };
 
@@ -18,7 +25,25 @@ fn main(
     @location(1) color: vec4<f32>,
     @location(2) tex_coords: vec2<f32>,
     @location(3) mode: u32,
+    @location(4) sdf_shape: vec4<f32>,
+    @location(5) sdf_softness: f32,
+    @location(6) lit: u32,
+    @location(7) normal_map: u32,
+    @location(8) metallic_roughness: vec2<f32>,
+    @location(9) emissive: vec3<f32>,
 ) -> VertexOutput {
     let out_pos: vec4<f32> = uniforms.proj * vec4<f32>(position, 1.0);
-    return VertexOutput(color, tex_coords, mode, out_pos);
+    return VertexOutput(
+        color,
+        tex_coords,
+        mode,
+        sdf_shape,
+        sdf_softness,
+        position,
+        lit,
+        normal_map,
+        metallic_roughness,
+        emissive,
+        out_pos,
+    );
 }
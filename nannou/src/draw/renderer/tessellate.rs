@@ -0,0 +1,282 @@
+//! Tessellation of primitives ahead of the generation of render commands.
+//!
+//! When the `parallel` feature is enabled, primitives that may be tessellated independently of
+//! one another (i.e. those described by shapes and paths rather than text) are tessellated across
+//! the threads of the rayon thread pool. Each primitive is tessellated into its own mesh, which is
+//! then stitched into the renderer's mesh in the order in which the primitives were submitted.
+//!
+//! `tessellate_draw` tessellates each primitive of a **Draw** into its own mesh on the CPU
+//! without draining its commands, e.g. for recording, caching and headless output.
+
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::material::Material;
+use crate::draw::renderer::cull;
+use crate::draw::renderer::SdfParams;
+use crate::draw::renderer::{GlyphCache, PrimitiveRender, RenderContext, RenderPrimitive};
+use crate::draw::vector::VectorPath;
+use crate::draw::{self, Draw, DrawCommand};
+use crate::geom;
+use crate::glam::Vec2;
+use lyon::tessellation::{FillTessellator, StrokeTessellator};
+
+/// The minimum number of independent primitives within a **Draw** for which tessellation is
+/// spread across threads. Below this, the cost of distributing the work outweighs the gain.
+pub const MIN_PARALLEL_PRIMITIVES: usize = 64;
+
+/// A draw command, or a primitive that has already been tessellated.
+pub(crate) enum Command {
+    Draw(DrawCommand),
+    Tessellated(Tessellated),
+}
+
+/// A primitive tessellated into its own mesh.
+pub(crate) struct Tessellated {
+    pub material: Option<Material>,
+    pub mesh: draw::Mesh,
+    /// The SDF params of each vertex, or empty if the primitive is not an SDF shape.
+    pub sdf_params: Vec<SdfParams>,
+    pub render: PrimitiveRender,
+}
+
+/// The state shared by all primitives during tessellation.
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+pub(crate) struct Shared<'a> {
+    pub intermediary_state: &'a draw::IntermediaryState,
+    pub theme: &'a draw::Theme,
+    pub output_attachment_size: Vec2,
+    pub output_attachment_scale_factor: f32,
+}
+
+impl Tessellated {
+    /// Append the tessellated vertices and indices to the given mesh, along with the SDF params
+    /// of each vertex.
+    ///
+    /// The SDF params buffer must be aligned with the vertices of the given mesh.
+    pub fn stitch(
+        self,
+        mesh: &mut draw::Mesh,
+        sdf_params_buffer: &mut Vec<SdfParams>,
+    ) -> (Option<Material>, PrimitiveRender) {
+        let Tessellated {
+            material,
+            mesh: tessellated,
+            mut sdf_params,
+            render,
+        } = self;
+        let offset = mesh.raw_vertex_count() as u32;
+        mesh.extend_vertices_from_slices(
+            tessellated.points(),
+            tessellated.colors(),
+            tessellated.tex_coords(),
+        );
+        mesh.extend_indices(tessellated.indices().iter().map(|&i| offset + i));
+        sdf_params.resize(tessellated.raw_vertex_count(), SdfParams::default());
+        sdf_params_buffer.extend(sdf_params);
+        (material, render)
+    }
+}
+
+/// Tessellate each primitive submitted to the given **Draw** into its own mesh on the CPU, in
+/// the order in which they are drawn.
+///
+/// Contexts are yielded as `Command::Draw` and primitives as `Command::Tessellated`. Primitives
+/// for which `skip` returns `true` and custom commands are omitted. When `sdf` is `false`,
+/// primitives that would be rendered as signed distance fields are tessellated from their paths
+/// instead. When `vector_paths` is `Some`, path-based primitives push their source paths rather
+/// than being tessellated. When `cull_rect` is `Some`, primitives drawn via `Draw::cull` are culled
+/// as though rendering to an output attachment spanning that rect.
+///
+/// Unlike rendering via the **draw::Renderer**, the **Draw** is not drained of its commands.
+pub(crate) fn tessellate_draw<F>(
+    draw: &Draw,
+    skip: F,
+    sdf: bool,
+    mut vector_paths: Option<&mut Vec<VectorPath>>,
+    cull_rect: Option<geom::Rect>,
+) -> Vec<Command>
+where
+    F: Fn(&Primitive) -> bool,
+{
+    draw.finish_remaining_drawings();
+    let draw_cmds: Vec<_> = draw
+        .state
+        .borrow()
+        .draw_commands
+        .iter()
+        .filter_map(|cmd| cmd.clone())
+        .collect();
+    let draw_cmds = draw::sort_by_z_index(draw_cmds);
+    let draw_cmds = match cull_rect {
+        Some(rect) => cull::cull(draw_cmds, rect),
+        None => draw_cmds,
+    };
+    let draw_state = draw.state.borrow();
+    let intermediary_state = draw_state.intermediary_state.borrow();
+
+    let mut fill_tessellator = FillTessellator::new();
+    let mut stroke_tessellator = StrokeTessellator::new();
+    let mut glyph_cache = GlyphCache::new([1, 1], 1.0, 1.0);
+    let mut curr_ctxt = draw::Context::default();
+    let mut commands = vec![];
+    for cmd in draw_cmds {
+        let mut prim = match cmd {
+            DrawCommand::Context(ctxt) => {
+                curr_ctxt = ctxt.clone();
+                commands.push(Command::Draw(DrawCommand::Context(ctxt)));
+                continue;
+            }
+            DrawCommand::Primitive(prim) => prim,
+            DrawCommand::Custom(_) => continue,
+        };
+        if skip(&prim) {
+            continue;
+        }
+        let mut mesh = draw::Mesh::default();
+        let mut sdf_params = vec![];
+        let ctxt = RenderContext {
+            intermediary_mesh: &intermediary_state.intermediary_mesh,
+            path_event_buffer: &intermediary_state.path_event_buffer,
+            path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+            path_points_textured_buffer: &intermediary_state.path_points_textured_buffer,
+            text_buffer: &intermediary_state.text_buffer,
+            theme: &draw_state.theme,
+            transform: &curr_ctxt.transform,
+            fill_tessellator: &mut fill_tessellator,
+            stroke_tessellator: &mut stroke_tessellator,
+            sdf_params_buffer: &mut sdf_params,
+            glyph_cache: &mut glyph_cache,
+            output_attachment_size: Vec2::ONE,
+            output_attachment_scale_factor: 1.0,
+            analytic_aa: curr_ctxt.analytic_aa && sdf,
+            sdf,
+            vector_paths: vector_paths.as_deref_mut(),
+        };
+        let material = prim.take_material();
+        let render = prim.render_primitive(ctxt, &mut mesh);
+        if mesh.indices().is_empty() {
+            continue;
+        }
+        let tessellated = Tessellated {
+            material,
+            mesh,
+            sdf_params,
+            render,
+        };
+        commands.push(Command::Tessellated(tessellated));
+    }
+    commands
+}
+
+/// Tessellate the independent primitives among the given commands ahead of time when doing so
+/// may be spread across threads.
+#[cfg(feature = "parallel")]
+pub(crate) fn tessellate(cmds: Vec<DrawCommand>, shared: &Shared) -> Vec<Command> {
+    use crate::glam::Mat4;
+    use rayon::prelude::*;
+
+    let count = cmds
+        .iter()
+        .filter(|cmd| matches!(cmd, DrawCommand::Primitive(prim) if is_independent(prim)))
+        .count();
+    if count < MIN_PARALLEL_PRIMITIVES {
+        return cmds.into_iter().map(Command::Draw).collect();
+    }
+
+    // Separate the independent primitives along with the context in which each was submitted.
+    let mut commands = Vec::with_capacity(cmds.len());
+    let mut jobs = Vec::with_capacity(count);
+    let (mut transform, mut analytic_aa) = (Mat4::IDENTITY, false);
+    for cmd in cmds {
+        match cmd {
+            DrawCommand::Context(ref ctxt) => {
+                transform = ctxt.transform;
+                analytic_aa = ctxt.analytic_aa;
+                commands.push(Some(Command::Draw(cmd)));
+            }
+            DrawCommand::Primitive(prim) if is_independent(&prim) => {
+                jobs.push((commands.len(), transform, analytic_aa, prim));
+                commands.push(None);
+            }
+            cmd => commands.push(Some(Command::Draw(cmd))),
+        }
+    }
+
+    // Tessellate each primitive into its own mesh, re-using tessellators on each thread.
+    let tessellated: Vec<_> = jobs
+        .into_par_iter()
+        .map_init(
+            || {
+                let glyph_cache = GlyphCache::new([1, 1], 1.0, 1.0);
+                (
+                    FillTessellator::new(),
+                    StrokeTessellator::new(),
+                    glyph_cache,
+                )
+            },
+            |(fill_tessellator, stroke_tessellator, glyph_cache), job| {
+                let (index, transform, analytic_aa, mut prim) = job;
+                let mut mesh = draw::Mesh::default();
+                let mut sdf_params = vec![];
+                let intermediary_state = shared.intermediary_state;
+                let ctxt = RenderContext {
+                    intermediary_mesh: &intermediary_state.intermediary_mesh,
+                    path_event_buffer: &intermediary_state.path_event_buffer,
+                    path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+                    path_points_textured_buffer: &intermediary_state.path_points_textured_buffer,
+                    text_buffer: &intermediary_state.text_buffer,
+                    theme: shared.theme,
+                    transform: &transform,
+                    fill_tessellator,
+                    stroke_tessellator,
+                    sdf_params_buffer: &mut sdf_params,
+                    glyph_cache,
+                    output_attachment_size: shared.output_attachment_size,
+                    output_attachment_scale_factor: shared.output_attachment_scale_factor,
+                    analytic_aa,
+                    sdf: true,
+                    vector_paths: None,
+                };
+                let material = prim.take_material();
+                let render = prim.render_primitive(ctxt, &mut mesh);
+                let tessellated = Tessellated {
+                    material,
+                    mesh,
+                    sdf_params,
+                    render,
+                };
+                (index, tessellated)
+            },
+        )
+        .collect();
+
+    for (index, tessellated) in tessellated {
+        commands[index] = Some(Command::Tessellated(tessellated));
+    }
+    commands.into_iter().flatten().collect()
+}
+
+/// Without the `parallel` feature, all primitives are tessellated while generating commands.
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn tessellate(cmds: Vec<DrawCommand>, _shared: &Shared) -> Vec<Command> {
+    cmds.into_iter().map(Command::Draw).collect()
+}
+
+// Whether the primitive may be tessellated independently of the state shared between primitives,
+// e.g. the glyph cache.
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+fn is_independent(prim: &Primitive) -> bool {
+    matches!(
+        prim,
+        Primitive::Arrow(_)
+            | Primitive::Brush(_)
+            | Primitive::Ellipse(_)
+            | Primitive::Hexagon(_)
+            | Primitive::Line(_)
+            | Primitive::Path(_)
+            | Primitive::Polygon(_)
+            | Primitive::Quad(_)
+            | Primitive::Rect(_)
+            | Primitive::Tri(_)
+            | Primitive::TurtlePath(_)
+    )
+}
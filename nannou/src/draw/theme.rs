@@ -24,17 +24,24 @@ pub struct Color {
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub enum Primitive {
     Arrow,
+    Brush,
+    Contours,
     Cuboid,
+    Custom,
     Ellipse,
+    Hexagon,
     Line,
     Mesh,
     Path,
     Polygon,
     Quad,
     Rect,
+    Spectrogram,
     Text,
     Texture,
     Tri,
+    VectorField,
+    Waveform,
 }
 
 impl Theme {
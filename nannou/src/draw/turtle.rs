@@ -0,0 +1,246 @@
+//! A turtle-graphics API for tracing lines with a **Draw**.
+//!
+//! A **Turtle** walks about the plane with a pen, tracing a line behind it whenever the pen is
+//! down. Consecutive moves with the same pen are joined into a single polyline. This is a friendly
+//! way to begin drawing and is well suited to recursive patterns like fractals and L-systems.
+//!
+//! ```no_run
+//! # use nannou::prelude::*;
+//! # fn view(app: &App, frame: Frame) {
+//! let draw = app.draw();
+//! let t = draw.turtle();
+//! t.pen_color(STEELBLUE).pen_weight(4.0);
+//! for _ in 0..4 {
+//!     t.forward(200.0).turn_degrees(90.0);
+//! }
+//! draw.to_frame(app, &frame).unwrap();
+//! # }
+//! ```
+
+use crate::color::{IntoLinSrgba, LinSrgba};
+use crate::draw::primitive::{Primitive, TurtlePath};
+use crate::draw::properties::ColorScalar;
+use crate::draw::Draw;
+use crate::geom::{pt2, Point2};
+use crate::math::{deg_to_rad, turns_to_rad};
+use std::cell::RefCell;
+
+/// The default weight of the line traced by a **Turtle**.
+pub const DEFAULT_PEN_WEIGHT: f32 = 1.0;
+
+/// Traces lines with a **Draw** by moving and turning.
+///
+/// Create a **Turtle** via `draw.turtle()`. All methods take `&self` and return `&Self` so that
+/// calls may be chained.
+///
+/// The traced lines are submitted to the **Draw** as they are drawn, so there is no need to
+/// drop the **Turtle** before rendering the **Draw**.
+#[derive(Debug)]
+pub struct Turtle {
+    draw: Draw,
+    state: RefCell<State>,
+}
+
+/// The pen with which a **Turtle** traces lines.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pen {
+    /// Whether or not the pen is touching the canvas.
+    pub down: bool,
+    /// The color of the line, or `None` to use the **Draw**'s theme.
+    pub color: Option<LinSrgba>,
+    /// The weight of the line.
+    pub weight: f32,
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    position: Point2,
+    heading: f32,
+    pen: Pen,
+    // The position, heading and pen stored by each call to `push`.
+    stack: Vec<(Point2, f32, Pen)>,
+    // The index of the path currently being traced, if any.
+    path: Option<usize>,
+}
+
+impl Turtle {
+    // Create a turtle at the origin heading along the x axis.
+    pub(crate) fn new(draw: Draw) -> Self {
+        let state = State {
+            position: pt2(0.0, 0.0),
+            heading: 0.0,
+            pen: Pen::default(),
+            stack: vec![],
+            path: None,
+        };
+        Turtle {
+            draw,
+            state: RefCell::new(state),
+        }
+    }
+
+    /// The current position.
+    pub fn position(&self) -> Point2 {
+        self.state.borrow().position
+    }
+
+    /// The current heading in radians, measured counter-clockwise from the *x* axis.
+    pub fn heading(&self) -> f32 {
+        self.state.borrow().heading
+    }
+
+    /// The current pen.
+    pub fn pen(&self) -> Pen {
+        self.state.borrow().pen
+    }
+
+    /// Move forward by the given distance along the current heading.
+    pub fn forward(&self, distance: f32) -> &Self {
+        let target = {
+            let state = self.state.borrow();
+            let (sin, cos) = state.heading.sin_cos();
+            state.position + pt2(cos, sin) * distance
+        };
+        self.goto(target)
+    }
+
+    /// Move backward by the given distance, without changing the heading.
+    pub fn back(&self, distance: f32) -> &Self {
+        self.forward(-distance)
+    }
+
+    /// Move to the given position, tracing a line if the pen is down.
+    ///
+    /// The heading is unchanged.
+    pub fn goto(&self, position: Point2) -> &Self {
+        let mut state = self.state.borrow_mut();
+        if state.pen.down && position != state.position {
+            let extended = state
+                .path
+                .is_some_and(|index| self.extend_path(index, state.position, position));
+            if !extended {
+                let path = TurtlePath::new(
+                    vec![state.position, position],
+                    state.pen.color,
+                    state.pen.weight,
+                );
+                state.path = Some(self.draw.insert_drawing(path.into()));
+            }
+        }
+        state.position = position;
+        self
+    }
+
+    /// Turn counter-clockwise by the given angle in radians.
+    ///
+    /// Negative angles turn clockwise.
+    pub fn turn(&self, radians: f32) -> &Self {
+        self.state.borrow_mut().heading += radians;
+        self
+    }
+
+    /// Turn counter-clockwise by the given angle in degrees.
+    pub fn turn_degrees(&self, degrees: f32) -> &Self {
+        self.turn(deg_to_rad(degrees))
+    }
+
+    /// Turn counter-clockwise by the given number of turns.
+    pub fn turn_turns(&self, turns: f32) -> &Self {
+        self.turn(turns_to_rad(turns))
+    }
+
+    /// Set the heading to the given angle in radians, measured counter-clockwise from the *x*
+    /// axis.
+    pub fn set_heading(&self, radians: f32) -> &Self {
+        self.state.borrow_mut().heading = radians;
+        self
+    }
+
+    /// Lift the pen so that subsequent moves do not trace a line.
+    pub fn pen_up(&self) -> &Self {
+        self.set_pen(|pen| pen.down = false)
+    }
+
+    /// Lower the pen so that subsequent moves trace a line.
+    pub fn pen_down(&self) -> &Self {
+        self.set_pen(|pen| pen.down = true)
+    }
+
+    /// Set the color of subsequent lines.
+    pub fn pen_color<C>(&self, color: C) -> &Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = self.draw.linear_color(color);
+        self.set_pen(|pen| pen.color = Some(color))
+    }
+
+    /// Set the weight of subsequent lines.
+    pub fn pen_weight(&self, weight: f32) -> &Self {
+        self.set_pen(|pen| pen.weight = weight)
+    }
+
+    /// Save the current position, heading and pen so that they may be restored via `pop`.
+    ///
+    /// This is useful for drawing branching structures like trees.
+    pub fn push(&self) -> &Self {
+        let mut state = self.state.borrow_mut();
+        let saved = (state.position, state.heading, state.pen);
+        state.stack.push(saved);
+        self
+    }
+
+    /// Restore the position, heading and pen most recently saved via `push`.
+    ///
+    /// Does nothing if there is no saved state.
+    pub fn pop(&self) -> &Self {
+        let mut state = self.state.borrow_mut();
+        if let Some((position, heading, pen)) = state.stack.pop() {
+            state.position = position;
+            state.heading = heading;
+            state.pen = pen;
+            state.path = None;
+        }
+        self
+    }
+
+    // Apply the given change to the pen, beginning a new path if the pen changed.
+    fn set_pen<F>(&self, f: F) -> &Self
+    where
+        F: FnOnce(&mut Pen),
+    {
+        let mut state = self.state.borrow_mut();
+        let mut pen = state.pen;
+        f(&mut pen);
+        if pen != state.pen {
+            state.pen = pen;
+            state.path = None;
+        }
+        self
+    }
+
+    // Extend the path at the given index if it is still being drawn and ends at `from`.
+    //
+    // The path is finished when the **Draw** is rendered or reset, in which case a new path is
+    // required.
+    fn extend_path(&self, index: usize, from: Point2, to: Point2) -> bool {
+        let mut state = self.draw.state.borrow_mut();
+        match state.drawing.get_mut(&index) {
+            Some(Primitive::TurtlePath(path)) if path.points.last() == Some(&from) => {
+                path.points.push(to);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Default for Pen {
+    fn default() -> Self {
+        Pen {
+            down: true,
+            color: None,
+            weight: DEFAULT_PEN_WEIGHT,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Items related to recovering the source paths of the primitives submitted to a **Draw**.
+//!
+//! The **draw::Renderer** tessellates each primitive into triangles, discarding the curves that
+//! describe it. Vector outputs such as SVG, PDF or pen plotters are better served by the original
+//! paths, so `paths` walks the commands of a **Draw** and collects the path describing each
+//! primitive, transformed into the coordinate space of the **Draw** along with its color and
+//! tessellation options.
+//!
+//! Only primitives that are described by paths are collected. **Text**, **Texture** and **Mesh**
+//! primitives, along with textured paths and polygons, are skipped. Primitives that would
+//! otherwise be rendered as signed distance fields are collected as regular paths.
+
+use crate::color::LinSrgba;
+use crate::draw::primitive::Primitive;
+use crate::draw::renderer::tessellate;
+use crate::draw::{self, Draw};
+use crate::glam::{Mat4, Vec2};
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillOptions, StrokeOptions};
+use std::fmt::Write;
+
+/// The source path of a primitive along with the style with which it is drawn.
+#[derive(Clone, Debug)]
+pub struct VectorPath {
+    /// The path in the coordinate space of the **Draw**, with the origin at the centre and the
+    /// y axis pointing upwards.
+    pub path: lyon::path::Path,
+    /// Whether the path is filled or stroked, and with what color.
+    pub style: Style,
+}
+
+/// Describes how a **VectorPath** is drawn.
+#[derive(Clone, Debug)]
+pub enum Style {
+    /// The area enclosed by the path is filled.
+    Fill {
+        color: LinSrgba,
+        options: FillOptions,
+    },
+    /// The outline of the path is stroked.
+    ///
+    /// The `line_width` of the options is scaled by the transform of the primitive.
+    Stroke {
+        color: LinSrgba,
+        options: StrokeOptions,
+    },
+}
+
+impl VectorPath {
+    // Produce a vector path by transforming the given events.
+    pub(crate) fn from_events<I>(
+        events: I,
+        transform: Mat4,
+        options: draw::primitive::path::Options,
+        color: LinSrgba,
+    ) -> Self
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        let t = lyon::math::Transform::new(
+            transform.x_axis.x,
+            transform.x_axis.y,
+            transform.y_axis.x,
+            transform.y_axis.y,
+            transform.w_axis.x,
+            transform.w_axis.y,
+        );
+        let path = events.into_iter().map(|e| e.transformed(&t)).collect();
+        let style = match options {
+            draw::primitive::path::Options::Fill(options) => Style::Fill { color, options },
+            draw::primitive::path::Options::Stroke(mut options) => {
+                let scale =
+                    transform.x_axis.truncate().length() * transform.y_axis.truncate().length();
+                options.line_width *= scale.sqrt();
+                Style::Stroke { color, options }
+            }
+        };
+        VectorPath { path, style }
+    }
+
+    /// The color with which the path is filled or stroked.
+    pub fn color(&self) -> LinSrgba {
+        match self.style {
+            Style::Fill { color, .. } | Style::Stroke { color, .. } => color,
+        }
+    }
+
+    /// Produce the path as SVG path data, suitable for the `d` attribute of a `<path>` element.
+    ///
+    /// The y axis is flipped and the origin shifted by `offset` so that the path may be placed
+    /// within an SVG document whose top-left corner is at `offset` in **Draw** coordinates, e.g.
+    /// `app.window_rect().top_left()`.
+    pub fn svg_path_data(&self, offset: Vec2) -> String {
+        let p = |p: lyon::math::Point| (p.x - offset.x, offset.y - p.y);
+        let mut d = String::new();
+        for event in self.path.iter() {
+            let _ = match event {
+                PathEvent::Begin { at } => {
+                    let (x, y) = p(at);
+                    write!(d, "M{} {} ", x, y)
+                }
+                PathEvent::Line { to, .. } => {
+                    let (x, y) = p(to);
+                    write!(d, "L{} {} ", x, y)
+                }
+                PathEvent::Quadratic { ctrl, to, .. } => {
+                    let ((cx, cy), (x, y)) = (p(ctrl), p(to));
+                    write!(d, "Q{} {} {} {} ", cx, cy, x, y)
+                }
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    let ((ax, ay), (bx, by), (x, y)) = (p(ctrl1), p(ctrl2), p(to));
+                    write!(d, "C{} {} {} {} {} {} ", ax, ay, bx, by, x, y)
+                }
+                PathEvent::End { close: true, .. } => write!(d, "Z "),
+                PathEvent::End { close: false, .. } => Ok(()),
+            };
+        }
+        d.truncate(d.trim_end().len());
+        d
+    }
+}
+
+/// Collect the source paths of the primitives submitted to the given **Draw**, in the order in
+/// which they were drawn.
+///
+/// Unlike rendering via the **draw::Renderer**, the **Draw** is not drained of its commands, so
+/// this may be called before rendering the same **Draw** to a frame.
+pub fn paths(draw: &Draw) -> Vec<VectorPath> {
+    let skip = |prim: &Primitive| {
+        matches!(
+            prim,
+            Primitive::Text(_)
+                | Primitive::Texture(_)
+                | Primitive::Mesh(_)
+                | Primitive::MeshVertexless(_)
+        )
+    };
+    let mut paths = vec![];
+    tessellate::tessellate_draw(draw, skip, false, Some(&mut paths), None);
+    paths
+}
@@ -6,6 +6,8 @@
 //! - [**WindowEvent**](./enum.WindowEvent.html) - a stripped-back, simplified, newcomer-friendly
 //!   version of the **raw**, low-level winit event.
 
+#[cfg(feature = "gamepad")]
+use crate::gamepad;
 use crate::geom::{self, Point2};
 use crate::glam::Vec2;
 use crate::window;
@@ -22,6 +24,12 @@ pub use winit::event::{
 pub trait LoopEvent: 'static + From<Update> {
     /// Produce a loop event from the given winit event.
     fn from_winit_event<'a, T>(_: &winit::event::Event<'a, T>, _: &App) -> Option<Self>;
+
+    /// Produce a loop event from the given gamepad event.
+    #[cfg(feature = "gamepad")]
+    fn from_gamepad_event(_: gamepad::Id, _: gamepad::Event) -> Option<Self> {
+        None
+    }
 }
 
 /// Update event, emitted on each pass of an application loop.
@@ -65,6 +73,12 @@ pub enum Event {
     Suspended,
     /// The application has been awakened.
     Resumed,
+
+    /// The state of the gamepad with the given Id has changed.
+    ///
+    /// The current state of all gamepads is available via `App::gamepads`.
+    #[cfg(feature = "gamepad")]
+    Gamepad(gamepad::Id, gamepad::Event),
 }
 
 /// The event associated with a touch at a single point.
@@ -76,6 +90,34 @@ pub struct TouchEvent {
     pub phase: TouchPhase,
     /// The position of the touch.
     pub position: Point2,
+    /// The normalized pressure of the touch in the range `0.0..=1.0`, if supported by the device.
+    pub pressure: Option<f32>,
+}
+
+/// The event associated with a pressure-sensitive stylus, e.g. a pen tablet or Apple Pencil.
+///
+/// Pen events are produced for touches that report pressure. Platforms on which tablet drivers
+/// emulate the mouse instead produce regular mouse events.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PenEvent {
+    /// The unique ID associated with this contact.
+    pub id: u64,
+    /// The state of the contact.
+    pub phase: TouchPhase,
+    /// The position of the pen.
+    pub position: Point2,
+    /// The normalized pressure of the pen in the range `0.0..=1.0`.
+    pub pressure: f32,
+    /// The altitude angle of the pen in radians, from `0.0` when parallel to the surface to `PI /
+    /// 2.0` when perpendicular to it.
+    ///
+    /// Currently only reported for the Apple Pencil on iOS.
+    pub tilt: Option<f32>,
+    /// Whether the barrel button of the pen is held.
+    ///
+    /// Tablet drivers report barrel button presses as right mouse button presses, so this is
+    /// `true` while the right mouse button is held during contact.
+    pub barrel_button: bool,
 }
 
 /// Pressure on a touch pad.
@@ -261,6 +303,7 @@ impl WindowEvent {
             winit::event::WindowEvent::Touch(winit::event::Touch {
                 phase,
                 location,
+                force,
                 id,
                 ..
             }) => {
@@ -268,10 +311,12 @@ impl WindowEvent {
                 let x = tx(x);
                 let y = ty(y);
                 let position = [x, y].into();
+                let pressure = force.map(|force| force.normalized() as f32);
                 let touch = TouchEvent {
                     phase: phase.clone(),
                     position,
                     id: id.clone(),
+                    pressure,
                 };
                 WindowEvent::Touch(touch)
             }
@@ -357,6 +402,12 @@ impl LoopEvent for Event {
         };
         Some(event)
     }
+
+    /// Convert the given gamepad event to a nannou `Event`.
+    #[cfg(feature = "gamepad")]
+    fn from_gamepad_event(id: gamepad::Id, event: gamepad::Event) -> Option<Self> {
+        Some(Event::Gamepad(id, event))
+    }
 }
 
 impl From<Update> for Event {
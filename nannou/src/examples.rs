@@ -0,0 +1,537 @@
+//! A gallery for browsing and launching the example sketches bundled with nannou.
+//!
+//! The gallery lists every example within the `examples`, `generative_design` and
+//! `nature_of_code` packages of the nannou repository, grouped by category (e.g. `draw`, `wgpu`,
+//! `audio`, `isf`). Selecting an example launches it as a separate process via `cargo`, as each
+//! sketch runs its own event loop.
+//!
+//! The gallery can also run each example in turn for a few seconds, reporting which of them failed
+//! to start. This doubles as an integration test of every subsystem on the current hardware.
+//!
+//! ```no_run
+//! nannou::examples::run_gallery();
+//! ```
+//!
+//! The gallery must be run from within a checkout of the nannou repository. Requires the `gallery`
+//! feature.
+
+use crate::color::{self, LinSrgba};
+use crate::event::{Key, MouseButton, MouseScrollDelta, TouchPhase};
+use crate::frame::Frame;
+use crate::geom::Rect;
+use crate::App;
+use instant::Instant;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+use std::{fmt, fs, io};
+
+/// The packages of the nannou repository containing examples.
+pub const PACKAGES: &[&str] = &["examples", "generative_design", "nature_of_code"];
+
+/// The duration for which each example runs when testing all examples.
+pub const DEFAULT_TEST_DURATION: Duration = Duration::from_secs(3);
+
+/// An example sketch listed within the manifest of one of the `PACKAGES`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    /// The package to which the example belongs.
+    pub package: String,
+    /// The name of the example, as passed to `cargo run --example`.
+    pub name: String,
+    /// The directory in which the example's source resides, e.g. `draw` or `audio`.
+    pub category: String,
+}
+
+/// Errors that might occur while listing examples.
+#[derive(Debug)]
+pub enum ListError {
+    /// Failed to read a package manifest.
+    Io(io::Error),
+    /// Failed to parse a package manifest.
+    Toml(toml::de::Error),
+}
+
+// The state of the gallery app.
+struct Gallery {
+    workspace: PathBuf,
+    examples: Vec<Example>,
+    statuses: Vec<Status>,
+    selected: usize,
+    scroll: f32,
+    running: Option<Running>,
+    test: Option<Test>,
+}
+
+// The status of each example within the gallery.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Status {
+    NotRun,
+    Running,
+    Exited,
+    Passed,
+    Failed,
+}
+
+// A child process launched by the gallery.
+struct Running {
+    // The example being run, or `None` while building all examples for a test.
+    index: Option<usize>,
+    child: Child,
+    started: Instant,
+}
+
+// The state of an in-progress test of all examples.
+struct Test {
+    built: bool,
+    queue: VecDeque<usize>,
+}
+
+// A row within the list.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Row {
+    // The header of a group, along with the index of its first example.
+    Header(usize),
+    Example(usize),
+}
+
+// The height of each row within the list.
+const ROW_HEIGHT: f32 = 20.0;
+// The height of the header containing the instructions.
+const HEADER_HEIGHT: f32 = 60.0;
+
+/// Find the root of the nannou repository by searching the ancestors of the current directory
+/// and of the executable.
+pub fn find_workspace() -> Option<PathBuf> {
+    let is_workspace = |dir: &Path| {
+        PACKAGES
+            .iter()
+            .all(|p| dir.join(p).join("Cargo.toml").exists())
+    };
+    let cwd = std::env::current_dir().ok();
+    let exe = std::env::current_exe().ok();
+    cwd.iter()
+        .chain(exe.iter())
+        .flat_map(|path| path.ancestors())
+        .find(|dir| is_workspace(dir))
+        .map(Path::to_path_buf)
+}
+
+/// List the examples of all `PACKAGES` within the given workspace, in the order in which they
+/// appear within each manifest.
+pub fn list(workspace: &Path) -> Result<Vec<Example>, ListError> {
+    let mut examples = vec![];
+    for &package in PACKAGES {
+        let manifest = fs::read_to_string(workspace.join(package).join("Cargo.toml"))?;
+        let manifest: toml::Value = toml::from_str(&manifest)?;
+        let entries = manifest
+            .get("example")
+            .and_then(toml::Value::as_array)
+            .map(|entries| &entries[..])
+            .unwrap_or(&[]);
+        for entry in entries {
+            let name = match entry.get("name").and_then(toml::Value::as_str) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let category = entry
+                .get("path")
+                .and_then(toml::Value::as_str)
+                .and_then(|path| Path::new(path).parent())
+                .and_then(|dir| dir.to_str())
+                .filter(|dir| !dir.is_empty())
+                .unwrap_or(package)
+                .to_string();
+            examples.push(Example {
+                package: package.to_string(),
+                name,
+                category,
+            });
+        }
+    }
+    Ok(examples)
+}
+
+/// Run the gallery app.
+///
+/// - Click an example or press `Return` to launch the selected example.
+/// - `Up` and `Down` or the mouse wheel move through the list.
+/// - `T` tests all examples, running each for `DEFAULT_TEST_DURATION`.
+/// - `S` stops the running example or test.
+///
+/// Panics if the nannou repository cannot be found or its manifests cannot be read.
+pub fn run_gallery() {
+    crate::app(gallery).update(update).exit(exit).run();
+}
+
+impl Example {
+    /// The `cargo` command for running the example in release mode.
+    pub fn command(&self, workspace: &Path) -> Command {
+        let mut command = Command::new("cargo");
+        command.current_dir(workspace).args([
+            "run",
+            "--release",
+            "-p",
+            &self.package,
+            "--example",
+            &self.name,
+        ]);
+        command
+    }
+
+    // The path to the example's executable once built in release mode.
+    fn executable(&self, workspace: &Path) -> PathBuf {
+        let target = std::env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| workspace.join("target"));
+        target
+            .join("release")
+            .join("examples")
+            .join(&self.name)
+            .with_extension(std::env::consts::EXE_EXTENSION)
+    }
+}
+
+impl fmt::Display for ListError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ListError::Io(ref err) => write!(f, "failed to read manifest: {}", err),
+            ListError::Toml(ref err) => write!(f, "failed to parse manifest: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ListError {}
+
+impl From<io::Error> for ListError {
+    fn from(err: io::Error) -> Self {
+        ListError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for ListError {
+    fn from(err: toml::de::Error) -> Self {
+        ListError::Toml(err)
+    }
+}
+
+impl Gallery {
+    // Stop the running process, if any.
+    fn stop(&mut self) {
+        if let Some(mut running) = self.running.take() {
+            running.child.kill().ok();
+            running.child.wait().ok();
+            if let Some(index) = running.index {
+                self.statuses[index] = Status::Exited;
+            }
+        }
+    }
+
+    // Launch the example at the given index via `cargo run`.
+    fn launch(&mut self, index: usize) {
+        self.stop();
+        self.test = None;
+        let spawned = self.examples[index]
+            .command(&self.workspace)
+            .stdin(Stdio::null())
+            .spawn();
+        self.start(Some(index), spawned);
+    }
+
+    // Begin testing all examples, starting with a release build of all of them.
+    fn test_all(&mut self) {
+        self.stop();
+        let mut command = Command::new("cargo");
+        command
+            .current_dir(&self.workspace)
+            .args(["build", "--release"]);
+        for package in PACKAGES {
+            command.args(["-p", package]);
+        }
+        let spawned = command.arg("--examples").stdin(Stdio::null()).spawn();
+        self.test = Some(Test {
+            built: false,
+            queue: (0..self.examples.len()).collect(),
+        });
+        self.start(None, spawned);
+    }
+
+    fn start(&mut self, index: Option<usize>, spawned: io::Result<Child>) {
+        match spawned {
+            Ok(child) => {
+                if let Some(index) = index {
+                    self.statuses[index] = Status::Running;
+                }
+                let started = Instant::now();
+                self.running = Some(Running {
+                    index,
+                    child,
+                    started,
+                });
+            }
+            Err(err) => {
+                eprintln!("failed to launch example: {}", err);
+                if let Some(index) = index {
+                    self.statuses[index] = Status::Failed;
+                }
+            }
+        }
+    }
+
+    // Check on the running process and advance any in-progress test.
+    fn poll(&mut self) {
+        let testing = self.test.is_some();
+        if let Some(running) = self.running.as_mut() {
+            let exited = match running.child.try_wait() {
+                Ok(exited) => exited.map(|status| status.success()),
+                Err(_) => Some(false),
+            };
+            let timed_out = running.started.elapsed() >= DEFAULT_TEST_DURATION;
+            let status = match (running.index, exited) {
+                // The build for the test finished.
+                (None, Some(success)) => {
+                    match self.test.as_mut() {
+                        Some(test) if success => test.built = true,
+                        _ => {
+                            eprintln!("failed to build examples");
+                            self.test = None;
+                        }
+                    }
+                    self.running = None;
+                    return;
+                }
+                (None, None) => return,
+                (Some(_), Some(true)) if testing => Status::Passed,
+                (Some(_), Some(true)) => Status::Exited,
+                (Some(_), Some(false)) => Status::Failed,
+                // Examples that run until the timeout during a test have passed.
+                (Some(_), None) if testing && timed_out => {
+                    running.child.kill().ok();
+                    running.child.wait().ok();
+                    Status::Passed
+                }
+                (Some(_), None) => return,
+            };
+            if let Some(index) = running.index {
+                self.statuses[index] = status;
+            }
+            self.running = None;
+        }
+
+        // Run the next example of the test.
+        let next = match self.test.as_mut() {
+            Some(test) if test.built => test.queue.pop_front(),
+            _ => return,
+        };
+        match next {
+            None => self.test = None,
+            Some(index) => {
+                self.selected = index;
+                let spawned = Command::new(self.examples[index].executable(&self.workspace))
+                    .current_dir(self.workspace.join(&self.examples[index].package))
+                    .stdin(Stdio::null())
+                    .spawn();
+                self.start(Some(index), spawned);
+            }
+        }
+    }
+
+    // The index of the example at the given y position within the window, if any.
+    fn index_at(&self, win: Rect, y: f32) -> Option<usize> {
+        let rows = self.rows();
+        let row = ((win.top() - HEADER_HEIGHT - y + self.scroll) / ROW_HEIGHT).floor();
+        if row < 0.0 {
+            return None;
+        }
+        match rows.get(row as usize) {
+            Some(&Row::Example(index)) => Some(index),
+            _ => None,
+        }
+    }
+
+    // The rows of the list, with a header for each package and category.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = vec![];
+        let mut last: Option<(&str, &str)> = None;
+        for (i, example) in self.examples.iter().enumerate() {
+            let group = (&example.package[..], &example.category[..]);
+            if last != Some(group) {
+                rows.push(Row::Header(i));
+                last = Some(group);
+            }
+            rows.push(Row::Example(i));
+        }
+        rows
+    }
+}
+
+fn gallery(app: &App) -> Gallery {
+    let workspace = find_workspace().expect("failed to find the nannou repository");
+    let examples = list(&workspace).expect("failed to list examples");
+    app.new_window()
+        .title("nannou examples")
+        .size(640, 800)
+        .key_pressed(key_pressed)
+        .mouse_pressed(mouse_pressed)
+        .mouse_wheel(mouse_wheel)
+        .view(view)
+        .build()
+        .unwrap();
+    // The escape key is reserved for stopping examples.
+    app.set_exit_on_escape(false);
+    let statuses = vec![Status::NotRun; examples.len()];
+    Gallery {
+        workspace,
+        examples,
+        statuses,
+        selected: 0,
+        scroll: 0.0,
+        running: None,
+        test: None,
+    }
+}
+
+fn update(_app: &App, gallery: &mut Gallery, _update: crate::event::Update) {
+    gallery.poll();
+}
+
+fn exit(_app: &App, mut gallery: Gallery) {
+    gallery.stop();
+}
+
+fn key_pressed(app: &App, gallery: &mut Gallery, key: Key) {
+    let len = gallery.examples.len();
+    match key {
+        Key::Up => gallery.selected = gallery.selected.saturating_sub(1),
+        Key::Down => gallery.selected = (gallery.selected + 1).min(len.saturating_sub(1)),
+        Key::Return if len > 0 => gallery.launch(gallery.selected),
+        Key::T => gallery.test_all(),
+        Key::S | Key::Escape => {
+            gallery.stop();
+            gallery.test = None;
+        }
+        _ => return,
+    }
+
+    // Keep the selected example in view.
+    let win = app.window_rect();
+    let row = gallery
+        .rows()
+        .iter()
+        .position(|&row| row == Row::Example(gallery.selected))
+        .unwrap_or(0) as f32;
+    let visible = win.h() - HEADER_HEIGHT - ROW_HEIGHT;
+    let y = row * ROW_HEIGHT;
+    gallery.scroll = gallery.scroll.clamp(y - visible, y);
+}
+
+fn mouse_pressed(app: &App, gallery: &mut Gallery, button: MouseButton) {
+    if button != MouseButton::Left {
+        return;
+    }
+    if let Some(index) = gallery.index_at(app.window_rect(), app.mouse.y) {
+        gallery.selected = index;
+        gallery.launch(index);
+    }
+}
+
+fn mouse_wheel(app: &App, gallery: &mut Gallery, delta: MouseScrollDelta, _phase: TouchPhase) {
+    let dy = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y * ROW_HEIGHT * 3.0,
+        MouseScrollDelta::PixelDelta(pos) => pos.y as f32,
+    };
+    let content = gallery.rows().len() as f32 * ROW_HEIGHT;
+    let visible = app.window_rect().h() - HEADER_HEIGHT;
+    gallery.scroll = (gallery.scroll - dy).min(content - visible).max(0.0);
+}
+
+fn view(app: &App, gallery: &Gallery, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(color::BLACK);
+    let win = app.window_rect();
+    let pad = 10.0;
+
+    // The list of examples, grouped by package and category.
+    let mut y = win.top() - HEADER_HEIGHT + gallery.scroll;
+    for row in gallery.rows() {
+        let row_y = y - ROW_HEIGHT * 0.5;
+        y -= ROW_HEIGHT;
+        if row_y > win.top() - HEADER_HEIGHT || row_y < win.bottom() - ROW_HEIGHT {
+            continue;
+        }
+        let rect = Rect::from_x_y_w_h(win.x(), row_y, win.w() - pad * 2.0, ROW_HEIGHT);
+        let index = match row {
+            Row::Example(index) => index,
+            Row::Header(index) => {
+                let example = &gallery.examples[index];
+                let label = format!("{} / {}", example.package, example.category);
+                draw.text(&label)
+                    .xy(rect.xy())
+                    .wh(rect.wh())
+                    .left_justify()
+                    .font_size(13)
+                    .color(LinSrgba::new(0.5, 0.7, 1.0, 1.0));
+                continue;
+            }
+        };
+        if index == gallery.selected {
+            draw.rect()
+                .xy(rect.xy())
+                .wh(rect.wh())
+                .color(LinSrgba::new(1.0, 1.0, 1.0, 0.1));
+        }
+        let example = &gallery.examples[index];
+        draw.text(&example.name)
+            .xy(rect.xy())
+            .wh(rect.pad_left(pad).wh())
+            .left_justify()
+            .font_size(13)
+            .color(color::WHITE);
+        let (status, status_color) = match gallery.statuses[index] {
+            Status::NotRun => ("", color::GRAY),
+            Status::Running => ("running", color::YELLOW),
+            Status::Exited => ("exited", color::GRAY),
+            Status::Passed => ("passed", color::LIMEGREEN),
+            Status::Failed => ("failed", color::RED),
+        };
+        draw.text(status)
+            .xy(rect.xy())
+            .wh(rect.wh())
+            .right_justify()
+            .font_size(13)
+            .color(status_color);
+    }
+
+    // The header, drawn over the list.
+    let header = Rect::from_w_h(win.w(), HEADER_HEIGHT).top_left_of(win);
+    draw.rect()
+        .xy(header.xy())
+        .wh(header.wh())
+        .color(color::BLACK);
+    let failed = gallery
+        .statuses
+        .iter()
+        .filter(|&&s| s == Status::Failed)
+        .count();
+    let state = match (&gallery.test, &gallery.running) {
+        (Some(test), _) if !test.built => "building examples...".to_string(),
+        (Some(test), _) => format!("testing: {} remaining", test.queue.len()),
+        (None, Some(_)) => "running".to_string(),
+        (None, None) => "idle".to_string(),
+    };
+    let text = format!(
+        "{} examples ({} failed) - {}\n[return/click] launch   [T] test all   [S] stop",
+        gallery.examples.len(),
+        failed,
+        state,
+    );
+    draw.text(&text)
+        .xy(header.xy())
+        .wh(header.pad(pad).wh())
+        .left_justify()
+        .font_size(14)
+        .color(color::WHITE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
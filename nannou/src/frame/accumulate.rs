@@ -0,0 +1,109 @@
+//! Items related to accumulating a window's frames over time, e.g. for motion blur and trails.
+//!
+//! When accumulation is enabled via `App::accumulate`, each resolved frame is blended with the
+//! accumulated image of all previous frames before it is presented. As a result, frames captured
+//! via `Window::capture_frame` and textures copied via `Frame::snapshot_to` also include the
+//! accumulated image.
+
+use crate::wgpu;
+
+// Blends a frame's resolved linear sRGBA texture with the accumulated image of previous frames.
+#[derive(Debug)]
+pub(crate) struct Accumulator {
+    // The accumulated image of all previous frames.
+    history: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    render_pipeline: wgpu::RenderPipeline,
+    // Whether or not the history texture contains a frame yet.
+    is_initialised: bool,
+}
+
+impl Accumulator {
+    pub(crate) fn new(device: &wgpu::Device, frame_texture: &wgpu::Texture) -> Self {
+        let shader_desc = wgpu::include_wgsl!("shaders/accumulate.wgsl");
+        let shader_mod = device.create_shader_module(shader_desc);
+
+        let history = wgpu::TextureBuilder::new()
+            .size(frame_texture.size())
+            .format(frame_texture.format())
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST)
+            .build(device);
+        let history_view = history.view().build();
+
+        let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                history_view.sample_type(),
+            )
+            .build(device);
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .texture_view(&history_view)
+            .build(device, &bind_group_layout);
+
+        // The history is weighted by `1 - alpha` and the new frame by `alpha`, where `alpha` is
+        // given via the blend constant.
+        let blend = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::OneMinusConstant,
+            dst_factor: wgpu::BlendFactor::Constant,
+            operation: wgpu::BlendOperation::Add,
+        };
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("nannou accumulate"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline =
+            wgpu::RenderPipelineBuilder::from_layout(&pipeline_layout, &shader_mod)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(&shader_mod)
+                .fragment_entry_point("fs_main")
+                .color_format(frame_texture.format())
+                .color_blend(blend)
+                .alpha_blend(blend)
+                .build(device);
+
+        Accumulator {
+            history,
+            bind_group,
+            render_pipeline,
+            is_initialised: false,
+        }
+    }
+
+    // Blend the accumulated history into the given frame texture, weighting the frame by `alpha`,
+    // then store the result as the new history.
+    pub(crate) fn encode(
+        &mut self,
+        alpha: f32,
+        frame_texture: &wgpu::Texture,
+        frame_texture_view: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // The first frame begins the history as is.
+        if self.is_initialised {
+            let mut render_pass = wgpu::RenderPassBuilder::new()
+                .color_attachment(frame_texture_view, |color| {
+                    color.load_op(wgpu::LoadOp::Load)
+                })
+                .begin(encoder);
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            let a = alpha.clamp(0.0, 1.0) as f64;
+            render_pass.set_blend_constant(wgpu::Color {
+                r: a,
+                g: a,
+                b: a,
+                a,
+            });
+            render_pass.draw(0..3, 0..1);
+        }
+        encoder.copy_texture_to_texture(
+            frame_texture.as_image_copy(),
+            self.history.as_image_copy(),
+            frame_texture.extent(),
+        );
+        self.is_initialised = true;
+    }
+}
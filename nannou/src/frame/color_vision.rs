@@ -0,0 +1,186 @@
+//! Items related to simulating color vision deficiencies when presenting a window's frames.
+//!
+//! The simulation is applied while writing a frame's intermediary linear sRGBA image to the
+//! window's surface texture. As a result, it only affects what is displayed in the window - frames
+//! captured via `Window::capture_frame` and textures read back from the frame are unaffected.
+
+use crate::wgpu::{self, util::DeviceExt};
+
+/// A color vision deficiency that may be simulated when presenting a window's frames.
+///
+/// The simulations are based on the model described by Machado, Oliveira and Fernandes (2009) at
+/// full severity.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorVision {
+    /// Present frames unmodified.
+    #[default]
+    Normal,
+    /// Absence of red-sensitive (L) cones.
+    Protanopia,
+    /// Absence of green-sensitive (M) cones.
+    Deuteranopia,
+    /// Absence of blue-sensitive (S) cones.
+    Tritanopia,
+}
+
+// Writes a linear sRGBA texture to a surface texture while applying a color vision simulation.
+#[derive(Debug)]
+pub(crate) struct Simulator {
+    bind_group: wgpu::BindGroup,
+    render_pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    // The mode currently described by the uniform buffer.
+    mode: ColorVision,
+}
+
+// The rows of the 3x3 simulation matrix, padded for uniform buffer alignment.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Uniforms {
+    rows: [[f32; 4]; 3],
+}
+
+impl ColorVision {
+    /// All available modes, beginning with `Normal`.
+    pub const ALL: [ColorVision; 4] = [
+        ColorVision::Normal,
+        ColorVision::Protanopia,
+        ColorVision::Deuteranopia,
+        ColorVision::Tritanopia,
+    ];
+
+    /// The mode following this one within `ColorVision::ALL`, wrapping back around to `Normal`.
+    ///
+    /// Useful for cycling through the simulations via a key press.
+    pub fn next(self) -> Self {
+        let ix = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(ix + 1) % Self::ALL.len()]
+    }
+
+    /// The linear RGB transform used to simulate the color vision deficiency.
+    pub fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorVision::Normal => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            ColorVision::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorVision::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorVision::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+impl Simulator {
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        src_texture: &wgpu::TextureView,
+        dst_format: wgpu::TextureFormat,
+    ) -> Self {
+        let shader_desc = wgpu::include_wgsl!("shaders/color_vision.wgsl");
+        let shader_mod = device.create_shader_module(shader_desc);
+
+        let sampler_desc = wgpu::SamplerBuilder::new().into_descriptor();
+        let sampler_filtering = wgpu::sampler_filtering(&sampler_desc);
+        let sampler = device.create_sampler(&sampler_desc);
+
+        let mode = ColorVision::Normal;
+        let uniforms = Uniforms::from(mode);
+        let uniform_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou color_vision uniform_buffer"),
+            contents: uniforms_as_bytes(&uniforms),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                src_texture.sample_type(),
+            )
+            .sampler(wgpu::ShaderStages::FRAGMENT, sampler_filtering)
+            .uniform_buffer(wgpu::ShaderStages::FRAGMENT, false)
+            .build(device);
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .texture_view(src_texture)
+            .sampler(&sampler)
+            .buffer::<Uniforms>(&uniform_buffer, 0..1)
+            .build(device, &bind_group_layout);
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("nannou color_vision"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let render_pipeline =
+            wgpu::RenderPipelineBuilder::from_layout(&pipeline_layout, &shader_mod)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(&shader_mod)
+                .fragment_entry_point("fs_main")
+                .color_format(dst_format)
+                .color_blend(wgpu::BlendComponent::REPLACE)
+                .alpha_blend(wgpu::BlendComponent::REPLACE)
+                .build(device);
+
+        Simulator {
+            bind_group,
+            render_pipeline,
+            uniform_buffer,
+            mode,
+        }
+    }
+
+    // Encode a render pass writing the source texture to the destination texture with the given
+    // simulation applied.
+    pub(crate) fn encode_render_pass(
+        &mut self,
+        device: &wgpu::Device,
+        mode: ColorVision,
+        dst_texture: &wgpu::TextureViewHandle,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        // Upload the new matrix if the mode has changed.
+        if self.mode != mode {
+            let uniforms = Uniforms::from(mode);
+            let size = std::mem::size_of::<Uniforms>() as wgpu::BufferAddress;
+            let staging = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+                label: Some("nannou color_vision uniform_buffer"),
+                contents: uniforms_as_bytes(&uniforms),
+                usage: wgpu::BufferUsages::COPY_SRC,
+            });
+            encoder.copy_buffer_to_buffer(&staging, 0, &self.uniform_buffer, 0, size);
+            self.mode = mode;
+        }
+
+        let mut render_pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(dst_texture, |color| color)
+            .begin(encoder);
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+impl From<ColorVision> for Uniforms {
+    fn from(mode: ColorVision) -> Self {
+        let [r, g, b] = mode.matrix();
+        let row = |[x, y, z]: [f32; 3]| [x, y, z, 0.0];
+        Uniforms {
+            rows: [row(r), row(g), row(b)],
+        }
+    }
+}
+
+fn uniforms_as_bytes(uniforms: &Uniforms) -> &[u8] {
+    unsafe { wgpu::bytes::from(uniforms) }
+}
@@ -2,13 +2,19 @@
 
 use crate::color::IntoLinSrgba;
 use crate::wgpu;
+use crate::window;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ops;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+mod accumulate;
+pub mod color_vision;
 pub mod raw;
 
+pub use self::color_vision::ColorVision;
 pub use self::raw::RawFrame;
 
 /// A **Frame** to which the user can draw graphics before it is presented to the display.
@@ -23,6 +29,24 @@ pub struct Frame<'swap_chain> {
     raw_frame: RawFrame<'swap_chain>,
     render_data: &'swap_chain RenderData,
     capture_data: &'swap_chain CaptureData,
+    // Textures into which the frame should be copied upon submission.
+    snapshots: RefCell<Vec<wgpu::Texture>>,
+}
+
+/// The textures of a single frame for a single window, provided to the window's `before_render`
+/// and `after_render` hooks.
+///
+/// Unlike a **Frame**, no command encoder is provided. Hooks that wish to encode commands, e.g. to
+/// copy the frame's texture to a buffer for readback, should create their own encoder and submit
+/// it to the queue of the `device_queue_pair`.
+pub struct FrameTextures<'a> {
+    window_id: window::Id,
+    nth: u64,
+    device_queue_pair: &'a Arc<wgpu::DeviceQueuePair>,
+    swap_chain_texture: &'a wgpu::TextureViewHandle,
+    swap_chain_texture_format: wgpu::TextureFormat,
+    size: [u32; 2],
+    render_data: Option<&'a RenderData>,
 }
 
 /// Data specific to the intermediary textures.
@@ -33,6 +57,18 @@ pub struct RenderData {
     size: [u32; 2],
     // For writing the intermediary linear sRGBA texture to the swap chain texture.
     texture_reshaper: wgpu::TextureReshaper,
+    // The color vision deficiency simulated when writing to the swap chain texture.
+    pub(crate) color_vision: Cell<ColorVision>,
+    // Lazily created the first time a simulation other than `Normal` is requested.
+    color_vision_simulator: RefCell<Option<color_vision::Simulator>>,
+    // The weight of each new frame when accumulating frames over time, if enabled.
+    pub(crate) accumulation: Cell<Option<f32>>,
+    // Lazily created the first time accumulation is enabled.
+    accumulator: RefCell<Option<accumulate::Accumulator>>,
+    // For writing the frame to snapshot textures whose size or format differs from the frame's,
+    // keyed by the destination format and sample count.
+    snapshot_reshapers: RefCell<HashMap<(wgpu::TextureFormat, u32), wgpu::TextureReshaper>>,
+    swap_chain_format: wgpu::TextureFormat,
 }
 
 /// Data related to the capturing of a frame.
@@ -72,6 +108,9 @@ impl<'swap_chain> Frame<'swap_chain> {
     // TODO: Kvark recommends trying `Rgb10A2Unorm`.
     pub const TEXTURE_FORMAT: wgpu::TextureFormat =
         wgpu::RenderPipelineBuilder::DEFAULT_COLOR_FORMAT;
+    /// The texture format used by the intermediary image on adapters that cannot render to
+    /// `TEXTURE_FORMAT`, e.g. GL ES 3.0 devices such as the Raspberry Pi.
+    pub const FALLBACK_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
 
     // Initialise a new empty frame ready for "drawing".
     pub(crate) fn new_empty(
@@ -83,6 +122,7 @@ impl<'swap_chain> Frame<'swap_chain> {
             raw_frame,
             render_data,
             capture_data,
+            snapshots: RefCell::new(Vec::new()),
         }
     }
 
@@ -93,6 +133,7 @@ impl<'swap_chain> Frame<'swap_chain> {
             ref capture_data,
             ref render_data,
             ref mut raw_frame,
+            ref snapshots,
         } = *self;
 
         // Resolve the MSAA if necessary.
@@ -105,6 +146,35 @@ impl<'swap_chain> Frame<'swap_chain> {
             );
         }
 
+        // Blend the resolved image with those of previous frames if accumulating.
+        {
+            let mut accumulator = render_data.accumulator.borrow_mut();
+            match render_data.accumulation.get() {
+                None => *accumulator = None,
+                Some(alpha) => {
+                    let device = raw_frame.device_queue_pair().device();
+                    let intermediary = &render_data.intermediary_lin_srgba;
+                    let accumulator = accumulator.get_or_insert_with(|| {
+                        accumulate::Accumulator::new(device, &intermediary.texture)
+                    });
+                    let mut encoder = raw_frame.command_encoder();
+                    accumulator.encode(
+                        alpha,
+                        &intermediary.texture,
+                        &intermediary.texture_view,
+                        &mut encoder,
+                    );
+                }
+            }
+        }
+
+        // Copy the resolved image to any textures requested via `snapshot_to`.
+        for dst in snapshots.borrow_mut().drain(..) {
+            let device = raw_frame.device_queue_pair().device();
+            let mut encoder = raw_frame.command_encoder();
+            render_data.encode_snapshot(device, &dst, &mut encoder);
+        }
+
         // Check to see if the user specified capturing the frame.
         let mut snapshot_capture = None;
         if let Ok(mut guard) = capture_data.next_frame_path.lock() {
@@ -123,12 +193,32 @@ impl<'swap_chain> Frame<'swap_chain> {
         // Convert the linear sRGBA image to the swapchain image.
         //
         // To do so, we sample the linear sRGBA image and draw it to the swapchain image using
-        // two triangles and a fragment shader.
+        // two triangles and a fragment shader. This happens after capturing so that any color
+        // vision simulation only affects the window.
         {
             let mut encoder = raw_frame.command_encoder();
-            render_data
-                .texture_reshaper
-                .encode_render_pass(raw_frame.swap_chain_texture(), &mut *encoder);
+            match render_data.color_vision.get() {
+                ColorVision::Normal => render_data
+                    .texture_reshaper
+                    .encode_render_pass(raw_frame.swap_chain_texture(), &mut *encoder),
+                mode => {
+                    let device = raw_frame.device_queue_pair().device();
+                    let mut simulator = render_data.color_vision_simulator.borrow_mut();
+                    let simulator = simulator.get_or_insert_with(|| {
+                        color_vision::Simulator::new(
+                            device,
+                            &render_data.intermediary_lin_srgba.texture_view,
+                            render_data.swap_chain_format,
+                        )
+                    });
+                    simulator.encode_render_pass(
+                        device,
+                        mode,
+                        raw_frame.swap_chain_texture(),
+                        &mut encoder,
+                    );
+                }
+            }
         }
 
         // Submit all commands on the device queue.
@@ -168,7 +258,7 @@ impl<'swap_chain> Frame<'swap_chain> {
     /// graphics pipeline render pass that is used.
     /// - Allow for the user's rendered image to persist between frames.
     ///
-    /// The exact format of the texture is equal to `Frame::TEXTURE_FORMAT`.
+    /// The exact format of the texture is given by `texture_format`.
     ///
     /// If the number of MSAA samples specified is greater than `1` (which it is by default if
     /// supported by the platform), this will be a multisampled texture. After the **view**
@@ -200,10 +290,12 @@ impl<'swap_chain> Frame<'swap_chain> {
         }
     }
 
-    /// The color format of the `Frame`'s intermediary linear sRGBA texture (equal to
-    /// `Frame::TEXTURE_FORMAT`).
+    /// The color format of the `Frame`'s intermediary linear sRGBA texture.
+    ///
+    /// This is `Frame::TEXTURE_FORMAT` unless the adapter cannot render to it, in which case it
+    /// is `Frame::FALLBACK_TEXTURE_FORMAT`.
     pub fn texture_format(&self) -> wgpu::TextureFormat {
-        Self::TEXTURE_FORMAT
+        self.render_data.texture_format()
     }
 
     /// The number of MSAA samples of the `Frame`'s intermediary linear sRGBA texture.
@@ -238,6 +330,21 @@ impl<'swap_chain> Frame<'swap_chain> {
         }
     }
 
+    /// Copy the contents of the frame into the given texture once drawing has completed.
+    ///
+    /// The copy occurs during submission, after the `view` function has returned and any
+    /// multisampling has been resolved, so the texture holds everything drawn this frame. This is
+    /// useful for feedback effects such as trails, where the previous frame is drawn into the next
+    /// via e.g. `draw.texture(&texture)`.
+    ///
+    /// If the texture's size and format match the frame's (see `texture_size` and
+    /// `texture_format`), the frame is copied directly and the texture requires the
+    /// `COPY_DST` usage. Otherwise the frame is scaled to fill the texture, which requires the
+    /// `RENDER_ATTACHMENT` usage.
+    pub fn snapshot_to(&self, texture: &wgpu::Texture) {
+        self.snapshots.borrow_mut().push(texture.clone());
+    }
+
     /// Clear the texture with the given color.
     pub fn clear<C>(&self, color: C)
     where
@@ -259,6 +366,8 @@ impl<'swap_chain> Frame<'swap_chain> {
     /// Before submission, the frame does the following:
     ///
     /// - If the frame's intermediary linear sRGBA texture is multisampled, resolve it.
+    /// - Blend the resolved image with those of previous frames if enabled via `App::accumulate`.
+    /// - Copy the resolved image to any textures requested via `snapshot_to`.
     /// - Write the intermediary linear sRGBA image to the swap chain texture.
     ///
     /// It can sometimes be useful to submit the **Frame** before `view` completes in order to read
@@ -287,11 +396,13 @@ impl RenderData {
     pub(crate) fn new(
         device: &wgpu::Device,
         swap_chain_dims: [u32; 2],
+        texture_format: wgpu::TextureFormat,
         swap_chain_format: wgpu::TextureFormat,
         msaa_samples: u32,
+        color_vision: ColorVision,
     ) -> Self {
         let intermediary_lin_srgba =
-            create_intermediary_lin_srgba(device, swap_chain_dims, msaa_samples);
+            create_intermediary_lin_srgba(device, swap_chain_dims, texture_format, msaa_samples);
         let src_sample_count = 1;
         let swap_chain_sample_count = 1;
         let texture_reshaper = wgpu::TextureReshaper::new(
@@ -307,7 +418,41 @@ impl RenderData {
             texture_reshaper,
             size: swap_chain_dims,
             msaa_samples,
+            color_vision: Cell::new(color_vision),
+            color_vision_simulator: RefCell::new(None),
+            accumulation: Cell::new(None),
+            accumulator: RefCell::new(None),
+            snapshot_reshapers: RefCell::new(HashMap::new()),
+            swap_chain_format,
+        }
+    }
+
+    // The format of the intermediary linear sRGBA image.
+    pub(crate) fn texture_format(&self) -> wgpu::TextureFormat {
+        self.intermediary_lin_srgba.texture.format()
+    }
+
+    // Encode a copy of the resolved linear sRGBA image to the given texture, scaling it via a
+    // render pass if the texture's size or format differ from the frame's.
+    fn encode_snapshot(
+        &self,
+        device: &wgpu::Device,
+        dst: &wgpu::Texture,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let src = &self.intermediary_lin_srgba.texture;
+        if dst.size() == src.size() && dst.format() == src.format() && dst.sample_count() == 1 {
+            encoder.copy_texture_to_texture(src.as_image_copy(), dst.as_image_copy(), src.extent());
+            return;
         }
+        let key = (dst.format(), dst.sample_count());
+        let mut reshapers = self.snapshot_reshapers.borrow_mut();
+        let reshaper = reshapers.entry(key).or_insert_with(|| {
+            let src_view = &self.intermediary_lin_srgba.texture_view;
+            wgpu::TextureReshaper::new(device, src_view, 1, src_view.sample_type(), key.1, key.0)
+        });
+        let dst_view = dst.view().build();
+        reshaper.encode_render_pass(&dst_view, encoder);
     }
 
     /// A full view into the associated texture.
@@ -322,6 +467,85 @@ impl RenderData {
     }
 }
 
+impl<'a> FrameTextures<'a> {
+    pub(crate) fn new(
+        window_id: window::Id,
+        nth: u64,
+        device_queue_pair: &'a Arc<wgpu::DeviceQueuePair>,
+        swap_chain_texture: &'a wgpu::TextureViewHandle,
+        swap_chain_texture_format: wgpu::TextureFormat,
+        size: [u32; 2],
+        render_data: Option<&'a RenderData>,
+    ) -> Self {
+        FrameTextures {
+            window_id,
+            nth,
+            device_queue_pair,
+            swap_chain_texture,
+            swap_chain_texture_format,
+            size,
+            render_data,
+        }
+    }
+
+    /// The `Id` of the window with which the frame is associated.
+    pub fn window_id(&self) -> window::Id {
+        self.window_id
+    }
+
+    /// The nth frame for the associated window.
+    pub fn nth(&self) -> u64 {
+        self.nth
+    }
+
+    /// The device and its queue used to render the frame.
+    pub fn device_queue_pair(&self) -> &Arc<wgpu::DeviceQueuePair> {
+        self.device_queue_pair
+    }
+
+    /// The swap chain texture to which the frame is presented.
+    pub fn swap_chain_texture(&self) -> &wgpu::TextureViewHandle {
+        self.swap_chain_texture
+    }
+
+    /// The texture format of the swap chain texture.
+    pub fn swap_chain_texture_format(&self) -> wgpu::TextureFormat {
+        self.swap_chain_texture_format
+    }
+
+    /// The frame's non-multisampled intermediary linear sRGBA texture.
+    ///
+    /// Within `after_render`, this contains the resolved result of the `view` function and is
+    /// suitable for capture or analysis. Within `before_render`, this contains the result of the
+    /// previous frame.
+    ///
+    /// Returns `None` for windows that use a `raw_view` function, as they have no intermediary
+    /// texture.
+    pub fn texture(&self) -> Option<&wgpu::Texture> {
+        self.render_data
+            .map(|data| &data.intermediary_lin_srgba.texture)
+    }
+
+    /// A full view into the `texture`.
+    pub fn texture_view(&self) -> Option<&wgpu::TextureView> {
+        self.render_data
+            .map(|data| &data.intermediary_lin_srgba.texture_view)
+    }
+
+    /// The multisampled intermediary texture to which the `view` function draws, if MSAA is
+    /// enabled.
+    pub fn msaa_texture_view(&self) -> Option<&wgpu::TextureView> {
+        self.render_data
+            .and_then(|data| data.intermediary_lin_srgba.msaa_texture.as_ref())
+            .map(|(_, view)| view)
+    }
+
+    /// The size of the frame's textures in pixels.
+    pub fn size(&self) -> [u32; 2] {
+        self.size
+    }
+}
+
 impl<'swap_chain> Drop for Frame<'swap_chain> {
     fn drop(&mut self) {
         if !self.raw_frame.is_submitted() {
@@ -330,41 +554,74 @@ impl<'swap_chain> Drop for Frame<'swap_chain> {
     }
 }
 
+// Select the format and number of MSAA samples of the intermediary image supported by the given
+// adapter, falling back to `Frame::FALLBACK_TEXTURE_FORMAT` and then to disabling MSAA.
+pub(crate) fn supported_texture_format(
+    adapter: &wgpu::Adapter,
+    msaa_samples: u32,
+) -> (wgpu::TextureFormat, u32) {
+    let formats = [Frame::TEXTURE_FORMAT, Frame::FALLBACK_TEXTURE_FORMAT];
+    let supports = |format: wgpu::TextureFormat, samples: u32| {
+        let features = adapter.get_texture_format_features(format);
+        features
+            .allowed_usages
+            .contains(wgpu::TextureUsages::RENDER_ATTACHMENT)
+            && (samples <= 1 || features.flags.sample_count_supported(samples))
+    };
+    for &samples in &[msaa_samples, 1] {
+        if let Some(&format) = formats.iter().find(|&&format| supports(format, samples)) {
+            return (format, samples);
+        }
+    }
+    (Frame::TEXTURE_FORMAT, msaa_samples)
+}
+
 fn create_lin_srgba_msaa_texture(
     device: &wgpu::Device,
     swap_chain_dims: [u32; 2],
+    format: wgpu::TextureFormat,
     msaa_samples: u32,
 ) -> wgpu::Texture {
     wgpu::TextureBuilder::new()
         .size(swap_chain_dims)
         .sample_count(msaa_samples)
         .usage(wgpu::TextureUsages::RENDER_ATTACHMENT)
-        .format(Frame::TEXTURE_FORMAT)
+        .format(format)
         .build(device)
 }
 
-fn create_lin_srgba_texture(device: &wgpu::Device, swap_chain_dims: [u32; 2]) -> wgpu::Texture {
+fn create_lin_srgba_texture(
+    device: &wgpu::Device,
+    swap_chain_dims: [u32; 2],
+    format: wgpu::TextureFormat,
+) -> wgpu::Texture {
     wgpu::TextureBuilder::new()
         .size(swap_chain_dims)
-        .format(Frame::TEXTURE_FORMAT)
-        .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+        .format(format)
+        .usage(
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        )
         .build(device)
 }
 
 fn create_intermediary_lin_srgba(
     device: &wgpu::Device,
     swap_chain_dims: [u32; 2],
+    format: wgpu::TextureFormat,
     msaa_samples: u32,
 ) -> IntermediaryLinSrgba {
     let msaa_texture = match msaa_samples {
         0 | 1 => None,
         _ => {
-            let texture = create_lin_srgba_msaa_texture(device, swap_chain_dims, msaa_samples);
+            let texture =
+                create_lin_srgba_msaa_texture(device, swap_chain_dims, format, msaa_samples);
             let texture_view = texture.view().build();
             Some((texture, texture_view))
         }
     };
-    let texture = create_lin_srgba_texture(device, swap_chain_dims);
+    let texture = create_lin_srgba_texture(device, swap_chain_dims, format);
     let texture_view = texture.view().build();
     IntermediaryLinSrgba {
         msaa_texture,
@@ -34,6 +34,7 @@ pub struct RawFrame<'swap_chain> {
     device_queue_pair: Arc<wgpu::DeviceQueuePair>,
     texture_format: wgpu::TextureFormat,
     window_rect: geom::Rect,
+    scale_factor: f32,
 }
 
 impl<'swap_chain> RawFrame<'swap_chain> {
@@ -45,6 +46,7 @@ impl<'swap_chain> RawFrame<'swap_chain> {
         swap_chain_texture: &'swap_chain wgpu::TextureViewHandle,
         texture_format: wgpu::TextureFormat,
         window_rect: geom::Rect,
+        scale_factor: f32,
     ) -> Self {
         let ce_desc = wgpu::CommandEncoderDescriptor {
             label: Some("nannou_raw_frame"),
@@ -59,6 +61,7 @@ impl<'swap_chain> RawFrame<'swap_chain> {
             device_queue_pair,
             texture_format,
             window_rect,
+            scale_factor,
         };
         frame
     }
@@ -103,6 +106,14 @@ impl<'swap_chain> RawFrame<'swap_chain> {
         self.window_rect
     }
 
+    /// The ratio of pixels to points for the window at the time the frame was produced.
+    ///
+    /// This is equivalent to the result of calling **Window::scale_factor** on the window
+    /// associated with this **Frame**.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
     /// The `nth` frame for the associated window since the application started.
     ///
     /// E.g. the first frame yielded will return `0`, the second will return `1`, and so on.
@@ -0,0 +1,18 @@
+// Writes the accumulated image of previous frames to the frame's linear sRGBA image, weighted via
+// the blend constant.
+
+@group(0) @binding(0)
+var history: texture_2d<f32>;
+
+// A single triangle covering the whole target.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32((index << 1u) & 2u);
+    let y = f32(index & 2u);
+    return vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) pos: vec4<f32>) -> @location(0) vec4<f32> {
+    return textureLoad(history, vec2<i32>(pos.xy), 0);
+}
@@ -0,0 +1,42 @@
+// Simulates color vision deficiency by applying a linear RGB transform to the frame's linear
+// sRGBA image while writing it to the surface texture.
+
+struct VertexOutput {
+    @location(0) tex_coords: vec2<f32>,
+    @builtin(position) out_pos: vec4<f32>,
+};
+
+struct Data {
+    // The rows of the 3x3 simulation matrix.
+    r: vec4<f32>,
+    g: vec4<f32>,
+    b: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+@group(0) @binding(1)
+var tex_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> uniforms: Data;
+
+// A single triangle covering the whole target.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32((index << 1u) & 2u);
+    let y = f32(index & 2u);
+    let tex_coords = vec2<f32>(x, y);
+    let out_pos = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    return VertexOutput(tex_coords, out_pos);
+}
+
+@fragment
+fn fs_main(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    let color = textureSample(tex, tex_sampler, tex_coords);
+    let rgb = vec3<f32>(
+        dot(uniforms.r.xyz, color.rgb),
+        dot(uniforms.g.xyz, color.rgb),
+        dot(uniforms.b.xyz, color.rgb),
+    );
+    return vec4<f32>(clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+}
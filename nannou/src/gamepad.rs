@@ -0,0 +1,295 @@
+//! Items related to gamepad input.
+//!
+//! Requires the `gamepad` feature. On Linux, this depends on `libudev` being installed.
+//!
+//! The state of all known gamepads is available via `App::gamepads`. Changes to that state are
+//! also delivered to the app's `event` function as `Event::Gamepad` events, including when
+//! gamepads are connected or disconnected.
+//!
+//! Gamepads are polled once per pass of the app loop. Note that in `LoopMode::Wait`, gamepad
+//! input alone does not wake the loop.
+
+use crate::geom::Point2;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+pub use gilrs::{Axis, Button};
+
+/// A unique identifier for a gamepad.
+///
+/// A gamepad that is disconnected and reconnected retains its **Id**.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id(usize);
+
+/// One of the two analog sticks of a gamepad.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+/// A change in the state of a gamepad.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The gamepad was connected.
+    Connected,
+    /// The gamepad was disconnected.
+    Disconnected,
+    /// A button was pressed.
+    ButtonPressed(Button),
+    /// A button was released.
+    ButtonReleased(Button),
+    /// The value of a button changed within the range `0.0..=1.0`, e.g. for analog triggers.
+    ButtonChanged(Button, f32),
+    /// The value of an axis changed within the range `-1.0..=1.0`.
+    ///
+    /// The value is delivered as reported by the gamepad, before the deadzone is applied.
+    AxisChanged(Axis, f32),
+}
+
+/// The state of a single gamepad.
+#[derive(Clone, Debug)]
+pub struct Gamepad {
+    id: Id,
+    name: String,
+    is_connected: bool,
+    pressed: HashSet<Button>,
+    buttons: HashMap<Button, f32>,
+    axes: HashMap<Axis, f32>,
+    deadzone: f32,
+}
+
+/// The state of all gamepads known to the **App**.
+pub struct Gamepads {
+    gilrs: Option<gilrs::Gilrs>,
+    gamepads: BTreeMap<Id, Gamepad>,
+}
+
+impl Id {
+    /// The index of the gamepad, unique for the lifetime of the **App**.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+impl Gamepad {
+    fn new(id: Id, name: &str) -> Self {
+        Gamepad {
+            id,
+            name: name.to_string(),
+            is_connected: false,
+            pressed: HashSet::new(),
+            buttons: HashMap::new(),
+            axes: HashMap::new(),
+            deadzone: 0.0,
+        }
+    }
+
+    /// The unique identifier of the gamepad.
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// The name of the gamepad as reported by the platform or its controller mapping.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether or not the gamepad is currently connected.
+    pub fn is_connected(&self) -> bool {
+        self.is_connected
+    }
+
+    /// Whether or not the given button is currently pressed.
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /// An iterator yielding all buttons that are currently pressed.
+    pub fn pressed(&self) -> impl Iterator<Item = Button> + '_ {
+        self.pressed.iter().cloned()
+    }
+
+    /// The value of the given button within the range `0.0..=1.0`.
+    ///
+    /// This is useful for analog buttons like triggers. Digital buttons are either `0.0` or
+    /// `1.0`.
+    pub fn button_value(&self, button: Button) -> f32 {
+        self.buttons.get(&button).cloned().unwrap_or(0.0)
+    }
+
+    /// The value of the given axis within the range `-1.0..=1.0` with the deadzone applied.
+    ///
+    /// Values within the deadzone are reported as `0.0` and the remaining range is rescaled so
+    /// that values still begin at `0.0` at the edge of the deadzone.
+    pub fn axis(&self, axis: Axis) -> f32 {
+        let value = self.raw_axis(axis);
+        let magnitude = rescale(value.abs(), self.deadzone);
+        magnitude.copysign(value)
+    }
+
+    /// The value of the given axis as reported by the gamepad, without the deadzone applied.
+    pub fn raw_axis(&self, axis: Axis) -> f32 {
+        self.axes.get(&axis).cloned().unwrap_or(0.0)
+    }
+
+    /// The position of the given stick with each component within the range `-1.0..=1.0`.
+    ///
+    /// The deadzone is applied radially, avoiding the bias towards the horizontal and vertical
+    /// axes that results from applying it to each axis separately.
+    pub fn stick(&self, stick: Stick) -> Point2 {
+        let (x, y) = match stick {
+            Stick::Left => (Axis::LeftStickX, Axis::LeftStickY),
+            Stick::Right => (Axis::RightStickX, Axis::RightStickY),
+        };
+        let raw = Point2::new(self.raw_axis(x), self.raw_axis(y));
+        let length = raw.length();
+        if length == 0.0 {
+            return raw;
+        }
+        raw / length * rescale(length.min(1.0), self.deadzone)
+    }
+
+    /// Short-hand for `stick(Stick::Left)`.
+    pub fn left_stick(&self) -> Point2 {
+        self.stick(Stick::Left)
+    }
+
+    /// Short-hand for `stick(Stick::Right)`.
+    pub fn right_stick(&self) -> Point2 {
+        self.stick(Stick::Right)
+    }
+
+    // Apply the given event to the state of the gamepad.
+    fn apply(&mut self, event: &Event) {
+        match *event {
+            Event::Connected => self.is_connected = true,
+            Event::Disconnected => {
+                self.is_connected = false;
+                self.pressed.clear();
+                self.buttons.clear();
+                self.axes.clear();
+            }
+            Event::ButtonPressed(button) => {
+                self.pressed.insert(button);
+            }
+            Event::ButtonReleased(button) => {
+                self.pressed.remove(&button);
+            }
+            Event::ButtonChanged(button, value) => {
+                self.buttons.insert(button, value);
+            }
+            Event::AxisChanged(axis, value) => {
+                self.axes.insert(axis, value);
+            }
+        }
+    }
+}
+
+impl Gamepads {
+    // Connect to the platform's gamepad API.
+    pub(crate) fn new() -> Self {
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(gilrs),
+            Err(gilrs::Error::NotImplemented(gilrs)) => {
+                eprintln!("gamepads are not supported on this platform");
+                Some(gilrs)
+            }
+            Err(err) => {
+                eprintln!("failed to initialise gamepad support: {}", err);
+                None
+            }
+        };
+        let mut gamepads = Gamepads {
+            gilrs,
+            gamepads: BTreeMap::new(),
+        };
+        // Register the gamepads that were already connected before the app started.
+        if let Some(gilrs) = gamepads.gilrs.as_ref() {
+            for (id, pad) in gilrs.gamepads() {
+                let id = Id(id.into());
+                let mut gamepad = Gamepad::new(id, pad.name());
+                gamepad.is_connected = true;
+                gamepads.gamepads.insert(id, gamepad);
+            }
+        }
+        gamepads
+    }
+
+    /// The state of the gamepad with the given **Id**, if it is known.
+    pub fn get(&self, id: Id) -> Option<&Gamepad> {
+        self.gamepads.get(&id)
+    }
+
+    /// An iterator yielding all connected gamepads in order of their **Id**.
+    pub fn iter(&self) -> impl Iterator<Item = &Gamepad> {
+        self.gamepads.values().filter(|g| g.is_connected)
+    }
+
+    /// The first connected gamepad, if any.
+    ///
+    /// This is convenient for sketches that only expect a single gamepad.
+    pub fn first(&self) -> Option<&Gamepad> {
+        self.iter().next()
+    }
+
+    /// The number of connected gamepads.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Whether or not no gamepads are connected.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Process all pending gamepad events, returning the events that changed the gamepad state.
+    pub(crate) fn poll(&mut self, deadzone: f32) -> Vec<(Id, Event)> {
+        let mut events = vec![];
+        if let Some(gilrs) = self.gilrs.as_mut() {
+            while let Some(gilrs::Event { id, event, .. }) = gilrs.next_event() {
+                let event = match event {
+                    gilrs::EventType::Connected => Event::Connected,
+                    gilrs::EventType::Disconnected => Event::Disconnected,
+                    gilrs::EventType::ButtonPressed(button, _) => Event::ButtonPressed(button),
+                    gilrs::EventType::ButtonReleased(button, _) => Event::ButtonReleased(button),
+                    gilrs::EventType::ButtonChanged(button, value, _) => {
+                        Event::ButtonChanged(button, value)
+                    }
+                    gilrs::EventType::AxisChanged(axis, value, _) => {
+                        Event::AxisChanged(axis, value)
+                    }
+                    gilrs::EventType::ButtonRepeated(..) | gilrs::EventType::Dropped => continue,
+                };
+                let name = gilrs.gamepad(id).name().to_string();
+                let id = Id(id.into());
+                self.gamepads
+                    .entry(id)
+                    .or_insert_with(|| Gamepad::new(id, &name))
+                    .apply(&event);
+                events.push((id, event));
+            }
+        }
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.deadzone = deadzone;
+        }
+        events
+    }
+}
+
+impl fmt::Debug for Gamepads {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Gamepads")
+            .field("gamepads", &self.gamepads)
+            .finish_non_exhaustive()
+    }
+}
+
+// Map a magnitude within `deadzone..=1.0` to `0.0..=1.0`.
+fn rescale(magnitude: f32, deadzone: f32) -> f32 {
+    if magnitude <= deadzone {
+        0.0
+    } else {
+        ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+}
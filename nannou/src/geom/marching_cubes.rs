@@ -0,0 +1,268 @@
+//! Extracting the isosurface of a 3D scalar field via the marching cubes algorithm.
+//!
+//! A **ScalarGrid3** stores values sampled at evenly spaced points throughout a cuboid, e.g. from
+//! 3D noise or a sum of metaballs. `marching_cubes` produces an indexed triangle mesh of the
+//! surface along which the field crosses a threshold, known as the "iso" value.
+//!
+//! Each cube of the grid is split into six tetrahedra sharing the cube's main diagonal, which
+//! avoids the ambiguous cases of the classic lookup table and always produces a surface without
+//! holes.
+//!
+//! ```
+//! use nannou::geom::{marching_cubes, Cuboid, ScalarGrid3};
+//!
+//! // A sphere of radius 50 as the surface along which the field equals 0.0.
+//! let bounds = Cuboid::from_x_y_z_w_h_d(0.0, 0.0, 0.0, 200.0, 200.0, 200.0);
+//! let grid = ScalarGrid3::from_fn(bounds, 21, 21, 21, |p| 50.0 - p.length());
+//!
+//! let surface = marching_cubes(&grid, 0.0);
+//! assert_eq!(surface.indices.len() % 3, 0);
+//! for (p, n) in surface.points.iter().zip(&surface.normals) {
+//!     assert!((p.length() - 50.0).abs() < 1.0);
+//!     assert!(n.dot(p.normalize()) > 0.9);
+//! }
+//! ```
+//!
+//! The resulting mesh may be drawn via
+//! `draw.mesh().indexed(surface.points, surface.indices)`.
+
+use crate::geom::{Cuboid, Point3};
+use crate::glam::Vec3;
+use std::collections::HashMap;
+
+/// A grid of scalar values sampled at evenly spaced points throughout a cuboid.
+///
+/// The outermost points lie along the faces of the cuboid.
+#[derive(Clone, Debug)]
+pub struct ScalarGrid3 {
+    bounds: Cuboid,
+    resolution: [usize; 3],
+    values: Vec<f32>,
+}
+
+/// An indexed triangle mesh of the surface along which a scalar field crosses a threshold.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Isosurface {
+    /// The position of each vertex.
+    pub points: Vec<Point3>,
+    /// The unit normal of each vertex, pointing from the inside of the surface to the outside.
+    pub normals: Vec<Vec3>,
+    /// Each trio of indices describes a triangle, wound counter-clockwise when viewed from the
+    /// outside.
+    pub indices: Vec<usize>,
+}
+
+// The six tetrahedra of a cube, each made of four corners. Corner `i` lies at offset
+// `(i & 1, i >> 1 & 1, i >> 2 & 1)`.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 1, 5, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 5, 7],
+    [0, 4, 6, 7],
+];
+
+impl ScalarGrid3 {
+    /// A grid of `cols` by `rows` by `layers` zero values evenly covering the given bounds.
+    ///
+    /// The number of points along each axis is at least 2.
+    pub fn new(bounds: Cuboid, cols: usize, rows: usize, layers: usize) -> Self {
+        let resolution = [cols.max(2), rows.max(2), layers.max(2)];
+        ScalarGrid3 {
+            bounds,
+            resolution,
+            values: vec![0.0; resolution.iter().product()],
+        }
+    }
+
+    /// A grid of `cols` by `rows` by `layers` values produced by sampling the given function at
+    /// each point.
+    pub fn from_fn<F>(bounds: Cuboid, cols: usize, rows: usize, layers: usize, f: F) -> Self
+    where
+        F: Fn(Point3) -> f32,
+    {
+        let mut grid = Self::new(bounds, cols, rows, layers);
+        grid.set_each(|p, _| f(p));
+        grid
+    }
+
+    /// The bounds of the grid.
+    pub fn bounds(&self) -> Cuboid {
+        self.bounds
+    }
+
+    /// The number of columns, rows and layers.
+    pub fn resolution(&self) -> [usize; 3] {
+        self.resolution
+    }
+
+    /// The values of the grid, row by row and then layer by layer from the left, bottom, front.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Mutable access to the values of the grid, row by row and then layer by layer from the
+    /// left, bottom, front.
+    pub fn values_mut(&mut self) -> &mut [f32] {
+        &mut self.values
+    }
+
+    /// The position of the point at the given column, row and layer, counting from the left,
+    /// bottom, front.
+    pub fn position(&self, col: usize, row: usize, layer: usize) -> Point3 {
+        let b = &self.bounds;
+        let start = Vec3::new(b.left(), b.bottom(), b.front());
+        start + Vec3::new(col as f32, row as f32, layer as f32) * self.step()
+    }
+
+    /// The value at the given column, row and layer.
+    ///
+    /// Returns `None` if the point is out of range.
+    pub fn get(&self, col: usize, row: usize, layer: usize) -> Option<f32> {
+        self.index(col, row, layer).map(|i| self.values[i])
+    }
+
+    /// Set the value at the given column, row and layer.
+    ///
+    /// Does nothing if the point is out of range.
+    pub fn set(&mut self, col: usize, row: usize, layer: usize, value: f32) {
+        if let Some(i) = self.index(col, row, layer) {
+            self.values[i] = value;
+        }
+    }
+
+    /// Update each value with the given function of the point's position and current value.
+    pub fn set_each<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Point3, f32) -> f32,
+    {
+        for i in 0..self.values.len() {
+            let [col, row, layer] = self.coords(i);
+            let p = self.position(col, row, layer);
+            self.values[i] = f(p, self.values[i]);
+        }
+    }
+
+    // The distance between neighbouring points along each axis.
+    fn step(&self) -> Vec3 {
+        let [cols, rows, layers] = self.resolution;
+        let (w, h, d) = self.bounds.w_h_d();
+        Vec3::new(w, h, d) / Vec3::new((cols - 1) as f32, (rows - 1) as f32, (layers - 1) as f32)
+    }
+
+    fn index(&self, col: usize, row: usize, layer: usize) -> Option<usize> {
+        let [cols, rows, layers] = self.resolution;
+        if col < cols && row < rows && layer < layers {
+            Some((layer * rows + row) * cols + col)
+        } else {
+            None
+        }
+    }
+
+    fn coords(&self, index: usize) -> [usize; 3] {
+        let [cols, rows, _] = self.resolution;
+        [index % cols, index / cols % rows, index / (cols * rows)]
+    }
+
+    // The unit normal at the given point, pointing towards decreasing values.
+    fn normal(&self, index: usize) -> Vec3 {
+        let coords = self.coords(index);
+        let step = self.step();
+        let mut gradient = [0.0; 3];
+        for axis in 0..3 {
+            let (mut lo, mut hi) = (coords, coords);
+            lo[axis] = lo[axis].saturating_sub(1);
+            hi[axis] = (hi[axis] + 1).min(self.resolution[axis] - 1);
+            let value = |[c, r, l]: [usize; 3]| self.values[self.index(c, r, l).unwrap()];
+            let distance = (hi[axis] - lo[axis]) as f32 * step[axis];
+            gradient[axis] = (value(hi) - value(lo)) / distance;
+        }
+        -Vec3::from(gradient).normalize_or_zero()
+    }
+}
+
+/// Produce a mesh of the surface along which the values of the grid cross the given iso value.
+///
+/// Points with values greater than or equal to the iso value are considered inside. Vertices are
+/// shared between neighbouring triangles and their normals follow the gradient of the field,
+/// giving a smoothly shaded surface.
+pub fn marching_cubes(grid: &ScalarGrid3, iso: f32) -> Isosurface {
+    let [cols, rows, layers] = grid.resolution;
+    let mut surface = Isosurface::default();
+    // The vertex along each edge between two grid points, keyed by the indices of the points.
+    let mut vertices: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut vertex = |surface: &mut Isosurface, a: usize, b: usize| {
+        *vertices.entry((a.min(b), a.max(b))).or_insert_with(|| {
+            let (va, vb) = (grid.values[a], grid.values[b]);
+            let t = ((iso - va) / (vb - va)).clamp(0.0, 1.0);
+            let t = if t.is_finite() { t } else { 0.5 };
+            let [ca, ra, la] = grid.coords(a);
+            let [cb, rb, lb] = grid.coords(b);
+            let p = grid.position(ca, ra, la).lerp(grid.position(cb, rb, lb), t);
+            let n = grid.normal(a).lerp(grid.normal(b), t).normalize_or_zero();
+            surface.points.push(p);
+            surface.normals.push(n);
+            surface.points.len() - 1
+        })
+    };
+
+    for layer in 0..layers - 1 {
+        for row in 0..rows - 1 {
+            for col in 0..cols - 1 {
+                let corners = [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+                    let (c, r, l) = (col + (i & 1), row + (i >> 1 & 1), layer + (i >> 2 & 1));
+                    (l * rows + r) * cols + c
+                });
+                for tet in &TETRAHEDRA {
+                    // Order the corners of the tetrahedron with those inside first.
+                    let mut sorted = [0; 4];
+                    let (mut n_inside, mut n_outside) = (0, 4);
+                    for &i in tet {
+                        let ix = corners[i];
+                        if grid.values[ix] >= iso {
+                            sorted[n_inside] = ix;
+                            n_inside += 1;
+                        } else {
+                            n_outside -= 1;
+                            sorted[n_outside] = ix;
+                        }
+                    }
+                    let [a, b, c, d] = sorted;
+                    match n_inside {
+                        1 => {
+                            let tri =
+                                [(a, b), (a, c), (a, d)].map(|(x, y)| vertex(&mut surface, x, y));
+                            push_triangle(&mut surface, tri);
+                        }
+                        2 => {
+                            let quad = [(a, c), (a, d), (b, d), (b, c)]
+                                .map(|(x, y)| vertex(&mut surface, x, y));
+                            push_triangle(&mut surface, [quad[0], quad[1], quad[2]]);
+                            push_triangle(&mut surface, [quad[0], quad[2], quad[3]]);
+                        }
+                        3 => {
+                            let tri =
+                                [(d, a), (d, b), (d, c)].map(|(x, y)| vertex(&mut surface, x, y));
+                            push_triangle(&mut surface, tri);
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+    surface
+}
+
+// Push the triangle, winding it counter-clockwise when viewed from the side its normals face.
+fn push_triangle(surface: &mut Isosurface, [a, b, c]: [usize; 3]) {
+    let p = &surface.points;
+    let facing = (p[b] - p[a]).cross(p[c] - p[a]);
+    let n = &surface.normals;
+    if facing.dot(n[a] + n[b] + n[c]) < 0.0 {
+        surface.indices.extend([a, c, b]);
+    } else {
+        surface.indices.extend([a, b, c]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Extracting the contours of a 2D scalar field via the marching squares algorithm.
+//!
+//! A **ScalarGrid** stores values sampled at evenly spaced points across a rect, e.g. from a
+//! noise function or a sum of metaballs. `marching_squares` traces the lines along which the
+//! field crosses a threshold, producing a **Contour** for each connected line. Contours that lie
+//! entirely within the grid are closed, while those that run into the edge of the grid are left
+//! open.
+//!
+//! ```
+//! use nannou::geom::{marching_squares, Rect, ScalarGrid};
+//!
+//! // A single metaball at the origin.
+//! let bounds = Rect::from_w_h(200.0, 200.0);
+//! let grid = ScalarGrid::from_fn(bounds, 41, 41, |p| 1000.0 / p.length_squared().max(1.0));
+//!
+//! // The field equals 1.0 along a circle of radius ~31.6.
+//! let contours = marching_squares(&grid, 1.0);
+//! assert_eq!(contours.len(), 1);
+//! assert!(contours[0].closed);
+//! for p in contours[0].smooth(2).points {
+//!     assert!((p.length() - 1000f32.sqrt()).abs() < 1.0);
+//! }
+//! ```
+//!
+//! Contours may be drawn directly via `draw.contours(&grid, levels)`.
+
+use crate::geom::{Point2, Rect};
+use crate::glam::Vec2;
+use std::collections::{HashMap, VecDeque};
+
+/// A grid of scalar values sampled at evenly spaced points across a rect.
+///
+/// The outermost points lie along the edges of the rect.
+#[derive(Clone, Debug)]
+pub struct ScalarGrid {
+    bounds: Rect,
+    cols: usize,
+    rows: usize,
+    values: Vec<f32>,
+}
+
+/// A line along which a scalar field crosses a threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    /// The points along the line.
+    pub points: Vec<Point2>,
+    /// Whether the last point connects back to the first.
+    pub closed: bool,
+}
+
+// An edge between two neighbouring points of the grid, described by the column and row of its
+// lower-left point and whether it runs horizontally.
+type Edge = (usize, usize, bool);
+
+impl ScalarGrid {
+    /// A grid of `cols` by `rows` zero values evenly covering the given bounds.
+    ///
+    /// The number of columns and rows is at least 2.
+    pub fn new(bounds: Rect, cols: usize, rows: usize) -> Self {
+        let (cols, rows) = (cols.max(2), rows.max(2));
+        ScalarGrid {
+            bounds,
+            cols,
+            rows,
+            values: vec![0.0; cols * rows],
+        }
+    }
+
+    /// A grid of `cols` by `rows` values produced by sampling the given function at each point.
+    pub fn from_fn<F>(bounds: Rect, cols: usize, rows: usize, f: F) -> Self
+    where
+        F: Fn(Point2) -> f32,
+    {
+        let mut grid = Self::new(bounds, cols, rows);
+        grid.set_each(|p, _| f(p));
+        grid
+    }
+
+    /// The bounds of the grid.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// The number of columns and rows.
+    pub fn resolution(&self) -> [usize; 2] {
+        [self.cols, self.rows]
+    }
+
+    /// The values of the grid, row by row from the bottom left.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Mutable access to the values of the grid, row by row from the bottom left.
+    pub fn values_mut(&mut self) -> &mut [f32] {
+        &mut self.values
+    }
+
+    /// The position of the point at the given column and row, counting from the bottom left.
+    pub fn position(&self, col: usize, row: usize) -> Point2 {
+        let step = Vec2::new(
+            self.bounds.w() / (self.cols - 1) as f32,
+            self.bounds.h() / (self.rows - 1) as f32,
+        );
+        self.bounds.bottom_left() + Vec2::new(col as f32, row as f32) * step
+    }
+
+    /// The value at the given column and row.
+    ///
+    /// Returns `None` if the point is out of range.
+    pub fn get(&self, col: usize, row: usize) -> Option<f32> {
+        if col < self.cols && row < self.rows {
+            Some(self.values[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// Set the value at the given column and row.
+    ///
+    /// Does nothing if the point is out of range.
+    pub fn set(&mut self, col: usize, row: usize, value: f32) {
+        if col < self.cols && row < self.rows {
+            self.values[row * self.cols + col] = value;
+        }
+    }
+
+    /// Update each value with the given function of the point's position and current value.
+    pub fn set_each<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Point2, f32) -> f32,
+    {
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let p = self.position(col, row);
+                let v = &mut self.values[row * self.cols + col];
+                *v = f(p, *v);
+            }
+        }
+    }
+
+    // The point along the given edge at which the field crosses the threshold.
+    fn crossing(&self, (col, row, horizontal): Edge, threshold: f32) -> Point2 {
+        let (col_b, row_b) = if horizontal {
+            (col + 1, row)
+        } else {
+            (col, row + 1)
+        };
+        let a = self.values[row * self.cols + col];
+        let b = self.values[row_b * self.cols + col_b];
+        let t = ((threshold - a) / (b - a)).clamp(0.0, 1.0);
+        let t = if t.is_finite() { t } else { 0.5 };
+        self.position(col, row).lerp(self.position(col_b, row_b), t)
+    }
+}
+
+impl Contour {
+    /// A smoothed copy of the contour, produced by applying the given number of iterations of
+    /// Chaikin's corner-cutting algorithm.
+    ///
+    /// Each iteration roughly doubles the number of points. The end points of open contours are
+    /// preserved.
+    pub fn smooth(&self, iterations: usize) -> Contour {
+        let mut points = self.points.clone();
+        for _ in 0..iterations {
+            if points.len() < 3 {
+                break;
+            }
+            let n = points.len();
+            let mut smoothed = Vec::with_capacity(n * 2 + 2);
+            let segments = if self.closed { n } else { n - 1 };
+            if !self.closed {
+                smoothed.push(points[0]);
+            }
+            for i in 0..segments {
+                let (a, b) = (points[i], points[(i + 1) % n]);
+                smoothed.push(a.lerp(b, 0.25));
+                smoothed.push(a.lerp(b, 0.75));
+            }
+            if !self.closed {
+                smoothed.push(points[n - 1]);
+            }
+            points = smoothed;
+        }
+        Contour {
+            points,
+            closed: self.closed,
+        }
+    }
+}
+
+/// Trace the lines along which the values of the grid cross the given threshold.
+///
+/// Points with values greater than or equal to the threshold are considered inside. Ambiguous
+/// cells, whose diagonally opposite corners are inside, are resolved by the average of their
+/// corners.
+pub fn marching_squares(grid: &ScalarGrid, threshold: f32) -> Vec<Contour> {
+    // Collect the segments crossing each cell as pairs of edges.
+    let mut segments: Vec<[Edge; 2]> = vec![];
+    for row in 0..grid.rows - 1 {
+        for col in 0..grid.cols - 1 {
+            let at = |c: usize, r: usize| grid.values[r * grid.cols + c];
+            let corners = [
+                at(col, row),
+                at(col + 1, row),
+                at(col + 1, row + 1),
+                at(col, row + 1),
+            ];
+            let case = corners
+                .iter()
+                .enumerate()
+                .filter(|&(_, &v)| v >= threshold)
+                .fold(0, |case, (i, _)| case | 1 << i);
+            let bottom = (col, row, true);
+            let right = (col + 1, row, false);
+            let top = (col, row + 1, true);
+            let left = (col, row, false);
+            let centre_inside = || corners.iter().sum::<f32>() / 4.0 >= threshold;
+            match case {
+                1 | 14 => segments.push([left, bottom]),
+                2 | 13 => segments.push([bottom, right]),
+                3 | 12 => segments.push([left, right]),
+                4 | 11 => segments.push([right, top]),
+                6 | 9 => segments.push([bottom, top]),
+                7 | 8 => segments.push([left, top]),
+                5 => {
+                    if centre_inside() {
+                        segments.extend([[bottom, right], [top, left]]);
+                    } else {
+                        segments.extend([[left, bottom], [right, top]]);
+                    }
+                }
+                10 => {
+                    if centre_inside() {
+                        segments.extend([[left, bottom], [right, top]]);
+                    } else {
+                        segments.extend([[bottom, right], [top, left]]);
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // Each edge is shared by at most two segments, one from each neighbouring cell.
+    let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (i, segment) in segments.iter().enumerate() {
+        for &edge in segment {
+            by_edge.entry(edge).or_default().push(i);
+        }
+    }
+
+    // Join the segments into contours.
+    let mut used = vec![false; segments.len()];
+    let mut contours = vec![];
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut edges: VecDeque<Edge> = segments[start].iter().copied().collect();
+        let mut closed = false;
+        // Follow the unused segment sharing the given edge, returning its other edge.
+        let mut follow = |edge: Edge| {
+            let &next = by_edge[&edge].iter().find(|&&i| !used[i])?;
+            used[next] = true;
+            let [a, b] = segments[next];
+            Some(if a == edge { b } else { a })
+        };
+        while let Some(edge) = follow(*edges.back().unwrap()) {
+            if edge == edges[0] {
+                closed = true;
+                break;
+            }
+            edges.push_back(edge);
+        }
+        if !closed {
+            while let Some(edge) = follow(edges[0]) {
+                edges.push_front(edge);
+            }
+        }
+        let points = edges
+            .into_iter()
+            .map(|edge| grid.crossing(edge, threshold))
+            .collect();
+        contours.push(Contour { points, closed });
+    }
+    contours
+}
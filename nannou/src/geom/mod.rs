@@ -9,7 +9,15 @@
 //! - Functions for determining the bounding rectangle or cuboid.
 //! - A function for finding the centroid.
 
+pub mod marching_cubes;
+pub mod marching_squares;
 pub mod path;
+pub mod quadtree;
+pub mod spatial_hash;
 
+pub use self::marching_cubes::{marching_cubes, Isosurface, ScalarGrid3};
+pub use self::marching_squares::{marching_squares, Contour, ScalarGrid};
 pub use self::path::{path, Path};
+pub use self::quadtree::QuadTree;
+pub use self::spatial_hash::SpatialHash;
 pub use nannou_core::geom::*;
@@ -0,0 +1,234 @@
+//! A quadtree for finding the points near a location without testing every point.
+//!
+//! The **QuadTree** recursively subdivides its bounds into quadrants as points are inserted,
+//! adapting to the density of the points. This makes it well suited to points that cluster
+//! unevenly, whereas the **SpatialHash** suits points spread evenly throughout a space.
+//!
+//! ```
+//! use nannou::geom::{pt2, QuadTree, Rect};
+//!
+//! let mut tree = QuadTree::new(Rect::from_w_h(100.0, 100.0));
+//! for i in 0..100 {
+//!     let x = (i % 10) as f32 * 10.0 - 45.0;
+//!     let y = (i / 10) as f32 * 10.0 - 45.0;
+//!     tree.insert(pt2(x, y), i).unwrap();
+//! }
+//! assert_eq!(tree.query_radius(pt2(-45.0, -45.0), 10.0).count(), 3);
+//! assert_eq!(tree.nearest(pt2(44.0, 44.0)).map(|(_, &i)| i), Some(99));
+//! assert!(tree.insert(pt2(100.0, 0.0), 100).is_err());
+//! ```
+
+use crate::geom::{pt2, Point2, Rect};
+use std::iter;
+
+/// Points within a rectangular area, recursively subdivided into quadrants, each associated with
+/// an item.
+#[derive(Clone, Debug)]
+pub struct QuadTree<T> {
+    root: Node,
+    capacity: usize,
+    points: Vec<(Point2, T)>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    rect: Rect,
+    depth: usize,
+    // The indices of the points within the node, empty once the node is subdivided.
+    points: Vec<usize>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl<T> QuadTree<T> {
+    /// The default number of points a node may contain before it is subdivided.
+    pub const DEFAULT_CAPACITY: usize = 8;
+    /// The depth beyond which nodes are no longer subdivided, limiting the depth of the tree in
+    /// the case that many points lie at the same position.
+    pub const MAX_DEPTH: usize = 16;
+
+    /// An empty quadtree covering the given rect.
+    pub fn new(rect: Rect) -> Self {
+        Self::with_capacity(rect, Self::DEFAULT_CAPACITY)
+    }
+
+    /// An empty quadtree covering the given rect whose nodes may contain the given number of points
+    /// before they are subdivided.
+    pub fn with_capacity(rect: Rect, capacity: usize) -> Self {
+        QuadTree {
+            root: Node::new(rect.absolute(), 0),
+            capacity: capacity.max(1),
+            points: vec![],
+        }
+    }
+
+    /// The rect covered by the tree.
+    pub fn rect(&self) -> Rect {
+        self.root.rect
+    }
+
+    /// The number of points within the tree.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether or not the tree contains any points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Remove all points.
+    pub fn clear(&mut self) {
+        self.root = Node::new(self.root.rect, 0);
+        self.points.clear();
+    }
+
+    /// Insert the given item at the given point.
+    ///
+    /// Returns the item as an `Err` if the point lies outside the tree's rect.
+    pub fn insert(&mut self, point: Point2, item: T) -> Result<(), T> {
+        if !self.root.rect.contains(point) {
+            return Err(item);
+        }
+        let index = self.points.len();
+        self.points.push((point, item));
+        let QuadTree {
+            root,
+            capacity,
+            points,
+        } = self;
+        let mut node = root;
+        loop {
+            if let Some(ref mut children) = node.children {
+                node = &mut children[quadrant(node.rect, point)];
+                continue;
+            }
+            node.points.push(index);
+            if node.points.len() > *capacity && node.depth < Self::MAX_DEPTH {
+                node.subdivide(points);
+            }
+            return Ok(());
+        }
+    }
+
+    /// All points within the tree along with their items, in the order in which they were
+    /// inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.points.iter().map(|(p, item)| (*p, item))
+    }
+
+    /// All points within the given rect along with their items.
+    pub fn query_rect(&self, rect: Rect) -> impl Iterator<Item = (Point2, &T)> {
+        let mut nodes = vec![&self.root];
+        let mut indices = [].iter();
+        iter::from_fn(move || loop {
+            if let Some(&ix) = indices.next() {
+                let (p, ref item) = self.points[ix];
+                if rect.contains(p) {
+                    return Some((p, item));
+                }
+                continue;
+            }
+            let node = nodes.pop()?;
+            if node.rect.overlap(rect).is_none() {
+                continue;
+            }
+            if let Some(ref children) = node.children {
+                nodes.extend(children.iter());
+            }
+            indices = node.points.iter();
+        })
+    }
+
+    /// All points within `radius` of `centre` along with their items.
+    pub fn query_radius(&self, centre: Point2, radius: f32) -> impl Iterator<Item = (Point2, &T)> {
+        let rect = Rect::from_xy_wh(centre, Point2::splat(radius * 2.0));
+        let radius_sq = radius * radius;
+        self.query_rect(rect)
+            .filter(move |(p, _)| p.distance_squared(centre) <= radius_sq)
+    }
+
+    /// The point nearest to the given point along with its item.
+    ///
+    /// Returns `None` if the tree is empty.
+    pub fn nearest(&self, point: Point2) -> Option<(Point2, &T)> {
+        let mut nearest: Option<(f32, usize)> = None;
+        let mut nodes = vec![&self.root];
+        while let Some(node) = nodes.pop() {
+            // Skip nodes that cannot contain a nearer point.
+            if let Some((distance_sq, _)) = nearest {
+                if rect_distance_squared(node.rect, point) >= distance_sq {
+                    continue;
+                }
+            }
+            for &ix in &node.points {
+                let distance_sq = self.points[ix].0.distance_squared(point);
+                if nearest.map(|(d, _)| distance_sq < d).unwrap_or(true) {
+                    nearest = Some((distance_sq, ix));
+                }
+            }
+            // Visit the quadrant containing the point first, as it is most likely to be nearest.
+            if let Some(ref children) = node.children {
+                let first = quadrant(node.rect, point);
+                nodes.extend(
+                    children
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != first)
+                        .map(|(_, c)| c),
+                );
+                nodes.push(&children[first]);
+            }
+        }
+        nearest.map(|(_, ix)| {
+            let (p, ref item) = self.points[ix];
+            (p, item)
+        })
+    }
+}
+
+impl Node {
+    fn new(rect: Rect, depth: usize) -> Self {
+        Node {
+            rect,
+            depth,
+            points: vec![],
+            children: None,
+        }
+    }
+
+    // Split the node into quadrants, moving its points into them.
+    fn subdivide<T>(&mut self, points: &[(Point2, T)]) {
+        let (l, r, b, t) = self.rect.l_r_b_t();
+        let (x, y) = self.rect.x_y();
+        let depth = self.depth + 1;
+        let mut children = Box::new([
+            Node::new(Rect::from_corners(pt2(l, b), pt2(x, y)), depth),
+            Node::new(Rect::from_corners(pt2(x, b), pt2(r, y)), depth),
+            Node::new(Rect::from_corners(pt2(l, y), pt2(x, t)), depth),
+            Node::new(Rect::from_corners(pt2(x, y), pt2(r, t)), depth),
+        ]);
+        for ix in self.points.drain(..) {
+            children[quadrant(self.rect, points[ix].0)].points.push(ix);
+        }
+        self.children = Some(children);
+    }
+}
+
+// The index of the quadrant of the rect containing the point, in the order of `Node::subdivide`.
+fn quadrant(rect: Rect, point: Point2) -> usize {
+    let (x, y) = rect.x_y();
+    match (point.x < x, point.y < y) {
+        (true, true) => 0,
+        (false, true) => 1,
+        (true, false) => 2,
+        (false, false) => 3,
+    }
+}
+
+// The squared distance from the point to the nearest point within the rect.
+fn rect_distance_squared(rect: Rect, point: Point2) -> f32 {
+    let (l, r, b, t) = rect.l_r_b_t();
+    let dx = (l - point.x).max(point.x - r).max(0.0);
+    let dy = (b - point.y).max(point.y - t).max(0.0);
+    dx * dx + dy * dy
+}
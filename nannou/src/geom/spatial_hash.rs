@@ -0,0 +1,186 @@
+//! A spatial hash for finding the points near a location without testing every point.
+//!
+//! Points are bucketed into a uniform grid of square cells. Queries only test the points within
+//! the cells that overlap the queried area, making the **SpatialHash** well suited to sketches in
+//! which many agents interact with their neighbours, e.g. boids and particle systems. For best
+//! results, the cell size should roughly match the most common query radius.
+//!
+//! As agents typically move every frame, the hash is usually rebuilt each update via `clear` and
+//! `insert`, retaining its allocations.
+//!
+//! ```
+//! use nannou::geom::{pt2, SpatialHash};
+//!
+//! let mut hash = SpatialHash::new(10.0);
+//! hash.insert(pt2(0.0, 0.0), "a");
+//! hash.insert(pt2(3.0, 4.0), "b");
+//! hash.insert(pt2(50.0, 0.0), "c");
+//! let mut near: Vec<_> = hash.query_radius(pt2(0.0, 0.0), 5.0).map(|(_, &s)| s).collect();
+//! near.sort();
+//! assert_eq!(near, ["a", "b"]);
+//! assert_eq!(hash.nearest(pt2(40.0, 0.0)).map(|(_, &s)| s), Some("c"));
+//! ```
+
+use crate::geom::{Point2, Rect};
+use std::collections::HashMap;
+
+/// Points bucketed into a uniform grid of square cells, each associated with an item.
+#[derive(Clone, Debug)]
+pub struct SpatialHash<T> {
+    cell_size: f32,
+    // The indices of the points within each cell.
+    cells: HashMap<[i32; 2], Vec<usize>>,
+    points: Vec<(Point2, T)>,
+    // The range of occupied cells, used to bound the search for the nearest point.
+    bounds: Option<[[i32; 2]; 2]>,
+}
+
+impl<T> SpatialHash<T> {
+    /// An empty spatial hash whose cells have the given width and height.
+    ///
+    /// **Panics** if `cell_size` is not greater than `0.0`.
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0, "the cell size must be greater than 0.0");
+        SpatialHash {
+            cell_size,
+            cells: Default::default(),
+            points: Default::default(),
+            bounds: None,
+        }
+    }
+
+    /// The width and height of each cell.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// The number of points within the hash.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether or not the hash contains any points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Remove all points, retaining allocated memory for re-use.
+    pub fn clear(&mut self) {
+        self.cells.values_mut().for_each(Vec::clear);
+        self.points.clear();
+        self.bounds = None;
+    }
+
+    /// Insert the given item at the given point.
+    pub fn insert(&mut self, point: Point2, item: T) {
+        let cell = self.cell(point);
+        self.cells.entry(cell).or_default().push(self.points.len());
+        self.points.push((point, item));
+        self.bounds = Some(match self.bounds {
+            None => [cell, cell],
+            Some([min, max]) => [
+                [min[0].min(cell[0]), min[1].min(cell[1])],
+                [max[0].max(cell[0]), max[1].max(cell[1])],
+            ],
+        });
+    }
+
+    /// All points within the hash along with their items, in the order in which they were
+    /// inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.points.iter().map(|(p, item)| (*p, item))
+    }
+
+    /// All points within the given rect along with their items.
+    pub fn query_rect(&self, rect: Rect) -> impl Iterator<Item = (Point2, &T)> {
+        // Only visit the cells that are both within the rect and occupied.
+        let [min, max] = self.bounds.unwrap_or([[0, 0], [-1, -1]]);
+        let [min_x, min_y] = self.cell(rect.bottom_left());
+        let [max_x, max_y] = self.cell(rect.top_right());
+        let (min_x, min_y) = (min_x.max(min[0]), min_y.max(min[1]));
+        let (max_x, max_y) = (max_x.min(max[0]), max_y.min(max[1]));
+        (min_y..=max_y)
+            .flat_map(move |y| (min_x..=max_x).map(move |x| [x, y]))
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flatten()
+            .map(move |&ix| {
+                let (p, ref item) = self.points[ix];
+                (p, item)
+            })
+            .filter(move |(p, _)| rect.contains(*p))
+    }
+
+    /// All points within `radius` of `centre` along with their items.
+    pub fn query_radius(&self, centre: Point2, radius: f32) -> impl Iterator<Item = (Point2, &T)> {
+        let rect = Rect::from_xy_wh(centre, Point2::splat(radius * 2.0));
+        let radius_sq = radius * radius;
+        self.query_rect(rect)
+            .filter(move |(p, _)| p.distance_squared(centre) <= radius_sq)
+    }
+
+    /// The point nearest to the given point along with its item.
+    ///
+    /// Returns `None` if the hash is empty.
+    pub fn nearest(&self, point: Point2) -> Option<(Point2, &T)> {
+        let [min, max] = self.bounds?;
+        let [cx, cy] = self.cell(point);
+        // The number of rings of cells around the point's cell that may contain points.
+        let max_ring = (cx - min[0])
+            .abs()
+            .max((cx - max[0]).abs())
+            .max((cy - min[1]).abs())
+            .max((cy - max[1]).abs());
+        let mut nearest: Option<(f32, usize)> = None;
+        for ring in 0..=max_ring {
+            // All points within further rings are at least this far away.
+            if let Some((distance_sq, _)) = nearest {
+                let ring_distance = (ring - 1) as f32 * self.cell_size;
+                if distance_sq <= ring_distance * ring_distance {
+                    break;
+                }
+            }
+            for cell in ring_cells([cx, cy], ring) {
+                for &ix in self.cells.get(&cell).into_iter().flatten() {
+                    let distance_sq = self.points[ix].0.distance_squared(point);
+                    if nearest.map(|(d, _)| distance_sq < d).unwrap_or(true) {
+                        nearest = Some((distance_sq, ix));
+                    }
+                }
+            }
+        }
+        nearest.map(|(_, ix)| {
+            let (p, ref item) = self.points[ix];
+            (p, item)
+        })
+    }
+
+    // The cell containing the given point.
+    fn cell(&self, point: Point2) -> [i32; 2] {
+        let cell = (point / self.cell_size).floor();
+        [cell.x as i32, cell.y as i32]
+    }
+}
+
+impl<T> Extend<(Point2, T)> for SpatialHash<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Point2, T)>,
+    {
+        for (point, item) in iter {
+            self.insert(point, item);
+        }
+    }
+}
+
+// The cells forming the square ring at the given distance from the given cell.
+fn ring_cells([cx, cy]: [i32; 2], ring: i32) -> impl Iterator<Item = [i32; 2]> {
+    (cy - ring..=cy + ring).flat_map(move |y| {
+        let on_edge = y == cy - ring || y == cy + ring;
+        let step = if on_edge {
+            1
+        } else {
+            (ring * 2).max(1) as usize
+        };
+        (cx - ring..=cx + ring).step_by(step).map(move |x| [x, y])
+    })
+}
@@ -0,0 +1,508 @@
+//! Interactive handles for editing the position, rotation and scale of model values with the
+//! mouse.
+//!
+//! A **Gizmo** is stored within your model and updated once per frame. Any number of values may
+//! then be attached to it via **Gizmo::transform**. Each attached value is rendered as a set of
+//! handles that may be dragged in order to tune the layout of a scene without recompiling.
+//!
+//! ```no_run
+//! use nannou::gizmo::{self, Gizmo};
+//! use nannou::prelude::*;
+//!
+//! struct Model {
+//!     gizmo: Gizmo,
+//!     light_pos: Vec2,
+//!     sun: gizmo::Transform,
+//! }
+//!
+//! fn update(app: &App, model: &mut Model, _update: Update) {
+//!     model.gizmo.update(app);
+//!     model.gizmo.transform(&mut model.light_pos);
+//!     model.gizmo.transform(&mut model.sun);
+//! }
+//!
+//! fn view(app: &App, model: &Model, frame: Frame) {
+//!     let draw = app.draw();
+//!     // ... draw the scene ...
+//!     model.gizmo.draw(&draw);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::color::LinSrgba;
+use crate::draw::Draw;
+use crate::geom::Vec3;
+use crate::glam::Vec2;
+use crate::App;
+
+/// The default length of the gizmo axes in points.
+pub const DEFAULT_SIZE: f32 = 60.0;
+
+/// The default distance in points within which the mouse is considered to be over a handle.
+pub const DEFAULT_TOLERANCE: f32 = 6.0;
+
+/// Tracks the mouse interaction with all values attached via **Gizmo::transform**.
+#[derive(Clone, Debug)]
+pub struct Gizmo {
+    /// The kind of transformation applied when dragging handles.
+    pub mode: Mode,
+    /// The length of the gizmo axes in points.
+    pub size: f32,
+    /// The distance in points within which the mouse is considered to be over a handle.
+    pub tolerance: f32,
+    /// Whether or not the gizmo should be rendered and respond to the mouse.
+    pub enabled: bool,
+    mouse: Vec2,
+    mouse_down: bool,
+    mouse_pressed: bool,
+    next_id: usize,
+    active: Option<Active>,
+    targets: Vec<Record>,
+}
+
+/// The kind of transformation applied by a **Gizmo**.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Mode {
+    /// Drag the centre to move freely, or an axis to move along that axis.
+    Translate,
+    /// Drag the ring to rotate around the centre.
+    ///
+    /// Targets that do not support rotation fall back to `Translate`.
+    Rotate,
+    /// Drag the centre to scale uniformly, or an axis to scale along that axis.
+    ///
+    /// Targets that do not support scaling fall back to `Translate`.
+    Scale,
+}
+
+/// The individual parts of a gizmo that may be hovered or dragged.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Handle {
+    /// The centre of the gizmo.
+    Center,
+    /// The x axis.
+    X,
+    /// The y axis.
+    Y,
+    /// The rotation ring.
+    Ring,
+}
+
+/// A 2D transform that may be edited via all gizmo modes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// The position of the origin.
+    pub position: Vec2,
+    /// The rotation around the origin in radians.
+    pub rotation: f32,
+    /// The scale along the local x and y axes.
+    pub scale: Vec2,
+}
+
+/// Values that may be edited via a **Gizmo**.
+///
+/// Only translation is required. Types that can also be rotated or scaled should override the
+/// respective pairs of methods.
+pub trait Target {
+    /// The position of the value in window coordinates.
+    fn position(&self) -> Vec2;
+
+    /// Move the value to the given position.
+    fn set_position(&mut self, position: Vec2);
+
+    /// The current rotation in radians, if the value supports rotation.
+    fn rotation(&self) -> Option<f32> {
+        None
+    }
+
+    /// Rotate the value to the given angle in radians.
+    fn set_rotation(&mut self, _radians: f32) {}
+
+    /// The current scale along the local x and y axes, if the value supports scaling.
+    fn scale(&self) -> Option<Vec2> {
+        None
+    }
+
+    /// Set the scale along the local x and y axes.
+    fn set_scale(&mut self, _scale: Vec2) {}
+}
+
+// The drag that is currently in progress.
+#[derive(Copy, Clone, Debug)]
+struct Active {
+    id: usize,
+    handle: Handle,
+    mouse: Vec2,
+    position: Vec2,
+    rotation: f32,
+    scale: Vec2,
+}
+
+// The state of an attached value, recorded for rendering.
+#[derive(Copy, Clone, Debug)]
+struct Record {
+    mode: Mode,
+    position: Vec2,
+    rotation: f32,
+    hovered: Option<Handle>,
+    active: Option<Handle>,
+}
+
+impl Gizmo {
+    /// Create a new gizmo with the given mode.
+    pub fn new(mode: Mode) -> Self {
+        Gizmo {
+            mode,
+            size: DEFAULT_SIZE,
+            tolerance: DEFAULT_TOLERANCE,
+            enabled: true,
+            mouse: Vec2::ZERO,
+            mouse_down: false,
+            mouse_pressed: false,
+            next_id: 0,
+            active: None,
+            targets: vec![],
+        }
+    }
+
+    /// Builder method for specifying the length of the gizmo axes.
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Update the mouse state and begin a new frame of interaction.
+    ///
+    /// This should be called once per frame, before any calls to `transform`.
+    pub fn update(&mut self, app: &App) {
+        let mouse = app.mouse.position();
+        let down = app.mouse.buttons.left().is_down();
+        self.update_mouse(mouse, down);
+    }
+
+    /// Update the mouse state from a custom source and begin a new frame of interaction.
+    ///
+    /// This is useful when the gizmo is drawn with a transformed `Draw` instance, in which case
+    /// the given `mouse` should be in the same coordinate space as the `Draw`.
+    pub fn update_mouse(&mut self, mouse: Vec2, down: bool) {
+        self.mouse_pressed = down && !self.mouse_down;
+        self.mouse_down = down;
+        self.mouse = mouse;
+        if !down {
+            self.active = None;
+        }
+        self.next_id = 0;
+        self.targets.clear();
+    }
+
+    /// Attach the given value to the gizmo, applying any edits made with the mouse.
+    ///
+    /// Values are identified by the order in which they are attached, so the same values should
+    /// be attached in the same order each frame. Returns `true` if the value was modified.
+    pub fn transform<T>(&mut self, target: &mut T) -> bool
+    where
+        T: Target + ?Sized,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        if !self.enabled {
+            return false;
+        }
+
+        let mode = match self.mode {
+            Mode::Rotate if target.rotation().is_none() => Mode::Translate,
+            Mode::Scale if target.scale().is_none() => Mode::Translate,
+            mode => mode,
+        };
+        let position = target.position();
+        let rotation = target.rotation().unwrap_or(0.0);
+        let scale = target.scale().unwrap_or(Vec2::ONE);
+
+        // Begin a drag if the mouse was pressed over one of our handles.
+        let hovered = self.hit_test(mode, position, rotation);
+        if self.mouse_pressed && self.active.is_none() {
+            if let Some(handle) = hovered {
+                self.active = Some(Active {
+                    id,
+                    handle,
+                    mouse: self.mouse,
+                    position,
+                    rotation,
+                    scale,
+                });
+            }
+        }
+
+        // Apply the drag in progress.
+        let mut changed = false;
+        let active = self.active.filter(|active| active.id == id);
+        if let Some(active) = active {
+            let delta = self.mouse - active.mouse;
+            match (mode, active.handle) {
+                (Mode::Translate, Handle::X) => {
+                    target.set_position(active.position + Vec2::new(delta.x, 0.0));
+                }
+                (Mode::Translate, Handle::Y) => {
+                    target.set_position(active.position + Vec2::new(0.0, delta.y));
+                }
+                (Mode::Translate, _) => target.set_position(active.position + delta),
+                (Mode::Rotate, _) => {
+                    let start = active.mouse - active.position;
+                    let end = self.mouse - active.position;
+                    let angle = start.angle_between(end);
+                    if angle.is_finite() {
+                        target.set_rotation(active.rotation + angle);
+                    }
+                }
+                (Mode::Scale, handle) => {
+                    let start = active.mouse - active.position;
+                    let end = self.mouse - active.position;
+                    let ratio = |axis: Vec2| {
+                        let (a, b) = (start.dot(axis), end.dot(axis));
+                        if a.abs() > f32::EPSILON {
+                            b / a
+                        } else {
+                            1.0
+                        }
+                    };
+                    let (x_axis, y_axis) = axes(active.rotation);
+                    let factor = match handle {
+                        Handle::X => Vec2::new(ratio(x_axis), 1.0),
+                        Handle::Y => Vec2::new(1.0, ratio(y_axis)),
+                        _ => {
+                            let s = match start.length() {
+                                len if len > f32::EPSILON => end.length() / len,
+                                _ => 1.0,
+                            };
+                            Vec2::splat(s)
+                        }
+                    };
+                    target.set_scale(active.scale * factor);
+                }
+            }
+            changed = target.position() != position
+                || target.rotation().unwrap_or(0.0) != rotation
+                || target.scale().unwrap_or(Vec2::ONE) != scale;
+        }
+
+        self.targets.push(Record {
+            mode,
+            position: target.position(),
+            rotation: target.rotation().unwrap_or(0.0),
+            hovered,
+            active: active.map(|active| active.handle),
+        });
+        changed
+    }
+
+    /// Whether or not one of the handles is currently being dragged.
+    ///
+    /// This can be used to avoid handling mouse input elsewhere while a gizmo has captured it.
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Whether or not the mouse is currently over one of the handles.
+    pub fn is_hovered(&self) -> bool {
+        self.targets.iter().any(|record| record.hovered.is_some())
+    }
+
+    /// Render the handles for all values attached since the last call to `update`.
+    pub fn draw(&self, draw: &Draw) {
+        if !self.enabled {
+            return;
+        }
+        let size = self.size;
+        let weight = 2.0;
+        for record in &self.targets {
+            let color_of = |handle: Handle, base: LinSrgba| -> LinSrgba {
+                if record.active == Some(handle) {
+                    lin(1.0, 0.9, 0.1)
+                } else if record.active.is_none() && record.hovered == Some(handle) {
+                    let (r, g, b, a) = base.into_components();
+                    LinSrgba::new((r + 1.0) * 0.5, (g + 1.0) * 0.5, (b + 1.0) * 0.5, a)
+                } else {
+                    base
+                }
+            };
+            let p = record.position;
+            let (x_axis, y_axis) = match record.mode {
+                Mode::Scale => axes(record.rotation),
+                _ => (Vec2::X, Vec2::Y),
+            };
+            let x_color = color_of(Handle::X, lin(1.0, 0.25, 0.25));
+            let y_color = color_of(Handle::Y, lin(0.25, 1.0, 0.25));
+            let center_color = color_of(Handle::Center, lin(0.9, 0.9, 0.9));
+            match record.mode {
+                Mode::Translate => {
+                    draw.arrow()
+                        .start(p)
+                        .end(p + x_axis * size)
+                        .weight(weight)
+                        .color(x_color);
+                    draw.arrow()
+                        .start(p)
+                        .end(p + y_axis * size)
+                        .weight(weight)
+                        .color(y_color);
+                    let s = center_size(size);
+                    draw.rect().xy(p).w_h(s, s).color(center_color);
+                }
+                Mode::Rotate => {
+                    let ring_color = color_of(Handle::Ring, lin(0.3, 0.5, 1.0));
+                    draw.ellipse()
+                        .xy(p)
+                        .radius(size)
+                        .no_fill()
+                        .stroke_weight(weight)
+                        .stroke_color(ring_color);
+                    let (dir, _) = axes(record.rotation);
+                    draw.line()
+                        .start(p)
+                        .end(p + dir * size)
+                        .weight(weight)
+                        .color(ring_color);
+                    draw.ellipse().xy(p).radius(weight * 1.5).color(ring_color);
+                }
+                Mode::Scale => {
+                    let s = center_size(size) * 0.75;
+                    for &(axis, color) in &[(x_axis, x_color), (y_axis, y_color)] {
+                        let end = p + axis * size;
+                        draw.line().start(p).end(end).weight(weight).color(color);
+                        draw.rect()
+                            .xy(end)
+                            .w_h(s, s)
+                            .rotate(record.rotation)
+                            .color(color);
+                    }
+                    draw.rect()
+                        .xy(p)
+                        .w_h(s * 1.5, s * 1.5)
+                        .rotate(record.rotation)
+                        .color(center_color);
+                }
+            }
+        }
+    }
+
+    // Determine which of the handles (if any) the mouse is over.
+    fn hit_test(&self, mode: Mode, position: Vec2, rotation: f32) -> Option<Handle> {
+        let size = self.size;
+        let tol = self.tolerance;
+        let rel = self.mouse - position;
+        let half_center = center_size(size) * 0.5 + tol;
+        match mode {
+            Mode::Translate | Mode::Scale => {
+                let (x_axis, y_axis) = match mode {
+                    Mode::Scale => axes(rotation),
+                    _ => (Vec2::X, Vec2::Y),
+                };
+                let (lx, ly) = (rel.dot(x_axis), rel.dot(y_axis));
+                if lx.abs() <= half_center && ly.abs() <= half_center {
+                    Some(Handle::Center)
+                } else if dist_to_axis(lx, ly, size) <= tol {
+                    Some(Handle::X)
+                } else if dist_to_axis(ly, lx, size) <= tol {
+                    Some(Handle::Y)
+                } else {
+                    None
+                }
+            }
+            Mode::Rotate => {
+                if (rel.length() - size).abs() <= tol {
+                    Some(Handle::Ring)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl Transform {
+    /// A transform at the given position with no rotation and a unit scale.
+    pub fn from_position(position: Vec2) -> Self {
+        Transform {
+            position,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Gizmo {
+    fn default() -> Self {
+        Gizmo::new(Mode::Translate)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            position: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::ONE,
+        }
+    }
+}
+
+impl Target for Vec2 {
+    fn position(&self) -> Vec2 {
+        *self
+    }
+    fn set_position(&mut self, position: Vec2) {
+        *self = position;
+    }
+}
+
+impl Target for Vec3 {
+    fn position(&self) -> Vec2 {
+        self.truncate()
+    }
+    fn set_position(&mut self, position: Vec2) {
+        self.x = position.x;
+        self.y = position.y;
+    }
+}
+
+impl Target for Transform {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+    fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+    fn rotation(&self) -> Option<f32> {
+        Some(self.rotation)
+    }
+    fn set_rotation(&mut self, radians: f32) {
+        self.rotation = radians;
+    }
+    fn scale(&self) -> Option<Vec2> {
+        Some(self.scale)
+    }
+    fn set_scale(&mut self, scale: Vec2) {
+        self.scale = scale;
+    }
+}
+
+// The local x and y axes for the given rotation.
+fn axes(rotation: f32) -> (Vec2, Vec2) {
+    let x = Vec2::new(rotation.cos(), rotation.sin());
+    (x, x.perp())
+}
+
+// The width of the centre handle for the given gizmo size.
+fn center_size(size: f32) -> f32 {
+    size * 0.2
+}
+
+// The distance from the point `(along, across)` to an axis of the given length.
+fn dist_to_axis(along: f32, across: f32, len: f32) -> f32 {
+    let along = along - along.max(0.0).min(len);
+    (along * along + across * across).sqrt()
+}
+
+fn lin(r: f32, g: f32, b: f32) -> LinSrgba {
+    LinSrgba::new(r, g, b, 1.0)
+}
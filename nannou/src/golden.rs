@@ -0,0 +1,369 @@
+//! Render **Draw**ings headlessly and compare them against stored reference ("golden") images.
+//!
+//! Golden image tests guard against unintended changes to rendering. Each test renders a
+//! **Draw**ing at a fixed size via a **Harness**, reads the pixels back and compares them to a
+//! reference image with a perceptual tolerance, so that the small differences in rasterization
+//! between GPUs and drivers do not cause failures.
+//!
+//! ```no_run
+//! use nannou::golden::Harness;
+//! use nannou::prelude::*;
+//!
+//! #[test]
+//! fn red_square() {
+//!     let mut harness = Harness::new([128, 128]).unwrap();
+//!     let draw = Draw::new();
+//!     draw.background().color(WHITE);
+//!     draw.rect().w_h(64.0, 64.0).color(RED);
+//!     harness.check(&draw, "tests/golden/red_square.png").unwrap();
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! When a reference image does not yet exist, the rendered image is saved in its place and the
+//! check passes. Set the `NANNOU_UPDATE_GOLDEN` environment variable to overwrite the reference
+//! images after an intended change. When a check fails, the rendered image and an image
+//! highlighting the differing pixels are saved beside the reference image with the `.actual.png`
+//! and `.diff.png` extensions.
+//!
+//! For deterministic results, drawings should not depend on time, randomness without a fixed
+//! seed, or system fonts.
+
+use crate::color::{Lab, Srgba};
+use crate::draw::{self, Draw};
+use crate::image::{self, GenericImage, GenericImageView, RgbaImage};
+use crate::wgpu;
+use futures::FutureExt;
+use std::path::{Path, PathBuf};
+use std::{error, fmt};
+
+/// The environment variable that, when set, causes `Harness::check` to overwrite reference
+/// images rather than compare against them.
+pub const UPDATE_ENV_VAR: &str = "NANNOU_UPDATE_GOLDEN";
+
+/// Renders **Draw**ings to images headlessly at a fixed size.
+#[derive(Debug)]
+pub struct Harness {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    renderer: draw::Renderer,
+    texture: wgpu::Texture,
+    tolerance: Tolerance,
+}
+
+/// The differences permitted between a rendered image and its reference image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tolerance {
+    /// The maximum CIE76 color difference (the distance between colors within the CIELAB color
+    /// space) at which two pixels are considered equal.
+    ///
+    /// Differences in alpha are scaled to the same range, i.e. fully transparent and opaque
+    /// differ by 100.
+    pub pixel: f32,
+    /// The maximum fraction of pixels that may differ.
+    pub differing: f32,
+}
+
+/// The result of comparing a rendered image to its reference image.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    /// The number of pixels whose difference exceeds the pixel tolerance.
+    pub differing_pixels: usize,
+    /// The total number of pixels compared.
+    pub total_pixels: usize,
+    /// The greatest difference between any two pixels.
+    pub max_difference: f32,
+    /// A faded copy of the rendered image with the differing pixels highlighted in red.
+    pub diff: RgbaImage,
+    /// The tolerance with which the images were compared.
+    pub tolerance: Tolerance,
+}
+
+/// Errors that might occur while rendering or checking a golden image.
+#[derive(Debug)]
+pub enum Error {
+    /// No GPU adapter was available.
+    NoAdapter,
+    /// Failed to request a device from the adapter.
+    RequestDevice(wgpu::RequestDeviceError),
+    /// Failed to map the rendered image into CPU memory.
+    Read(wgpu::BufferAsyncError),
+    /// Failed to load the reference image.
+    Load(image::ImageError),
+    /// Failed to save an image.
+    Save(image::ImageError),
+    /// The rendered image and the reference image differ in size.
+    SizeMismatch {
+        actual: [u32; 2],
+        expected: [u32; 2],
+    },
+    /// The rendered image differs from the reference image beyond the tolerance.
+    Mismatch {
+        path: PathBuf,
+        differing_pixels: usize,
+        total_pixels: usize,
+        max_difference: f32,
+    },
+}
+
+impl Harness {
+    /// The format of the rendered images.
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// Create a harness rendering images of the given size in pixels via the default GPU
+    /// adapter.
+    ///
+    /// Rendering is not multisampled, so that results depend as little as possible on the GPU.
+    pub fn new(size: [u32; 2]) -> Result<Self, Error> {
+        let instance = wgpu::Instance::default();
+        let options = wgpu::RequestAdapterOptions::default();
+        let adapter = futures::executor::block_on(instance.request_adapter(&options))
+            .or_else(|| instance.enumerate_adapters(wgpu::Backends::all()).next())
+            .ok_or(Error::NoAdapter)?;
+        let desc = wgpu::DeviceDescriptor {
+            label: Some("nannou_golden"),
+            features: wgpu::Features::empty(),
+            limits: adapter.limits(),
+        };
+        let (device, queue) = futures::executor::block_on(adapter.request_device(&desc, None))
+            .map_err(Error::RequestDevice)?;
+        Ok(Self::from_device(device, queue, size))
+    }
+
+    /// Create a harness rendering images of the given size in pixels via the given device.
+    pub fn from_device(device: wgpu::Device, queue: wgpu::Queue, size: [u32; 2]) -> Self {
+        let texture = wgpu::TextureBuilder::new()
+            .size(size)
+            .format(Self::FORMAT)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC)
+            .build(&device);
+        let renderer = draw::RendererBuilder::new().build(&device, size, 1.0, 1, Self::FORMAT);
+        Harness {
+            device,
+            queue,
+            renderer,
+            texture,
+            tolerance: Tolerance::default(),
+        }
+    }
+
+    /// Specify the tolerance used by `check`.
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The size of the rendered images in pixels.
+    pub fn size(&self) -> [u32; 2] {
+        self.texture.size()
+    }
+
+    /// Render the given **Draw**ing and read the resulting image back to the CPU.
+    ///
+    /// The drawing's commands are consumed as when drawing to a frame.
+    pub fn render(&mut self, draw: &Draw) -> Result<RgbaImage, Error> {
+        let desc = wgpu::CommandEncoderDescriptor {
+            label: Some("nannou_golden"),
+        };
+        let mut encoder = self.device.create_command_encoder(&desc);
+        self.renderer
+            .render_to_texture(&self.device, &mut encoder, draw, &self.texture);
+        let buffer = self.texture.to_buffer(&self.device, &mut encoder);
+        self.queue.submit(Some(encoder.finish()));
+
+        // Begin mapping the buffer before waiting on the device.
+        let mut read = Box::pin(buffer.read());
+        let mapping = match read.as_mut().now_or_never() {
+            Some(mapping) => mapping,
+            None => {
+                self.device.poll(wgpu::Maintain::Wait);
+                futures::executor::block_on(read)
+            }
+        }
+        .map_err(Error::Read)?;
+        // Safe, as the buffer was created for a texture of the matching format.
+        let view = unsafe { mapping.as_image::<image::Rgba<u8>>() };
+        let mut image = RgbaImage::new(view.width(), view.height());
+        image
+            .copy_from(&view, 0, 0)
+            .expect("nannou internal error: image copy failed");
+        Ok(image)
+    }
+
+    /// Render the given **Draw**ing and compare it to the reference image at the given path.
+    ///
+    /// If the reference image does not exist or the `NANNOU_UPDATE_GOLDEN` environment variable
+    /// is set, the rendered image is saved as the reference instead. See the module
+    /// documentation for details.
+    pub fn check<P>(&mut self, draw: &Draw, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let image = self.render(draw)?;
+        check_image(&image, path, &self.tolerance)
+    }
+}
+
+impl Tolerance {
+    /// The default pixel tolerance, roughly the smallest color difference noticeable by eye.
+    pub const DEFAULT_PIXEL: f32 = 2.3;
+    /// The default fraction of pixels that may differ, allowing for differences in the
+    /// rasterization of edges between GPUs.
+    pub const DEFAULT_DIFFERING: f32 = 0.005;
+
+    /// Require that all pixels match exactly.
+    pub fn exact() -> Self {
+        Tolerance {
+            pixel: 0.0,
+            differing: 0.0,
+        }
+    }
+}
+
+impl Comparison {
+    /// Whether or not the compared images are equal within the tolerance.
+    pub fn passes(&self) -> bool {
+        let allowed = (self.tolerance.differing * self.total_pixels as f32).floor() as usize;
+        self.differing_pixels <= allowed
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            pixel: Self::DEFAULT_PIXEL,
+            differing: Self::DEFAULT_DIFFERING,
+        }
+    }
+}
+
+/// Compare the given rendered image to the given reference image, pixel by pixel.
+pub fn compare(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: &Tolerance,
+) -> Result<Comparison, Error> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(Error::SizeMismatch {
+            actual: actual.dimensions().into(),
+            expected: expected.dimensions().into(),
+        });
+    }
+    let (w, h) = actual.dimensions();
+    let mut diff = RgbaImage::new(w, h);
+    let mut differing_pixels = 0;
+    let mut max_difference = 0.0f32;
+    let pixels = actual
+        .pixels()
+        .zip(expected.pixels())
+        .zip(diff.pixels_mut());
+    for ((a, e), d) in pixels {
+        let difference = if a == e { 0.0 } else { pixel_difference(a, e) };
+        max_difference = max_difference.max(difference);
+        *d = if difference > tolerance.pixel {
+            differing_pixels += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            // Fade the matching pixels towards white so that the differences stand out.
+            let fade = |c: u8| 192 + c / 4;
+            image::Rgba([fade(a[0]), fade(a[1]), fade(a[2]), 255])
+        };
+    }
+    Ok(Comparison {
+        differing_pixels,
+        total_pixels: (w * h) as usize,
+        max_difference,
+        diff,
+        tolerance: *tolerance,
+    })
+}
+
+/// Compare the given rendered image to the reference image at the given path.
+///
+/// Behaves the same as `Harness::check`, for images rendered by other means.
+pub fn check_image<P>(image: &RgbaImage, path: P, tolerance: &Tolerance) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_ENV_VAR).is_some() || !path.exists() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        return image.save(path).map_err(Error::Save);
+    }
+    let expected = image::open(path).map_err(Error::Load)?.to_rgba8();
+    let comparison = compare(image, &expected, tolerance)?;
+    if comparison.passes() {
+        return Ok(());
+    }
+    image
+        .save(path.with_extension("actual.png"))
+        .map_err(Error::Save)?;
+    comparison
+        .diff
+        .save(path.with_extension("diff.png"))
+        .map_err(Error::Save)?;
+    Err(Error::Mismatch {
+        path: path.to_path_buf(),
+        differing_pixels: comparison.differing_pixels,
+        total_pixels: comparison.total_pixels,
+        max_difference: comparison.max_difference,
+    })
+}
+
+// The perceptual difference between two pixels, including their alpha.
+fn pixel_difference(a: &image::Rgba<u8>, b: &image::Rgba<u8>) -> f32 {
+    let lab = |p: &image::Rgba<u8>| -> Lab {
+        let rgb = Srgba::new(p[0], p[1], p[2], p[3]).into_format::<f32, f32>();
+        rgb.color.into_linear().into()
+    };
+    let (a_lab, b_lab) = (lab(a), lab(b));
+    let (dl, da, db) = (a_lab.l - b_lab.l, a_lab.a - b_lab.a, a_lab.b - b_lab.b);
+    let color = (dl * dl + da * da + db * db).sqrt();
+    let alpha = (a[3] as f32 - b[3] as f32).abs() / 255.0 * 100.0;
+    color.max(alpha)
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::RequestDevice(ref err) => Some(err),
+            Error::Read(ref err) => Some(err),
+            Error::Load(ref err) => Some(err),
+            Error::Save(ref err) => Some(err),
+            Error::NoAdapter | Error::SizeMismatch { .. } | Error::Mismatch { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NoAdapter => write!(f, "no GPU adapter is available"),
+            Error::RequestDevice(ref err) => write!(f, "failed to request a device: {}", err),
+            Error::Read(ref err) => write!(f, "failed to read the rendered image: {}", err),
+            Error::Load(ref err) => write!(f, "failed to load the reference image: {}", err),
+            Error::Save(ref err) => write!(f, "failed to save the image: {}", err),
+            Error::SizeMismatch { actual, expected } => write!(
+                f,
+                "the rendered image is {}x{} but the reference image is {}x{}",
+                actual[0], actual[1], expected[0], expected[1],
+            ),
+            Error::Mismatch {
+                ref path,
+                differing_pixels,
+                total_pixels,
+                max_difference,
+            } => write!(
+                f,
+                "{} of {} pixels differ from `{}` (max difference {:.2}), see the `.actual.png` \
+                 and `.diff.png` images beside it",
+                differing_pixels,
+                total_pixels,
+                path.display(),
+                max_difference,
+            ),
+        }
+    }
+}
@@ -0,0 +1,354 @@
+//! Snapshots of a model over time, for rewinding and inspecting simulations.
+//!
+//! Emergent behaviour in simulations can be difficult to debug as the moment at which things went
+//! wrong has often passed by the time the problem is noticed. A **History** serializes the model
+//! every `interval` frames into a ring buffer of snapshots, while the **Scrubber** provides an
+//! in-window timeline for selecting any snapshot to restore.
+//!
+//! ```no_run
+//! use nannou::history::{History, Scrubber};
+//! use nannou::prelude::*;
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Sim {
+//!     particles: Vec<Vec2>,
+//! }
+//!
+//! struct Model {
+//!     sim: Sim,
+//!     history: History,
+//!     scrubber: Scrubber,
+//! }
+//!
+//! fn update(app: &App, model: &mut Model, _update: Update) {
+//!     // While scrubbing, restore the selected snapshot rather than stepping the simulation.
+//!     if let Some(index) = model.scrubber.update(app, &model.history) {
+//!         model.sim = model.history.restore(index).unwrap().unwrap();
+//!     }
+//!     if model.scrubber.selected().is_some() {
+//!         return;
+//!     }
+//!     // ... step the simulation ...
+//!     model.history.record(app.elapsed_frames(), app.time, &model.sim).unwrap();
+//! }
+//!
+//! fn view(app: &App, model: &Model, frame: Frame) {
+//!     let draw = app.draw();
+//!     // ... draw the simulation ...
+//!     model.scrubber.draw(&draw, &model.history);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::color::LinSrgba;
+use crate::draw::Draw;
+use crate::geom::Rect;
+use crate::glam::Vec2;
+use crate::App;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// A ring buffer of serialized snapshots of a model.
+#[derive(Clone, Debug)]
+pub struct History {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+    interval: u64,
+}
+
+/// A serialized snapshot of a model at a single frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    frame: u64,
+    time: f32,
+    data: Vec<u8>,
+}
+
+/// An in-window timeline for selecting a snapshot from a **History**.
+///
+/// - Press or drag within the timeline to select a snapshot.
+/// - Press the right mouse button to clear the selection and resume.
+#[derive(Clone, Debug)]
+pub struct Scrubber {
+    /// The area of the window occupied by the timeline.
+    pub rect: Rect,
+    /// Whether or not the timeline should be rendered and respond to the mouse.
+    pub enabled: bool,
+    mouse: Vec2,
+    left_down: bool,
+    right_down: bool,
+    dragging: bool,
+    selected: Option<usize>,
+}
+
+impl History {
+    /// The default number of snapshots retained.
+    pub const DEFAULT_CAPACITY: usize = 600;
+    /// The default number of frames between snapshots.
+    pub const DEFAULT_INTERVAL: u64 = 1;
+
+    /// Create a history retaining at most `capacity` snapshots, taken once every `interval`
+    /// frames.
+    pub fn new(capacity: usize, interval: u64) -> Self {
+        History {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            interval: interval.max(1),
+        }
+    }
+
+    /// The maximum number of snapshots retained.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of frames between snapshots.
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Snapshot the model if at least `interval` frames have passed since the latest snapshot.
+    ///
+    /// The oldest snapshot is discarded once the history is at capacity. Returns whether or not a
+    /// snapshot was taken.
+    pub fn record<M>(&mut self, frame: u64, time: f32, model: &M) -> serde_json::Result<bool>
+    where
+        M: Serialize,
+    {
+        if let Some(latest) = self.latest() {
+            if frame < latest.frame + self.interval {
+                return Ok(false);
+            }
+        }
+        let data = serde_json::to_vec(model)?;
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(Snapshot { frame, time, data });
+        Ok(true)
+    }
+
+    /// Deserialize the model from the snapshot at the given index, where `0` is the oldest.
+    ///
+    /// Returns `None` if there is no snapshot at the given index.
+    pub fn restore<M>(&self, index: usize) -> Option<serde_json::Result<M>>
+    where
+        M: DeserializeOwned,
+    {
+        self.get(index).map(Snapshot::restore)
+    }
+
+    /// The snapshot at the given index, where `0` is the oldest.
+    pub fn get(&self, index: usize) -> Option<&Snapshot> {
+        self.snapshots.get(index)
+    }
+
+    /// The most recent snapshot.
+    pub fn latest(&self) -> Option<&Snapshot> {
+        self.snapshots.back()
+    }
+
+    /// An iterator yielding all snapshots from oldest to newest.
+    pub fn snapshots(&self) -> impl ExactSizeIterator<Item = &Snapshot> {
+        self.snapshots.iter()
+    }
+
+    /// Discard all snapshots after the given index, e.g. before resuming from a restored snapshot.
+    pub fn truncate(&mut self, index: usize) {
+        self.snapshots.truncate(index + 1);
+    }
+
+    /// Discard all snapshots.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// The number of snapshots retained.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Whether or not any snapshots have been taken.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+impl Snapshot {
+    /// The frame at which the snapshot was taken.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// The time in seconds at which the snapshot was taken.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// The size of the serialized model in bytes.
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Deserialize the model from the snapshot.
+    pub fn restore<M>(&self) -> serde_json::Result<M>
+    where
+        M: DeserializeOwned,
+    {
+        serde_json::from_slice(&self.data)
+    }
+}
+
+impl Scrubber {
+    /// Create a timeline occupying the given area of the window.
+    pub fn new(rect: Rect) -> Self {
+        Scrubber {
+            rect,
+            enabled: true,
+            mouse: Vec2::ZERO,
+            left_down: false,
+            right_down: false,
+            dragging: false,
+            selected: None,
+        }
+    }
+
+    /// Update the timeline with the app's mouse state.
+    ///
+    /// Returns the index of the selected snapshot if the selection changed.
+    pub fn update(&mut self, app: &App, history: &History) -> Option<usize> {
+        let mouse = app.mouse.position();
+        let left = app.mouse.buttons.left().is_down();
+        let right = app.mouse.buttons.right().is_down();
+        self.update_mouse(mouse, left, right, history)
+    }
+
+    /// Update the timeline with a custom mouse state.
+    ///
+    /// The `mouse` position should be in the same coordinate space as `rect`.
+    pub fn update_mouse(
+        &mut self,
+        mouse: Vec2,
+        left: bool,
+        right: bool,
+        history: &History,
+    ) -> Option<usize> {
+        let left_pressed = left && !self.left_down;
+        let right_pressed = right && !self.right_down;
+        self.mouse = mouse;
+        self.left_down = left;
+        self.right_down = right;
+        if !self.enabled || history.is_empty() {
+            self.dragging = false;
+            self.selected = None;
+            return None;
+        }
+
+        if !left {
+            self.dragging = false;
+        }
+        if left_pressed && self.rect.contains(mouse) {
+            self.dragging = true;
+        }
+        if right_pressed && !self.dragging {
+            self.selected = None;
+            return None;
+        }
+        if !self.dragging {
+            return None;
+        }
+        let index = self.index_at(mouse.x, history.len());
+        if self.selected == Some(index) {
+            return None;
+        }
+        self.selected = Some(index);
+        self.selected
+    }
+
+    /// The index of the selected snapshot, if any.
+    ///
+    /// While a snapshot is selected, the sketch should avoid stepping or recording the model.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Clear the selection.
+    pub fn deselect(&mut self) {
+        self.selected = None;
+        self.dragging = false;
+    }
+
+    /// Whether or not the timeline is currently being dragged.
+    pub fn is_active(&self) -> bool {
+        self.dragging
+    }
+
+    /// Render the timeline for the given history.
+    pub fn draw(&self, draw: &Draw, history: &History) {
+        if !self.enabled {
+            return;
+        }
+        let r = self.rect;
+        draw.rect()
+            .xy(r.xy())
+            .wh(r.wh())
+            .color(LinSrgba::new(0.0, 0.0, 0.0, 0.6))
+            .stroke_weight(1.0)
+            .stroke_color(LinSrgba::new(1.0, 1.0, 1.0, 0.3));
+
+        // The extent of the recorded history relative to its capacity.
+        let len = history.len();
+        if len == 0 {
+            return;
+        }
+        let filled = r.w() * len as f32 / history.capacity() as f32;
+        draw.rect()
+            .x_y(r.left() + filled * 0.5, r.y())
+            .w_h(filled, r.h())
+            .color(LinSrgba::new(1.0, 1.0, 1.0, 0.1));
+
+        // The playhead at the selected or latest snapshot.
+        let index = self.selected.unwrap_or(len - 1);
+        let x = self.x_at(index, len);
+        let color = match self.selected {
+            Some(_) => LinSrgba::new(1.0, 0.8, 0.2, 1.0),
+            None => LinSrgba::new(0.6, 0.6, 0.6, 1.0),
+        };
+        draw.line()
+            .start(Vec2::new(x, r.bottom()))
+            .end(Vec2::new(x, r.top()))
+            .weight(2.0)
+            .color(color);
+        if let Some(snapshot) = history.get(index) {
+            let label = format!("frame {} ({:.2}s)", snapshot.frame(), snapshot.time());
+            draw.text(&label)
+                .xy(r.xy())
+                .wh(r.wh())
+                .font_size(12)
+                .color(LinSrgba::new(1.0, 1.0, 1.0, 0.8));
+        }
+    }
+
+    // The snapshot index at the given x position along a timeline of `len` snapshots.
+    fn index_at(&self, x: f32, len: usize) -> usize {
+        let r = self.rect;
+        let t = if r.w() > 0.0 {
+            ((x - r.left()) / r.w()).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        ((t * (len - 1) as f32).round() as usize).min(len - 1)
+    }
+
+    // The x position of the snapshot at the given index along a timeline of `len` snapshots.
+    fn x_at(&self, index: usize, len: usize) -> f32 {
+        let r = self.rect;
+        match len {
+            0 | 1 => r.right(),
+            _ => r.left() + r.w() * index as f32 / (len - 1) as f32,
+        }
+    }
+}
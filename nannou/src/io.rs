@@ -1,5 +1,8 @@
 //! An extension of the `std::io` module. Includes functions for safely saving and loading files
 //! from any serializable types, along with functions specifically for working with JSON and TOML.
+//!
+//! The `load_bytes` function may be used to load files without blocking, including on the web
+//! where they are fetched from the server.
 
 use serde;
 use serde_json;
@@ -185,6 +188,47 @@ where
     Ok(t)
 }
 
+/// Asynchronously load the bytes of the file at the given path.
+///
+/// On the web, where there is no file system, the path is instead fetched as a URL relative to
+/// the page.
+pub async fn load_bytes<P>(path: P) -> io::Result<Vec<u8>>
+where
+    P: AsRef<Path>,
+{
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::fs::read(path).await
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        fetch_bytes(&path.as_ref().to_string_lossy()).await
+    }
+}
+
+// Fetch the bytes at the given URL via the browser's `fetch` API.
+#[cfg(target_arch = "wasm32")]
+async fn fetch_bytes(url: &str) -> io::Result<Vec<u8>> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    let js_err =
+        |err: wasm_bindgen::JsValue| io::Error::new(io::ErrorKind::Other, format!("{:?}", err));
+    let window = web_sys::window()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "no global `window` exists"))?;
+    let response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(js_err)?;
+    let response: web_sys::Response = response.dyn_into().map_err(js_err)?;
+    if !response.ok() {
+        let msg = format!("failed to fetch `{}`: status {}", url, response.status());
+        return Err(io::Error::new(io::ErrorKind::NotFound, msg));
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_err)?)
+        .await
+        .map_err(js_err)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
 /// Attempt to recursively walk the given directory and all its sub-directories.
 ///
 /// This function is shorthand for the `walkdir` crate's `WalkDir::new` constructor.
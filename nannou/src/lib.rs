@@ -22,25 +22,53 @@ pub use self::draw::Draw;
 pub use self::event::Event;
 pub use self::frame::Frame;
 #[doc(inline)]
-pub use nannou_core::{color, glam, math, rand};
+pub use nannou_core::{glam, math, rand};
 #[doc(inline)]
 pub use nannou_mesh as mesh;
 #[doc(inline)]
 pub use nannou_wgpu as wgpu;
 
 pub mod app;
+pub mod audio_buffer;
+pub mod brush;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod clipboard;
+pub mod color;
+pub mod config;
+pub mod contact_sheet;
+pub mod curve;
+pub mod display;
+pub mod drag;
 pub mod draw;
 pub mod ease;
 pub mod event;
+#[cfg(all(feature = "gallery", not(target_arch = "wasm32")))]
+pub mod examples;
 pub mod frame;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
 pub mod geom;
+pub mod gizmo;
+pub mod golden;
+pub mod history;
 pub mod image;
 pub mod io;
+pub mod lsystem;
 pub mod noise;
+pub mod particles;
+pub mod performance;
+#[cfg(feature = "physics2d")]
+pub mod physics2d;
 pub mod prelude;
+pub mod profile;
+pub mod span;
 pub mod state;
+pub mod stats;
 pub mod text;
 pub mod time;
+pub mod timecode;
+pub mod vector_field;
+pub mod watch;
 pub mod window;
 
 /// Begin building the `App`.
@@ -0,0 +1,484 @@
+//! Items related to L-systems, a classic technique for generating plants, fractals and other
+//! self-similar forms.
+//!
+//! An **LSystem** begins with a sequence of **Module**s known as the axiom. Each iteration
+//! rewrites every module according to the rules for its symbol, producing a new sequence. The
+//! resulting sequence is then interpreted as a series of turtle-graphics commands by an
+//! **Interpreter**.
+//!
+//! Modules are written as strings of symbols, each optionally followed by parameters, e.g.
+//! `"F(10)+(30)F"`. Rules may be:
+//!
+//! - **Deterministic**, always replacing a symbol with the same modules.
+//! - **Stochastic**, choosing between the rules for a symbol according to their weights.
+//! - **Parametric**, producing modules from the parameters of the module being replaced.
+//!
+//! ```
+//! use nannou::lsystem::{Interpreter, LSystem};
+//!
+//! // The Koch curve.
+//! let koch = LSystem::new("F").rule('F', "F+F-F-F+F");
+//! let modules = koch.generate(2);
+//! assert_eq!(modules.len(), 49);
+//!
+//! // Trace the curve with 90 degree turns, producing a single unbroken line.
+//! let lines = Interpreter::new().angle_degrees(90.0).polylines(&modules);
+//! assert_eq!(lines.len(), 1);
+//! assert_eq!(lines[0].len(), 26);
+//! ```
+//!
+//! The resulting lines may be stroked via `draw.path()` using `Interpreter::path`, or traced
+//! with a `draw.turtle()` via `Interpreter::trace`.
+
+use crate::draw::Turtle;
+use crate::geom::{self, pt2, Point2};
+use crate::math::deg_to_rad;
+use crate::rand::Rng;
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use std::{fmt, mem};
+
+/// A symbol along with its parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    /// The symbol identifying the module, e.g. `'F'`.
+    pub symbol: char,
+    /// The parameters of the module, e.g. the length of a line.
+    pub params: Vec<f32>,
+}
+
+/// A set of rules for repeatedly rewriting a sequence of modules, beginning with an axiom.
+#[derive(Clone)]
+pub struct LSystem {
+    axiom: Vec<Module>,
+    rules: HashMap<char, Vec<Rule>>,
+}
+
+/// Interprets a sequence of modules as turtle-graphics commands.
+///
+/// The following symbols are understood, while all others are ignored:
+///
+/// - `F` and `G` move forward, drawing a line.
+/// - `f` and `g` move forward without drawing.
+/// - `+` turns counter-clockwise and `-` turns clockwise.
+/// - `|` turns around.
+/// - `[` saves the current position and heading, and `]` restores them.
+///
+/// The first parameter of a move or turn overrides the step length or the angle in degrees
+/// respectively.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interpreter {
+    step: f32,
+    angle: f32,
+    position: Point2,
+    heading: f32,
+}
+
+/// Errors that might occur while parsing modules from a string.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The parameters of the module with the given symbol are missing their closing parenthesis.
+    UnclosedParams(char),
+    /// A parameter of the module with the given symbol is not a number.
+    InvalidParam(char, String),
+}
+
+#[derive(Clone)]
+struct Rule {
+    weight: f32,
+    successor: Successor,
+}
+
+#[derive(Clone)]
+enum Successor {
+    Modules(Vec<Module>),
+    Function(Arc<RuleFn>),
+}
+
+type RuleFn = dyn Fn(&[f32]) -> Option<Vec<Module>> + Send + Sync;
+
+// The turtle-graphics command described by a module.
+enum Command {
+    Forward(f32, bool),
+    Turn(f32),
+    Push,
+    Pop,
+}
+
+impl Module {
+    /// A module with the given symbol and parameters.
+    pub fn new(symbol: char, params: &[f32]) -> Self {
+        Module {
+            symbol,
+            params: params.to_vec(),
+        }
+    }
+}
+
+impl LSystem {
+    /// An L-system beginning with the given axiom and no rules.
+    ///
+    /// **Panics** if the axiom cannot be parsed. See `parse` for the accepted format.
+    pub fn new(axiom: &str) -> Self {
+        LSystem {
+            axiom: parse_or_panic(axiom),
+            rules: HashMap::new(),
+        }
+    }
+
+    /// Add a rule replacing each module with the given symbol with the given successor.
+    ///
+    /// **Panics** if the successor cannot be parsed. See `parse` for the accepted format.
+    pub fn rule(self, symbol: char, successor: &str) -> Self {
+        self.stochastic_rule(symbol, 1.0, successor)
+    }
+
+    /// Add a rule replacing each module with the given symbol with the given successor, chosen
+    /// randomly between all rules for the symbol according to its weight.
+    ///
+    /// **Panics** if the successor cannot be parsed. See `parse` for the accepted format.
+    pub fn stochastic_rule(mut self, symbol: char, weight: f32, successor: &str) -> Self {
+        let successor = Successor::Modules(parse_or_panic(successor));
+        self.push_rule(symbol, weight, successor);
+        self
+    }
+
+    /// Add a rule replacing each module with the given symbol with the modules produced by the
+    /// given function of the module's parameters.
+    ///
+    /// The rule only applies when the function returns `Some`, allowing for conditions on the
+    /// parameters.
+    pub fn parametric_rule<F>(self, symbol: char, f: F) -> Self
+    where
+        F: 'static + Fn(&[f32]) -> Option<Vec<Module>> + Send + Sync,
+    {
+        self.stochastic_parametric_rule(symbol, 1.0, f)
+    }
+
+    /// The same as `parametric_rule`, but the rule is chosen randomly between all applicable
+    /// rules for the symbol according to its weight.
+    pub fn stochastic_parametric_rule<F>(mut self, symbol: char, weight: f32, f: F) -> Self
+    where
+        F: 'static + Fn(&[f32]) -> Option<Vec<Module>> + Send + Sync,
+    {
+        self.push_rule(symbol, weight, Successor::Function(Arc::new(f)));
+        self
+    }
+
+    /// The modules from which the L-system begins.
+    pub fn axiom(&self) -> &[Module] {
+        &self.axiom
+    }
+
+    /// Rewrite the axiom the given number of times.
+    pub fn generate(&self, iterations: usize) -> Vec<Module> {
+        self.generate_with(iterations, &mut crate::rand::thread_rng())
+    }
+
+    /// The same as `generate`, but uses the given random number generator to choose between
+    /// stochastic rules, e.g. a seeded generator for reproducible results.
+    pub fn generate_with<R>(&self, iterations: usize, rng: &mut R) -> Vec<Module>
+    where
+        R: Rng + ?Sized,
+    {
+        let mut modules = self.axiom.clone();
+        for _ in 0..iterations {
+            let mut next = Vec::with_capacity(modules.len() * 2);
+            for module in &modules {
+                match self.rewrite(module, rng) {
+                    Some(successor) => next.extend(successor),
+                    None => next.push(module.clone()),
+                }
+            }
+            modules = next;
+        }
+        modules
+    }
+
+    fn push_rule(&mut self, symbol: char, weight: f32, successor: Successor) {
+        let rule = Rule { weight, successor };
+        self.rules.entry(symbol).or_default().push(rule);
+    }
+
+    // The modules replacing the given module, or `None` if no rules apply.
+    fn rewrite<R>(&self, module: &Module, rng: &mut R) -> Option<Vec<Module>>
+    where
+        R: Rng + ?Sized,
+    {
+        let rules = self.rules.get(&module.symbol)?;
+        let mut candidates: Vec<(f32, Vec<Module>)> = rules
+            .iter()
+            .filter_map(|rule| {
+                let modules = match rule.successor {
+                    Successor::Modules(ref modules) => modules.clone(),
+                    Successor::Function(ref f) => f(&module.params)?,
+                };
+                Some((rule.weight, modules))
+            })
+            .collect();
+        if candidates.len() <= 1 {
+            return candidates.pop().map(|(_, modules)| modules);
+        }
+        let total: f32 = candidates.iter().map(|&(w, _)| w).sum();
+        let ix = if total <= 0.0 {
+            rng.gen_range(0..candidates.len())
+        } else {
+            let mut target = rng.gen::<f32>() * total;
+            candidates
+                .iter()
+                .position(|&(w, _)| {
+                    let found = target < w;
+                    target -= w;
+                    found
+                })
+                // Guard against rounding errors by choosing the last candidate with a weight.
+                .unwrap_or_else(|| candidates.iter().rposition(|&(w, _)| w > 0.0).unwrap_or(0))
+        };
+        Some(candidates.swap_remove(ix).1)
+    }
+}
+
+impl Interpreter {
+    /// The default distance moved by each step.
+    pub const DEFAULT_STEP: f32 = 10.0;
+    /// The default angle of each turn in radians, i.e. 25 degrees.
+    pub const DEFAULT_ANGLE: f32 = 25.0 * PI / 180.0;
+    /// The default initial heading in radians, facing up the *y* axis.
+    pub const DEFAULT_HEADING: f32 = PI / 2.0;
+
+    /// An interpreter beginning at the origin and facing up the *y* axis.
+    pub fn new() -> Self {
+        Interpreter {
+            step: Self::DEFAULT_STEP,
+            angle: Self::DEFAULT_ANGLE,
+            position: pt2(0.0, 0.0),
+            heading: Self::DEFAULT_HEADING,
+        }
+    }
+
+    /// The distance moved by each step.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// The angle of each turn in radians.
+    pub fn angle(mut self, radians: f32) -> Self {
+        self.angle = radians;
+        self
+    }
+
+    /// The angle of each turn in degrees.
+    pub fn angle_degrees(self, degrees: f32) -> Self {
+        self.angle(deg_to_rad(degrees))
+    }
+
+    /// The position from which interpretation begins.
+    pub fn position(mut self, position: Point2) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// The initial heading in radians, measured counter-clockwise from the *x* axis.
+    pub fn heading(mut self, radians: f32) -> Self {
+        self.heading = radians;
+        self
+    }
+
+    /// The lines traced by the given modules.
+    ///
+    /// A new line begins each time the turtle moves without drawing or restores a saved state.
+    pub fn polylines(&self, modules: &[Module]) -> Vec<Vec<Point2>> {
+        let mut lines = vec![];
+        let mut line = vec![];
+        let (mut position, mut heading) = (self.position, self.heading);
+        let mut stack = vec![];
+        let finish = |line: &mut Vec<Point2>, lines: &mut Vec<Vec<Point2>>| {
+            if line.len() > 1 {
+                lines.push(mem::take(line));
+            }
+            line.clear();
+        };
+        for module in modules {
+            match self.command(module) {
+                Some(Command::Forward(distance, draw)) => {
+                    let (sin, cos) = heading.sin_cos();
+                    let next = position + pt2(cos, sin) * distance;
+                    if draw {
+                        if line.is_empty() {
+                            line.push(position);
+                        }
+                        line.push(next);
+                    } else {
+                        finish(&mut line, &mut lines);
+                    }
+                    position = next;
+                }
+                Some(Command::Turn(radians)) => heading += radians,
+                Some(Command::Push) => stack.push((position, heading)),
+                Some(Command::Pop) => {
+                    if let Some((p, h)) = stack.pop() {
+                        finish(&mut line, &mut lines);
+                        position = p;
+                        heading = h;
+                    }
+                }
+                None => (),
+            }
+        }
+        finish(&mut line, &mut lines);
+        lines
+    }
+
+    /// The lines traced by the given modules as a single path, ready for stroking via
+    /// `draw.path().stroke().events(path.iter())`.
+    pub fn path(&self, modules: &[Module]) -> geom::Path {
+        let mut builder = lyon::path::Path::builder();
+        for line in self.polylines(modules) {
+            builder.begin(line[0].to_array().into());
+            for p in &line[1..] {
+                builder.line_to(p.to_array().into());
+            }
+            builder.end(false);
+        }
+        builder.build().into()
+    }
+
+    /// Trace the given modules with the given turtle, using its pen for all lines.
+    ///
+    /// The turtle first moves to the interpreter's position and heading without drawing.
+    pub fn trace(&self, modules: &[Module], turtle: &Turtle) {
+        let down = turtle.pen().down;
+        turtle
+            .pen_up()
+            .goto(self.position)
+            .set_heading(self.heading);
+        if down {
+            turtle.pen_down();
+        }
+        for module in modules {
+            match self.command(module) {
+                Some(Command::Forward(distance, true)) => {
+                    turtle.forward(distance);
+                }
+                Some(Command::Forward(distance, false)) => {
+                    let down = turtle.pen().down;
+                    turtle.pen_up().forward(distance);
+                    if down {
+                        turtle.pen_down();
+                    }
+                }
+                Some(Command::Turn(radians)) => {
+                    turtle.turn(radians);
+                }
+                Some(Command::Push) => {
+                    turtle.push();
+                }
+                Some(Command::Pop) => {
+                    turtle.pop();
+                }
+                None => (),
+            }
+        }
+    }
+
+    fn command(&self, module: &Module) -> Option<Command> {
+        let param = module.params.first().copied();
+        let turn = || param.map(deg_to_rad).unwrap_or(self.angle);
+        let command = match module.symbol {
+            'F' | 'G' => Command::Forward(param.unwrap_or(self.step), true),
+            'f' | 'g' => Command::Forward(param.unwrap_or(self.step), false),
+            '+' => Command::Turn(turn()),
+            '-' => Command::Turn(-turn()),
+            '|' => Command::Turn(PI),
+            '[' => Command::Push,
+            ']' => Command::Pop,
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// Parse a sequence of modules from the given string.
+///
+/// Each character other than whitespace is the symbol of a module. A symbol may be followed by
+/// a comma-separated list of numeric parameters within parentheses, e.g. `"F(10)+(30)F"`.
+pub fn parse(s: &str) -> Result<Vec<Module>, ParseError> {
+    let mut modules = vec![];
+    let mut chars = s.chars().filter(|c| !c.is_whitespace()).peekable();
+    while let Some(symbol) = chars.next() {
+        let mut params = vec![];
+        if chars.peek() == Some(&'(') {
+            chars.next();
+            let mut list = String::new();
+            loop {
+                match chars.next() {
+                    Some(')') => break,
+                    Some(c) => list.push(c),
+                    None => return Err(ParseError::UnclosedParams(symbol)),
+                }
+            }
+            for param in list.split(',').filter(|p| !p.is_empty()) {
+                let value = param
+                    .parse()
+                    .map_err(|_| ParseError::InvalidParam(symbol, param.to_string()))?;
+                params.push(value);
+            }
+        }
+        modules.push(Module { symbol, params });
+    }
+    Ok(modules)
+}
+
+fn parse_or_panic(s: &str) -> Vec<Module> {
+    parse(s).unwrap_or_else(|err| panic!("failed to parse modules {:?}: {}", s, err))
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|p| p.to_string()).collect();
+            write!(f, "({})", params.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LSystem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut symbols: Vec<_> = self.rules.keys().collect();
+        symbols.sort();
+        f.debug_struct("LSystem")
+            .field("axiom", &self.axiom)
+            .field("rules", &symbols)
+            .finish()
+    }
+}
+
+impl StdError for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::UnclosedParams(symbol) => {
+                write!(f, "the parameters of `{}` are missing a `)`", symbol)
+            }
+            ParseError::InvalidParam(symbol, ref param) => {
+                write!(
+                    f,
+                    "the parameter `{}` of `{}` is not a number",
+                    param, symbol
+                )
+            }
+        }
+    }
+}
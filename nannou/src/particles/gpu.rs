@@ -0,0 +1,393 @@
+// The GPU resources used to render particles and to simulate them within a compute shader.
+
+use super::{gradient, Force, Particle, System};
+use crate::draw::renderer::{self, ViewResources};
+use crate::glam::Mat4;
+use crate::wgpu::{self, util::DeviceExt};
+use std::collections::HashMap;
+use std::mem;
+
+// The number of entries in the lookup tables describing the size and color of particles over
+// their lifetime.
+const LUT_LEN: usize = 64;
+const WORKGROUP_SIZE: u32 = 64;
+
+// The position, followed by the age, lifetime and size of each particle instance.
+const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 2] = [
+    wgpu::VertexAttribute {
+        format: wgpu::VertexFormat::Float32x2,
+        offset: 0,
+        shader_location: 0,
+    },
+    wgpu::VertexAttribute {
+        format: wgpu::VertexFormat::Float32x3,
+        offset: 16,
+        shader_location: 1,
+    },
+];
+
+const ADDITIVE_BLEND: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::SrcAlpha,
+    dst_factor: wgpu::BlendFactor::One,
+    operation: wgpu::BlendOperation::Add,
+};
+
+// A force as laid out within the simulation shader's storage buffer.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct ForceRaw {
+    kind: u32,
+    _pad: [u32; 3],
+    params: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct SimParams {
+    dt: f32,
+    time: f32,
+    force_count: u32,
+    particle_count: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct RenderUniforms {
+    transform: Mat4,
+    colors: [[f32; 4]; LUT_LEN],
+    // The size multipliers, packed four per element to satisfy uniform array alignment.
+    sizes: [[f32; 4]; LUT_LEN / 4],
+}
+
+// The appearance of a system's particles at the time it was drawn.
+#[derive(Clone, Debug)]
+pub(crate) struct Appearance {
+    colors: [[f32; 4]; LUT_LEN],
+    sizes: [[f32; 4]; LUT_LEN / 4],
+    additive: bool,
+}
+
+// State shared between a **System** and the callbacks submitted when drawing it.
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    // The simulation time accumulated since the GPU simulation was last advanced.
+    pub dt: f32,
+    pub time: f32,
+    pub forces: Vec<ForceRaw>,
+    capacity: usize,
+    // Particles yet to be uploaded along with the slot that they occupy.
+    pending: Vec<(u32, Particle)>,
+    particle_buffer: Option<wgpu::Buffer>,
+    resources: Option<Resources>,
+}
+
+#[derive(Debug)]
+struct Resources {
+    shader: wgpu::ShaderModule,
+    render_bind_group_layout: wgpu::BindGroupLayout,
+    render_pipeline_layout: wgpu::PipelineLayout,
+    // One pipeline per combination of output format, sample count and additive blending.
+    render_pipelines: HashMap<(wgpu::TextureFormat, u32, bool), wgpu::RenderPipeline>,
+    compute_bind_group_layout: wgpu::BindGroupLayout,
+    compute_pipeline: wgpu::ComputePipeline,
+}
+
+impl<'a> From<&'a Force> for ForceRaw {
+    fn from(force: &'a Force) -> Self {
+        let (kind, params) = match *force {
+            Force::Gravity(g) => (0, [g.x, g.y, 0.0, 0.0]),
+            Force::Drag(coefficient) => (1, [coefficient, 0.0, 0.0, 0.0]),
+            Force::CurlNoise {
+                scale,
+                strength,
+                speed,
+            } => (2, [scale, strength, speed, 0.0]),
+            Force::Attractor { position, strength } => (3, [position.x, position.y, strength, 0.0]),
+        };
+        ForceRaw {
+            kind,
+            _pad: [0; 3],
+            params,
+        }
+    }
+}
+
+impl Appearance {
+    pub fn new(system: &System) -> Self {
+        let mut colors = [[0.0; 4]; LUT_LEN];
+        let mut sizes = [[0.0; 4]; LUT_LEN / 4];
+        for i in 0..LUT_LEN {
+            let t = i as f32 / (LUT_LEN - 1) as f32;
+            let mut color = gradient(&system.colors, t);
+            color.alpha *= system.alpha_over_life.sample(t);
+            colors[i] = [color.red, color.green, color.blue, color.alpha];
+            sizes[i / 4][i % 4] = system.size_over_life.sample(t);
+        }
+        Appearance {
+            colors,
+            sizes,
+            additive: system.additive,
+        }
+    }
+}
+
+impl State {
+    // Remove all particles from the GPU.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.particle_buffer = None;
+        self.dt = 0.0;
+    }
+
+    // Queue the given particle for upload to the given slot.
+    pub fn spawn(&mut self, slot: u32, particle: Particle, capacity: usize) {
+        if self.capacity != capacity {
+            self.capacity = capacity;
+            self.particle_buffer = None;
+        }
+        self.pending.push((slot, particle));
+        // Only the most recent particles can fit.
+        if self.pending.len() > capacity {
+            let excess = self.pending.len() - capacity;
+            self.pending.drain(..excess);
+        }
+    }
+
+    // Render particles simulated on the CPU.
+    pub fn render_particles(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &ViewResources,
+        appearance: &Appearance,
+        particles: &[Particle],
+    ) {
+        if particles.is_empty() {
+            return;
+        }
+        let buffer = view.device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou particles instance_buffer"),
+            contents: particles_as_bytes(particles),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let resources = self
+            .resources
+            .get_or_insert_with(|| Resources::new(view.device));
+        resources.render(encoder, view, appearance, &buffer, particles.len() as u32);
+    }
+
+    // Upload any newly spawned particles, advance the simulation and render the particles.
+    pub fn simulate_and_render(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &ViewResources,
+        appearance: &Appearance,
+    ) {
+        let State {
+            ref mut dt,
+            time,
+            ref forces,
+            capacity,
+            ref mut pending,
+            ref mut particle_buffer,
+            ref mut resources,
+        } = *self;
+        if capacity == 0 {
+            return;
+        }
+        let device = view.device;
+        let resources = resources.get_or_insert_with(|| Resources::new(device));
+
+        // Particles are zero-initialised, and so begin dead with a lifetime of `0.0`.
+        let buffer = particle_buffer.get_or_insert_with(|| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("nannou particles particle_buffer"),
+                size: (capacity * mem::size_of::<Particle>()) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsages::VERTEX
+                    | wgpu::BufferUsages::STORAGE
+                    | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        // Copy each run of consecutive slots from a staging buffer.
+        if !pending.is_empty() {
+            let particles: Vec<Particle> = pending.iter().map(|&(_, p)| p).collect();
+            let staging = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+                label: Some("nannou particles staging_buffer"),
+                contents: particles_as_bytes(&particles),
+                usage: wgpu::BufferUsages::COPY_SRC,
+            });
+            let size = mem::size_of::<Particle>() as wgpu::BufferAddress;
+            let mut start = 0;
+            for i in 1..=pending.len() {
+                let run_ended = i == pending.len() || pending[i].0 != pending[i - 1].0 + 1;
+                if run_ended {
+                    let slot = pending[start].0 as wgpu::BufferAddress;
+                    let len = (i - start) as wgpu::BufferAddress;
+                    let src = start as wgpu::BufferAddress * size;
+                    encoder.copy_buffer_to_buffer(&staging, src, buffer, slot * size, len * size);
+                    start = i;
+                }
+            }
+            pending.clear();
+        }
+
+        // Advance the simulation by the time accumulated since it was last advanced.
+        let step = mem::replace(dt, 0.0);
+        if step > 0.0 {
+            let params = SimParams {
+                dt: step,
+                time,
+                force_count: forces.len() as u32,
+                particle_count: capacity as u32,
+            };
+            let params_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+                label: Some("nannou particles sim_params_buffer"),
+                contents: unsafe { wgpu::bytes::from(&params) },
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+            // Storage buffers may not be empty.
+            let forces: &[ForceRaw] = match forces.is_empty() {
+                true => &[ForceRaw {
+                    kind: u32::MAX,
+                    _pad: [0; 3],
+                    params: [0.0; 4],
+                }],
+                false => forces,
+            };
+            let forces_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+                label: Some("nannou particles forces_buffer"),
+                contents: unsafe { wgpu::bytes::from_slice(forces) },
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+            let bind_group = wgpu::BindGroupBuilder::new()
+                .buffer::<SimParams>(&params_buffer, 0..1)
+                .buffer::<Particle>(buffer, 0..capacity)
+                .buffer::<ForceRaw>(&forces_buffer, 0..forces.len())
+                .build(device, &resources.compute_bind_group_layout);
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("nannou particles compute_pass"),
+            });
+            pass.set_pipeline(&resources.compute_pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let workgroups = (capacity as u32).div_ceil(WORKGROUP_SIZE);
+            pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+
+        resources.render(encoder, view, appearance, buffer, capacity as u32);
+    }
+}
+
+impl Resources {
+    fn new(device: &wgpu::Device) -> Self {
+        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/particles.wgsl"));
+        let simulate_shader =
+            device.create_shader_module(wgpu::include_wgsl!("shaders/simulate.wgsl"));
+
+        let render_bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .uniform_buffer(wgpu::ShaderStages::VERTEX, false)
+            .build(device);
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("nannou particles render"),
+                bind_group_layouts: &[&render_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let compute_bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .uniform_buffer(wgpu::ShaderStages::COMPUTE, false)
+            .storage_buffer(wgpu::ShaderStages::COMPUTE, false, false)
+            .storage_buffer(wgpu::ShaderStages::COMPUTE, false, true)
+            .build(device);
+        let compute_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("nannou particles simulate"),
+                bind_group_layouts: &[&compute_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let compute_pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("nannou particles simulate"),
+            layout: Some(&compute_pipeline_layout),
+            module: &simulate_shader,
+            entry_point: "main",
+        });
+
+        Resources {
+            shader,
+            render_bind_group_layout,
+            render_pipeline_layout,
+            render_pipelines: HashMap::new(),
+            compute_bind_group_layout,
+            compute_pipeline,
+        }
+    }
+
+    // Render `count` particle instances from the given buffer.
+    fn render(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &ViewResources,
+        appearance: &Appearance,
+        instances: &wgpu::Buffer,
+        count: u32,
+    ) {
+        let device = view.device;
+        let projection = renderer::projection(view.output_attachment_size, view.scale_factor);
+        let uniforms = RenderUniforms {
+            transform: projection * view.transform,
+            colors: appearance.colors,
+            sizes: appearance.sizes,
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou particles uniform_buffer"),
+            contents: unsafe { wgpu::bytes::from(&uniforms) },
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .buffer::<RenderUniforms>(&uniform_buffer, 0..1)
+            .build(device, &self.render_bind_group_layout);
+
+        let key = (
+            view.output_attachment_format,
+            view.sample_count,
+            appearance.additive,
+        );
+        let Resources {
+            ref shader,
+            ref render_pipeline_layout,
+            ref mut render_pipelines,
+            ..
+        } = *self;
+        let pipeline = render_pipelines.entry(key).or_insert_with(|| {
+            let color_blend = match appearance.additive {
+                true => ADDITIVE_BLEND,
+                false => wgpu::RenderPipelineBuilder::DEFAULT_COLOR_BLEND,
+            };
+            wgpu::RenderPipelineBuilder::from_layout(render_pipeline_layout, shader)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(shader)
+                .fragment_entry_point("fs_main")
+                .color_format(view.output_attachment_format)
+                .color_blend(color_blend)
+                .add_instance_buffer::<Particle>(&INSTANCE_ATTRIBUTES)
+                .sample_count(view.sample_count)
+                .build(device)
+        });
+
+        let mut pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(view.output_attachment, |color| {
+                color
+                    .resolve_target(view.resolve_target)
+                    .load_op(wgpu::LoadOp::Load)
+            })
+            .begin(encoder);
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.set_vertex_buffer(0, instances.slice(..));
+        pass.draw(0..6, 0..count);
+    }
+}
+
+fn particles_as_bytes(data: &[Particle]) -> &[u8] {
+    unsafe { wgpu::bytes::from_slice(data) }
+}
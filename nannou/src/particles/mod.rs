@@ -0,0 +1,618 @@
+//! A particle system with emitters, forces and properties that vary over the life of each
+//! particle.
+//!
+//! A **System** spawns particles from its **Emitter**s, accelerates them with its **Force**s and
+//! renders them as soft round sprites whose size and color follow curves over their lifetime.
+//!
+//! Particles may be simulated on the CPU or, for much larger systems, within a GPU compute shader.
+//! Either way, particles are rendered with a single instanced draw call via `Draw::custom`.
+//!
+//! ```no_run
+//! use nannou::particles::{self, Emitter, Force};
+//! use nannou::prelude::*;
+//!
+//! fn model(_app: &App) -> particles::System {
+//!     particles::System::new()
+//!         .emitter(Emitter::point(pt2(0.0, -200.0)).rate(500.0).spread(0.5))
+//!         .force(Force::Gravity(vec2(0.0, -98.0)))
+//!         .colors(vec![lin_srgba(1.0, 0.8, 0.2, 1.0), lin_srgba(1.0, 0.1, 0.0, 1.0)])
+//! }
+//!
+//! fn update(_app: &App, system: &mut particles::System, update: Update) {
+//!     system.update(update.since_last.secs() as f32);
+//! }
+//!
+//! fn view(app: &App, system: &particles::System, frame: Frame) {
+//!     let draw = app.draw();
+//!     draw.background().color(BLACK);
+//!     system.draw(&draw);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::color::LinSrgba;
+use crate::curve::{self, Curve};
+use crate::draw::Draw;
+use crate::geom::{Point2, Rect};
+use crate::glam::Vec2;
+use crate::rand::rngs::SmallRng;
+use crate::rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+use std::f32::consts::PI;
+use std::ops::Range;
+use std::rc::Rc;
+
+mod gpu;
+
+/// The default maximum number of particles that may be alive at once.
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// A single particle.
+///
+/// The layout of this type matches that of the particles stored on the GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Particle {
+    /// The position of the particle.
+    pub position: Point2,
+    /// The velocity of the particle in points per second.
+    pub velocity: Vec2,
+    /// The time in seconds since the particle was emitted.
+    pub age: f32,
+    /// The total time in seconds for which the particle lives.
+    pub lifetime: f32,
+    /// The base diameter of the particle, scaled over its life by the system's size curve.
+    pub size: f32,
+    /// A random value within `0.0..1.0` assigned to the particle when emitted.
+    pub seed: f32,
+}
+
+/// The simulation backends that a **System** may use.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Backend {
+    /// Particles are simulated on the CPU and uploaded each time the system is drawn.
+    ///
+    /// Particles may be inspected via `System::particles`.
+    #[default]
+    Cpu,
+    /// Particles are stored and simulated on the GPU within a compute shader.
+    ///
+    /// The simulation is advanced when the system is rendered, so calls to `System::update`
+    /// between renders accumulate.
+    Gpu,
+}
+
+/// The area from which an **Emitter** spawns particles.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Spawn particles at a single point.
+    Point(Point2),
+    /// Spawn particles along the line between two points.
+    Line(Point2, Point2),
+    /// Spawn particles within a rectangle.
+    Rect(Rect),
+    /// Spawn particles within the ellipse inscribed in a rectangle.
+    Ellipse(Rect),
+    /// Spawn particles along a polyline, e.g. the outline of a shape.
+    Polyline(Vec<Point2>),
+}
+
+/// Describes how and where particles are spawned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Emitter {
+    /// The area from which particles are spawned.
+    pub shape: Shape,
+    /// The number of particles spawned per second.
+    pub rate: f32,
+    /// The initial direction of particles in radians.
+    pub direction: f32,
+    /// The range of angles in radians around `direction` within which particles are spawned.
+    pub spread: f32,
+    /// The range of initial speeds in points per second.
+    pub speed: Range<f32>,
+    /// The range of lifetimes in seconds.
+    pub lifetime: Range<f32>,
+    /// The range of base diameters in points.
+    pub size: Range<f32>,
+    // The fraction of a particle that is yet to be spawned.
+    pending: f32,
+}
+
+/// A force that accelerates all particles within a **System**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Force {
+    /// A constant acceleration in points per second per second.
+    Gravity(Vec2),
+    /// Slows particles in proportion to their velocity by the given coefficient.
+    Drag(f32),
+    /// Pushes particles along a turbulent, swirling, divergence-free flow.
+    CurlNoise {
+        /// The scale of the noise field. Smaller values produce broader swirls.
+        scale: f32,
+        /// The magnitude of the acceleration.
+        strength: f32,
+        /// The rate at which the noise field changes over time.
+        speed: f32,
+    },
+    /// Accelerates particles towards the given position. Negative strengths repel particles.
+    Attractor {
+        /// The position of the attractor.
+        position: Point2,
+        /// The magnitude of the acceleration.
+        strength: f32,
+    },
+}
+
+/// A collection of particles along with the emitters and forces that describe their behaviour.
+#[derive(Debug)]
+pub struct System {
+    /// The emitters from which particles are spawned.
+    pub emitters: Vec<Emitter>,
+    /// The forces applied to all particles.
+    pub forces: Vec<Force>,
+    /// Scales the size of each particle over its life.
+    pub size_over_life: Curve,
+    /// Scales the opacity of each particle over its life.
+    pub alpha_over_life: Curve,
+    /// The colors of particles over their life, evenly spaced from birth to death.
+    pub colors: Vec<LinSrgba>,
+    /// Whether particles are blended additively, brightening where they overlap.
+    pub additive: bool,
+    backend: Backend,
+    capacity: usize,
+    time: f32,
+    rng: SmallRng,
+    // The live particles when using the CPU backend.
+    particles: Vec<Particle>,
+    // The time at which the particle in each GPU slot expires.
+    expiries: Vec<f32>,
+    next_slot: usize,
+    gpu: Rc<RefCell<gpu::State>>,
+}
+
+impl Emitter {
+    /// An emitter with the given shape and the default properties.
+    pub fn new(shape: Shape) -> Self {
+        Emitter {
+            shape,
+            rate: 100.0,
+            direction: PI * 0.5,
+            spread: PI * 2.0,
+            speed: 50.0..100.0,
+            lifetime: 1.0..2.0,
+            size: 4.0..8.0,
+            pending: 0.0,
+        }
+    }
+
+    /// An emitter that spawns particles at a single point.
+    pub fn point(point: Point2) -> Self {
+        Self::new(Shape::Point(point))
+    }
+
+    /// An emitter that spawns particles along the line between two points.
+    pub fn line(start: Point2, end: Point2) -> Self {
+        Self::new(Shape::Line(start, end))
+    }
+
+    /// An emitter that spawns particles within a rectangle.
+    pub fn rect(rect: Rect) -> Self {
+        Self::new(Shape::Rect(rect))
+    }
+
+    /// An emitter that spawns particles within the ellipse inscribed in a rectangle.
+    pub fn ellipse(rect: Rect) -> Self {
+        Self::new(Shape::Ellipse(rect))
+    }
+
+    /// An emitter that spawns particles along the given polyline.
+    pub fn polyline<I>(points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Point2>,
+    {
+        Self::new(Shape::Polyline(
+            points.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    /// The number of particles spawned per second.
+    ///
+    /// By default, this is `100.0`.
+    pub fn rate(mut self, rate: f32) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// The initial direction of particles in radians.
+    ///
+    /// By default, particles are emitted upwards.
+    pub fn direction(mut self, radians: f32) -> Self {
+        self.direction = radians;
+        self
+    }
+
+    /// The range of angles in radians around the `direction` within which particles are spawned.
+    ///
+    /// By default, particles are emitted in all directions.
+    pub fn spread(mut self, radians: f32) -> Self {
+        self.spread = radians;
+        self
+    }
+
+    /// The range of initial speeds in points per second.
+    pub fn speed(mut self, speed: Range<f32>) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// The range of lifetimes in seconds.
+    pub fn lifetime(mut self, lifetime: Range<f32>) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// The range of base diameters in points.
+    pub fn size(mut self, size: Range<f32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    // Produce a new particle from this emitter.
+    fn spawn(&self, rng: &mut SmallRng) -> Particle {
+        let position = self.shape.sample(rng);
+        let angle = self.direction + (rng.gen::<f32>() - 0.5) * self.spread;
+        let speed = lerp(&self.speed, rng.gen());
+        Particle {
+            position,
+            velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+            age: 0.0,
+            lifetime: lerp(&self.lifetime, rng.gen()),
+            size: lerp(&self.size, rng.gen()),
+            seed: rng.gen(),
+        }
+    }
+}
+
+impl Shape {
+    // A random point within the shape.
+    fn sample(&self, rng: &mut SmallRng) -> Point2 {
+        match *self {
+            Shape::Point(p) => p,
+            Shape::Line(a, b) => a.lerp(b, rng.gen()),
+            Shape::Rect(r) => {
+                let (x, y) = (rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5);
+                r.xy() + Vec2::new(x, y) * r.wh()
+            }
+            Shape::Ellipse(r) => {
+                let angle = rng.gen::<f32>() * PI * 2.0;
+                let radius = rng.gen::<f32>().sqrt() * 0.5;
+                r.xy() + Vec2::new(angle.cos(), angle.sin()) * radius * r.wh()
+            }
+            Shape::Polyline(ref points) => {
+                let total: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+                let mut target = rng.gen::<f32>() * total;
+                for w in points.windows(2) {
+                    let len = w[0].distance(w[1]);
+                    if target <= len && len > 0.0 {
+                        return w[0].lerp(w[1], target / len);
+                    }
+                    target -= len;
+                }
+                points.last().cloned().unwrap_or(Point2::ZERO)
+            }
+        }
+    }
+}
+
+impl Force {
+    // The acceleration applied to the given particle at the given time.
+    fn acceleration(&self, particle: &Particle, time: f32) -> Vec2 {
+        match *self {
+            Force::Gravity(g) => g,
+            Force::Drag(coefficient) => -particle.velocity * coefficient,
+            Force::CurlNoise {
+                scale,
+                strength,
+                speed,
+            } => curl(particle.position * scale, time * speed) * strength,
+            Force::Attractor { position, strength } => {
+                (position - particle.position).normalize_or_zero() * strength
+            }
+        }
+    }
+}
+
+impl System {
+    /// A system simulated on the CPU without any emitters or forces.
+    pub fn new() -> Self {
+        System {
+            emitters: vec![],
+            forces: vec![],
+            size_over_life: Curve::constant(1.0),
+            alpha_over_life: Curve::new([[0.0, 1.0], [1.0, 0.0]])
+                .with_interpolation(curve::Interpolation::Linear),
+            colors: vec![LinSrgba::new(1.0, 1.0, 1.0, 1.0)],
+            additive: false,
+            backend: Backend::Cpu,
+            capacity: DEFAULT_CAPACITY,
+            time: 0.0,
+            rng: SmallRng::seed_from_u64(0),
+            particles: vec![],
+            expiries: vec![],
+            next_slot: 0,
+            gpu: Default::default(),
+        }
+    }
+
+    /// Simulate the system with the given backend.
+    ///
+    /// Any live particles are removed.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self.clear();
+        self
+    }
+
+    /// The maximum number of particles that may be alive at once.
+    ///
+    /// Particles are not emitted while the system is full when using the CPU backend. When using
+    /// the GPU backend, new particles replace the oldest. By default, this is
+    /// `DEFAULT_CAPACITY`.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self.clear();
+        self
+    }
+
+    /// Add an emitter to the system.
+    pub fn emitter(mut self, emitter: Emitter) -> Self {
+        self.emitters.push(emitter);
+        self
+    }
+
+    /// Add a force to the system.
+    pub fn force(mut self, force: Force) -> Self {
+        self.forces.push(force);
+        self
+    }
+
+    /// Scale the size of each particle over its life by the given curve.
+    ///
+    /// By default, the size is constant.
+    pub fn size_over_life(mut self, curve: Curve) -> Self {
+        self.size_over_life = curve;
+        self
+    }
+
+    /// Scale the opacity of each particle over its life by the given curve.
+    ///
+    /// By default, particles fade out linearly.
+    pub fn alpha_over_life(mut self, curve: Curve) -> Self {
+        self.alpha_over_life = curve;
+        self
+    }
+
+    /// The colors of particles over their life, evenly spaced from birth to death.
+    ///
+    /// By default, particles are white.
+    pub fn colors(mut self, colors: Vec<LinSrgba>) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Whether particles are blended additively, brightening where they overlap.
+    pub fn additive(mut self, additive: bool) -> Self {
+        self.additive = additive;
+        self
+    }
+
+    /// The seed used to randomise emitted particles.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = SmallRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The backend used to simulate the system.
+    pub fn simulation_backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// The number of live particles.
+    pub fn len(&self) -> usize {
+        match self.backend {
+            Backend::Cpu => self.particles.len(),
+            Backend::Gpu => self.expiries.iter().filter(|&&t| t > self.time).count(),
+        }
+    }
+
+    /// Whether or not there are no live particles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The live particles.
+    ///
+    /// This is always empty when using the GPU backend, as the particles only exist in GPU
+    /// memory.
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Remove all live particles.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.expiries.clear();
+        self.next_slot = 0;
+        self.gpu.borrow_mut().clear();
+    }
+
+    /// Immediately spawn the given number of particles from the given emitter.
+    ///
+    /// The emitter need not belong to the system, which is useful for bursts of particles.
+    pub fn emit(&mut self, emitter: &Emitter, count: usize) {
+        for _ in 0..count {
+            let particle = emitter.spawn(&mut self.rng);
+            self.push(particle);
+        }
+    }
+
+    /// Spawn particles from the emitters and advance the simulation by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        let mut emitters = std::mem::take(&mut self.emitters);
+        for emitter in &mut emitters {
+            emitter.pending += emitter.rate.max(0.0) * dt;
+            let count = emitter.pending.floor();
+            emitter.pending -= count;
+            self.emit(emitter, count as usize);
+        }
+        self.emitters = emitters;
+        self.time += dt;
+
+        match self.backend {
+            Backend::Cpu => {
+                let System {
+                    ref forces,
+                    ref mut particles,
+                    time,
+                    ..
+                } = *self;
+                for particle in particles.iter_mut() {
+                    let acceleration = forces
+                        .iter()
+                        .fold(Vec2::ZERO, |a, f| a + f.acceleration(particle, time));
+                    particle.velocity += acceleration * dt;
+                    particle.position += particle.velocity * dt;
+                    particle.age += dt;
+                }
+                particles.retain(|p| p.age < p.lifetime);
+            }
+            Backend::Gpu => {
+                let mut gpu = self.gpu.borrow_mut();
+                gpu.dt += dt;
+                gpu.time = self.time;
+                gpu.forces = self.forces.iter().map(gpu::ForceRaw::from).collect();
+            }
+        }
+    }
+
+    /// Draw the particles.
+    ///
+    /// The particles are rendered via `Draw::custom` with the transform of the given **Draw**.
+    pub fn draw(&self, draw: &Draw) {
+        let appearance = gpu::Appearance::new(self);
+        let particles = match self.backend {
+            Backend::Cpu => Some(self.particles.clone()),
+            Backend::Gpu => None,
+        };
+        let gpu = self.gpu.clone();
+        draw.custom(move |encoder, view| {
+            let mut gpu = gpu.borrow_mut();
+            match particles {
+                Some(ref particles) => gpu.render_particles(encoder, view, &appearance, particles),
+                None => gpu.simulate_and_render(encoder, view, &appearance),
+            }
+        });
+    }
+
+    // Add a particle to the system, if there is room.
+    fn push(&mut self, particle: Particle) {
+        if self.capacity == 0 {
+            return;
+        }
+        match self.backend {
+            Backend::Cpu => {
+                if self.particles.len() < self.capacity {
+                    self.particles.push(particle);
+                }
+            }
+            Backend::Gpu => {
+                let slot = self.next_slot;
+                self.next_slot = (slot + 1) % self.capacity;
+                let expiry = self.time + particle.lifetime;
+                match self.expiries.get_mut(slot) {
+                    Some(t) => *t = expiry,
+                    None => self.expiries.push(expiry),
+                }
+                self.gpu
+                    .borrow_mut()
+                    .spawn(slot as u32, particle, self.capacity);
+            }
+        }
+    }
+}
+
+impl Default for System {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Linearly interpolate within the given range.
+fn lerp(range: &Range<f32>, t: f32) -> f32 {
+    range.start + (range.end - range.start) * t
+}
+
+// Sample the evenly spaced gradient of colors at `t` within `0.0..=1.0`.
+fn gradient(colors: &[LinSrgba], t: f32) -> LinSrgba {
+    match colors.len() {
+        0 => LinSrgba::new(1.0, 1.0, 1.0, 1.0),
+        1 => colors[0],
+        n => {
+            let x = t.clamp(0.0, 1.0) * (n - 1) as f32;
+            let i = (x.floor() as usize).min(n - 2);
+            let f = x - i as f32;
+            let (a, b) = (colors[i], colors[i + 1]);
+            LinSrgba::new(
+                a.red + (b.red - a.red) * f,
+                a.green + (b.green - a.green) * f,
+                a.blue + (b.blue - a.blue) * f,
+                a.alpha + (b.alpha - a.alpha) * f,
+            )
+        }
+    }
+}
+
+// The curl of a 3D value noise potential field sampled in the xy plane at time `z`.
+//
+// This mirrors the implementation within the simulation compute shader.
+fn curl(p: Vec2, z: f32) -> Vec2 {
+    const E: f32 = 0.01;
+    let dx = noise(p.x + E, p.y, z) - noise(p.x - E, p.y, z);
+    let dy = noise(p.x, p.y + E, z) - noise(p.x, p.y - E, z);
+    Vec2::new(dy, -dx) / (2.0 * E)
+}
+
+fn noise(x: f32, y: f32, z: f32) -> f32 {
+    let (ix, iy, iz) = (x.floor(), y.floor(), z.floor());
+    let (fx, fy, fz) = (fade(x - ix), fade(y - iy), fade(z - iz));
+    let (ix, iy, iz) = (ix as i32, iy as i32, iz as i32);
+    let v = |dx: i32, dy: i32, dz: i32| {
+        let h = hash((ix + dx) as u32 ^ hash((iy + dy) as u32 ^ hash((iz + dz) as u32)));
+        h as f32 / u32::MAX as f32
+    };
+    let mix = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let x0 = mix(
+        mix(v(0, 0, 0), v(1, 0, 0), fx),
+        mix(v(0, 1, 0), v(1, 1, 0), fx),
+        fy,
+    );
+    let x1 = mix(
+        mix(v(0, 0, 1), v(1, 0, 1), fx),
+        mix(v(0, 1, 1), v(1, 1, 1), fx),
+        fy,
+    );
+    mix(x0, x1, fz)
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+// The PCG hash.
+fn hash(x: u32) -> u32 {
+    let state = x.wrapping_mul(747796405).wrapping_add(2891336453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+    (word >> 22) ^ word
+}
@@ -0,0 +1,66 @@
+// Renders each particle as an instanced, soft round sprite.
+
+struct Uniforms {
+    transform: mat4x4<f32>,
+    // The color of particles over their life.
+    colors: array<vec4<f32>, 64>,
+    // The size multiplier of particles over their life, packed four per element.
+    sizes: array<vec4<f32>, 16>,
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) local: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+fn size_at(i: u32) -> f32 {
+    return uniforms.sizes[i / 4u][i % 4u];
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) position: vec2<f32>,
+    // The age, lifetime and size of the particle.
+    @location(1) life: vec3<f32>,
+) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+    );
+    let local = corners[vertex_index];
+    var out: VertexOutput;
+    out.local = local;
+
+    // Collapse dead particles to a single point so that they produce no fragments.
+    if (life.x >= life.y) {
+        out.position = vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        out.color = vec4<f32>(0.0);
+        return out;
+    }
+
+    // Interpolate between the neighbouring entries of the lookup tables.
+    let x = clamp(life.x / life.y, 0.0, 1.0) * 63.0;
+    let i = min(u32(x), 62u);
+    let f = x - f32(i);
+    out.color = mix(uniforms.colors[i], uniforms.colors[i + 1u], f);
+    let size = mix(size_at(i), size_at(i + 1u), f) * life.z;
+
+    let p = position + local * size * 0.5;
+    out.position = uniforms.transform * vec4<f32>(p, 0.0, 1.0);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = 1.0 - smoothstep(0.5, 1.0, length(in.local));
+    return vec4<f32>(in.color.xyz, in.color.w * coverage);
+}
@@ -0,0 +1,105 @@
+// Advances each particle by applying forces and integrating its velocity and position.
+
+struct Params {
+    dt: f32,
+    time: f32,
+    force_count: u32,
+    particle_count: u32,
+};
+
+struct Particle {
+    position: vec2<f32>,
+    velocity: vec2<f32>,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    seed: f32,
+};
+
+struct Force {
+    kind: u32,
+    params: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+@group(0) @binding(1)
+var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2)
+var<storage, read> forces: array<Force>;
+
+// The PCG hash.
+fn hash(x: u32) -> u32 {
+    let state = x * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn fade(t: vec3<f32>) -> vec3<f32> {
+    return t * t * (3.0 - 2.0 * t);
+}
+
+fn value(i: vec3<i32>) -> f32 {
+    let h = hash(u32(i.x) ^ hash(u32(i.y) ^ hash(u32(i.z))));
+    return f32(h) / 4294967295.0;
+}
+
+// 3D value noise within the range 0.0 to 1.0.
+fn noise(p: vec3<f32>) -> f32 {
+    let i = vec3<i32>(floor(p));
+    let f = fade(fract(p));
+    let x0 = mix(
+        mix(value(i), value(i + vec3<i32>(1, 0, 0)), f.x),
+        mix(value(i + vec3<i32>(0, 1, 0)), value(i + vec3<i32>(1, 1, 0)), f.x),
+        f.y,
+    );
+    let x1 = mix(
+        mix(value(i + vec3<i32>(0, 0, 1)), value(i + vec3<i32>(1, 0, 1)), f.x),
+        mix(value(i + vec3<i32>(0, 1, 1)), value(i + vec3<i32>(1, 1, 1)), f.x),
+        f.y,
+    );
+    return mix(x0, x1, f.z);
+}
+
+// The curl of the noise potential field sampled in the xy plane at time `z`.
+fn curl(p: vec2<f32>, z: f32) -> vec2<f32> {
+    let e = 0.01;
+    let dx = noise(vec3<f32>(p.x + e, p.y, z)) - noise(vec3<f32>(p.x - e, p.y, z));
+    let dy = noise(vec3<f32>(p.x, p.y + e, z)) - noise(vec3<f32>(p.x, p.y - e, z));
+    return vec2<f32>(dy, -dx) / (2.0 * e);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= params.particle_count) {
+        return;
+    }
+    var p = particles[index];
+    if (p.age >= p.lifetime) {
+        return;
+    }
+
+    var acceleration = vec2<f32>(0.0);
+    for (var i = 0u; i < params.force_count; i = i + 1u) {
+        let force = forces[i];
+        let v = force.params;
+        if (force.kind == 0u) {
+            acceleration = acceleration + v.xy;
+        } else if (force.kind == 1u) {
+            acceleration = acceleration - p.velocity * v.x;
+        } else if (force.kind == 2u) {
+            acceleration = acceleration + curl(p.position * v.x, params.time * v.z) * v.y;
+        } else if (force.kind == 3u) {
+            let d = v.xy - p.position;
+            if (length(d) > 0.0) {
+                acceleration = acceleration + normalize(d) * v.z;
+            }
+        }
+    }
+
+    p.velocity = p.velocity + acceleration * params.dt;
+    p.position = p.position + p.velocity * params.dt;
+    p.age = p.age + params.dt;
+    particles[index] = p;
+}
@@ -0,0 +1,94 @@
+//! Items related to switching a sketch between an editor layout and a clean performance output.
+//!
+//! A sketch used for live performance often has two faces: an **editor** state in which a small
+//! preview sits alongside UI, scopes and other tooling, and a **performance** state in which the
+//! output fills a chosen display (e.g. a projector) with nothing else visible.
+//!
+//! Each window may be given a **Role** via `window::Builder::performance_role`:
+//!
+//! - The **Output** window is the clean output. In **Performance** mode it is made borderless
+//!   fullscreen on the performance display with the cursor hidden, and it is restored to its
+//!   windowed state when returning to **Editor** mode. This is also the window to capture from
+//!   when recording the performance, see `App::output_window`.
+//! - **Editor** windows hold UI and tooling. In **Performance** mode they are hidden if they share
+//!   the performance display with the output, and remain visible on other displays so that they
+//!   may still be used from a secondary screen.
+//!
+//! Windows without a role are unaffected. Switch modes via `App::set_performance_mode` or assign a
+//! key via `app::Builder::performance_key` to toggle between them with a single keystroke.
+
+use crate::app::App;
+use crate::window::Fullscreen;
+
+/// The state of the sketch as a whole.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Windows are shown as they were built, with UI and tooling visible.
+    #[default]
+    Editor,
+    /// The output window fills the performance display and editor windows make way for it.
+    Performance,
+}
+
+/// The part that a window plays in the performance **Mode** of the sketch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// A window containing UI, scopes or other tooling used while editing the sketch.
+    Editor,
+    /// The window presenting the clean output of the sketch.
+    Output,
+}
+
+impl Mode {
+    /// The opposite mode.
+    pub fn toggled(self) -> Self {
+        match self {
+            Mode::Editor => Mode::Performance,
+            Mode::Performance => Mode::Editor,
+        }
+    }
+}
+
+// Update the state of every window with a role in accordance with the given mode.
+pub(crate) fn apply(app: &App, mode: Mode) {
+    let windows = app.windows.borrow();
+    let output = windows
+        .values()
+        .find(|window| window.performance_role() == Some(Role::Output));
+
+    // The monitor on which the performance is presented.
+    let monitor = match app.performance_display() {
+        Some(id) => match app.display(id) {
+            Some(display) => Some(display.monitor().clone()),
+            None => {
+                eprintln!(
+                    "no display for {:?}, falling back to the current monitor",
+                    id
+                );
+                output.and_then(|window| window.current_monitor())
+            }
+        },
+        None => output.and_then(|window| window.current_monitor()),
+    };
+
+    for window in windows.values() {
+        match (window.performance_role(), mode) {
+            (Some(Role::Output), Mode::Performance) => {
+                let fullscreen = Fullscreen::Borderless(monitor.clone());
+                window.set_fullscreen_with(Some(fullscreen));
+                window.set_cursor_visible(false);
+            }
+            (Some(Role::Output), Mode::Editor) => {
+                window.set_fullscreen_with(None);
+                window.set_cursor_visible(true);
+            }
+            (Some(Role::Editor), Mode::Performance) => {
+                if monitor.is_some() && window.current_monitor() == monitor {
+                    window.set_visible(false);
+                }
+            }
+            (Some(Role::Editor), Mode::Editor) => window.set_visible(true),
+            (None, _) => (),
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! Items related to 2D rigid body physics, provided by [rapier](https://rapier.rs).
+//!
+//! Requires the `physics2d` feature.
+//!
+//! A **World** owns a rapier simulation along with a fixed-rate clock so that stepping stays in
+//! sync with app time regardless of the frame rate. The helper methods on **World** accept
+//! positions and sizes in the same points used by **Draw**, converting them to the meters
+//! preferred by rapier via the world's `pixels_per_meter` scale.
+//!
+//! The rapier body, collider and joint sets are public for full control. Note that these store
+//! all values in meters. Use `World::to_physics` and `World::from_physics` to convert.
+//!
+//! Colliders and joints may be rendered via `draw.debug_physics(&world)`.
+//!
+//! ```no_run
+//! use nannou::physics2d::{BodyType, World};
+//! use nannou::prelude::*;
+//!
+//! # fn update(world: &mut World, update: &Update) {
+//! world.add_rect(BodyType::Fixed, Rect::from_x_y_w_h(0.0, -200.0, 600.0, 20.0));
+//! let ball = world.add_circle(BodyType::Dynamic, pt2(0.0, 200.0), 20.0);
+//! world.update(update);
+//! let position = world.position(ball);
+//! # }
+//! ```
+
+use crate::color::{self, LinSrgba};
+use crate::draw::Draw;
+use crate::event::Update;
+use crate::geom::{self, pt2, Point2};
+use crate::glam::Vec2;
+use rapier2d::parry::shape::TypedShape;
+use rapier2d::prelude::*;
+use std::fmt;
+
+pub use rapier2d;
+pub use rapier2d::prelude::RigidBodyType as BodyType;
+pub use rapier2d::prelude::{ColliderHandle, ImpulseJointHandle, RigidBodyHandle};
+
+/// The default number of pixels per meter of simulated space.
+pub const DEFAULT_PIXELS_PER_METER: f32 = 50.0;
+
+/// The default rate at which the simulation is stepped.
+pub const DEFAULT_STEPS_PER_SECOND: f32 = 60.0;
+
+/// The default maximum number of steps taken per call to `World::advance`.
+pub const DEFAULT_MAX_STEPS: usize = 4;
+
+// The number of segments used to outline circles and capsule ends in debug rendering.
+const DEBUG_CIRCLE_SEGMENTS: usize = 32;
+
+/// A 2D physics simulation stepped at a fixed rate.
+pub struct World {
+    /// The acceleration applied to all dynamic bodies, in points per second squared.
+    pub gravity: Vec2,
+    /// The number of steps taken per second of app time.
+    pub steps_per_second: f32,
+    /// The maximum number of steps taken per call to `advance`.
+    ///
+    /// This stops the simulation from falling further and further behind when a step takes
+    /// longer than the time it simulates. Excess time is discarded.
+    pub max_steps: usize,
+    /// All rigid bodies within the simulation.
+    pub bodies: RigidBodySet,
+    /// All colliders within the simulation.
+    pub colliders: ColliderSet,
+    /// The joints between pairs of bodies.
+    pub impulse_joints: ImpulseJointSet,
+    /// The joints forming multibodies.
+    pub multibody_joints: MultibodyJointSet,
+    /// Parameters of the rapier solver. Note that `dt` is overwritten before each step.
+    pub integration_parameters: IntegrationParameters,
+    pixels_per_meter: f32,
+    accumulator: f32,
+    pipeline: PhysicsPipeline,
+    islands: IslandManager,
+    broad_phase: BroadPhase,
+    narrow_phase: NarrowPhase,
+    ccd_solver: CCDSolver,
+    query_pipeline: QueryPipeline,
+}
+
+impl World {
+    /// An empty world with earth-like gravity.
+    pub fn new() -> Self {
+        World {
+            gravity: pt2(0.0, -9.81 * DEFAULT_PIXELS_PER_METER),
+            steps_per_second: DEFAULT_STEPS_PER_SECOND,
+            max_steps: DEFAULT_MAX_STEPS,
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            integration_parameters: IntegrationParameters::default(),
+            pixels_per_meter: DEFAULT_PIXELS_PER_METER,
+            accumulator: 0.0,
+            pipeline: PhysicsPipeline::new(),
+            islands: IslandManager::new(),
+            broad_phase: BroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            ccd_solver: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
+        }
+    }
+
+    /// Specify the acceleration applied to all dynamic bodies, in points per second squared.
+    pub fn gravity(mut self, gravity: Vec2) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Specify the number of points that make up a meter of simulated space.
+    ///
+    /// Rapier is tuned for objects between roughly 0.1 and 10 meters in size, so this should be
+    /// chosen such that the bodies in the scene fall within that range. The gravity is rescaled
+    /// so that it remains the same in meters per second squared. By default, this is
+    /// `DEFAULT_PIXELS_PER_METER`.
+    ///
+    /// This should be specified before adding any bodies.
+    pub fn pixels_per_meter(mut self, pixels_per_meter: f32) -> Self {
+        self.gravity *= pixels_per_meter / self.pixels_per_meter;
+        self.pixels_per_meter = pixels_per_meter;
+        self
+    }
+
+    /// Specify the number of steps taken per second of app time.
+    pub fn steps_per_second(mut self, steps_per_second: f32) -> Self {
+        self.steps_per_second = steps_per_second;
+        self
+    }
+
+    /// The number of points that make up a meter of simulated space.
+    pub fn scale(&self) -> f32 {
+        self.pixels_per_meter
+    }
+
+    /// Convert a point or vector from points to the meters used by rapier.
+    pub fn to_physics(&self, point: Point2) -> Vector<Real> {
+        let p = point / self.pixels_per_meter;
+        vector![p.x, p.y]
+    }
+
+    /// Convert a point or vector from the meters used by rapier to points.
+    pub fn from_physics(&self, vector: Vector<Real>) -> Point2 {
+        pt2(vector.x, vector.y) * self.pixels_per_meter
+    }
+
+    /// Advance the simulation by the time elapsed since the last update.
+    ///
+    /// Returns the number of steps taken.
+    pub fn update(&mut self, update: &Update) -> usize {
+        self.advance(update.since_last.as_secs_f32())
+    }
+
+    /// Advance the simulation by the given number of seconds.
+    ///
+    /// The simulation is stepped at the fixed `steps_per_second` rate. Time that does not make up
+    /// a whole step is carried over to the next call. Returns the number of steps taken.
+    pub fn advance(&mut self, secs: f32) -> usize {
+        let dt = 1.0 / self.steps_per_second;
+        self.accumulator += secs;
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < self.max_steps {
+            self.accumulator -= dt;
+            self.step(dt);
+            steps += 1;
+        }
+        if steps == self.max_steps {
+            self.accumulator = self.accumulator.min(dt);
+        }
+        steps
+    }
+
+    /// Step the simulation once by the given number of seconds, ignoring the fixed rate.
+    pub fn step(&mut self, secs: f32) {
+        self.integration_parameters.dt = secs;
+        let gravity = self.to_physics(self.gravity);
+        self.pipeline.step(
+            &gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            &(),
+            &(),
+        );
+    }
+
+    /// Add a body of the given type with the given collider, positioned at `position` in points.
+    ///
+    /// The collider should be described in meters. See the `add_*` methods for adding bodies
+    /// described in points.
+    pub fn add_body(
+        &mut self,
+        body_type: BodyType,
+        position: Point2,
+        collider: ColliderBuilder,
+    ) -> RigidBodyHandle {
+        let body = RigidBodyBuilder::new(body_type).translation(self.to_physics(position));
+        let handle = self.bodies.insert(body);
+        self.colliders
+            .insert_with_parent(collider, handle, &mut self.bodies);
+        handle
+    }
+
+    /// Add a body with a circular collider.
+    pub fn add_circle(
+        &mut self,
+        body_type: BodyType,
+        center: Point2,
+        radius: f32,
+    ) -> RigidBodyHandle {
+        let collider = ColliderBuilder::ball(radius / self.pixels_per_meter);
+        self.add_body(body_type, center, collider)
+    }
+
+    /// Add a body with a rectangular collider matching the given rect.
+    pub fn add_rect(&mut self, body_type: BodyType, rect: geom::Rect) -> RigidBodyHandle {
+        let half = rect.wh() * 0.5 / self.pixels_per_meter;
+        let collider = ColliderBuilder::cuboid(half.x, half.y);
+        self.add_body(body_type, rect.xy(), collider)
+    }
+
+    /// Add a body with a collider matching the convex hull of the given points.
+    ///
+    /// The body is positioned at the centroid of the points. Returns `None` if the points do not
+    /// enclose an area.
+    pub fn add_polygon<I>(&mut self, body_type: BodyType, points: I) -> Option<RigidBodyHandle>
+    where
+        I: IntoIterator<Item = Point2>,
+    {
+        let points: Vec<Point2> = points.into_iter().collect();
+        let centroid = geom::centroid(points.iter().cloned())?;
+        let vertices: Vec<_> = points
+            .iter()
+            .map(|&p| Point::from(self.to_physics(p - centroid)))
+            .collect();
+        let collider = ColliderBuilder::convex_hull(&vertices)?;
+        Some(self.add_body(body_type, centroid, collider))
+    }
+
+    /// Add a body with a collider made of the line segments between the given points.
+    ///
+    /// The body is positioned at the origin. This is useful for describing fixed terrain.
+    pub fn add_polyline<I>(&mut self, body_type: BodyType, points: I) -> RigidBodyHandle
+    where
+        I: IntoIterator<Item = Point2>,
+    {
+        let vertices = points
+            .into_iter()
+            .map(|p| Point::from(self.to_physics(p)))
+            .collect();
+        let collider = ColliderBuilder::polyline(vertices, None);
+        self.add_body(body_type, pt2(0.0, 0.0), collider)
+    }
+
+    /// Join the two bodies so that they may rotate freely about the given point.
+    pub fn add_pin_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        anchor: Point2,
+    ) -> Option<ImpulseJointHandle> {
+        let anchor = self.to_physics(anchor);
+        let local_anchor =
+            |body: &RigidBody| body.position().inverse_transform_point(&anchor.into());
+        let anchor1 = local_anchor(self.bodies.get(body1)?);
+        let anchor2 = local_anchor(self.bodies.get(body2)?);
+        let joint = RevoluteJointBuilder::new()
+            .local_anchor1(anchor1)
+            .local_anchor2(anchor2);
+        Some(self.impulse_joints.insert(body1, body2, joint, true))
+    }
+
+    /// Remove the body along with its colliders and joints.
+    pub fn remove(&mut self, body: RigidBodyHandle) {
+        self.bodies.remove(
+            body,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        );
+    }
+
+    /// The position of the body in points.
+    pub fn position(&self, body: RigidBodyHandle) -> Option<Point2> {
+        self.bodies
+            .get(body)
+            .map(|b| self.from_physics(*b.translation()))
+    }
+
+    /// The rotation of the body in radians.
+    pub fn rotation(&self, body: RigidBodyHandle) -> Option<f32> {
+        self.bodies.get(body).map(|b| b.rotation().angle())
+    }
+
+    /// The velocity of the body in points per second.
+    pub fn velocity(&self, body: RigidBodyHandle) -> Option<Vec2> {
+        self.bodies
+            .get(body)
+            .map(|b| self.from_physics(*b.linvel()))
+    }
+
+    /// Move the body to the given position in points, waking it up.
+    pub fn set_position(&mut self, body: RigidBodyHandle, position: Point2) {
+        let translation = self.to_physics(position);
+        if let Some(body) = self.bodies.get_mut(body) {
+            body.set_translation(translation, true);
+        }
+    }
+
+    /// Apply an impulse to the body, in points per second times its mass.
+    pub fn apply_impulse(&mut self, body: RigidBodyHandle, impulse: Vec2) {
+        let impulse = self.to_physics(impulse);
+        if let Some(body) = self.bodies.get_mut(body) {
+            body.apply_impulse(impulse, true);
+        }
+    }
+
+    /// The body with a collider containing the given point, if any.
+    ///
+    /// This is useful for picking bodies with the mouse. The result reflects the positions of
+    /// colliders as of the last step.
+    pub fn body_at(&self, point: Point2) -> Option<RigidBodyHandle> {
+        let point = Point::from(self.to_physics(point));
+        let mut found = None;
+        self.query_pipeline.intersections_with_point(
+            &self.bodies,
+            &self.colliders,
+            &point,
+            QueryFilter::default(),
+            |handle| {
+                found = self.colliders.get(handle).and_then(|c| c.parent());
+                found.is_none()
+            },
+        );
+        found
+    }
+
+    /// Draw the outlines of all colliders and the anchors of all joints.
+    ///
+    /// Dynamic bodies are orange, or grey while sleeping. Fixed and kinematic bodies are green
+    /// and blue respectively. Joints are drawn in red.
+    pub fn debug_draw(&self, draw: &Draw) {
+        let weight = 1.5;
+        for (_, collider) in self.colliders.iter() {
+            let color = collider
+                .parent()
+                .and_then(|h| self.bodies.get(h))
+                .map(debug_color)
+                .unwrap_or_else(|| debug_color_srgb(0.8, 0.8, 0.8));
+            let iso = collider.position();
+            let to_points = |p: &Point<Real>| self.from_physics((iso * p).coords);
+            match collider.shape().as_typed_shape() {
+                TypedShape::Ball(ball) => {
+                    let outline = circle_points(ball.radius);
+                    let points = outline.iter().map(|p| to_points(p));
+                    draw.polyline()
+                        .weight(weight)
+                        .points_closed(points)
+                        .color(color);
+                    // Show the rotation of the ball.
+                    draw.line()
+                        .weight(weight)
+                        .points(to_points(&Point::origin()), to_points(&outline[0]))
+                        .color(color);
+                }
+                TypedShape::Cuboid(cuboid) => {
+                    let h = cuboid.half_extents;
+                    let corners = [
+                        point![-h.x, -h.y],
+                        point![h.x, -h.y],
+                        point![h.x, h.y],
+                        point![-h.x, h.y],
+                    ];
+                    draw.polyline()
+                        .weight(weight)
+                        .points_closed(corners.iter().map(to_points))
+                        .color(color);
+                }
+                TypedShape::Capsule(capsule) => {
+                    let (a, b, r) = (capsule.segment.a, capsule.segment.b, capsule.radius);
+                    let dir = (b - a).normalize();
+                    let angle = dir.y.atan2(dir.x);
+                    let half = DEBUG_CIRCLE_SEGMENTS / 2;
+                    let arc = |center: Point<Real>, start: f32| {
+                        (0..=half).map(move |i| {
+                            let t = start + std::f32::consts::PI * i as f32 / half as f32;
+                            center + vector![t.cos(), t.sin()] * r
+                        })
+                    };
+                    let half_pi = std::f32::consts::FRAC_PI_2;
+                    let points: Vec<_> = arc(b, angle - half_pi)
+                        .chain(arc(a, angle + half_pi))
+                        .collect();
+                    draw.polyline()
+                        .weight(weight)
+                        .points_closed(points.iter().map(to_points))
+                        .color(color);
+                }
+                TypedShape::Segment(segment) => {
+                    draw.line()
+                        .weight(weight)
+                        .points(to_points(&segment.a), to_points(&segment.b))
+                        .color(color);
+                }
+                TypedShape::Triangle(tri) => {
+                    draw.polyline()
+                        .weight(weight)
+                        .points_closed([tri.a, tri.b, tri.c].iter().map(to_points))
+                        .color(color);
+                }
+                TypedShape::ConvexPolygon(poly) => {
+                    draw.polyline()
+                        .weight(weight)
+                        .points_closed(poly.points().iter().map(to_points))
+                        .color(color);
+                }
+                TypedShape::Polyline(polyline) => {
+                    for segment in polyline.segments() {
+                        draw.line()
+                            .weight(weight)
+                            .points(to_points(&segment.a), to_points(&segment.b))
+                            .color(color);
+                    }
+                }
+                TypedShape::TriMesh(mesh) => {
+                    for tri in mesh.triangles() {
+                        draw.polyline()
+                            .weight(weight)
+                            .points_closed([tri.a, tri.b, tri.c].iter().map(to_points))
+                            .color(color);
+                    }
+                }
+                // Other shapes are not yet supported.
+                _ => (),
+            }
+        }
+
+        let joint_color = debug_color_srgb(0.9, 0.2, 0.2);
+        for (_, joint) in self.impulse_joints.iter() {
+            let (body1, body2) = match (self.bodies.get(joint.body1), self.bodies.get(joint.body2))
+            {
+                (Some(b1), Some(b2)) => (b1, b2),
+                _ => continue,
+            };
+            let anchor1 = self.from_physics((body1.position() * joint.data.local_anchor1()).coords);
+            let anchor2 = self.from_physics((body2.position() * joint.data.local_anchor2()).coords);
+            let origin1 = self.from_physics(*body1.translation());
+            let origin2 = self.from_physics(*body2.translation());
+            draw.line()
+                .weight(weight)
+                .points(origin1, anchor1)
+                .color(joint_color);
+            draw.line()
+                .weight(weight)
+                .points(origin2, anchor2)
+                .color(joint_color);
+            draw.ellipse()
+                .xy(anchor1)
+                .radius(weight * 2.0)
+                .color(joint_color);
+        }
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for World {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("World")
+            .field("gravity", &self.gravity)
+            .field("steps_per_second", &self.steps_per_second)
+            .field("max_steps", &self.max_steps)
+            .field("pixels_per_meter", &self.pixels_per_meter)
+            .field("bodies", &self.bodies.len())
+            .field("colliders", &self.colliders.len())
+            .field("impulse_joints", &self.impulse_joints.len())
+            .finish()
+    }
+}
+
+// The color with which the colliders of the given body are drawn.
+fn debug_color(body: &RigidBody) -> LinSrgba {
+    if body.is_fixed() {
+        debug_color_srgb(0.3, 0.8, 0.4)
+    } else if body.is_kinematic() {
+        debug_color_srgb(0.3, 0.6, 0.9)
+    } else if body.is_sleeping() {
+        debug_color_srgb(0.5, 0.5, 0.5)
+    } else {
+        debug_color_srgb(1.0, 0.6, 0.2)
+    }
+}
+
+fn debug_color_srgb(r: f32, g: f32, b: f32) -> LinSrgba {
+    color::srgba(r, g, b, 1.0).into_linear()
+}
+
+// Points around a circle of the given radius, beginning on the positive x axis.
+fn circle_points(radius: Real) -> Vec<Point<Real>> {
+    (0..DEBUG_CIRCLE_SEGMENTS)
+        .map(|i| {
+            let t = std::f32::consts::TAU * i as f32 / DEBUG_CIRCLE_SEGMENTS as f32;
+            point![t.cos() * radius, t.sin() * radius]
+        })
+        .collect()
+}
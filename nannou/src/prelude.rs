@@ -4,10 +4,10 @@ pub use crate::app::{self, App, LoopMode};
 pub use crate::draw::Draw;
 pub use crate::event::WindowEvent::*;
 pub use crate::event::{
-    AxisMotion, Event, Key, MouseButton, MouseScrollDelta, TouchEvent, TouchPhase,
+    AxisMotion, Event, Key, MouseButton, MouseScrollDelta, PenEvent, TouchEvent, TouchPhase,
     TouchpadPressure, Update, WindowEvent,
 };
-pub use crate::frame::{Frame, RawFrame};
+pub use crate::frame::{Frame, FrameTextures, RawFrame};
 pub use crate::io::{load_from_json, load_from_toml, safe_file_save, save_to_json, save_to_toml};
 pub use crate::text::{self, text};
 pub use crate::time::DurationF64;
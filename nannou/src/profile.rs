@@ -0,0 +1,88 @@
+//! Items related to profiling sketches via the `tracing` and `puffin` crates.
+//!
+//! When the `profile-tracing` feature is enabled, nannou emits `tracing` spans for each frame's
+//! `update` and `view`, along with the tessellation and encoding of each named scope of a
+//! **Draw** (see `Draw::scope`). Any `tracing` subscriber may be used to collect them, e.g.
+//! `tracing-flame` or `tracing-chrome` for producing flamegraphs.
+//!
+//! When the `profile-puffin` feature is enabled, the same spans are reported to the global
+//! `puffin` profiler and a new `puffin` frame begins with each iteration of the event loop.
+//! Profiling must first be enabled via `puffin::set_scopes_on(true)`, after which the profile
+//! may be viewed by serving it via the `puffin_http` crate to `puffin_viewer`.
+//!
+//! When neither feature is enabled, spans cost nothing.
+
+/// A profiling span that ends when dropped.
+#[must_use = "the span ends as soon as it is dropped"]
+pub struct Span {
+    #[cfg(feature = "profile-tracing")]
+    _tracing: tracing::span::EnteredSpan,
+    #[cfg(feature = "profile-puffin")]
+    _puffin: Option<puffin::ProfilerScope>,
+}
+
+/// The stage of rendering during which a **Draw** scope's span is entered.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum DrawStage {
+    Tessellate,
+    Encode,
+}
+
+/// Whether or not any profiling feature is enabled.
+pub(crate) const ENABLED: bool = cfg!(any(feature = "profile-tracing", feature = "profile-puffin"));
+
+/// Enter a span with the given name, e.g. to attribute the cost of part of a sketch's `update`.
+pub fn scope(name: &str) -> Span {
+    #[cfg(not(any(feature = "profile-tracing", feature = "profile-puffin")))]
+    let _ = name;
+    Span {
+        #[cfg(feature = "profile-tracing")]
+        _tracing: tracing::info_span!("scope", name).entered(),
+        #[cfg(feature = "profile-puffin")]
+        _puffin: puffin::profile_scope_custom!("scope", name),
+    }
+}
+
+// Enter the span for the user's `update` function.
+pub(crate) fn update() -> Span {
+    Span {
+        #[cfg(feature = "profile-tracing")]
+        _tracing: tracing::info_span!("update").entered(),
+        #[cfg(feature = "profile-puffin")]
+        _puffin: puffin::profile_scope_custom!("update"),
+    }
+}
+
+// Enter the span for the user's `view` function.
+pub(crate) fn view() -> Span {
+    Span {
+        #[cfg(feature = "profile-tracing")]
+        _tracing: tracing::info_span!("view").entered(),
+        #[cfg(feature = "profile-puffin")]
+        _puffin: puffin::profile_scope_custom!("view"),
+    }
+}
+
+// Enter the span for the given stage of rendering the **Draw** scope with the given name.
+pub(crate) fn draw_scope(stage: DrawStage, name: &str) -> Span {
+    #[cfg(not(any(feature = "profile-tracing", feature = "profile-puffin")))]
+    let _ = (stage, name);
+    Span {
+        #[cfg(feature = "profile-tracing")]
+        _tracing: match stage {
+            DrawStage::Tessellate => tracing::info_span!("draw_tessellate", name).entered(),
+            DrawStage::Encode => tracing::info_span!("draw_encode", name).entered(),
+        },
+        #[cfg(feature = "profile-puffin")]
+        _puffin: match stage {
+            DrawStage::Tessellate => puffin::profile_scope_custom!("draw_tessellate", name),
+            DrawStage::Encode => puffin::profile_scope_custom!("draw_encode", name),
+        },
+    }
+}
+
+// Mark the beginning of a new iteration of the event loop.
+pub(crate) fn new_frame() {
+    #[cfg(feature = "profile-puffin")]
+    puffin::GlobalProfiler::lock().new_frame();
+}
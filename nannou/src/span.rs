@@ -0,0 +1,211 @@
+//! Items related to spanning a single logical canvas across all connected monitors.
+//!
+//! This is useful for multi-projector walls or LED installations driven by a single machine.
+//! Each monitor is covered by a borderless window (or a single window spans all of them) and each
+//! window acts as a viewport into a shared logical canvas.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::span::Span;
+//!
+//! struct Model {
+//!     span: Span,
+//! }
+//!
+//! fn model(app: &App) -> Model {
+//!     let span = app
+//!         .span_all_monitors()
+//!         .window(|window| window.view(view))
+//!         .build()
+//!         .unwrap();
+//!     Model { span }
+//! }
+//!
+//! fn view(app: &App, model: &Model, frame: Frame) {
+//!     // Draw in canvas coordinates, where the origin is the centre of the whole wall.
+//!     let draw = model.span.draw(app, frame.window_id());
+//!     draw.background().color(BLACK);
+//!     let canvas = model.span.canvas();
+//!     draw.ellipse().x(canvas.left() + (app.time * 200.0) % canvas.w());
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! # fn main() {}
+//! ```
+
+use crate::draw::Draw;
+use crate::geom::Rect;
+use crate::window::{self, Fullscreen};
+use crate::App;
+use std::collections::HashMap;
+
+/// Begin building a set of windows spanning all connected monitors.
+///
+/// Created via `App::span_all_monitors`.
+pub struct Builder<'app> {
+    app: &'app App,
+    single_window: bool,
+    window: Option<Box<dyn Fn(window::Builder<'app>) -> window::Builder<'app> + 'app>>,
+}
+
+/// A logical canvas spanning all connected monitors, along with the windows that display it.
+#[derive(Clone, Debug)]
+pub struct Span {
+    canvas: Rect,
+    viewports: HashMap<window::Id, Rect>,
+    windows: Vec<window::Id>,
+}
+
+// The area covered by a monitor in desktop coordinates, where y points downwards.
+struct MonitorArea {
+    monitor: winit::monitor::MonitorHandle,
+    // The area in points.
+    rect: Rect,
+}
+
+impl<'app> Builder<'app> {
+    /// Begin building a span across all of the app's available monitors.
+    pub fn new(app: &'app App) -> Self {
+        Builder {
+            app,
+            single_window: false,
+            window: None,
+        }
+    }
+
+    /// Create a single borderless window covering all monitors, rather than one fullscreen
+    /// window per monitor.
+    ///
+    /// This can reduce overhead when driving many outputs, however not all platforms support
+    /// windows that extend beyond a single monitor. By default, this is `false`.
+    pub fn single_window(mut self, single_window: bool) -> Self {
+        self.single_window = single_window;
+        self
+    }
+
+    /// Configure each window before it is built, e.g. to specify its `view` function.
+    ///
+    /// The given function is called once for each window.
+    pub fn window<F>(mut self, f: F) -> Self
+    where
+        F: 'app + Fn(window::Builder<'app>) -> window::Builder<'app>,
+    {
+        self.window = Some(Box::new(f));
+        self
+    }
+
+    /// Build the windows.
+    ///
+    /// If no monitors can be detected, a single window is created and the canvas matches its
+    /// size.
+    pub fn build(self) -> Result<Span, window::BuildError> {
+        let Builder {
+            app,
+            single_window,
+            window,
+        } = self;
+        let configure = |builder: window::Builder<'app>| match window {
+            Some(ref f) => f(builder),
+            None => builder,
+        };
+
+        let areas: Vec<_> = app
+            .available_monitors()
+            .into_iter()
+            .map(|monitor| {
+                let sf = monitor.scale_factor();
+                let rect = desktop_rect(
+                    monitor.position().to_logical::<f32>(sf).into(),
+                    monitor.size().to_logical::<f32>(sf).into(),
+                );
+                MonitorArea { monitor, rect }
+            })
+            .collect();
+
+        // Fall back to a single regular window.
+        if areas.is_empty() {
+            let id = configure(app.new_window()).build()?;
+            let canvas = app.window(id).expect("no window for id").rect();
+            let viewports = Some((id, canvas)).into_iter().collect();
+            let windows = vec![id];
+            return Ok(Span {
+                canvas,
+                viewports,
+                windows,
+            });
+        }
+
+        // The bounding box of all monitors in desktop coordinates.
+        let bounds = areas
+            .iter()
+            .skip(1)
+            .fold(areas[0].rect, |bounds, area| bounds.max(area.rect));
+        let canvas = Rect::from_wh(bounds.wh());
+        let to_canvas = |rect: Rect| {
+            let x = rect.x() - bounds.x();
+            let y = bounds.y() - rect.y();
+            Rect::from_x_y_w_h(x, y, rect.w(), rect.h())
+        };
+
+        let mut viewports = HashMap::new();
+        let mut windows = vec![];
+        if single_window {
+            let builder = app.new_window().span_all_displays();
+            let id = configure(builder).build()?;
+            viewports.insert(id, canvas);
+            windows.push(id);
+        } else {
+            for area in areas {
+                let fullscreen = Fullscreen::Borderless(Some(area.monitor));
+                let builder = app
+                    .new_window()
+                    .decorations(false)
+                    .fullscreen_with(Some(fullscreen));
+                let id = configure(builder).build()?;
+                viewports.insert(id, to_canvas(area.rect));
+                windows.push(id);
+            }
+        }
+
+        Ok(Span {
+            canvas,
+            viewports,
+            windows,
+        })
+    }
+}
+
+impl Span {
+    /// The logical canvas covering all monitors, centred on the origin.
+    ///
+    /// The canvas is described in points, using the same orientation as the **Draw** API.
+    pub fn canvas(&self) -> Rect {
+        self.canvas
+    }
+
+    /// The windows displaying the canvas, in the order in which they were created.
+    pub fn windows(&self) -> &[window::Id] {
+        &self.windows
+    }
+
+    /// The region of the canvas displayed by the window with the given ID.
+    pub fn viewport(&self, window: window::Id) -> Option<Rect> {
+        self.viewports.get(&window).cloned()
+    }
+
+    /// Produce the **App**'s **Draw** instance, transformed so that canvas coordinates map to the
+    /// region of the canvas displayed by the given window.
+    ///
+    /// If the window is not a part of the span, the **Draw** is left untransformed.
+    pub fn draw(&self, app: &App, window: window::Id) -> Draw {
+        let draw = app.draw();
+        match self.viewport(window) {
+            Some(viewport) => draw.xy(-viewport.xy()),
+            None => draw,
+        }
+    }
+}
+
+// A rect in desktop coordinates from a top-left position and size.
+fn desktop_rect([x, y]: [f32; 2], [w, h]: [f32; 2]) -> Rect {
+    Rect::from_x_y_w_h(x + w * 0.5, y + h * 0.5, w, h)
+}
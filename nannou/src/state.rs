@@ -1,9 +1,11 @@
-//! Small tracked parts of the application state. Includes **window**, **keys**, **mouse**, and
-//! **time** - each of which are stored in the **App**.
+//! Small tracked parts of the application state. Includes **window**, **keys**, **mouse**,
+//! **pen**, **touch** and **time** - each of which are stored in the **App**.
 
 pub use self::keys::Keys;
 pub use self::mouse::Mouse;
+pub use self::pen::Pen;
 pub use self::time::Time;
+pub use self::touch::Touches;
 pub use self::window::Window;
 
 /// Tracked state related to the focused window.
@@ -223,6 +225,236 @@ pub mod mouse {
     }
 }
 
+/// Tracked state related to pen and tablet input.
+pub mod pen {
+    use crate::event::{PenEvent, TouchPhase};
+    use crate::geom::Point2;
+    use crate::window;
+
+    /// The state of a pressure-sensitive stylus, e.g. a pen tablet or Apple Pencil.
+    ///
+    /// See `PenEvent` for the platforms on which pen input is supported.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Pen {
+        /// The ID of the window with which the pen was last associated.
+        pub window: Option<window::Id>,
+        /// The last position of the pen relative to the middle of `window`.
+        pub position: Point2,
+        /// The normalized pressure of the pen in the range `0.0..=1.0`.
+        ///
+        /// This is `0.0` while the pen is not in contact.
+        pub pressure: f32,
+        /// The last altitude angle of the pen in radians, if reported by the platform.
+        pub tilt: Option<f32>,
+        /// Whether the pen is currently in contact.
+        pub is_down: bool,
+        /// Whether the barrel button of the pen is held.
+        pub barrel_button: bool,
+        // The ID and phase of the last contact.
+        last_contact: Option<(u64, TouchPhase)>,
+    }
+
+    impl Pen {
+        /// The event describing the last change to the pen's state, if any.
+        pub fn event(&self) -> Option<PenEvent> {
+            let (id, phase) = self.last_contact?;
+            Some(PenEvent {
+                id,
+                phase,
+                position: self.position,
+                pressure: self.pressure,
+                tilt: self.tilt,
+                barrel_button: self.barrel_button,
+            })
+        }
+
+        // Update the state of the pen with the given contact.
+        pub(crate) fn update(
+            &mut self,
+            window: window::Id,
+            id: u64,
+            phase: TouchPhase,
+            position: Point2,
+            pressure: f32,
+            tilt: Option<f32>,
+        ) {
+            self.window = Some(window);
+            self.position = position;
+            self.tilt = tilt;
+            self.last_contact = Some((id, phase));
+            match phase {
+                TouchPhase::Started | TouchPhase::Moved => {
+                    self.pressure = pressure;
+                    self.is_down = true;
+                }
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    self.pressure = 0.0;
+                    self.is_down = false;
+                    self.barrel_button = false;
+                }
+            }
+        }
+    }
+}
+
+/// Tracked state related to touch input.
+pub mod touch {
+    use crate::event::{TouchEvent, TouchPhase};
+    use crate::geom::{Point2, Vec2};
+    use crate::window;
+    use std::collections::BTreeMap;
+
+    /// The state of all touches currently in contact with a touch screen or tablet.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Touches {
+        touches: BTreeMap<u64, Touch>,
+        // The ID of the first touch to make contact since all touches were lifted.
+        primary: Option<u64>,
+        // The positions of the two touches at the beginning of the current two-finger gesture.
+        gesture_start: Option<[(u64, Point2); 2]>,
+    }
+
+    /// A single touch that is currently in contact.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Touch {
+        /// The unique ID associated with this touch, e.g. useful for distinguishing between
+        /// fingers.
+        pub id: u64,
+        /// The window with which the touch is associated.
+        pub window: window::Id,
+        /// The phase of the last event for this touch, either `Started` or `Moved`.
+        pub phase: TouchPhase,
+        /// The position of the touch relative to the middle of `window`.
+        pub position: Point2,
+        /// The position at which the touch started.
+        pub start_position: Point2,
+        /// The normalized pressure of the touch in the range `0.0..=1.0`, if supported by the
+        /// device.
+        pub pressure: Option<f32>,
+    }
+
+    /// A gesture recognised from exactly two touches.
+    ///
+    /// All values are described relative to the positions of the touches at the moment the second
+    /// touch began.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Gesture {
+        /// The ratio between the current and initial distance between the touches.
+        ///
+        /// Values greater than `1.0` indicate the touches have been spread apart, values less than
+        /// `1.0` indicate a pinch.
+        pub scale: f32,
+        /// The angle in radians by which the touches have rotated counter-clockwise about their
+        /// centre, in the range `-PI..=PI`.
+        pub rotation: f32,
+        /// The distance by which the centre of the touches has moved, i.e. a two-finger pan.
+        pub translation: Vec2,
+        /// The current centre point between the two touches.
+        pub centre: Point2,
+    }
+
+    impl Touches {
+        /// The number of touches currently in contact.
+        pub fn len(&self) -> usize {
+            self.touches.len()
+        }
+
+        /// Whether there are no touches currently in contact.
+        pub fn is_empty(&self) -> bool {
+            self.touches.is_empty()
+        }
+
+        /// The touch with the given ID, if it is currently in contact.
+        pub fn get(&self, id: u64) -> Option<&Touch> {
+            self.touches.get(&id)
+        }
+
+        /// The first touch to make contact since all touches were lifted, if it remains in
+        /// contact.
+        pub fn primary(&self) -> Option<&Touch> {
+            self.touches.get(&self.primary?)
+        }
+
+        /// An iterator yielding all touches currently in contact, ordered by ID.
+        pub fn iter(&self) -> impl Iterator<Item = &Touch> {
+            self.touches.values()
+        }
+
+        /// The centre point of all touches currently in contact, if any.
+        pub fn centroid(&self) -> Option<Point2> {
+            if self.touches.is_empty() {
+                return None;
+            }
+            let sum: Vec2 = self.touches.values().map(|t| &t.position).sum();
+            Some(sum / self.touches.len() as f32)
+        }
+
+        /// The pinch, rotate and pan gesture described by the two touches currently in contact.
+        ///
+        /// Returns `None` unless exactly two touches are in contact.
+        pub fn gesture(&self) -> Option<Gesture> {
+            let [(a_id, a_start), (b_id, b_start)] = self.gesture_start?;
+            let a = self.touches.get(&a_id)?.position;
+            let b = self.touches.get(&b_id)?.position;
+            let (start, current) = (b_start - a_start, b - a);
+            let scale = match start.length() {
+                len if len > 0.0 => current.length() / len,
+                _ => 1.0,
+            };
+            let rotation = start.angle_between(current);
+            let rotation = if rotation.is_nan() { 0.0 } else { rotation };
+            let centre = (a + b) * 0.5;
+            let translation = centre - (a_start + b_start) * 0.5;
+            Some(Gesture {
+                scale,
+                rotation,
+                translation,
+                centre,
+            })
+        }
+
+        // Update the state of the touches with the given event.
+        pub(crate) fn update(&mut self, window: window::Id, event: &TouchEvent) {
+            match event.phase {
+                TouchPhase::Started | TouchPhase::Moved => {
+                    if self.touches.is_empty() {
+                        self.primary = Some(event.id);
+                    }
+                    let touch = self.touches.entry(event.id).or_insert(Touch {
+                        id: event.id,
+                        window,
+                        phase: event.phase,
+                        position: event.position,
+                        start_position: event.position,
+                        pressure: event.pressure,
+                    });
+                    touch.phase = event.phase;
+                    touch.position = event.position;
+                    touch.pressure = event.pressure;
+                }
+                TouchPhase::Ended | TouchPhase::Cancelled => {
+                    self.touches.remove(&event.id);
+                    if self.primary == Some(event.id) {
+                        self.primary = None;
+                    }
+                }
+            }
+
+            // Begin a new gesture whenever the pair of touches changes.
+            let mut touches = self.touches.values();
+            self.gesture_start = match (touches.next(), touches.next(), touches.next()) {
+                (Some(a), Some(b), None) => match self.gesture_start {
+                    Some([(a_id, _), (b_id, _)]) if a_id == a.id && b_id == b.id => {
+                        self.gesture_start
+                    }
+                    _ => Some([(a.id, a.position), (b.id, b.position)]),
+                },
+                _ => None,
+            };
+        }
+    }
+}
+
 /// Tracked durations related to the App.
 pub mod time {
     /// The state of time tracked by the App.
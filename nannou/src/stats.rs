@@ -0,0 +1,87 @@
+//! Items related to the built-in statistics overlay, useful for optimizing heavy sketches.
+//!
+//! When shown, the overlay is drawn over the top left corner of each window by `Draw::to_frame`
+//! and describes:
+//!
+//! - The frame rate and frame duration.
+//! - The CPU time spent within the user's `update` and `view` functions.
+//! - The CPU time spent tessellating the **Draw**ing and encoding its render passes.
+//! - The GPU time spent executing the render passes, if the window's device has
+//!   `Features::TIMESTAMP_QUERY` enabled (see `window::Builder::device_descriptor`).
+//! - The number of vertices, indices, draw calls, pipeline switches and texture binds.
+//!
+//! Show the overlay via `App::show_stats`, or assign a key via `app::Builder::stats_key` to
+//! toggle it with a single keystroke.
+
+use crate::color;
+use crate::draw::{self, renderer};
+use crate::geom;
+use std::time::Duration;
+
+/// The font size of the overlay text.
+pub const FONT_SIZE: u32 = 12;
+
+/// The padding in points between the edge of the window, the overlay and its text.
+pub const PADDING: f32 = 8.0;
+
+/// The width of the overlay in points.
+pub const WIDTH: f32 = 240.0;
+
+/// The CPU time spent within the user's functions, measured by the **App**.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CpuTimings {
+    /// The time spent within the most recent `update` function and `Update` event.
+    pub update: Duration,
+    /// The time spent within the most recent `view` function.
+    pub view: Duration,
+}
+
+/// The lines of text describing the given statistics, as shown by the overlay.
+pub fn lines(fps: f32, cpu: &CpuTimings, render: &renderer::Stats) -> Vec<String> {
+    let frame = if fps > 0.0 {
+        ms(Duration::from_secs_f32(1.0 / fps))
+    } else {
+        "-".to_string()
+    };
+    let gpu = render.gpu.map(ms).unwrap_or_else(|| "n/a".to_string());
+    vec![
+        format!("{:.1} fps ({})", fps, frame),
+        format!("update {}  view {}", ms(cpu.update), ms(cpu.view)),
+        format!(
+            "tessellate {}  encode {}",
+            ms(render.tessellation),
+            ms(render.encoding)
+        ),
+        format!("gpu {}", gpu),
+        format!("{} vertices  {} indices", render.vertices, render.indices),
+        format!(
+            "{} draws  {} pipelines  {} textures",
+            render.draw_calls, render.pipeline_switches, render.texture_binds
+        ),
+    ]
+}
+
+/// Draw the overlay with the given lines of text to the top left corner of the given rect.
+pub fn draw_overlay(draw: &draw::Draw, rect: geom::Rect, lines: &[String]) {
+    let line_h = FONT_SIZE as f32 * 1.5;
+    let h = lines.len() as f32 * line_h + PADDING * 2.0;
+    let overlay = geom::Rect::from_w_h(WIDTH, h).top_left_of(rect.pad(PADDING));
+    let draw = draw.no_depth_test();
+    draw.rect()
+        .xy(overlay.xy())
+        .wh(overlay.wh())
+        .color(color::rgba(0.0, 0.0, 0.0, 0.7));
+    draw.text(&lines.join("\n"))
+        .xy(overlay.xy())
+        .wh(overlay.pad(PADDING).wh())
+        .font_size(FONT_SIZE)
+        .line_spacing(line_h - FONT_SIZE as f32)
+        .left_justify()
+        .align_text_top()
+        .color(color::WHITE);
+}
+
+// Format the given duration in milliseconds.
+fn ms(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1_000.0)
+}
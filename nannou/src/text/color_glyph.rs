@@ -0,0 +1,767 @@
+//! Rasterizing color glyphs such as emoji, along with the atlas in which they are cached.
+//!
+//! Color glyphs are described either by layers of outlines filled with solid colors or gradients
+//! (the `COLR` and `CPAL` tables) or by embedded bitmaps (the `sbix` and `CBDT` tables). Neither
+//! is supported by rusttype, so these are read from the font data retained by the `font` module.
+//! Only fonts loaded via the `font` module (e.g. `font::from_file` or `font::from_system`) may
+//! provide color glyphs.
+//!
+//! Of the `COLR` compositing modes, the Porter-Duff operators are supported. Blend modes such as
+//! `Multiply` are composited as `SourceOver`. Only PNG and premultiplied BGRA bitmaps are
+//! supported.
+
+use crate::text::{font, rt, Font, GlyphId, PositionedGlyph, Scale};
+use ab_glyph_rasterizer::{point, Point, Rasterizer};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use ttf_parser::colr::{ClipBox, ColorStop, CompositeMode, GradientExtend, Paint, Painter};
+use ttf_parser::{OutlineBuilder, RasterImageFormat, RgbaColor, Transform};
+
+/// A rasterized color glyph.
+#[derive(Clone, Debug)]
+pub struct Image {
+    /// The non-premultiplied sRGB pixels of the glyph.
+    pub rgba: image::RgbaImage,
+    /// The offset of the left edge of the image from the glyph's origin in pixels.
+    pub left: i32,
+    /// The offset of the top edge of the image from the glyph's origin in pixels, where positive
+    /// values are below the baseline.
+    pub top: i32,
+}
+
+/// An RGBA texture atlas caching the color glyphs drawn by the text primitive.
+///
+/// Glyphs are packed into rows. When the atlas is full, it is cleared before caching the next
+/// glyph, so glyphs cached earlier within the same frame may be replaced.
+pub struct Atlas {
+    size: [u32; 2],
+    pixels: Vec<u8>,
+    // Whether or not each glyph of each font is a color glyph.
+    is_color: HashMap<(font::Id, GlyphId), bool>,
+    entries: HashMap<Key, Option<Entry>>,
+    // The position of the next glyph within the current row and the height of the row.
+    cursor: [u32; 2],
+    row_height: u32,
+    /// Set to `true` when glyphs have been cached since the atlas was last uploaded.
+    pub requires_upload: bool,
+}
+
+// Identifies a glyph rasterized at a particular size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Key {
+    font: font::Id,
+    glyph: GlyphId,
+    // The scale in quarter pixels.
+    scale: u32,
+}
+
+// The location of a cached glyph within the atlas.
+#[derive(Copy, Clone, Debug)]
+struct Entry {
+    min: [u32; 2],
+    size: [u32; 2],
+    left: i32,
+    top: i32,
+}
+
+// The gap left between glyphs to avoid bleeding when sampling.
+const PADDING: u32 = 1;
+
+// Paints the layers of a `COLR` glyph onto a canvas of premultiplied sRGB pixels.
+struct Canvas<'f, 'a> {
+    face: &'f ttf_parser::Face<'a>,
+    // The number of pixels per font unit.
+    px_per_unit: f32,
+    // The position of the canvas' top left corner in pixels, with the y axis pointing up.
+    left: f32,
+    top: f32,
+    width: usize,
+    height: usize,
+    transforms: Vec<Transform>,
+    // The coverage of the most recently outlined glyph.
+    outline: Vec<f32>,
+    clips: Vec<Vec<f32>>,
+    layers: Vec<(Vec<[f32; 4]>, CompositeMode)>,
+}
+
+// Collects the bounds of the outlines painted by a `COLR` glyph in font units.
+struct Bounds<'f, 'a> {
+    face: &'f ttf_parser::Face<'a>,
+    transforms: Vec<Transform>,
+    outline: Option<ttf_parser::Rect>,
+    bounds: Option<[f32; 4]>,
+}
+
+// Feeds the outline of a glyph to the rasterizer.
+struct Outline<'r> {
+    rasterizer: &'r mut Rasterizer,
+    transform: Transform,
+    px_per_unit: f32,
+    left: f32,
+    top: f32,
+    start: Point,
+    last: Point,
+}
+
+impl Atlas {
+    /// Create an empty atlas with the given size in pixels.
+    pub fn new(size: [u32; 2]) -> Self {
+        let [w, h] = size;
+        Atlas {
+            size,
+            pixels: vec![0; w as usize * h as usize * 4],
+            is_color: Default::default(),
+            entries: Default::default(),
+            cursor: [0; 2],
+            row_height: 0,
+            requires_upload: false,
+        }
+    }
+
+    /// The size of the atlas in pixels.
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.size
+    }
+
+    /// The RGBA pixels of the atlas.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Whether or not the given glyph of the given font is a color glyph.
+    pub fn is_color(&mut self, font: &Font, glyph: GlyphId) -> bool {
+        *self
+            .is_color
+            .entry((font::id(font), glyph))
+            .or_insert_with(|| is_color(font, glyph))
+    }
+
+    /// Cache the given color glyph if necessary and return its texture coordinates within the
+    /// atlas alongside its rect in pixels.
+    ///
+    /// Returns `None` if the glyph is not a color glyph or is too large to fit within the atlas.
+    pub fn rect_for(
+        &mut self,
+        font: &Font,
+        glyph: &PositionedGlyph,
+    ) -> Option<(rt::Rect<f32>, rt::Rect<i32>)> {
+        if !self.is_color(font, glyph.id()) {
+            return None;
+        }
+        let scale = glyph.scale();
+        let key = Key {
+            font: font::id(font),
+            glyph: glyph.id(),
+            scale: (scale.y * 4.0).round() as u32,
+        };
+        let entry = match self.entries.get(&key) {
+            Some(&entry) => entry,
+            None => {
+                let scale = Scale::uniform(key.scale as f32 / 4.0);
+                let entry = rasterize(font, glyph.id(), scale).and_then(|image| self.insert(image));
+                self.entries.insert(key, entry);
+                entry
+            }
+        }?;
+        let [w, h] = self.size;
+        let uv = rt::Rect {
+            min: rt::point(
+                entry.min[0] as f32 / w as f32,
+                entry.min[1] as f32 / h as f32,
+            ),
+            max: rt::point(
+                (entry.min[0] + entry.size[0]) as f32 / w as f32,
+                (entry.min[1] + entry.size[1]) as f32 / h as f32,
+            ),
+        };
+        let position = glyph.position();
+        let min = rt::point(
+            position.x.round() as i32 + entry.left,
+            position.y.round() as i32 + entry.top,
+        );
+        let max = rt::point(min.x + entry.size[0] as i32, min.y + entry.size[1] as i32);
+        Some((uv, rt::Rect { min, max }))
+    }
+
+    // Pack the image into the atlas, clearing the atlas first if it is full.
+    fn insert(&mut self, image: Image) -> Option<Entry> {
+        let (w, h) = image.rgba.dimensions();
+        let [atlas_w, atlas_h] = self.size;
+        if w + PADDING > atlas_w || h + PADDING > atlas_h {
+            return None;
+        }
+        if self.cursor[0] + w + PADDING > atlas_w {
+            self.cursor = [0, self.cursor[1] + self.row_height];
+            self.row_height = 0;
+        }
+        if self.cursor[1] + h + PADDING > atlas_h {
+            self.clear();
+        }
+        let min = self.cursor;
+        for (y, row) in image.rgba.rows().enumerate() {
+            let start = ((min[1] as usize + y) * atlas_w as usize + min[0] as usize) * 4;
+            let dst = &mut self.pixels[start..start + w as usize * 4];
+            for (dst, px) in dst.chunks_exact_mut(4).zip(row) {
+                dst.copy_from_slice(&px.0);
+            }
+        }
+        self.cursor[0] += w + PADDING;
+        self.row_height = self.row_height.max(h + PADDING);
+        self.requires_upload = true;
+        Some(Entry {
+            min,
+            size: [w, h],
+            left: image.left,
+            top: image.top,
+        })
+    }
+
+    // Remove all cached glyphs.
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.pixels.iter_mut().for_each(|b| *b = 0);
+        self.cursor = [0; 2];
+        self.row_height = 0;
+    }
+}
+
+/// Whether or not the given glyph of the given font is described by `COLR` layers or an embedded
+/// bitmap.
+pub fn is_color(font: &Font, glyph: GlyphId) -> bool {
+    let (data, index) = match font::data(font) {
+        Some(data) => data,
+        None => return false,
+    };
+    let face = match ttf_parser::Face::parse(&data, index) {
+        Ok(face) => face,
+        Err(_) => return false,
+    };
+    let id = match u16::try_from(glyph.0) {
+        Ok(id) => ttf_parser::GlyphId(id),
+        Err(_) => return false,
+    };
+    face.is_color_glyph(id) || face.glyph_raster_image(id, u16::MAX).is_some()
+}
+
+/// Rasterize the given color glyph at the given scale.
+///
+/// Returns `None` if the glyph is not a color glyph.
+pub fn rasterize(font: &Font, glyph: GlyphId, scale: Scale) -> Option<Image> {
+    let (data, index) = font::data(font)?;
+    let face = ttf_parser::Face::parse(&data, index).ok()?;
+    let id = ttf_parser::GlyphId(u16::try_from(glyph.0).ok()?);
+    // Rusttype scales glyphs such that the font's ascent to descent spans `scale.y` pixels.
+    let hhea = face.tables().hhea;
+    let height = f32::from(hhea.ascender) - f32::from(hhea.descender);
+    if height <= 0.0 {
+        return None;
+    }
+    let px_per_unit = scale.y / height;
+    if face.is_color_glyph(id) {
+        paint_layers(&face, id, px_per_unit)
+    } else {
+        decode_bitmap(&face, id, px_per_unit)
+    }
+}
+
+// Paint the `COLR` layers of the given glyph.
+fn paint_layers(
+    face: &ttf_parser::Face,
+    id: ttf_parser::GlyphId,
+    px_per_unit: f32,
+) -> Option<Image> {
+    let foreground = RgbaColor::new(0, 0, 0, 255);
+    let mut bounds = Bounds {
+        face,
+        transforms: vec![Transform::default()],
+        outline: None,
+        bounds: None,
+    };
+    face.paint_color_glyph(id, 0, foreground, &mut bounds)?;
+    let [x0, y0, x1, y1] = bounds.bounds?;
+    let left = (x0 * px_per_unit).floor();
+    let top = (y1 * px_per_unit).ceil();
+    let width = ((x1 * px_per_unit).ceil() - left).max(1.0) as usize;
+    let height = (top - (y0 * px_per_unit).floor()).max(1.0) as usize;
+    let len = width * height;
+    let mut canvas = Canvas {
+        face,
+        px_per_unit,
+        left,
+        top,
+        width,
+        height,
+        transforms: vec![Transform::default()],
+        outline: vec![0.0; len],
+        clips: vec![vec![1.0; len]],
+        layers: vec![(vec![[0.0; 4]; len], CompositeMode::SourceOver)],
+    };
+    face.paint_color_glyph(id, 0, foreground, &mut canvas)?;
+    let (pixels, _) = canvas.layers.swap_remove(0);
+    let mut rgba = image::RgbaImage::new(width as u32, height as u32);
+    for (px, &[r, g, b, a]) in rgba.pixels_mut().zip(&pixels) {
+        if a > 0.0 {
+            let c = |v: f32| ((v / a).clamp(0.0, 1.0) * 255.0).round() as u8;
+            *px = image::Rgba([c(r), c(g), c(b), (a.clamp(0.0, 1.0) * 255.0).round() as u8]);
+        }
+    }
+    Some(Image {
+        rgba,
+        left: left as i32,
+        top: -(top as i32),
+    })
+}
+
+// Decode and scale the embedded bitmap of the given glyph.
+fn decode_bitmap(
+    face: &ttf_parser::Face,
+    id: ttf_parser::GlyphId,
+    px_per_unit: f32,
+) -> Option<Image> {
+    let ppem = px_per_unit * f32::from(face.units_per_em());
+    let raster =
+        face.glyph_raster_image(id, ppem.round().clamp(1.0, f32::from(u16::MAX)) as u16)?;
+    let rgba = match raster.format {
+        RasterImageFormat::PNG => {
+            image::load_from_memory_with_format(raster.data, image::ImageFormat::Png)
+                .ok()?
+                .to_rgba8()
+        }
+        RasterImageFormat::BitmapPremulBgra32 => {
+            let (w, h) = (u32::from(raster.width), u32::from(raster.height));
+            let mut rgba = image::RgbaImage::new(w, h);
+            let len = w as usize * h as usize * 4;
+            for (px, bgra) in rgba
+                .pixels_mut()
+                .zip(raster.data.get(..len)?.chunks_exact(4))
+            {
+                let a = bgra[3];
+                let c = |v: u8| match a {
+                    0 => 0,
+                    _ => (u32::from(v) * 255 / u32::from(a)).min(255) as u8,
+                };
+                *px = image::Rgba([c(bgra[2]), c(bgra[1]), c(bgra[0]), a]);
+            }
+            rgba
+        }
+        _ => return None,
+    };
+    if raster.pixels_per_em == 0 {
+        return None;
+    }
+    let scale = ppem / f32::from(raster.pixels_per_em);
+    let (w, h) = rgba.dimensions();
+    let w = ((w as f32 * scale).round() as u32).max(1);
+    let h = ((h as f32 * scale).round() as u32).max(1);
+    let rgba = image::imageops::resize(&rgba, w, h, image::imageops::FilterType::Triangle);
+    // The offsets describe the bottom left corner of the bitmap relative to the glyph's origin.
+    let left = (f32::from(raster.x) * scale).round() as i32;
+    let bottom = (f32::from(raster.y) * scale).round() as i32;
+    Some(Image {
+        rgba,
+        left,
+        top: -(bottom + h as i32),
+    })
+}
+
+impl<'f, 'a> Canvas<'f, 'a> {
+    fn transform(&self) -> Transform {
+        *self.transforms.last().expect("no transform")
+    }
+
+    fn clip(&self) -> &[f32] {
+        self.clips.last().expect("no clip")
+    }
+
+    // Rasterize a path described by the given function into a coverage mask.
+    fn rasterize<F>(&self, f: F) -> Vec<f32>
+    where
+        F: FnOnce(&mut Outline),
+    {
+        let mut rasterizer = Rasterizer::new(self.width, self.height);
+        let mut outline = Outline {
+            rasterizer: &mut rasterizer,
+            transform: self.transform(),
+            px_per_unit: self.px_per_unit,
+            left: self.left,
+            top: self.top,
+            start: point(0.0, 0.0),
+            last: point(0.0, 0.0),
+        };
+        f(&mut outline);
+        let mut coverage = vec![0.0; self.width * self.height];
+        rasterizer.for_each_pixel(|i, c| coverage[i] = c.min(1.0));
+        coverage
+    }
+
+    // The position of the centre of the given pixel in font units, prior to the current
+    // transform.
+    fn paint_space(&self, i: usize) -> Option<[f32; 2]> {
+        let x = ((i % self.width) as f32 + 0.5 + self.left) / self.px_per_unit;
+        let y = (self.top - ((i / self.width) as f32 + 0.5)) / self.px_per_unit;
+        let t = self.transform();
+        let det = t.a * t.d - t.b * t.c;
+        if det == 0.0 {
+            return None;
+        }
+        let (x, y) = (x - t.e, y - t.f);
+        Some([(t.d * x - t.c * y) / det, (t.a * y - t.b * x) / det])
+    }
+}
+
+impl<'f, 'a> Painter<'a> for Canvas<'f, 'a> {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        let face = self.face;
+        self.outline = self.rasterize(|outline| {
+            face.outline_glyph(glyph_id, outline);
+            outline.close();
+        });
+    }
+
+    fn paint(&mut self, paint: Paint<'a>) {
+        let stops = match paint {
+            Paint::Solid(_) => vec![],
+            Paint::LinearGradient(ref g) => stops(g.stops(0, &[])),
+            Paint::RadialGradient(ref g) => stops(g.stops(0, &[])),
+            Paint::SweepGradient(ref g) => stops(g.stops(0, &[])),
+        };
+        let mut pixels = std::mem::take(&mut self.layers.last_mut().expect("no layer").0);
+        for (i, dst) in pixels.iter_mut().enumerate() {
+            let coverage = self.outline[i] * self.clip()[i];
+            if coverage <= 0.0 {
+                continue;
+            }
+            let color = match paint {
+                Paint::Solid(color) => Some(premultiply(color)),
+                Paint::LinearGradient(ref g) => self.paint_space(i).and_then(|[x, y]| {
+                    let t = linear_offset(g.x0, g.y0, g.x1, g.y1, g.x2, g.y2, x, y)?;
+                    Some(sample(&stops, g.extend, t))
+                }),
+                Paint::RadialGradient(ref g) => self.paint_space(i).and_then(|[x, y]| {
+                    let t = radial_offset(g.x0, g.y0, g.r0, g.x1, g.y1, g.r1, x, y)?;
+                    Some(sample(&stops, g.extend, t))
+                }),
+                Paint::SweepGradient(ref g) => self.paint_space(i).map(|[x, y]| {
+                    // Angles are stored in half turns, offset by a half turn.
+                    let start = (g.start_angle + 1.0) * 180.0;
+                    let end = (g.end_angle + 1.0) * 180.0;
+                    let angle = (y - g.center_y).atan2(x - g.center_x).to_degrees();
+                    let angle = (angle + 360.0) % 360.0;
+                    let span = end - start;
+                    let t = if span == 0.0 {
+                        0.0
+                    } else {
+                        (angle - start) / span
+                    };
+                    sample(&stops, g.extend, t)
+                }),
+            };
+            if let Some(color) = color {
+                let src = color.map(|c| c * coverage);
+                *dst = composite(src, *dst, CompositeMode::SourceOver);
+            }
+        }
+        self.layers.last_mut().expect("no layer").0 = pixels;
+    }
+
+    fn push_clip(&mut self) {
+        let clip = self
+            .outline
+            .iter()
+            .zip(self.clip())
+            .map(|(a, b)| a * b)
+            .collect();
+        self.clips.push(clip);
+    }
+
+    fn push_clip_box(&mut self, clipbox: ClipBox) {
+        let mask = self.rasterize(|outline| {
+            outline.move_to(clipbox.x_min, clipbox.y_min);
+            outline.line_to(clipbox.x_max, clipbox.y_min);
+            outline.line_to(clipbox.x_max, clipbox.y_max);
+            outline.line_to(clipbox.x_min, clipbox.y_max);
+            outline.close();
+        });
+        let clip = mask.iter().zip(self.clip()).map(|(a, b)| a * b).collect();
+        self.clips.push(clip);
+    }
+
+    fn pop_clip(&mut self) {
+        if self.clips.len() > 1 {
+            self.clips.pop();
+        }
+    }
+
+    fn push_layer(&mut self, mode: CompositeMode) {
+        let len = self.width * self.height;
+        self.layers.push((vec![[0.0; 4]; len], mode));
+    }
+
+    fn pop_layer(&mut self) {
+        if self.layers.len() < 2 {
+            return;
+        }
+        let (src, mode) = self.layers.pop().expect("no layer");
+        let dst = &mut self.layers.last_mut().expect("no layer").0;
+        for (dst, src) in dst.iter_mut().zip(src) {
+            *dst = composite(src, *dst, mode);
+        }
+    }
+
+    fn push_transform(&mut self, transform: Transform) {
+        let t = Transform::combine(self.transform(), transform);
+        self.transforms.push(t);
+    }
+
+    fn pop_transform(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+    }
+}
+
+impl<'f, 'a> Painter<'a> for Bounds<'f, 'a> {
+    fn outline_glyph(&mut self, glyph_id: ttf_parser::GlyphId) {
+        self.outline = self.face.glyph_bounding_box(glyph_id);
+    }
+
+    fn paint(&mut self, _: Paint<'a>) {
+        let rect = match self.outline {
+            Some(rect) => rect,
+            None => return,
+        };
+        let t = *self.transforms.last().expect("no transform");
+        let corners = [
+            (rect.x_min, rect.y_min),
+            (rect.x_max, rect.y_min),
+            (rect.x_max, rect.y_max),
+            (rect.x_min, rect.y_max),
+        ];
+        for (x, y) in corners {
+            let (x, y) = apply(t, f32::from(x), f32::from(y));
+            self.bounds = Some(match self.bounds {
+                None => [x, y, x, y],
+                Some([x0, y0, x1, y1]) => [x0.min(x), y0.min(y), x1.max(x), y1.max(y)],
+            });
+        }
+    }
+
+    fn push_clip(&mut self) {}
+
+    fn push_clip_box(&mut self, _: ClipBox) {}
+
+    fn pop_clip(&mut self) {}
+
+    fn push_layer(&mut self, _: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, transform: Transform) {
+        let t = *self.transforms.last().expect("no transform");
+        self.transforms.push(Transform::combine(t, transform));
+    }
+
+    fn pop_transform(&mut self) {
+        if self.transforms.len() > 1 {
+            self.transforms.pop();
+        }
+    }
+}
+
+impl<'r> Outline<'r> {
+    // Map a point in font units to the canvas in pixels.
+    fn map(&self, x: f32, y: f32) -> Point {
+        let (x, y) = apply(self.transform, x, y);
+        point(
+            x * self.px_per_unit - self.left,
+            self.top - y * self.px_per_unit,
+        )
+    }
+}
+
+impl<'r> OutlineBuilder for Outline<'r> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.start = self.map(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.map(x, y);
+        self.rasterizer.draw_line(self.last, p);
+        self.last = p;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (p1, p) = (self.map(x1, y1), self.map(x, y));
+        self.rasterizer.draw_quad(self.last, p1, p);
+        self.last = p;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (p1, p2, p) = (self.map(x1, y1), self.map(x2, y2), self.map(x, y));
+        self.rasterizer.draw_cubic(self.last, p1, p2, p);
+        self.last = p;
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.rasterizer.draw_line(self.last, self.start);
+        }
+        self.last = self.start;
+    }
+}
+
+// Apply the transform to the given point.
+fn apply(t: Transform, x: f32, y: f32) -> (f32, f32) {
+    (t.a * x + t.c * y + t.e, t.b * x + t.d * y + t.f)
+}
+
+// The given color as premultiplied sRGB components in the range `0.0..=1.0`.
+fn premultiply(color: RgbaColor) -> [f32; 4] {
+    let a = f32::from(color.alpha) / 255.0;
+    let c = |v: u8| f32::from(v) / 255.0 * a;
+    [c(color.red), c(color.green), c(color.blue), a]
+}
+
+// Collect the color stops of a gradient, ordered by offset.
+fn stops<I>(stops: I) -> Vec<(f32, [f32; 4])>
+where
+    I: Iterator<Item = ColorStop>,
+{
+    let mut stops: Vec<_> = stops
+        .map(|stop| (stop.stop_offset, premultiply(stop.color)))
+        .collect();
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    stops
+}
+
+// Sample the color stops at the given offset along the gradient.
+fn sample(stops: &[(f32, [f32; 4])], extend: GradientExtend, t: f32) -> [f32; 4] {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return [0.0; 4],
+    };
+    let (t0, t1) = (first.0, last.0);
+    let t = match extend {
+        _ if t1 <= t0 => t,
+        GradientExtend::Pad => t,
+        GradientExtend::Repeat => t0 + (t - t0).rem_euclid(t1 - t0),
+        GradientExtend::Reflect => {
+            let span = t1 - t0;
+            let u = (t - t0).rem_euclid(span * 2.0);
+            t0 + if u > span { span * 2.0 - u } else { u }
+        }
+    };
+    if t <= t0 {
+        return first.1;
+    }
+    if t >= t1 {
+        return last.1;
+    }
+    let ix = stops
+        .iter()
+        .position(|s| s.0 >= t)
+        .unwrap_or(stops.len() - 1);
+    let (a, b) = (stops[ix.saturating_sub(1)], stops[ix]);
+    let f = if b.0 > a.0 {
+        (t - a.0) / (b.0 - a.0)
+    } else {
+        0.0
+    };
+    [0, 1, 2, 3].map(|c| a.1[c] + (b.1[c] - a.1[c]) * f)
+}
+
+// The offset along a linear gradient from `p0` to `p1`, rotated such that its color lines are
+// parallel to `p0` to `p2`.
+#[allow(clippy::too_many_arguments)]
+fn linear_offset(
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    x: f32,
+    y: f32,
+) -> Option<f32> {
+    let (dx1, dy1) = (x1 - x0, y1 - y0);
+    let (dx2, dy2) = (x2 - x0, y2 - y0);
+    // Project `p1` onto the line perpendicular to `p0` to `p2`.
+    let (nx, ny) = (dy2, -dx2);
+    let n2 = nx * nx + ny * ny;
+    let (gx, gy) = if n2 == 0.0 {
+        (dx1, dy1)
+    } else {
+        let k = (dx1 * nx + dy1 * ny) / n2;
+        (nx * k, ny * k)
+    };
+    let g2 = gx * gx + gy * gy;
+    if g2 == 0.0 {
+        return None;
+    }
+    Some(((x - x0) * gx + (y - y0) * gy) / g2)
+}
+
+// The offset along a two point conical gradient between the given circles, preferring the
+// larger of the two solutions with a non-negative radius.
+#[allow(clippy::too_many_arguments)]
+fn radial_offset(
+    x0: f32,
+    y0: f32,
+    r0: f32,
+    x1: f32,
+    y1: f32,
+    r1: f32,
+    x: f32,
+    y: f32,
+) -> Option<f32> {
+    let (cdx, cdy, dr) = (x1 - x0, y1 - y0, r1 - r0);
+    let (pdx, pdy) = (x - x0, y - y0);
+    let a = cdx * cdx + cdy * cdy - dr * dr;
+    let b = pdx * cdx + pdy * cdy + r0 * dr;
+    let c = pdx * pdx + pdy * pdy - r0 * r0;
+    let radius = |t: f32| r0 + t * dr;
+    if a.abs() < 1e-6 {
+        if b == 0.0 {
+            return None;
+        }
+        let t = c / (2.0 * b);
+        return (radius(t) >= 0.0).then_some(t);
+    }
+    let disc = b * b - a * c;
+    if disc < 0.0 {
+        return None;
+    }
+    let sqrt = disc.sqrt();
+    let (t0, t1) = ((b + sqrt) / a, (b - sqrt) / a);
+    let (hi, lo) = if t0 > t1 { (t0, t1) } else { (t1, t0) };
+    if radius(hi) >= 0.0 {
+        Some(hi)
+    } else if radius(lo) >= 0.0 {
+        Some(lo)
+    } else {
+        None
+    }
+}
+
+// Composite the premultiplied source over the destination with the given Porter-Duff operator.
+fn composite(src: [f32; 4], dst: [f32; 4], mode: CompositeMode) -> [f32; 4] {
+    let (sa, da) = (src[3], dst[3]);
+    let (fa, fb) = match mode {
+        CompositeMode::Clear => (0.0, 0.0),
+        CompositeMode::Source => (1.0, 0.0),
+        CompositeMode::Destination => (0.0, 1.0),
+        CompositeMode::DestinationOver => (1.0 - da, 1.0),
+        CompositeMode::SourceIn => (da, 0.0),
+        CompositeMode::DestinationIn => (0.0, sa),
+        CompositeMode::SourceOut => (1.0 - da, 0.0),
+        CompositeMode::DestinationOut => (0.0, 1.0 - sa),
+        CompositeMode::SourceAtop => (da, 1.0 - sa),
+        CompositeMode::DestinationAtop => (1.0 - da, sa),
+        CompositeMode::Xor => (1.0 - da, 1.0 - sa),
+        CompositeMode::Plus => (1.0, 1.0),
+        _ => (1.0, 1.0 - sa),
+    };
+    [0, 1, 2, 3].map(|c| (src[c] * fa + dst[c] * fb).min(1.0))
+}
@@ -52,7 +52,7 @@ pub struct XysPerLineFromText<'a> {
 /// `Xs` iterators are produced by the `XysPerLine` iterator.
 pub struct Xs<'a, 'b> {
     next_x: Option<Scalar>,
-    layout: text::LayoutIter<'a, 'b>,
+    layout: text::font::Glyphs<'a, 'b>,
 }
 
 impl Index {
@@ -428,7 +428,8 @@ where
             let (x, y) = (line_rect.left() as f32, line_rect.top() as f32);
             let point = text::rt::Point { x: x, y: y };
             let y = line_rect.y;
-            let layout = font.layout(line, scale, point);
+            let spacing = text::line::word_spacing(line, font, font_size, line_rect.w());
+            let layout = text::font::layout(font, line, scale, point).word_spacing(spacing);
             let xs = Xs {
                 next_x: Some(line_rect.x.start),
                 layout: layout,
@@ -450,7 +451,7 @@ impl<'a, 'b> Iterator for Xs<'a, 'b> {
     type Item = Scalar;
     fn next(&mut self) -> Option<Self::Item> {
         self.next_x.map(|x| {
-            self.next_x = self.layout.next().map(|g| {
+            self.next_x = self.layout.next().map(|(_, g)| {
                 g.pixel_bounding_box()
                     .map(|r| r.max.x as Scalar)
                     .unwrap_or_else(|| x + g.unpositioned().h_metrics().advance_width as Scalar)
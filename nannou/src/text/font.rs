@@ -1,9 +1,45 @@
-//! The `font::Id` and `font::Map` types.
+//! The `font::Id` and `font::Map` types, the font fallback chain and system font discovery.
+//!
+//! When laying out text, characters that are missing from the text's font are resolved through the
+//! chain of fallback fonts in order. For example, a sketch may fall back from its own font to a
+//! broader system font and then to an emoji font:
+//!
+//! ```no_run
+//! use nannou::text::font;
+//!
+//! let cjk = font::from_file("assets/fonts/NotoSansCJK.ttc").unwrap();
+//! let emoji = font::from_file("assets/fonts/NotoEmoji.ttf").unwrap();
+//! font::set_fallbacks(vec![cjk, emoji]);
+//! ```
+//!
+//! When no font in the chain provides a character, the fonts of the `SYSTEM_FALLBACK_FAMILIES`
+//! that are installed are searched as a last resort, so that e.g. emoji are drawn without the
+//! sketch providing an emoji font. See `set_system_fallbacks` to disable this.
+//!
+//! Color glyphs described by color layers or bitmaps (i.e. the COLR, CBDT or sbix tables) are
+//! drawn in color by the text primitive. See the `color_glyph` module.
+//!
+//! Fonts installed on the system may be loaded by family name, so that sketches need not ship
+//! font files for common fonts:
+//!
+//! ```no_run
+//! use nannou::text::font;
+//!
+//! for family in font::system_families() {
+//!     println!("{}: {:?}", family, font::system_styles(family));
+//! }
+//! let font = font::from_system("Helvetica Neue").unwrap();
+//! let bold = font::from_system_style("Helvetica Neue", "Bold").unwrap();
+//! ```
 
-use crate::text::{Font, FontCollection};
+use crate::text::{rt, Font, FontCollection, GlyphId, PositionedGlyph, Scale};
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::{Arc, OnceLock, RwLock, Weak};
+use std::{env, fs};
 
 /// A type-safe wrapper around the `FontId`.
 ///
@@ -33,6 +69,34 @@ pub struct Ids<'a> {
     keys: std::collections::hash_map::Keys<'a, Id, Font>,
 }
 
+/// Lays out a line of text, resolving each character through the fallback chain.
+///
+/// Yields each positioned glyph alongside the index of the font that provides it, where `0` is
+/// the primary font and `1..` refers to the fonts returned by `fallbacks`.
+pub struct Glyphs<'a, 'b> {
+    font: &'a Font,
+    fallbacks: Arc<Vec<Font>>,
+    chars: std::str::Chars<'b>,
+    scale: Scale,
+    start: rt::Point<f32>,
+    caret: f32,
+    word_spacing: f32,
+    last: Option<(usize, GlyphId)>,
+}
+
+/// A font face installed on the system, as found by `system_fonts`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SystemFont {
+    /// The family name, e.g. `"Helvetica Neue"`.
+    pub family: String,
+    /// The style within the family, e.g. `"Bold Italic"`.
+    pub style: String,
+    /// The path to the font file.
+    pub path: PathBuf,
+    /// The index of the face within the font file, for files containing a collection of fonts.
+    pub index: u32,
+}
+
 /// Returned when loading new fonts from file or bytes.
 #[derive(Debug)]
 pub enum Error {
@@ -40,11 +104,41 @@ pub enum Error {
     Io(std::io::Error),
     /// No `Font`s could be yielded from the `FontCollection`.
     NoFont,
+    /// No installed font matches the requested family and style.
+    NotInstalled(String),
 }
 
 /// The name of the default directory that is searched for fonts.
 pub const DEFAULT_DIRECTORY_NAME: &str = "fonts";
 
+/// The styles preferred, in order, when loading a system font family without specifying a style.
+pub const DEFAULT_SYSTEM_STYLES: &[&str] = &["Regular", "Book", "Normal", "Roman", "Medium"];
+
+/// The file extensions of the font files found by `system_fonts`.
+pub const SYSTEM_FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "otc"];
+
+/// The system font families searched, in order, for characters that no font within the chain of
+/// fallbacks provides. Those that are not installed are skipped.
+///
+/// Emoji fonts come last so that characters with both a text and an emoji presentation prefer the
+/// former.
+pub const SYSTEM_FALLBACK_FAMILIES: &[&str] = &[
+    "Noto Sans",
+    "DejaVu Sans",
+    "Arial Unicode MS",
+    "Segoe UI",
+    "Segoe UI Symbol",
+    "Noto Sans Symbols",
+    "Noto Sans Symbols 2",
+    "Noto Sans CJK SC",
+    "PingFang SC",
+    "Microsoft YaHei",
+    "Noto Color Emoji",
+    "Apple Color Emoji",
+    "Segoe UI Emoji",
+    "Twemoji Mozilla",
+];
+
 impl Id {
     /// Returns the inner `usize` from the `Id`.
     pub fn index(self) -> usize {
@@ -118,6 +212,175 @@ pub fn id(font: &Font) -> Id {
     Id((hasher.finish() % std::usize::MAX as u64) as usize)
 }
 
+// The chain of fonts used for characters missing from the font used to lay out text.
+fn fallback_chain() -> &'static RwLock<Arc<Vec<Font>>> {
+    static FALLBACKS: OnceLock<RwLock<Arc<Vec<Font>>>> = OnceLock::new();
+    FALLBACKS.get_or_init(Default::default)
+}
+
+// The chain of fallbacks followed by the system fallbacks, alongside the chain it extends.
+type ExtendedChain = Option<(Arc<Vec<Font>>, Arc<Vec<Font>>)>;
+
+fn extended_chain() -> &'static RwLock<ExtendedChain> {
+    static EXTENDED: OnceLock<RwLock<ExtendedChain>> = OnceLock::new();
+    EXTENDED.get_or_init(Default::default)
+}
+
+// Whether or not the system fallbacks are searched.
+static SYSTEM_FALLBACKS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+// The data of each font loaded via this module, by font ID, alongside the index of the face.
+type FontData = HashMap<Id, (Weak<[u8]>, u32)>;
+
+fn font_data() -> &'static RwLock<FontData> {
+    static DATA: OnceLock<RwLock<FontData>> = OnceLock::new();
+    DATA.get_or_init(Default::default)
+}
+
+/// The data of the given font alongside the index of its face within the data.
+///
+/// This provides access to the tables that rusttype does not support, e.g. those describing
+/// color glyphs. Only fonts loaded via this module are tracked.
+pub(crate) fn data(font: &Font) -> Option<(Arc<[u8]>, u32)> {
+    let data = font_data()
+        .read()
+        .expect("failed to acquire font data lock");
+    let (bytes, index) = data.get(&id(font))?;
+    Some((bytes.upgrade()?, *index))
+}
+
+/// The fonts searched, in order, for characters that are missing from the font used to lay out
+/// text.
+pub fn fallbacks() -> Arc<Vec<Font>> {
+    fallback_chain()
+        .read()
+        .expect("failed to acquire fallback fonts lock")
+        .clone()
+}
+
+/// Replace the chain of fallback fonts.
+///
+/// Text laid out after this call resolves missing characters through the given fonts in order.
+pub fn set_fallbacks<I>(fonts: I)
+where
+    I: IntoIterator<Item = Font>,
+{
+    let fonts = Arc::new(fonts.into_iter().collect());
+    *fallback_chain()
+        .write()
+        .expect("failed to acquire fallback fonts lock") = fonts;
+}
+
+/// Append a font to the end of the chain of fallback fonts.
+pub fn push_fallback(font: Font) {
+    let mut chain = fallback_chain()
+        .write()
+        .expect("failed to acquire fallback fonts lock");
+    let mut fonts = (**chain).clone();
+    fonts.push(font);
+    *chain = Arc::new(fonts);
+}
+
+/// Enable or disable searching the `SYSTEM_FALLBACK_FAMILIES` for characters that no font within
+/// the chain of fallbacks provides.
+///
+/// This is enabled by default.
+pub fn set_system_fallbacks(enabled: bool) {
+    SYSTEM_FALLBACKS_ENABLED.store(enabled, atomic::Ordering::Relaxed);
+}
+
+/// The fonts of the `SYSTEM_FALLBACK_FAMILIES` that are installed on the system.
+///
+/// The fonts are loaded on the first call and cached for subsequent calls.
+pub fn system_fallbacks() -> &'static [Font] {
+    static FONTS: OnceLock<Vec<Font>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        SYSTEM_FALLBACK_FAMILIES
+            .iter()
+            .filter_map(|family| from_system(family).ok())
+            .collect()
+    })
+}
+
+/// The fonts searched, in order, for the characters of the given text that are missing from the
+/// given font.
+///
+/// This is the chain of `fallbacks`, extended by the `system_fallbacks` if the chain is missing
+/// any of the text's characters and system fallbacks are enabled. As the system fallbacks are
+/// appended to the end, the index of each font within the chain does not depend on the text.
+pub fn fallbacks_for(font: &Font, text: &str) -> Arc<Vec<Font>> {
+    let fallbacks = fallbacks();
+    if !SYSTEM_FALLBACKS_ENABLED.load(atomic::Ordering::Relaxed) {
+        return fallbacks;
+    }
+    let missing = text.chars().any(|ch| {
+        !ch.is_control()
+            && !has_glyph(font, ch)
+            && !fallbacks.iter().any(|fallback| has_glyph(fallback, ch))
+    });
+    if !missing {
+        return fallbacks;
+    }
+    if let Some((chain, extended)) = &*extended_chain()
+        .read()
+        .expect("failed to acquire fallback fonts lock")
+    {
+        if Arc::ptr_eq(chain, &fallbacks) {
+            return extended.clone();
+        }
+    }
+    let fonts = fallbacks.iter().chain(system_fallbacks()).cloned();
+    let extended = Arc::new(fonts.collect::<Vec<_>>());
+    *extended_chain()
+        .write()
+        .expect("failed to acquire fallback fonts lock") = Some((fallbacks, extended.clone()));
+    extended
+}
+
+/// Whether or not the given font provides a glyph for the given character.
+pub fn has_glyph(font: &Font, ch: char) -> bool {
+    font.glyph(ch).id() != GlyphId(0)
+}
+
+/// Select the font that provides the glyph for the given character.
+///
+/// Returns the index of the font within the chain, where `0` is the given `font` and `1..` refers
+/// to the `fallbacks`. Control characters and characters that no font provides are resolved to
+/// the given `font`.
+pub fn select<'a>(font: &'a Font, fallbacks: &'a [Font], ch: char) -> (usize, &'a Font) {
+    if ch.is_control() || has_glyph(font, ch) {
+        return (0, font);
+    }
+    fallbacks
+        .iter()
+        .enumerate()
+        .find(|(_, fallback)| has_glyph(fallback, ch))
+        .map(|(i, fallback)| (i + 1, fallback))
+        .unwrap_or((0, font))
+}
+
+/// Lay out the given line of text starting at the given point, resolving each character through
+/// the chain of fallback fonts returned by `fallbacks_for`.
+///
+/// This is a fallback-aware alternative to `rusttype::Font::layout`.
+pub fn layout<'a, 'b>(
+    font: &'a Font,
+    text: &'b str,
+    scale: Scale,
+    start: rt::Point<f32>,
+) -> Glyphs<'a, 'b> {
+    Glyphs {
+        font,
+        fallbacks: fallbacks_for(font, text),
+        chars: text.chars(),
+        scale,
+        start,
+        caret: start.x,
+        word_spacing: 0.0,
+        last: None,
+    }
+}
+
 /// Load a `FontCollection` from a file at a given path.
 pub fn collection_from_file<P>(path: P) -> Result<FontCollection, std::io::Error>
 where
@@ -136,8 +399,77 @@ pub fn from_file<P>(path: P) -> Result<Font, Error>
 where
     P: AsRef<std::path::Path>,
 {
-    let collection = collection_from_file(path)?;
-    collection.into_font().or(Err(Error::NoFont))
+    let data = fs::read(path)?;
+    load(data, None)
+}
+
+// Load the face at the given index of the given font data, or the only face if `None`.
+//
+// The data is retained so that the tables unsupported by rusttype may be read, e.g. those
+// describing color glyphs. Faces without outlines, e.g. bitmap emoji fonts, are given empty
+// outline tables as rusttype fails to load them otherwise.
+fn load(data: Vec<u8>, index: Option<u32>) -> Result<Font, Error> {
+    let (data, index) = match with_empty_outlines(&data, index.unwrap_or(0)) {
+        Some(patched) => (patched, None),
+        None => (data, index),
+    };
+    let data: Arc<[u8]> = data.into();
+    let collection = FontCollection::from_bytes(data.clone()).or(Err(Error::NoFont))?;
+    let font = match index {
+        None => collection.into_font(),
+        Some(index) => collection.font_at(index as usize),
+    };
+    let font = font.or(Err(Error::NoFont))?;
+    font_data()
+        .write()
+        .expect("failed to acquire font data lock")
+        .insert(id(&font), (Arc::downgrade(&data), index.unwrap_or(0)));
+    Ok(font)
+}
+
+// If the face at the given index has color bitmaps but no outlines, produce a font containing
+// only that face with empty `glyf` and `loca` tables added.
+fn with_empty_outlines(data: &[u8], index: u32) -> Option<Vec<u8>> {
+    let face = ttf_parser::Face::parse(data, index).ok()?;
+    let raw = face.raw_face();
+    let has = |tag: &[u8; 4]| raw.table(ttf_parser::Tag::from_bytes(tag)).is_some();
+    if has(b"glyf") || has(b"CFF ") || has(b"CFF2") || !(has(b"CBDT") || has(b"sbix")) {
+        return None;
+    }
+    // Every glyph is empty, so each offset within `loca` is zero. The long format is used in
+    // case the `head` table specifies it.
+    let loca = vec![0u8; (usize::from(face.number_of_glyphs()) + 1) * 4];
+    let mut tables: Vec<([u8; 4], &[u8])> = raw
+        .table_records
+        .into_iter()
+        .filter_map(|record| {
+            let table = raw.table(record.tag)?;
+            Some((record.tag.to_bytes(), table))
+        })
+        .collect();
+    tables.push((*b"glyf", &[]));
+    tables.push((*b"loca", &loca));
+    tables.sort_by_key(|&(tag, _)| tag);
+
+    // The header and table records, followed by the table data aligned to four bytes.
+    let num_tables = u16::try_from(tables.len()).ok()?;
+    let mut font = Vec::with_capacity(data.len() + loca.len());
+    font.extend_from_slice(&0x00010000u32.to_be_bytes());
+    font.extend_from_slice(&num_tables.to_be_bytes());
+    font.extend_from_slice(&[0; 6]);
+    let mut offset = 12 + tables.len() * 16;
+    for &(tag, table) in &tables {
+        font.extend_from_slice(&tag);
+        font.extend_from_slice(&[0; 4]);
+        font.extend_from_slice(&u32::try_from(offset).ok()?.to_be_bytes());
+        font.extend_from_slice(&u32::try_from(table.len()).ok()?.to_be_bytes());
+        offset += (table.len() + 3) & !3;
+    }
+    for &(_, table) in &tables {
+        font.extend_from_slice(table);
+        font.resize((font.len() + 3) & !3, 0);
+    }
+    Some(font)
 }
 
 /// Load the default notosans font.
@@ -152,6 +484,164 @@ pub fn default_notosans() -> Font {
         .expect("the `notosans::REGULAR_TTF` font collection contained no fonts")
 }
 
+/// The directories searched for fonts installed on the system.
+pub fn system_directories() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    let home = env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_arch = "wasm32") {
+        // There is no access to the system's fonts from the browser.
+    } else if cfg!(target_os = "windows") {
+        if let Some(windir) = env::var_os("WINDIR") {
+            dirs.push(PathBuf::from(windir).join("Fonts"));
+        }
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Microsoft/Windows/Fonts"));
+        }
+    } else if cfg!(any(target_os = "macos", target_os = "ios")) {
+        dirs.push("/System/Library/Fonts".into());
+        dirs.push("/Library/Fonts".into());
+        if let Some(home) = home {
+            dirs.push(home.join("Library/Fonts"));
+        }
+    } else {
+        dirs.push("/usr/share/fonts".into());
+        dirs.push("/usr/local/share/fonts".into());
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+        if let Some(data_home) = data_home {
+            dirs.push(data_home.join("fonts"));
+        }
+        if let Some(home) = home {
+            dirs.push(home.join(".fonts"));
+        }
+    }
+    dirs
+}
+
+/// Every font face installed on the system, sorted by family and style.
+///
+/// The `system_directories` are scanned on the first call, reading the names of each font file
+/// found. The result is cached for subsequent calls.
+pub fn system_fonts() -> &'static [SystemFont] {
+    static FONTS: OnceLock<Vec<SystemFont>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut fonts = vec![];
+        for dir in system_directories() {
+            let entries = crate::io::walk_dir(dir)
+                .follow_links(true)
+                .into_iter()
+                .filter_map(Result::ok);
+            for entry in entries {
+                if is_font_file(entry.path()) {
+                    fonts.extend(system_fonts_in_file(entry.path()));
+                }
+            }
+        }
+        fonts.sort();
+        fonts.dedup();
+        fonts
+    })
+}
+
+/// The names of the font families installed on the system, sorted and without duplicates.
+pub fn system_families() -> Vec<&'static str> {
+    let mut families: Vec<_> = system_fonts().iter().map(|f| &f.family[..]).collect();
+    families.dedup();
+    families
+}
+
+/// The styles of the given font family installed on the system.
+///
+/// The family name is matched case-insensitively.
+pub fn system_styles(family: &str) -> Vec<&'static str> {
+    system_fonts()
+        .iter()
+        .filter(|f| f.family.eq_ignore_ascii_case(family))
+        .map(|f| &f.style[..])
+        .collect()
+}
+
+/// Load the font of the given family installed on the system, e.g. `"Helvetica Neue"`.
+///
+/// The family name is matched case-insensitively. When the family has more than one style, the
+/// first of the `DEFAULT_SYSTEM_STYLES` that is installed is preferred.
+pub fn from_system(family: &str) -> Result<Font, Error> {
+    let faces: Vec<_> = system_fonts()
+        .iter()
+        .filter(|f| f.family.eq_ignore_ascii_case(family))
+        .collect();
+    let preferred = DEFAULT_SYSTEM_STYLES
+        .iter()
+        .find_map(|style| faces.iter().find(|f| f.style.eq_ignore_ascii_case(style)));
+    match preferred.or_else(|| faces.first()) {
+        Some(face) => face.load(),
+        None => Err(Error::NotInstalled(family.to_string())),
+    }
+}
+
+/// Load the font of the given family and style installed on the system, e.g. `"Bold Italic"`.
+///
+/// Both the family and style are matched case-insensitively.
+pub fn from_system_style(family: &str, style: &str) -> Result<Font, Error> {
+    system_fonts()
+        .iter()
+        .find(|f| f.family.eq_ignore_ascii_case(family) && f.style.eq_ignore_ascii_case(style))
+        .ok_or_else(|| Error::NotInstalled(format!("{} {}", family, style)))
+        .and_then(SystemFont::load)
+}
+
+// Whether or not the file at the given path has the extension of a font file.
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            SYSTEM_FONT_EXTENSIONS
+                .iter()
+                .any(|font_ext| ext.eq_ignore_ascii_case(font_ext))
+        })
+        .unwrap_or(false)
+}
+
+// Read the names of each font face within the font file at the given path.
+fn system_fonts_in_file(path: &Path) -> Vec<SystemFont> {
+    use ttf_parser::name_id;
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(_) => return vec![],
+    };
+    let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+    (0..count)
+        .filter_map(|index| {
+            let face = ttf_parser::Face::parse(&data, index).ok()?;
+            let family = face_name(&face, name_id::TYPOGRAPHIC_FAMILY)
+                .or_else(|| face_name(&face, name_id::FAMILY))?;
+            let style = face_name(&face, name_id::TYPOGRAPHIC_SUBFAMILY)
+                .or_else(|| face_name(&face, name_id::SUBFAMILY))
+                .unwrap_or_else(|| "Regular".to_string());
+            let path = path.to_path_buf();
+            Some(SystemFont {
+                family,
+                style,
+                path,
+                index,
+            })
+        })
+        .collect()
+}
+
+// The face's name with the given ID, preferring US English.
+fn face_name(face: &ttf_parser::Face, name_id: u16) -> Option<String> {
+    const ENGLISH_US: u16 = 0x0409;
+    let mut names: Vec<_> = face
+        .names()
+        .into_iter()
+        .filter(|name| name.name_id == name_id && name.is_unicode())
+        .collect();
+    names.sort_by_key(|name| name.language_id != ENGLISH_US);
+    names.iter().find_map(|name| name.to_string())
+}
+
 /// The directory that is searched for default fonts.
 pub fn default_directory(assets: &Path) -> PathBuf {
     assets.join(DEFAULT_DIRECTORY_NAME)
@@ -189,6 +679,54 @@ pub fn default(assets: &Path) -> Result<Font, Error> {
     Err(Error::NoFont)
 }
 
+impl SystemFont {
+    /// Load the font face.
+    pub fn load(&self) -> Result<Font, Error> {
+        let data = fs::read(&self.path)?;
+        load(data, Some(self.index))
+    }
+}
+
+impl<'a, 'b> Glyphs<'a, 'b> {
+    /// Insert the given additional space after each whitespace character.
+    ///
+    /// This is used to stretch lines of text across their width. See `line::word_spacing`.
+    pub fn word_spacing(mut self, spacing: f32) -> Self {
+        self.word_spacing = spacing;
+        self
+    }
+
+    /// The fonts referred to by the font index yielded alongside each glyph.
+    pub fn fallbacks(&self) -> &Arc<Vec<Font>> {
+        &self.fallbacks
+    }
+}
+
+impl<'a, 'b> Iterator for Glyphs<'a, 'b> {
+    type Item = (usize, PositionedGlyph);
+    fn next(&mut self) -> Option<Self::Item> {
+        let ch = self.chars.next()?;
+        let (index, font) = select(self.font, &self.fallbacks, ch);
+        let g = font.glyph(ch).scaled(self.scale);
+        // Kerning only applies between glyphs of the same font.
+        if let Some((last_index, last_id)) = self.last {
+            if last_index == index {
+                self.caret += font.pair_kerning(self.scale, last_id, g.id());
+            }
+        }
+        self.last = Some((index, g.id()));
+        let advance_width = g.h_metrics().advance_width;
+        let g = g
+            .positioned(rt::point(self.caret, self.start.y))
+            .standalone();
+        self.caret += advance_width;
+        if ch.is_whitespace() {
+            self.caret += self.word_spacing;
+        }
+        Some((index, g))
+    }
+}
+
 impl Iterator for NewIds {
     type Item = Id;
     fn next(&mut self) -> Option<Self::Item> {
@@ -213,7 +751,7 @@ impl std::error::Error for Error {
     fn cause(&self) -> Option<&dyn std::error::Error> {
         match *self {
             Error::Io(ref e) => Some(e),
-            Error::NoFont => None,
+            Error::NoFont | Error::NotInstalled(_) => None,
         }
     }
 }
@@ -223,6 +761,7 @@ impl std::fmt::Display for Error {
         match *self {
             Error::Io(ref e) => std::fmt::Display::fmt(e, f),
             Error::NoFont => write!(f, "No `Font` found in the loaded `FontCollection`."),
+            Error::NotInstalled(ref name) => write!(f, "No installed font matches `{}`.", name),
         }
     }
 }
@@ -1,6 +1,6 @@
 //! Logic and types specific to individual glyph layout.
 
-use crate::geom::{Range, Rect};
+use crate::geom::{self, Range, Rect};
 use crate::text::{self, FontSize, Scalar, ScaledGlyph};
 
 /// Some position along the X axis (used within `CharXs`).
@@ -15,8 +15,8 @@ pub struct Rects<'a, 'b> {
     ///
     /// Every yielded `Rect` will use this as its `y` `Range`.
     y: Range,
-    /// `PositionedGlyphs` yielded by the RustType `LayoutIter`.
-    layout: text::LayoutIter<'a, 'b>,
+    /// `PositionedGlyphs` yielded by the fallback-aware font layout.
+    layout: text::font::Glyphs<'a, 'b>,
 }
 
 /// An iterator that, for every `(line, line_rect)` pair yielded by the given iterator,
@@ -60,7 +60,7 @@ impl<'a, 'b> Iterator for Rects<'a, 'b> {
     type Item = (ScaledGlyph<'a>, Rect);
     fn next(&mut self) -> Option<Self::Item> {
         let Rects { ref mut layout, y } = *self;
-        layout.next().map(|g| {
+        layout.next().map(|(_, g)| {
             let left = g.position().x;
             let (right, height) = g
                 .pixel_bounding_box()
@@ -95,8 +95,9 @@ where
         lines_with_rects.next().map(|(line, line_rect)| {
             let (x, y) = (line_rect.left() as f32, line_rect.top() as f32);
             let point = text::rt::Point { x: x, y: y };
+            let spacing = text::line::word_spacing(line, font, font_size, line_rect.w());
             Rects {
-                layout: font.layout(line, scale, point),
+                layout: text::font::layout(font, line, scale, point).word_spacing(spacing),
                 y: line_rect.y,
             }
         })
@@ -342,3 +343,18 @@ pub fn path_events(glyph: ScaledGlyph) -> Option<impl Iterator<Item = lyon::path
         .and_then(|bb| glyph.shape().map(|ctrs| (bb, ctrs)))
         .map(|(bb, ctrs)| contours_to_path(bb, ctrs))
 }
+
+/// Produce the outline of the given scaled glyph as a path.
+///
+/// The path's origin lies at the glyph's origin on the baseline, with *y* increasing upwards.
+///
+/// Returns `None` for glyphs without an outline, such as whitespace.
+pub fn outline(glyph: ScaledGlyph) -> Option<geom::Path> {
+    use lyon::path::builder::PathBuilder;
+    let events = path_events(glyph)?;
+    let mut builder = geom::path();
+    for event in events {
+        builder.path_event(event);
+    }
+    Some(builder.build())
+}
@@ -93,6 +93,11 @@ impl Builder {
         self.justify(Justify::Right)
     }
 
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range.
+    pub fn full_justify(self) -> Self {
+        self.justify(Justify::Full)
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(mut self, spacing: Scalar) -> Self {
         self.line_spacing = Some(spacing);
@@ -99,6 +99,7 @@ pub struct Infos<'a, F> {
 #[derive(Clone)]
 pub struct Rects<I> {
     infos: I,
+    x_bounds: Range,
     x_align: text::Justify,
     line_spacing: Scalar,
     last_line_top: Scalar,
@@ -202,6 +203,8 @@ impl<'a> Infos<'a, NextBreakFnPtr> {
 /// A function for finding the advance width between the given character that also considers
 /// the kerning for some previous glyph.
 ///
+/// The glyph is resolved through the given chain of `fallbacks` if missing from `font`.
+///
 /// This also updates the `last_glyph` with the glyph produced for the given `char`.
 ///
 /// This is primarily for use within the `next_break` functions below.
@@ -210,19 +213,22 @@ impl<'a> Infos<'a, NextBreakFnPtr> {
 fn advance_width_and_height(
     ch: char,
     font: &text::Font,
+    fallbacks: &[text::Font],
     scale: text::Scale,
-    last_glyph: &mut Option<text::GlyphId>,
+    last_glyph: &mut Option<(usize, text::GlyphId)>,
 ) -> (Scalar, Scalar) {
+    let (font_ix, font) = text::font::select(font, fallbacks, ch);
     let g = font.glyph(ch).scaled(scale);
     let kern = last_glyph
-        .map(|last| font.pair_kerning(scale, last, g.id()))
+        .filter(|&(last_ix, _)| last_ix == font_ix)
+        .map(|(_, last)| font.pair_kerning(scale, last, g.id()))
         .unwrap_or(0.0);
     let advance_width = g.h_metrics().advance_width;
     let height = g
         .exact_bounding_box()
         .map(|bb| bb.min.y.abs() as Scalar)
         .unwrap_or(0.0);
-    *last_glyph = Some(g.id());
+    *last_glyph = Some((font_ix, g.id()));
     let adv_w = (kern + advance_width) as Scalar;
     (adv_w, height)
 }
@@ -231,6 +237,7 @@ fn advance_width_and_height(
 /// along with the width of the line.
 fn next_break(text: &str, font: &text::Font, font_size: FontSize) -> NextBreak {
     let scale = text::pt_to_scale(font_size);
+    let fallbacks = text::font::fallbacks_for(font, text);
     let mut width = 0.0;
     let mut height = 0.0;
     let mut char_i = 0;
@@ -267,7 +274,7 @@ fn next_break(text: &str, font: &text::Font, font_size: FontSize) -> NextBreak {
         }
 
         // Update the width.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
+        let (adv_w, h) = advance_width_and_height(ch, font, &fallbacks, scale, &mut last_glyph);
         width += adv_w;
         height = height.max(h);
         char_i += 1;
@@ -295,6 +302,7 @@ fn next_break_by_character(
     max_width: Scalar,
 ) -> NextBreak {
     let scale = text::pt_to_scale(font_size);
+    let fallbacks = text::font::fallbacks_for(font, text);
     let mut width = 0.0;
     let mut height = 0.0;
     let mut char_i = 0;
@@ -331,7 +339,7 @@ fn next_break_by_character(
         }
 
         // Add the character's width to the width so far.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
+        let (adv_w, h) = advance_width_and_height(ch, font, &fallbacks, scale, &mut last_glyph);
         let new_width = width + adv_w;
 
         // Check for a line wrap.
@@ -385,6 +393,7 @@ fn next_break_by_whitespace(
         width_before: Scalar,
     }
     let scale = text::pt_to_scale(font_size);
+    let fallbacks = text::font::fallbacks_for(font, text);
     let mut last_whitespace_start = None;
     let mut width = 0.0;
     let mut height = 0.0;
@@ -422,7 +431,7 @@ fn next_break_by_whitespace(
         }
 
         // Add the character's width to the width so far.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
+        let (adv_w, h) = advance_width_and_height(ch, font, &fallbacks, scale, &mut last_glyph);
         let new_width = width + adv_w;
 
         // Check for a line wrap.
@@ -493,7 +502,7 @@ pub fn width(text: &str, font: &text::Font, font_size: FontSize) -> Scalar {
     let point = text::rt::Point { x: 0.0, y: 0.0 };
 
     let mut total_w = 0.0;
-    for g in font.layout(text, scale, point) {
+    for (_, g) in text::font::layout(font, text, scale, point) {
         match g.pixel_bounding_box() {
             Some(bb) => total_w = bb.max.x as f32,
             None => total_w += g.unpositioned().h_metrics().advance_width,
@@ -578,15 +587,10 @@ pub fn rects<I>(
 where
     I: Iterator<Item = Info>,
 {
+    let x_bounds = Range::new(0.0, max_width);
     let first_rect = infos.next().map(|first_info| {
         // Calculate the `x` `Range` of the first line `Rect`.
-        let x_bounds = Range::new(0.0, max_width);
-        let range = Range::new(0.0, first_info.width);
-        let x = match x_align {
-            text::Justify::Left => range.align_start_of(x_bounds),
-            text::Justify::Center => range.align_middle_of(x_bounds),
-            text::Justify::Right => range.align_end_of(x_bounds),
-        };
+        let x = align_x(&first_info, x_bounds, x_align);
         let y_start = -(font_size as Scalar);
         //let y_end = y_start + first_info.height;
         let y_end = y_start + font_size as Scalar;
@@ -595,6 +599,7 @@ where
     });
     Rects {
         infos: infos,
+        x_bounds,
         next: first_rect,
         x_align: x_align,
         last_line_top: 0.0,
@@ -603,6 +608,49 @@ where
     }
 }
 
+// Align the given line along the *x* axis within the given bounds.
+fn align_x(info: &Info, x_bounds: Range, x_align: text::Justify) -> Range {
+    let range = Range::new(0.0, info.width);
+    match x_align {
+        text::Justify::Left => range.align_start_of(x_bounds),
+        text::Justify::Center => range.align_middle_of(x_bounds),
+        text::Justify::Right => range.align_end_of(x_bounds),
+        // Wrapped lines are stretched across the bounds, while the last line of each paragraph
+        // is aligned to the start.
+        text::Justify::Full => match info.end_break {
+            Break::Wrap { .. } => x_bounds,
+            Break::Newline { .. } | Break::End { .. } => range.align_start_of(x_bounds),
+        },
+    }
+}
+
+/// The additional space to insert after each whitespace character so that the given line of text
+/// fills the given width.
+///
+/// This is used to lay out glyphs for lines stretched by `Justify::Full`. Returns `0.0` if the
+/// line contains no whitespace or if its glyphs already fill the width.
+pub fn word_spacing(text: &str, font: &text::Font, font_size: FontSize, width: Scalar) -> Scalar {
+    // Differences smaller than this are considered rounding errors.
+    const EPSILON: Scalar = 0.01;
+    let scale = text::pt_to_scale(font_size);
+    let fallbacks = text::font::fallbacks_for(font, text);
+    let mut last_glyph = None;
+    let mut line_width = 0.0;
+    let mut spaces = 0;
+    for ch in text.chars() {
+        let (adv_w, _) = advance_width_and_height(ch, font, &fallbacks, scale, &mut last_glyph);
+        line_width += adv_w;
+        if ch.is_whitespace() {
+            spaces += 1;
+        }
+    }
+    let extra = width - line_width;
+    if spaces == 0 || extra < EPSILON {
+        return 0.0;
+    }
+    extra / spaces as Scalar
+}
+
 /// Produces an iterator yielding a `Rect` for the selected range in each selected line in a block
 /// of text.
 ///
@@ -754,6 +802,7 @@ where
         let Rects {
             ref mut next,
             ref mut infos,
+            x_bounds,
             x_align,
             ref mut last_line_top,
             font_size,
@@ -770,14 +819,7 @@ where
                     Range::new(y_start, y_end)
                 };
 
-                let x = {
-                    let range = Range::new(0.0, info.width);
-                    match x_align {
-                        text::Justify::Left => range.align_start_of(line_rect.x),
-                        text::Justify::Center => range.align_middle_of(line_rect.x),
-                        text::Justify::Right => range.align_end_of(line_rect.x),
-                    }
-                };
+                let x = align_x(&info, x_bounds, x_align);
 
                 Rect { x: x, y: y }
             });
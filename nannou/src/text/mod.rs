@@ -3,6 +3,7 @@
 //! Currently, this crate is used primarily by the `draw.text()` API but will also play an
 //! important role in future GUI work.
 
+pub mod color_glyph;
 pub mod cursor;
 pub mod font;
 pub mod glyph;
@@ -111,10 +112,11 @@ pub enum Justify {
     Center,
     /// Align text to the end of the bounding `Rect`'s *x* axis.
     Right,
-    // /// Align wrapped text to both the start and end of the bounding `Rect`s *x* axis.
-    // ///
-    // /// Extra space is added between words in order to achieve this alignment.
-    // TODO: Full,
+    /// Align wrapped text to both the start and end of the bounding `Rect`s *x* axis.
+    ///
+    /// Extra space is added between words in order to achieve this alignment. The last line of
+    /// each paragraph is aligned to the start.
+    Full,
 }
 
 /// The way in which text should wrap around the width.
@@ -218,6 +220,11 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.right_justify())
     }
 
+    /// Align wrapped lines of text to both ends of the bounding **Rect**'s *x* axis range.
+    pub fn full_justify(self) -> Self {
+        self.map_layout(|l| l.full_justify())
+    }
+
     /// Specify how much vertical space should separate each line of text.
     pub fn line_spacing(self, spacing: Scalar) -> Self {
         self.map_layout(|l| l.line_spacing(spacing))
@@ -478,6 +485,19 @@ impl<'a> Text<'a> {
         })
     }
 
+    /// Produce the outline of every glyph in every line as a separate path.
+    ///
+    /// Glyphs are positioned in the same manner as the `path_events` method. Glyphs without an
+    /// outline, such as whitespace, are skipped.
+    pub fn glyph_outlines(&self) -> Vec<geom::Path> {
+        self.glyphs()
+            .filter_map(|(g, r)| {
+                let translation = lyon::math::Translation::new(r.left(), r.bottom());
+                glyph::outline(g).map(|path| path.transformed(&translation))
+            })
+            .collect()
+    }
+
     /// Produce an iterator yielding positioned rusttype glyphs ready for caching.
     ///
     /// The window dimensions (in logical space) and scale_factor are required to transform glyph
@@ -497,6 +517,25 @@ impl<'a> Text<'a> {
         )
     }
 
+    /// Produce an iterator yielding positioned rusttype glyphs ready for caching, alongside the
+    /// index of the font that provides each.
+    ///
+    /// A font index of `0` refers to the text's font, while `1..` refers to the fonts returned
+    /// by `font::fallbacks_for` the text.
+    pub fn rt_glyphs_with_fonts<'b: 'a>(
+        &'b self,
+        window_size: Vec2,
+        scale_factor: Scalar,
+    ) -> impl 'a + 'b + Iterator<Item = (usize, PositionedGlyph)> {
+        rt_positioned_glyphs_with_fonts(
+            self.lines_with_rects(),
+            &self.font,
+            self.layout.font_size,
+            window_size,
+            scale_factor,
+        )
+    }
+
     /// Converts this `Text` instance into an instance that owns the inner text string.
     pub fn into_owned(self) -> Text<'static> {
         let Text {
@@ -629,6 +668,28 @@ pub fn rt_positioned_glyphs<'a, I>(
     window_size: Vec2,
     scale_factor: Scalar,
 ) -> impl 'a + Iterator<Item = PositionedGlyph>
+where
+    I: IntoIterator<Item = (&'a str, geom::Rect)>,
+    I::IntoIter: 'a,
+{
+    rt_positioned_glyphs_with_fonts(lines_with_rects, font, font_size, window_size, scale_factor)
+        .map(|(_, g)| g)
+}
+
+/// Produce the position of each glyph ready for the rusttype glyph cache, alongside the index of
+/// the font that provides it.
+///
+/// A font index of `0` refers to the given `font`, while `1..` refers to the fonts returned by
+/// `font::fallbacks_for` the text.
+///
+/// Window dimensions are expected in logical coordinates.
+pub fn rt_positioned_glyphs_with_fonts<'a, I>(
+    lines_with_rects: I,
+    font: &'a Font,
+    font_size: FontSize,
+    window_size: Vec2,
+    scale_factor: Scalar,
+) -> impl 'a + Iterator<Item = (usize, PositionedGlyph)>
 where
     I: IntoIterator<Item = (&'a str, geom::Rect)>,
     I::IntoIter: 'a,
@@ -647,7 +708,8 @@ where
                 trans_y(line_rect.bottom()) as f32,
             );
             let point = rt::Point { x: x, y: y };
-            font.layout(line, scale, point).map(|g| g.standalone())
+            let spacing = line::word_spacing(line, font, font_size, line_rect.w()) * scale_factor;
+            font::layout(font, line, scale, point).word_spacing(spacing)
         })
 }
 
@@ -677,3 +739,20 @@ pub fn pt_to_scale(font_size_in_points: FontSize) -> Scale {
 pub fn text(s: &str) -> Builder {
     Builder::from(s)
 }
+
+/// Produce the outline of each glyph in the given line of text as a separate path.
+///
+/// The glyphs are laid out with kerning along a baseline that begins at the origin, with *y*
+/// increasing upwards. Each path may then be transformed, morphed or plotted independently of the
+/// others. Glyphs without an outline, such as whitespace, are skipped.
+///
+/// For multi-line text laid out within a bounding rect, see `Text::glyph_outlines`.
+pub fn glyph_outlines(text: &str, font: &Font, font_size: FontSize) -> Vec<geom::Path> {
+    let scale = pt_to_scale(font_size);
+    font::layout(font, text, scale, rt::point(0.0, 0.0))
+        .filter_map(|(_, g)| {
+            let translation = lyon::math::Translation::new(g.position().x, 0.0);
+            glyph::outline(g.into_unpositioned()).map(|path| path.transformed(&translation))
+        })
+        .collect()
+}
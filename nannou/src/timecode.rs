@@ -0,0 +1,436 @@
+//! Driving animation from an external MIDI clock or MIDI timecode rather than app time.
+//!
+//! In show-control scenarios, visuals must follow the lighting desk, DAW or playback machine
+//! rather than the app's own clock. The types within this module decode the relevant MIDI messages
+//! from any source, e.g. a `midir` input callback:
+//!
+//! - **MidiClock** follows MIDI beat clock (24 pulses per beat), providing the tempo and position
+//!   in beats.
+//! - **Mtc** follows MIDI timecode, providing the position in seconds.
+//! - **Chase** smooths either position into a continuous clock, slewing its rate to correct for
+//!   drift and jitter and jumping when the source locates to a new position.
+//!
+//! ```
+//! use nannou::timecode::{Chase, Mtc};
+//! use std::time::{Duration, Instant};
+//!
+//! let start = Instant::now();
+//! let mut mtc = Mtc::new();
+//! // A full frame message locating to 01:00:00:00 at 25 fps.
+//! mtc.message(&[0xF0, 0x7F, 0x7F, 0x01, 0x01, 0x21, 0x00, 0x00, 0x00, 0xF7], start);
+//! assert_eq!(mtc.seconds(start), Some(3600.0));
+//!
+//! // Animate via the chased time rather than `app.time`.
+//! let mut chase = Chase::new();
+//! let t = chase.update(mtc.seconds(start), start);
+//! assert_eq!(t, 3600.0);
+//! ```
+//!
+//! Linear timecode (LTC) is carried as audio rather than MIDI. Once decoded into a **Timecode**
+//! by other means, its `to_seconds` may be passed to the **Chase** in the same manner.
+
+use instant::Instant;
+use std::time::Duration;
+
+/// The number of MIDI clock messages per beat.
+pub const CLOCKS_PER_BEAT: u32 = 24;
+
+// MIDI status bytes.
+const SYSEX: u8 = 0xF0;
+const QUARTER_FRAME: u8 = 0xF1;
+const SONG_POSITION: u8 = 0xF2;
+const SYSEX_END: u8 = 0xF7;
+const CLOCK: u8 = 0xF8;
+const START: u8 = 0xFA;
+const CONTINUE: u8 = 0xFB;
+const STOP: u8 = 0xFC;
+
+/// Follows an external MIDI beat clock.
+#[derive(Clone, Debug)]
+pub struct MidiClock {
+    /// The weight given to each new measurement of the interval between clocks, from `0.0` to
+    /// `1.0`. Lower values give a steadier tempo while higher values follow tempo changes faster.
+    pub smoothing: f64,
+    running: bool,
+    // Whether the next clock marks the current position rather than advancing it.
+    awaiting_first_clock: bool,
+    clocks: u64,
+    last_clock: Option<Instant>,
+    interval: Option<f64>,
+}
+
+/// Follows external MIDI timecode.
+#[derive(Clone, Debug)]
+pub struct Mtc {
+    /// The duration without quarter frame messages after which the timecode is considered
+    /// stopped.
+    pub timeout: Duration,
+    pieces: [u8; 8],
+    received: u8,
+    last_quarter_frame: Option<Instant>,
+    position: Option<(Timecode, Instant)>,
+}
+
+/// A position in SMPTE timecode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub rate: FrameRate,
+}
+
+/// The frame rates supported by MIDI timecode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameRate {
+    Fps24,
+    Fps25,
+    /// 29.97 frames per second with drop-frame numbering.
+    Fps30Drop,
+    Fps30,
+}
+
+/// A continuous clock that chases an external position, correcting for drift.
+///
+/// Between updates the clock advances with real time at its current rate. Each time a reference
+/// position is given, the rate is adjusted slightly so that any error is corrected gradually over
+/// `correction_time`, avoiding visible jumps. Errors larger than `jump_threshold` are assumed to
+/// be intentional relocations and are corrected immediately.
+#[derive(Clone, Debug)]
+pub struct Chase {
+    /// The duration over which errors are corrected, in seconds.
+    pub correction_time: f64,
+    /// The maximum deviation of the rate from `1.0` while correcting errors.
+    pub max_rate_adjustment: f64,
+    /// The error beyond which the clock jumps directly to the reference position.
+    pub jump_threshold: f64,
+    time: f64,
+    rate: f64,
+    last_reference: Option<f64>,
+    last_update: Option<Instant>,
+}
+
+impl MidiClock {
+    /// The default weight given to each new measurement of the clock interval.
+    pub const DEFAULT_SMOOTHING: f64 = 0.1;
+
+    /// Clocks further apart than this are considered to have stopped rather than slowed.
+    const MAX_INTERVAL: f64 = 0.5;
+
+    /// A stopped clock positioned at the first beat.
+    pub fn new() -> Self {
+        MidiClock {
+            smoothing: Self::DEFAULT_SMOOTHING,
+            running: false,
+            awaiting_first_clock: false,
+            clocks: 0,
+            last_clock: None,
+            interval: None,
+        }
+    }
+
+    /// Process a MIDI message received at the given instant.
+    ///
+    /// Returns `false` if the message is not related to MIDI clock.
+    pub fn message(&mut self, bytes: &[u8], now: Instant) -> bool {
+        match *bytes {
+            [CLOCK, ..] => self.clock(now),
+            [START, ..] => {
+                self.clocks = 0;
+                self.running = true;
+                self.awaiting_first_clock = true;
+            }
+            [CONTINUE, ..] => {
+                self.running = true;
+                self.awaiting_first_clock = true;
+            }
+            [STOP, ..] => self.running = false,
+            // The position is given in sixteenth notes, each of which is six clocks.
+            [SONG_POSITION, lsb, msb, ..] => {
+                let sixteenths = (lsb as u64 & 0x7F) | ((msb as u64 & 0x7F) << 7);
+                self.clocks = sixteenths * 6;
+                self.awaiting_first_clock = true;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn clock(&mut self, now: Instant) {
+        if let Some(last) = self.last_clock {
+            let interval = now.duration_since(last).as_secs_f64();
+            if interval < Self::MAX_INTERVAL {
+                self.interval = Some(match self.interval {
+                    Some(avg) => avg + (interval - avg) * self.smoothing,
+                    None => interval,
+                });
+            }
+        }
+        self.last_clock = Some(now);
+        if !self.running {
+            return;
+        }
+        if self.awaiting_first_clock {
+            self.awaiting_first_clock = false;
+        } else {
+            self.clocks += 1;
+        }
+    }
+
+    /// Whether or not the clock is running, i.e. a start or continue message was received more
+    /// recently than a stop message.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// The number of clocks received since the start of the song.
+    pub fn clocks(&self) -> u64 {
+        self.clocks
+    }
+
+    /// The tempo in beats per minute, once at least two clocks have been received.
+    pub fn bpm(&self) -> Option<f64> {
+        self.interval
+            .map(|interval| 60.0 / (interval * CLOCKS_PER_BEAT as f64))
+    }
+
+    /// The position in beats since the start of the song at the given instant.
+    ///
+    /// While running, the position is interpolated between clocks using the measured tempo. It
+    /// never passes the position of the next expected clock, so it does not run ahead when the
+    /// source slows down or stops.
+    pub fn beats(&self, now: Instant) -> f64 {
+        let mut clocks = self.clocks as f64;
+        if let (true, false, Some(last), Some(interval)) = (
+            self.running,
+            self.awaiting_first_clock,
+            self.last_clock,
+            self.interval,
+        ) {
+            let since = now.saturating_duration_since(last).as_secs_f64();
+            clocks += (since / interval).min(1.0);
+        }
+        clocks / CLOCKS_PER_BEAT as f64
+    }
+}
+
+impl Mtc {
+    /// The default duration without quarter frame messages after which the timecode is stopped.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(250);
+
+    /// Timecode that has not yet received a position.
+    pub fn new() -> Self {
+        Mtc {
+            timeout: Self::DEFAULT_TIMEOUT,
+            pieces: [0; 8],
+            received: 0,
+            last_quarter_frame: None,
+            position: None,
+        }
+    }
+
+    /// Process a MIDI message received at the given instant.
+    ///
+    /// Both quarter frame messages and full frame system exclusive messages are supported.
+    /// Returns `false` if the message is not related to MIDI timecode.
+    pub fn message(&mut self, bytes: &[u8], now: Instant) -> bool {
+        match *bytes {
+            [QUARTER_FRAME, data, ..] => self.quarter_frame(data, now),
+            [SYSEX, 0x7F, _device, 0x01, 0x01, hr, mn, sc, fr, SYSEX_END, ..] => {
+                let timecode = Timecode::from_mtc([hr, mn, sc, fr]);
+                self.position = Some((timecode, now));
+                self.last_quarter_frame = None;
+                self.received = 0;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn quarter_frame(&mut self, data: u8, now: Instant) {
+        let piece = (data >> 4) as usize & 0x7;
+        self.pieces[piece] = data & 0x0F;
+        self.last_quarter_frame = Some(now);
+        // Pieces must arrive in order, beginning with the first.
+        if piece == 0 {
+            self.received = 1;
+        } else if self.received & (1 << (piece - 1)) != 0 {
+            self.received |= 1 << piece;
+        } else {
+            self.received = 0;
+        }
+        if self.received != 0xFF {
+            return;
+        }
+        self.received = 0;
+        let p = &self.pieces;
+        let bytes = [
+            p[6] | (p[7] << 4),
+            p[4] | (p[5] << 4),
+            p[2] | (p[3] << 4),
+            p[0] | (p[1] << 4),
+        ];
+        // The timecode refers to the frame at which the first piece was sent, two frames ago.
+        let timecode = Timecode::from_mtc(bytes);
+        let offset = Duration::from_secs_f64(2.0 / timecode.rate.fps());
+        self.position = Some((timecode, now.checked_sub(offset).unwrap_or(now)));
+    }
+
+    /// Whether or not quarter frame messages are being received.
+    pub fn is_running(&self, now: Instant) -> bool {
+        self.last_quarter_frame
+            .map(|last| now.saturating_duration_since(last) < self.timeout)
+            .unwrap_or(false)
+    }
+
+    /// The most recently received timecode.
+    pub fn timecode(&self) -> Option<Timecode> {
+        self.position.map(|(timecode, _)| timecode)
+    }
+
+    /// The position in seconds at the given instant, if a position has been received.
+    ///
+    /// While running, the position advances with real time since the last complete timecode.
+    pub fn seconds(&self, now: Instant) -> Option<f64> {
+        let (timecode, at) = self.position?;
+        let mut seconds = timecode.to_seconds();
+        if self.is_running(now) {
+            seconds += now.saturating_duration_since(at).as_secs_f64();
+        }
+        Some(seconds)
+    }
+}
+
+impl Timecode {
+    // Decode the hours, minutes, seconds and frames bytes of a MIDI timecode message.
+    fn from_mtc([hr, mn, sc, fr]: [u8; 4]) -> Self {
+        Timecode {
+            hours: hr & 0x1F,
+            minutes: mn & 0x3F,
+            seconds: sc & 0x3F,
+            frames: fr & 0x1F,
+            rate: FrameRate::from_mtc((hr >> 5) & 0x3),
+        }
+    }
+
+    /// The position in seconds, accounting for drop-frame numbering.
+    pub fn to_seconds(&self) -> f64 {
+        let h = self.hours as u64;
+        let m = self.minutes as u64;
+        let s = self.seconds as u64;
+        let f = self.frames as u64;
+        match self.rate {
+            FrameRate::Fps30Drop => {
+                // Two frame numbers are skipped each minute, except every tenth minute.
+                let total_minutes = h * 60 + m;
+                let dropped = 2 * (total_minutes - total_minutes / 10);
+                let frame = (h * 3600 + m * 60 + s) * 30 + f - dropped;
+                frame as f64 / self.rate.fps()
+            }
+            _ => (h * 3600 + m * 60 + s) as f64 + f as f64 / self.rate.fps(),
+        }
+    }
+}
+
+impl FrameRate {
+    fn from_mtc(code: u8) -> Self {
+        match code {
+            0 => FrameRate::Fps24,
+            1 => FrameRate::Fps25,
+            2 => FrameRate::Fps30Drop,
+            _ => FrameRate::Fps30,
+        }
+    }
+
+    /// The number of frames per second.
+    pub fn fps(self) -> f64 {
+        match self {
+            FrameRate::Fps24 => 24.0,
+            FrameRate::Fps25 => 25.0,
+            FrameRate::Fps30Drop => 30_000.0 / 1_001.0,
+            FrameRate::Fps30 => 30.0,
+        }
+    }
+}
+
+impl Chase {
+    /// The default duration over which errors are corrected.
+    pub const DEFAULT_CORRECTION_TIME: f64 = 1.0;
+    /// The default maximum deviation of the rate from `1.0`.
+    pub const DEFAULT_MAX_RATE_ADJUSTMENT: f64 = 0.05;
+    /// The default error beyond which the clock jumps to the reference position.
+    pub const DEFAULT_JUMP_THRESHOLD: f64 = 0.5;
+
+    /// A clock at zero that waits for a reference position.
+    pub fn new() -> Self {
+        Chase {
+            correction_time: Self::DEFAULT_CORRECTION_TIME,
+            max_rate_adjustment: Self::DEFAULT_MAX_RATE_ADJUSTMENT,
+            jump_threshold: Self::DEFAULT_JUMP_THRESHOLD,
+            time: 0.0,
+            rate: 1.0,
+            last_reference: None,
+            last_update: None,
+        }
+    }
+
+    /// Advance the clock to the given instant, correcting towards the reference position if
+    /// there is one, and return the current time.
+    ///
+    /// While the reference position is not changing, e.g. as the source is paused, the clock holds
+    /// at the reference position. Without a reference position, the clock holds at its current
+    /// time. This should typically be called once per update.
+    pub fn update(&mut self, reference: Option<f64>, now: Instant) -> f64 {
+        let dt = self
+            .last_update
+            .map(|last| now.saturating_duration_since(last).as_secs_f64())
+            .unwrap_or(0.0);
+        self.last_update = Some(now);
+        let reference = match reference {
+            Some(reference) => reference,
+            None => return self.time,
+        };
+        let moving = self.last_reference.is_some_and(|last| last != reference);
+        self.last_reference = Some(reference);
+
+        self.time += dt * self.rate;
+        let error = reference - self.time;
+        if !moving || error.abs() > self.jump_threshold {
+            self.time = reference;
+            self.rate = 1.0;
+            return self.time;
+        }
+        let max = self.max_rate_adjustment;
+        self.rate = 1.0 + (error / self.correction_time).max(-max).min(max);
+        self.time
+    }
+
+    /// The current time as of the last update.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The rate at which the clock is currently advancing relative to real time.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+}
+
+impl Default for MidiClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Mtc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Chase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,295 @@
+//! Items related to 2D vector fields, a staple of flow-field generative art.
+//!
+//! A **VectorField2** maps each point within its bounds to a vector. It may either store a grid of
+//! vectors that are bilinearly interpolated when sampled, or wrap a function that is evaluated
+//! on demand.
+//!
+//! ```
+//! use nannou::prelude::*;
+//! use nannou::vector_field::VectorField2;
+//!
+//! // A field swirling about the origin.
+//! let bounds = Rect::from_w_h(400.0, 400.0);
+//! let field = VectorField2::from_fn(bounds, |p| vec2(-p.y, p.x) * 0.01);
+//!
+//! // Trace the path of a particle through the field.
+//! let line = field.streamline(pt2(100.0, 0.0), 0.1, 100);
+//! assert_eq!(line.len(), 101);
+//! ```
+//!
+//! Fields may be visualised via `draw.vector_field(&field)`.
+
+use crate::geom::{pt2, Point2, Rect};
+use crate::glam::Vec2;
+use std::fmt;
+use std::sync::Arc;
+
+/// A 2D vector field, described either by a grid of vectors or by a function.
+#[derive(Clone)]
+pub struct VectorField2 {
+    bounds: Rect,
+    kind: Kind,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Grid {
+        cols: usize,
+        rows: usize,
+        vectors: Vec<Vec2>,
+    },
+    Function(Arc<dyn Fn(Point2) -> Vec2 + Send + Sync>),
+}
+
+impl VectorField2 {
+    /// A grid of `cols` by `rows` zero vectors evenly covering the given bounds.
+    ///
+    /// Each vector lies at the centre of its cell. The number of columns and rows is at least 1.
+    pub fn grid(bounds: Rect, cols: usize, rows: usize) -> Self {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        VectorField2 {
+            bounds,
+            kind: Kind::Grid {
+                cols,
+                rows,
+                vectors: vec![Vec2::ZERO; cols * rows],
+            },
+        }
+    }
+
+    /// A grid of `cols` by `rows` vectors produced by sampling the given function at the centre
+    /// of each cell.
+    pub fn grid_from_fn<F>(bounds: Rect, cols: usize, rows: usize, f: F) -> Self
+    where
+        F: Fn(Point2) -> Vec2,
+    {
+        let mut field = Self::grid(bounds, cols, rows);
+        field.set_each(|p, _| f(p));
+        field
+    }
+
+    /// A field that evaluates the given function each time it is sampled.
+    ///
+    /// The bounds are used when drawing the field and to limit the length of streamlines.
+    pub fn from_fn<F>(bounds: Rect, f: F) -> Self
+    where
+        F: 'static + Fn(Point2) -> Vec2 + Send + Sync,
+    {
+        VectorField2 {
+            bounds,
+            kind: Kind::Function(Arc::new(f)),
+        }
+    }
+
+    /// A divergence-free field produced by taking the curl of the given scalar potential.
+    ///
+    /// Particles advected through such a field swirl without bunching up or spreading apart. This
+    /// is commonly used with a noise function as the potential, known as "curl noise".
+    pub fn curl_of<F>(bounds: Rect, potential: F) -> Self
+    where
+        F: 'static + Fn(Point2) -> f32 + Send + Sync,
+    {
+        let h = finite_difference_step(bounds);
+        Self::from_fn(bounds, move |p| {
+            let dx = (potential(p + pt2(h, 0.0)) - potential(p - pt2(h, 0.0))) / (2.0 * h);
+            let dy = (potential(p + pt2(0.0, h)) - potential(p - pt2(0.0, h))) / (2.0 * h);
+            Vec2::new(dy, -dx)
+        })
+    }
+
+    /// The bounds of the field.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// The number of columns and rows if the field is a grid.
+    pub fn resolution(&self) -> Option<[usize; 2]> {
+        match self.kind {
+            Kind::Grid { cols, rows, .. } => Some([cols, rows]),
+            Kind::Function(_) => None,
+        }
+    }
+
+    /// The centre of the cell at the given column and row of a grid, counting from the bottom
+    /// left.
+    ///
+    /// Returns `None` if the field is not a grid.
+    pub fn cell_position(&self, col: usize, row: usize) -> Option<Point2> {
+        let [cols, rows] = self.resolution()?;
+        Some(cell_position(self.bounds, cols, rows, col, row))
+    }
+
+    /// The vector at the given column and row of a grid.
+    ///
+    /// Returns `None` if the field is not a grid or the cell is out of range.
+    pub fn get(&self, col: usize, row: usize) -> Option<Vec2> {
+        match self.kind {
+            Kind::Grid {
+                cols,
+                rows,
+                ref vectors,
+            } if col < cols && row < rows => Some(vectors[row * cols + col]),
+            _ => None,
+        }
+    }
+
+    /// Set the vector at the given column and row of a grid.
+    ///
+    /// Does nothing if the field is not a grid or the cell is out of range.
+    pub fn set(&mut self, col: usize, row: usize, vector: Vec2) {
+        if let Kind::Grid {
+            cols,
+            rows,
+            ref mut vectors,
+        } = self.kind
+        {
+            if col < cols && row < rows {
+                vectors[row * cols + col] = vector;
+            }
+        }
+    }
+
+    /// Update each vector of a grid with the given function of the cell's position and current
+    /// vector.
+    ///
+    /// Does nothing if the field is not a grid.
+    pub fn set_each<F>(&mut self, mut f: F)
+    where
+        F: FnMut(Point2, Vec2) -> Vec2,
+    {
+        let bounds = self.bounds;
+        if let Kind::Grid {
+            cols,
+            rows,
+            ref mut vectors,
+        } = self.kind
+        {
+            for row in 0..rows {
+                for col in 0..cols {
+                    let v = &mut vectors[row * cols + col];
+                    *v = f(cell_position(bounds, cols, rows, col, row), *v);
+                }
+            }
+        }
+    }
+
+    /// The vector at the given point.
+    ///
+    /// Grids are bilinearly interpolated between the centres of the surrounding cells. Points
+    /// outside the grid take the value of the nearest edge.
+    pub fn sample(&self, point: Point2) -> Vec2 {
+        match self.kind {
+            Kind::Function(ref f) => f(point),
+            Kind::Grid {
+                cols,
+                rows,
+                ref vectors,
+            } => {
+                let cell = cell_size(self.bounds, cols, rows);
+                let offset = point - self.bounds.bottom_left();
+                let gx = (offset.x / cell.x - 0.5).clamp(0.0, (cols - 1) as f32);
+                let gy = (offset.y / cell.y - 0.5).clamp(0.0, (rows - 1) as f32);
+                // Handle non-finite coordinates, e.g. from a zero-sized bounds.
+                let gx = if gx.is_finite() { gx } else { 0.0 };
+                let gy = if gy.is_finite() { gy } else { 0.0 };
+                let (x0, y0) = (gx.floor() as usize, gy.floor() as usize);
+                let (x1, y1) = ((x0 + 1).min(cols - 1), (y0 + 1).min(rows - 1));
+                let (tx, ty) = (gx - x0 as f32, gy - y0 as f32);
+                let at = |x: usize, y: usize| vectors[y * cols + x];
+                let bottom = at(x0, y0).lerp(at(x1, y0), tx);
+                let top = at(x0, y1).lerp(at(x1, y1), tx);
+                bottom.lerp(top, ty)
+            }
+        }
+    }
+
+    /// The curl of the field at the given point, i.e. how strongly it rotates counter-clockwise.
+    pub fn curl(&self, point: Point2) -> f32 {
+        let (dx, dy) = self.partial_derivatives(point);
+        dx.y - dy.x
+    }
+
+    /// The divergence of the field at the given point, i.e. how strongly it flows outwards.
+    pub fn divergence(&self, point: Point2) -> f32 {
+        let (dx, dy) = self.partial_derivatives(point);
+        dx.x + dy.y
+    }
+
+    /// Move the given point through the field over the given time step.
+    ///
+    /// This uses the midpoint method, which follows curved flows more closely than simply adding
+    /// the vector at the point.
+    pub fn advect(&self, point: Point2, dt: f32) -> Point2 {
+        let mid = point + self.sample(point) * dt * 0.5;
+        point + self.sample(mid) * dt
+    }
+
+    /// Move each of the given points through the field over the given time step.
+    pub fn advect_all(&self, points: &mut [Point2], dt: f32) {
+        for p in points {
+            *p = self.advect(*p, dt);
+        }
+    }
+
+    /// Trace the path of a particle through the field from the given start point.
+    ///
+    /// The particle is advected `steps` times by `dt`. The path ends early if the particle leaves
+    /// the bounds of the field or comes to a stop.
+    pub fn streamline(&self, start: Point2, dt: f32, steps: usize) -> Vec<Point2> {
+        let mut points = Vec::with_capacity(steps + 1);
+        points.push(start);
+        let mut p = start;
+        for _ in 0..steps {
+            let next = self.advect(p, dt);
+            if next == p || !self.bounds.contains(next) {
+                break;
+            }
+            points.push(next);
+            p = next;
+        }
+        points
+    }
+
+    // Approximate the partial derivatives of the field with respect to x and y.
+    fn partial_derivatives(&self, point: Point2) -> (Vec2, Vec2) {
+        let h = match self.kind {
+            Kind::Grid { cols, rows, .. } => cell_size(self.bounds, cols, rows).min_element(),
+            Kind::Function(_) => finite_difference_step(self.bounds),
+        };
+        let h = if h > 0.0 { h } else { 1e-3 };
+        let dx = (self.sample(point + pt2(h, 0.0)) - self.sample(point - pt2(h, 0.0))) / (2.0 * h);
+        let dy = (self.sample(point + pt2(0.0, h)) - self.sample(point - pt2(0.0, h))) / (2.0 * h);
+        (dx, dy)
+    }
+}
+
+impl fmt::Debug for VectorField2 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("VectorField2");
+        s.field("bounds", &self.bounds);
+        match self.kind {
+            Kind::Grid { cols, rows, .. } => s.field("resolution", &[cols, rows]),
+            Kind::Function(_) => s.field("function", &".."),
+        };
+        s.finish()
+    }
+}
+
+fn cell_size(bounds: Rect, cols: usize, rows: usize) -> Vec2 {
+    Vec2::new(bounds.w() / cols as f32, bounds.h() / rows as f32)
+}
+
+fn cell_position(bounds: Rect, cols: usize, rows: usize, col: usize, row: usize) -> Point2 {
+    let cell = cell_size(bounds, cols, rows);
+    bounds.bottom_left() + Vec2::new(col as f32 + 0.5, row as f32 + 0.5) * cell
+}
+
+// A step size for finite differences of a function field, relative to the size of its bounds.
+fn finite_difference_step(bounds: Rect) -> f32 {
+    let h = bounds.w().min(bounds.h()) * 1e-3;
+    if h > 0.0 {
+        h
+    } else {
+        1e-3
+    }
+}
@@ -0,0 +1,162 @@
+//! Reloading data files that drive a sketch whenever they change on disk.
+//!
+//! Generative data pieces often involve a lot of iteration on the data itself. Watching a file via
+//! `Builder::watch` calls the given function with the file's contents once at startup and again
+//! each time the file is modified, so the data may be cleaned up and re-exported while the sketch
+//! keeps running.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::watch::Data;
+//!
+//! struct Model {
+//!     points: Vec<Vec2>,
+//! }
+//!
+//! fn main() {
+//!     nannou::app(model)
+//!         .watch("data/points.csv", load_points)
+//!         .simple_window(view)
+//!         .run();
+//! }
+//!
+//! fn model(_app: &App) -> Model {
+//!     Model { points: vec![] }
+//! }
+//!
+//! fn load_points(_app: &App, model: &mut Model, data: &Data) {
+//!     let rows = match data.rows() {
+//!         Ok(rows) => rows,
+//!         Err(err) => return eprintln!("failed to read {}: {}", data.path().display(), err),
+//!     };
+//!     model.points = rows
+//!         .iter()
+//!         .filter_map(|row| Some(vec2(row.get(0)?.parse().ok()?, row.get(1)?.parse().ok()?)))
+//!         .collect();
+//! }
+//!
+//! fn view(app: &App, model: &Model, frame: Frame) {
+//!     let draw = app.draw();
+//!     draw.background().color(BLACK);
+//!     for &p in &model.points {
+//!         draw.ellipse().xy(p).radius(2.0).color(WHITE);
+//!     }
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//! ```
+
+use crate::app::{App, WatchFn};
+use instant::Instant;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{fs, str};
+
+/// The interval at which watched files are checked for modifications.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The contents of a watched file.
+#[derive(Clone, Debug)]
+pub struct Data {
+    path: PathBuf,
+    bytes: Vec<u8>,
+}
+
+// A file being watched along with the function to call when it changes.
+pub(crate) struct Watcher<M> {
+    path: PathBuf,
+    watch: WatchFn<M>,
+    modified: Option<SystemTime>,
+    last_poll: Option<Instant>,
+}
+
+impl Data {
+    /// The path of the watched file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The raw contents of the file.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The contents of the file as text.
+    pub fn text(&self) -> Result<&str, str::Utf8Error> {
+        str::from_utf8(&self.bytes)
+    }
+
+    /// The non-empty lines of the file with surrounding whitespace trimmed.
+    pub fn lines(&self) -> Result<Vec<&str>, str::Utf8Error> {
+        let lines = self
+            .text()?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        Ok(lines)
+    }
+
+    /// The non-empty lines of the file split into trimmed, comma-separated fields.
+    ///
+    /// This is a minimal CSV reader suitable for numeric data. Quoted fields are not supported.
+    pub fn rows(&self) -> Result<Vec<Vec<&str>>, str::Utf8Error> {
+        let rows = self
+            .lines()?
+            .into_iter()
+            .map(|line| line.split(',').map(str::trim).collect())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Deserialize the contents of the file from JSON.
+    pub fn json<T>(&self) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(&self.bytes)
+    }
+}
+
+impl<M> Watcher<M> {
+    pub(crate) fn new(path: PathBuf, watch: WatchFn<M>) -> Self {
+        Watcher {
+            path,
+            watch,
+            modified: None,
+            last_poll: None,
+        }
+    }
+
+    // Reload the file and call the user's function if it was modified since the last poll.
+    //
+    // Returns whether or not the function was called.
+    pub(crate) fn poll(&mut self, app: &App, model: &mut M, now: Instant) -> bool {
+        if let Some(last_poll) = self.last_poll {
+            if now.duration_since(last_poll) < POLL_INTERVAL {
+                return false;
+            }
+        }
+        self.last_poll = Some(now);
+
+        // The file may be missing temporarily while being saved, so wait for it to reappear.
+        let modified = match fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return false,
+        };
+        if self.modified == Some(modified) {
+            return false;
+        }
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(_) => return false,
+        };
+        self.modified = Some(modified);
+        let data = Data {
+            path: self.path.clone(),
+            bytes,
+        };
+        (self.watch)(app, model, &data);
+        true
+    }
+}
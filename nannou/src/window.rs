@@ -4,16 +4,21 @@
 //! which can be used to build a [**Window**](./struct.Window.html).
 
 use crate::color::IntoLinSrgba;
+use crate::display;
 use crate::event::{
-    Key, MouseButton, MouseScrollDelta, TouchEvent, TouchPhase, TouchpadPressure, WindowEvent,
+    Key, MouseButton, MouseScrollDelta, PenEvent, TouchEvent, TouchPhase, TouchpadPressure,
+    WindowEvent,
 };
-use crate::frame::{self, Frame, RawFrame};
+use crate::frame::{self, ColorVision, Frame, FrameTextures, RawFrame};
 use crate::geom;
 use crate::geom::Point2;
-use crate::glam::Vec2;
+use crate::glam::{Mat4, Vec2};
+use crate::performance;
 use crate::wgpu;
 use crate::App;
 use std::any::Any;
+use std::cell::Cell;
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -53,12 +58,18 @@ pub struct Builder<'app> {
     max_capture_frame_jobs: u32,
     capture_frame_timeout: Option<Duration>,
     clear_color: Option<wgpu::Color>,
+    color_vision: ColorVision,
+    camera: Mat4,
+    letterbox: Option<f32>,
+    performance_role: Option<performance::Role>,
 }
 
 /// For storing all user functions within the window.
 #[derive(Debug, Default)]
 pub(crate) struct UserFunctions {
     pub(crate) view: Option<View>,
+    pub(crate) before_render: Option<RenderHookFnAny>,
+    pub(crate) after_render: Option<RenderHookFnAny>,
     pub(crate) event: Option<EventFnAny>,
     pub(crate) raw_event: Option<RawEventFnAny>,
     pub(crate) key_pressed: Option<KeyPressedFnAny>,
@@ -73,10 +84,13 @@ pub(crate) struct UserFunctions {
     pub(crate) moved: Option<MovedFnAny>,
     pub(crate) resized: Option<ResizedFnAny>,
     pub(crate) touch: Option<TouchFnAny>,
+    pub(crate) pen: Option<PenFnAny>,
     pub(crate) touchpad_pressure: Option<TouchpadPressureFnAny>,
     pub(crate) hovered_file: Option<HoveredFileFnAny>,
     pub(crate) hovered_file_cancelled: Option<HoveredFileCancelledFnAny>,
     pub(crate) dropped_file: Option<DroppedFileFnAny>,
+    pub(crate) hovered_file_at: Option<HoveredFileAtFnAny>,
+    pub(crate) dropped_file_at: Option<DroppedFileAtFnAny>,
     pub(crate) focused: Option<FocusedFnAny>,
     pub(crate) unfocused: Option<UnfocusedFnAny>,
     pub(crate) closed: Option<ClosedFnAny>,
@@ -91,6 +105,11 @@ pub type ViewFn<Model> = fn(&App, &Model, Frame);
 /// texture rather than to a convenient intermediary image.
 pub type RawViewFn<Model> = fn(&App, &Model, RawFrame);
 
+/// A function called immediately before or after a window's view function.
+///
+/// See the `before_render` and `after_render` builder methods.
+pub type RenderHookFn<Model> = fn(&App, &Model, &FrameTextures);
+
 /// The same as `ViewFn`, but provides no user model to draw from.
 ///
 /// Useful for simple, stateless sketching.
@@ -146,6 +165,9 @@ pub type ResizedFn<Model> = fn(&App, &mut Model, Vec2);
 /// A function for processing touch events.
 pub type TouchFn<Model> = fn(&App, &mut Model, TouchEvent);
 
+/// A function for processing pen events.
+pub type PenFn<Model> = fn(&App, &mut Model, PenEvent);
+
 /// A function for processing touchpad pressure events.
 pub type TouchpadPressureFn<Model> = fn(&App, &mut Model, TouchpadPressure);
 
@@ -158,6 +180,12 @@ pub type HoveredFileCancelledFn<Model> = fn(&App, &mut Model);
 /// A function for processing dropped file events.
 pub type DroppedFileFn<Model> = fn(&App, &mut Model, PathBuf);
 
+/// A function for processing hovered file events along with the position of the mouse.
+pub type HoveredFileAtFn<Model> = fn(&App, &mut Model, PathBuf, Point2);
+
+/// A function for processing dropped file events along with the position of the mouse.
+pub type DroppedFileAtFn<Model> = fn(&App, &mut Model, PathBuf, Point2);
+
 /// A function for processing window focused events.
 pub type FocusedFn<Model> = fn(&App, &mut Model);
 
@@ -167,10 +195,25 @@ pub type UnfocusedFn<Model> = fn(&App, &mut Model);
 /// A function for processing window closed events.
 pub type ClosedFn<Model> = fn(&App, &mut Model);
 
+/// Information about the GPU used by a window.
+#[derive(Clone, Debug)]
+pub struct GpuInfo {
+    /// The adapter's name, vendor, device type, driver and backend.
+    pub adapter: wgpu::AdapterInfo,
+    /// The features enabled for the window's device.
+    pub features: wgpu::Features,
+    /// The limits of the window's device.
+    pub limits: wgpu::Limits,
+}
+
 /// Errors that might occur while building the window.
 #[derive(Debug)]
 pub enum BuildError {
     NoAvailableAdapter,
+    /// The adapter does not support the given required device features.
+    UnsupportedFeatures(wgpu::Features),
+    /// The adapter does not support the required device limits.
+    UnsupportedLimits,
     WinitOsError(winit::error::OsError),
 }
 
@@ -213,6 +256,7 @@ macro_rules! fn_any {
 
 fn_any!(ViewFn<M>, ViewFnAny);
 fn_any!(RawViewFn<M>, RawViewFnAny);
+fn_any!(RenderHookFn<M>, RenderHookFnAny);
 fn_any!(EventFn<M>, EventFnAny);
 fn_any!(RawEventFn<M>, RawEventFnAny);
 fn_any!(KeyPressedFn<M>, KeyPressedFnAny);
@@ -227,10 +271,13 @@ fn_any!(MouseWheelFn<M>, MouseWheelFnAny);
 fn_any!(MovedFn<M>, MovedFnAny);
 fn_any!(ResizedFn<M>, ResizedFnAny);
 fn_any!(TouchFn<M>, TouchFnAny);
+fn_any!(PenFn<M>, PenFnAny);
 fn_any!(TouchpadPressureFn<M>, TouchpadPressureFnAny);
 fn_any!(HoveredFileFn<M>, HoveredFileFnAny);
 fn_any!(HoveredFileCancelledFn<M>, HoveredFileCancelledFnAny);
 fn_any!(DroppedFileFn<M>, DroppedFileFnAny);
+fn_any!(HoveredFileAtFn<M>, HoveredFileAtFnAny);
+fn_any!(DroppedFileAtFn<M>, DroppedFileAtFnAny);
 fn_any!(FocusedFn<M>, FocusedFnAny);
 fn_any!(UnfocusedFn<M>, UnfocusedFnAny);
 fn_any!(ClosedFn<M>, ClosedFnAny);
@@ -252,6 +299,14 @@ pub struct Window {
     pub(crate) tracked_state: TrackedState,
     pub(crate) is_invalidated: bool, // Whether framebuffer must be cleared
     pub(crate) clear_color: wgpu::Color,
+    camera: Cell<Mat4>,
+    letterbox: Cell<Option<f32>>,
+    performance_role: Option<performance::Role>,
+    present_modes: Vec<wgpu::PresentMode>,
+    max_frame_latency: Option<u32>,
+    adapter_info: wgpu::AdapterInfo,
+    // Submissions for frames that may still be in flight, used to enforce `max_frame_latency`.
+    frames_in_flight: VecDeque<wgpu::SubmissionIndex>,
 }
 
 // Data related to `Frame`s produced for this window's surface textures.
@@ -282,6 +337,7 @@ pub struct SurfaceConfigurationBuilder {
     pub usage: Option<wgpu::TextureUsages>,
     pub format: Option<wgpu::TextureFormat>,
     pub present_mode: Option<wgpu::PresentMode>,
+    pub desired_maximum_frame_latency: Option<u32>,
 }
 
 impl SurfaceConfigurationBuilder {
@@ -324,6 +380,18 @@ impl SurfaceConfigurationBuilder {
         self
     }
 
+    /// The maximum number of frames that may be queued for presentation at once.
+    ///
+    /// Lower values reduce the latency between input and display at the cost of throughput, as
+    /// nannou will wait for the GPU to finish the oldest frame before beginning the next one. A
+    /// value of `1` ensures each frame has been processed before the next is requested.
+    ///
+    /// By default, this is unspecified and frames are queued as the graphics backend sees fit.
+    pub fn desired_maximum_frame_latency(mut self, frames: u32) -> Self {
+        self.desired_maximum_frame_latency = Some(frames);
+        self
+    }
+
     /// Build the surface configuration.
     pub(crate) fn build(
         self,
@@ -332,17 +400,19 @@ impl SurfaceConfigurationBuilder {
         [width_px, height_px]: [u32; 2],
     ) -> wgpu::SurfaceConfiguration {
         let usage = self.usage.unwrap_or(Self::DEFAULT_USAGE);
-        let format = self
-            .format
-            .or_else(|| {
-                surface
-                    .get_capabilities(&adapter)
-                    .formats
-                    .get(0)
-                    .map(|x| x.clone())
+        // Fall back to the preferred format if the requested one is not supported by the surface.
+        let supported = surface.get_capabilities(&adapter).formats;
+        let format = match self.format {
+            Some(format) if supported.is_empty() || supported.contains(&format) => format,
+            _ => supported.first().cloned().unwrap_or(Self::DEFAULT_FORMAT),
+        };
+        let present_mode = self
+            .present_mode
+            .map(|mode| {
+                let supported = surface.get_capabilities(adapter).present_modes;
+                supported_present_mode(&supported, mode)
             })
-            .unwrap_or(Self::DEFAULT_FORMAT);
-        let present_mode = self.present_mode.unwrap_or(Self::DEFAULT_PRESENT_MODE);
+            .unwrap_or(Self::DEFAULT_PRESENT_MODE);
         wgpu::SurfaceConfiguration {
             usage,
             format,
@@ -355,6 +425,27 @@ impl SurfaceConfigurationBuilder {
     }
 }
 
+// Fall back to the default present mode if the requested one is not supported by the surface.
+//
+// wgpu will panic if a surface is configured with an unsupported present mode.
+fn supported_present_mode(
+    supported: &[wgpu::PresentMode],
+    mode: wgpu::PresentMode,
+) -> wgpu::PresentMode {
+    match mode {
+        wgpu::PresentMode::AutoVsync | wgpu::PresentMode::AutoNoVsync => mode,
+        _ if supported.contains(&mode) => mode,
+        _ => {
+            eprintln!(
+                "present mode {:?} is unsupported by the surface, falling back to {:?}",
+                mode,
+                SurfaceConfigurationBuilder::DEFAULT_PRESENT_MODE,
+            );
+            SurfaceConfigurationBuilder::DEFAULT_PRESENT_MODE
+        }
+    }
+}
+
 impl<'app> Builder<'app> {
     /// The default power preference used to request the WGPU adapter.
     pub const DEFAULT_POWER_PREFERENCE: wgpu::PowerPreference = wgpu::DEFAULT_POWER_PREFERENCE;
@@ -368,7 +459,7 @@ impl<'app> Builder<'app> {
             window: winit::window::WindowBuilder::new(),
             title_was_set: false,
             surface_conf_builder: Default::default(),
-            power_preference: Self::DEFAULT_POWER_PREFERENCE,
+            power_preference: app.power_preference(),
             force_fallback_adapter: Self::DEFAULT_FORCE_FALLBACK_ADAPTER,
             device_desc: None,
             user_functions: Default::default(),
@@ -376,6 +467,10 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs: Default::default(),
             capture_frame_timeout: Default::default(),
             clear_color: None,
+            color_vision: ColorVision::Normal,
+            camera: Mat4::IDENTITY,
+            letterbox: None,
+            performance_role: None,
         }
     }
 
@@ -396,7 +491,9 @@ impl<'app> Builder<'app> {
 
     /// Specify the power preference desired for the WGPU adapter.
     ///
-    /// By default, this is `wgpu::PowerPreference::HighPerformance`.
+    /// By default, the app's power preference is used. See `app::Builder::power_preference`.
+    ///
+    /// **Note:** This is ignored if the app selects its adapter via `app::Builder::adapter`.
     pub fn power_preference(mut self, pref: wgpu::PowerPreference) -> Self {
         self.power_preference = pref;
         self
@@ -413,6 +510,8 @@ impl<'app> Builder<'app> {
 
     /// Specify a device descriptor to use when requesting the logical device from the adapter.
     /// This allows for specifying custom wgpu device extensions.
+    ///
+    /// By default, the app's device descriptor is used. See `app::Builder::device_descriptor`.
     pub fn device_descriptor(mut self, device_desc: wgpu::DeviceDescriptor<'static>) -> Self {
         self.device_desc = Some(device_desc);
         self
@@ -443,6 +542,38 @@ impl<'app> Builder<'app> {
         self
     }
 
+    /// The way in which the window's frames are presented to the display.
+    ///
+    /// E.g. `Fifo` waits for vertical sync, `Mailbox` replaces any queued frame with the newest
+    /// and `Immediate` presents frames as soon as they are ready, possibly resulting in tearing.
+    /// If the given mode is not supported by the window's surface, `Fifo` is used instead.
+    ///
+    /// This is short-hand for the `SurfaceConfigurationBuilder::present_mode` method.
+    pub fn present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.surface_conf_builder = self.surface_conf_builder.present_mode(present_mode);
+        self
+    }
+
+    /// The maximum number of frames that may be queued for presentation at once.
+    ///
+    /// This is short-hand for the `SurfaceConfigurationBuilder::desired_maximum_frame_latency`
+    /// method.
+    pub fn desired_maximum_frame_latency(mut self, frames: u32) -> Self {
+        self.surface_conf_builder = self
+            .surface_conf_builder
+            .desired_maximum_frame_latency(frames);
+        self
+    }
+
+    /// Simulate the given color vision deficiency when presenting the window's frames.
+    ///
+    /// Only the image displayed within the window is affected. Captured frames are unaffected.
+    /// By default, this is `ColorVision::Normal`. See `Window::set_color_vision`.
+    pub fn color_vision(mut self, color_vision: ColorVision) -> Self {
+        self.color_vision = color_vision;
+        self
+    }
+
     /// Provide a simple function for drawing to the window.
     ///
     /// This is similar to `view` but does not provide access to user data via a Model type. This
@@ -476,6 +607,35 @@ impl<'app> Builder<'app> {
         self
     }
 
+    /// A function called each frame immediately before the window's view function.
+    ///
+    /// The hook is called after the frame's textures have been cleared if the window was
+    /// invalidated, but before any of the view's commands have been submitted. This is a
+    /// well-defined point for uploading data that the view depends upon, or for signalling the
+    /// start of a frame to external systems.
+    pub fn before_render<M>(mut self, hook_fn: RenderHookFn<M>) -> Self
+    where
+        M: 'static,
+    {
+        self.user_functions.before_render = Some(RenderHookFnAny::from_fn_ptr(hook_fn));
+        self
+    }
+
+    /// A function called each frame immediately after the window's view function.
+    ///
+    /// By the time the hook is called, the frame's commands have been submitted to the queue and
+    /// the intermediary texture holds the resolved result of the view, though the frame has not
+    /// yet been presented to the display. This is a well-defined point for custom capture,
+    /// reading back the frame for analysis, or signalling external systems that the frame is
+    /// complete.
+    pub fn after_render<M>(mut self, hook_fn: RenderHookFn<M>) -> Self
+    where
+        M: 'static,
+    {
+        self.user_functions.after_render = Some(RenderHookFnAny::from_fn_ptr(hook_fn));
+        self
+    }
+
     /// Set the initial color of the window background
     /// when its contents are invalidated, e.g. upon window resize.
     pub fn clear_color<C>(mut self, color: C) -> Self
@@ -490,6 +650,32 @@ impl<'app> Builder<'app> {
         self
     }
 
+    /// Specify the initial camera transform for the window.
+    ///
+    /// The camera is applied to the **Draw** produced by `App::draw_for_window`, allowing each
+    /// window to provide a different view onto the same model. See `Window::set_camera`.
+    pub fn camera(mut self, camera: Mat4) -> Self {
+        self.camera = camera;
+        self
+    }
+
+    /// Letterbox the window to the given aspect ratio (width / height), e.g. `16.0 / 9.0`.
+    ///
+    /// See `Window::set_letterbox` for details.
+    pub fn letterbox(mut self, aspect: f32) -> Self {
+        self.letterbox = Some(aspect);
+        self
+    }
+
+    /// Specify the part that the window plays when switching between the editor and performance
+    /// modes of the sketch.
+    ///
+    /// See the `performance` module for details.
+    pub fn performance_role(mut self, role: performance::Role) -> Self {
+        self.performance_role = Some(role);
+        self
+    }
+
     /// A function for updating your model on `WindowEvent`s associated with this window.
     ///
     /// These include events such as key presses, mouse movement, clicks, resizing, etc.
@@ -621,6 +807,18 @@ impl<'app> Builder<'app> {
         self
     }
 
+    /// A function for processing pen events associated with this window.
+    ///
+    /// Pen events are produced for touches that report pressure, in addition to the regular touch
+    /// events. See `PenEvent` for details.
+    pub fn pen<M>(mut self, f: PenFn<M>) -> Self
+    where
+        M: 'static,
+    {
+        self.user_functions.pen = Some(PenFnAny::from_fn_ptr(f));
+        self
+    }
+
     /// A function for processing touchpad pressure events associated with this window.
     pub fn touchpad_pressure<M>(mut self, f: TouchpadPressureFn<M>) -> Self
     where
@@ -676,6 +874,32 @@ impl<'app> Builder<'app> {
         self
     }
 
+    /// A function for processing hovered file events associated with this window, along with the
+    /// position of the mouse relative to the centre of the window.
+    ///
+    /// Some platforms do not report mouse movement while a file is being dragged, in which case
+    /// the position is the last known position of the mouse within the window.
+    pub fn hovered_file_at<M>(mut self, f: HoveredFileAtFn<M>) -> Self
+    where
+        M: 'static,
+    {
+        self.user_functions.hovered_file_at = Some(HoveredFileAtFnAny::from_fn_ptr(f));
+        self
+    }
+
+    /// A function for processing dropped file events associated with this window, along with the
+    /// position of the mouse relative to the centre of the window.
+    ///
+    /// This is useful for placing dropped images, SVGs or audio files where they were dropped.
+    /// See `hovered_file_at` for notes on the accuracy of the position.
+    pub fn dropped_file_at<M>(mut self, f: DroppedFileAtFn<M>) -> Self
+    where
+        M: 'static,
+    {
+        self.user_functions.dropped_file_at = Some(DroppedFileAtFnAny::from_fn_ptr(f));
+        self
+    }
+
     /// A function for processing the focused event associated with this window.
     pub fn focused<M>(mut self, f: FocusedFn<M>) -> Self
     where
@@ -760,6 +984,10 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs,
             capture_frame_timeout,
             clear_color,
+            color_vision,
+            camera,
+            letterbox,
+            performance_role,
         } = self;
 
         // If the title was not set, default to the "nannou - <exe_name>".
@@ -926,20 +1154,46 @@ impl<'app> Builder<'app> {
             compatible_surface: Some(&surface),
             force_fallback_adapter,
         };
-        let adapter = app
-            .wgpu_adapters()
-            .get_or_request_async(request_adapter_opts, app.instance())
-            .await
-            .ok_or(BuildError::NoAvailableAdapter)?;
+        let adapter = match app.adapter_fn() {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(select) => app.wgpu_adapters().get_or_select(
+                app.backends(),
+                Some(&surface),
+                app.instance(),
+                select,
+            ),
+            _ => {
+                app.wgpu_adapters()
+                    .get_or_request_async(request_adapter_opts, app.instance())
+                    .await
+            }
+        }
+        .ok_or(BuildError::NoAvailableAdapter)?;
+        let adapter_info = adapter.get_info();
 
         // Instantiate the logical device.
-        let device_desc = device_desc.unwrap_or_else(wgpu::default_device_descriptor);
+        let mut device_desc = device_desc.unwrap_or_else(|| app.device_descriptor());
+        // Fall back to reduced limits on downlevel adapters (e.g. GL ES) unless limits were given.
+        if device_desc.limits == wgpu::Limits::default()
+            && !device_desc.limits.check_limits(&adapter.limits())
+        {
+            device_desc.limits = wgpu::fallback_limits(&adapter);
+        }
+        let missing_features = device_desc.features - adapter.features();
+        if !missing_features.is_empty() {
+            return Err(BuildError::UnsupportedFeatures(missing_features));
+        }
+        if !device_desc.limits.check_limits(&adapter.limits()) {
+            return Err(BuildError::UnsupportedLimits);
+        }
         let device_queue_pair = adapter.get_or_request_device_async(device_desc).await;
 
         // Configure the surface.
         let win_physical_size = window.inner_size();
         let win_dims_px: [u32; 2] = win_physical_size.into();
         let device = device_queue_pair.device();
+        let present_modes = surface.get_capabilities(&adapter).present_modes;
+        let max_frame_latency = surface_conf_builder.desired_maximum_frame_latency;
         let surface_conf = surface_conf_builder.build(&surface, &*adapter, win_dims_px);
         surface.configure(&device, &surface_conf);
 
@@ -948,13 +1202,16 @@ impl<'app> Builder<'app> {
         let (frame_data, msaa_samples) = match user_functions.view {
             Some(View::WithModel(_)) | Some(View::Sketch(_)) | None => {
                 let msaa_samples = msaa_samples.unwrap_or(Frame::DEFAULT_MSAA_SAMPLES);
-                // TODO: Verity that requested sample count is valid for surface?
+                let (texture_format, msaa_samples) =
+                    frame::supported_texture_format(&adapter, msaa_samples);
                 let surface_dims = [surface_conf.width, surface_conf.height];
                 let render = frame::RenderData::new(
                     &device,
                     surface_dims,
+                    texture_format,
                     surface_conf.format,
                     msaa_samples,
+                    color_vision,
                 );
                 let capture =
                     frame::CaptureData::new(max_capture_frame_jobs, capture_frame_timeout);
@@ -984,6 +1241,13 @@ impl<'app> Builder<'app> {
             tracked_state,
             is_invalidated,
             clear_color,
+            camera: Cell::new(camera),
+            letterbox: Cell::new(letterbox),
+            performance_role,
+            present_modes,
+            max_frame_latency,
+            adapter_info,
+            frames_in_flight: VecDeque::new(),
         };
         app.windows.borrow_mut().insert(window_id, window);
 
@@ -1012,6 +1276,10 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs,
             capture_frame_timeout,
             clear_color,
+            color_vision,
+            camera,
+            letterbox,
+            performance_role,
         } = self;
         let window = map(window);
         Builder {
@@ -1027,6 +1295,10 @@ impl<'app> Builder<'app> {
             max_capture_frame_jobs,
             capture_frame_timeout,
             clear_color,
+            color_vision,
+            camera,
+            letterbox,
+            performance_role,
         }
     }
 
@@ -1061,6 +1333,14 @@ impl<'app> Builder<'app> {
         self.map_window(|w| w.with_inner_size(winit::dpi::PhysicalSize { width, height }))
     }
 
+    /// Requests the window's top-left hand corner to be at the given position in pixels relative
+    /// to the top-left hand corner of the desktop.
+    ///
+    /// See `Window::outer_position_pixels` for more information about the desktop coordinates.
+    pub fn position_pixels(self, x: i32, y: i32) -> Self {
+        self.map_window(|w| w.with_position(winit::dpi::PhysicalPosition { x, y }))
+    }
+
     /// Whether or not the window should be resizable after creation.
     pub fn resizable(self, resizable: bool) -> Self {
         self.map_window(|w| w.with_resizable(resizable))
@@ -1081,6 +1361,47 @@ impl<'app> Builder<'app> {
         self.fullscreen_with(Some(fullscreen))
     }
 
+    /// Create the window as a borderless fullscreen window on the display with the given **Id**.
+    ///
+    /// See `App::displays` for the list of available displays. If no display exists for the given
+    /// **Id**, the window is made fullscreen on the primary monitor instead.
+    pub fn fullscreen_on(self, display: display::Id) -> Self {
+        let monitor = match self.app.display(display) {
+            Some(display) => Some(display.monitor().clone()),
+            None => {
+                eprintln!(
+                    "no display for {:?}, falling back to the primary monitor",
+                    display
+                );
+                self.app.primary_monitor()
+            }
+        };
+        self.fullscreen_with(Some(Fullscreen::Borderless(monitor)))
+    }
+
+    /// Create a single borderless window covering the bounding area of all connected displays.
+    ///
+    /// This is useful for multi-projector installations where the platform allows a window to
+    /// extend across several monitors. See `App::span_all_monitors` for an alternative that
+    /// creates a fullscreen window per display. If no displays can be detected, this has no
+    /// effect.
+    pub fn span_all_displays(self) -> Self {
+        let displays = self.app.displays();
+        let bounds = match displays.first() {
+            None => return self,
+            Some(first) => displays
+                .iter()
+                .skip(1)
+                .fold(first.rect_pixels(), |bounds, d| bounds.max(d.rect_pixels())),
+        };
+        let (x, y) = (bounds.left().round() as i32, bounds.bottom().round() as i32);
+        let (w, h) = (bounds.w().round() as u32, bounds.h().round() as u32);
+        self.decorations(false)
+            .resizable(false)
+            .position_pixels(x, y)
+            .size_pixels(w, h)
+    }
+
     /// Set the window fullscreen state with the given settings.
     ///
     /// - `None` indicates a normal window. This is the default case.
@@ -1489,6 +1810,30 @@ impl Window {
         &self.device_queue_pair
     }
 
+    /// Information about the GPU used by the window, including its name, backend, features and
+    /// limits.
+    pub fn gpu_info(&self) -> GpuInfo {
+        let device = self.device_queue_pair.device();
+        GpuInfo {
+            adapter: self.adapter_info.clone(),
+            features: device.features(),
+            limits: device.limits(),
+        }
+    }
+
+    /// The format of the intermediary texture to which the `view` function's `Frame`s are drawn.
+    ///
+    /// This is `Frame::TEXTURE_FORMAT` unless the adapter cannot render to it, in which case it
+    /// is `Frame::FALLBACK_TEXTURE_FORMAT`. Custom render pipelines targeting the frame should use
+    /// this format in order to support such adapters.
+    ///
+    /// Returns `None` if the window uses a **raw_view** function.
+    pub fn frame_texture_format(&self) -> Option<wgpu::TextureFormat> {
+        self.frame_data
+            .as_ref()
+            .map(|data| data.render.texture_format())
+    }
+
     /// The number of samples used in the MSAA for the image associated with the `view` function's
     /// `Frame` type.
     ///
@@ -1498,8 +1843,87 @@ impl Window {
         self.msaa_samples
     }
 
+    /// The color vision deficiency simulated when presenting the window's frames.
+    pub fn color_vision(&self) -> ColorVision {
+        self.frame_data
+            .as_ref()
+            .map(|data| data.render.color_vision.get())
+            .unwrap_or_default()
+    }
+
+    /// Simulate the given color vision deficiency when presenting the window's frames.
+    ///
+    /// This is useful for checking the accessibility of a palette live, e.g. by cycling through
+    /// modes on a key press via `ColorVision::next`. Only the image displayed within the window is
+    /// affected - frames captured via `capture_frame` remain unmodified.
+    ///
+    /// **Note:** This has no effect for windows that use a `raw_view` function.
+    pub fn set_color_vision(&self, color_vision: ColorVision) {
+        if let Some(data) = self.frame_data.as_ref() {
+            data.render.color_vision.set(color_vision);
+        }
+    }
+
+    /// The way in which the window's frames are presented to the display.
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.surface_conf.present_mode
+    }
+
+    /// Change the way in which the window's frames are presented to the display.
+    ///
+    /// This reconfigures the window's surface. If the given mode is not supported by the surface,
+    /// `Fifo` is used instead.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        self.surface_conf.present_mode = supported_present_mode(&self.present_modes, present_mode);
+        self.reconfigure_surface(self.tracked_state.physical_size.into());
+    }
+
+    /// The maximum number of frames that may be queued for presentation at once, if specified.
+    pub fn desired_maximum_frame_latency(&self) -> Option<u32> {
+        self.max_frame_latency
+    }
+
+    /// Specify the maximum number of frames that may be queued for presentation at once.
+    ///
+    /// `None` leaves frame queueing to the graphics backend.
+    pub fn set_desired_maximum_frame_latency(&mut self, frames: Option<u32>) {
+        self.max_frame_latency = frames;
+        if frames.is_none() {
+            self.frames_in_flight.clear();
+        }
+    }
+
     // Custom methods.
 
+    // Track the most recent submission for the window's frame, blocking on the oldest frame in
+    // flight if the desired maximum frame latency has been exceeded.
+    pub(crate) fn throttle_frame_latency(&mut self) {
+        let max = match self.max_frame_latency {
+            None => return,
+            Some(max) => max.max(1) as usize,
+        };
+        let index = self.queue().submit(std::iter::empty());
+        self.frames_in_flight.push_back(index);
+        while self.frames_in_flight.len() > max {
+            let index = self.frames_in_flight.pop_front().unwrap();
+            self.device()
+                .poll(wgpu::Maintain::WaitForSubmissionIndex(index));
+        }
+    }
+
+    // Recreate the surface for the window's native window, e.g. after the app is resumed on mobile
+    // platforms where the surface is destroyed upon suspending.
+    pub(crate) fn recreate_surface(&mut self, instance: &wgpu::Instance) {
+        self.surface = unsafe {
+            instance
+                .create_surface(&self.window)
+                .expect("Could not create surface")
+        };
+        self.tracked_state.physical_size = self.window.inner_size();
+        self.reconfigure_surface(self.tracked_state.physical_size.into());
+        self.is_invalidated = true;
+    }
+
     // A utility function to simplify the reconfiguration of the window's wgpu surface.
     //
     // Upon resizing of the window, the window's surface needs to be reconfigured to match.
@@ -1511,12 +1935,14 @@ impl Window {
         self.surface_conf.width = self.tracked_state.physical_size.width;
         self.surface_conf.height = self.tracked_state.physical_size.height;
         self.surface.configure(self.device(), &self.surface_conf);
-        if self.frame_data.is_some() {
+        if let Some(texture_format) = self.frame_texture_format() {
             let render_data = frame::RenderData::new(
                 self.device(),
                 self.tracked_state.physical_size.into(),
+                texture_format,
                 self.surface_conf.format,
                 self.msaa_samples,
+                self.color_vision(),
             );
             self.frame_data.as_mut().unwrap().render = render_data;
         }
@@ -1548,6 +1974,121 @@ impl Window {
         geom::Rect::from_w_h(w, h)
     }
 
+    /// The area of the window that is not obscured by system UI such as a phone's notch, rounded
+    /// corners or home indicator, described in points relative to the centre of the window.
+    ///
+    /// Interactive elements and important content should be placed within this area.
+    ///
+    /// **Note:** The safe area is only known on iOS. On all other platforms this is equivalent to
+    /// `rect`.
+    pub fn safe_area(&self) -> geom::Rect {
+        #[cfg(target_os = "ios")]
+        {
+            // On iOS, the inner position and size describe the safe area within the screen.
+            let scale = self.tracked_state.scale_factor;
+            if let (Ok(outer), Ok(inner)) =
+                (self.window.outer_position(), self.window.inner_position())
+            {
+                let outer_size = self.window.outer_size().to_logical::<f32>(scale);
+                let inner_size = self.window.inner_size().to_logical::<f32>(scale);
+                let outer = outer.to_logical::<f32>(scale);
+                let inner = inner.to_logical::<f32>(scale);
+                let left = inner.x - outer.x - outer_size.width / 2.0;
+                let top = outer_size.height / 2.0 - (inner.y - outer.y);
+                return geom::Rect::from_corners(
+                    geom::pt2(left, top - inner_size.height),
+                    geom::pt2(left + inner_size.width, top),
+                );
+            }
+        }
+        self.rect()
+    }
+
+    /// The same as `rect`, but with the dimensions described in physical pixels.
+    ///
+    /// This matches the size of the textures that the window's frames are rendered to and may
+    /// differ from `rect` on HiDPI displays.
+    pub fn rect_pixels(&self) -> geom::Rect {
+        let (w, h) = self.inner_size_pixels();
+        geom::Rect::from_w_h(w as f32, h as f32)
+    }
+
+    /// The largest rectangle with the given aspect ratio (width / height) that fits within the
+    /// window, centred on the window.
+    ///
+    /// This is useful for laying out fixed-aspect compositions (e.g. `16.0 / 9.0`) so that they
+    /// behave predictably at arbitrary window or projector resolutions.
+    pub fn fit_rect(&self, aspect: f32) -> geom::Rect {
+        let rect = self.rect();
+        let (w, h) = rect.w_h();
+        if w / h > aspect {
+            geom::Rect::from_w_h(h * aspect, h)
+        } else {
+            geom::Rect::from_w_h(w, w / aspect)
+        }
+    }
+
+    /// The aspect ratio to which the window is letterboxed, if any.
+    pub fn letterbox(&self) -> Option<f32> {
+        self.letterbox.get()
+    }
+
+    /// Letterbox the window to the given aspect ratio (width / height), or `None` to disable
+    /// letterboxing.
+    ///
+    /// While letterboxed:
+    ///
+    /// - The areas of the window outside of the `letterbox_rect` are covered with black bars each
+    ///   time a **Draw** is rendered to one of the window's frames via `Draw::to_frame`.
+    /// - The positions of mouse and touch input are clamped to the `letterbox_rect`, both within
+    ///   the `App`'s mouse state and the window's events.
+    pub fn set_letterbox(&self, aspect: Option<f32>) {
+        self.letterbox.set(aspect);
+    }
+
+    /// The area of the window that is visible while letterboxed.
+    ///
+    /// This is equal to `fit_rect` for the letterbox aspect ratio, or `rect` if the window is not
+    /// letterboxed.
+    pub fn letterbox_rect(&self) -> geom::Rect {
+        match self.letterbox() {
+            Some(aspect) => self.fit_rect(aspect),
+            None => self.rect(),
+        }
+    }
+
+    /// The part that the window plays when switching between the editor and performance modes of
+    /// the sketch, if any.
+    pub fn performance_role(&self) -> Option<performance::Role> {
+        self.performance_role
+    }
+
+    // Clamp the given position to the letterboxed area of the window.
+    pub(crate) fn clamp_to_letterbox(&self, p: Point2) -> Point2 {
+        match self.letterbox() {
+            Some(_) => {
+                let rect = self.letterbox_rect();
+                p.clamp(rect.bottom_left(), rect.top_right())
+            }
+            None => p,
+        }
+    }
+
+    /// The camera transform applied to the **Draw** produced by `App::draw_for_window`.
+    ///
+    /// By default, this is the identity transform.
+    pub fn camera(&self) -> Mat4 {
+        self.camera.get()
+    }
+
+    /// Specify the camera transform applied to the **Draw** produced by `App::draw_for_window`.
+    ///
+    /// E.g. `Mat4::from_scale(Vec3::splat(2.0))` zooms the window's view of the model by a factor
+    /// of two.
+    pub fn set_camera(&self, camera: Mat4) {
+        self.camera.set(camera);
+    }
+
     /// Capture the next frame right before it is drawn to this window and write it to an image
     /// file at the given path. If a frame already exists, it will be captured before its `submit`
     /// method is called or before it is `drop`ped.
@@ -1631,6 +2172,16 @@ impl fmt::Display for BuildError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             BuildError::NoAvailableAdapter => write!(f, "no available wgpu adapter detected"),
+            BuildError::UnsupportedFeatures(features) => {
+                write!(
+                    f,
+                    "the wgpu adapter does not support features {:?}",
+                    features
+                )
+            }
+            BuildError::UnsupportedLimits => {
+                write!(f, "the wgpu adapter does not support the required limits")
+            }
             BuildError::WinitOsError(ref e) => e.fmt(f),
         }
     }
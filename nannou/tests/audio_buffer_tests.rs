@@ -0,0 +1,58 @@
+use nannou::audio_buffer::AudioBuffer;
+
+// Produce the bytes of a WAV file containing the given interleaved 16-bit samples.
+fn wav_i16(sample_rate: u32, channels: u16, samples: &[i16]) -> Vec<u8> {
+    let data_len = samples.len() as u32 * 2;
+    let mut bytes = vec![];
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+    bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        bytes.extend_from_slice(&s.to_le_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn test_decode_wav_i16_stereo() {
+    let bytes = wav_i16(44_100, 2, &[0, 16_384, -32_768, 0, 16_384, -16_384]);
+    let buffer = AudioBuffer::from_wav_bytes(&bytes).unwrap();
+    assert_eq!(buffer.sample_rate(), 44_100);
+    assert_eq!(buffer.channel_count(), 2);
+    assert_eq!(buffer.len_frames(), 3);
+    assert_eq!(buffer.channel(0).unwrap(), &[0.0, -1.0, 0.5]);
+    assert_eq!(buffer.channel(1).unwrap(), &[0.5, 0.0, -0.5]);
+    assert_eq!(buffer.sample(None, 0), 0.25);
+    assert!(AudioBuffer::from_wav_bytes(b"RIFF....WAVX").is_err());
+}
+
+#[test]
+fn test_spectrum_peak() {
+    let sample_rate = 8_000;
+    let freq = 1_000.0;
+    let samples: Vec<f32> = (0..4_096)
+        .map(|i| (std::f32::consts::TAU * freq * i as f32 / sample_rate as f32).sin())
+        .collect();
+    let buffer = AudioBuffer::from_interleaved(sample_rate, 1, &samples);
+    let spectrum = buffer.spectrum(None, 2_048, 1_024);
+    assert_eq!(spectrum.len(), 513);
+    let (peak, magnitude) =
+        spectrum
+            .iter()
+            .cloned()
+            .enumerate()
+            .fold((0, 0.0), |a, b| if b.1 > a.1 { b } else { a });
+    // Each bin spans sample_rate / window_size = 7.8125Hz.
+    assert_eq!(peak, 128);
+    assert!((magnitude - 1.0).abs() < 0.05);
+}
@@ -0,0 +1,61 @@
+use nannou::config::{self, Standard};
+use serde_derive::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default)]
+struct Config {
+    #[serde(flatten)]
+    standard: Standard,
+    particles: usize,
+    title: String,
+}
+
+#[test]
+fn test_config_from_args() {
+    let args = [
+        "--size",
+        "800,600",
+        "--fullscreen",
+        "--seed=42",
+        "--output",
+        "out/",
+    ];
+    let (config, standard) = config::from_args::<Config, _>(args.iter().cloned()).unwrap();
+    let expected = Standard {
+        size: Some([800, 600]),
+        fullscreen: true,
+        seed: Some(42),
+        output: Some(PathBuf::from("out/")),
+    };
+    assert_eq!(standard, expected);
+    assert_eq!(config.standard, expected);
+    assert_eq!(config.particles, 0);
+}
+
+#[test]
+fn test_config_file_overridden_by_args() {
+    let path = std::env::temp_dir().join("nannou_config_tests.toml");
+    std::fs::write(
+        &path,
+        "particles = 100\ntitle = \"gallery\"\nsize = [640, 480]\n",
+    )
+    .unwrap();
+    let path_arg = path.to_str().unwrap().to_string();
+    let args = vec![
+        "--config".to_string(),
+        path_arg,
+        "--particles=5".to_string(),
+    ];
+    let (config, standard) = config::from_args::<Config, _>(args).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(config.particles, 5);
+    assert_eq!(config.title, "gallery");
+    assert_eq!(standard.size, Some([640, 480]));
+}
+
+#[test]
+fn test_config_unexpected_arg() {
+    let result = config::from_args::<Config, _>(vec!["positional"]);
+    assert!(matches!(result, Err(config::Error::Args(_))));
+}
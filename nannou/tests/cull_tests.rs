@@ -0,0 +1,113 @@
+#![cfg(feature = "headless")]
+
+use nannou::draw::{self, Draw};
+use nannou::geom::Rect;
+use nannou::glam::Mat4;
+use nannou::prelude::*;
+
+// The area of the frame to which the draws are culled.
+fn frame_rect() -> Rect {
+    Rect::from_w_h(200.0, 200.0)
+}
+
+fn vertex_count(draw: &Draw) -> usize {
+    draw::render_visible_to_mesh(draw, frame_rect()).vertex_count()
+}
+
+#[test]
+fn test_offscreen_primitives_are_culled() {
+    let draw = Draw::new();
+    let culled = draw.cull();
+    culled.rect().x_y(500.0, 0.0).w_h(50.0, 50.0);
+    culled
+        .line()
+        .start(pt2(0.0, 500.0))
+        .end(pt2(50.0, 500.0))
+        .weight(4.0);
+    culled.ellipse().x_y(-500.0, -500.0).w_h(50.0, 50.0);
+    assert_eq!(vertex_count(&draw), 0);
+    // Primitives are only culled when a visible area is given.
+    assert!(draw::render_to_mesh(&draw).vertex_count() > 0);
+}
+
+#[test]
+fn test_onscreen_primitives_are_kept() {
+    let draw = Draw::new();
+    draw.cull().rect().w_h(50.0, 50.0);
+    assert!(vertex_count(&draw) > 0);
+}
+
+#[test]
+fn test_primitives_outside_scissor_are_culled() {
+    let draw = Draw::new();
+    let scissored = draw
+        .cull()
+        .scissor(Rect::from_x_y_w_h(-50.0, 0.0, 100.0, 200.0));
+    // Within the frame but entirely outside of the scissor.
+    scissored.rect().x_y(60.0, 0.0).w_h(20.0, 20.0);
+    assert_eq!(vertex_count(&draw), 0);
+    // Partially within the scissor, to be clipped by it.
+    scissored.rect().x_y(0.0, 0.0).w_h(20.0, 20.0);
+    assert!(vertex_count(&draw) > 0);
+}
+
+#[test]
+fn test_strokes_reaching_onscreen_are_kept() {
+    // The fill lies just outside of the frame.
+    let draw = Draw::new();
+    draw.cull().rect().x_y(130.0, 0.0).w_h(50.0, 50.0);
+    assert_eq!(vertex_count(&draw), 0);
+    // The stroke extends beyond the fill into the frame.
+    let draw = Draw::new();
+    draw.cull()
+        .rect()
+        .x_y(130.0, 0.0)
+        .w_h(50.0, 50.0)
+        .no_fill()
+        .stroke(WHITE)
+        .stroke_weight(40.0);
+    assert!(vertex_count(&draw) > 0);
+}
+
+#[test]
+fn test_rotated_primitives_reaching_onscreen_are_kept() {
+    // A thin bar lying above the frame.
+    let draw = Draw::new();
+    draw.cull().rect().x_y(0.0, 150.0).w_h(300.0, 10.0);
+    assert_eq!(vertex_count(&draw), 0);
+    // Rotated about its centre, the bar extends down into the frame.
+    let draw = Draw::new();
+    draw.cull()
+        .rect()
+        .x_y(0.0, 150.0)
+        .w_h(300.0, 10.0)
+        .rotate(PI / 2.0);
+    assert!(vertex_count(&draw) > 0);
+    // Likewise when the rotation is applied by the draw's transform.
+    let draw = Draw::new();
+    draw.cull()
+        .rotate(PI / 2.0)
+        .rect()
+        .x_y(150.0, 0.0)
+        .w_h(10.0, 300.0);
+    assert_eq!(vertex_count(&draw), 0);
+    draw.cull()
+        .rotate(PI / 2.0)
+        .rect()
+        .x_y(0.0, 150.0)
+        .w_h(10.0, 300.0);
+    assert!(vertex_count(&draw) > 0);
+}
+
+#[test]
+fn test_perspective_primitives_are_kept() {
+    // Points on the plane of the eye are left to the rasterizer to clip.
+    let draw = Draw::new();
+    let perspective = Mat4::perspective_rh(PI / 2.0, 1.0, 0.1, 100.0);
+    draw.cull()
+        .transform(perspective)
+        .rect()
+        .x_y(500.0, 0.0)
+        .w_h(50.0, 50.0);
+    assert!(vertex_count(&draw) > 0);
+}
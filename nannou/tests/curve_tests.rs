@@ -0,0 +1,25 @@
+use nannou::curve::{Curve, Editor};
+use nannou::geom::{vec2, Rect};
+
+#[test]
+fn test_editor_empty_curve() {
+    let mut editor = Editor::new(Rect::from_w_h(100.0, 100.0));
+    let mut curve = Curve::new(Vec::<[f32; 2]>::new());
+    assert!(!editor.update_mouse(vec2(0.0, 0.0), true, false, &mut curve));
+    assert!(!editor.update_mouse(vec2(10.0, 10.0), false, true, &mut curve));
+    assert!(curve.points().is_empty());
+}
+
+#[test]
+fn test_editor_stale_indices() {
+    let mut editor = Editor::new(Rect::from_w_h(100.0, 100.0));
+    let mut curve = Curve::ease_in_out();
+    // Begin dragging the last control point, at the top right of the editor.
+    editor.update_mouse(vec2(50.0, 50.0), true, false, &mut curve);
+    assert!(editor.is_active());
+    // Replace the curve with one that has fewer points while dragging.
+    curve = Curve::new([[0.0, 0.0], [1.0, 1.0]]);
+    editor.update_mouse(vec2(40.0, 40.0), true, false, &mut curve);
+    editor.update_mouse(vec2(40.0, 40.0), false, true, &mut curve);
+    assert_eq!(curve.points().len(), 2);
+}
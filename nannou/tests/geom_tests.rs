@@ -13,3 +13,235 @@ fn angle_test() {
     let vector = vec2(70.7, -60.8);
     assert_eq!(vector.angle(), -0.7102547457375739);
 }
+
+#[test]
+fn hex_test() {
+    use nannou::geom::hex::{Layout, DIRECTIONS};
+    use nannou::geom::Hex;
+
+    let centre = Hex::new(2, -1);
+    for radius in 0..4 {
+        let ring: Vec<_> = centre.ring(radius).collect();
+        assert_eq!(ring.len(), (radius as usize * 6).max(1));
+        assert!(ring.iter().all(|&h| h.distance(centre) == radius));
+        let range: Vec<_> = centre.range(radius).collect();
+        let r = radius as usize;
+        assert_eq!(range.len(), 3 * r * (r + 1) + 1);
+        assert!(range.iter().all(|&h| h.distance(centre) <= radius));
+    }
+    assert!(centre.neighbors().all(|h| h.distance(centre) == 1));
+    assert_eq!(
+        DIRECTIONS.iter().fold(Hex::ORIGIN, |a, &b| a + b),
+        Hex::ORIGIN
+    );
+
+    for layout in [
+        Layout::pointy(10.0),
+        Layout::flat(10.0).origin(pt2(3.0, -7.0)),
+    ] {
+        for hex in Hex::ORIGIN.range(3) {
+            let p = layout.to_point(hex);
+            assert_eq!(layout.to_hex(p), hex);
+            for corner in layout.corners(hex).iter() {
+                assert!((corner.distance(p) - 10.0).abs() < 1e-4);
+                assert_eq!(layout.to_hex(p + (*corner - p) * 0.9), hex);
+            }
+        }
+    }
+}
+
+#[test]
+fn intersect_test() {
+    use nannou::geom::intersect::{self, Ray};
+    use nannou::geom::{Cuboid, Rect};
+
+    // The nearest face of a cube is hit.
+    let cube = Cuboid::from_xyz_whd(Vec3::ZERO, Vec3::splat(2.0));
+    let ray = Ray::new(vec3(0.5, 0.25, 10.0), vec3(0.0, 0.0, -2.0));
+    let (_, hit) = ray.mesh(cube.triangles_iter()).unwrap();
+    assert_eq!(hit.t, 4.5);
+    assert_eq!(hit.point, vec3(0.5, 0.25, 1.0));
+    assert_eq!(hit.normal, vec3(0.0, 0.0, 1.0));
+    assert!(Ray::new(vec3(0.0, 0.0, 10.0), Vec3::Z)
+        .mesh(cube.triangles_iter())
+        .is_none());
+
+    // Segments.
+    let a = [pt2(0.0, 0.0), pt2(2.0, 2.0)];
+    assert_eq!(
+        intersect::segment_segment(a, [pt2(0.0, 2.0), pt2(2.0, 0.0)]),
+        Some(pt2(1.0, 1.0))
+    );
+    assert_eq!(
+        intersect::segment_segment(a, [pt2(1.0, 1.0), pt2(3.0, 3.0)]),
+        Some(pt2(1.0, 1.0))
+    );
+    assert!(intersect::segment_segment(a, [pt2(3.0, 0.0), pt2(0.0, 3.0)]).is_some());
+    assert_eq!(
+        intersect::segment_segment(a, [pt2(1.0, 0.0), pt2(3.0, 2.0)]),
+        None
+    );
+
+    // Rects are pushed apart along the axis of least overlap.
+    let a = Rect::from_x_y_w_h(0.0, 0.0, 10.0, 10.0);
+    let b = Rect::from_x_y_w_h(8.0, 1.0, 10.0, 10.0);
+    let contact = intersect::rect_rect(a, b).unwrap();
+    assert_eq!(contact.normal, vec2(1.0, 0.0));
+    assert_eq!(contact.depth, 2.0);
+    assert_eq!(contact.point, pt2(4.0, 0.5));
+    assert!(intersect::rect_rect(a, Rect::from_x_y_w_h(20.0, 0.0, 5.0, 5.0)).is_none());
+
+    // Circles.
+    let contact = intersect::circle_circle(pt2(0.0, 0.0), 2.0, pt2(0.0, 3.0), 2.0).unwrap();
+    assert_eq!(contact.normal, vec2(0.0, 1.0));
+    assert_eq!(contact.depth, 1.0);
+    assert_eq!(contact.point, pt2(0.0, 1.5));
+    assert!(intersect::circle_circle(pt2(0.0, 0.0), 1.0, pt2(3.0, 0.0), 1.0).is_none());
+
+    // A circle touching a rect from above, and one whose centre lies within it.
+    let contact = intersect::rect_circle(a, pt2(0.0, 7.0), 3.0).unwrap();
+    assert_eq!(contact.normal, vec2(0.0, 1.0));
+    assert_eq!(contact.depth, 1.0);
+    let contact = intersect::rect_circle(a, pt2(-4.0, 0.0), 2.0).unwrap();
+    assert_eq!(contact.normal, vec2(-1.0, 0.0));
+    assert_eq!(contact.depth, 3.0);
+
+    // Convex polygons agree with the equivalent rects.
+    let corners = |r: Rect| {
+        [
+            r.bottom_left(),
+            r.bottom_right(),
+            r.top_right(),
+            r.top_left(),
+        ]
+    };
+    let contact = intersect::polygon_polygon(&corners(a), &corners(b)).unwrap();
+    assert_eq!(contact.normal, vec2(1.0, 0.0));
+    assert_eq!(contact.depth, 2.0);
+    let c = Rect::from_x_y_w_h(11.0, 0.0, 1.0, 1.0);
+    assert!(intersect::polygon_polygon(&corners(a), &corners(c)).is_none());
+    let contact = intersect::circle_polygon(pt2(0.0, 7.0), 3.0, &corners(a)).unwrap();
+    assert_eq!(contact.normal, vec2(0.0, -1.0));
+    assert_eq!(contact.depth, 1.0);
+}
+
+#[test]
+fn spatial_query_test() {
+    use nannou::geom::{QuadTree, Rect, SpatialHash};
+
+    // Compare the results of each structure against testing every point.
+    let mut points = vec![];
+    let mut seed = 1u32;
+    let mut rand = || {
+        seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        (seed >> 8) as f32 / (1 << 24) as f32 * 200.0 - 100.0
+    };
+    for i in 0..1000 {
+        points.push((pt2(rand(), rand() * 0.1), i));
+    }
+    let mut hash = SpatialHash::new(7.0);
+    hash.extend(points.iter().cloned());
+    let mut tree = QuadTree::with_capacity(Rect::from_w_h(200.0, 200.0), 4);
+    for &(p, i) in &points {
+        tree.insert(p, i).unwrap();
+    }
+    assert_eq!(hash.len(), points.len());
+    assert_eq!(tree.len(), points.len());
+
+    for _ in 0..100 {
+        let (centre, radius) = (pt2(rand(), rand()), rand().abs() * 0.2);
+        let mut expected: Vec<_> = points
+            .iter()
+            .filter(|(p, _)| p.distance(centre) <= radius)
+            .map(|&(_, i)| i)
+            .collect();
+        expected.sort();
+        let mut from_hash: Vec<_> = hash.query_radius(centre, radius).map(|(_, &i)| i).collect();
+        from_hash.sort();
+        let mut from_tree: Vec<_> = tree.query_radius(centre, radius).map(|(_, &i)| i).collect();
+        from_tree.sort();
+        assert_eq!(from_hash, expected);
+        assert_eq!(from_tree, expected);
+
+        let nearest = points
+            .iter()
+            .map(|&(p, _)| p.distance(centre))
+            .fold(f32::MAX, f32::min);
+        assert_eq!(hash.nearest(centre).unwrap().0.distance(centre), nearest);
+        assert_eq!(tree.nearest(centre).unwrap().0.distance(centre), nearest);
+    }
+
+    hash.clear();
+    tree.clear();
+    assert!(hash.nearest(pt2(0.0, 0.0)).is_none());
+    assert!(tree.nearest(pt2(0.0, 0.0)).is_none());
+    assert_eq!(hash.query_radius(pt2(0.0, 0.0), 1e9).count(), 0);
+}
+
+#[test]
+fn marching_squares_test() {
+    use nannou::geom::{marching_squares, ScalarGrid};
+
+    // Two separate metaballs produce two closed contours.
+    let bounds = geom::Rect::from_w_h(200.0, 100.0);
+    let ball = |p: Point2, c: Point2| 100.0 / p.distance_squared(c).max(1.0);
+    let (a, b) = (pt2(-50.0, 0.0), pt2(50.0, 0.0));
+    let grid = ScalarGrid::from_fn(bounds, 81, 41, |p| ball(p, a) + ball(p, b));
+    let mut contours = marching_squares(&grid, 1.0);
+    assert_eq!(contours.len(), 2);
+    contours.sort_by(|a, b| a.points[0].x.partial_cmp(&b.points[0].x).unwrap());
+    for (contour, centre) in contours.iter().zip([a, b]) {
+        assert!(contour.closed);
+        for p in &contour.points {
+            assert!((p.distance(centre) - 10.0).abs() < 0.5);
+        }
+    }
+
+    // A ramp crossing the grid produces a single open line with its ends on the edges.
+    let grid = ScalarGrid::from_fn(bounds, 11, 11, |p| p.x);
+    let contours = marching_squares(&grid, 25.0);
+    assert_eq!(contours.len(), 1);
+    assert!(!contours[0].closed);
+    assert_eq!(contours[0].points.len(), 11);
+    assert!(contours[0].points.iter().all(|p| (p.x - 25.0).abs() < 1e-3));
+    let smoothed = contours[0].smooth(1);
+    assert_eq!(smoothed.points.first(), contours[0].points.first());
+    assert_eq!(smoothed.points.last(), contours[0].points.last());
+
+    // Thresholds beyond the range of the field produce no contours.
+    assert!(marching_squares(&grid, 1000.0).is_empty());
+    assert!(marching_squares(&grid, -1000.0).is_empty());
+}
+
+#[test]
+fn marching_cubes_test() {
+    use nannou::geom::{marching_cubes, Cuboid, ScalarGrid3};
+    use std::collections::HashMap;
+
+    let bounds = Cuboid::from_x_y_z_w_h_d(0.0, 0.0, 0.0, 200.0, 200.0, 200.0);
+    let grid = ScalarGrid3::from_fn(bounds, 21, 21, 21, |p| 50.0 - p.length());
+    let surface = marching_cubes(&grid, 0.0);
+    assert_eq!(surface.points.len(), surface.normals.len());
+    assert!(!surface.indices.is_empty());
+
+    // The surface is closed, with every edge shared by exactly two triangles.
+    let mut edges = HashMap::new();
+    for tri in surface.indices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (tri[i], tri[(i + 1) % 3]);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+
+    // Triangles are wound counter-clockwise when viewed from outside the sphere. Those touching
+    // grid points that lie exactly on the surface may be degenerate.
+    for tri in surface.indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| surface.points[tri[i]]);
+        assert!((b - a).cross(c - a).dot(a + b + c) >= 0.0);
+    }
+
+    // A field entirely inside or outside produces no surface.
+    assert!(marching_cubes(&grid, 1000.0).indices.is_empty());
+    assert!(marching_cubes(&grid, -1000.0).indices.is_empty());
+}
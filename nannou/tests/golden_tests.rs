@@ -0,0 +1,108 @@
+use nannou::golden::{self, Harness, Tolerance};
+use nannou::image::{Rgba, RgbaImage};
+use nannou::prelude::*;
+
+fn checkerboard() -> RgbaImage {
+    RgbaImage::from_fn(16, 16, |x, y| match (x + y) % 2 == 0 {
+        true => Rgba([255, 255, 255, 255]),
+        false => Rgba([0, 0, 0, 255]),
+    })
+}
+
+#[test]
+fn test_compare_identical() {
+    let image = checkerboard();
+    let comparison = golden::compare(&image, &image, &Tolerance::exact()).unwrap();
+    assert_eq!(comparison.differing_pixels, 0);
+    assert_eq!(comparison.max_difference, 0.0);
+    assert!(comparison.passes());
+}
+
+#[test]
+fn test_compare_within_tolerance() {
+    // A barely perceptible change to every pixel.
+    let expected = checkerboard();
+    let mut actual = expected.clone();
+    for p in actual.pixels_mut() {
+        p[0] = p[0].saturating_sub(1);
+    }
+    let comparison = golden::compare(&actual, &expected, &Tolerance::default()).unwrap();
+    assert!(comparison.max_difference > 0.0);
+    assert!(comparison.passes());
+    let comparison = golden::compare(&actual, &expected, &Tolerance::exact()).unwrap();
+    assert!(!comparison.passes());
+}
+
+#[test]
+fn test_compare_beyond_tolerance() {
+    let expected = checkerboard();
+    let mut actual = expected.clone();
+    for x in 0..4 {
+        actual.put_pixel(x, 0, Rgba([255, 0, 0, 255]));
+    }
+    let comparison = golden::compare(&actual, &expected, &Tolerance::default()).unwrap();
+    assert_eq!(comparison.differing_pixels, 4);
+    assert!(!comparison.passes());
+    assert_eq!(comparison.diff.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+    assert_ne!(comparison.diff.get_pixel(0, 1), &Rgba([255, 0, 0, 255]));
+}
+
+#[test]
+fn test_compare_size_mismatch() {
+    let small = RgbaImage::new(8, 8);
+    let result = golden::compare(&small, &checkerboard(), &Tolerance::default());
+    assert!(matches!(result, Err(golden::Error::SizeMismatch { .. })));
+}
+
+#[test]
+fn test_golden_shapes() {
+    let mut harness = match Harness::new([128, 128]) {
+        Ok(harness) => harness,
+        // Skip on machines without a GPU adapter.
+        Err(golden::Error::NoAdapter) => return,
+        Err(err) => panic!("{}", err),
+    };
+    let draw = Draw::new();
+    draw.background().color(WHITE);
+    draw.rect().x_y(-24.0, 24.0).w_h(48.0, 48.0).color(RED);
+    draw.ellipse().x_y(24.0, 24.0).radius(20.0).color(BLUE);
+    draw.tri()
+        .points([-48.0, -48.0], [0.0, -48.0], [-24.0, -8.0])
+        .color(GREEN);
+    draw.line()
+        .start(pt2(8.0, -48.0))
+        .end(pt2(48.0, -8.0))
+        .weight(4.0)
+        .color(BLACK);
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/shapes.png");
+    harness.check(&draw, path).unwrap();
+}
+
+#[test]
+fn test_color_glyphs_render_in_color() {
+    let mut harness = match Harness::new([128, 128]) {
+        Ok(harness) => harness,
+        // Skip on machines without a GPU adapter.
+        Err(golden::Error::NoAdapter) => return,
+        Err(err) => panic!("{}", err),
+    };
+    // U+F0101 maps to a glyph filled with a gradient from red to blue.
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fonts/colr_1.ttf");
+    let font = nannou::text::font::from_file(path).unwrap();
+    let draw = Draw::new();
+    draw.background().color(WHITE);
+    draw.text("\u{F0101}")
+        .font(font)
+        .font_size(64)
+        .w_h(128.0, 128.0)
+        .color(BLACK);
+    let image = harness.render(&draw).unwrap();
+    // The glyph is drawn with its own colors rather than the text color.
+    let red = image
+        .pixels()
+        .any(|px| px[0] > 200 && px[1] < 50 && px[2] < 50);
+    let blue = image
+        .pixels()
+        .any(|px| px[2] > 200 && px[0] < 50 && px[1] < 50);
+    assert!(red && blue);
+}
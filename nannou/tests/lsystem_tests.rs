@@ -0,0 +1,92 @@
+use nannou::geom::pt2;
+use nannou::lsystem::{self, Interpreter, LSystem, Module, ParseError};
+use nannou::rand::rngs::StdRng;
+use nannou::rand::SeedableRng;
+
+#[test]
+fn parse_test() {
+    let modules = lsystem::parse("F(10) +(30, 2.5)[A]").unwrap();
+    let expected = [
+        Module::new('F', &[10.0]),
+        Module::new('+', &[30.0, 2.5]),
+        Module::new('[', &[]),
+        Module::new('A', &[]),
+        Module::new(']', &[]),
+    ];
+    assert_eq!(modules, expected);
+    let s: String = modules.iter().map(|m| m.to_string()).collect();
+    assert_eq!(s, "F(10)+(30,2.5)[A]");
+    assert!(matches!(
+        lsystem::parse("F(10"),
+        Err(ParseError::UnclosedParams('F'))
+    ));
+    assert!(matches!(
+        lsystem::parse("F(x)"),
+        Err(ParseError::InvalidParam('F', _))
+    ));
+}
+
+#[test]
+fn stochastic_rule_test() {
+    let system = LSystem::new("A")
+        .stochastic_rule('A', 1.0, "B")
+        .stochastic_rule('A', 3.0, "C")
+        .stochastic_rule('A', 0.0, "D");
+    let mut rng = StdRng::seed_from_u64(0);
+    let (mut b, mut c) = (0, 0);
+    for _ in 0..1000 {
+        match system.generate_with(1, &mut rng)[0].symbol {
+            'B' => b += 1,
+            'C' => c += 1,
+            s => panic!("unexpected symbol {}", s),
+        }
+    }
+    assert!(b > 150 && b < 350);
+    assert_eq!(b + c, 1000);
+
+    // The same seed produces the same result.
+    let a = system.generate_with(4, &mut StdRng::seed_from_u64(1));
+    let b = system.generate_with(4, &mut StdRng::seed_from_u64(1));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn parametric_rule_test() {
+    // Each line splits into two shorter lines until they become too short.
+    let system = LSystem::new("F(16)").parametric_rule('F', |p| {
+        let len = p[0];
+        if len < 2.0 {
+            return None;
+        }
+        Some(vec![
+            Module::new('F', &[len / 2.0]),
+            Module::new('F', &[len / 2.0]),
+        ])
+    });
+    let modules = system.generate(10);
+    assert_eq!(modules.len(), 16);
+    assert!(modules.iter().all(|m| m.params == [1.0]));
+}
+
+#[test]
+fn interpreter_test() {
+    // A "Y" shape with two branches.
+    let modules = lsystem::parse("F[+F][-F]fF").unwrap();
+    let lines = Interpreter::new()
+        .step(1.0)
+        .angle_degrees(90.0)
+        .polylines(&modules);
+    let round = |p: nannou::geom::Point2| pt2(p.x.round(), p.y.round());
+    let lines: Vec<Vec<_>> = lines
+        .into_iter()
+        .map(|l| l.into_iter().map(round).collect())
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            vec![pt2(0.0, 0.0), pt2(0.0, 1.0), pt2(-1.0, 1.0)],
+            vec![pt2(0.0, 1.0), pt2(1.0, 1.0)],
+            vec![pt2(0.0, 2.0), pt2(0.0, 3.0)],
+        ]
+    );
+}
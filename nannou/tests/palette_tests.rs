@@ -0,0 +1,38 @@
+use nannou::color::{lin_srgba, Gradient, GradientSpace, LinSrgba, Palette, PaletteGradient};
+use nannou::image::{DynamicImage, Rgba, RgbaImage};
+
+#[test]
+fn test_palette_from_image() {
+    // Three quarters red, one quarter blue.
+    let image = RgbaImage::from_fn(8, 8, |x, _| match x < 6 {
+        true => Rgba([255, 0, 0, 255]),
+        false => Rgba([0, 0, 255, 255]),
+    });
+    let palette = Palette::from_image(&DynamicImage::ImageRgba8(image), 4);
+    assert_eq!(palette.len(), 2);
+    assert_eq!(palette.weights(), &[0.75, 0.25]);
+    let red = lin_srgba(1.0, 0.0, 0.0, 1.0);
+    let [r, g, b] = [palette[0].red - red.red, palette[0].green, palette[0].blue];
+    assert!(r.abs() < 1e-3 && g.abs() < 1e-3 && b.abs() < 1e-3);
+}
+
+#[test]
+fn test_gradient_at() {
+    let black = lin_srgba(0.0, 0.0, 0.0, 1.0);
+    let white = lin_srgba(1.0, 1.0, 1.0, 0.0);
+    let gradient = Gradient::new([(1.0, white), (0.5, black)]).space(GradientSpace::LinearSrgb);
+    assert_eq!(gradient.stops()[0].0, 0.5);
+    assert_eq!(gradient.at(0.0), black);
+    assert_eq!(gradient.at(2.0), white);
+    let mid = gradient.at(0.75);
+    assert!((mid.red - 0.5).abs() < 1e-6 && (mid.alpha - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn test_palette_gradient_get() {
+    let black = lin_srgba(0.0, 0.0, 0.0, 1.0);
+    let white = lin_srgba(1.0, 1.0, 1.0, 1.0);
+    let gradient = PaletteGradient::new(vec![black, white]);
+    let mid: LinSrgba = gradient.get(0.5);
+    assert!((mid.red - 0.5).abs() < 1e-6 && (mid.alpha - 1.0).abs() < 1e-6);
+}
@@ -0,0 +1,22 @@
+use nannou::prelude::*;
+
+#[test]
+fn test_pick_sdf_ellipse() {
+    let draw = Draw::new();
+    draw.ellipse().radius(50.0).sdf().pick_id(1);
+    assert_eq!(draw.pick(pt2(0.0, 0.0)), Some(1));
+    assert_eq!(draw.pick(pt2(45.0, 0.0)), Some(1));
+    // The corner of the bounding box lies outside of the circle.
+    assert_eq!(draw.pick(pt2(45.0, 45.0)), None);
+}
+
+#[test]
+fn test_pick_sdf_rounded_rect() {
+    let draw = Draw::new();
+    draw.rect()
+        .w_h(100.0, 100.0)
+        .sdf_corner_radius(20.0)
+        .pick_id(1);
+    assert_eq!(draw.pick(pt2(0.0, 48.0)), Some(1));
+    assert_eq!(draw.pick(pt2(48.0, 48.0)), None);
+}
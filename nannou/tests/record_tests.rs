@@ -0,0 +1,133 @@
+use nannou::draw::renderer::VertexMode;
+use nannou::draw::{RecordedCommand, RecordedFrame};
+use nannou::golden::{self, Harness, Tolerance};
+use nannou::image::{self, Rgba, RgbaImage};
+use nannou::prelude::*;
+
+const SIZE: [u32; 2] = [128, 128];
+
+// Request a device via the default GPU adapter, or `None` on machines without one.
+fn device_queue() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let options = wgpu::RequestAdapterOptions::default();
+    let adapter = futures::executor::block_on(instance.request_adapter(&options))
+        .or_else(|| instance.enumerate_adapters(wgpu::Backends::all()).next())?;
+    let desc = wgpu::DeviceDescriptor {
+        label: Some("nannou_record_tests"),
+        features: wgpu::Features::empty(),
+        limits: adapter.limits(),
+    };
+    futures::executor::block_on(adapter.request_device(&desc, None)).ok()
+}
+
+// Render the given drawing, then render a replay of its recording and compare the two.
+fn assert_replay_matches(harness: &mut Harness, draw: &Draw) -> RecordedFrame {
+    let frame = RecordedFrame::record(draw);
+    let expected = harness.render(draw).unwrap();
+    let replay = Draw::new();
+    frame.replay(&replay);
+    let actual = harness.render(&replay).unwrap();
+    let comparison = golden::compare(&actual, &expected, &Tolerance::default()).unwrap();
+    assert!(comparison.passes(), "{:?}", comparison.max_difference);
+    frame
+}
+
+// The vertex modes of the recorded meshes.
+fn vertex_modes(frame: &RecordedFrame) -> Vec<VertexMode> {
+    frame
+        .commands
+        .iter()
+        .filter_map(|cmd| match *cmd {
+            RecordedCommand::Mesh { vertex_mode, .. } => Some(vertex_mode),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_record_sdf_ellipse() {
+    let mut harness = match Harness::new(SIZE) {
+        Ok(harness) => harness,
+        // Skip on machines without a GPU adapter.
+        Err(golden::Error::NoAdapter) => return,
+        Err(err) => panic!("{}", err),
+    };
+    let draw = Draw::new();
+    draw.background().color(WHITE);
+    draw.ellipse().w_h(80.0, 50.0).sdf().color(BLUE);
+    let frame = assert_replay_matches(&mut harness, &draw);
+    assert_eq!(vertex_modes(&frame), vec![VertexMode::SdfEllipse]);
+
+    // The SDF params survive serialization.
+    let json = serde_json::to_string(&frame).unwrap();
+    let deserialized: RecordedFrame = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, frame);
+    match deserialized.commands.last() {
+        Some(RecordedCommand::Mesh {
+            points, sdf_params, ..
+        }) => assert_eq!(sdf_params.len(), points.len()),
+        cmd => panic!("expected a mesh, found {:?}", cmd),
+    }
+}
+
+#[test]
+fn test_record_textured_quad() {
+    let (device, queue) = match device_queue() {
+        Some(device_queue) => device_queue,
+        None => return,
+    };
+    let checkerboard = RgbaImage::from_fn(8, 8, |x, y| match (x + y) % 2 == 0 {
+        true => Rgba([255, 0, 0, 255]),
+        false => Rgba([0, 0, 255, 255]),
+    });
+    let usage = wgpu::TextureBuilder::default_image_texture_usage();
+    let image = image::DynamicImage::ImageRgba8(checkerboard);
+    let texture = wgpu::Texture::load_from_image(&device, &queue, usage, &image);
+    let mut harness = Harness::from_device(device, queue, SIZE);
+
+    let draw = Draw::new();
+    draw.background().color(WHITE);
+    draw.texture(&texture).w_h(64.0, 64.0);
+    let frame = assert_replay_matches(&mut harness, &draw);
+    assert_eq!(vertex_modes(&frame), vec![VertexMode::Texture]);
+    match frame.commands.last() {
+        Some(RecordedCommand::Mesh {
+            tex_coords,
+            texture,
+            ..
+        }) => {
+            assert!(tex_coords.iter().any(|&t| t != Vec2::ZERO));
+            assert!(texture.0.is_some());
+        }
+        cmd => panic!("expected a mesh, found {:?}", cmd),
+    }
+}
+
+#[test]
+fn test_cached_sdf_ellipse() {
+    let mut harness = match Harness::new(SIZE) {
+        Ok(harness) => harness,
+        // Skip on machines without a GPU adapter.
+        Err(golden::Error::NoAdapter) => return,
+        Err(err) => panic!("{}", err),
+    };
+    let ellipse = |draw: &Draw| {
+        draw.ellipse().w_h(80.0, 50.0).sdf().color(BLUE);
+    };
+    let draw = Draw::new();
+    draw.background().color(WHITE);
+    ellipse(&draw.x(10.0));
+    let expected = harness.render(&draw).unwrap();
+
+    // Render the cached ellipse on both the frame on which it is recorded and a later frame.
+    let draw = Draw::new();
+    for _ in 0..2 {
+        // As with `app.draw()`, the **Draw** is reset at the beginning of each frame.
+        draw.reset();
+        draw.background().color(WHITE);
+        draw.x(10.0).cached("ellipse", ellipse);
+        let actual = harness.render(&draw).unwrap();
+        let comparison = golden::compare(&actual, &expected, &Tolerance::exact()).unwrap();
+        assert!(comparison.passes(), "{:?}", comparison.max_difference);
+    }
+}
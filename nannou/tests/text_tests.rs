@@ -0,0 +1,166 @@
+use nannou::text;
+
+// The smallest x coordinate of all endpoints within the given path.
+fn min_x(path: &nannou::geom::Path) -> f32 {
+    path.iter()
+        .map(|event| event.to().x)
+        .fold(f32::INFINITY, f32::min)
+}
+
+#[test]
+fn glyph_outlines_skip_whitespace() {
+    let font = text::font::default_notosans();
+    let outlines = text::glyph_outlines("a b c", &font, 24);
+    assert_eq!(outlines.len(), 3);
+}
+
+#[test]
+fn glyph_outlines_advance_along_baseline() {
+    let font = text::font::default_notosans();
+    let outlines = text::glyph_outlines("lll", &font, 24);
+    let xs: Vec<f32> = outlines.iter().map(min_x).collect();
+    assert!(xs[0] < xs[1] && xs[1] < xs[2]);
+    let (a, b) = (xs[1] - xs[0], xs[2] - xs[1]);
+    assert!((a - b).abs() < 1e-3);
+}
+
+#[test]
+fn glyph_outlines_are_above_baseline() {
+    let font = text::font::default_notosans();
+    let outline = &text::glyph_outlines("x", &font, 24)[0];
+    assert!(outline.iter().all(|event| event.to().y >= -1e-3));
+    assert!(outline.iter().any(|event| event.to().y > 1.0));
+}
+
+#[test]
+fn font_select_falls_back_for_missing_glyphs() {
+    let font = text::font::default_notosans();
+    let emoji = '\u{1F600}';
+    assert!(text::font::has_glyph(&font, 'a'));
+    assert!(!text::font::has_glyph(&font, emoji));
+    let fallbacks = vec![font.clone()];
+    assert_eq!(text::font::select(&font, &fallbacks, 'a').0, 0);
+    // Characters that no font provides resolve to the primary font.
+    assert_eq!(text::font::select(&font, &fallbacks, emoji).0, 0);
+    assert_eq!(text::font::select(&font, &[], emoji).0, 0);
+}
+
+#[test]
+fn from_system_reports_missing_families() {
+    match text::font::from_system("No Such Font Family") {
+        Err(text::font::Error::NotInstalled(name)) => assert_eq!(name, "No Such Font Family"),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("loaded a font that does not exist"),
+    }
+}
+
+#[test]
+fn full_justify_stretches_wrapped_lines() {
+    use nannou::geom::Rect;
+    let font = text::font::default_notosans();
+    let rect = Rect::from_w_h(120.0, 200.0);
+    let text = text::text("lorem ipsum dolor sit amet consectetur adipiscing elit")
+        .font(font)
+        .font_size(12)
+        .full_justify()
+        .build(rect);
+    let line_rects: Vec<_> = text.line_rects().collect();
+    assert!(line_rects.len() > 1);
+    let (last, wrapped) = line_rects.split_last().unwrap();
+    for r in wrapped {
+        assert!((r.w() - rect.w()).abs() < 1e-3);
+    }
+    assert!(last.w() < rect.w());
+    assert!((last.left() - rect.left()).abs() < 1e-3);
+
+    // The glyphs of the first line are spread across its full width.
+    let first_line: Vec<_> = text.glyphs_per_line().next().unwrap().collect();
+    let (_, last_glyph) = first_line.last().unwrap();
+    assert!(rect.right() - last_glyph.right() < 2.0);
+}
+
+// A COLRv1 test font from https://github.com/googlefonts/color-fonts and a subset of Noto Color
+// Emoji with CBDT bitmaps and no outlines.
+const COLR_FONT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fonts/colr_1.ttf");
+const CBDT_FONT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fonts/NotoColorEmoji-Partial.ttf"
+);
+
+#[test]
+fn color_glyph_rasterizes_colr_gradients() {
+    let font = text::font::from_file(COLR_FONT).unwrap();
+    // Glyph 9 fills a rect with a linear gradient from red to blue.
+    let glyph = text::GlyphId(9);
+    assert!(text::color_glyph::is_color(&font, glyph));
+    let image = text::color_glyph::rasterize(&font, glyph, text::Scale::uniform(64.0)).unwrap();
+    let (w, h) = image.rgba.dimensions();
+    assert!(w > 0 && h > 0);
+    // The glyph sits above the baseline.
+    assert!(image.top < 0);
+    let opaque: Vec<_> = image.rgba.pixels().filter(|px| px[3] == 255).collect();
+    assert!(!opaque.is_empty());
+    assert!(opaque.iter().any(|px| px[0] > 200 && px[2] < 50));
+    assert!(opaque.iter().any(|px| px[2] > 200 && px[0] < 50));
+}
+
+#[test]
+fn color_glyph_rasterizes_bitmaps() {
+    let font = text::font::from_file(CBDT_FONT).unwrap();
+    let glyph = font.glyph('©').id();
+    assert_ne!(glyph, text::GlyphId(0));
+    assert!(text::color_glyph::is_color(&font, glyph));
+    let image = text::color_glyph::rasterize(&font, glyph, text::Scale::uniform(32.0)).unwrap();
+    let (w, h) = image.rgba.dimensions();
+    assert!(w > 16 && w <= 48 && h > 16 && h <= 48);
+    assert!(image.rgba.pixels().any(|px| px[3] > 0));
+    // Glyphs of fonts with outlines are not color glyphs.
+    let notosans = text::font::default_notosans();
+    let a = notosans.glyph('a').id();
+    assert!(!text::color_glyph::is_color(&notosans, a));
+}
+
+#[test]
+fn color_glyph_atlas_caches_glyphs() {
+    let font = text::font::from_file(CBDT_FONT).unwrap();
+    let mut atlas = text::color_glyph::Atlas::new([256; 2]);
+    let scale = text::Scale::uniform(32.0);
+    let glyph = |ch| {
+        font.glyph(ch)
+            .scaled(scale)
+            .positioned(text::rt::point(10.0, 40.0))
+    };
+    let (uv, rect) = atlas.rect_for(&font, &glyph('©')).unwrap();
+    assert!(atlas.requires_upload);
+    assert!(uv.min.x >= 0.0 && uv.max.x <= 1.0 && uv.min.x < uv.max.x);
+    assert!(rect.min.y < 40 && rect.max.x > 10);
+    // Cached glyphs are reused, while new glyphs are packed alongside.
+    atlas.requires_upload = false;
+    assert_eq!(atlas.rect_for(&font, &glyph('©')).unwrap().0, uv);
+    assert!(!atlas.requires_upload);
+    let (uv2, _) = atlas.rect_for(&font, &glyph('®')).unwrap();
+    assert!(uv2.min.x >= uv.max.x);
+    // Glyphs that are not color glyphs are left to the glyph cache.
+    let notosans = text::font::default_notosans();
+    let a = notosans
+        .glyph('a')
+        .scaled(scale)
+        .positioned(text::rt::point(0.0, 0.0));
+    assert!(atlas.rect_for(&notosans, &a).is_none());
+}
+
+#[test]
+fn fallbacks_for_text_without_missing_glyphs_is_the_fallback_chain() {
+    let font = text::font::default_notosans();
+    let fallbacks = text::font::fallbacks_for(&font, "hello");
+    assert!(std::sync::Arc::ptr_eq(&fallbacks, &text::font::fallbacks()));
+}
+
+#[test]
+fn fallbacks_for_missing_glyphs_appends_system_fallbacks() {
+    let font = text::font::default_notosans();
+    let chain = text::font::fallbacks();
+    let fallbacks = text::font::fallbacks_for(&font, "smile \u{1F600}");
+    let system = text::font::system_fallbacks();
+    assert_eq!(fallbacks.len(), chain.len() + system.len());
+}
@@ -0,0 +1,84 @@
+use nannou::timecode::{Chase, FrameRate, MidiClock, Mtc, Timecode};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_midi_clock_tempo_and_position() {
+    let start = Instant::now();
+    // 120 bpm is 48 clocks per second.
+    let interval = Duration::from_secs_f64(1.0 / 48.0);
+    let mut clock = MidiClock::new();
+    assert!(clock.message(&[0xFA], start));
+    for i in 0..=48 {
+        clock.message(&[0xF8], start + interval * i);
+    }
+    assert_eq!(clock.clocks(), 48);
+    assert!((clock.bpm().unwrap() - 120.0).abs() < 1e-3);
+    assert_eq!(clock.beats(start + interval * 48), 2.0);
+    // Interpolation never passes the next expected clock.
+    assert_eq!(clock.beats(start + interval * 60), 2.0 + 1.0 / 24.0);
+    // Song position pointer of one bar, i.e. sixteen sixteenth notes.
+    clock.message(&[0xF2, 16, 0], start);
+    assert_eq!(clock.beats(start), 4.0);
+}
+
+#[test]
+fn test_mtc_quarter_frames() {
+    let start = Instant::now();
+    let mut mtc = Mtc::new();
+    // 00:01:02:03 at 30 fps.
+    let bytes = [0x60, 0x01, 0x02, 0x03];
+    let pieces = [
+        bytes[3] & 0xF,
+        bytes[3] >> 4,
+        bytes[2] & 0xF,
+        bytes[2] >> 4,
+        bytes[1] & 0xF,
+        bytes[1] >> 4,
+        bytes[0] & 0xF,
+        bytes[0] >> 4,
+    ];
+    for (i, piece) in pieces.iter().enumerate() {
+        assert!(mtc.message(&[0xF1, ((i as u8) << 4) | piece], start));
+    }
+    let expected = Timecode {
+        hours: 0,
+        minutes: 1,
+        seconds: 2,
+        frames: 3,
+        rate: FrameRate::Fps30,
+    };
+    assert_eq!(mtc.timecode(), Some(expected));
+    // The complete timecode arrives two frames after the frame it refers to.
+    let seconds = mtc.seconds(start).unwrap();
+    assert!((seconds - (62.0 + 5.0 / 30.0)).abs() < 1e-6);
+    // Without further quarter frames, the position holds.
+    let later = start + Duration::from_secs(1);
+    assert!(!mtc.is_running(later));
+}
+
+#[test]
+fn test_drop_frame_seconds() {
+    let timecode = Timecode {
+        hours: 0,
+        minutes: 1,
+        seconds: 0,
+        frames: 2,
+        rate: FrameRate::Fps30Drop,
+    };
+    // Frames 00 and 01 of the first second of each minute are skipped, except every tenth.
+    assert!((timecode.to_seconds() - 1800.0 * 1.001 / 30.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_chase_corrects_drift_and_jumps() {
+    let start = Instant::now();
+    let second = Duration::from_secs(1);
+    let mut chase = Chase::new();
+    assert_eq!(chase.update(Some(10.0), start), 10.0);
+    // The reference runs slightly fast, so the clock speeds up rather than jumping.
+    assert_eq!(chase.update(Some(11.01), start + second), 11.0);
+    assert!((chase.rate() - 1.01).abs() < 1e-9);
+    // A relocation jumps directly to the reference.
+    assert_eq!(chase.update(Some(60.0), start + second * 2), 60.0);
+    assert_eq!(chase.rate(), 1.0);
+}
@@ -4,7 +4,7 @@
 //! If a desired conversion is missing, feel free to open an issue or pull request!
 
 use crate::color::white_point::D65;
-use crate::color::{self, encoding, Alpha, Component, IntoColor, LinSrgba};
+use crate::color::{self, encoding, Alpha, Component, IntoColor, LinSrgba, Srgba};
 use crate::math::num_traits::Float;
 
 /// Types that may be converted directly into a linear sRGBA color representation.
@@ -20,8 +20,47 @@ where
 {
     /// Convert self into RGBA.
     fn into_lin_srgba(self) -> LinSrgba<S>;
+
+    /// The color space in which the components of `self` are described.
+    ///
+    /// Used to detect likely mix-ups between sRGB and linear sRGB values. Color types that are not
+    /// tied to either encoding return `ColorSpace::Other`.
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Other
+    }
+}
+
+/// The color space in which a color's components are described.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// Gamma encoded sRGB, or a representation derived from it (e.g. HSL, HSV). Colors picked from
+    /// a color picker or image editor, along with the named colors, are typically sRGB.
+    Srgb,
+    /// Linear sRGB, the space in which nannou blends and renders colors.
+    LinearSrgb,
+    /// A space tied to neither encoding, e.g. XYZ or L*a*b*.
+    Other,
 }
 
+/// Explicit conversions between the sRGB and linear sRGB encodings for any color that may be
+/// converted into linear sRGBA.
+///
+/// Where the `Draw` API accepts colors, they are converted via `to_linear`.
+pub trait ColorExt: IntoLinSrgba<f32> + Sized {
+    /// Convert the color to linear sRGBA, the space in which nannou blends and renders colors.
+    fn to_linear(self) -> LinSrgba {
+        self.into_lin_srgba()
+    }
+
+    /// Convert the color to gamma encoded sRGBA, e.g. for display in a color picker or for
+    /// writing to an 8-bit image.
+    fn to_srgb(self) -> Srgba {
+        Srgba::from_linear(self.into_lin_srgba())
+    }
+}
+
+impl<C> ColorExt for C where C: IntoLinSrgba<f32> {}
+
 impl<S> IntoLinSrgba<S> for color::Xyz<D65, S>
 where
     S: Component + Float,
@@ -68,6 +107,10 @@ where
         let alpha = S::max_intensity();
         Alpha { color, alpha }
     }
+
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::LinearSrgb
+    }
 }
 
 impl<T, S> IntoLinSrgba<S> for color::Srgb<T>
@@ -80,6 +123,10 @@ where
         let alpha = S::max_intensity();
         Alpha { color, alpha }
     }
+
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Srgb
+    }
 }
 
 impl<S> IntoLinSrgba<S> for color::Hsl<encoding::Srgb, S>
@@ -89,6 +136,10 @@ where
     fn into_lin_srgba(self) -> LinSrgba<S> {
         into_lin_srgb_with_alpha(self)
     }
+
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Srgb
+    }
 }
 
 impl<S> IntoLinSrgba<S> for color::Hsv<encoding::Srgb, S>
@@ -98,6 +149,10 @@ where
     fn into_lin_srgba(self) -> LinSrgba<S> {
         into_lin_srgb_with_alpha(self)
     }
+
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Srgb
+    }
 }
 
 impl<S> IntoLinSrgba<S> for color::Hwb<encoding::Srgb, S>
@@ -107,6 +162,10 @@ where
     fn into_lin_srgba(self) -> LinSrgba<S> {
         into_lin_srgb_with_alpha(self)
     }
+
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Srgb
+    }
 }
 
 impl<S> IntoLinSrgba<S> for color::SrgbLuma<S>
@@ -116,6 +175,10 @@ where
     fn into_lin_srgba(self) -> LinSrgba<S> {
         into_lin_srgb_with_alpha(self)
     }
+
+    fn color_space(&self) -> ColorSpace {
+        ColorSpace::Srgb
+    }
 }
 
 impl<C, S, T> IntoLinSrgba<S> for Alpha<C, T>
@@ -130,6 +193,10 @@ where
         srgba.alpha = alpha.convert();
         srgba
     }
+
+    fn color_space(&self) -> ColorSpace {
+        self.color.color_space()
+    }
 }
 
 fn into_lin_srgb_with_alpha<C, S>(color: C) -> LinSrgba<S>
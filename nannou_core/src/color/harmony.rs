@@ -0,0 +1,74 @@
+//! Color harmonies, produced by rotating the hue of a base color.
+//!
+//! Hues are rotated within the perceptually uniform **Oklch** space, so that the resulting
+//! colors share the lightness and colorfulness of the base color. Each function returns the base
+//! color first, followed by the colors that complement it.
+//!
+//! ```
+//! use nannou_core::color::{harmony, STEELBLUE};
+//!
+//! let [base, a, b] = harmony::triadic(STEELBLUE);
+//! ```
+
+use crate::color::conv::IntoLinSrgba;
+use crate::color::oklab::Oklch;
+use crate::color::LinSrgba;
+
+/// The base color along with the color on the opposite side of the hue circle.
+pub fn complementary<C>(color: C) -> [LinSrgba; 2]
+where
+    C: IntoLinSrgba<f32>,
+{
+    rotations(color, [0.0, 180.0])
+}
+
+/// The base color along with the two colors either side of its complement.
+///
+/// `spread` is the distance in degrees of each color from the complement, e.g. `30.0`.
+pub fn split_complementary<C>(color: C, spread: f32) -> [LinSrgba; 3]
+where
+    C: IntoLinSrgba<f32>,
+{
+    rotations(color, [0.0, 180.0 - spread, 180.0 + spread])
+}
+
+/// The base color along with two colors evenly spaced around the hue circle.
+pub fn triadic<C>(color: C) -> [LinSrgba; 3]
+where
+    C: IntoLinSrgba<f32>,
+{
+    rotations(color, [0.0, 120.0, 240.0])
+}
+
+/// The base color along with three colors evenly spaced around the hue circle.
+pub fn tetradic<C>(color: C) -> [LinSrgba; 4]
+where
+    C: IntoLinSrgba<f32>,
+{
+    rotations(color, [0.0, 90.0, 180.0, 270.0])
+}
+
+/// The base color along with its neighbours either side on the hue circle.
+///
+/// `spread` is the distance in degrees of each neighbour from the base color, e.g. `30.0`.
+pub fn analogous<C>(color: C, spread: f32) -> [LinSrgba; 3]
+where
+    C: IntoLinSrgba<f32>,
+{
+    rotations(color, [0.0, -spread, spread])
+}
+
+// Produce a color for each hue rotation of the base color, retaining its alpha.
+fn rotations<C, const N: usize>(color: C, degrees: [f32; N]) -> [LinSrgba; N]
+where
+    C: IntoLinSrgba<f32>,
+{
+    let color = color.into_lin_srgba();
+    let base = Oklch::from_lin_srgb(color.color);
+    let mut colors = [color; N];
+    for (c, &d) in colors.iter_mut().zip(degrees.iter()).skip(1) {
+        let rgb = base.rotate_hue(d).to_lin_srgb_in_gamut();
+        *c = LinSrgba::new(rgb.red, rgb.green, rgb.blue, color.alpha);
+    }
+    colors
+}
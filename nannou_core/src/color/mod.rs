@@ -4,9 +4,12 @@
 //! See the [**named**](./named/index.html) module for a set of provided color constants.
 
 pub mod conv;
+pub mod harmony;
+pub mod oklab;
 
-pub use self::conv::IntoLinSrgba;
+pub use self::conv::{ColorExt, ColorSpace, IntoLinSrgba};
 pub use self::named::*;
+pub use self::oklab::{mix_oklab, mix_oklch, oklab, oklch, Oklab, Oklch};
 #[doc(inline)]
 pub use palette::*;
 
@@ -0,0 +1,287 @@
+//! The Oklab and Oklch perceptual color spaces.
+//!
+//! Oklab is designed such that equal distances correspond to roughly equal perceived differences
+//! in color. This makes it well suited to interpolating between colors and to producing color
+//! harmonies. Oklch describes the same space in terms of lightness, chroma and hue.
+//!
+//! See [Björn Ottosson's introduction](https://bottosson.github.io/posts/oklab/) for details.
+
+use crate::color::conv::IntoLinSrgba;
+use crate::color::{LinSrgb, LinSrgba};
+use crate::math::num_traits::Float;
+
+/// A color in the Oklab color space.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Oklab {
+    /// The perceived lightness, from `0.0` (black) to `1.0` (white).
+    pub l: f32,
+    /// How green (negative) or red (positive) the color is.
+    pub a: f32,
+    /// How blue (negative) or yellow (positive) the color is.
+    pub b: f32,
+}
+
+/// A color in the Oklch color space, the polar form of **Oklab**.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Oklch {
+    /// The perceived lightness, from `0.0` (black) to `1.0` (white).
+    pub l: f32,
+    /// The colorfulness, from `0.0` (gray) to roughly `0.37` for the most saturated sRGB colors.
+    pub chroma: f32,
+    /// The hue in degrees.
+    pub hue: f32,
+}
+
+impl Oklab {
+    /// Construct a new **Oklab** color.
+    pub fn new(l: f32, a: f32, b: f32) -> Self {
+        Oklab { l, a, b }
+    }
+
+    /// Convert from linear sRGB.
+    pub fn from_lin_srgb(color: LinSrgb) -> Self {
+        let (r, g, b) = (color.red, color.green, color.blue);
+        let l = 0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b;
+        let m = 0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b;
+        let s = 0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b;
+        let (l, m, s) = (Float::cbrt(l), Float::cbrt(m), Float::cbrt(s));
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+
+    /// Convert to linear sRGB.
+    ///
+    /// Colors outside of the sRGB gamut produce components outside of the `0.0..=1.0` range.
+    pub fn to_lin_srgb(self) -> LinSrgb {
+        let l = self.l + 0.396_337_78 * self.a + 0.215_803_76 * self.b;
+        let m = self.l - 0.105_561_346 * self.a - 0.063_854_17 * self.b;
+        let s = self.l - 0.089_484_18 * self.a - 1.291_485_5 * self.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        LinSrgb::new(
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        )
+    }
+
+    /// Linearly interpolate towards `other` by `t`, where `0.0` is `self` and `1.0` is `other`.
+    pub fn mix(self, other: Self, t: f32) -> Self {
+        Oklab {
+            l: lerp(self.l, other.l, t),
+            a: lerp(self.a, other.a, t),
+            b: lerp(self.b, other.b, t),
+        }
+    }
+
+    /// The perceived difference between two colors.
+    pub fn distance(self, other: Self) -> f32 {
+        let (dl, da, db) = (self.l - other.l, self.a - other.a, self.b - other.b);
+        Float::sqrt(dl * dl + da * da + db * db)
+    }
+}
+
+impl Oklch {
+    /// Construct a new **Oklch** color with the hue given in degrees.
+    pub fn new(l: f32, chroma: f32, hue: f32) -> Self {
+        Oklch { l, chroma, hue }
+    }
+
+    /// Convert from linear sRGB.
+    pub fn from_lin_srgb(color: LinSrgb) -> Self {
+        Oklab::from_lin_srgb(color).into()
+    }
+
+    /// Convert to linear sRGB.
+    ///
+    /// Colors outside of the sRGB gamut produce components outside of the `0.0..=1.0` range. See
+    /// `to_lin_srgb_in_gamut` for an alternative.
+    pub fn to_lin_srgb(self) -> LinSrgb {
+        Oklab::from(self).to_lin_srgb()
+    }
+
+    /// Convert to linear sRGB, reducing the chroma as necessary to fit within the sRGB gamut.
+    ///
+    /// Unlike clamping each component, this retains the lightness and hue of the color.
+    pub fn to_lin_srgb_in_gamut(self) -> LinSrgb {
+        let l = self.l.clamp(0.0, 1.0);
+        let in_gamut = |c: LinSrgb| {
+            let e = 1e-4;
+            let valid = |x: f32| x >= -e && x <= 1.0 + e;
+            valid(c.red) && valid(c.green) && valid(c.blue)
+        };
+        let color = Oklch { l, ..self }.to_lin_srgb();
+        if in_gamut(color) {
+            return color;
+        }
+        // Bisect the chroma between gray and the requested chroma.
+        let (mut lo, mut hi) = (0.0, self.chroma.max(0.0));
+        for _ in 0..20 {
+            let mid = (lo + hi) * 0.5;
+            match in_gamut(Oklch::new(l, mid, self.hue).to_lin_srgb()) {
+                true => lo = mid,
+                false => hi = mid,
+            }
+        }
+        let color = Oklch::new(l, lo, self.hue).to_lin_srgb();
+        let clamp = |x: f32| x.clamp(0.0, 1.0);
+        LinSrgb::new(clamp(color.red), clamp(color.green), clamp(color.blue))
+    }
+
+    /// Rotate the hue by the given number of degrees.
+    pub fn rotate_hue(self, degrees: f32) -> Self {
+        let hue = (self.hue + degrees) % 360.0;
+        let hue = if hue < 0.0 { hue + 360.0 } else { hue };
+        Oklch { hue, ..self }
+    }
+
+    /// Interpolate towards `other` by `t`, where `0.0` is `self` and `1.0` is `other`.
+    ///
+    /// The hue is interpolated in the direction of the shortest path around the hue circle. If
+    /// either color is gray, the hue of the other is used.
+    pub fn mix(self, other: Self, t: f32) -> Self {
+        let e = 1e-4;
+        let (hue_a, hue_b) = match (self.chroma < e, other.chroma < e) {
+            (true, false) => (other.hue, other.hue),
+            (false, true) => (self.hue, self.hue),
+            _ => (self.hue, other.hue),
+        };
+        let mut delta = (hue_b - hue_a) % 360.0;
+        if delta > 180.0 {
+            delta -= 360.0;
+        } else if delta < -180.0 {
+            delta += 360.0;
+        }
+        Oklch {
+            l: lerp(self.l, other.l, t),
+            chroma: lerp(self.chroma, other.chroma, t),
+            hue: hue_a,
+        }
+        .rotate_hue(delta * t)
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(c: Oklch) -> Self {
+        let (sin, cos) = Float::sin_cos(Float::to_radians(c.hue));
+        Oklab {
+            l: c.l,
+            a: c.chroma * cos,
+            b: c.chroma * sin,
+        }
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(c: Oklab) -> Self {
+        let chroma = Float::sqrt(c.a * c.a + c.b * c.b);
+        let hue = Float::to_degrees(Float::atan2(c.b, c.a));
+        let hue = if hue < 0.0 { hue + 360.0 } else { hue };
+        Oklch {
+            l: c.l,
+            chroma,
+            hue,
+        }
+    }
+}
+
+impl From<LinSrgb> for Oklab {
+    fn from(c: LinSrgb) -> Self {
+        Oklab::from_lin_srgb(c)
+    }
+}
+
+impl From<LinSrgb> for Oklch {
+    fn from(c: LinSrgb) -> Self {
+        Oklch::from_lin_srgb(c)
+    }
+}
+
+impl From<Oklab> for LinSrgb {
+    fn from(c: Oklab) -> Self {
+        c.to_lin_srgb()
+    }
+}
+
+impl From<Oklch> for LinSrgb {
+    fn from(c: Oklch) -> Self {
+        c.to_lin_srgb()
+    }
+}
+
+impl IntoLinSrgba<f32> for Oklab {
+    fn into_lin_srgba(self) -> LinSrgba {
+        let c = self.to_lin_srgb();
+        LinSrgba::new(c.red, c.green, c.blue, 1.0)
+    }
+}
+
+impl IntoLinSrgba<f32> for Oklch {
+    fn into_lin_srgba(self) -> LinSrgba {
+        Oklab::from(self).into_lin_srgba()
+    }
+}
+
+/// A short-hand constructor for `Oklab::new`.
+pub fn oklab(l: f32, a: f32, b: f32) -> Oklab {
+    Oklab::new(l, a, b)
+}
+
+/// A short-hand constructor for `Oklch::new(l, chroma, h * 360.0)`.
+///
+/// The given hue expects a value between `0.0` and `1.0` where `0.0` is 0 degrees and `1.0` is
+/// 360 degrees (or 2 PI radians).
+pub fn oklch(l: f32, chroma: f32, h: f32) -> Oklch {
+    Oklch::new(l, chroma, h * 360.0)
+}
+
+/// Interpolate between two colors in the Oklab space, where `t` of `0.0` is `a` and `1.0` is
+/// `b`.
+///
+/// Alpha is interpolated linearly.
+pub fn mix_oklab<A, B>(a: A, b: B, t: f32) -> LinSrgba
+where
+    A: IntoLinSrgba<f32>,
+    B: IntoLinSrgba<f32>,
+{
+    let (a, b) = (a.into_lin_srgba(), b.into_lin_srgba());
+    let c = Oklab::from(a.color)
+        .mix(Oklab::from(b.color), t)
+        .to_lin_srgb();
+    LinSrgba::new(c.red, c.green, c.blue, lerp(a.alpha, b.alpha, t))
+}
+
+/// Interpolate between two colors in the Oklch space, where `t` of `0.0` is `a` and `1.0` is
+/// `b`.
+///
+/// Hues are interpolated along the shortest path, typically producing more vivid intermediate
+/// colors than `mix_oklab`. Alpha is interpolated linearly.
+pub fn mix_oklch<A, B>(a: A, b: B, t: f32) -> LinSrgba
+where
+    A: IntoLinSrgba<f32>,
+    B: IntoLinSrgba<f32>,
+{
+    let (a, b) = (a.into_lin_srgba(), b.into_lin_srgba());
+    let c = Oklch::from(a.color)
+        .mix(Oklch::from(b.color), t)
+        .to_lin_srgb_in_gamut();
+    LinSrgba::new(c.red, c.green, c.blue, lerp(a.alpha, b.alpha, t))
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+#[test]
+fn test_oklab_round_trip() {
+    let white = Oklab::from_lin_srgb(LinSrgb::new(1.0, 1.0, 1.0));
+    assert!((white.l - 1.0).abs() < 1e-3);
+    assert!(white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+    let color = LinSrgb::new(0.8, 0.3, 0.1);
+    let back = Oklch::from_lin_srgb(color).rotate_hue(360.0).to_lin_srgb();
+    assert!((back.red - color.red).abs() < 1e-3);
+    assert!((back.green - color.green).abs() < 1e-3);
+    assert!((back.blue - color.blue).abs() < 1e-3);
+}
@@ -0,0 +1,398 @@
+//! Items related to hexagonal grids.
+//!
+//! Hexagons are addressed via axial coordinates, i.e. a column `q` and a row `r`, with the third
+//! cube coordinate `s` derived as `-q - r`. A **Layout** describes the size, orientation and
+//! origin of the grid and converts between hexagons and points.
+//!
+//! The coordinate system follows the conventions described in Red Blob Games' "Hexagonal Grids"
+//! guide, with `r` increasing upwards to match nannou's coordinate system.
+
+use crate::geom::Point2;
+use crate::math::num_traits::Float;
+use core::ops;
+
+/// The number of corners and neighbours of a hexagon.
+pub const NUM_SIDES: usize = 6;
+
+/// A hexagon within a grid, addressed via axial coordinates.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    /// The column.
+    pub q: i32,
+    /// The row.
+    pub r: i32,
+}
+
+/// The orientation of the hexagons within a grid.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    /// Hexagons have a corner at the top and bottom. Rows are horizontal.
+    #[default]
+    Pointy,
+    /// Hexagons have an edge at the top and bottom. Columns are vertical.
+    Flat,
+}
+
+/// Describes the size, orientation and position of a hexagonal grid.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Layout {
+    /// The orientation of the hexagons.
+    pub orientation: Orientation,
+    /// The distance from the centre of a hexagon to each of its corners.
+    ///
+    /// The `x` and `y` components may differ in order to stretch the grid.
+    pub size: Point2,
+    /// The position of the centre of the hexagon at the grid origin.
+    pub origin: Point2,
+}
+
+/// An iterator yielding the six neighbours of a hexagon.
+#[derive(Clone, Debug)]
+pub struct Neighbors {
+    hex: Hex,
+    index: usize,
+}
+
+/// An iterator yielding every hexagon at an exact distance from a centre hexagon.
+#[derive(Clone, Debug)]
+pub struct Ring {
+    next: Hex,
+    radius: u32,
+    side: usize,
+    step: u32,
+    done: bool,
+}
+
+/// An iterator yielding every hexagon within some distance of a centre hexagon.
+#[derive(Clone, Debug)]
+pub struct Range {
+    centre: Hex,
+    radius: i32,
+    q: i32,
+    r: i32,
+}
+
+/// The offset to each of a hexagon's neighbours, in counter-clockwise order.
+pub const DIRECTIONS: [Hex; NUM_SIDES] = [
+    Hex::new(1, 0),
+    Hex::new(0, 1),
+    Hex::new(-1, 1),
+    Hex::new(-1, 0),
+    Hex::new(0, -1),
+    Hex::new(1, -1),
+];
+
+impl Hex {
+    /// The hexagon at the origin of the grid.
+    pub const ORIGIN: Self = Hex::new(0, 0);
+
+    /// Construct a hexagon from its axial coordinates.
+    pub const fn new(q: i32, r: i32) -> Self {
+        Hex { q, r }
+    }
+
+    /// Construct a hexagon from its cube coordinates.
+    ///
+    /// The `s` coordinate is implied by `q` and `r` and is ignored.
+    pub const fn from_cube([q, r, _s]: [i32; 3]) -> Self {
+        Hex::new(q, r)
+    }
+
+    /// Round fractional axial coordinates to the nearest hexagon.
+    pub fn round(q: f32, r: f32) -> Self {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (Float::round(q), Float::round(r), Float::round(s));
+        let (dq, dr, ds) = (Float::abs(rq - q), Float::abs(rr - r), Float::abs(rs - s));
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Hex::new(rq as i32, rr as i32)
+    }
+
+    /// The third cube coordinate, equal to `-q - r`.
+    pub const fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The cube coordinates of the hexagon.
+    pub const fn cube(self) -> [i32; 3] {
+        [self.q, self.r, self.s()]
+    }
+
+    /// The neighbouring hexagon in the given direction.
+    ///
+    /// Directions are indices into `DIRECTIONS` and wrap around, i.e. direction `6` is equal to
+    /// direction `0`.
+    pub fn neighbor(self, direction: usize) -> Self {
+        self + DIRECTIONS[direction % NUM_SIDES]
+    }
+
+    /// An iterator yielding the six neighbours of the hexagon in counter-clockwise order.
+    pub fn neighbors(self) -> Neighbors {
+        Neighbors {
+            hex: self,
+            index: 0,
+        }
+    }
+
+    /// The number of steps between this hexagon and the other.
+    pub fn distance(self, other: Self) -> u32 {
+        let [q, r, s] = (self - other).cube();
+        q.unsigned_abs().max(r.unsigned_abs()).max(s.unsigned_abs())
+    }
+
+    /// The number of steps between this hexagon and the origin.
+    pub fn length(self) -> u32 {
+        self.distance(Hex::ORIGIN)
+    }
+
+    /// An iterator yielding every hexagon at exactly `radius` steps from this one.
+    ///
+    /// A radius of `0` yields only this hexagon.
+    pub fn ring(self, radius: u32) -> Ring {
+        let next = self + DIRECTIONS[4] * radius as i32;
+        Ring {
+            next,
+            radius,
+            side: 0,
+            step: 0,
+            done: false,
+        }
+    }
+
+    /// An iterator yielding every hexagon within `radius` steps of this one, including itself.
+    pub fn range(self, radius: u32) -> Range {
+        let radius = radius as i32;
+        Range {
+            centre: self,
+            radius,
+            q: -radius,
+            r: 0,
+        }
+    }
+}
+
+impl Layout {
+    /// A layout with the given orientation where each hexagon has the given radius.
+    pub fn new(orientation: Orientation, radius: f32) -> Self {
+        Layout {
+            orientation,
+            size: Point2::splat(radius),
+            origin: Point2::ZERO,
+        }
+    }
+
+    /// A layout with pointy-topped hexagons of the given radius.
+    pub fn pointy(radius: f32) -> Self {
+        Self::new(Orientation::Pointy, radius)
+    }
+
+    /// A layout with flat-topped hexagons of the given radius.
+    pub fn flat(radius: f32) -> Self {
+        Self::new(Orientation::Flat, radius)
+    }
+
+    /// Position the centre of the origin hexagon at the given point.
+    pub fn origin(mut self, origin: Point2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// The point at the centre of the given hexagon.
+    pub fn to_point(&self, hex: Hex) -> Point2 {
+        let [f0, f1, f2, f3] = self.orientation.forward();
+        let (q, r) = (hex.q as f32, hex.r as f32);
+        let x = (f0 * q + f1 * r) * self.size.x;
+        let y = (f2 * q + f3 * r) * self.size.y;
+        self.origin + Point2::new(x, y)
+    }
+
+    /// The fractional axial coordinates of the given point.
+    pub fn to_fractional(&self, point: Point2) -> (f32, f32) {
+        let [b0, b1, b2, b3] = self.orientation.inverse();
+        let p = (point - self.origin) / self.size;
+        let q = b0 * p.x + b1 * p.y;
+        let r = b2 * p.x + b3 * p.y;
+        (q, r)
+    }
+
+    /// The hexagon containing the given point.
+    pub fn to_hex(&self, point: Point2) -> Hex {
+        let (q, r) = self.to_fractional(point);
+        Hex::round(q, r)
+    }
+
+    /// The corners of the given hexagon in counter-clockwise order.
+    pub fn corners(&self, hex: Hex) -> [Point2; NUM_SIDES] {
+        let centre = self.to_point(hex);
+        let mut corners = [centre; NUM_SIDES];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            *corner += self.corner_offset(i);
+        }
+        corners
+    }
+
+    /// The width and height of a single hexagon.
+    pub fn hex_wh(&self) -> Point2 {
+        let sqrt_3 = Float::sqrt(3.0f32);
+        match self.orientation {
+            Orientation::Pointy => Point2::new(sqrt_3 * self.size.x, 2.0 * self.size.y),
+            Orientation::Flat => Point2::new(2.0 * self.size.x, sqrt_3 * self.size.y),
+        }
+    }
+
+    // The offset of the corner at the given index from the centre of a hexagon.
+    fn corner_offset(&self, index: usize) -> Point2 {
+        let angle = core::f32::consts::TAU * (self.orientation.start_angle() + index as f32)
+            / NUM_SIDES as f32;
+        Point2::new(
+            self.size.x * Float::cos(angle),
+            self.size.y * Float::sin(angle),
+        )
+    }
+}
+
+impl Orientation {
+    // The matrix converting axial coordinates to points.
+    fn forward(self) -> [f32; 4] {
+        let sqrt_3 = Float::sqrt(3.0f32);
+        match self {
+            Orientation::Pointy => [sqrt_3, sqrt_3 / 2.0, 0.0, 3.0 / 2.0],
+            Orientation::Flat => [3.0 / 2.0, 0.0, sqrt_3 / 2.0, sqrt_3],
+        }
+    }
+
+    // The matrix converting points to axial coordinates.
+    fn inverse(self) -> [f32; 4] {
+        let sqrt_3 = Float::sqrt(3.0f32);
+        match self {
+            Orientation::Pointy => [sqrt_3 / 3.0, -1.0 / 3.0, 0.0, 2.0 / 3.0],
+            Orientation::Flat => [2.0 / 3.0, 0.0, -1.0 / 3.0, sqrt_3 / 3.0],
+        }
+    }
+
+    // The angle of the first corner as a fraction of a sixth of a turn.
+    fn start_angle(self) -> f32 {
+        match self {
+            Orientation::Pointy => 0.5,
+            Orientation::Flat => 0.0,
+        }
+    }
+}
+
+impl Iterator for Neighbors {
+    type Item = Hex;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < NUM_SIDES {
+            let hex = self.hex.neighbor(self.index);
+            self.index += 1;
+            Some(hex)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Neighbors {
+    fn len(&self) -> usize {
+        NUM_SIDES - self.index
+    }
+}
+
+impl Iterator for Ring {
+    type Item = Hex;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let hex = self.next;
+        if self.radius == 0 {
+            self.done = true;
+            return Some(hex);
+        }
+        self.next = hex.neighbor(self.side);
+        self.step += 1;
+        if self.step == self.radius {
+            self.step = 0;
+            self.side += 1;
+            self.done = self.side == NUM_SIDES;
+        }
+        Some(hex)
+    }
+}
+
+impl Iterator for Range {
+    type Item = Hex;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.q > self.radius {
+            return None;
+        }
+        let hex = self.centre + Hex::new(self.q, self.r);
+        if self.r < self.radius.min(-self.q + self.radius) {
+            self.r += 1;
+        } else {
+            self.q += 1;
+            self.r = (-self.radius).max(-self.q - self.radius);
+        }
+        Some(hex)
+    }
+}
+
+impl ops::Add for Hex {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl ops::Sub for Hex {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl ops::Mul<i32> for Hex {
+    type Output = Self;
+    fn mul(self, scale: i32) -> Self {
+        Hex::new(self.q * scale, self.r * scale)
+    }
+}
+
+impl ops::Neg for Hex {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl ops::AddAssign for Hex {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl ops::SubAssign for Hex {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl From<(i32, i32)> for Hex {
+    fn from((q, r): (i32, i32)) -> Self {
+        Hex::new(q, r)
+    }
+}
+
+impl From<Hex> for (i32, i32) {
+    fn from(hex: Hex) -> Self {
+        (hex.q, hex.r)
+    }
+}
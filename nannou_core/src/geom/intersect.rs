@@ -0,0 +1,435 @@
+//! Intersection and overlap queries between geometric primitives.
+//!
+//! Includes raycasting against triangles and meshes, segment intersection, point-in-polygon
+//! tests and overlap tests between rects, circles and convex polygons. The overlap tests produce a
+//! **Contact** describing how to push the shapes apart, useful for simple collision response.
+//!
+//! Circles are described by their centre and radius, while polygons are described by a slice of
+//! their vertices in either winding order.
+
+use crate::geom::{Point2, Rect, Tri, Vec2, Vec3, Vertex3d};
+use core::iter;
+
+/// A ray beginning at an `origin` and extending infinitely in a `direction`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    /// The point from which the ray is cast.
+    pub origin: Vec3,
+    /// The direction in which the ray is cast. Need not be normalised.
+    pub direction: Vec3,
+}
+
+/// The point at which a **Ray** hits a triangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    /// The distance along the ray to the hit in multiples of the ray's direction.
+    pub t: f32,
+    /// The point at which the ray hit.
+    pub point: Vec3,
+    /// The unit normal of the triangle, facing against the ray.
+    pub normal: Vec3,
+    /// The barycentric coordinates of the point within the triangle, i.e. the weights of each
+    /// vertex. Useful for interpolating vertex attributes such as colors.
+    pub barycentric: Vec3,
+}
+
+/// Describes the overlap between two shapes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact {
+    /// The unit direction from the first shape towards the second along which the shapes overlap
+    /// the least.
+    pub normal: Vec2,
+    /// The distance along the `normal` by which the shapes overlap.
+    ///
+    /// Moving the first shape by `-normal * depth` (or the second by `normal * depth`) separates
+    /// the shapes.
+    pub depth: f32,
+    /// An approximate point within the region in which the shapes overlap.
+    pub point: Point2,
+}
+
+impl Ray {
+    /// A ray beginning at `origin` and extending in `direction`.
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Ray { origin, direction }
+    }
+
+    /// The point at the given distance along the ray in multiples of the ray's direction.
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// The nearest point at which the ray hits the given triangle.
+    ///
+    /// See the `ray_triangle` function for details.
+    pub fn triangle<V>(&self, tri: Tri<V>) -> Option<RayHit>
+    where
+        V: Vertex3d<Scalar = f32>,
+    {
+        ray_triangle(*self, tri)
+    }
+
+    /// The nearest point at which the ray hits any of the given triangles.
+    ///
+    /// See the `ray_mesh` function for details.
+    pub fn mesh<I, V>(&self, tris: I) -> Option<(usize, RayHit)>
+    where
+        I: IntoIterator<Item = Tri<V>>,
+        V: Vertex3d<Scalar = f32>,
+    {
+        ray_mesh(*self, tris)
+    }
+}
+
+/// The point at which the ray hits the given triangle, if any.
+///
+/// Both sides of the triangle are hit. Triangles behind the ray's origin are not hit.
+///
+/// ```
+/// use nannou_core::geom::intersect::{ray_triangle, Ray};
+/// use nannou_core::geom::{vec3, Tri};
+///
+/// let tri = Tri([vec3(-1.0, -1.0, 0.0), vec3(1.0, -1.0, 0.0), vec3(0.0, 1.0, 0.0)]);
+/// let ray = Ray::new(vec3(0.0, 0.0, 5.0), vec3(0.0, 0.0, -1.0));
+/// let hit = ray_triangle(ray, tri).unwrap();
+/// assert_eq!(hit.t, 5.0);
+/// assert_eq!(hit.normal, vec3(0.0, 0.0, 1.0));
+/// ```
+pub fn ray_triangle<V>(ray: Ray, tri: Tri<V>) -> Option<RayHit>
+where
+    V: Vertex3d<Scalar = f32>,
+{
+    // The Möller-Trumbore algorithm.
+    let Tri([a, b, c]) = tri.map_vertices(|v| Vec3::from(v.point3()));
+    let e1 = b - a;
+    let e2 = c - a;
+    let p = ray.direction.cross(e2);
+    let det = e1.dot(p);
+    if det == 0.0 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = ray.origin - a;
+    let u = s.dot(p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = ray.direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = e2.dot(q) * inv_det;
+    if t < 0.0 {
+        return None;
+    }
+    let mut normal = e1.cross(e2).normalize();
+    if normal.dot(ray.direction) > 0.0 {
+        normal = -normal;
+    }
+    Some(RayHit {
+        t,
+        point: ray.at(t),
+        normal,
+        barycentric: Vec3::new(1.0 - u - v, u, v),
+    })
+}
+
+/// The nearest point at which the ray hits any of the given triangles, along with the index of
+/// the triangle that was hit.
+///
+/// The triangles may be those of a mesh, e.g. via `Cuboid::triangles_iter` or a mesh's
+/// `triangles` method.
+pub fn ray_mesh<I, V>(ray: Ray, tris: I) -> Option<(usize, RayHit)>
+where
+    I: IntoIterator<Item = Tri<V>>,
+    V: Vertex3d<Scalar = f32>,
+{
+    tris.into_iter()
+        .enumerate()
+        .filter_map(|(i, tri)| ray_triangle(ray, tri).map(|hit| (i, hit)))
+        .fold(None, |nearest, (i, hit)| match nearest {
+            Some((_, ref nearest_hit)) if nearest_hit.t <= hit.t => nearest,
+            _ => Some((i, hit)),
+        })
+}
+
+/// The point at which the segments `a` and `b` intersect, if any.
+///
+/// If the segments are collinear and overlap, the point of the overlap nearest the start of `a`
+/// is returned.
+///
+/// ```
+/// use nannou_core::geom::intersect::segment_segment;
+/// use nannou_core::geom::pt2;
+///
+/// let a = [pt2(-1.0, 0.0), pt2(1.0, 0.0)];
+/// let b = [pt2(0.0, -1.0), pt2(0.0, 1.0)];
+/// assert_eq!(segment_segment(a, b), Some(pt2(0.0, 0.0)));
+/// ```
+pub fn segment_segment([a0, a1]: [Point2; 2], [b0, b1]: [Point2; 2]) -> Option<Point2> {
+    let r = a1 - a0;
+    let s = b1 - b0;
+    let qp = b0 - a0;
+    let denom = r.perp_dot(s);
+    if denom == 0.0 {
+        // Parallel segments only intersect if collinear and overlapping.
+        let r_len_sq = r.length_squared();
+        if qp.perp_dot(r) != 0.0 || r_len_sq == 0.0 {
+            return None;
+        }
+        let t0 = qp.dot(r) / r_len_sq;
+        let t1 = t0 + s.dot(r) / r_len_sq;
+        let start = t0.min(t1).max(0.0);
+        let end = t0.max(t1).min(1.0);
+        return if start <= end {
+            Some(a0 + r * start)
+        } else {
+            None
+        };
+    }
+    let t = qp.perp_dot(s) / denom;
+    let u = qp.perp_dot(r) / denom;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(a0 + r * t)
+    } else {
+        None
+    }
+}
+
+/// Whether or not the polygon described by the given vertices contains the point.
+///
+/// Unlike `polygon::contains`, which triangulates the polygon as a fan, this supports concave and
+/// self-intersecting polygons via the even-odd rule.
+///
+/// ```
+/// use nannou_core::geom::intersect::point_in_polygon;
+/// use nannou_core::geom::pt2;
+///
+/// // A "U" shape.
+/// let u = [
+///     pt2(0.0, 0.0), pt2(3.0, 0.0), pt2(3.0, 3.0), pt2(2.0, 3.0),
+///     pt2(2.0, 1.0), pt2(1.0, 1.0), pt2(1.0, 3.0), pt2(0.0, 3.0),
+/// ];
+/// assert!(point_in_polygon(pt2(0.5, 2.0), u.iter().cloned()));
+/// assert!(!point_in_polygon(pt2(1.5, 2.0), u.iter().cloned()));
+/// ```
+pub fn point_in_polygon<I>(point: Point2, polygon: I) -> bool
+where
+    I: IntoIterator<Item = Point2>,
+{
+    let mut points = polygon.into_iter();
+    let first = match points.next() {
+        None => return false,
+        Some(p) => p,
+    };
+    let mut prev = first;
+    let mut inside = false;
+    for curr in points.chain(iter::once(first)) {
+        if (curr.y > point.y) != (prev.y > point.y) {
+            let x = prev.x + (point.y - prev.y) / (curr.y - prev.y) * (curr.x - prev.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+        prev = curr;
+    }
+    inside
+}
+
+/// Describes the overlap between the two rects, if any.
+pub fn rect_rect(a: Rect, b: Rect) -> Option<Contact> {
+    let overlap = a.overlap(b)?;
+    let d = b.xy() - a.xy();
+    let (w, h) = overlap.w_h();
+    if w <= 0.0 || h <= 0.0 {
+        return None;
+    }
+    let (normal, depth) = if w < h {
+        (Vec2::new(sign(d.x), 0.0), w)
+    } else {
+        (Vec2::new(0.0, sign(d.y)), h)
+    };
+    let point = overlap.xy();
+    Some(Contact {
+        normal,
+        depth,
+        point,
+    })
+}
+
+/// Describes the overlap between the circles with the given centres and radii, if any.
+pub fn circle_circle(a: Point2, a_radius: f32, b: Point2, b_radius: f32) -> Option<Contact> {
+    let d = b - a;
+    let distance = d.length();
+    let depth = a_radius + b_radius - distance;
+    if depth <= 0.0 {
+        return None;
+    }
+    let normal = if distance > 0.0 {
+        d / distance
+    } else {
+        Vec2::X
+    };
+    let point = a + normal * (a_radius - depth * 0.5);
+    Some(Contact {
+        normal,
+        depth,
+        point,
+    })
+}
+
+/// Describes the overlap between the rect and the circle with the given centre and radius, if
+/// any.
+pub fn rect_circle(rect: Rect, centre: Point2, radius: f32) -> Option<Contact> {
+    let (l, r, b, t) = rect.l_r_b_t();
+    let closest = Vec2::new(centre.x.max(l).min(r), centre.y.max(b).min(t));
+    let d = centre - closest;
+    let distance = d.length();
+    if distance > 0.0 {
+        let depth = radius - distance;
+        if depth <= 0.0 {
+            return None;
+        }
+        return Some(Contact {
+            normal: d / distance,
+            depth,
+            point: closest,
+        });
+    }
+    // The centre lies within the rect, so push the circle out via the nearest edge.
+    let edges = [
+        (centre.x - l, -Vec2::X),
+        (r - centre.x, Vec2::X),
+        (centre.y - b, -Vec2::Y),
+        (t - centre.y, Vec2::Y),
+    ];
+    let (distance, normal) =
+        edges.iter().cloned().fold(
+            edges[0],
+            |min, edge| if edge.0 < min.0 { edge } else { min },
+        );
+    Some(Contact {
+        normal,
+        depth: distance + radius,
+        point: centre,
+    })
+}
+
+/// Describes the overlap between the two convex polygons, if any.
+///
+/// Returns `None` if either polygon has fewer than three vertices.
+pub fn polygon_polygon(a: &[Point2], b: &[Point2]) -> Option<Contact> {
+    if a.len() < 3 || b.len() < 3 {
+        return None;
+    }
+    // Find the axis of least overlap among the edge normals of both polygons.
+    let mut least: Option<(Vec2, f32)> = None;
+    for axis in edge_normals(a).chain(edge_normals(b)) {
+        let depth = overlap(project(a, axis), project(b, axis))?;
+        if least.map(|(_, d)| depth < d).unwrap_or(true) {
+            least = Some((axis, depth));
+        }
+    }
+    let (mut normal, depth) = least?;
+    if (centroid(b) - centroid(a)).dot(normal) < 0.0 {
+        normal = -normal;
+    }
+
+    // Approximate the point of contact with the vertices of each polygon within the other.
+    let inside_a = b
+        .iter()
+        .filter(|&&p| point_in_polygon(p, a.iter().cloned()));
+    let inside_b = a
+        .iter()
+        .filter(|&&p| point_in_polygon(p, b.iter().cloned()));
+    let (sum, count) = inside_a
+        .chain(inside_b)
+        .fold((Vec2::ZERO, 0), |(sum, n), &p| (sum + p, n + 1));
+    let point = match count {
+        0 => (centroid(a) + centroid(b)) * 0.5,
+        n => sum / n as f32,
+    };
+    Some(Contact {
+        normal,
+        depth,
+        point,
+    })
+}
+
+/// Describes the overlap between the circle with the given centre and radius and the convex
+/// polygon, if any.
+///
+/// Returns `None` if the polygon has fewer than three vertices.
+pub fn circle_polygon(centre: Point2, radius: f32, polygon: &[Point2]) -> Option<Contact> {
+    if polygon.len() < 3 {
+        return None;
+    }
+    // Along with the edge normals, test the axis towards the vertex nearest the circle.
+    let nearest = polygon.iter().cloned().fold(polygon[0], |nearest, p| {
+        if p.distance_squared(centre) < nearest.distance_squared(centre) {
+            p
+        } else {
+            nearest
+        }
+    });
+    let vertex_axis = (nearest - centre).try_normalize();
+    let mut least: Option<(Vec2, f32)> = None;
+    for axis in edge_normals(polygon).chain(vertex_axis) {
+        let c = centre.dot(axis);
+        let depth = overlap((c - radius, c + radius), project(polygon, axis))?;
+        if least.map(|(_, d)| depth < d).unwrap_or(true) {
+            least = Some((axis, depth));
+        }
+    }
+    let (mut normal, depth) = least?;
+    if (centroid(polygon) - centre).dot(normal) < 0.0 {
+        normal = -normal;
+    }
+    let point = centre + normal * (radius - depth * 0.5);
+    Some(Contact {
+        normal,
+        depth,
+        point,
+    })
+}
+
+// The unit normals of each edge of the polygon.
+fn edge_normals(polygon: &[Point2]) -> impl Iterator<Item = Vec2> + '_ {
+    let next = polygon.iter().skip(1).chain(polygon.first());
+    polygon
+        .iter()
+        .zip(next)
+        .filter_map(|(&a, &b)| (b - a).perp().try_normalize())
+}
+
+// The range covered by the polygon when projected onto the axis.
+fn project(polygon: &[Point2], axis: Vec2) -> (f32, f32) {
+    polygon.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+        let d = p.dot(axis);
+        (min.min(d), max.max(d))
+    })
+}
+
+// The overlap between the two ranges, if any.
+fn overlap((a_min, a_max): (f32, f32), (b_min, b_max): (f32, f32)) -> Option<f32> {
+    let depth = a_max.min(b_max) - a_min.max(b_min);
+    if depth > 0.0 {
+        Some(depth)
+    } else {
+        None
+    }
+}
+
+// The mean of the polygon's vertices.
+fn centroid(polygon: &[Point2]) -> Point2 {
+    polygon.iter().fold(Vec2::ZERO, |sum, &p| sum + p) / polygon.len() as f32
+}
+
+fn sign(x: f32) -> f32 {
+    if x < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
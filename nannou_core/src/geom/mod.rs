@@ -11,6 +11,8 @@
 
 pub mod cuboid;
 pub mod ellipse;
+pub mod hex;
+pub mod intersect;
 pub mod point;
 pub mod polygon;
 pub mod quad;
@@ -23,6 +25,7 @@ pub mod vertex;
 
 pub use self::cuboid::Cuboid;
 pub use self::ellipse::Ellipse;
+pub use self::hex::Hex;
 pub use self::point::{pt2, pt3, pt4, Point2, Point3, Point4};
 pub use self::polygon::Polygon;
 pub use self::quad::Quad;
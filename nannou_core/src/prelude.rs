@@ -2,11 +2,12 @@
 
 pub use crate::color::named::*;
 pub use crate::color::{
-    gray, hsl, hsla, hsv, hsva, lin_srgb, lin_srgba, rgb, rgb8, rgba, rgba8, srgb, srgb8, srgba,
-    srgba8,
+    gray, hsl, hsla, hsv, hsva, lin_srgb, lin_srgba, oklab, oklch, rgb, rgb8, rgba, rgba8, srgb,
+    srgb8, srgba, srgba8,
 };
 pub use crate::color::{
-    Gray, Hsl, Hsla, Hsv, Hsva, LinSrgb, LinSrgba, Rgb, Rgb8, Rgba, Rgba8, Srgb, Srgba,
+    ColorExt, Gray, Hsl, Hsla, Hsv, Hsva, LinSrgb, LinSrgba, Oklab, Oklch, Rgb, Rgb8, Rgba, Rgba8,
+    Srgb, Srgba,
 };
 pub use crate::geom::{self, pt2, pt3, Cuboid, Point2, Point3, Rect};
 #[allow(deprecated)]
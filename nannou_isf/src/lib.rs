@@ -1,4 +1,7 @@
 //! A crate aimed at making it easy to set up an ISF hot-loading environment with nannou.
+//!
+//! Shaders are compiled to SPIR-V via shaderc by default. Enabling the `naga` feature instead
+//! translates them to WGSL via naga's GLSL frontend.
 
 pub use crate::pipeline::{IsfPipeline, IsfTime};
 use std::path::Path;
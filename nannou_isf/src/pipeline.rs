@@ -188,6 +188,12 @@ pub enum ShaderError {
         #[from]
         err: hotglsl::CompileError,
     },
+    #[cfg(feature = "naga")]
+    #[error("an error occurred while translating GLSL: {err}")]
+    Glsl {
+        #[from]
+        err: wgpu::GlslError,
+    },
 }
 
 const VERTICES: [Vertex; 4] = [
@@ -416,17 +422,15 @@ pub fn compile_isf_shader(
         .and_then(|(old_str, isf)| {
             let isf_str = crate::glsl_string_from_isf(&isf);
             let new_str = crate::prefix_isf_glsl_str(&isf_str, old_str);
-            let ty = hotglsl::ShaderStage::Fragment;
-            hotglsl::compile_str(&new_str, ty).map_err(From::from)
+            compile_glsl_str(device, &new_str)
         });
-    let (bytes, error) = split_result(res);
-    let module = bytes.map(|b| wgpu::shader_from_spirv_bytes(device, &b));
-    (module, error)
+    split_result(res)
 }
 
 /// Compile a regular, non-ISF shader.
 ///
 /// This is used for compiling the vertex shaders.
+#[cfg(not(feature = "naga"))]
 pub fn compile_shader(
     device: &wgpu::Device,
     path: &Path,
@@ -437,6 +441,40 @@ pub fn compile_shader(
     (module, compile_err)
 }
 
+/// Compile a regular, non-ISF shader.
+///
+/// This is used for compiling the vertex shaders. The shader stage is inferred from the file
+/// extension, defaulting to the vertex stage.
+#[cfg(feature = "naga")]
+pub fn compile_shader(
+    device: &wgpu::Device,
+    path: &Path,
+) -> (Option<wgpu::ShaderModule>, Option<ShaderError>) {
+    let stage = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("frag") => wgpu::naga::ShaderStage::Fragment,
+        Some("comp") => wgpu::naga::ShaderStage::Compute,
+        _ => wgpu::naga::ShaderStage::Vertex,
+    };
+    let res = std::fs::read_to_string(&path)
+        .map_err(ShaderError::from)
+        .and_then(|s| Ok(wgpu::shader_from_glsl(device, &s, stage)?));
+    split_result(res)
+}
+
+// Compile the ISF fragment shader to SPIR-V via shaderc.
+#[cfg(not(feature = "naga"))]
+fn compile_glsl_str(device: &wgpu::Device, src: &str) -> Result<wgpu::ShaderModule, ShaderError> {
+    let bytes = hotglsl::compile_str(src, hotglsl::ShaderStage::Fragment)?;
+    Ok(wgpu::shader_from_spirv_bytes(device, &bytes))
+}
+
+// Translate the ISF fragment shader to WGSL via naga's GLSL frontend.
+#[cfg(feature = "naga")]
+fn compile_glsl_str(device: &wgpu::Device, src: &str) -> Result<wgpu::ShaderModule, ShaderError> {
+    let stage = wgpu::naga::ShaderStage::Fragment;
+    Ok(wgpu::shader_from_glsl(device, src, stage)?)
+}
+
 impl Shader {
     pub fn fragment_from_path(device: &wgpu::Device, path: PathBuf) -> Self {
         let (module, error) = compile_isf_shader(device, &path);
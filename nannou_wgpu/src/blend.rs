@@ -8,6 +8,14 @@ pub const NORMAL: wgpu::BlendComponent = wgpu::BlendComponent {
     operation: wgpu::BlendOperation::Add,
 };
 
+/// Blends colors that have already been multiplied by their alpha, e.g. those sampled from a
+/// texture rendered with `NORMAL` blending.
+pub const PREMULTIPLIED: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::One,
+    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+    operation: wgpu::BlendOperation::Add,
+};
+
 pub const ADD: wgpu::BlendComponent = wgpu::BlendComponent {
     src_factor: wgpu::BlendFactor::Src,
     dst_factor: wgpu::BlendFactor::Dst,
@@ -25,6 +25,17 @@ pub struct AdapterMap {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct AdapterMapKey {
     power_preference: wgpu::PowerPreference,
+    // Identifies the adapter if it was chosen via `AdapterMap::get_or_select`.
+    selected: Option<AdapterId>,
+}
+
+// Identifies one of the adapters enumerated by an instance.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct AdapterId {
+    name: String,
+    vendor: u32,
+    device: u32,
+    backend: wgpu::Backend,
 }
 
 /// A single active adapter and its map of connected devices.
@@ -103,7 +114,10 @@ impl AdapterMap {
         instance: &'a wgpu::Instance,
     ) -> Option<Arc<ActiveAdapter>> {
         let power_preference = options.power_preference;
-        let key = AdapterMapKey { power_preference };
+        let key = AdapterMapKey {
+            power_preference,
+            selected: None,
+        };
         let mut map = self
             .map
             .lock()
@@ -135,7 +149,10 @@ impl AdapterMap {
             device_map,
         });
         let power_preference = options.power_preference;
-        let key = AdapterMapKey { power_preference };
+        let key = AdapterMapKey {
+            power_preference,
+            selected: None,
+        };
         let mut map = self
             .map
             .lock()
@@ -144,6 +161,61 @@ impl AdapterMap {
         Some(adapter)
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    /// Check for an active adapter for which `select` returns `true` or select one from those
+    /// enumerated by the instance for the given backends.
+    ///
+    /// This allows for choosing between adapters on machines with more than one GPU. Adapters are
+    /// considered in the order in which they are enumerated. If a surface is given, only adapters
+    /// compatible with the surface are considered.
+    ///
+    /// Returns `None` if no available adapter is selected.
+    pub fn get_or_select<F>(
+        &self,
+        backends: wgpu::Backends,
+        compatible_surface: Option<&wgpu::Surface>,
+        instance: &wgpu::Instance,
+        mut select: F,
+    ) -> Option<Arc<ActiveAdapter>>
+    where
+        F: FnMut(&wgpu::AdapterInfo) -> bool,
+    {
+        let mut map = self
+            .map
+            .lock()
+            .expect("failed to acquire `AdapterMap` lock");
+        let active = map
+            .iter()
+            .filter(|(key, _)| key.selected.is_some())
+            .find(|(_, adapter)| select(&adapter.get_info()));
+        if let Some((_, adapter)) = active {
+            return Some(adapter.clone());
+        }
+        let adapter = instance
+            .enumerate_adapters(backends)
+            .filter(|adapter| match compatible_surface {
+                Some(surface) => adapter.is_surface_supported(surface),
+                None => true,
+            })
+            .find(|adapter| select(&adapter.get_info()))?;
+        let info = adapter.get_info();
+        let key = AdapterMapKey {
+            power_preference: wgpu::DEFAULT_POWER_PREFERENCE,
+            selected: Some(AdapterId {
+                name: info.name,
+                vendor: info.vendor,
+                device: info.device,
+                backend: info.backend,
+            }),
+        };
+        let device_map = Default::default();
+        let adapter = Arc::new(ActiveAdapter {
+            adapter,
+            device_map,
+        });
+        Some(map.entry(key).or_insert(adapter).clone())
+    }
+
     /// Clear all adapters that currently have no connected devices.
     ///
     /// First clears all devices that no longer have any external references.
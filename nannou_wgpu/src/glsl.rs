@@ -0,0 +1,81 @@
+//! Translation of GLSL shaders to WGSL via naga's GLSL frontend.
+//!
+//! This allows for using the large body of existing creative-coding GLSL with nannou without
+//! porting it by hand. Shaders must target Vulkan-style GLSL 440 or 450, declaring the `set` and
+//! `binding` of each resource and using separate textures and samplers.
+
+use crate as wgpu;
+use std::fmt;
+
+/// Errors that might occur while translating GLSL to WGSL.
+#[derive(Debug)]
+pub enum GlslError {
+    /// The source failed to parse, described by the 1-based line number and message of each
+    /// error.
+    Parse(Vec<(u32, String)>),
+    /// The parsed shader is invalid, e.g. due to mismatched types.
+    Validation(String),
+    /// The shader uses a feature that cannot be expressed in WGSL.
+    Translation(String),
+}
+
+/// Translate the given GLSL source for the given shader stage to WGSL.
+///
+/// The entry point of the resulting shader is named `main`.
+pub fn glsl_to_wgsl(source: &str, stage: naga::ShaderStage) -> Result<String, GlslError> {
+    let options = naga::front::glsl::Options::from(stage);
+    let module = naga::front::glsl::Frontend::default()
+        .parse(&options, source)
+        .map_err(|errs| {
+            let errs = errs
+                .into_iter()
+                .map(|err| (err.meta.location(source).line_number, err.kind.to_string()))
+                .collect();
+            GlslError::Parse(errs)
+        })?;
+    let flags = naga::valid::ValidationFlags::all();
+    let capabilities = naga::valid::Capabilities::all();
+    let info = naga::valid::Validator::new(flags, capabilities)
+        .validate(&module)
+        .map_err(|err| GlslError::Validation(err.emit_to_string_with_path(source, "glsl")))?;
+    let flags = naga::back::wgsl::WriterFlags::empty();
+    naga::back::wgsl::write_string(&module, &info, flags)
+        .map_err(|err| GlslError::Translation(err.to_string()))
+}
+
+/// Create a wgpu shader module from the given GLSL source for the given shader stage.
+///
+/// The source is translated to WGSL via `glsl_to_wgsl`, allowing errors to be reported rather
+/// than treated as fatal. The entry point of the module is named `main`.
+pub fn shader_from_glsl(
+    device: &wgpu::Device,
+    source: &str,
+    stage: naga::ShaderStage,
+) -> Result<wgpu::ShaderModule, GlslError> {
+    let wgsl = glsl_to_wgsl(source, stage)?;
+    let desc = wgpu::ShaderModuleDescriptor {
+        label: Some("nannou_shader_module"),
+        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+    };
+    Ok(device.create_shader_module(desc))
+}
+
+impl fmt::Display for GlslError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GlslError::Parse(errs) => {
+                for (i, (line, msg)) in errs.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "line {}: {}", line, msg)?;
+                }
+                Ok(())
+            }
+            GlslError::Validation(err) => write!(f, "invalid shader: {}", err),
+            GlslError::Translation(err) => write!(f, "failed to translate shader: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for GlslError {}
@@ -16,6 +16,9 @@
 //!
 //! Note that when using `nannou_wgpu` via `nannou::wgpu`, both features are enabled by default.
 //!
+//! The `glsl` feature enables translating GLSL shaders to WGSL via naga's GLSL frontend, see
+//! `wgpu::shader_from_glsl`. The `naga` crate is re-exported for specifying shader stages.
+//!
 //! Useful links:
 //!
 //! - An awesome [guide for wgpu-rs](https://sotrh.github.io/learn-wgpu/#what-is-wgpu). Highly
@@ -27,9 +30,12 @@
 mod bind_group_builder;
 pub mod blend;
 mod device_map;
+#[cfg(feature = "glsl")]
+mod glsl;
 mod render_pass;
 mod render_pipeline_builder;
 mod sampler_builder;
+mod shader_params;
 mod texture;
 
 // Re-export all of `wgpu` along with its documentation.
@@ -42,29 +48,41 @@ pub use self::bind_group_builder::{
 pub use self::device_map::{
     ActiveAdapter, AdapterMap, AdapterMapKey, DeviceMap, DeviceMapKey, DeviceQueuePair,
 };
+#[cfg(feature = "glsl")]
+pub use self::glsl::{glsl_to_wgsl, shader_from_glsl, GlslError};
 pub use self::render_pass::{
     Builder as RenderPassBuilder,
     ColorAttachmentDescriptorBuilder as RenderPassColorAttachmentDescriptorBuilder,
 };
 pub use self::render_pipeline_builder::RenderPipelineBuilder;
 pub use self::sampler_builder::SamplerBuilder;
+pub use self::shader_params::{ShaderBuffer, ShaderParam, ShaderParams};
+pub use self::texture::blur::Blur;
 #[cfg(feature = "capturer")]
 pub use self::texture::capturer::{
     AwaitWorkerTimeout as TextureCapturerAwaitWorkerTimeout, Capturer as TextureCapturer,
     Snapshot as TextureSnapshot,
 };
+pub use self::texture::fx::ImageFx;
 #[cfg(feature = "image")]
 pub use self::texture::image::{
     format_from_image_color_type as texture_format_from_image_color_type, ImageHolder,
     ImageReadMapping, WithDeviceQueuePair,
 };
+pub use self::texture::levels::Levels;
 pub use self::texture::reshaper::Reshaper as TextureReshaper;
 pub use self::texture::row_padded_buffer::RowPaddedBuffer;
+pub use self::texture::shadertoy::{Shadertoy, ShadertoyError};
+pub use self::texture::simulation::{
+    GameOfLife, GrayScott, Rule as SimulationRule, Simulation, SmoothLife,
+};
 pub use self::texture::{
     descriptor_eq as texture_descriptor_eq, extent_3d_eq,
     format_size_bytes as texture_format_size_bytes, Builder as TextureBuilder, Texture, TextureId,
     TextureView, TextureViewId, ToTextureView,
 };
+#[cfg(feature = "glsl")]
+pub use naga;
 #[doc(inline)]
 pub use wgpu_upstream::{
     include_wgsl,
@@ -78,7 +96,7 @@ pub use wgpu_upstream::{
     CommandEncoder, CommandEncoderDescriptor, CompareFunction, ComputePass, ComputePassDescriptor,
     ComputePipeline, ComputePipelineDescriptor, DepthBiasState, DepthStencilState, Device,
     DeviceDescriptor, DeviceType, DownlevelCapabilities, DownlevelFlags, DynamicOffset, Error,
-    Extent3d, Face, Features, FilterMode, FragmentState, FrontFace, ImageCopyBuffer,
+    ErrorFilter, Extent3d, Face, Features, FilterMode, FragmentState, FrontFace, ImageCopyBuffer,
     ImageCopyBufferBase, ImageCopyTexture, ImageCopyTextureBase, ImageDataLayout,
     ImageSubresourceRange, IndexFormat, Instance, Label, Limits, LoadOp, Maintain, MapMode,
     MultisampleState, Operations, Origin3d, PipelineLayout, PipelineLayoutDescriptor,
@@ -90,14 +108,14 @@ pub use wgpu_upstream::{
     RenderPipelineDescriptor, RequestAdapterOptions, RequestAdapterOptionsBase, RequestDeviceError,
     Sampler, SamplerBorderColor, SamplerDescriptor, ShaderLocation, ShaderModel, ShaderModule,
     ShaderModuleDescriptor, ShaderSource, ShaderStages, StencilFaceState, StencilOperation,
-    StencilState, StorageTextureAccess, Surface, SurfaceConfiguration, SurfaceError, SurfaceStatus,
-    SurfaceTexture, Texture as TextureHandle, TextureAspect, TextureDescriptor, TextureDimension,
-    TextureFormat, TextureFormatFeatureFlags, TextureFormatFeatures, TextureSampleType,
-    TextureUsages, TextureView as TextureViewHandle, TextureViewDescriptor, TextureViewDimension,
-    UncapturedErrorHandler, VertexAttribute, VertexBufferLayout, VertexFormat, VertexState,
-    VertexStepMode, COPY_BUFFER_ALIGNMENT, COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT,
-    PUSH_CONSTANT_ALIGNMENT, QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES, QUERY_SIZE,
-    VERTEX_STRIDE_ALIGNMENT,
+    StencilState, StorageTextureAccess, SubmissionIndex, Surface, SurfaceConfiguration,
+    SurfaceError, SurfaceStatus, SurfaceTexture, Texture as TextureHandle, TextureAspect,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureFormatFeatureFlags,
+    TextureFormatFeatures, TextureSampleType, TextureUsages, TextureView as TextureViewHandle,
+    TextureViewDescriptor, TextureViewDimension, UncapturedErrorHandler, VertexAttribute,
+    VertexBufferLayout, VertexFormat, VertexState, VertexStepMode, COPY_BUFFER_ALIGNMENT,
+    COPY_BYTES_PER_ROW_ALIGNMENT, MAP_ALIGNMENT, PUSH_CONSTANT_ALIGNMENT,
+    QUERY_RESOLVE_BUFFER_ALIGNMENT, QUERY_SET_MAX_QUERIES, QUERY_SIZE, VERTEX_STRIDE_ALIGNMENT,
 };
 
 /// The default power preference used for requesting the WGPU adapter.
@@ -147,6 +165,26 @@ pub fn default_device_descriptor() -> DeviceDescriptor<'static> {
     }
 }
 
+/// The most capable of the default, downlevel and WebGL2 limits supported by the given adapter,
+/// with texture dimension limits raised to those of the adapter.
+///
+/// Useful for running on GL ES adapters, such as that of the Raspberry Pi, which do not support
+/// the default limits.
+pub fn fallback_limits(adapter: &Adapter) -> Limits {
+    let supported = adapter.limits();
+    let candidates = [
+        Limits::default(),
+        Limits::downlevel_defaults(),
+        Limits::downlevel_webgl2_defaults(),
+    ];
+    candidates
+        .iter()
+        .find(|limits| limits.check_limits(&supported))
+        .cloned()
+        .unwrap_or_else(|| supported.clone())
+        .using_resolution(supported)
+}
+
 /// Adds a simple render pass command to the given encoder that resolves the given multisampled
 /// `src_texture` to the given non-multisampled `dst_texture`.
 ///
@@ -536,11 +536,11 @@ fn build(
             &single_color_state[..]
         }
         (true, false) => color_states,
-        (false, true) => panic!("specified color states but no fragment shader"),
-        (false, false) => match color_state.is_some() {
+        (false, true) => match color_state.is_some() {
             true => panic!("specified color state fields but no fragment shader"),
             false => &[],
         },
+        (false, false) => panic!("specified color states but no fragment shader"),
     };
     let fragment = match (fs_mod, color_states.is_empty()) {
         (Some(fs_mod), false) => Some(wgpu::FragmentState {
@@ -0,0 +1,503 @@
+//! A set of named values that may be passed to a shader as a uniform buffer, along with any
+//! storage buffers and texture arrays.
+
+use crate::{self as wgpu, util::DeviceExt};
+
+/// A set of named values to be passed to a shader via a uniform buffer.
+///
+/// **ShaderParams** packs its values according to the std140 layout shared by WGSL and GLSL
+/// uniform buffers and can generate the matching shader declarations. This avoids hand-writing a
+/// `#[repr(C)]` struct with the necessary padding along with its shader-side counterpart.
+///
+/// Data that is too large for a uniform buffer, e.g. audio FFT bins or particle positions, may be
+/// declared as a storage buffer via `buffer`, while `texture_array` declares a binding for a two
+/// dimensional texture array. These are bound separately from the uniform values.
+///
+/// ```
+/// # use nannou_wgpu as wgpu;
+/// let params = wgpu::ShaderParams::new()
+///     .float("speed", 0.5)
+///     .vec2("center", [0.0, 0.25])
+///     .color("tint", (1.0, 0.5, 0.2, 1.0));
+/// assert_eq!(params.std140_size(), 32);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShaderParams {
+    params: Vec<(String, ShaderParam)>,
+    buffers: Vec<(String, ShaderBuffer)>,
+    texture_arrays: Vec<String>,
+}
+
+/// A single value within a set of **ShaderParams**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShaderParam {
+    Float(f32),
+    Int(i32),
+    Uint(u32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+}
+
+/// The contents of a read-only storage buffer within a set of **ShaderParams**.
+///
+/// Each is available to the shader as a runtime-sized array of the element type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShaderBuffer {
+    Float(Vec<f32>),
+    Vec2(Vec<[f32; 2]>),
+    Vec4(Vec<[f32; 4]>),
+}
+
+impl ShaderParams {
+    /// An empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a parameter with the given name and value.
+    ///
+    /// Parameters are laid out in the order in which they are first added. If a parameter with
+    /// the given name already exists, its value is replaced.
+    pub fn param<S, P>(mut self, name: S, value: P) -> Self
+    where
+        S: Into<String>,
+        P: Into<ShaderParam>,
+    {
+        self.set(name, value);
+        self
+    }
+
+    /// Add an `f32` parameter.
+    pub fn float<S: Into<String>>(self, name: S, value: f32) -> Self {
+        self.param(name, value)
+    }
+
+    /// Add an `i32` parameter.
+    pub fn int<S: Into<String>>(self, name: S, value: i32) -> Self {
+        self.param(name, value)
+    }
+
+    /// Add a `u32` parameter.
+    pub fn uint<S: Into<String>>(self, name: S, value: u32) -> Self {
+        self.param(name, value)
+    }
+
+    /// Add a two-component vector parameter, e.g. a `Vec2`.
+    pub fn vec2<S: Into<String>, V: Into<[f32; 2]>>(self, name: S, value: V) -> Self {
+        self.param(name, value.into())
+    }
+
+    /// Add a three-component vector parameter, e.g. a `Vec3`.
+    pub fn vec3<S: Into<String>, V: Into<[f32; 3]>>(self, name: S, value: V) -> Self {
+        self.param(name, value.into())
+    }
+
+    /// Add a four-component vector parameter, e.g. a `Vec4`.
+    pub fn vec4<S: Into<String>, V: Into<[f32; 4]>>(self, name: S, value: V) -> Self {
+        self.param(name, value.into())
+    }
+
+    /// Add a color parameter as a four-component vector.
+    ///
+    /// The components are passed as they are, e.g. a `LinSrgba` for linear components.
+    pub fn color<S, C>(self, name: S, color: C) -> Self
+    where
+        S: Into<String>,
+        C: Into<(f32, f32, f32, f32)>,
+    {
+        let (r, g, b, a) = color.into();
+        self.param(name, [r, g, b, a])
+    }
+
+    /// Add a read-only storage buffer with the given name and contents.
+    ///
+    /// If a buffer with the given name already exists, its contents are replaced.
+    pub fn buffer<S, B>(mut self, name: S, data: B) -> Self
+    where
+        S: Into<String>,
+        B: Into<ShaderBuffer>,
+    {
+        self.set_buffer(name, data);
+        self
+    }
+
+    /// Add a binding with the given name for a two dimensional texture array.
+    pub fn texture_array<S: Into<String>>(mut self, name: S) -> Self {
+        let name = name.into();
+        if !self.texture_arrays.contains(&name) {
+            self.texture_arrays.push(name);
+        }
+        self
+    }
+
+    /// Set the value of the parameter with the given name, adding it if it does not yet exist.
+    ///
+    /// Note that changing the type of a parameter changes the layout of the set, which must be
+    /// reflected in the shader.
+    pub fn set<S, P>(&mut self, name: S, value: P)
+    where
+        S: Into<String>,
+        P: Into<ShaderParam>,
+    {
+        let name = name.into();
+        let value = value.into();
+        match self.params.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, param)) => *param = value,
+            None => self.params.push((name, value)),
+        }
+    }
+
+    /// Set the contents of the storage buffer with the given name, adding it if it does not yet
+    /// exist.
+    pub fn set_buffer<S, B>(&mut self, name: S, data: B)
+    where
+        S: Into<String>,
+        B: Into<ShaderBuffer>,
+    {
+        let name = name.into();
+        let data = data.into();
+        match self.buffers.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, buffer)) => *buffer = data,
+            None => self.buffers.push((name, data)),
+        }
+    }
+
+    /// The value of the parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<ShaderParam> {
+        self.params.iter().find(|(n, _)| n == name).map(|&(_, p)| p)
+    }
+
+    /// The parameters in the order in which they are laid out.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, ShaderParam)> {
+        self.params.iter().map(|(n, p)| (&n[..], *p))
+    }
+
+    /// The storage buffer with the given name.
+    pub fn get_buffer(&self, name: &str) -> Option<&ShaderBuffer> {
+        self.buffers.iter().find(|(n, _)| n == name).map(|(_, b)| b)
+    }
+
+    /// The storage buffers in the order in which they were added.
+    pub fn buffers(&self) -> impl Iterator<Item = (&str, &ShaderBuffer)> {
+        self.buffers.iter().map(|(n, b)| (&n[..], b))
+    }
+
+    /// The names of the texture array bindings in the order in which they were added.
+    pub fn texture_arrays(&self) -> impl Iterator<Item = &str> {
+        self.texture_arrays.iter().map(|n| &n[..])
+    }
+
+    /// The number of parameters, excluding storage buffers and texture arrays.
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Whether or not there are no parameters, in which case no uniform buffer is required.
+    ///
+    /// Storage buffers and texture arrays are not considered.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// The size of the parameters in bytes when laid out according to std140.
+    ///
+    /// This is rounded up to a multiple of 16 bytes.
+    pub fn std140_size(&self) -> usize {
+        let end = self
+            .offsets()
+            .last()
+            .map(|(o, p)| o + p.size())
+            .unwrap_or(0);
+        round_up(end, 16)
+    }
+
+    /// The parameters laid out according to std140, ready for uploading to a uniform buffer.
+    pub fn to_std140_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; self.std140_size()];
+        for (offset, param) in self.offsets() {
+            param.write_bytes(&mut bytes[offset..offset + param.size()]);
+        }
+        bytes
+    }
+
+    /// A WGSL declaration of a struct with the given name matching the layout of the parameters.
+    pub fn wgsl_struct(&self, name: &str) -> String {
+        let mut s = format!("struct {} {{\n", name);
+        for (n, p) in self.iter() {
+            s.push_str(&format!("    {}: {},\n", n, p.wgsl_type()));
+        }
+        s.push_str("};\n");
+        s
+    }
+
+    /// A GLSL declaration of a uniform block at the given set and binding matching the layout of
+    /// the parameters.
+    ///
+    /// The block is named `block_name` and its members are accessed via `instance_name`.
+    pub fn glsl_block(
+        &self,
+        set: u32,
+        binding: u32,
+        block_name: &str,
+        instance_name: &str,
+    ) -> String {
+        let mut s = format!(
+            "layout(set = {}, binding = {}, std140) uniform {} {{\n",
+            set, binding, block_name
+        );
+        for (n, p) in self.iter() {
+            s.push_str(&format!("    {} {};\n", p.glsl_type(), n));
+        }
+        s.push_str(&format!("}} {};\n", instance_name));
+        s
+    }
+
+    /// Create a uniform buffer containing the parameters.
+    ///
+    /// The buffer may later be updated via `upload`.
+    pub fn create_uniform_buffer(&self, device: &wgpu::Device) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou_shader_params"),
+            contents: &self.to_std140_bytes(),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        })
+    }
+
+    /// Encode the commands necessary to copy the parameters to the given uniform buffer.
+    ///
+    /// The buffer must be at least `std140_size` bytes and have `BufferUsages::COPY_DST` enabled.
+    pub fn upload(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        buffer: &wgpu::Buffer,
+    ) {
+        if self.is_empty() {
+            return;
+        }
+        let bytes = self.to_std140_bytes();
+        let staging = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou_shader_params_staging"),
+            contents: &bytes,
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        let size = bytes.len() as wgpu::BufferAddress;
+        encoder.copy_buffer_to_buffer(&staging, 0, buffer, 0, size);
+    }
+
+    // The std140 offset of each parameter in bytes.
+    fn offsets(&self) -> impl Iterator<Item = (usize, ShaderParam)> + '_ {
+        let mut end = 0;
+        self.params.iter().map(move |&(_, param)| {
+            let offset = round_up(end, param.align());
+            end = offset + param.size();
+            (offset, param)
+        })
+    }
+}
+
+impl ShaderParam {
+    /// Whether or not the parameter is of the same type as `other`.
+    pub fn ty_matches(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// The name of the type in WGSL.
+    pub fn wgsl_type(&self) -> &'static str {
+        match *self {
+            ShaderParam::Float(_) => "f32",
+            ShaderParam::Int(_) => "i32",
+            ShaderParam::Uint(_) => "u32",
+            ShaderParam::Vec2(_) => "vec2<f32>",
+            ShaderParam::Vec3(_) => "vec3<f32>",
+            ShaderParam::Vec4(_) => "vec4<f32>",
+        }
+    }
+
+    /// The name of the type in GLSL.
+    pub fn glsl_type(&self) -> &'static str {
+        match *self {
+            ShaderParam::Float(_) => "float",
+            ShaderParam::Int(_) => "int",
+            ShaderParam::Uint(_) => "uint",
+            ShaderParam::Vec2(_) => "vec2",
+            ShaderParam::Vec3(_) => "vec3",
+            ShaderParam::Vec4(_) => "vec4",
+        }
+    }
+
+    fn size(&self) -> usize {
+        match *self {
+            ShaderParam::Float(_) | ShaderParam::Int(_) | ShaderParam::Uint(_) => 4,
+            ShaderParam::Vec2(_) => 8,
+            ShaderParam::Vec3(_) => 12,
+            ShaderParam::Vec4(_) => 16,
+        }
+    }
+
+    // Three-component vectors are aligned to 16 bytes under std140.
+    fn align(&self) -> usize {
+        match *self {
+            ShaderParam::Float(_) | ShaderParam::Int(_) | ShaderParam::Uint(_) => 4,
+            ShaderParam::Vec2(_) => 8,
+            ShaderParam::Vec3(_) | ShaderParam::Vec4(_) => 16,
+        }
+    }
+
+    fn write_bytes(&self, bytes: &mut [u8]) {
+        match *self {
+            ShaderParam::Float(f) => bytes.copy_from_slice(&f.to_le_bytes()),
+            ShaderParam::Int(i) => bytes.copy_from_slice(&i.to_le_bytes()),
+            ShaderParam::Uint(u) => bytes.copy_from_slice(&u.to_le_bytes()),
+            ShaderParam::Vec2(ref v) => write_floats(bytes, v),
+            ShaderParam::Vec3(ref v) => write_floats(bytes, v),
+            ShaderParam::Vec4(ref v) => write_floats(bytes, v),
+        }
+    }
+}
+
+impl ShaderBuffer {
+    /// Whether or not the buffer has the same element type as `other`.
+    pub fn ty_matches(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        match *self {
+            ShaderBuffer::Float(ref v) => v.len(),
+            ShaderBuffer::Vec2(ref v) => v.len(),
+            ShaderBuffer::Vec4(ref v) => v.len(),
+        }
+    }
+
+    /// Whether or not the buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The name of the element type in WGSL.
+    pub fn wgsl_element_type(&self) -> &'static str {
+        match *self {
+            ShaderBuffer::Float(_) => "f32",
+            ShaderBuffer::Vec2(_) => "vec2<f32>",
+            ShaderBuffer::Vec4(_) => "vec4<f32>",
+        }
+    }
+
+    /// The name of the element type in GLSL.
+    pub fn glsl_element_type(&self) -> &'static str {
+        match *self {
+            ShaderBuffer::Float(_) => "float",
+            ShaderBuffer::Vec2(_) => "vec2",
+            ShaderBuffer::Vec4(_) => "vec4",
+        }
+    }
+
+    /// The contents as bytes, ready for uploading to a storage buffer.
+    ///
+    /// As a binding may not be empty, an empty buffer produces a single zeroed element.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let floats: Vec<f32> = match *self {
+            ShaderBuffer::Float(ref v) => v.clone(),
+            ShaderBuffer::Vec2(ref v) => v.iter().flatten().cloned().collect(),
+            ShaderBuffer::Vec4(ref v) => v.iter().flatten().cloned().collect(),
+        };
+        let mut bytes: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+        if bytes.is_empty() {
+            bytes.resize(self.element_size(), 0);
+        }
+        bytes
+    }
+
+    fn element_size(&self) -> usize {
+        match *self {
+            ShaderBuffer::Float(_) => 4,
+            ShaderBuffer::Vec2(_) => 8,
+            ShaderBuffer::Vec4(_) => 16,
+        }
+    }
+}
+
+impl From<f32> for ShaderParam {
+    fn from(f: f32) -> Self {
+        ShaderParam::Float(f)
+    }
+}
+
+impl From<i32> for ShaderParam {
+    fn from(i: i32) -> Self {
+        ShaderParam::Int(i)
+    }
+}
+
+impl From<u32> for ShaderParam {
+    fn from(u: u32) -> Self {
+        ShaderParam::Uint(u)
+    }
+}
+
+impl From<[f32; 2]> for ShaderParam {
+    fn from(v: [f32; 2]) -> Self {
+        ShaderParam::Vec2(v)
+    }
+}
+
+impl From<[f32; 3]> for ShaderParam {
+    fn from(v: [f32; 3]) -> Self {
+        ShaderParam::Vec3(v)
+    }
+}
+
+impl From<[f32; 4]> for ShaderParam {
+    fn from(v: [f32; 4]) -> Self {
+        ShaderParam::Vec4(v)
+    }
+}
+
+impl From<Vec<f32>> for ShaderBuffer {
+    fn from(v: Vec<f32>) -> Self {
+        ShaderBuffer::Float(v)
+    }
+}
+
+impl From<Vec<[f32; 2]>> for ShaderBuffer {
+    fn from(v: Vec<[f32; 2]>) -> Self {
+        ShaderBuffer::Vec2(v)
+    }
+}
+
+impl From<Vec<[f32; 4]>> for ShaderBuffer {
+    fn from(v: Vec<[f32; 4]>) -> Self {
+        ShaderBuffer::Vec4(v)
+    }
+}
+
+impl<'a> From<&'a [f32]> for ShaderBuffer {
+    fn from(v: &'a [f32]) -> Self {
+        ShaderBuffer::Float(v.to_vec())
+    }
+}
+
+impl<'a> From<&'a [[f32; 2]]> for ShaderBuffer {
+    fn from(v: &'a [[f32; 2]]) -> Self {
+        ShaderBuffer::Vec2(v.to_vec())
+    }
+}
+
+impl<'a> From<&'a [[f32; 4]]> for ShaderBuffer {
+    fn from(v: &'a [[f32; 4]]) -> Self {
+        ShaderBuffer::Vec4(v.to_vec())
+    }
+}
+
+fn write_floats(bytes: &mut [u8], floats: &[f32]) {
+    for (chunk, f) in bytes.chunks_mut(4).zip(floats) {
+        chunk.copy_from_slice(&f.to_le_bytes());
+    }
+}
+
+fn round_up(n: usize, align: usize) -> usize {
+    n.div_ceil(align) * align
+}
@@ -0,0 +1,387 @@
+use crate::{self as wgpu, util::DeviceExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A handle to a GPU blur that may be applied to any texture.
+///
+/// Radii up to `Blur::MAX_GAUSSIAN_RADIUS` are blurred with a separable gaussian in two passes.
+/// Larger radii are first downsampled using the "dual filter" (a variation of the Kawase blur),
+/// blurred at the reduced resolution and then upsampled again. This keeps the cost of large blurs
+/// roughly constant at the expense of some accuracy.
+///
+/// GPU resources are created the first time the blur is encoded and reused thereafter. Clones of
+/// a **Blur** share the same radius and resources, allowing a handle to be cheaply moved into
+/// callbacks. A **Blur** should only be used with a single device.
+#[derive(Clone, Debug)]
+pub struct Blur {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    radius: f32,
+    gpu: Option<Gpu>,
+}
+
+// Resources created the first time the blur is encoded.
+#[derive(Debug)]
+struct Gpu {
+    shader_mod: wgpu::ShaderModule,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+    targets: Option<Targets>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    pass: Pass,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Pass {
+    Gaussian,
+    Down,
+    Up,
+}
+
+// Intermediary textures for a particular source size and format.
+#[derive(Debug)]
+struct Targets {
+    size: [u32; 2],
+    format: wgpu::TextureFormat,
+    // The multisampled source is resolved to this texture before blurring.
+    resolved: Option<wgpu::Texture>,
+    // Each level of the downsample chain, beginning at half the source size.
+    levels: Vec<wgpu::Texture>,
+    // Holds the result of the first gaussian pass at the smallest level.
+    scratch: wgpu::Texture,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Uniforms {
+    texel: [f32; 2],
+    direction: [f32; 2],
+    sigma: f32,
+    taps: i32,
+}
+
+impl Blur {
+    /// Radii larger than this are blurred at a reduced resolution using the dual filter.
+    pub const MAX_GAUSSIAN_RADIUS: f32 = 16.0;
+
+    /// A blur with the given radius in pixels.
+    ///
+    /// The radius is the distance beyond which a pixel has no noticeable influence, i.e. three
+    /// standard deviations of the gaussian.
+    pub fn new(radius: f32) -> Self {
+        let state = State { radius, gpu: None };
+        Blur {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// The radius of the blur in pixels.
+    pub fn radius(&self) -> f32 {
+        self.state.lock().expect("failed to lock blur state").radius
+    }
+
+    /// Update the radius of the blur in pixels.
+    ///
+    /// This affects all clones of the handle.
+    pub fn set_radius(&self, radius: f32) {
+        self.state.lock().expect("failed to lock blur state").radius = radius;
+    }
+
+    /// Encode the commands necessary to blur the `src` texture into the `dst` texture.
+    ///
+    /// The `src` and `dst` textures must be the same size and may refer to the same texture. The
+    /// `src` texture must have `TextureUsages::TEXTURE_BINDING` enabled and the `dst` texture
+    /// must have `TextureUsages::RENDER_ATTACHMENT` enabled.
+    pub fn encode(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+    ) {
+        self.encode_multisampled(device, encoder, src, dst, 1)
+    }
+
+    /// The same as `encode`, but for `src` and `dst` textures with the given sample count.
+    ///
+    /// If the sample count is greater than `1`, the `src` texture is first resolved to an
+    /// intermediary texture and so only requires `TextureUsages::RENDER_ATTACHMENT`.
+    pub fn encode_multisampled(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+        sample_count: u32,
+    ) {
+        let mut state = self.state.lock().expect("failed to lock blur state");
+        let State {
+            radius,
+            ref mut gpu,
+        } = *state;
+        let gpu = gpu.get_or_insert_with(|| Gpu::new(device));
+
+        let size = src.size();
+        let format = src.format();
+        let multisampled = sample_count > 1;
+        let level_count = level_count(radius, size);
+        gpu.update_targets(device, size, format, level_count, multisampled);
+
+        // Ensure all pipelines are ready before encoding.
+        let key = |pass, format, sample_count| PipelineKey {
+            pass,
+            format,
+            sample_count,
+        };
+        let last_pass = if level_count == 0 {
+            Pass::Gaussian
+        } else {
+            Pass::Up
+        };
+        let keys = [
+            key(Pass::Gaussian, format, 1),
+            key(Pass::Down, format, 1),
+            key(Pass::Up, format, 1),
+            key(last_pass, dst.format(), sample_count),
+        ];
+        for k in keys.iter() {
+            gpu.pipeline(device, *k);
+        }
+
+        let targets = gpu.targets.as_ref().expect("no blur targets");
+        let resolved = targets.resolved.as_ref().map(|t| t.view().build());
+        let levels: Vec<_> = targets.levels.iter().map(|t| t.view().build()).collect();
+        let scratch = targets.scratch.view().build();
+
+        // Resolve the multisampled source if necessary.
+        let src = match resolved {
+            None => src,
+            Some(ref resolved) => {
+                wgpu::resolve_texture(src, resolved, encoder);
+                resolved
+            }
+        };
+
+        let sigma = radius.max(0.0) / 3.0;
+        let dst_key = keys[3];
+        match levels.last() {
+            // Blur at full resolution.
+            None => {
+                let uniforms = Uniforms::gaussian(size, [1.0, 0.0], sigma);
+                gpu.encode_pass(device, encoder, keys[0], src, &scratch, uniforms);
+                let uniforms = Uniforms::gaussian(size, [0.0, 1.0], sigma);
+                gpu.encode_pass(device, encoder, dst_key, &scratch, dst, uniforms);
+            }
+
+            // Downsample, blur the smallest level, then upsample back to the destination.
+            Some(smallest) => {
+                let mut prev = src;
+                for level in &levels {
+                    let uniforms = Uniforms::texel(prev.size());
+                    gpu.encode_pass(device, encoder, keys[1], prev, level, uniforms);
+                    prev = level;
+                }
+
+                let smallest_size = smallest.size();
+                let sigma = sigma * smallest_size[0] as f32 / size[0] as f32;
+                let uniforms = Uniforms::gaussian(smallest_size, [1.0, 0.0], sigma);
+                gpu.encode_pass(device, encoder, keys[0], smallest, &scratch, uniforms);
+                let uniforms = Uniforms::gaussian(smallest_size, [0.0, 1.0], sigma);
+                gpu.encode_pass(device, encoder, keys[0], &scratch, smallest, uniforms);
+
+                for pair in levels.windows(2).rev() {
+                    let uniforms = Uniforms::texel(pair[1].size());
+                    gpu.encode_pass(device, encoder, keys[2], &pair[1], &pair[0], uniforms);
+                }
+                let uniforms = Uniforms::texel(levels[0].size());
+                gpu.encode_pass(device, encoder, dst_key, &levels[0], dst, uniforms);
+            }
+        }
+    }
+}
+
+impl Gpu {
+    fn new(device: &wgpu::Device) -> Self {
+        let shader_desc = wgpu::include_wgsl!("shaders/blur.wgsl");
+        let shader_mod = device.create_shader_module(shader_desc);
+
+        let sampler_desc = wgpu::SamplerBuilder::new().into_descriptor();
+        let sampler_filtering = wgpu::sampler_filtering(&sampler_desc);
+        let sampler = device.create_sampler(&sampler_desc);
+
+        let bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                wgpu::TextureSampleType::Float { filterable: true },
+            )
+            .sampler(wgpu::ShaderStages::FRAGMENT, sampler_filtering)
+            .uniform_buffer(wgpu::ShaderStages::FRAGMENT, false)
+            .build(device);
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("nannou blur"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        Gpu {
+            shader_mod,
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+            pipelines: HashMap::new(),
+            targets: None,
+        }
+    }
+
+    // Retrieve the pipeline for the given pass and target, creating it if necessary.
+    fn pipeline(&mut self, device: &wgpu::Device, key: PipelineKey) -> &wgpu::RenderPipeline {
+        let Gpu {
+            ref shader_mod,
+            ref pipeline_layout,
+            ref mut pipelines,
+            ..
+        } = *self;
+        pipelines.entry(key).or_insert_with(|| {
+            let entry_point = match key.pass {
+                Pass::Gaussian => "fs_gaussian",
+                Pass::Down => "fs_down",
+                Pass::Up => "fs_up",
+            };
+            wgpu::RenderPipelineBuilder::from_layout(pipeline_layout, shader_mod)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(shader_mod)
+                .fragment_entry_point(entry_point)
+                .color_format(key.format)
+                .color_blend(wgpu::BlendComponent::REPLACE)
+                .alpha_blend(wgpu::BlendComponent::REPLACE)
+                .sample_count(key.sample_count)
+                .build(device)
+        })
+    }
+
+    // Recreate the intermediary textures if the source has changed.
+    fn update_targets(
+        &mut self,
+        device: &wgpu::Device,
+        size: [u32; 2],
+        format: wgpu::TextureFormat,
+        level_count: usize,
+        multisampled: bool,
+    ) {
+        if let Some(ref targets) = self.targets {
+            if targets.size == size
+                && targets.format == format
+                && targets.levels.len() == level_count
+                && targets.resolved.is_some() == multisampled
+            {
+                return;
+            }
+        }
+        let texture = |size: [u32; 2]| {
+            wgpu::TextureBuilder::new()
+                .size(size)
+                .format(format)
+                .usage(
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                )
+                .build(device)
+        };
+        let resolved = if multisampled {
+            Some(texture(size))
+        } else {
+            None
+        };
+        let levels: Vec<_> = (1..=level_count)
+            .map(|i| texture(level_size(size, i)))
+            .collect();
+        let scratch = texture(level_size(size, level_count));
+        self.targets = Some(Targets {
+            size,
+            format,
+            resolved,
+            levels,
+            scratch,
+        });
+    }
+
+    // Encode a single full screen pass sampling `src` and writing to `dst`.
+    fn encode_pass(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        key: PipelineKey,
+        src: &wgpu::TextureViewHandle,
+        dst: &wgpu::TextureViewHandle,
+        uniforms: Uniforms,
+    ) {
+        let uniform_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou blur uniform_buffer"),
+            contents: uniforms_as_bytes(&uniforms),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = wgpu::BindGroupBuilder::new()
+            .texture_view(src)
+            .sampler(&self.sampler)
+            .buffer::<Uniforms>(&uniform_buffer, 0..1)
+            .build(device, &self.bind_group_layout);
+        let mut render_pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(dst, |color| color)
+            .begin(encoder);
+        render_pass.set_pipeline(&self.pipelines[&key]);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+impl Uniforms {
+    // Uniforms for a dual filter pass sampling a texture of the given size.
+    fn texel([w, h]: [u32; 2]) -> Self {
+        Uniforms {
+            texel: [1.0 / w as f32, 1.0 / h as f32],
+            direction: [0.0, 0.0],
+            sigma: 0.0,
+            taps: 0,
+        }
+    }
+
+    // Uniforms for a gaussian pass sampling a texture of the given size.
+    fn gaussian(size: [u32; 2], direction: [f32; 2], sigma: f32) -> Self {
+        let taps = (sigma * 3.0).ceil().min(Blur::MAX_GAUSSIAN_RADIUS) as i32;
+        Uniforms {
+            direction,
+            sigma,
+            taps,
+            ..Self::texel(size)
+        }
+    }
+}
+
+// The number of times the source is halved before blurring.
+fn level_count(radius: f32, [w, h]: [u32; 2]) -> usize {
+    if radius <= Blur::MAX_GAUSSIAN_RADIUS {
+        return 0;
+    }
+    let levels = (radius / Blur::MAX_GAUSSIAN_RADIUS).log2().ceil() as usize;
+    let max_levels = 31 - w.min(h).max(1).leading_zeros() as usize;
+    levels.min(max_levels)
+}
+
+fn level_size([w, h]: [u32; 2], level: usize) -> [u32; 2] {
+    [(w >> level).max(1), (h >> level).max(1)]
+}
+
+fn uniforms_as_bytes(uniforms: &Uniforms) -> &[u8] {
+    unsafe { wgpu::bytes::from(uniforms) }
+}
@@ -0,0 +1,82 @@
+// Shaders for the passes of `wgpu::Blur`.
+//
+// Small radii are blurred with a separable gaussian. Large radii are first downsampled with the
+// dual filter, blurred at the reduced resolution and then upsampled again.
+
+struct VertexOutput {
+    @location(0) tex_coords: vec2<f32>,
+    @builtin(position) out_pos: vec4<f32>,
+};
+
+struct Data {
+    // The size of a single texel of the source texture.
+    texel: vec2<f32>,
+    // The direction of a gaussian pass in texels.
+    direction: vec2<f32>,
+    // The standard deviation of the gaussian.
+    sigma: f32,
+    // The number of gaussian taps either side of the centre.
+    taps: i32,
+};
+
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+@group(0) @binding(1)
+var tex_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> uniforms: Data;
+
+// A single triangle covering the whole target.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32((index << 1u) & 2u);
+    let y = f32(index & 2u);
+    let tex_coords = vec2<f32>(x, y);
+    let out_pos = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    return VertexOutput(tex_coords, out_pos);
+}
+
+// One dimension of a separable gaussian blur.
+@fragment
+fn fs_gaussian(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    let step = uniforms.direction * uniforms.texel;
+    var color = textureSample(tex, tex_sampler, tex_coords);
+    var total = 1.0;
+    let k = -0.5 / max(uniforms.sigma * uniforms.sigma, 0.0001);
+    for (var i = 1; i <= uniforms.taps; i++) {
+        let x = f32(i);
+        let weight = exp(x * x * k);
+        let offset = step * x;
+        color += textureSample(tex, tex_sampler, tex_coords + offset) * weight;
+        color += textureSample(tex, tex_sampler, tex_coords - offset) * weight;
+        total += 2.0 * weight;
+    }
+    return color / total;
+}
+
+// The dual filter downsample, rendering to a target half the size of the source.
+@fragment
+fn fs_down(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    let h = uniforms.texel;
+    var color = textureSample(tex, tex_sampler, tex_coords) * 4.0;
+    color += textureSample(tex, tex_sampler, tex_coords - h);
+    color += textureSample(tex, tex_sampler, tex_coords + h);
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(h.x, -h.y));
+    color += textureSample(tex, tex_sampler, tex_coords - vec2<f32>(h.x, -h.y));
+    return color / 8.0;
+}
+
+// The dual filter upsample, rendering to a target twice the size of the source.
+@fragment
+fn fs_up(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    let h = uniforms.texel * 0.5;
+    var color = textureSample(tex, tex_sampler, tex_coords + vec2<f32>(-h.x * 2.0, 0.0));
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(-h.x, h.y)) * 2.0;
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(0.0, h.y * 2.0));
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(h.x, h.y)) * 2.0;
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(h.x * 2.0, 0.0));
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(h.x, -h.y)) * 2.0;
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(0.0, -h.y * 2.0));
+    color += textureSample(tex, tex_sampler, tex_coords + vec2<f32>(-h.x, -h.y)) * 2.0;
+    return color / 12.0;
+}
@@ -0,0 +1,79 @@
+use crate as wgpu;
+
+/// An image pass that may be applied from one texture to another.
+///
+/// **ImageFx** provides a common interface to nannou's image passes, allowing them to be chosen
+/// and chained at runtime. Each variant is a cheaply cloneable handle to its pass.
+#[derive(Clone, Debug)]
+pub enum ImageFx {
+    /// A gaussian or dual filter blur.
+    Blur(wgpu::Blur),
+    /// Histogram based auto-levels or equalization.
+    Levels(wgpu::Levels),
+}
+
+impl ImageFx {
+    /// A blur with the given radius in pixels.
+    pub fn blur(radius: f32) -> Self {
+        ImageFx::Blur(wgpu::Blur::new(radius))
+    }
+
+    /// Stretch the range of luminance to fill the output.
+    ///
+    /// See `wgpu::Levels::auto`.
+    pub fn auto_levels() -> Self {
+        ImageFx::Levels(wgpu::Levels::auto())
+    }
+
+    /// Redistribute luminance so that each level is equally common.
+    ///
+    /// See `wgpu::Levels::equalize`.
+    pub fn equalize() -> Self {
+        ImageFx::Levels(wgpu::Levels::equalize())
+    }
+
+    /// Encode the commands necessary to apply the pass from the `src` texture to the `dst`
+    /// texture.
+    ///
+    /// The `src` and `dst` textures must be the same size and may refer to the same texture.
+    pub fn encode(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+    ) {
+        self.encode_multisampled(device, encoder, src, dst, 1)
+    }
+
+    /// The same as `encode`, but for `src` and `dst` textures with the given sample count.
+    pub fn encode_multisampled(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+        sample_count: u32,
+    ) {
+        match *self {
+            ImageFx::Blur(ref blur) => {
+                blur.encode_multisampled(device, encoder, src, dst, sample_count)
+            }
+            ImageFx::Levels(ref levels) => {
+                levels.encode_multisampled(device, encoder, src, dst, sample_count)
+            }
+        }
+    }
+}
+
+impl From<wgpu::Blur> for ImageFx {
+    fn from(blur: wgpu::Blur) -> Self {
+        ImageFx::Blur(blur)
+    }
+}
+
+impl From<wgpu::Levels> for ImageFx {
+    fn from(levels: wgpu::Levels) -> Self {
+        ImageFx::Levels(levels)
+    }
+}
@@ -0,0 +1,398 @@
+use crate::{self as wgpu, util::DeviceExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A handle to a GPU pass that remaps a texture based on its luminance histogram.
+///
+/// The histogram is computed on the GPU each time the pass is encoded, so the remapping adapts to
+/// the content of the texture without any readback. This is useful for normalizing camera feeds
+/// and simulation textures that drift in range.
+///
+/// - `Levels::auto` stretches the range between the darkest and lightest pixels to fill the
+///   output, commonly known as "auto levels".
+/// - `Levels::equalize` redistributes luminance so that each level is equally common, bringing
+///   out detail in low contrast images.
+///
+/// Clones of a **Levels** share the same GPU resources, including the levels of previous frames
+/// used for smoothing. A **Levels** should only be used with a single device.
+///
+/// Requires compute shader support.
+#[derive(Clone, Debug)]
+pub struct Levels {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    equalize: bool,
+    clip: f32,
+    smoothing: f32,
+    gpu: Option<Gpu>,
+}
+
+// Resources created the first time the pass is encoded.
+#[derive(Debug)]
+struct Gpu {
+    compute_bind_group_layout: wgpu::BindGroupLayout,
+    histogram_pipeline: wgpu::ComputePipeline,
+    levels_pipeline: wgpu::ComputePipeline,
+    histogram_buffer: wgpu::Buffer,
+    levels_buffer: wgpu::Buffer,
+    levels_uniform_buffer: wgpu::Buffer,
+    remap_shader_mod: wgpu::ShaderModule,
+    remap_bind_group_layout: wgpu::BindGroupLayout,
+    remap_pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+    remap_pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+    // A copy of the source, used when resolving multisampled sources or remapping in place.
+    input: Option<wgpu::Texture>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    entry_point: &'static str,
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Params {
+    size: [u32; 2],
+    clip: f32,
+    smoothing: f32,
+    equalize: u32,
+    _pad: u32,
+}
+
+// The size of the `Levels` struct shared by the shaders.
+const LEVELS_SIZE: wgpu::BufferAddress = 16 + 64 * 16;
+const HISTOGRAM_BINS: wgpu::BufferAddress = 256;
+const WORKGROUP_SIZE: u32 = 16;
+
+impl Levels {
+    /// The default fraction of pixels clipped at each end of the range by `Levels::auto`.
+    pub const DEFAULT_CLIP: f32 = 0.005;
+
+    /// Stretch the range of luminance to fill the output.
+    ///
+    /// The black and white points ignore the darkest and lightest `DEFAULT_CLIP` fraction of
+    /// pixels so that a few outliers do not prevent the range from being stretched.
+    pub fn auto() -> Self {
+        Self::new(false)
+    }
+
+    /// Redistribute luminance so that each level is equally common.
+    ///
+    /// Colors are scaled to their equalized luminance, retaining their hue.
+    pub fn equalize() -> Self {
+        Self::new(true)
+    }
+
+    fn new(equalize: bool) -> Self {
+        let state = State {
+            equalize,
+            clip: Self::DEFAULT_CLIP,
+            smoothing: 0.0,
+            gpu: None,
+        };
+        Levels {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// The fraction of pixels clipped at each end of the range when finding the black and white
+    /// points.
+    pub fn clip(self, clip: f32) -> Self {
+        self.state.lock().expect("failed to lock levels state").clip = clip.clamp(0.0, 0.5);
+        self
+    }
+
+    /// How much of the previous levels are retained each time the pass is encoded, from `0.0` to
+    /// `1.0`.
+    ///
+    /// By default this is `0.0`. Higher values avoid sudden jumps in brightness when remapping a
+    /// sequence of frames, e.g. from a camera feed.
+    pub fn smoothing(self, smoothing: f32) -> Self {
+        self.state
+            .lock()
+            .expect("failed to lock levels state")
+            .smoothing = smoothing.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Encode the commands necessary to remap the `src` texture into the `dst` texture.
+    ///
+    /// The `src` and `dst` textures must be the same size and may refer to the same texture. The
+    /// `src` texture must have `TextureUsages::TEXTURE_BINDING` enabled and the `dst` texture
+    /// must have `TextureUsages::RENDER_ATTACHMENT` enabled.
+    pub fn encode(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+    ) {
+        self.encode_multisampled(device, encoder, src, dst, 1)
+    }
+
+    /// The same as `encode`, but for `src` and `dst` textures with the given sample count.
+    ///
+    /// If the sample count is greater than `1`, the `src` texture is first resolved to an
+    /// intermediary texture and so only requires `TextureUsages::RENDER_ATTACHMENT`.
+    pub fn encode_multisampled(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        src: &wgpu::TextureView,
+        dst: &wgpu::TextureView,
+        sample_count: u32,
+    ) {
+        let mut state = self.state.lock().expect("failed to lock levels state");
+        let State {
+            equalize,
+            clip,
+            smoothing,
+            ref mut gpu,
+        } = *state;
+        let gpu = gpu.get_or_insert_with(|| Gpu::new(device));
+
+        let size = src.size();
+        let format = src.format();
+        let key = |entry_point, format, sample_count| PipelineKey {
+            entry_point,
+            format,
+            sample_count,
+        };
+        let copy_key = key("fs_copy", format, 1);
+        let remap_key = key("fs_remap", dst.format(), sample_count);
+        gpu.remap_pipeline(device, copy_key);
+        gpu.remap_pipeline(device, remap_key);
+
+        // Copy the source if it is multisampled or is also the destination.
+        let needs_input = sample_count > 1 || src.texture_id() == dst.texture_id();
+        let input_view = match needs_input {
+            false => None,
+            true => {
+                let input = gpu.update_input(device, size, format);
+                let view = input.view().build();
+                match sample_count > 1 {
+                    true => wgpu::resolve_texture(src, &view, encoder),
+                    false => {
+                        let bind_group = gpu.remap_bind_group(device, src);
+                        gpu.encode_remap_pass(encoder, copy_key, &bind_group, &view);
+                    }
+                }
+                Some(view)
+            }
+        };
+        let src = input_view.as_ref().unwrap_or(src);
+
+        // Compute the histogram and levels.
+        let params = Params {
+            size,
+            clip,
+            smoothing,
+            equalize: equalize as u32,
+            _pad: 0,
+        };
+        let params_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou levels params_buffer"),
+            contents: params_as_bytes(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let compute_bind_group = wgpu::BindGroupBuilder::new()
+            .texture_view(src)
+            .buffer::<Params>(&params_buffer, 0..1)
+            .buffer_bytes(&gpu.histogram_buffer, 0, None)
+            .buffer_bytes(&gpu.levels_buffer, 0, None)
+            .build(device, &gpu.compute_bind_group_layout);
+        encoder.clear_buffer(&gpu.histogram_buffer, 0, None);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("nannou levels"),
+            });
+            pass.set_bind_group(0, &compute_bind_group, &[]);
+            pass.set_pipeline(&gpu.histogram_pipeline);
+            let [w, h] = size;
+            pass.dispatch_workgroups(w.div_ceil(WORKGROUP_SIZE), h.div_ceil(WORKGROUP_SIZE), 1);
+            pass.set_pipeline(&gpu.levels_pipeline);
+            pass.dispatch_workgroups(1, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(
+            &gpu.levels_buffer,
+            0,
+            &gpu.levels_uniform_buffer,
+            0,
+            LEVELS_SIZE,
+        );
+
+        // Remap the source into the destination.
+        let bind_group = gpu.remap_bind_group(device, src);
+        gpu.encode_remap_pass(encoder, remap_key, &bind_group, dst);
+    }
+}
+
+impl Gpu {
+    fn new(device: &wgpu::Device) -> Self {
+        // The compute passes.
+        let histogram_desc = wgpu::include_wgsl!("shaders/histogram.wgsl");
+        let histogram_shader_mod = device.create_shader_module(histogram_desc);
+        let compute_bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .texture(
+                wgpu::ShaderStages::COMPUTE,
+                false,
+                wgpu::TextureViewDimension::D2,
+                wgpu::TextureSampleType::Float { filterable: false },
+            )
+            .uniform_buffer(wgpu::ShaderStages::COMPUTE, false)
+            .storage_buffer(wgpu::ShaderStages::COMPUTE, false, false)
+            .storage_buffer(wgpu::ShaderStages::COMPUTE, false, false)
+            .build(device);
+        let compute_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("nannou levels compute"),
+                bind_group_layouts: &[&compute_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let compute_pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("nannou levels compute"),
+                layout: Some(&compute_pipeline_layout),
+                module: &histogram_shader_mod,
+                entry_point,
+            })
+        };
+        let histogram_pipeline = compute_pipeline("histogram_main");
+        let levels_pipeline = compute_pipeline("levels_main");
+
+        let histogram_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("nannou levels histogram_buffer"),
+            size: HISTOGRAM_BINS * 4,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let levels_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("nannou levels levels_buffer"),
+            size: LEVELS_SIZE,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let levels_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("nannou levels levels_uniform_buffer"),
+            size: LEVELS_SIZE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // The render passes.
+        let remap_desc = wgpu::include_wgsl!("shaders/remap.wgsl");
+        let remap_shader_mod = device.create_shader_module(remap_desc);
+        let sampler_desc = wgpu::SamplerBuilder::new().into_descriptor();
+        let sampler_filtering = wgpu::sampler_filtering(&sampler_desc);
+        let sampler = device.create_sampler(&sampler_desc);
+        let remap_bind_group_layout = wgpu::BindGroupLayoutBuilder::new()
+            .texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                wgpu::TextureSampleType::Float { filterable: true },
+            )
+            .sampler(wgpu::ShaderStages::FRAGMENT, sampler_filtering)
+            .uniform_buffer(wgpu::ShaderStages::FRAGMENT, false)
+            .build(device);
+        let remap_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("nannou levels remap"),
+                bind_group_layouts: &[&remap_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        Gpu {
+            compute_bind_group_layout,
+            histogram_pipeline,
+            levels_pipeline,
+            histogram_buffer,
+            levels_buffer,
+            levels_uniform_buffer,
+            remap_shader_mod,
+            remap_bind_group_layout,
+            remap_pipeline_layout,
+            sampler,
+            remap_pipelines: HashMap::new(),
+            input: None,
+        }
+    }
+
+    // Create the render pipeline for the given key if it does not yet exist.
+    fn remap_pipeline(&mut self, device: &wgpu::Device, key: PipelineKey) {
+        let Gpu {
+            ref remap_shader_mod,
+            ref remap_pipeline_layout,
+            ref mut remap_pipelines,
+            ..
+        } = *self;
+        remap_pipelines.entry(key).or_insert_with(|| {
+            wgpu::RenderPipelineBuilder::from_layout(remap_pipeline_layout, remap_shader_mod)
+                .vertex_entry_point("vs_main")
+                .fragment_shader(remap_shader_mod)
+                .fragment_entry_point(key.entry_point)
+                .color_format(key.format)
+                .color_blend(wgpu::BlendComponent::REPLACE)
+                .alpha_blend(wgpu::BlendComponent::REPLACE)
+                .sample_count(key.sample_count)
+                .build(device)
+        });
+    }
+
+    // Recreate the input texture if the source has changed.
+    fn update_input(
+        &mut self,
+        device: &wgpu::Device,
+        size: [u32; 2],
+        format: wgpu::TextureFormat,
+    ) -> &wgpu::Texture {
+        if let Some(ref input) = self.input {
+            if input.size() == size && input.format() == format {
+                return self.input.as_ref().unwrap();
+            }
+        }
+        let input = wgpu::TextureBuilder::new()
+            .size(size)
+            .format(format)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .build(device);
+        self.input.insert(input)
+    }
+
+    fn remap_bind_group(
+        &self,
+        device: &wgpu::Device,
+        src: &wgpu::TextureViewHandle,
+    ) -> wgpu::BindGroup {
+        wgpu::BindGroupBuilder::new()
+            .texture_view(src)
+            .sampler(&self.sampler)
+            .buffer_bytes(&self.levels_uniform_buffer, 0, None)
+            .build(device, &self.remap_bind_group_layout)
+    }
+
+    fn encode_remap_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        key: PipelineKey,
+        bind_group: &wgpu::BindGroup,
+        dst: &wgpu::TextureViewHandle,
+    ) {
+        let mut render_pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(dst, |color| color)
+            .begin(encoder);
+        render_pass.set_pipeline(&self.remap_pipelines[&key]);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+fn params_as_bytes(params: &Params) -> &[u8] {
+    unsafe { wgpu::bytes::from(params) }
+}
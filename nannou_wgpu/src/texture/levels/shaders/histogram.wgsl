@@ -0,0 +1,107 @@
+// Compute shaders for `wgpu::Levels`.
+//
+// The first pass accumulates a 256 bin histogram of the source texture's luminance. The second
+// pass finds the black and white points along with the cumulative distribution of luminance,
+// optionally blending them with those of previous frames.
+
+struct Params {
+    // The size of the source texture in pixels.
+    size: vec2<u32>,
+    // The fraction of pixels clipped at each end when finding the black and white points.
+    clip: f32,
+    // How much of the previous levels are retained, from 0.0 to 1.0.
+    smoothing: f32,
+    // Whether the image is to be equalized rather than stretched.
+    equalize: u32,
+};
+
+struct Levels {
+    black: f32,
+    white: f32,
+    equalize: u32,
+    initialized: u32,
+    // The cumulative distribution of luminance, four bins per element.
+    cdf: array<vec4<f32>, 64>,
+};
+
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var<uniform> params: Params;
+@group(0) @binding(2)
+var<storage, read_write> histogram: array<atomic<u32>, 256>;
+@group(0) @binding(3)
+var<storage, read_write> levels: Levels;
+
+var<workgroup> local_histogram: array<atomic<u32>, 256>;
+
+fn luminance(rgb: vec3<f32>) -> f32 {
+    return dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+@compute @workgroup_size(16, 16)
+fn histogram_main(
+    @builtin(global_invocation_id) id: vec3<u32>,
+    @builtin(local_invocation_index) index: u32,
+) {
+    atomicStore(&local_histogram[index], 0u);
+    workgroupBarrier();
+    if (id.x < params.size.x && id.y < params.size.y) {
+        let color = textureLoad(src, vec2<i32>(id.xy), 0);
+        let l = clamp(luminance(color.rgb), 0.0, 1.0);
+        let bin = min(u32(l * 256.0), 255u);
+        atomicAdd(&local_histogram[bin], 1u);
+    }
+    workgroupBarrier();
+    let count = atomicLoad(&local_histogram[index]);
+    if (count > 0u) {
+        atomicAdd(&histogram[index], count);
+    }
+}
+
+@compute @workgroup_size(1)
+fn levels_main() {
+    var total = 0u;
+    for (var i = 0u; i < 256u; i++) {
+        total += atomicLoad(&histogram[i]);
+    }
+    let n = f32(total);
+    let clip = n * params.clip;
+
+    // Find the black and white points and accumulate the distribution.
+    var black = 0.0;
+    var white = 1.0;
+    var found_black = false;
+    var found_white = false;
+    var sum = 0u;
+    var cdf: array<f32, 256>;
+    for (var i = 0u; i < 256u; i++) {
+        sum += atomicLoad(&histogram[i]);
+        let f = f32(sum);
+        cdf[i] = f / max(n, 1.0);
+        if (!found_black && f > clip) {
+            black = f32(i) / 256.0;
+            found_black = true;
+        }
+        if (!found_white && f >= n - clip) {
+            white = f32(i + 1u) / 256.0;
+            found_white = true;
+        }
+    }
+    if (total == 0u) {
+        black = 0.0;
+        white = 1.0;
+    }
+
+    // Blend with the previous levels.
+    let s = select(0.0, clamp(params.smoothing, 0.0, 1.0), levels.initialized == 1u);
+    levels.black = mix(black, levels.black, s);
+    levels.white = mix(white, levels.white, s);
+    for (var i = 0u; i < 64u; i++) {
+        let j = i * 4u;
+        let v = vec4<f32>(cdf[j], cdf[j + 1u], cdf[j + 2u], cdf[j + 3u]);
+        levels.cdf[i] = mix(v, levels.cdf[i], s);
+    }
+    levels.equalize = params.equalize;
+    levels.initialized = 1u;
+}
@@ -0,0 +1,67 @@
+// Remaps the source texture using the levels found by `histogram.wgsl`.
+
+struct VertexOutput {
+    @location(0) tex_coords: vec2<f32>,
+    @builtin(position) out_pos: vec4<f32>,
+};
+
+struct Levels {
+    black: f32,
+    white: f32,
+    equalize: u32,
+    initialized: u32,
+    // The cumulative distribution of luminance, four bins per element.
+    cdf: array<vec4<f32>, 64>,
+};
+
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+@group(0) @binding(1)
+var tex_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> levels: Levels;
+
+fn luminance(rgb: vec3<f32>) -> f32 {
+    return dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+fn cdf_at(i: u32) -> f32 {
+    return levels.cdf[i / 4u][i % 4u];
+}
+
+// A single triangle covering the whole target.
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let x = f32((index << 1u) & 2u);
+    let y = f32(index & 2u);
+    let tex_coords = vec2<f32>(x, y);
+    let out_pos = vec4<f32>(x * 2.0 - 1.0, 1.0 - y * 2.0, 0.0, 1.0);
+    return VertexOutput(tex_coords, out_pos);
+}
+
+// Copies the source so that it may be remapped in place.
+@fragment
+fn fs_copy(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    return textureSample(tex, tex_sampler, tex_coords);
+}
+
+@fragment
+fn fs_remap(@location(0) tex_coords: vec2<f32>) -> @location(0) vec4<f32> {
+    let color = textureSample(tex, tex_sampler, tex_coords);
+
+    // Map luminance through its cumulative distribution, retaining the color's hue.
+    if (levels.equalize == 1u) {
+        let l = luminance(color.rgb);
+        let x = clamp(l * 256.0 - 0.5, 0.0, 255.0);
+        let i = u32(floor(x));
+        let j = min(i + 1u, 255u);
+        let target_l = mix(cdf_at(i), cdf_at(j), x - f32(i));
+        let rgb = color.rgb * (target_l / max(l, 0.0001));
+        return vec4<f32>(rgb, color.a);
+    }
+
+    // Stretch the range between the black and white points to fill 0.0 to 1.0.
+    let range = max(levels.white - levels.black, 0.0001);
+    let rgb = max((color.rgb - vec3<f32>(levels.black)) / range, vec3<f32>(0.0));
+    return vec4<f32>(rgb, color.a);
+}
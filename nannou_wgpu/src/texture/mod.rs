@@ -2,12 +2,17 @@ use crate::{self as wgpu, RowPaddedBuffer, TextureHandle, TextureViewHandle};
 use std::ops::Deref;
 use std::sync::Arc;
 
+pub mod blur;
 #[cfg(feature = "capturer")]
 pub mod capturer;
+pub mod fx;
 #[cfg(feature = "image")]
 pub mod image;
+pub mod levels;
 pub mod reshaper;
 pub mod row_padded_buffer;
+pub mod shadertoy;
+pub mod simulation;
 
 /// Types that can produce a texture view.
 ///
@@ -581,6 +586,11 @@ impl<'a> ViewBuilder<'a> {
         self
     }
 
+    pub fn base_mip_level(mut self, base_mip_level: u32) -> Self {
+        self.info.base_mip_level = base_mip_level;
+        self
+    }
+
     /// Mip level count.
     ///
     /// If `Some`, base_mip_level + count must be less or equal to underlying texture mip count.
@@ -625,6 +635,19 @@ impl<'a> ViewBuilder<'a> {
         self.base_array_layer(layer).array_layer_count(Some(1))
     }
 
+    /// Short-hand for specifying a **TextureView** for a single given mip level.
+    ///
+    /// In other words, this is short-hand for the following:
+    ///
+    /// ```ignore
+    /// builder
+    ///     .base_mip_level(level)
+    ///     .level_count(Some(1))
+    /// ```
+    pub fn level(self, level: u32) -> Self {
+        self.base_mip_level(level).level_count(Some(1))
+    }
+
     pub fn build(self) -> TextureView {
         let descriptor = self.info.descriptor();
         TextureView {
@@ -0,0 +1,690 @@
+use crate::{self as wgpu, util::DeviceExt};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::{fmt, fs, io};
+
+/// A handle to a Shadertoy-style fragment shader, rendered over the whole of a texture.
+///
+/// The shader is written in the style of [Shadertoy](https://www.shadertoy.com), i.e. as a
+/// `mainImage` function producing the color of each pixel from its coordinates along with the
+/// familiar `iTime`, `iResolution`, `iMouse` and `iChannel0..3` inputs. This allows for quickly
+/// trying out shaders shared online, complementing the more structured ISF support of
+/// `nannou_isf`.
+///
+/// WGSL shaders must define `fn mainImage(fragCoord: vec2<f32>) -> vec4<f32>`. As WGSL has no
+/// combined image samplers, channels are sampled via `textureSample(iChannel0, iSampler, uv)`.
+/// GLSL shaders, supported via the `glsl` feature, may be used as written on Shadertoy.
+///
+/// Additional inputs may be declared via the `*_with_params` constructors, and are available to
+/// the shader as the members of `iParams`, e.g. `iParams.speed`. Any storage buffers and texture
+/// arrays declared by the `ShaderParams` are available by name, with texture arrays sampled via
+/// `iSampler` in WGSL. Note that storage buffers require a device supporting them within
+/// fragment shaders.
+///
+/// Each channel may be bound to any texture, e.g. a loaded image or a texture updated each frame
+/// with the latest frame of a video or webcam. Unbound channels are transparent black.
+///
+/// Clones of a **Shadertoy** share the same inputs, allowing a handle to be updated and encoded
+/// within a `view` function. A **Shadertoy** should only be used with the device on which it was
+/// created.
+#[derive(Clone, Debug)]
+pub struct Shadertoy {
+    state: Arc<Mutex<State>>,
+}
+
+/// Errors that might occur while loading a Shadertoy-style shader.
+#[derive(Debug)]
+pub enum ShadertoyError {
+    /// Failed to read the shader file.
+    Io(io::Error),
+    /// The file extension does not describe a supported shading language. GLSL requires the
+    /// `glsl` feature.
+    UnsupportedExtension(PathBuf),
+    /// The shader failed to parse or validate.
+    Compile(String),
+}
+
+#[derive(Debug)]
+struct State {
+    uniforms: Uniforms,
+    // The time of the last encoded frame, used to produce `iTimeDelta`.
+    last_time: Option<f32>,
+    mouse_pressed: bool,
+    channels: [Option<wgpu::TextureView>; CHANNEL_COUNT],
+    params: wgpu::ShaderParams,
+    texture_arrays: Vec<Option<wgpu::TextureView>>,
+    gpu: Gpu,
+}
+
+#[derive(Debug)]
+struct Gpu {
+    vs_mod: wgpu::ShaderModule,
+    fs_mod: wgpu::ShaderModule,
+    fs_entry_point: &'static str,
+    uniform_buffer: wgpu::Buffer,
+    params_buffer: Option<wgpu::Buffer>,
+    storage_buffers: Vec<wgpu::Buffer>,
+    // Bound to channels and texture arrays without a texture.
+    empty_view: wgpu::TextureView,
+    empty_array_view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct PipelineKey {
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+struct Uniforms {
+    resolution: [f32; 3],
+    time: f32,
+    mouse: [f32; 4],
+    date: [f32; 4],
+    channel_resolution: [[f32; 4]; CHANNEL_COUNT],
+    time_delta: f32,
+    frame_rate: f32,
+    frame: i32,
+    sample_rate: f32,
+}
+
+const CHANNEL_COUNT: usize = 4;
+// The first binding following the built-in inputs.
+const PARAMS_BINDING: u32 = 6;
+const PARAMS_STRUCT: &str = "NannouShadertoyParams";
+const PARAMS_INSTANCE: &str = "iParams";
+const WGSL_PRELUDE: &str = include_str!("shaders/prelude.wgsl");
+#[cfg(feature = "glsl")]
+const GLSL_PRELUDE: &str = include_str!("shaders/prelude.glsl");
+#[cfg(feature = "glsl")]
+const GLSL_MAIN: &str = include_str!("shaders/main.glsl");
+
+impl Shadertoy {
+    /// The sample rate reported via `iSampleRate`.
+    pub const DEFAULT_SAMPLE_RATE: f32 = 44_100.0;
+
+    /// Load a shader from the given WGSL source defining
+    /// `fn mainImage(fragCoord: vec2<f32>) -> vec4<f32>`.
+    pub fn from_wgsl(device: &wgpu::Device, source: &str) -> Result<Self, ShadertoyError> {
+        Self::from_wgsl_with_params(device, source, Default::default())
+    }
+
+    /// Load a shader from the given WGSL source with the given additional inputs.
+    pub fn from_wgsl_with_params(
+        device: &wgpu::Device,
+        source: &str,
+        params: wgpu::ShaderParams,
+    ) -> Result<Self, ShadertoyError> {
+        let source = format!("{}{}\n{}", WGSL_PRELUDE, wgsl_params(&params), source);
+        let desc = wgpu::ShaderModuleDescriptor {
+            label: Some("nannou shadertoy"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        };
+        Self::from_desc(device, desc, "nannou_shadertoy_main", params)
+    }
+
+    /// Load a shader from the given GLSL source defining
+    /// `void mainImage(out vec4 fragColor, in vec2 fragCoord)`.
+    #[cfg(feature = "glsl")]
+    pub fn from_glsl(device: &wgpu::Device, source: &str) -> Result<Self, ShadertoyError> {
+        Self::from_glsl_with_params(device, source, Default::default())
+    }
+
+    /// Load a shader from the given GLSL source with the given additional inputs.
+    #[cfg(feature = "glsl")]
+    pub fn from_glsl_with_params(
+        device: &wgpu::Device,
+        source: &str,
+        params: wgpu::ShaderParams,
+    ) -> Result<Self, ShadertoyError> {
+        let prelude = format!("{}{}", GLSL_PRELUDE, glsl_params(&params));
+        let source = format!("{}\n{}\n{}", prelude, source, GLSL_MAIN);
+        let wgsl = wgpu::glsl_to_wgsl(&source, naga::ShaderStage::Fragment).map_err(|err| {
+            // Number lines from the start of the user's source.
+            let err = match err {
+                wgpu::GlslError::Parse(errs) => {
+                    let prelude_lines = prelude.matches('\n').count() as u32 + 1;
+                    let errs = errs
+                        .into_iter()
+                        .map(|(line, msg)| (line.saturating_sub(prelude_lines), msg))
+                        .collect();
+                    wgpu::GlslError::Parse(errs)
+                }
+                err => err,
+            };
+            ShadertoyError::Compile(err.to_string())
+        })?;
+        let desc = wgpu::ShaderModuleDescriptor {
+            label: Some("nannou shadertoy"),
+            source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+        };
+        Self::from_desc(device, desc, "main", params)
+    }
+
+    /// Load a shader from the file at the given path.
+    ///
+    /// Files ending in `.wgsl` are loaded as WGSL, while those ending in `.glsl` or `.frag` are
+    /// loaded as GLSL.
+    pub fn from_path<P>(device: &wgpu::Device, path: P) -> Result<Self, ShadertoyError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_path_with_params(device, path, Default::default())
+    }
+
+    /// Load a shader from the file at the given path with the given additional inputs.
+    pub fn from_path_with_params<P>(
+        device: &wgpu::Device,
+        path: P,
+        params: wgpu::ShaderParams,
+    ) -> Result<Self, ShadertoyError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let ext = path.extension().and_then(|ext| ext.to_str());
+        match ext {
+            Some("wgsl") => {
+                let source = fs::read_to_string(path)?;
+                Self::from_wgsl_with_params(device, &source, params)
+            }
+            #[cfg(feature = "glsl")]
+            Some("glsl") | Some("frag") => {
+                let source = fs::read_to_string(path)?;
+                Self::from_glsl_with_params(device, &source, params)
+            }
+            _ => Err(ShadertoyError::UnsupportedExtension(path.to_path_buf())),
+        }
+    }
+
+    fn from_desc(
+        device: &wgpu::Device,
+        desc: wgpu::ShaderModuleDescriptor,
+        fs_entry_point: &'static str,
+        params: wgpu::ShaderParams,
+    ) -> Result<Self, ShadertoyError> {
+        // Catch errors in the user's shader rather than treating them as fatal.
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let fs_mod = device.create_shader_module(desc);
+        if let Some(err) = futures::executor::block_on(device.pop_error_scope()) {
+            return Err(ShadertoyError::Compile(err.to_string()));
+        }
+        let sampler_desc = wgpu::SamplerBuilder::new()
+            .address_mode(wgpu::AddressMode::Repeat)
+            .into_descriptor();
+        let uniforms = Uniforms {
+            sample_rate: Self::DEFAULT_SAMPLE_RATE,
+            ..Default::default()
+        };
+        let state = State {
+            uniforms,
+            last_time: None,
+            mouse_pressed: false,
+            channels: Default::default(),
+            texture_arrays: params.texture_arrays().map(|_| None).collect(),
+            gpu: Gpu::new(device, fs_mod, fs_entry_point, &sampler_desc, &params),
+            params,
+        };
+        Ok(Shadertoy {
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Bind the given texture to the channel at the given index, from `0` to `3`.
+    ///
+    /// The texture must be two dimensional with a filterable float format and
+    /// `TextureUsages::TEXTURE_BINDING` enabled. Note that textures built with a height of `1`
+    /// are one dimensional unless specified otherwise via `TextureBuilder::dimension`.
+    pub fn channel(self, index: usize, texture: &dyn wgpu::ToTextureView) -> Self {
+        self.set_channel(index, texture);
+        self
+    }
+
+    /// The sampler used for all channels.
+    ///
+    /// By default, channels are linearly filtered and repeat beyond their edges.
+    pub fn sampler(self, device: &wgpu::Device, desc: wgpu::SamplerDescriptor<'static>) -> Self {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        state.gpu.sampler = device.create_sampler(&desc);
+        drop(state);
+        self
+    }
+
+    /// Bind the given texture to the channel at the given index, from `0` to `3`.
+    ///
+    /// Indices beyond the last channel are ignored.
+    pub fn set_channel(&self, index: usize, texture: &dyn wgpu::ToTextureView) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        if let Some(channel) = state.channels.get_mut(index) {
+            *channel = Some(texture.to_texture_view());
+        }
+    }
+
+    /// Unbind the texture from the channel at the given index, if any.
+    pub fn clear_channel(&self, index: usize) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        if let Some(channel) = state.channels.get_mut(index) {
+            *channel = None;
+        }
+    }
+
+    /// Set `iTime`, the playback time in seconds.
+    ///
+    /// `iTimeDelta` and `iFrameRate` are derived from the change in time between encoded frames.
+    pub fn set_time(&self, seconds: f32) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        state.uniforms.time = seconds;
+    }
+
+    /// Update `iMouse` with the position of the mouse in pixels from the bottom left and whether
+    /// or not the mouse is pressed.
+    ///
+    /// As on Shadertoy, `iMouse.xy` follows the mouse only while it is pressed, `iMouse.zw` is
+    /// the position at which it was last pressed, `iMouse.z` is negative while released and
+    /// `iMouse.w` is negative other than on the frame the mouse was pressed.
+    pub fn set_mouse(&self, [x, y]: [f32; 2], pressed: bool) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        let [_, _, click_x, click_y] = state.uniforms.mouse;
+        let (click_x, click_y) = (click_x.abs(), click_y.abs());
+        state.uniforms.mouse = match (state.mouse_pressed, pressed) {
+            (false, true) => [x, y, x, y],
+            (true, true) => [x, y, click_x, -click_y],
+            (_, false) => {
+                let [px, py, _, _] = state.uniforms.mouse;
+                [px, py, -click_x, -click_y]
+            }
+        };
+        state.mouse_pressed = pressed;
+    }
+
+    /// Set `iDate` as the year, month (from `0`), day (from `1`) and seconds since midnight.
+    pub fn set_date(&self, date: [f32; 4]) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        state.uniforms.date = date;
+    }
+
+    /// Set `iSampleRate`, the sample rate of audio channels.
+    pub fn set_sample_rate(&self, sample_rate: f32) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        state.uniforms.sample_rate = sample_rate;
+    }
+
+    /// Set the value of the input with the given name within `iParams`.
+    ///
+    /// Inputs that were not declared when loading the shader, or values of a different type to
+    /// that declared, are ignored.
+    pub fn set_param<P>(&self, name: &str, value: P)
+    where
+        P: Into<wgpu::ShaderParam>,
+    {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        let value = value.into();
+        match state.params.get(name) {
+            Some(param) if param.ty_matches(&value) => state.params.set(name, value),
+            _ => (),
+        }
+    }
+
+    /// Set the contents of the storage buffer with the given name.
+    ///
+    /// Buffers that were not declared when loading the shader, or contents of a different element
+    /// type to that declared, are ignored.
+    pub fn set_buffer<B>(&self, name: &str, data: B)
+    where
+        B: Into<wgpu::ShaderBuffer>,
+    {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        let data = data.into();
+        match state.params.get_buffer(name) {
+            Some(buffer) if buffer.ty_matches(&data) => state.params.set_buffer(name, data),
+            _ => (),
+        }
+    }
+
+    /// Bind the given texture to the texture array with the given name.
+    ///
+    /// The view must have the `D2Array` dimension, as is the default for textures with more than
+    /// one array layer. Texture arrays that were not declared when loading the shader are
+    /// ignored.
+    pub fn set_texture_array(&self, name: &str, texture: &dyn wgpu::ToTextureView) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        let index = state.params.texture_arrays().position(|n| n == name);
+        if let Some(index) = index {
+            state.texture_arrays[index] = Some(texture.to_texture_view());
+        }
+    }
+
+    /// Reset `iFrame` to `0`, as though the shader had just been loaded.
+    pub fn restart(&self) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        state.uniforms.frame = 0;
+        state.last_time = None;
+    }
+
+    /// Encode the commands necessary to render the shader over the whole of the `dst` texture.
+    ///
+    /// The `dst` texture must have `TextureUsages::RENDER_ATTACHMENT` enabled. Each call advances
+    /// `iFrame`.
+    pub fn encode(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        dst: &wgpu::TextureView,
+    ) {
+        self.encode_multisampled(device, encoder, dst, 1)
+    }
+
+    /// The same as `encode`, but for a `dst` texture with the given sample count, e.g. a nannou
+    /// `Frame`'s texture.
+    pub fn encode_multisampled(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        dst: &wgpu::TextureView,
+        sample_count: u32,
+    ) {
+        let mut state = self.state.lock().expect("failed to lock shadertoy state");
+        let State {
+            ref mut uniforms,
+            ref mut last_time,
+            ref channels,
+            ref params,
+            ref texture_arrays,
+            ref mut gpu,
+            ..
+        } = *state;
+
+        // Update the inputs derived from the destination, channels and time.
+        let [w, h] = dst.size();
+        uniforms.resolution = [w as f32, h as f32, 1.0];
+        for (res, channel) in uniforms.channel_resolution.iter_mut().zip(channels) {
+            *res = match channel {
+                Some(view) => {
+                    let [w, h] = view.size();
+                    [w as f32, h as f32, 1.0, 0.0]
+                }
+                None => [0.0; 4],
+            };
+        }
+        uniforms.time_delta = last_time.map(|t| uniforms.time - t).unwrap_or(0.0);
+        uniforms.frame_rate = match uniforms.time_delta > 0.0 {
+            true => 1.0 / uniforms.time_delta,
+            false => 0.0,
+        };
+        *last_time = Some(uniforms.time);
+
+        let uniforms_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou shadertoy uniforms_buffer"),
+            contents: uniforms_as_bytes(uniforms),
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        let size = std::mem::size_of::<Uniforms>() as wgpu::BufferAddress;
+        encoder.copy_buffer_to_buffer(&uniforms_buffer, 0, &gpu.uniform_buffer, 0, size);
+        if let Some(ref params_buffer) = gpu.params_buffer {
+            params.upload(device, encoder, params_buffer);
+        }
+        for ((_, data), buffer) in params.buffers().zip(&mut gpu.storage_buffers) {
+            let bytes = data.to_bytes();
+            let size = bytes.len() as wgpu::BufferAddress;
+            if buffer.size() != size {
+                *buffer = create_storage_buffer(device, size);
+            }
+            let staging = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+                label: Some("nannou shadertoy storage_staging_buffer"),
+                contents: &bytes,
+                usage: wgpu::BufferUsages::COPY_SRC,
+            });
+            encoder.copy_buffer_to_buffer(&staging, 0, buffer, 0, size);
+        }
+
+        let key = PipelineKey {
+            format: dst.format(),
+            sample_count,
+        };
+        gpu.pipeline(device, key);
+        let mut builder =
+            wgpu::BindGroupBuilder::new().buffer::<Uniforms>(&gpu.uniform_buffer, 0..1);
+        for channel in channels {
+            builder = builder.texture_view(channel.as_ref().unwrap_or(&gpu.empty_view));
+        }
+        builder = builder.sampler(&gpu.sampler);
+        if let Some(ref params_buffer) = gpu.params_buffer {
+            builder = builder.buffer_bytes(params_buffer, 0, None);
+        }
+        for buffer in &gpu.storage_buffers {
+            builder = builder.buffer_bytes(buffer, 0, None);
+        }
+        for view in texture_arrays {
+            builder = builder.texture_view(view.as_ref().unwrap_or(&gpu.empty_array_view));
+        }
+        let bind_group = builder.build(device, &gpu.bind_group_layout);
+        let mut render_pass = wgpu::RenderPassBuilder::new()
+            .color_attachment(dst, |color| color)
+            .begin(encoder);
+        render_pass.set_pipeline(&gpu.pipelines[&key]);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        drop(render_pass);
+
+        uniforms.frame += 1;
+    }
+}
+
+impl Gpu {
+    fn new(
+        device: &wgpu::Device,
+        fs_mod: wgpu::ShaderModule,
+        fs_entry_point: &'static str,
+        sampler_desc: &wgpu::SamplerDescriptor,
+        params: &wgpu::ShaderParams,
+    ) -> Self {
+        let vs_mod = device.create_shader_module(wgpu::include_wgsl!("shaders/vs.wgsl"));
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("nannou shadertoy uniform_buffer"),
+            size: std::mem::size_of::<Uniforms>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params_buffer = if params.is_empty() {
+            None
+        } else {
+            Some(params.create_uniform_buffer(device))
+        };
+        let storage_buffers = params
+            .buffers()
+            .map(|(_, data)| create_storage_buffer(device, data.to_bytes().len() as _))
+            .collect();
+        let empty_texture = wgpu::TextureBuilder::new()
+            .size([1, 1])
+            .dimension(wgpu::TextureDimension::D2)
+            .format(wgpu::TextureFormat::Rgba8Unorm)
+            .usage(wgpu::TextureUsages::TEXTURE_BINDING)
+            .build(device);
+        let empty_view = empty_texture.view().build();
+        let empty_array_view = empty_texture
+            .view()
+            .dimension(wgpu::TextureViewDimension::D2Array)
+            .build();
+        let sampler = device.create_sampler(sampler_desc);
+        let sample_type = wgpu::TextureSampleType::Float { filterable: true };
+        let mut layout_builder =
+            wgpu::BindGroupLayoutBuilder::new().uniform_buffer(wgpu::ShaderStages::FRAGMENT, false);
+        for _ in 0..CHANNEL_COUNT {
+            layout_builder = layout_builder.texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2,
+                sample_type,
+            );
+        }
+        layout_builder = layout_builder.sampler(wgpu::ShaderStages::FRAGMENT, true);
+        if params_buffer.is_some() {
+            layout_builder = layout_builder.uniform_buffer(wgpu::ShaderStages::FRAGMENT, false);
+        }
+        for _ in params.buffers() {
+            layout_builder =
+                layout_builder.storage_buffer(wgpu::ShaderStages::FRAGMENT, false, true);
+        }
+        for _ in params.texture_arrays() {
+            layout_builder = layout_builder.texture(
+                wgpu::ShaderStages::FRAGMENT,
+                false,
+                wgpu::TextureViewDimension::D2Array,
+                sample_type,
+            );
+        }
+        let bind_group_layout = layout_builder.build(device);
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("nannou shadertoy"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        Gpu {
+            vs_mod,
+            fs_mod,
+            fs_entry_point,
+            uniform_buffer,
+            params_buffer,
+            storage_buffers,
+            empty_view,
+            empty_array_view,
+            sampler,
+            bind_group_layout,
+            pipeline_layout,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    // Create the pipeline for the given destination if it does not yet exist.
+    fn pipeline(&mut self, device: &wgpu::Device, key: PipelineKey) {
+        let Gpu {
+            ref vs_mod,
+            ref fs_mod,
+            fs_entry_point,
+            ref pipeline_layout,
+            ..
+        } = *self;
+        self.pipelines.entry(key).or_insert_with(|| {
+            wgpu::RenderPipelineBuilder::from_layout(pipeline_layout, vs_mod)
+                .fragment_shader(fs_mod)
+                .fragment_entry_point(fs_entry_point)
+                .color_format(key.format)
+                .color_blend(wgpu::BlendComponent::REPLACE)
+                .alpha_blend(wgpu::BlendComponent::REPLACE)
+                .sample_count(key.sample_count)
+                .build(device)
+        });
+    }
+}
+
+impl From<io::Error> for ShadertoyError {
+    fn from(err: io::Error) -> Self {
+        ShadertoyError::Io(err)
+    }
+}
+
+impl fmt::Display for ShadertoyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShadertoyError::Io(err) => write!(f, "failed to read shader: {}", err),
+            ShadertoyError::UnsupportedExtension(path) => {
+                write!(f, "unsupported shader file extension: {}", path.display())
+            }
+            ShadertoyError::Compile(err) => write!(f, "failed to compile shader: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ShadertoyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShadertoyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+fn create_storage_buffer(device: &wgpu::Device, size: wgpu::BufferAddress) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("nannou shadertoy storage_buffer"),
+        size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+// WGSL declarations of the user's inputs, bound in order following the built-in inputs.
+fn wgsl_params(params: &wgpu::ShaderParams) -> String {
+    let mut s = String::new();
+    let mut binding = PARAMS_BINDING;
+    if !params.is_empty() {
+        s.push_str(&params.wgsl_struct(PARAMS_STRUCT));
+        s.push_str(&format!(
+            "@group(0) @binding({})\nvar<uniform> {}: {};\n",
+            binding, PARAMS_INSTANCE, PARAMS_STRUCT
+        ));
+        binding += 1;
+    }
+    for (name, data) in params.buffers() {
+        s.push_str(&format!(
+            "@group(0) @binding({})\nvar<storage, read> {}: array<{}>;\n",
+            binding,
+            name,
+            data.wgsl_element_type()
+        ));
+        binding += 1;
+    }
+    for name in params.texture_arrays() {
+        s.push_str(&format!(
+            "@group(0) @binding({})\nvar {}: texture_2d_array<f32>;\n",
+            binding, name
+        ));
+        binding += 1;
+    }
+    s
+}
+
+// GLSL declarations of the user's inputs, bound in order following the built-in inputs.
+#[cfg(feature = "glsl")]
+fn glsl_params(params: &wgpu::ShaderParams) -> String {
+    let mut s = String::new();
+    let mut binding = PARAMS_BINDING;
+    if !params.is_empty() {
+        s.push_str(&params.glsl_block(0, binding, PARAMS_STRUCT, PARAMS_INSTANCE));
+        binding += 1;
+    }
+    for (name, data) in params.buffers() {
+        s.push_str(&format!(
+            "layout(set = 0, binding = {}, std430) readonly buffer NannouShadertoyBuffer{} {{\n",
+            binding, binding
+        ));
+        s.push_str(&format!(
+            "    {} {}[];\n}};\n",
+            data.glsl_element_type(),
+            name
+        ));
+        binding += 1;
+    }
+    for name in params.texture_arrays() {
+        s.push_str(&format!(
+            "layout(set = 0, binding = {}) uniform texture2DArray nannou_{};\n",
+            binding, name
+        ));
+        s.push_str(&format!(
+            "#define {} sampler2DArray(nannou_{}, nannou_sampler)\n",
+            name, name
+        ));
+        binding += 1;
+    }
+    s
+}
+
+fn uniforms_as_bytes(uniforms: &Uniforms) -> &[u8] {
+    unsafe { wgpu::bytes::from(uniforms) }
+}
@@ -0,0 +1,11 @@
+
+// The entry point appended to Shadertoy-style GLSL shaders.
+
+layout(location = 0) out vec4 nannou_frag_color;
+
+void main() {
+    vec4 color = vec4(0.0);
+    // Shadertoy's origin is the bottom left.
+    mainImage(color, vec2(gl_FragCoord.x, iResolution.y - gl_FragCoord.y));
+    nannou_frag_color = color;
+}
@@ -0,0 +1,28 @@
+#version 450
+
+// Declarations prepended to Shadertoy-style GLSL shaders.
+//
+// The source that follows must define `void mainImage(out vec4 fragColor, in vec2 fragCoord)`.
+
+layout(set = 0, binding = 0) uniform NannouShadertoyUniforms {
+    vec3 iResolution;
+    float iTime;
+    vec4 iMouse;
+    vec4 iDate;
+    vec3 iChannelResolution[4];
+    float iTimeDelta;
+    float iFrameRate;
+    int iFrame;
+    float iSampleRate;
+};
+layout(set = 0, binding = 1) uniform texture2D nannou_channel0;
+layout(set = 0, binding = 2) uniform texture2D nannou_channel1;
+layout(set = 0, binding = 3) uniform texture2D nannou_channel2;
+layout(set = 0, binding = 4) uniform texture2D nannou_channel3;
+layout(set = 0, binding = 5) uniform sampler nannou_sampler;
+
+#define iChannel0 sampler2D(nannou_channel0, nannou_sampler)
+#define iChannel1 sampler2D(nannou_channel1, nannou_sampler)
+#define iChannel2 sampler2D(nannou_channel2, nannou_sampler)
+#define iChannel3 sampler2D(nannou_channel3, nannou_sampler)
+
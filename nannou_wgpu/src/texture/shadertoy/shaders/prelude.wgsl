@@ -0,0 +1,58 @@
+// Declarations prepended to Shadertoy-style WGSL shaders.
+//
+// The source that follows must define `fn mainImage(fragCoord: vec2<f32>) -> vec4<f32>`. The
+// familiar Shadertoy inputs are available as module-scope variables, along with the `iChannel0`
+// to `iChannel3` textures and the `iSampler` with which they may be sampled.
+
+struct NannouShadertoyUniforms {
+    resolution: vec3<f32>,
+    time: f32,
+    mouse: vec4<f32>,
+    date: vec4<f32>,
+    channel_resolution: array<vec4<f32>, 4>,
+    time_delta: f32,
+    frame_rate: f32,
+    frame: i32,
+    sample_rate: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> nannou_shadertoy: NannouShadertoyUniforms;
+@group(0) @binding(1)
+var iChannel0: texture_2d<f32>;
+@group(0) @binding(2)
+var iChannel1: texture_2d<f32>;
+@group(0) @binding(3)
+var iChannel2: texture_2d<f32>;
+@group(0) @binding(4)
+var iChannel3: texture_2d<f32>;
+@group(0) @binding(5)
+var iSampler: sampler;
+
+var<private> iResolution: vec3<f32>;
+var<private> iTime: f32;
+var<private> iTimeDelta: f32;
+var<private> iFrameRate: f32;
+var<private> iFrame: i32;
+var<private> iMouse: vec4<f32>;
+var<private> iDate: vec4<f32>;
+var<private> iChannelResolution: array<vec3<f32>, 4>;
+var<private> iSampleRate: f32;
+
+@fragment
+fn nannou_shadertoy_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let u = nannou_shadertoy;
+    iResolution = u.resolution;
+    iTime = u.time;
+    iTimeDelta = u.time_delta;
+    iFrameRate = u.frame_rate;
+    iFrame = u.frame;
+    iMouse = u.mouse;
+    iDate = u.date;
+    for (var i = 0; i < 4; i++) {
+        iChannelResolution[i] = nannou_shadertoy.channel_resolution[i].xyz;
+    }
+    iSampleRate = u.sample_rate;
+    // Shadertoy's origin is the bottom left.
+    return mainImage(vec2<f32>(position.x, iResolution.y - position.y));
+}
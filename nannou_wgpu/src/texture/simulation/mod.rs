@@ -0,0 +1,491 @@
+use crate::{self as wgpu, util::DeviceExt};
+use std::sync::{Arc, Mutex};
+
+/// A handle to a grid of cells simulated within a compute shader, e.g. reaction-diffusion or the
+/// Game of Life.
+///
+/// The state of each cell is stored within a pair of textures that are swapped each step, known
+/// as "double buffering". After stepping, the state is mapped to a gradient between two colors
+/// and written to the output `texture`, ready for drawing via `draw.texture()`. The edges of the
+/// grid wrap around.
+///
+/// Clones of a **Simulation** share the same state, allowing a handle to be stepped within a
+/// `view` function. A **Simulation** should only be used with the device on which it was
+/// created.
+///
+/// Requires compute shader support.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    state: Arc<Mutex<State>>,
+    output: wgpu::Texture,
+}
+
+/// The rule by which the state of each cell is updated.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rule {
+    GrayScott(GrayScott),
+    GameOfLife(GameOfLife),
+    SmoothLife(SmoothLife),
+}
+
+/// The Gray-Scott model of two chemicals, *A* and *B*, diffusing and reacting with one another.
+///
+/// Small changes to the feed and kill rates produce a wide variety of spots, stripes and
+/// labyrinths. Cells are seeded with the concentration of *B*, shown when colorized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GrayScott {
+    /// The rate at which *A* is replenished.
+    pub feed: f32,
+    /// The rate at which *B* is removed.
+    pub kill: f32,
+    /// The rate at which *A* diffuses.
+    pub diffusion_a: f32,
+    /// The rate at which *B* diffuses.
+    pub diffusion_b: f32,
+    /// The time step of each update.
+    pub dt: f32,
+}
+
+/// Conway's Game of Life, or any other "life-like" cellular automaton.
+///
+/// Cells are either alive or dead. Seeded values greater than `0.5` are alive.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GameOfLife {
+    /// A bit mask of the numbers of living neighbours for which a dead cell is born.
+    pub birth: u32,
+    /// A bit mask of the numbers of living neighbours for which a living cell survives.
+    pub survival: u32,
+}
+
+/// SmoothLife, a continuous generalisation of the Game of Life producing gliding, organic forms.
+///
+/// The state of each cell is integrated over a disk and the surrounding ring, with the ring's
+/// fill determining whether the cell is born or dies. Seeded values range from `0.0` to `1.0`.
+/// The cost of each step grows with the square of `outer_radius`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SmoothLife {
+    /// The radius of the disk around each cell in cells.
+    pub inner_radius: f32,
+    /// The outer radius of the ring around each cell in cells.
+    pub outer_radius: f32,
+    /// The range of ring fill for which a cell is born.
+    pub birth: [f32; 2],
+    /// The range of ring fill for which a cell survives.
+    pub death: [f32; 2],
+    /// The smoothness of the transition between the birth and death ranges.
+    pub alpha_n: f32,
+    /// The smoothness of the transition between living and dead cells.
+    pub alpha_m: f32,
+    /// The time step of each update.
+    pub dt: f32,
+}
+
+#[derive(Debug)]
+struct State {
+    rule: Rule,
+    colors: [[f32; 4]; 2],
+    // The textures holding the state, along with the index of the current state.
+    cells: [wgpu::Texture; 2],
+    current: usize,
+    gpu: Gpu,
+}
+
+#[derive(Debug)]
+struct Gpu {
+    step_bind_group_layout: wgpu::BindGroupLayout,
+    colorize_bind_group_layout: wgpu::BindGroupLayout,
+    gray_scott_pipeline: wgpu::ComputePipeline,
+    game_of_life_pipeline: wgpu::ComputePipeline,
+    smooth_life_pipeline: wgpu::ComputePipeline,
+    colorize_pipeline: wgpu::ComputePipeline,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct Params {
+    size: [u32; 2],
+    birth: u32,
+    survival: u32,
+    feed: f32,
+    kill: f32,
+    diffusion_a: f32,
+    diffusion_b: f32,
+    dt: f32,
+    inner_radius: f32,
+    outer_radius: f32,
+    alpha_n: f32,
+    alpha_m: f32,
+    birth_lo: f32,
+    birth_hi: f32,
+    death_lo: f32,
+    death_hi: f32,
+    channel: u32,
+    _pad: [u32; 2],
+    low: [f32; 4],
+    high: [f32; 4],
+}
+
+const STATE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba32Float;
+const WORKGROUP_SIZE: u32 = 8;
+
+impl Simulation {
+    /// The format of the output texture.
+    pub const OUTPUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+    /// The default colors of cells with values of `0.0` and `1.0`, i.e. black and white.
+    pub const DEFAULT_COLORS: [[f32; 4]; 2] = [[0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 1.0]];
+
+    /// A simulation of the given rule over a grid of the given size, in which all values are
+    /// `0.0`.
+    pub fn new<R>(device: &wgpu::Device, size: [u32; 2], rule: R) -> Self
+    where
+        R: Into<Rule>,
+    {
+        let cells_texture = || {
+            wgpu::TextureBuilder::new()
+                .size(size)
+                .format(STATE_FORMAT)
+                .usage(
+                    wgpu::TextureUsages::STORAGE_BINDING
+                        | wgpu::TextureUsages::TEXTURE_BINDING
+                        | wgpu::TextureUsages::COPY_DST,
+                )
+                .build(device)
+        };
+        let cells = [cells_texture(), cells_texture()];
+        let output = wgpu::TextureBuilder::new()
+            .size(size)
+            .format(Self::OUTPUT_FORMAT)
+            .usage(
+                wgpu::TextureUsages::STORAGE_BINDING
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC,
+            )
+            .build(device);
+        let state = State {
+            rule: rule.into(),
+            colors: Self::DEFAULT_COLORS,
+            cells,
+            current: 0,
+            gpu: Gpu::new(device),
+        };
+        Simulation {
+            state: Arc::new(Mutex::new(state)),
+            output,
+        }
+    }
+
+    /// A Gray-Scott reaction-diffusion simulation with the default parameters.
+    pub fn gray_scott(device: &wgpu::Device, size: [u32; 2]) -> Self {
+        Self::new(device, size, GrayScott::default())
+    }
+
+    /// A Game of Life simulation with Conway's rules.
+    pub fn game_of_life(device: &wgpu::Device, size: [u32; 2]) -> Self {
+        Self::new(device, size, GameOfLife::default())
+    }
+
+    /// A SmoothLife simulation with the default parameters.
+    pub fn smooth_life(device: &wgpu::Device, size: [u32; 2]) -> Self {
+        Self::new(device, size, SmoothLife::default())
+    }
+
+    /// The colors of cells with values of `0.0` and `1.0` as linear RGBA. Values in between are
+    /// linearly interpolated.
+    pub fn colors(self, low: [f32; 4], high: [f32; 4]) -> Self {
+        self.set_colors(low, high);
+        self
+    }
+
+    /// The size of the grid in cells.
+    pub fn size(&self) -> [u32; 2] {
+        self.output.size()
+    }
+
+    /// The texture to which the colorized state is written each step.
+    pub fn texture(&self) -> &wgpu::Texture {
+        &self.output
+    }
+
+    /// The rule by which the state of each cell is updated.
+    pub fn rule(&self) -> Rule {
+        self.state
+            .lock()
+            .expect("failed to lock simulation state")
+            .rule
+    }
+
+    /// Change the rule by which the state of each cell is updated, retaining the current state.
+    pub fn set_rule<R>(&self, rule: R)
+    where
+        R: Into<Rule>,
+    {
+        self.state
+            .lock()
+            .expect("failed to lock simulation state")
+            .rule = rule.into();
+    }
+
+    /// Change the colors of cells with values of `0.0` and `1.0` as linear RGBA.
+    pub fn set_colors(&self, low: [f32; 4], high: [f32; 4]) {
+        self.state
+            .lock()
+            .expect("failed to lock simulation state")
+            .colors = [low, high];
+    }
+
+    /// Encode the commands necessary to replace the state of each cell with the value produced by
+    /// the given function of the cell's column and row, counting from the top left.
+    pub fn seed<F>(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, mut f: F)
+    where
+        F: FnMut(u32, u32) -> f32,
+    {
+        let state = self.state.lock().expect("failed to lock simulation state");
+        let [w, h] = self.size();
+        let mut data = Vec::with_capacity((w * h * 4) as usize);
+        for row in 0..h {
+            for col in 0..w {
+                let v = f(col, row);
+                let cell = match state.rule {
+                    // Space is initially filled with A, into which B is seeded.
+                    Rule::GrayScott(_) => [1.0 - v, v, 0.0, 1.0],
+                    Rule::GameOfLife(_) | Rule::SmoothLife(_) => [v, 0.0, 0.0, 1.0],
+                };
+                data.extend_from_slice(&cell);
+            }
+        }
+        let bytes = unsafe { wgpu::bytes::from_slice(&data) };
+        state.cells[state.current].upload_data(device, encoder, bytes);
+    }
+
+    /// Encode the commands necessary to advance the simulation by the given number of steps and
+    /// write the result to the output `texture`.
+    pub fn step(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, steps: u32) {
+        let mut state = self.state.lock().expect("failed to lock simulation state");
+        let params = state.params(self.size());
+        let params_buffer = device.create_buffer_init(&wgpu::BufferInitDescriptor {
+            label: Some("nannou simulation params_buffer"),
+            contents: params_as_bytes(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let views = [state.cells[0].view().build(), state.cells[1].view().build()];
+        let step_bind_group = |src: usize| {
+            wgpu::BindGroupBuilder::new()
+                .texture_view(&views[src])
+                .texture_view(&views[1 - src])
+                .buffer::<Params>(&params_buffer, 0..1)
+                .build(device, &state.gpu.step_bind_group_layout)
+        };
+        let bind_groups = [step_bind_group(0), step_bind_group(1)];
+        let output_view = self.output.view().build();
+        let pipeline = match state.rule {
+            Rule::GrayScott(_) => &state.gpu.gray_scott_pipeline,
+            Rule::GameOfLife(_) => &state.gpu.game_of_life_pipeline,
+            Rule::SmoothLife(_) => &state.gpu.smooth_life_pipeline,
+        };
+        let [w, h] = self.size();
+        let workgroups = [w.div_ceil(WORKGROUP_SIZE), h.div_ceil(WORKGROUP_SIZE)];
+        // Each step swaps the roles of the two textures.
+        let last = (state.current + steps as usize) % 2;
+        let colorize_bind_group = wgpu::BindGroupBuilder::new()
+            .texture_view(&views[last])
+            .texture_view(&output_view)
+            .buffer::<Params>(&params_buffer, 0..1)
+            .build(device, &state.gpu.colorize_bind_group_layout);
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("nannou simulation"),
+            });
+            pass.set_pipeline(pipeline);
+            for step in 0..steps as usize {
+                pass.set_bind_group(0, &bind_groups[(state.current + step) % 2], &[]);
+                pass.dispatch_workgroups(workgroups[0], workgroups[1], 1);
+            }
+            pass.set_pipeline(&state.gpu.colorize_pipeline);
+            pass.set_bind_group(0, &colorize_bind_group, &[]);
+            pass.dispatch_workgroups(workgroups[0], workgroups[1], 1);
+        }
+        state.current = last;
+    }
+}
+
+impl State {
+    fn params(&self, size: [u32; 2]) -> Params {
+        let [low, high] = self.colors;
+        let mut params = Params {
+            size,
+            birth: 0,
+            survival: 0,
+            feed: 0.0,
+            kill: 0.0,
+            diffusion_a: 0.0,
+            diffusion_b: 0.0,
+            dt: 0.0,
+            inner_radius: 0.0,
+            outer_radius: 0.0,
+            alpha_n: 0.0,
+            alpha_m: 0.0,
+            birth_lo: 0.0,
+            birth_hi: 0.0,
+            death_lo: 0.0,
+            death_hi: 0.0,
+            channel: 0,
+            _pad: [0; 2],
+            low,
+            high,
+        };
+        match self.rule {
+            Rule::GrayScott(gs) => {
+                params.feed = gs.feed;
+                params.kill = gs.kill;
+                params.diffusion_a = gs.diffusion_a;
+                params.diffusion_b = gs.diffusion_b;
+                params.dt = gs.dt;
+                params.channel = 1;
+            }
+            Rule::GameOfLife(gol) => {
+                params.birth = gol.birth;
+                params.survival = gol.survival;
+            }
+            Rule::SmoothLife(sl) => {
+                params.inner_radius = sl.inner_radius;
+                params.outer_radius = sl.outer_radius.max(sl.inner_radius);
+                params.alpha_n = sl.alpha_n;
+                params.alpha_m = sl.alpha_m;
+                params.birth_lo = sl.birth[0];
+                params.birth_hi = sl.birth[1];
+                params.death_lo = sl.death[0];
+                params.death_hi = sl.death[1];
+                params.dt = sl.dt;
+            }
+        }
+        params
+    }
+}
+
+impl Gpu {
+    fn new(device: &wgpu::Device) -> Self {
+        let desc = wgpu::include_wgsl!("shaders/simulation.wgsl");
+        let shader_mod = device.create_shader_module(desc);
+        let bind_group_layout = |dst_format| {
+            wgpu::BindGroupLayoutBuilder::new()
+                .texture(
+                    wgpu::ShaderStages::COMPUTE,
+                    false,
+                    wgpu::TextureViewDimension::D2,
+                    wgpu::TextureSampleType::Float { filterable: false },
+                )
+                .storage_texture(
+                    wgpu::ShaderStages::COMPUTE,
+                    dst_format,
+                    wgpu::TextureViewDimension::D2,
+                    wgpu::StorageTextureAccess::WriteOnly,
+                )
+                .uniform_buffer(wgpu::ShaderStages::COMPUTE, false)
+                .build(device)
+        };
+        let step_bind_group_layout = bind_group_layout(STATE_FORMAT);
+        let colorize_bind_group_layout = bind_group_layout(Simulation::OUTPUT_FORMAT);
+        let compute_pipeline = |layout, entry_point| {
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("nannou simulation"),
+                bind_group_layouts: &[layout],
+                push_constant_ranges: &[],
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("nannou simulation"),
+                layout: Some(&pipeline_layout),
+                module: &shader_mod,
+                entry_point,
+            })
+        };
+        let gray_scott_pipeline = compute_pipeline(&step_bind_group_layout, "gray_scott");
+        let game_of_life_pipeline = compute_pipeline(&step_bind_group_layout, "game_of_life");
+        let smooth_life_pipeline = compute_pipeline(&step_bind_group_layout, "smooth_life");
+        let colorize_pipeline = compute_pipeline(&colorize_bind_group_layout, "colorize");
+        Gpu {
+            step_bind_group_layout,
+            colorize_bind_group_layout,
+            gray_scott_pipeline,
+            game_of_life_pipeline,
+            smooth_life_pipeline,
+            colorize_pipeline,
+        }
+    }
+}
+
+impl GameOfLife {
+    /// A life-like automaton in which dead cells with any of the `birth` numbers of living
+    /// neighbours are born and living cells with any of the `survival` numbers survive.
+    ///
+    /// For example, Conway's rules, commonly written "B3/S23", are described by
+    /// `GameOfLife::new(&[3], &[2, 3])`. Numbers greater than `8` are ignored.
+    pub fn new(birth: &[u32], survival: &[u32]) -> Self {
+        let mask = |counts: &[u32]| {
+            counts
+                .iter()
+                .filter(|&&n| n <= 8)
+                .fold(0, |mask, &n| mask | 1 << n)
+        };
+        GameOfLife {
+            birth: mask(birth),
+            survival: mask(survival),
+        }
+    }
+}
+
+impl Default for GrayScott {
+    /// Parameters producing a labyrinth of coral-like growth.
+    fn default() -> Self {
+        GrayScott {
+            feed: 0.0545,
+            kill: 0.062,
+            diffusion_a: 1.0,
+            diffusion_b: 0.5,
+            dt: 1.0,
+        }
+    }
+}
+
+impl Default for GameOfLife {
+    /// Conway's rules, "B3/S23".
+    fn default() -> Self {
+        GameOfLife::new(&[3], &[2, 3])
+    }
+}
+
+impl Default for SmoothLife {
+    /// The parameters of Stephan Rafler's original SmoothLife paper, at a reduced radius.
+    fn default() -> Self {
+        SmoothLife {
+            inner_radius: 4.0,
+            outer_radius: 12.0,
+            birth: [0.278, 0.365],
+            death: [0.267, 0.445],
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+            dt: 0.1,
+        }
+    }
+}
+
+impl From<GrayScott> for Rule {
+    fn from(rule: GrayScott) -> Self {
+        Rule::GrayScott(rule)
+    }
+}
+
+impl From<GameOfLife> for Rule {
+    fn from(rule: GameOfLife) -> Self {
+        Rule::GameOfLife(rule)
+    }
+}
+
+impl From<SmoothLife> for Rule {
+    fn from(rule: SmoothLife) -> Self {
+        Rule::SmoothLife(rule)
+    }
+}
+
+fn params_as_bytes(params: &Params) -> &[u8] {
+    unsafe { wgpu::bytes::from(params) }
+}
@@ -0,0 +1,156 @@
+// Compute shaders for `wgpu::Simulation`.
+//
+// Each step reads the state of every cell from `src` and writes the next state to `dst`, wrapping
+// around the edges of the grid. The `colorize` pass maps the state to a color for display.
+
+struct Params {
+    // The size of the grid in cells.
+    size: vec2<u32>,
+    // Game of Life: bit masks of the neighbour counts for which cells are born or survive.
+    birth: u32,
+    survival: u32,
+    // Gray-Scott.
+    feed: f32,
+    kill: f32,
+    diffusion_a: f32,
+    diffusion_b: f32,
+    // The time step for Gray-Scott and SmoothLife.
+    dt: f32,
+    // SmoothLife.
+    inner_radius: f32,
+    outer_radius: f32,
+    alpha_n: f32,
+    alpha_m: f32,
+    birth_lo: f32,
+    birth_hi: f32,
+    death_lo: f32,
+    death_hi: f32,
+    // The channel of the state shown by `colorize`.
+    channel: u32,
+    low: vec4<f32>,
+    high: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var src: texture_2d<f32>;
+@group(0) @binding(1)
+var dst: texture_storage_2d<rgba32float, write>;
+@group(0) @binding(2)
+var<uniform> params: Params;
+
+@group(0) @binding(1)
+var output: texture_storage_2d<rgba8unorm, write>;
+
+// The state of the cell at the given offset from `id`, wrapping around the edges.
+//
+// Whole multiples of the size are added before taking the remainder, as `%` with a negative
+// operand is undefined on some backends.
+fn cell(id: vec2<u32>, offset: vec2<i32>) -> vec4<f32> {
+    let size = vec2<i32>(params.size);
+    let p = vec2<i32>(id) + offset + size * (abs(offset) / size + 1);
+    return textureLoad(src, p % size, 0);
+}
+
+fn in_bounds(id: vec3<u32>) -> bool {
+    return id.x < params.size.x && id.y < params.size.y;
+}
+
+@compute @workgroup_size(8, 8)
+fn gray_scott(@builtin(global_invocation_id) id: vec3<u32>) {
+    if !in_bounds(id) {
+        return;
+    }
+    let c = cell(id.xy, vec2<i32>(0, 0)).xy;
+    let adjacent = cell(id.xy, vec2<i32>(1, 0)).xy
+        + cell(id.xy, vec2<i32>(-1, 0)).xy
+        + cell(id.xy, vec2<i32>(0, 1)).xy
+        + cell(id.xy, vec2<i32>(0, -1)).xy;
+    let diagonal = cell(id.xy, vec2<i32>(1, 1)).xy
+        + cell(id.xy, vec2<i32>(-1, 1)).xy
+        + cell(id.xy, vec2<i32>(1, -1)).xy
+        + cell(id.xy, vec2<i32>(-1, -1)).xy;
+    let laplacian = adjacent * 0.2 + diagonal * 0.05 - c;
+    let a = c.x;
+    let b = c.y;
+    let reaction = a * b * b;
+    let da = params.diffusion_a * laplacian.x - reaction + params.feed * (1.0 - a);
+    let db = params.diffusion_b * laplacian.y + reaction - (params.kill + params.feed) * b;
+    let next = clamp(c + vec2<f32>(da, db) * params.dt, vec2<f32>(0.0), vec2<f32>(1.0));
+    textureStore(dst, vec2<i32>(id.xy), vec4<f32>(next, 0.0, 1.0));
+}
+
+@compute @workgroup_size(8, 8)
+fn game_of_life(@builtin(global_invocation_id) id: vec3<u32>) {
+    if !in_bounds(id) {
+        return;
+    }
+    var neighbours = 0u;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            if x != 0 || y != 0 {
+                neighbours += u32(cell(id.xy, vec2<i32>(x, y)).x > 0.5);
+            }
+        }
+    }
+    let alive = cell(id.xy, vec2<i32>(0, 0)).x > 0.5;
+    let mask = select(params.birth, params.survival, alive);
+    let next = f32((mask >> neighbours) & 1u);
+    textureStore(dst, vec2<i32>(id.xy), vec4<f32>(next, 0.0, 0.0, 1.0));
+}
+
+fn sigma(x: f32, a: f32, alpha: f32) -> f32 {
+    return 1.0 / (1.0 + exp(-(x - a) * 4.0 / alpha));
+}
+
+fn sigma_interval(x: f32, a: f32, b: f32) -> f32 {
+    return sigma(x, a, params.alpha_n) * (1.0 - sigma(x, b, params.alpha_n));
+}
+
+fn sigma_mix(x: f32, y: f32, m: f32) -> f32 {
+    let t = sigma(m, 0.5, params.alpha_m);
+    return x * (1.0 - t) + y * t;
+}
+
+@compute @workgroup_size(8, 8)
+fn smooth_life(@builtin(global_invocation_id) id: vec3<u32>) {
+    if !in_bounds(id) {
+        return;
+    }
+    // Integrate the state over the inner disk and the surrounding annulus, with anti-aliased
+    // edges.
+    let ri = params.inner_radius;
+    let ra = params.outer_radius;
+    let r = i32(ceil(ra));
+    var inner = vec2<f32>(0.0);
+    var outer = vec2<f32>(0.0);
+    for (var y = -r; y <= r; y++) {
+        for (var x = -r; x <= r; x++) {
+            let d = length(vec2<f32>(f32(x), f32(y)));
+            let w_inner = clamp(ri + 0.5 - d, 0.0, 1.0);
+            let w_outer = clamp(ra + 0.5 - d, 0.0, 1.0) - w_inner;
+            if w_inner + w_outer > 0.0 {
+                let v = cell(id.xy, vec2<i32>(x, y)).x;
+                inner += vec2<f32>(v * w_inner, w_inner);
+                outer += vec2<f32>(v * w_outer, w_outer);
+            }
+        }
+    }
+    let m = inner.x / max(inner.y, 1e-6);
+    let n = outer.x / max(outer.y, 1e-6);
+    let lo = sigma_mix(params.birth_lo, params.death_lo, m);
+    let hi = sigma_mix(params.birth_hi, params.death_hi, m);
+    let s = sigma_interval(n, lo, hi);
+    let f = cell(id.xy, vec2<i32>(0, 0)).x;
+    let next = clamp(f + params.dt * (2.0 * s - 1.0), 0.0, 1.0);
+    textureStore(dst, vec2<i32>(id.xy), vec4<f32>(next, 0.0, 0.0, 1.0));
+}
+
+@compute @workgroup_size(8, 8)
+fn colorize(@builtin(global_invocation_id) id: vec3<u32>) {
+    if !in_bounds(id) {
+        return;
+    }
+    let state = cell(id.xy, vec2<i32>(0, 0));
+    let t = clamp(state[params.channel], 0.0, 1.0);
+    textureStore(output, vec2<i32>(id.xy), mix(params.low, params.high, t));
+}